@@ -0,0 +1,225 @@
+use std::collections::VecDeque;
+
+use crate::Group;
+
+/// Cayley graph of a finite group relative to a set of generators.
+///
+/// The vertices are the group elements, and there is a directed edge
+/// g -> g * s for every element g and generator s. Elements are found by
+/// breadth-first search starting from the identity, so element 0 is always
+/// the identity and elements are sorted by word length.
+///
+/// Elements are compared with PartialEq only (no hashing), so this works
+/// for types like Mobius where equality is only approximate or is defined
+/// up to a quotient (M == -M). The tradeoff is that lookups are linear, which
+/// is fine for the small groups this is intended for.
+pub struct CayleyGraph<G: Group> {
+    elements: Vec<G>,
+    generator_count: usize,
+    // neighbors[i][j] is the index of elements[i] * generators[j]
+    neighbors: Vec<Vec<usize>>,
+}
+
+impl<G: Group> CayleyGraph<G> {
+    /// Enumerate the group generated by the given generators. The group
+    /// must be finite, else this will never terminate. See try_generate()
+    /// for a bounded version.
+    pub fn generate(generators: &[G]) -> Self {
+        Self::try_generate(generators, usize::MAX).unwrap()
+    }
+
+    /// Enumerate the group generated by the given generators, but give up
+    /// if more than max_order elements are found.
+    pub fn try_generate(generators: &[G], max_order: usize) -> Result<Self, String> {
+        let mut elements = vec![G::identity()];
+        let mut neighbors: Vec<Vec<usize>> = vec![];
+        let mut queue = VecDeque::from([0usize]);
+
+        while let Some(index) = queue.pop_front() {
+            let mut row = Vec::with_capacity(generators.len());
+            for generator in generators {
+                let product = elements[index].clone() * generator.clone();
+                let product_index = match elements.iter().position(|x| *x == product) {
+                    Some(i) => i,
+                    None => {
+                        if elements.len() >= max_order {
+                            return Err(format!("group order exceeds {}", max_order));
+                        }
+                        elements.push(product);
+                        queue.push_back(elements.len() - 1);
+                        elements.len() - 1
+                    }
+                };
+                row.push(product_index);
+            }
+
+            // BFS visits the elements in the order they were discovered,
+            // so the rows line up with the elements.
+            neighbors.push(row);
+        }
+
+        Ok(Self {
+            elements,
+            generator_count: generators.len(),
+            neighbors,
+        })
+    }
+
+    /// The number of elements in the group
+    pub fn order(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn generator_count(&self) -> usize {
+        self.generator_count
+    }
+
+    /// The group elements in BFS order. Index 0 is the identity
+    pub fn elements(&self) -> &[G] {
+        &self.elements
+    }
+
+    /// Find the index of an element in the graph, if present.
+    pub fn index_of(&self, element: &G) -> Option<usize> {
+        self.elements.iter().position(|x| x == element)
+    }
+
+    /// Get the index of elements[index] * generators[generator]
+    pub fn neighbor(&self, index: usize, generator: usize) -> usize {
+        self.neighbors[index][generator]
+    }
+
+    /// Adjacency list. Row i lists the neighbors of element i, one per
+    /// generator in the same order as the generators.
+    pub fn adjacency_list(&self) -> &[Vec<usize>] {
+        &self.neighbors
+    }
+
+    /// Iterate over the edges of the graph as (from, generator, to) triples
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        self.neighbors.iter().enumerate().flat_map(|(from, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(generator, to)| (from, generator, *to))
+        })
+    }
+
+    /// Format the graph in GraphViz DOT format. Nodes are labeled by
+    /// their index, edges are labeled with the corresponding entry of
+    /// generator_labels. This returns an error if there are fewer labels
+    /// than generators.
+    pub fn to_dot(&self, generator_labels: &[&str]) -> Result<String, String> {
+        self.to_dot_with_labels(generator_labels, |i, _| i.to_string())
+    }
+
+    /// Format the graph in GraphViz DOT format, using a callback to label
+    /// each (index, element) pair. This returns an error if there are fewer
+    /// labels than generators.
+    pub fn to_dot_with_labels(
+        &self,
+        generator_labels: &[&str],
+        element_label: impl Fn(usize, &G) -> String,
+    ) -> Result<String, String> {
+        if generator_labels.len() < self.generator_count {
+            return Err(format!(
+                "need a label for each of the {} generators, got {}",
+                self.generator_count,
+                generator_labels.len()
+            ));
+        }
+
+        let mut lines = vec![String::from("digraph cayley {")];
+        for (i, element) in self.elements.iter().enumerate() {
+            lines.push(format!(
+                "  {} [label=\"{}\"];",
+                i,
+                element_label(i, element)
+            ));
+        }
+        for (from, generator, to) in self.edges() {
+            lines.push(format!(
+                "  {} -> {} [label=\"{}\"];",
+                from, to, generator_labels[generator]
+            ));
+        }
+        lines.push(String::from("}"));
+
+        Ok(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_groups::Cyclic;
+
+    use super::*;
+
+    #[test]
+    pub fn generate_with_no_generators_returns_trivial_group() {
+        let result = CayleyGraph::<Cyclic<5>>::generate(&[]);
+
+        assert_eq!(result.order(), 1);
+        assert_eq!(result.elements(), &[Cyclic(0)]);
+    }
+
+    #[test]
+    pub fn generate_finds_all_elements_in_bfs_order() {
+        let result = CayleyGraph::generate(&[Cyclic::<6>(2), Cyclic::<6>(3)]);
+
+        let expected = [0, 2, 3, 4, 5, 1].map(Cyclic::<6>);
+        assert_eq!(result.elements(), &expected);
+    }
+
+    #[test]
+    pub fn generate_computes_neighbors() {
+        let result = CayleyGraph::generate(&[Cyclic::<4>(1)]);
+
+        assert_eq!(
+            result.adjacency_list(),
+            &[vec![1], vec![2], vec![3], vec![0]]
+        );
+    }
+
+    #[test]
+    pub fn try_generate_with_small_max_order_returns_error() {
+        let result = CayleyGraph::try_generate(&[Cyclic::<12>(1)], 10);
+
+        assert!(result.is_err_and(|e| e.contains("group order exceeds 10")));
+    }
+
+    #[test]
+    pub fn edges_has_one_edge_per_element_and_generator() {
+        let graph = CayleyGraph::generate(&[Cyclic::<6>(2), Cyclic::<6>(3)]);
+
+        let result = graph.edges().count();
+
+        assert_eq!(result, 12);
+    }
+
+    #[test]
+    pub fn to_dot_formats_nodes_and_edges() {
+        let graph = CayleyGraph::generate(&[Cyclic::<2>(1)]);
+
+        let result = graph.to_dot(&["a"]).unwrap();
+
+        let expected = [
+            "digraph cayley {",
+            "  0 [label=\"0\"];",
+            "  1 [label=\"1\"];",
+            "  0 -> 1 [label=\"a\"];",
+            "  1 -> 0 [label=\"a\"];",
+            "}",
+        ]
+        .join("\n");
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn to_dot_with_too_few_labels_returns_error() {
+        let graph = CayleyGraph::generate(&[Cyclic::<6>(2), Cyclic(3)]);
+
+        let result = graph.to_dot(&["a"]);
+
+        assert!(result.is_err_and(|e| e.contains("2 generators, got 1")));
+    }
+}
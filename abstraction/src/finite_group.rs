@@ -0,0 +1,363 @@
+use std::collections::BTreeSet;
+
+use crate::{CayleyGraph, Group};
+
+/// A subgroup, represented as a sorted list of element indices into
+/// the parent FiniteGroup
+pub type Subgroup = Vec<usize>;
+
+/// A finite group enumerated from a list of generators, along with
+/// its full multiplication table. All the analysis functions work with
+/// element indices (see elements()) rather than the elements themselves,
+/// so G only needs to implement PartialEq.
+pub struct FiniteGroup<G: Group> {
+    elements: Vec<G>,
+    // table[i][j] is the index of elements[i] * elements[j]
+    table: Vec<Vec<usize>>,
+    // inverses[i] is the index of elements[i]^-1
+    inverses: Vec<usize>,
+}
+
+impl<G: Group> FiniteGroup<G> {
+    /// Enumerate the group generated by the given elements. The group must
+    /// be finite, else this will never terminate.
+    pub fn from_generators(generators: &[G]) -> Self {
+        Self::try_from_generators(generators, usize::MAX).unwrap()
+    }
+
+    /// Enumerate the group generated by the given elements, returning
+    /// an error if the group has more than max_order elements
+    pub fn try_from_generators(generators: &[G], max_order: usize) -> Result<Self, String> {
+        let graph = CayleyGraph::try_generate(generators, max_order)?;
+        Ok(Self::from_cayley_graph(&graph))
+    }
+
+    /// Compute the full multiplication table from a Cayley graph
+    pub fn from_cayley_graph(graph: &CayleyGraph<G>) -> Self {
+        let elements = graph.elements().to_vec();
+        let table: Vec<Vec<usize>> = elements
+            .iter()
+            .map(|a| {
+                elements
+                    .iter()
+                    .map(|b| {
+                        let product = a.clone() * b.clone();
+                        graph
+                            .index_of(&product)
+                            .expect("group is not closed under multiplication")
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // The identity is always at index 0, so a^-1 is the element
+        // that appears in column 0 of row a
+        let inverses = table
+            .iter()
+            .map(|row| row.iter().position(|x| *x == 0).unwrap())
+            .collect();
+
+        Self {
+            elements,
+            table,
+            inverses,
+        }
+    }
+
+    pub fn order(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// The elements of the group. Index 0 is the identity
+    pub fn elements(&self) -> &[G] {
+        &self.elements
+    }
+
+    pub fn index_of(&self, element: &G) -> Option<usize> {
+        self.elements.iter().position(|x| x == element)
+    }
+
+    /// The multiplication table in terms of indices
+    pub fn cayley_table(&self) -> &[Vec<usize>] {
+        &self.table
+    }
+
+    pub fn multiply(&self, a: usize, b: usize) -> usize {
+        self.table[a][b]
+    }
+
+    pub fn inverse(&self, a: usize) -> usize {
+        self.inverses[a]
+    }
+
+    /// Compute the conjugate aba^-1 in terms of indices
+    pub fn sandwich(&self, bread: usize, filling: usize) -> usize {
+        self.multiply(self.multiply(bread, filling), self.inverse(bread))
+    }
+
+    /// The smallest n > 0 such that a^n = I
+    pub fn element_order(&self, a: usize) -> usize {
+        let mut power = a;
+        let mut n = 1;
+        while power != 0 {
+            power = self.multiply(power, a);
+            n += 1;
+        }
+
+        n
+    }
+
+    /// Compute the element orders for every element
+    pub fn element_orders(&self) -> Vec<usize> {
+        (0..self.order()).map(|a| self.element_order(a)).collect()
+    }
+
+    /// Partition the group into conjugacy classes. The classes are sorted
+    /// by their smallest index, so the first class is always {I}
+    pub fn conjugacy_classes(&self) -> Vec<Subgroup> {
+        let mut visited = vec![false; self.order()];
+        let mut classes = Vec::new();
+        for a in 0..self.order() {
+            if visited[a] {
+                continue;
+            }
+
+            let class: BTreeSet<usize> = (0..self.order()).map(|g| self.sandwich(g, a)).collect();
+            for x in class.iter() {
+                visited[*x] = true;
+            }
+            classes.push(class.into_iter().collect());
+        }
+
+        classes
+    }
+
+    /// The center of the group, i.e. the elements that commute with
+    /// every other element
+    pub fn center(&self) -> Subgroup {
+        (0..self.order())
+            .filter(|a| (0..self.order()).all(|b| self.multiply(*a, b) == self.multiply(b, *a)))
+            .collect()
+    }
+
+    pub fn is_abelian(&self) -> bool {
+        self.center().len() == self.order()
+    }
+
+    /// Check if a set of indices is closed under multiplication and
+    /// inverses (and therefore contains the identity)
+    pub fn is_subgroup(&self, subset: &[usize]) -> bool {
+        !subset.is_empty()
+            && subset.iter().all(|a| {
+                subset.contains(&self.inverse(*a))
+                    && subset
+                        .iter()
+                        .all(|b| subset.contains(&self.multiply(*a, *b)))
+            })
+    }
+
+    /// Check if a subgroup is normal, i.e. gHg^-1 = H for all g
+    pub fn is_normal(&self, subgroup: &[usize]) -> bool {
+        self.is_subgroup(subgroup)
+            && (0..self.order()).all(|g| {
+                subgroup
+                    .iter()
+                    .all(|h| subgroup.contains(&self.sandwich(g, *h)))
+            })
+    }
+
+    /// Compute the subgroup generated by the given elements
+    pub fn generated_subgroup(&self, generators: &[usize]) -> Subgroup {
+        let mut subgroup: BTreeSet<usize> = BTreeSet::from([0]);
+        let mut frontier = vec![0];
+        while let Some(a) = frontier.pop() {
+            for g in generators {
+                let product = self.multiply(a, *g);
+                if subgroup.insert(product) {
+                    frontier.push(product);
+                }
+            }
+        }
+
+        subgroup.into_iter().collect()
+    }
+
+    /// Enumerate all the subgroups. This starts with the cyclic subgroups
+    /// and repeatedly joins pairs of subgroups until no new subgroups
+    /// are found. Every subgroup of a finite group is a join of cyclic
+    /// subgroups, so this finds all of them. The result is sorted by order.
+    ///
+    /// This is brute force, so it is only practical for small groups.
+    pub fn subgroups(&self) -> Vec<Subgroup> {
+        let mut found: BTreeSet<Subgroup> = (0..self.order())
+            .map(|a| self.generated_subgroup(&[a]))
+            .collect();
+
+        let mut frontier: Vec<Subgroup> = found.iter().cloned().collect();
+        while !frontier.is_empty() {
+            let mut next_frontier = Vec::new();
+            let known: Vec<Subgroup> = found.iter().cloned().collect();
+            for a in frontier.iter() {
+                for b in known.iter() {
+                    let generators: Vec<usize> = a.iter().chain(b.iter()).cloned().collect();
+                    let join = self.generated_subgroup(&generators);
+                    if found.insert(join.clone()) {
+                        next_frontier.push(join);
+                    }
+                }
+            }
+            frontier = next_frontier;
+        }
+
+        let mut result: Vec<Subgroup> = found.into_iter().collect();
+        result.sort_by_key(|x| x.len());
+        result
+    }
+
+    /// Enumerate all the normal subgroups, sorted by order
+    pub fn normal_subgroups(&self) -> Vec<Subgroup> {
+        self.subgroups()
+            .into_iter()
+            .filter(|x| self.is_normal(x))
+            .collect()
+    }
+
+    /// Compute the Hasse diagram of the subgroup lattice. This returns
+    /// the subgroups (sorted by order) and a list of pairs (i, j) meaning
+    /// subgroups[i] is a maximal proper subgroup of subgroups[j]
+    pub fn subgroup_lattice(&self) -> (Vec<Subgroup>, Vec<(usize, usize)>) {
+        let subgroups = self.subgroups();
+        let contains = |big: &Subgroup, small: &Subgroup| {
+            big.len() > small.len() && small.iter().all(|x| big.binary_search(x).is_ok())
+        };
+
+        let mut covers = Vec::new();
+        for (i, small) in subgroups.iter().enumerate() {
+            for (j, big) in subgroups.iter().enumerate() {
+                if !contains(big, small) {
+                    continue;
+                }
+
+                let is_maximal = !subgroups
+                    .iter()
+                    .any(|middle| contains(big, middle) && contains(middle, small));
+                if is_maximal {
+                    covers.push((i, j));
+                }
+            }
+        }
+
+        (subgroups, covers)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_groups::Dihedral;
+
+    use super::*;
+
+    fn d4() -> FiniteGroup<Dihedral<4>> {
+        FiniteGroup::from_generators(&[Dihedral(1, false), Dihedral(0, true)])
+    }
+
+    #[test]
+    pub fn from_generators_computes_order() {
+        let group = d4();
+
+        assert_eq!(group.order(), 8);
+    }
+
+    #[test]
+    pub fn try_from_generators_with_small_max_order_returns_error() {
+        let result = FiniteGroup::try_from_generators(&[Dihedral::<4>(1, false)], 3);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn inverse_is_inverse_in_table() {
+        let group = d4();
+
+        for a in 0..group.order() {
+            assert_eq!(group.multiply(a, group.inverse(a)), 0);
+        }
+    }
+
+    #[test]
+    pub fn element_orders_computes_orders() {
+        let group = d4();
+
+        let mut result = group.element_orders();
+        result.sort();
+
+        // identity, 5 involutions (r^2 and 4 flips) and 2 quarter turns
+        assert_eq!(result, vec![1, 2, 2, 2, 2, 2, 4, 4]);
+    }
+
+    #[test]
+    pub fn conjugacy_classes_partitions_group() {
+        let group = d4();
+
+        let result = group.conjugacy_classes();
+
+        let mut sizes: Vec<usize> = result.iter().map(|x| x.len()).collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![1, 1, 2, 2, 2]);
+        assert_eq!(result[0], vec![0]);
+    }
+
+    #[test]
+    pub fn center_of_d4_is_half_turn() {
+        let group = d4();
+
+        let result = group.center();
+
+        let half_turn = group.index_of(&Dihedral(2, false)).unwrap();
+        assert_eq!(result, vec![0, half_turn]);
+    }
+
+    #[test]
+    pub fn is_abelian_detects_nonabelian_group() {
+        let group = d4();
+
+        assert!(!group.is_abelian());
+    }
+
+    #[test]
+    pub fn subgroups_finds_all_subgroups_of_d4() {
+        let group = d4();
+
+        let result = group.subgroups();
+
+        // trivial, 5 of order 2, 3 of order 4, and the whole group
+        let sizes: Vec<usize> = result.iter().map(|x| x.len()).collect();
+        assert_eq!(sizes, vec![1, 2, 2, 2, 2, 2, 4, 4, 4, 8]);
+    }
+
+    #[test]
+    pub fn normal_subgroups_finds_normal_subgroups_of_d4() {
+        let group = d4();
+
+        let result = group.normal_subgroups();
+
+        // trivial, center, 3 subgroups of index 2, and the whole group
+        let sizes: Vec<usize> = result.iter().map(|x| x.len()).collect();
+        assert_eq!(sizes, vec![1, 2, 4, 4, 4, 8]);
+    }
+
+    #[test]
+    pub fn subgroup_lattice_connects_maximal_subgroups() {
+        let group = d4();
+
+        let (subgroups, covers) = group.subgroup_lattice();
+
+        // The trivial group is covered by each of the 5 subgroups of order 2
+        let trivial_covers = covers.iter().filter(|(i, _)| *i == 0).count();
+        assert_eq!(trivial_covers, 5);
+        // The whole group covers the 3 subgroups of order 4
+        let whole = subgroups.len() - 1;
+        let whole_covers = covers.iter().filter(|(_, j)| *j == whole).count();
+        assert_eq!(whole_covers, 3);
+    }
+}
@@ -0,0 +1,87 @@
+use crate::Group;
+
+/// A (left) group action of a group G on a set X. Each group element
+/// is realized as a function X -> X such that
+/// - the identity acts trivially: I.act(x) = x
+/// - the action is compatible with the group operation:
+///   g.act(h.act(x)) = (g * h).act(x)
+///
+/// Like the group laws, these aren't representable in the type system,
+/// so it's up to the implementation to make sure this is valid.
+pub trait GroupAction<X>: Group {
+    fn act(&self, x: &X) -> X;
+}
+
+/// Compute the orbit of a point under the group generated by the
+/// generators, i.e. every point reachable by applying generators
+/// repeatedly. Points are found in breadth-first order, so result[0]
+/// is always the starting point.
+///
+/// Points are compared with PartialEq only, so lookups are linear. The
+/// orbit must be finite, else this will never terminate.
+pub fn orbit<G: GroupAction<X>, X: PartialEq + Clone>(generators: &[G], x: &X) -> Vec<X> {
+    let mut points = vec![x.clone()];
+    let mut index = 0;
+    while index < points.len() {
+        for generator in generators {
+            let image = generator.act(&points[index]);
+            if !points.contains(&image) {
+                points.push(image);
+            }
+        }
+        index += 1;
+    }
+
+    points
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{test_groups::Cyclic, Monoid};
+
+    use super::*;
+
+    /// Cyclic group acting on arrays by rotating the entries
+    impl<const N: usize> GroupAction<[usize; N]> for Cyclic<N> {
+        fn act(&self, x: &[usize; N]) -> [usize; N] {
+            std::array::from_fn(|i| x[(i + N - self.0) % N])
+        }
+    }
+
+    #[test]
+    pub fn identity_acts_trivially() {
+        let x = [1, 2, 3, 4];
+
+        let result = Cyclic::<4>::identity().act(&x);
+
+        assert_eq!(result, x);
+    }
+
+    #[test]
+    pub fn action_is_compatible_with_multiplication() {
+        let x = [1, 2, 3, 4];
+        let g = Cyclic::<4>(1);
+        let h = Cyclic::<4>(2);
+
+        let result = g.act(&h.act(&x));
+
+        assert_eq!(result, (g * h).act(&x));
+    }
+
+    #[test]
+    pub fn orbit_finds_all_rotations() {
+        let result = orbit(&[Cyclic::<4>(1)], &[1, 2, 3, 4]);
+
+        assert_eq!(
+            result,
+            vec![[1, 2, 3, 4], [4, 1, 2, 3], [3, 4, 1, 2], [2, 3, 4, 1]]
+        );
+    }
+
+    #[test]
+    pub fn orbit_of_symmetric_point_is_smaller_than_group() {
+        let result = orbit(&[Cyclic::<4>(1)], &[1, 2, 1, 2]);
+
+        assert_eq!(result.len(), 2);
+    }
+}
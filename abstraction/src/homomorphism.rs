@@ -0,0 +1,255 @@
+use crate::{CayleyGraph, FiniteGroup, Group};
+
+/// A homomorphism between two finite groups, defined by where it sends
+/// each generator of the domain.
+///
+/// Since every element of the domain is a word in the generators, the images
+/// of the generators determine the whole map. However, not every choice
+/// of images is consistent with the relations of the domain group, so
+/// the constructor verifies this by walking every edge of the domain's
+/// Cayley graph.
+pub struct Homomorphism<G: Group, H: Group> {
+    domain: CayleyGraph<G>,
+    // images[i] is the image of domain.elements()[i]
+    images: Vec<H>,
+}
+
+impl<G: Group, H: Group> Homomorphism<G, H> {
+    /// Define the map g_i -> h_i for each generator g_i. This returns an
+    /// error if the map is not a homomorphism
+    pub fn from_generator_images(generators: &[G], images: &[H]) -> Result<Self, String> {
+        if generators.len() != images.len() {
+            return Err(String::from(
+                "generators and images must have the same length",
+            ));
+        }
+
+        let domain = CayleyGraph::generate(generators);
+
+        // The Cayley graph is in BFS order, so each element is first
+        // reached from an element that already has an image.
+        let mut element_images: Vec<Option<H>> = vec![None; domain.order()];
+        element_images[0] = Some(H::identity());
+        for (from, generator, to) in domain.edges() {
+            let from_image = element_images[from]
+                .clone()
+                .expect("BFS order guarantees the source has an image");
+            let image = from_image * images[generator].clone();
+            match &element_images[to] {
+                None => element_images[to] = Some(image),
+                Some(existing) if *existing == image => {}
+                Some(_) => {
+                    return Err(format!(
+                        "map is not a homomorphism: element {} has multiple images",
+                        to
+                    ))
+                }
+            }
+        }
+
+        Ok(Self {
+            domain,
+            images: element_images.into_iter().map(|x| x.unwrap()).collect(),
+        })
+    }
+
+    /// The elements of the domain group
+    pub fn domain(&self) -> &[G] {
+        self.domain.elements()
+    }
+
+    /// Evaluate the map. This returns None if the element is not
+    /// in the domain
+    pub fn image(&self, element: &G) -> Option<H> {
+        self.domain
+            .index_of(element)
+            .map(|i| self.images[i].clone())
+    }
+
+    /// List the distinct elements of the image
+    pub fn image_elements(&self) -> Vec<H> {
+        let mut result: Vec<H> = Vec::new();
+        for image in self.images.iter() {
+            if !result.contains(image) {
+                result.push(image.clone());
+            }
+        }
+        result
+    }
+
+    /// The elements of the domain that map to the identity
+    pub fn kernel(&self) -> Vec<G> {
+        let identity = H::identity();
+        self.domain
+            .elements()
+            .iter()
+            .zip(self.images.iter())
+            .filter(|(_, image)| **image == identity)
+            .map(|(element, _)| element.clone())
+            .collect()
+    }
+
+    /// A homomorphism is injective if and only if the kernel is trivial
+    pub fn is_injective(&self) -> bool {
+        self.kernel().len() == 1
+    }
+
+    /// Check if the map is onto a codomain group. Every image must be an
+    /// element of the codomain, otherwise the images could form a
+    /// different group of the same order
+    pub fn is_surjective(&self, codomain: &FiniteGroup<H>) -> bool {
+        let image = self.image_elements();
+        image.iter().all(|h| codomain.index_of(h).is_some()) && image.len() == codomain.order()
+    }
+
+    /// Check if the map is a bijection onto a codomain group
+    pub fn is_isomorphism(&self, codomain: &FiniteGroup<H>) -> bool {
+        self.is_injective() && self.is_surjective(codomain)
+    }
+}
+
+/// Brute-force search for an isomorphism from the group generated by
+/// domain_generators onto the codomain group. Generator images are only
+/// chosen from codomain elements with matching element order, but this is
+/// still exponential in the number of generators so it is only practical
+/// for small groups.
+pub fn find_isomorphism<G: Group, H: Group>(
+    domain_generators: &[G],
+    codomain: &FiniteGroup<H>,
+) -> Option<Homomorphism<G, H>> {
+    let domain = FiniteGroup::from_generators(domain_generators);
+    if domain.order() != codomain.order() {
+        return None;
+    }
+
+    let codomain_orders = codomain.element_orders();
+    let candidates: Vec<Vec<usize>> = domain_generators
+        .iter()
+        .map(|g| {
+            let order = domain.element_order(domain.index_of(g).unwrap());
+            (0..codomain.order())
+                .filter(|h| codomain_orders[*h] == order)
+                .collect()
+        })
+        .collect();
+
+    // Iterate over the choices like an odometer
+    let mut choice = vec![0; domain_generators.len()];
+    if candidates.iter().any(|x| x.is_empty()) {
+        return None;
+    }
+    loop {
+        let images: Vec<H> = choice
+            .iter()
+            .zip(candidates.iter())
+            .map(|(i, options)| codomain.elements()[options[*i]].clone())
+            .collect();
+        if let Ok(map) = Homomorphism::from_generator_images(domain_generators, &images) {
+            if map.is_isomorphism(codomain) {
+                return Some(map);
+            }
+        }
+
+        let mut place = 0;
+        loop {
+            if place == choice.len() {
+                return None;
+            }
+            choice[place] += 1;
+            if choice[place] < candidates[place].len() {
+                break;
+            }
+            choice[place] = 0;
+            place += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_groups::{Cyclic, Dihedral};
+
+    use super::*;
+
+    #[test]
+    pub fn from_generator_images_with_mismatched_lengths_returns_error() {
+        let result =
+            Homomorphism::from_generator_images(&[Cyclic::<4>(1)], &[Cyclic::<4>(1), Cyclic(2)]);
+
+        assert!(result.is_err_and(|e| e.contains("same length")));
+    }
+
+    #[test]
+    pub fn from_generator_images_with_inconsistent_map_returns_error() {
+        // 1 has order 4 but its image would have order 3
+        let result = Homomorphism::from_generator_images(&[Cyclic::<4>(1)], &[Cyclic::<3>(1)]);
+
+        assert!(result.is_err_and(|e| e.contains("not a homomorphism")));
+    }
+
+    #[test]
+    pub fn image_maps_element() {
+        let map =
+            Homomorphism::from_generator_images(&[Cyclic::<4>(1)], &[Cyclic::<2>(1)]).unwrap();
+
+        let result = map.image(&Cyclic(3));
+
+        assert_eq!(result, Some(Cyclic(1)));
+    }
+
+    #[test]
+    pub fn kernel_computes_kernel() {
+        let map =
+            Homomorphism::from_generator_images(&[Cyclic::<4>(1)], &[Cyclic::<2>(1)]).unwrap();
+
+        let result = map.kernel();
+
+        assert_eq!(result, vec![Cyclic(0), Cyclic(2)]);
+        assert!(!map.is_injective());
+    }
+
+    #[test]
+    pub fn automorphism_is_isomorphism() {
+        let codomain = FiniteGroup::from_generators(&[Cyclic::<4>(1)]);
+        let map =
+            Homomorphism::from_generator_images(&[Cyclic::<4>(1)], &[Cyclic::<4>(3)]).unwrap();
+
+        assert!(map.is_isomorphism(&codomain));
+    }
+
+    #[test]
+    pub fn is_surjective_rejects_other_subgroup_of_same_order() {
+        // Both the flips with even rotation and the flips with odd
+        // rotation form a copy of D_3 inside of D_6
+        let even = FiniteGroup::from_generators(&[Dihedral::<6>(2, false), Dihedral(0, true)]);
+        let map = Homomorphism::from_generator_images(
+            &[Dihedral::<3>(1, false), Dihedral(0, true)],
+            &[Dihedral::<6>(2, false), Dihedral(1, true)],
+        )
+        .unwrap();
+
+        assert_eq!(map.image_elements().len(), even.order());
+        assert!(!map.is_surjective(&even));
+        assert!(!map.is_isomorphism(&even));
+    }
+
+    #[test]
+    pub fn find_isomorphism_finds_d3_as_dihedral_subgroup_of_d6() {
+        // The rotations by 2 steps and the flips with even rotation
+        // form a copy of D_3 inside of D_6
+        let subgroup = FiniteGroup::from_generators(&[Dihedral::<6>(2, false), Dihedral(0, true)]);
+
+        let result = find_isomorphism(&[Dihedral::<3>(1, false), Dihedral(0, true)], &subgroup);
+
+        assert!(result.is_some());
+    }
+
+    #[test]
+    pub fn find_isomorphism_rejects_cyclic_vs_dihedral() {
+        let codomain = FiniteGroup::from_generators(&[Dihedral::<3>(1, false), Dihedral(0, true)]);
+
+        let result = find_isomorphism(&[Cyclic::<6>(1)], &codomain);
+
+        assert!(result.is_none());
+    }
+}
@@ -1,9 +1,35 @@
+mod cayley_graph;
+mod finite_group;
+mod group_action;
+mod homomorphism;
+pub mod prelude;
+mod products;
+mod search;
+mod semigroup;
+#[cfg(test)]
+mod test_groups;
+
 use std::ops::Mul;
 
+pub use cayley_graph::CayleyGraph;
+pub use finite_group::{FiniteGroup, Subgroup};
+pub use group_action::{orbit, GroupAction};
+pub use homomorphism::{find_isomorphism, Homomorphism};
+pub use products::{DirectProduct, FreeLetter, FreeProduct, Integer};
+pub use search::{iterative_deepening, Search, SearchOrder, SearchSpace};
+pub use semigroup::Semigroup;
+#[cfg(feature = "parallel")]
+pub use semigroup::{par_sconcat, par_sconcat_with};
+
+/// Iterator over I, a, a^2, ... that stops early if it cycles back to
+/// the identity. For elements of infinite order (e.g. most Mobius
+/// transformations), this never ends unless capped with take_or_cycle()
 pub struct PowerIterator<S: Monoid> {
     element: S,
     current: S,
     power: usize,
+    max: Option<usize>,
+    order: Option<usize>,
 }
 
 impl<S: Monoid> PowerIterator<S> {
@@ -12,19 +38,56 @@ impl<S: Monoid> PowerIterator<S> {
             element,
             current: S::identity(),
             power: 0,
+            max: None,
+            order: None,
         }
     }
+
+    /// Stop after at most max powers I, a, ..., a^(max - 1), or sooner
+    /// if the powers cycle back to the identity. Unlike take(), this
+    /// still records the cycle for order()
+    pub fn take_or_cycle(self, max: usize) -> Self {
+        Self {
+            max: Some(max),
+            ..self
+        }
+    }
+
+    /// Run the iterator to the end and return the order of the element,
+    /// i.e. the smallest n > 0 with a^n = I, or None if the cap from
+    /// take_or_cycle() was reached first. Without a cap, this only
+    /// returns for elements of finite order
+    pub fn order(mut self) -> Option<usize> {
+        while self.next().is_some() {}
+        self.order
+    }
 }
 
 impl<S: Monoid> Iterator for PowerIterator<S> {
     type Item = S;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.order.is_some() {
+            return None;
+        }
+
+        if self.max.is_some_and(|max| self.power >= max) {
+            // a^max was already computed, so an order of exactly max is
+            // still detected
+            if self.power > 0 && self.current == S::identity() {
+                self.order = Some(self.power);
+            }
+            return None;
+        }
+
         let current = self.current.clone();
         self.current = self.element.clone() * self.current.clone();
+        let power = self.power;
+        self.power += 1;
 
-        if self.power > 0 && current == S::identity() {
+        if power > 0 && current == S::identity() {
             // Cycle detected, short-circuit
+            self.order = Some(power);
             None
         } else {
             Some(current)
@@ -48,7 +111,7 @@ pub trait Monoid: PartialEq + Clone + Mul<Self, Output = Self> {
     fn pow(&self, exponent: usize) -> Self {
         if exponent == 0 {
             Self::identity()
-        } else if exponent % 2 == 0 {
+        } else if exponent.is_multiple_of(2) {
             let half = self.pow(exponent / 2);
             half.clone() * half
         } else {
@@ -60,12 +123,36 @@ pub trait Monoid: PartialEq + Clone + Mul<Self, Output = Self> {
     /// Iterate over I, a, a^2, a^3, ...
     /// For elements with finite order, this will stop if the product
     /// equals Self::identity(). Eg.
-    /// if a is a 3-cycle permutation, you'd get I, a, a^2, but not a^3 = I
+    /// if a is a 3-cycle permutation, you'd get I, a, a^2, but not a^3 = I.
+    /// For elements that may have infinite order, cap the iterator with
+    /// PowerIterator::take_or_cycle()
     fn power_iter(&self) -> PowerIterator<Self> {
         PowerIterator::new(self.clone())
     }
+
+    /// Multiply the elements of an iterator in order. Unlike
+    /// Semigroup::sconcat_iter(), an empty iterator gives the identity
+    fn mconcat_iter(items: impl IntoIterator<Item = Self>) -> Self {
+        items
+            .into_iter()
+            .fold(Self::identity(), |product, x| product * x)
+    }
+
+    /// Check that a floating point representation of the element is still
+    /// valid, e.g. that a matrix is still normalized after a long product.
+    /// Exact representations like permutations are always valid, which is
+    /// the default
+    fn check_invariants(&self) -> Result<(), String> {
+        Ok(())
+    }
 }
 
+/// A commutative monoid is a monoid where a * b = b * a for all a, b.
+/// Like associativity, this can't be checked by the type system, so this
+/// is a marker trait and it's up to the implementation to make sure
+/// it's valid.
+pub trait CommutativeMonoid: Monoid {}
+
 /// A group is a monoid with the additional requirement that elements
 /// must have inverses.
 pub trait Group: Monoid {
@@ -108,6 +195,19 @@ pub trait Group: Monoid {
     }
 }
 
+/// Multiply elements left to right, so long words read the same as they
+/// are written on paper. compose![a, b, a.inverse()] is
+/// a * b * a.inverse(). Each expression is evaluated once, so elements
+/// don't need to be Copy.
+#[macro_export]
+macro_rules! compose {
+    ($first:expr $(, $rest:expr)* $(,)?) => {{
+        let result = $first;
+        $(let result = result * $rest;)*
+        result
+    }};
+}
+
 /// Test that identity * identity = identity (idempotent)
 /// Test that identity * x = x * identity = x for each x (identity law)
 #[macro_export]
@@ -262,3 +362,75 @@ macro_rules! test_group {
         }
     };
 }
+
+#[cfg(test)]
+mod test {
+    use crate::test_groups::Cyclic;
+
+    use super::*;
+
+    #[test]
+    pub fn power_iter_stops_at_identity() {
+        let result: Vec<Cyclic<6>> = Cyclic::<6>(2).power_iter().collect();
+
+        assert_eq!(result, vec![Cyclic(0), Cyclic(2), Cyclic(4)]);
+    }
+
+    #[test]
+    pub fn inv_power_iter_stops_at_identity() {
+        let result: Vec<Cyclic<4>> = Cyclic::<4>(1).inv_power_iter().collect();
+
+        assert_eq!(result, vec![Cyclic(0), Cyclic(3), Cyclic(2), Cyclic(1)]);
+    }
+
+    #[test]
+    pub fn take_or_cycle_caps_infinite_order() {
+        let result: Vec<Integer> = Integer(2).power_iter().take_or_cycle(3).collect();
+
+        assert_eq!(result, vec![Integer(0), Integer(2), Integer(4)]);
+    }
+
+    #[test]
+    pub fn order_finds_cycle_within_cap() {
+        let result = Cyclic::<6>(2).power_iter().take_or_cycle(10).order();
+
+        assert_eq!(result, Some(3));
+    }
+
+    #[test]
+    pub fn order_of_identity_is_one() {
+        let result = Cyclic::<6>::identity().power_iter().order();
+
+        assert_eq!(result, Some(1));
+    }
+
+    #[test]
+    pub fn order_with_cap_reached_returns_none() {
+        let result = Integer(1).power_iter().take_or_cycle(100).order();
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    pub fn order_with_cap_equal_to_order_finds_cycle() {
+        let result = Cyclic::<3>(1).power_iter().take_or_cycle(3).order();
+
+        assert_eq!(result, Some(3));
+    }
+
+    #[test]
+    pub fn compose_multiplies_all_elements() {
+        let a = Cyclic::<5>(2);
+
+        let result = compose![a, a, a.inverse(), Cyclic(4)];
+
+        assert_eq!(result, Cyclic(1));
+    }
+
+    #[test]
+    pub fn compose_of_one_element_is_that_element() {
+        let result = compose![Cyclic::<5>(3)];
+
+        assert_eq!(result, Cyclic(3));
+    }
+}
@@ -0,0 +1,9 @@
+//! The traits and types most code needs, for glob importing:
+//!
+//! ```
+//! use abstraction::prelude::*;
+//! ```
+pub use crate::{
+    orbit, CommutativeMonoid, DirectProduct, FiniteGroup, FreeProduct, Group, GroupAction, Monoid,
+    PowerIterator, Semigroup,
+};
@@ -0,0 +1,292 @@
+use std::ops::Mul;
+
+use crate::{CommutativeMonoid, Group, Monoid};
+
+/// The integers under addition, written multiplicatively. This is the
+/// infinite cyclic group Z, useful as a factor of products like Z * Z
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Integer(pub isize);
+
+impl Mul for Integer {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl Monoid for Integer {
+    fn identity() -> Self {
+        Self(0)
+    }
+}
+
+impl Group for Integer {
+    fn inverse(&self) -> Self {
+        Self(-self.0)
+    }
+}
+
+impl CommutativeMonoid for Integer {}
+
+/// Direct product G x H. Elements are pairs, and multiplication is done
+/// component-wise, so the two factors commute with each other.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DirectProduct<G, H>(pub G, pub H);
+
+impl<G: Monoid, H: Monoid> Mul for DirectProduct<G, H> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(self.0 * rhs.0, self.1 * rhs.1)
+    }
+}
+
+impl<G: Monoid, H: Monoid> Monoid for DirectProduct<G, H> {
+    fn identity() -> Self {
+        Self(G::identity(), H::identity())
+    }
+}
+
+impl<G: Group, H: Group> Group for DirectProduct<G, H> {
+    fn inverse(&self) -> Self {
+        Self(self.0.inverse(), self.1.inverse())
+    }
+}
+
+impl<G: CommutativeMonoid, H: CommutativeMonoid> CommutativeMonoid for DirectProduct<G, H> {}
+
+/// A single letter of a word in the free product, from one of the
+/// two factors
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FreeLetter<G, H> {
+    Left(G),
+    Right(H),
+}
+
+impl<G: Group, H: Group> FreeLetter<G, H> {
+    fn inverse(&self) -> Self {
+        match self {
+            Self::Left(g) => Self::Left(g.inverse()),
+            Self::Right(h) => Self::Right(h.inverse()),
+        }
+    }
+
+    fn is_identity(&self) -> bool {
+        match self {
+            Self::Left(g) => *g == G::identity(),
+            Self::Right(h) => *h == H::identity(),
+        }
+    }
+}
+
+/// Free product G * H. Elements are words of letters alternating between
+/// non-identity elements of G and H, with no relations between the two
+/// factors. For example, Z * Z is the free group on two generators, which
+/// is the abstract group behind the Apollonian gasket's two parabolic
+/// generators.
+///
+/// Words are always kept in normal form: adjacent letters from the
+/// same factor are multiplied together, and identity letters are removed.
+/// This way, PartialEq on the words is equality in the group.
+#[derive(Clone, PartialEq, Debug)]
+pub struct FreeProduct<G, H> {
+    letters: Vec<FreeLetter<G, H>>,
+}
+
+impl<G: Group, H: Group> FreeProduct<G, H> {
+    /// Embed an element of the left factor
+    pub fn left(g: G) -> Self {
+        Self::from_letters(vec![FreeLetter::Left(g)])
+    }
+
+    /// Embed an element of the right factor
+    pub fn right(h: H) -> Self {
+        Self::from_letters(vec![FreeLetter::Right(h)])
+    }
+
+    /// Build a word from any list of letters, reducing it to normal form
+    pub fn from_letters(letters: Vec<FreeLetter<G, H>>) -> Self {
+        let mut result = Self {
+            letters: Vec::with_capacity(letters.len()),
+        };
+        for letter in letters {
+            result.push(letter);
+        }
+        result
+    }
+
+    /// The letters of the reduced word
+    pub fn letters(&self) -> &[FreeLetter<G, H>] {
+        &self.letters
+    }
+
+    /// The number of letters in the reduced word
+    pub fn len(&self) -> usize {
+        self.letters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.letters.is_empty()
+    }
+
+    /// Append a letter, merging it with the last letter if they come from
+    /// the same factor. If they cancel, the merged letter is removed, which
+    /// exposes the previous letter (from the other factor).
+    fn push(&mut self, letter: FreeLetter<G, H>) {
+        let merged = match (self.letters.last(), &letter) {
+            (Some(FreeLetter::Left(a)), FreeLetter::Left(b)) => {
+                FreeLetter::Left(a.clone() * b.clone())
+            }
+            (Some(FreeLetter::Right(a)), FreeLetter::Right(b)) => {
+                FreeLetter::Right(a.clone() * b.clone())
+            }
+            _ => {
+                if !letter.is_identity() {
+                    self.letters.push(letter);
+                }
+                return;
+            }
+        };
+
+        self.letters.pop();
+        if !merged.is_identity() {
+            self.letters.push(merged);
+        }
+    }
+
+    /// Map the word into a concrete group K by sending each factor through
+    /// a homomorphism and multiplying the results. By the universal property
+    /// of the free product, any pair of homomorphisms G -> K and H -> K
+    /// extends to G * H this way.
+    pub fn evaluate<K: Monoid>(&self, left: impl Fn(&G) -> K, right: impl Fn(&H) -> K) -> K {
+        K::mconcat_iter(self.letters.iter().map(|letter| match letter {
+            FreeLetter::Left(g) => left(g),
+            FreeLetter::Right(h) => right(h),
+        }))
+    }
+}
+
+impl<G: Group, H: Group> Mul for FreeProduct<G, H> {
+    type Output = Self;
+
+    fn mul(mut self, rhs: Self) -> Self::Output {
+        // Both words are reduced, so cancellation only happens where
+        // they meet
+        for letter in rhs.letters {
+            self.push(letter);
+        }
+        self
+    }
+}
+
+impl<G: Group, H: Group> Monoid for FreeProduct<G, H> {
+    fn identity() -> Self {
+        Self {
+            letters: Vec::new(),
+        }
+    }
+}
+
+impl<G: Group, H: Group> Group for FreeProduct<G, H> {
+    fn inverse(&self) -> Self {
+        Self {
+            letters: self.letters.iter().rev().map(|x| x.inverse()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_groups::Cyclic;
+
+    use super::*;
+
+    type ZZ = FreeProduct<Integer, Integer>;
+
+    fn a() -> ZZ {
+        ZZ::left(Integer(1))
+    }
+
+    fn b() -> ZZ {
+        ZZ::right(Integer(1))
+    }
+
+    #[test]
+    pub fn direct_product_multiplies_componentwise() {
+        let x = DirectProduct(Cyclic::<3>(2), Integer(5));
+        let y = DirectProduct(Cyclic::<3>(2), Integer(-2));
+
+        let result = x * y;
+
+        assert_eq!(result, DirectProduct(Cyclic(1), Integer(3)));
+    }
+
+    #[test]
+    pub fn direct_product_of_finite_groups_has_product_order() {
+        let generators = [
+            DirectProduct(Cyclic::<2>(1), Cyclic::<3>(0)),
+            DirectProduct(Cyclic::<2>(0), Cyclic::<3>(1)),
+        ];
+
+        let result = crate::FiniteGroup::from_generators(&generators);
+
+        assert_eq!(result.order(), 6);
+    }
+
+    #[test]
+    pub fn from_letters_merges_adjacent_letters() {
+        let result = ZZ::from_letters(vec![
+            FreeLetter::Left(Integer(1)),
+            FreeLetter::Left(Integer(2)),
+            FreeLetter::Right(Integer(0)),
+            FreeLetter::Right(Integer(-1)),
+        ]);
+
+        assert_eq!(
+            result.letters(),
+            &[FreeLetter::Left(Integer(3)), FreeLetter::Right(Integer(-1))]
+        );
+    }
+
+    #[test]
+    pub fn mul_cancels_where_words_meet() {
+        let x = a() * b() * a();
+        let y = a().inverse() * b().inverse() * a();
+
+        let result = x * y;
+
+        assert_eq!(result, a() * a());
+        assert_eq!(result.len(), 1);
+    }
+
+    #[test]
+    pub fn element_times_inverse_is_identity() {
+        let x = a() * b() * b() * a().inverse() * b();
+
+        let result = x.clone() * x.inverse();
+
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    pub fn free_product_generators_do_not_commute() {
+        let result = ZZ::commutator(a(), b());
+
+        assert_eq!(result.len(), 4);
+    }
+
+    #[test]
+    pub fn evaluate_maps_to_direct_product() {
+        // The abelianization Z * Z -> Z x Z counts the exponents
+        let x = a() * b() * a() * b().inverse() * b().inverse();
+
+        let result = x.evaluate(
+            |g| DirectProduct(*g, Integer(0)),
+            |h| DirectProduct(Integer(0), *h),
+        );
+
+        assert_eq!(result, DirectProduct(Integer(2), Integer(-1)));
+    }
+}
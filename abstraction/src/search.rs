@@ -0,0 +1,209 @@
+use std::collections::VecDeque;
+
+/// A space of states to search, such as the words of a monoid acting by
+/// a list of generators. Each state only needs to know its children.
+pub trait SearchSpace {
+    type State;
+
+    /// Children of a state at the given depth, in the order they should
+    /// be visited
+    fn children(&self, depth: usize, state: &Self::State) -> Vec<Self::State>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchOrder {
+    DepthFirst,
+    BreadthFirst,
+}
+
+/// Callback for Search::retain()
+type RetainFn<'a, S> = Box<dyn FnMut(usize, &S) -> bool + 'a>;
+
+/// Depth-first or breadth-first traversal of a SearchSpace up to a
+/// maximum depth, yielding (depth, state) pairs. In both orders, the
+/// children of a state are visited in the order children() lists them.
+/// The search can be pruned with retain().
+pub struct Search<'a, T: SearchSpace> {
+    space: &'a T,
+    order: SearchOrder,
+    max_depth: usize,
+    // pairs of (depth, state). For depth-first search, the back of the
+    // queue is the top of the stack
+    frontier: VecDeque<(usize, T::State)>,
+    retain: Option<RetainFn<'a, T::State>>,
+}
+
+impl<'a, T: SearchSpace> Search<'a, T> {
+    pub fn new(space: &'a T, order: SearchOrder, root: T::State, max_depth: usize) -> Self {
+        Self::from_frontier(space, order, vec![(0, root)], max_depth)
+    }
+
+    /// Continue a search from the given (depth, state) pairs. For
+    /// depth-first search these are the stack from bottom to top, for
+    /// breadth-first search they are the queue from front to back.
+    pub fn from_frontier(
+        space: &'a T,
+        order: SearchOrder,
+        frontier: Vec<(usize, T::State)>,
+        max_depth: usize,
+    ) -> Self {
+        Self {
+            space,
+            order,
+            max_depth,
+            frontier: frontier.into(),
+            retain: None,
+        }
+    }
+
+    /// Only visit children where keep(depth, child) returns true. Pruned
+    /// states are not expanded either, so their whole subtree is skipped.
+    /// The callback can have state, e.g. a set of states already seen.
+    /// States passed to the constructor are always visited.
+    pub fn retain(self, keep: impl FnMut(usize, &T::State) -> bool + 'a) -> Self {
+        Self {
+            retain: Some(Box::new(keep)),
+            ..self
+        }
+    }
+
+    /// The states still to visit, in the same order as from_frontier()
+    pub fn frontier(&self) -> impl Iterator<Item = &(usize, T::State)> {
+        self.frontier.iter()
+    }
+}
+
+impl<T: SearchSpace> Iterator for Search<'_, T> {
+    type Item = (usize, T::State);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, state) = match self.order {
+            SearchOrder::DepthFirst => self.frontier.pop_back(),
+            SearchOrder::BreadthFirst => self.frontier.pop_front(),
+        }?;
+
+        if depth < self.max_depth {
+            let mut children = self.space.children(depth, &state);
+            if let Some(keep) = self.retain.as_mut() {
+                children.retain(|child| keep(depth + 1, child));
+            }
+
+            let children = children.into_iter().map(|child| (depth + 1, child));
+            match self.order {
+                // Push onto the stack in reverse order so the children
+                // are visited in order
+                SearchOrder::DepthFirst => self.frontier.extend(children.rev()),
+                SearchOrder::BreadthFirst => self.frontier.extend(children),
+            }
+        }
+
+        Some((depth, state))
+    }
+}
+
+/// Iterative deepening: repeated depth-first searches with increasing
+/// max depth, yielding only the states at exactly that depth. This gives
+/// the same order as a breadth-first search, but only needs memory for
+/// one path at a time.
+pub fn iterative_deepening<T: SearchSpace>(
+    space: &T,
+    root: T::State,
+    max_depth: usize,
+) -> impl Iterator<Item = (usize, T::State)> + '_
+where
+    T::State: Clone,
+{
+    (0..=max_depth).flat_map(move |level| {
+        Search::new(space, SearchOrder::DepthFirst, root.clone(), level)
+            .filter(move |(depth, _)| *depth == level)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    /// Binary tree labeled like a heap, where n has children 2n, 2n + 1
+    struct Heap;
+
+    impl SearchSpace for Heap {
+        type State = u32;
+
+        fn children(&self, _depth: usize, state: &u32) -> Vec<u32> {
+            vec![2 * state, 2 * state + 1]
+        }
+    }
+
+    /// Integers where n has children n + 1 and n + 2, so there are many
+    /// paths to the same state
+    struct Steps;
+
+    impl SearchSpace for Steps {
+        type State = u32;
+
+        fn children(&self, _depth: usize, state: &u32) -> Vec<u32> {
+            vec![state + 1, state + 2]
+        }
+    }
+
+    fn states(search: impl Iterator<Item = (usize, u32)>) -> Vec<u32> {
+        search.map(|(_, state)| state).collect()
+    }
+
+    #[test]
+    pub fn depth_first_visits_children_in_order() {
+        let search = Search::new(&Heap, SearchOrder::DepthFirst, 1, 2);
+
+        assert_eq!(states(search), vec![1, 2, 4, 5, 3, 6, 7]);
+    }
+
+    #[test]
+    pub fn breadth_first_visits_by_depth() {
+        let search = Search::new(&Heap, SearchOrder::BreadthFirst, 1, 2);
+
+        let result: Vec<(usize, u32)> = search.collect();
+
+        let expected = vec![(0, 1), (1, 2), (1, 3), (2, 4), (2, 5), (2, 6), (2, 7)];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn retain_prunes_subtrees() {
+        let search = Search::new(&Heap, SearchOrder::DepthFirst, 1, 3).retain(|_, n| n % 2 == 0);
+
+        assert_eq!(states(search), vec![1, 2, 4, 8]);
+    }
+
+    #[test]
+    pub fn retain_with_seen_set_visits_each_state_once() {
+        let mut seen = HashSet::from([0]);
+        let search = Search::new(&Steps, SearchOrder::BreadthFirst, 0, 3)
+            .retain(move |_, n| seen.insert(*n));
+
+        assert_eq!(states(search), vec![0, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    pub fn from_frontier_resumes_search() {
+        let mut search = Search::new(&Heap, SearchOrder::DepthFirst, 1, 2);
+        let first: Vec<u32> = states(search.by_ref().take(3));
+        let frontier: Vec<(usize, u32)> = search.frontier().cloned().collect();
+
+        let resumed = Search::from_frontier(&Heap, SearchOrder::DepthFirst, frontier, 2);
+
+        assert_eq!(first, vec![1, 2, 4]);
+        assert_eq!(states(resumed), vec![5, 3, 6, 7]);
+    }
+
+    #[test]
+    pub fn iterative_deepening_matches_breadth_first() {
+        let expected: Vec<(usize, u32)> =
+            Search::new(&Heap, SearchOrder::BreadthFirst, 1, 3).collect();
+
+        let result: Vec<(usize, u32)> = iterative_deepening(&Heap, 1, 3).collect();
+
+        assert_eq!(result, expected);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::Monoid;
+
+/// A semigroup is a set S along with an associative binary operation.
+/// Unlike a monoid there is no identity element, so combining an empty
+/// list of elements has no answer. The folding functions return None
+/// in that case.
+///
+/// Every monoid is a semigroup under multiplication. Other types, like
+/// collections of tiles, can implement this directly with concatenation as
+/// the operation.
+pub trait Semigroup: Clone {
+    /// The binary operation. This must be associative:
+    /// a.combine(b.combine(c)) = a.combine(b).combine(c)
+    fn combine(self, other: Self) -> Self;
+
+    /// Combine a slice of elements in order, or None if it is empty
+    fn sconcat(items: &[Self]) -> Option<Self> {
+        Self::sconcat_iter(items.iter().cloned())
+    }
+
+    /// Combine the elements of an iterator in order, or None if it is empty
+    fn sconcat_iter(items: impl IntoIterator<Item = Self>) -> Option<Self> {
+        items.into_iter().reduce(Self::combine)
+    }
+}
+
+impl<M: Monoid> Semigroup for M {
+    fn combine(self, other: Self) -> Self {
+        self * other
+    }
+}
+
+/// Below this many elements, par_sconcat() folds sequentially since
+/// spawning threads would cost more than it saves
+#[cfg(feature = "parallel")]
+const PARALLEL_CHUNK_SIZE: usize = 256;
+
+/// Combine a slice of elements by splitting it in half recursively and
+/// combining the halves on separate threads. Since the operation is
+/// associative, this gives the same result as sconcat(), just faster for
+/// long lists where each combine is expensive.
+#[cfg(feature = "parallel")]
+pub fn par_sconcat<S: Semigroup + Send + Sync>(items: &[S]) -> Option<S> {
+    par_sconcat_with(items, &|job| job())
+}
+
+/// Like par_sconcat(), but each worker thread runs its share of the fold
+/// inside run_on_worker. This is for state that lives in thread locals and
+/// would otherwise be lost on the new threads, e.g. run_on_worker can
+/// re-apply a setting captured on the calling thread before calling job.
+#[cfg(feature = "parallel")]
+pub fn par_sconcat_with<S, W>(items: &[S], run_on_worker: &W) -> Option<S>
+where
+    S: Semigroup + Send + Sync,
+    W: Fn(&dyn Fn() -> Option<S>) -> Option<S> + Sync,
+{
+    let threads = std::thread::available_parallelism().map_or(1, |x| x.get());
+    // Each level of the tree doubles the number of threads
+    let max_depth = threads.next_power_of_two().trailing_zeros();
+    par_sconcat_recursive(items, max_depth, run_on_worker)
+}
+
+#[cfg(feature = "parallel")]
+fn par_sconcat_recursive<S, W>(items: &[S], depth: u32, run_on_worker: &W) -> Option<S>
+where
+    S: Semigroup + Send + Sync,
+    W: Fn(&dyn Fn() -> Option<S>) -> Option<S> + Sync,
+{
+    if depth == 0 || items.len() <= PARALLEL_CHUNK_SIZE {
+        return S::sconcat(items);
+    }
+
+    let (left, right) = items.split_at(items.len() / 2);
+    let (left, right) = std::thread::scope(|scope| {
+        let left = scope
+            .spawn(|| run_on_worker(&|| par_sconcat_recursive(left, depth - 1, run_on_worker)));
+        let right = par_sconcat_recursive(right, depth - 1, run_on_worker);
+        (left.join().expect("worker thread panicked"), right)
+    });
+
+    match (left, right) {
+        (Some(a), Some(b)) => Some(a.combine(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_groups::Cyclic;
+
+    use super::*;
+
+    #[test]
+    pub fn sconcat_with_empty_slice_returns_none() {
+        let result = Cyclic::<5>::sconcat(&[]);
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    pub fn sconcat_combines_in_order() {
+        let result = Cyclic::<5>::sconcat(&[Cyclic(1), Cyclic(2), Cyclic(4)]);
+
+        assert_eq!(result, Some(Cyclic(2)));
+    }
+
+    #[test]
+    pub fn sconcat_iter_combines_iterator() {
+        let result = Cyclic::<6>::sconcat_iter((0..4).map(Cyclic));
+
+        assert_eq!(result, Some(Cyclic(0)));
+    }
+
+    #[test]
+    pub fn mconcat_iter_with_empty_iterator_returns_identity() {
+        let result = Cyclic::<3>::mconcat_iter(std::iter::empty());
+
+        assert_eq!(result, Cyclic(0));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    pub fn par_sconcat_matches_sconcat() {
+        let items: Vec<Cyclic<7>> = (0..10000).map(|x| Cyclic(x % 7)).collect();
+
+        let result = par_sconcat(&items);
+
+        assert_eq!(result, Cyclic::sconcat(&items));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    pub fn par_sconcat_with_runs_workers_inside_wrapper() {
+        use std::cell::Cell;
+
+        thread_local! {
+            static WRAPPED: Cell<bool> = const { Cell::new(false) };
+        }
+
+        /// Remembers whether every combine ran on a thread where the
+        /// wrapper had set the flag
+        #[derive(Clone)]
+        struct AllWrapped(bool);
+
+        impl Semigroup for AllWrapped {
+            fn combine(self, other: Self) -> Self {
+                Self(self.0 && other.0 && WRAPPED.with(|x| x.get()))
+            }
+        }
+
+        let wrapper = |job: &dyn Fn() -> Option<AllWrapped>| {
+            WRAPPED.with(|x| x.set(true));
+            job()
+        };
+        WRAPPED.with(|x| x.set(true));
+        let items = vec![AllWrapped(true); 10000];
+
+        let with_wrapper = par_sconcat_with(&items, &wrapper).unwrap();
+        WRAPPED.with(|x| x.set(true));
+        let without_wrapper = par_sconcat(&items).unwrap();
+
+        assert!(with_wrapper.0);
+        let threads = std::thread::available_parallelism().map_or(1, |x| x.get());
+        assert_eq!(without_wrapper.0, threads == 1);
+    }
+}
@@ -0,0 +1,64 @@
+//! Small concrete groups used for testing the generic group algorithms
+use std::ops::Mul;
+
+use crate::{CommutativeMonoid, Group, Monoid};
+
+/// Cyclic group Z_n under addition, written multiplicatively
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Cyclic<const N: usize>(pub usize);
+
+impl<const N: usize> Mul for Cyclic<N> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self((self.0 + rhs.0) % N)
+    }
+}
+
+impl<const N: usize> Monoid for Cyclic<N> {
+    fn identity() -> Self {
+        Self(0)
+    }
+}
+
+impl<const N: usize> Group for Cyclic<N> {
+    fn inverse(&self) -> Self {
+        Self((N - self.0) % N)
+    }
+}
+
+impl<const N: usize> CommutativeMonoid for Cyclic<N> {}
+
+/// Dihedral group D_n as pairs (rotation, is_flip).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Dihedral<const N: usize>(pub usize, pub bool);
+
+impl<const N: usize> Mul for Dihedral<N> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let Self(a, flip_a) = self;
+        let Self(b, flip_b) = rhs;
+        // a flip conjugates a rotation to its inverse
+        let b_rotation = if flip_a { N - b } else { b };
+        Self((a + b_rotation) % N, flip_a ^ flip_b)
+    }
+}
+
+impl<const N: usize> Monoid for Dihedral<N> {
+    fn identity() -> Self {
+        Self(0, false)
+    }
+}
+
+impl<const N: usize> Group for Dihedral<N> {
+    fn inverse(&self) -> Self {
+        let &Self(a, flip) = self;
+        if flip {
+            *self
+        } else {
+            Self((N - a) % N, false)
+        }
+    }
+}
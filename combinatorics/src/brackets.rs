@@ -0,0 +1,329 @@
+use std::{fmt::Display, str::FromStr};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Bracket {
+    Open,
+    Close,
+}
+
+impl Bracket {
+    fn height_change(&self) -> isize {
+        match self {
+            Self::Open => 1,
+            Self::Close => -1,
+        }
+    }
+}
+
+/// A string of balanced brackets, a.k.a. a Dyck word. Every prefix has
+/// at least as many open brackets as close brackets, and the total
+/// counts are equal. There are Catalan(n) of these with n pairs.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BalancedBrackets {
+    brackets: Vec<Bracket>,
+}
+
+impl BalancedBrackets {
+    pub fn new(brackets: Vec<Bracket>) -> Result<Self, String> {
+        let mut height = 0;
+        for (i, bracket) in brackets.iter().enumerate() {
+            height += bracket.height_change();
+            if height < 0 {
+                return Err(format!("unmatched close bracket at index {}", i));
+            }
+        }
+
+        if height != 0 {
+            return Err(format!("{} unmatched open brackets", height));
+        }
+
+        Ok(Self { brackets })
+    }
+
+    pub fn empty() -> Self {
+        Self { brackets: vec![] }
+    }
+
+    pub fn get(&self) -> &[Bracket] {
+        &self.brackets
+    }
+
+    /// Number of pairs of brackets, i.e. half the length of the string
+    pub fn pair_count(&self) -> usize {
+        self.brackets.len() / 2
+    }
+
+    /// All balanced strings with n pairs in lexicographic order, with
+    /// open before close. E.g. for n = 2, (()) then ()()
+    pub fn enumerate(n: usize) -> Vec<Self> {
+        let mut result = Vec::new();
+        let mut prefix = Vec::with_capacity(2 * n);
+        enumerate_suffixes(n, 0, 0, &mut prefix, &mut result);
+        result
+    }
+
+    /// Uniformly random balanced string with n pairs. random_index(k)
+    /// must return a uniform random index in [0, k), e.g.
+    /// SplitMix64::next_index() from the mobius crate.
+    ///
+    /// This shuffles n open and n + 1 close brackets, then uses the
+    /// cycle lemma: exactly one rotation of the shuffled string is a
+    /// balanced string followed by one extra close bracket.
+    pub fn random(n: usize, mut random_index: impl FnMut(usize) -> usize) -> Self {
+        let mut brackets = vec![Bracket::Open; n];
+        brackets.extend(vec![Bracket::Close; n + 1]);
+
+        // Fisher-Yates shuffle
+        for i in (1..brackets.len()).rev() {
+            brackets.swap(i, random_index(i + 1));
+        }
+
+        // Start just after the first position where the height is lowest.
+        // The rotation then ends with the extra close bracket.
+        let mut height = 0;
+        let mut lowest = (0, 0);
+        for (i, bracket) in brackets.iter().enumerate() {
+            height += bracket.height_change();
+            if height < lowest.0 {
+                lowest = (height, i + 1);
+            }
+        }
+
+        let start = lowest.1 % brackets.len();
+        brackets.rotate_left(start);
+        brackets.pop();
+
+        Self { brackets }
+    }
+
+    /// Find the partner of each bracket
+    pub fn matched(&self) -> MatchedBalancedBrackets {
+        let mut partners = vec![0; self.brackets.len()];
+        let mut stack = Vec::new();
+        for (i, bracket) in self.brackets.iter().enumerate() {
+            match bracket {
+                Bracket::Open => stack.push(i),
+                Bracket::Close => {
+                    let open = stack.pop().expect("brackets are balanced");
+                    partners[open] = i;
+                    partners[i] = open;
+                }
+            }
+        }
+
+        MatchedBalancedBrackets {
+            brackets: self.clone(),
+            partners,
+        }
+    }
+}
+
+fn enumerate_suffixes(
+    n: usize,
+    opened: usize,
+    closed: usize,
+    prefix: &mut Vec<Bracket>,
+    result: &mut Vec<BalancedBrackets>,
+) {
+    if closed == n {
+        result.push(BalancedBrackets {
+            brackets: prefix.clone(),
+        });
+        return;
+    }
+
+    if opened < n {
+        prefix.push(Bracket::Open);
+        enumerate_suffixes(n, opened + 1, closed, prefix, result);
+        prefix.pop();
+    }
+
+    if closed < opened {
+        prefix.push(Bracket::Close);
+        enumerate_suffixes(n, opened, closed + 1, prefix, result);
+        prefix.pop();
+    }
+}
+
+impl FromStr for BalancedBrackets {
+    type Err = String;
+
+    /// Parse a string like "(()())"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let brackets = s
+            .chars()
+            .map(|c| match c {
+                '(' => Ok(Bracket::Open),
+                ')' => Ok(Bracket::Close),
+                c => Err(format!("invalid bracket '{}'", c)),
+            })
+            .collect::<Result<Vec<Bracket>, String>>()?;
+        Self::new(brackets)
+    }
+}
+
+impl Display for BalancedBrackets {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for bracket in &self.brackets {
+            match bracket {
+                Bracket::Open => write!(f, "(")?,
+                Bracket::Close => write!(f, ")")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Balanced brackets where each bracket knows the index of its partner
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MatchedBalancedBrackets {
+    brackets: BalancedBrackets,
+    partners: Vec<usize>,
+}
+
+impl MatchedBalancedBrackets {
+    pub fn brackets(&self) -> &BalancedBrackets {
+        &self.brackets
+    }
+
+    pub fn partner(&self, index: usize) -> Option<usize> {
+        self.partners.get(index).copied()
+    }
+
+    /// The pairs (open, close) of matching bracket indices, sorted by
+    /// the open bracket. Drawing each pair as a semicircle over a line
+    /// gives the arc diagram of the brackets, see
+    /// mobius::integer_arcs::bracket_arcs()
+    pub fn arcs(&self) -> Vec<(usize, usize)> {
+        self.brackets
+            .get()
+            .iter()
+            .enumerate()
+            .filter(|(_, bracket)| **bracket == Bracket::Open)
+            .map(|(i, _)| (i, self.partners[i]))
+            .collect()
+    }
+
+    /// Number of pairs of brackets enclosing each bracket, including its
+    /// own pair
+    pub fn depths(&self) -> Vec<usize> {
+        let mut depth = 0;
+        self.brackets
+            .get()
+            .iter()
+            .map(|bracket| match bracket {
+                Bracket::Open => {
+                    depth += 1;
+                    depth
+                }
+                Bracket::Close => {
+                    depth -= 1;
+                    depth + 1
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use test_case::test_case;
+
+    use super::*;
+
+    fn parse(s: &str) -> BalancedBrackets {
+        s.parse().unwrap()
+    }
+
+    #[test_case(""; "empty")]
+    #[test_case("()"; "one pair")]
+    #[test_case("(()())()"; "nested")]
+    pub fn from_str_round_trips(s: &str) {
+        let result = parse(s).to_string();
+
+        assert_eq!(result, s);
+    }
+
+    #[test_case(")(", "unmatched close bracket at index 0")]
+    #[test_case("(()", "1 unmatched open brackets")]
+    #[test_case("(]", "invalid bracket ']'")]
+    pub fn from_str_with_unbalanced_string_returns_error(s: &str, expected: &str) {
+        let result = s.parse::<BalancedBrackets>();
+
+        assert_eq!(result, Err(String::from(expected)));
+    }
+
+    #[test_case(0, 1)]
+    #[test_case(1, 1)]
+    #[test_case(3, 5)]
+    #[test_case(5, 42)]
+    pub fn enumerate_counts_catalan_numbers(n: usize, expected: usize) {
+        let result = BalancedBrackets::enumerate(n);
+
+        assert_eq!(result.len(), expected);
+        assert!(result.iter().all(|x| x.pair_count() == n));
+    }
+
+    #[test]
+    pub fn enumerate_is_in_lexicographic_order() {
+        let result: Vec<String> = BalancedBrackets::enumerate(3)
+            .iter()
+            .map(|x| x.to_string())
+            .collect();
+
+        assert_eq!(result, ["((()))", "(()())", "(())()", "()(())", "()()()"]);
+    }
+
+    #[test]
+    pub fn random_reaches_every_string() {
+        // Small linear congruential generator so this crate has no
+        // random number dependency
+        let mut state: u64 = 1;
+        let mut random_index = |k: usize| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((state >> 33) % k as u64) as usize
+        };
+
+        let result: HashSet<BalancedBrackets> = (0..200)
+            .map(|_| BalancedBrackets::random(3, &mut random_index))
+            .collect();
+
+        let expected: HashSet<BalancedBrackets> =
+            BalancedBrackets::enumerate(3).into_iter().collect();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn random_with_zero_pairs_is_empty() {
+        let result = BalancedBrackets::random(0, |_| 0);
+
+        assert_eq!(result, BalancedBrackets::empty());
+    }
+
+    #[test]
+    pub fn matched_finds_partners() {
+        let result = parse("(()())").matched();
+
+        let partners: Vec<usize> = (0..6).map(|i| result.partner(i).unwrap()).collect();
+        assert_eq!(partners, vec![5, 2, 1, 4, 3, 0]);
+        assert_eq!(result.partner(6), None);
+    }
+
+    #[test]
+    pub fn arcs_are_sorted_by_open_bracket() {
+        let result = parse("(())()").matched().arcs();
+
+        assert_eq!(result, vec![(0, 3), (1, 2), (4, 5)]);
+    }
+
+    #[test]
+    pub fn depths_count_enclosing_pairs() {
+        let result = parse("(())()").matched().depths();
+
+        assert_eq!(result, vec![1, 2, 2, 1, 1, 1]);
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::VecDeque;
+
+use crate::MatchedBalancedBrackets;
+
+/// A chord diagram: 2n points around a circle, paired up by n chords.
+/// Unlike balanced brackets, chords may cross. For example, the Gauss
+/// diagram of a knot shadow pairs up the two visits to each crossing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChordDiagram {
+    // (start, end) with start < end, sorted by start
+    chords: Vec<(usize, usize)>,
+}
+
+impl ChordDiagram {
+    /// Points are numbered 0 to 2n - 1 around the circle, and each must
+    /// be the endpoint of exactly one chord
+    pub fn new(chords: &[(usize, usize)]) -> Result<Self, String> {
+        let point_count = 2 * chords.len();
+        let mut used = vec![false; point_count];
+        for &(a, b) in chords {
+            for point in [a, b] {
+                if point >= point_count {
+                    return Err(format!(
+                        "point {} out of range for {} chords",
+                        point,
+                        chords.len()
+                    ));
+                }
+                if used[point] {
+                    return Err(format!("point {} is used by more than one chord", point));
+                }
+                used[point] = true;
+            }
+        }
+
+        let mut chords: Vec<(usize, usize)> =
+            chords.iter().map(|&(a, b)| (a.min(b), a.max(b))).collect();
+        chords.sort();
+
+        Ok(Self { chords })
+    }
+
+    pub fn chords(&self) -> &[(usize, usize)] {
+        &self.chords
+    }
+
+    pub fn point_count(&self) -> usize {
+        2 * self.chords.len()
+    }
+
+    /// Two chords cross if their endpoints alternate around the circle
+    pub fn crosses(&self, i: usize, j: usize) -> bool {
+        let (a, b) = self.chords[i];
+        let (c, d) = self.chords[j];
+        (a < c && c < b && b < d) || (c < a && a < d && d < b)
+    }
+
+    /// Number of crossings when chords on the same side cross and chords
+    /// on opposite sides don't. outside[i] is the side of chord i; use
+    /// all false to count the crossings of the plain diagram
+    pub fn crossing_count(&self, outside: &[bool]) -> usize {
+        let n = self.chords.len();
+        (0..n)
+            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .filter(|&(i, j)| outside[i] == outside[j] && self.crosses(i, j))
+            .count()
+    }
+
+    /// Choose a side of the circle for each chord to reduce crossings,
+    /// returning outside[i] for chord i. If the crossing chords can be
+    /// split into two non-crossing sets, this finds the split. Otherwise
+    /// it moves chords to the other side while that reduces crossings,
+    /// which is a local minimum but not always the best possible.
+    pub fn route_two_sides(&self) -> Vec<bool> {
+        let n = self.chords.len();
+        let neighbors: Vec<Vec<usize>> = (0..n)
+            .map(|i| (0..n).filter(|&j| j != i && self.crosses(i, j)).collect())
+            .collect();
+
+        // Try to 2-color the crossing graph one component at a time
+        let mut outside: Vec<Option<bool>> = vec![None; n];
+        for start in 0..n {
+            if outside[start].is_some() {
+                continue;
+            }
+
+            outside[start] = Some(false);
+            let mut queue = VecDeque::from([start]);
+            while let Some(i) = queue.pop_front() {
+                let side = outside[i].expect("queued chords have a side");
+                for &j in &neighbors[i] {
+                    if outside[j].is_none() {
+                        outside[j] = Some(!side);
+                        queue.push_back(j);
+                    }
+                }
+            }
+        }
+        let mut outside: Vec<bool> = outside.into_iter().flatten().collect();
+
+        // Each flip removes at least one crossing, so this terminates
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for i in 0..n {
+                let same_side = neighbors[i]
+                    .iter()
+                    .filter(|&&j| outside[j] == outside[i])
+                    .count();
+                if 2 * same_side > neighbors[i].len() {
+                    outside[i] = !outside[i];
+                    improved = true;
+                }
+            }
+        }
+
+        outside
+    }
+}
+
+impl From<&MatchedBalancedBrackets> for ChordDiagram {
+    /// Balanced brackets give the chord diagrams with no crossings
+    fn from(brackets: &MatchedBalancedBrackets) -> Self {
+        Self {
+            chords: brackets.arcs(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::BalancedBrackets;
+
+    use super::*;
+
+    #[test_case(&[(0, 2)], "point 2 out of range"; "out of range")]
+    #[test_case(&[(0, 1), (1, 2)], "point 1 is used by more than one chord"; "shared point")]
+    pub fn new_with_invalid_pairing_returns_error(chords: &[(usize, usize)], expected: &str) {
+        let result = ChordDiagram::new(chords);
+
+        assert!(result.is_err_and(|e| e.contains(expected)));
+    }
+
+    #[test]
+    pub fn new_sorts_chords() {
+        let result = ChordDiagram::new(&[(3, 1), (2, 0)]).unwrap();
+
+        assert_eq!(result.chords(), &[(0, 2), (1, 3)]);
+    }
+
+    #[test]
+    pub fn crosses_detects_alternating_endpoints() {
+        let diagram = ChordDiagram::new(&[(0, 2), (1, 3), (4, 5)]).unwrap();
+
+        assert!(diagram.crosses(0, 1));
+        assert!(diagram.crosses(1, 0));
+        assert!(!diagram.crosses(0, 2));
+    }
+
+    #[test]
+    pub fn from_brackets_has_no_crossings() {
+        let brackets: BalancedBrackets = "(()(()))()".parse().unwrap();
+
+        let result = ChordDiagram::from(&brackets.matched());
+
+        assert_eq!(result.crossing_count(&[false; 5]), 0);
+    }
+
+    #[test]
+    pub fn route_two_sides_splits_bipartite_crossings() {
+        // A chain of crossing chords 0-1-2
+        let diagram = ChordDiagram::new(&[(0, 2), (1, 4), (3, 5)]).unwrap();
+
+        let result = diagram.route_two_sides();
+
+        assert_eq!(diagram.crossing_count(&[false; 3]), 2);
+        assert_eq!(diagram.crossing_count(&result), 0);
+    }
+
+    #[test_case(3, 1; "triangle")]
+    #[test_case(4, 2; "four chords")]
+    #[test_case(5, 4; "five chords")]
+    pub fn route_two_sides_with_all_crossing_finds_minimum(n: usize, expected: usize) {
+        // Diameters of a 2n-gon all cross each other, so the best split
+        // puts half on each side
+        let chords: Vec<(usize, usize)> = (0..n).map(|i| (i, i + n)).collect();
+        let diagram = ChordDiagram::new(&chords).unwrap();
+
+        let result = diagram.route_two_sides();
+
+        assert_eq!(diagram.crossing_count(&result), expected);
+    }
+}
@@ -0,0 +1,7 @@
+mod brackets;
+mod chords;
+mod meanders;
+
+pub use brackets::{BalancedBrackets, Bracket, MatchedBalancedBrackets};
+pub use chords::ChordDiagram;
+pub use meanders::{loop_count, Meander};
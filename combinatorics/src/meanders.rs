@@ -0,0 +1,204 @@
+use crate::{BalancedBrackets, MatchedBalancedBrackets};
+
+/// Number of closed curves formed by drawing the arcs of upper above a
+/// line and the arcs of lower below it. Both must have the same length.
+pub fn loop_count(upper: &MatchedBalancedBrackets, lower: &MatchedBalancedBrackets) -> usize {
+    let length = upper.brackets().get().len();
+    let mut visited = vec![false; length];
+    let mut loops = 0;
+    for start in 0..length {
+        if visited[start] {
+            continue;
+        }
+
+        loops += 1;
+        let mut current = start;
+        let mut use_upper = true;
+        while !visited[current] {
+            visited[current] = true;
+            let arcs = if use_upper { upper } else { lower };
+            current = arcs.partner(current).expect("lengths match");
+            use_upper = !use_upper;
+        }
+    }
+
+    loops
+}
+
+/// A closed meander: a single closed curve crossing a line 2n times,
+/// up to deformation. Cutting along the line splits it into an arch
+/// configuration above the line and one below it, each a set of
+/// balanced brackets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Meander {
+    upper: MatchedBalancedBrackets,
+    lower: MatchedBalancedBrackets,
+}
+
+impl Meander {
+    pub fn new(upper: &BalancedBrackets, lower: &BalancedBrackets) -> Result<Self, String> {
+        if upper.pair_count() != lower.pair_count() {
+            return Err(format!(
+                "upper and lower arches must have the same size, got {} and {}",
+                upper.pair_count(),
+                lower.pair_count()
+            ));
+        }
+
+        let (upper, lower) = (upper.matched(), lower.matched());
+        let loops = loop_count(&upper, &lower);
+        if loops > 1 {
+            return Err(format!("arches form {} closed curves, not 1", loops));
+        }
+
+        Ok(Self { upper, lower })
+    }
+
+    pub fn upper(&self) -> &MatchedBalancedBrackets {
+        &self.upper
+    }
+
+    pub fn lower(&self) -> &MatchedBalancedBrackets {
+        &self.lower
+    }
+
+    /// Number of times the curve crosses the line
+    pub fn crossing_count(&self) -> usize {
+        self.upper.brackets().get().len()
+    }
+
+    /// All closed meanders with 2n crossings, ordered by upper then
+    /// lower arches. There are 1, 2, 8, 42, 262, ... of these, and the
+    /// count grows quickly, so this is only practical for small n.
+    pub fn enumerate(n: usize) -> Vec<Self> {
+        let arches = BalancedBrackets::enumerate(n);
+        arches
+            .iter()
+            .flat_map(|upper| arches.iter().map(move |lower| (upper, lower)))
+            .filter_map(|(upper, lower)| Self::new(upper, lower).ok())
+            .collect()
+    }
+
+    /// Uniformly random closed meander with 2n crossings, by picking
+    /// random pairs of arch configurations until they form a single
+    /// curve. The fraction of pairs that do shrinks as n grows, so this
+    /// gives up after max_attempts. See BalancedBrackets::random() for
+    /// random_index.
+    pub fn random(
+        n: usize,
+        max_attempts: usize,
+        mut random_index: impl FnMut(usize) -> usize,
+    ) -> Result<Self, String> {
+        for _ in 0..max_attempts {
+            let upper = BalancedBrackets::random(n, &mut random_index);
+            let lower = BalancedBrackets::random(n, &mut random_index);
+            if let Ok(meander) = Self::new(&upper, &lower) {
+                return Ok(meander);
+            }
+        }
+
+        Err(format!(
+            "no meander found in {} attempts, try a larger limit",
+            max_attempts
+        ))
+    }
+
+    /// The meandric permutation: the crossings in the order the curve
+    /// visits them, starting at crossing 0 and leaving it along its
+    /// upper arc
+    pub fn path(&self) -> Vec<usize> {
+        let mut result = Vec::with_capacity(self.crossing_count());
+        if self.crossing_count() == 0 {
+            return result;
+        }
+
+        let mut current = 0;
+        let mut use_upper = true;
+        loop {
+            result.push(current);
+            let arcs = if use_upper { &self.upper } else { &self.lower };
+            current = arcs.partner(current).expect("crossing is on the line");
+            use_upper = !use_upper;
+            if current == 0 {
+                return result;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::*;
+
+    fn parse(s: &str) -> BalancedBrackets {
+        s.parse().unwrap()
+    }
+
+    #[test_case(1, 1)]
+    #[test_case(2, 2)]
+    #[test_case(3, 8)]
+    #[test_case(4, 42)]
+    pub fn enumerate_counts_meanders(n: usize, expected: usize) {
+        let result = Meander::enumerate(n);
+
+        assert_eq!(result.len(), expected);
+    }
+
+    #[test]
+    pub fn loop_count_of_mirrored_arches_is_pair_count() {
+        // Reflecting arches across the line closes each arch separately
+        let arches = parse("(()())").matched();
+
+        let result = loop_count(&arches, &arches);
+
+        assert_eq!(result, 3);
+    }
+
+    #[test]
+    pub fn new_with_several_loops_returns_error() {
+        let result = Meander::new(&parse("()()"), &parse("()()"));
+
+        assert!(result.is_err_and(|e| e.contains("2 closed curves")));
+    }
+
+    #[test]
+    pub fn new_with_different_sizes_returns_error() {
+        let result = Meander::new(&parse("()"), &parse("()()"));
+
+        assert!(result.is_err_and(|e| e.contains("same size")));
+    }
+
+    #[test]
+    pub fn path_visits_every_crossing() {
+        let meander = Meander::new(&parse("(())()"), &parse("()(())")).unwrap();
+
+        let result = meander.path();
+
+        assert_eq!(result, vec![0, 3, 4, 5, 2, 1]);
+    }
+
+    #[test]
+    pub fn random_returns_meander() {
+        let mut state: u64 = 7;
+        let random_index = |k: usize| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((state >> 33) % k as u64) as usize
+        };
+
+        let result = Meander::random(4, 1000, random_index).unwrap();
+
+        assert_eq!(result.crossing_count(), 8);
+        assert!(Meander::enumerate(4).contains(&result));
+    }
+
+    #[test]
+    pub fn random_with_no_attempts_returns_error() {
+        let result = Meander::random(2, 0, |_| 0);
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,454 @@
+//! Conformal geometric algebra for the plane, R(3, 1). The basis vectors
+//! are e1, e2 for the plane, plus e3 (squares to +1) and e4 (squares to
+//! -1), which combine into the null vectors
+//!
+//! - origin = (e4 - e3) / 2
+//! - infinity = e3 + e4
+//!
+//! so that origin . infinity = -1. A point x is the null vector
+//! x + |x|^2/2 infinity + origin. Circles and lines are encoded as dual
+//! vectors whose inner product with a point is zero exactly when the
+//! point is on them. These same vectors are reflectors: sandwiching with
+//! them inverts in the circle or reflects in the line, so any Mobius
+//! transformation can be written as a product of them (a versor).
+use mobius::{
+    geometry::{Circle, GeneralizedCircle, Line},
+    unit_complex::UnitComplex,
+    Complex, Mobius, Tolerance,
+};
+
+use crate::{Multivector, Signature, UnitBlade};
+
+/// Size of the origin component, relative to the x and y components,
+/// below which the vector is a point at infinity or a line
+const TOLERANCE: f64 = 1e-12;
+
+/// Size of the origin component, relative to the infinity component,
+/// below which it is lost to rounding error. The origin component is
+/// stored as the difference of the e3 and e4 components, each about as
+/// large as the infinity component, i.e. |z|^2 / 2 for a point.
+const ROUNDING_TOLERANCE: f64 = 1e-14;
+
+/// TOLERANCE scaled to the current mobius::Tolerance
+fn tolerance() -> f64 {
+    Tolerance::current().scale_relative(TOLERANCE)
+}
+
+/// Check if the origin component is too small to tell the vector apart
+/// from a point at infinity or a line. This doesn't compare it to the
+/// full size of the vector, since the infinity component of a finite
+/// point grows like |z|^2
+fn origin_part_is_zero(x: f64, y: f64, infinity_part: f64, origin_part: f64) -> bool {
+    let rounding = Tolerance::current().scale_relative(ROUNDING_TOLERANCE);
+    origin_part.abs() <= tolerance() * x.abs().max(y.abs())
+        || origin_part.abs() <= rounding * infinity_part.abs()
+}
+
+fn signature() -> Signature {
+    Signature::cga_2d()
+}
+
+fn basis(i: usize) -> Multivector {
+    Multivector::basis_vector(signature(), i).expect("CGA 2D has 4 basis vectors")
+}
+
+/// The null vector (e4 - e3) / 2 representing the origin
+pub fn origin() -> Multivector {
+    basis(3).sub(&basis(2)).unwrap().scale(0.5)
+}
+
+/// The null vector e3 + e4 representing the point at infinity
+pub fn infinity() -> Multivector {
+    basis(2).add(&basis(3)).unwrap()
+}
+
+fn vector(x: f64, y: f64, infinity_part: f64, origin_part: f64) -> Multivector {
+    // a infinity + b origin = (a - b/2) e3 + (a + b/2) e4
+    Multivector::vector(
+        signature(),
+        &[
+            x,
+            y,
+            infinity_part - origin_part / 2.0,
+            infinity_part + origin_part / 2.0,
+        ],
+    )
+    .expect("CGA 2D vectors have 4 components")
+}
+
+/// Split a vector into (x, y, infinity part, origin part). Other grades
+/// are ignored, since applying a versor can leave rounding error in them
+fn components(v: &Multivector) -> Result<(f64, f64, f64, f64), String> {
+    if v.signature() != signature() {
+        return Err(format!(
+            "expected a CGA 2D multivector, got {:?}",
+            v.signature()
+        ));
+    }
+    let [x, y, e3, e4] = [0, 1, 2, 3].map(|i| v.get(UnitBlade::new(1 << i)).unwrap());
+    Ok((x, y, (e3 + e4) / 2.0, e4 - e3))
+}
+
+/// Inner product of two vectors
+pub fn dot(a: &Multivector, b: &Multivector) -> Result<f64, String> {
+    Ok(a.dot(b)?.scalar_part())
+}
+
+/// Embed a point as a null vector. Infinity maps to infinity()
+pub fn point(z: Complex) -> Multivector {
+    match z {
+        Complex::Infinity => infinity(),
+        z => vector(z.real(), z.imag(), z.norm() / 2.0, 1.0),
+    }
+}
+
+/// Recover the point from a (possibly scaled) null vector
+pub fn to_point(v: &Multivector) -> Result<Complex, String> {
+    let (x, y, infinity_part, origin_part) = components(v)?;
+    if origin_part_is_zero(x, y, infinity_part, origin_part) {
+        if x == 0.0 && y == 0.0 && infinity_part == 0.0 {
+            return Err(String::from("the zero vector is not a point"));
+        }
+        return Ok(Complex::Infinity);
+    }
+
+    Ok(Complex::new(x / origin_part, y / origin_part))
+}
+
+/// Bivector for the pair of points {a, b}
+pub fn point_pair(a: Complex, b: Complex) -> Multivector {
+    point(a).wedge(&point(b)).unwrap()
+}
+
+/// Trivector for the circle (or line) through three points. A point p
+/// is on it when point(p) ^ circle = 0
+pub fn circle_through(a: Complex, b: Complex, c: Complex) -> Multivector {
+    point_pair(a, b).wedge(&point(c)).unwrap()
+}
+
+/// Trivector for the line through two points, i.e. the circle through
+/// them and infinity
+pub fn line_through(a: Complex, b: Complex) -> Multivector {
+    point_pair(a, b).wedge(&infinity()).unwrap()
+}
+
+/// Dual of a multivector, X I^-1 where I = e1 e2 e3 e4. This turns the
+/// trivectors from circle_through() and line_through() into the dual
+/// vectors used by to_generalized_circle() (up to scale)
+pub fn dual(x: &Multivector) -> Result<Multivector, String> {
+    // I^2 = -1 in R(3, 1), so I^-1 = -I
+    let pseudoscalar = Multivector::from_blade(signature(), UnitBlade::new(0b1111), -1.0)?;
+    x.geometric_product(&pseudoscalar)
+}
+
+/// Dual vector for a circle: point(center) - r^2/2 infinity
+pub fn circle(circle: Circle) -> Multivector {
+    let Circle { center, radius } = circle;
+    vector(
+        center.real(),
+        center.imag(),
+        (center.norm() - radius * radius) / 2.0,
+        1.0,
+    )
+}
+
+/// Dual vector for a line: unit_normal + distance * infinity
+pub fn line(line: Line) -> Multivector {
+    let n = line.unit_normal.get();
+    vector(n.real(), n.imag(), line.distance, 0.0)
+}
+
+/// Recover the circle or line from its dual vector
+pub fn to_generalized_circle(v: &Multivector) -> Result<GeneralizedCircle, String> {
+    let (x, y, infinity_part, origin_part) = components(v)?;
+    if origin_part_is_zero(x, y, infinity_part, origin_part) {
+        let unit_normal = UnitComplex::normalize(Complex::new(x, y))
+            .map_err(|_| String::from("vector is not a line"))?;
+        let distance = infinity_part / Complex::new(x, y).mag();
+        let line = Line::new(unit_normal, distance).map_err(|e| e.to_string())?;
+        return Ok(GeneralizedCircle::Line(line));
+    }
+
+    // The infinity part is (|c|^2 - r^2) / 2 times the origin part. This
+    // is computed from the components rather than from the norm, which
+    // would subtract the squares of the e3 and e4 components. Imaginary
+    // circles have a negative squared radius.
+    let (center_x, center_y) = (x / origin_part, y / origin_part);
+    let radius_squared =
+        center_x * center_x + center_y * center_y - 2.0 * infinity_part / origin_part;
+    if radius_squared <= 0.0 {
+        return Err(format!(
+            "vector is an imaginary circle: r^2 = {}",
+            radius_squared
+        ));
+    }
+
+    let center = Complex::new(center_x, center_y);
+    Ok(GeneralizedCircle::Circle(Circle::new(
+        center,
+        radius_squared.sqrt(),
+    )))
+}
+
+/// Apply a versor V to a vector X: V X V^-1. Points and circles are only
+/// defined up to scale, so the sign for odd versors is not needed.
+pub fn apply_versor(versor: &Multivector, x: &Multivector) -> Result<Multivector, String> {
+    versor
+        .geometric_product(x)?
+        .geometric_product(&versor.versor_inverse()?)
+}
+
+/// Versor for the composition of reflecting first in a, then in b
+fn reflect_twice(a: &Multivector, b: &Multivector) -> Multivector {
+    b.geometric_product(a).unwrap()
+}
+
+fn line_through_origin(unit_normal: UnitComplex, distance: f64) -> Multivector {
+    line(Line::new(unit_normal, distance).expect("distance is finite"))
+}
+
+/// Translation as a reflection in two parallel lines
+fn translation_versor(t: Complex) -> Multivector {
+    let Ok(direction) = UnitComplex::normalize(t) else {
+        return Multivector::scalar(signature(), 1.0);
+    };
+
+    let first = line_through_origin(direction, 0.0);
+    let second = line_through_origin(direction, t.mag() / 2.0);
+    reflect_twice(&first, &second)
+}
+
+/// z -> kz as a rotation (two reflections in lines through the origin)
+/// followed by a dilation (two inversions in circles about the origin)
+fn scale_versor(k: Complex) -> Multivector {
+    let half_angle = k.arg_or_zero() / 2.0;
+    let rotation = reflect_twice(
+        &line(Line::real_axis()),
+        &line_through_origin(UnitComplex::from_angle(half_angle).rot90(), 0.0),
+    );
+    let dilation = reflect_twice(
+        &circle(Circle::unit_circle()),
+        &circle(Circle::new(Complex::Zero, k.mag().sqrt())),
+    );
+
+    dilation.geometric_product(&rotation).unwrap()
+}
+
+/// z -> 1/z, inversion in the unit circle followed by reflection in the
+/// real axis
+fn reciprocal_versor() -> Multivector {
+    reflect_twice(&circle(Circle::unit_circle()), &line(Line::real_axis()))
+}
+
+/// Versor for a Mobius transformation, built from reflections and
+/// inversions. Applying it with apply_versor() to point(z) gives
+/// point(m * z)
+pub fn mobius_versor(m: Mobius) -> Multivector {
+    let Mobius { a, b, c, d } = m;
+    let factors = if c == Complex::Zero {
+        // m(z) = (a/d)z + b/d
+        vec![translation_versor(b / d), scale_versor(a / d)]
+    } else {
+        // m(z) = a/c - 1/(c^2 (z + d/c))
+        vec![
+            translation_versor(a / c),
+            scale_versor(-(c * c).inverse()),
+            reciprocal_versor(),
+            translation_versor(d / c),
+        ]
+    };
+
+    factors
+        .iter()
+        .fold(Multivector::scalar(signature(), 1.0), |product, x| {
+            product.geometric_product(x).unwrap()
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_close(a: Complex, b: Complex) {
+        match (a, b) {
+            (Complex::Infinity, Complex::Infinity) => {}
+            (a, b) => assert!((a - b).mag() < 1e-9, "{} != {}", a, b),
+        }
+    }
+
+    fn test_mobius() -> Vec<Mobius> {
+        vec![
+            Mobius::IDENTITY,
+            Mobius::INVERSION,
+            mobius::translation(Complex::new(1.0, -2.0)).unwrap(),
+            mobius::rotation(1.0).unwrap() * mobius::scale(3.0).unwrap(),
+            Mobius::from_unnormalized(
+                Complex::new(1.0, 2.0),
+                Complex::new(-1.0, 0.5),
+                Complex::new(0.5, -1.0),
+                Complex::new(2.0, 1.0),
+            )
+            .unwrap(),
+        ]
+    }
+
+    #[test]
+    fn origin_and_infinity_are_null_and_dual() {
+        assert_eq!(origin().norm_squared().unwrap(), 0.0);
+        assert_eq!(infinity().norm_squared().unwrap(), 0.0);
+        assert_eq!(dot(&origin(), &infinity()).unwrap(), -1.0);
+    }
+
+    #[test]
+    fn point_is_null_vector() {
+        let p = point(Complex::new(3.0, -4.0));
+
+        assert_eq!(p.norm_squared().unwrap(), 0.0);
+        assert_eq!(to_point(&p).unwrap(), Complex::new(3.0, -4.0));
+    }
+
+    #[test]
+    fn dot_of_points_is_half_squared_distance() {
+        let a = point(Complex::new(1.0, 1.0));
+        let b = point(Complex::new(4.0, 5.0));
+
+        let result = dot(&a, &b).unwrap();
+
+        assert_eq!(result, -12.5);
+    }
+
+    #[test]
+    fn to_point_of_infinity_returns_infinity() {
+        let result = to_point(&infinity().scale(2.0)).unwrap();
+
+        assert_eq!(result, Complex::Infinity);
+    }
+
+    #[test]
+    fn far_point_round_trips() {
+        for z in [Complex::new(2e6, 0.0), Complex::new(-3e6, 4e6)] {
+            let result = to_point(&point(z)).unwrap();
+
+            assert!((result - z).mag() < 1e-9 * z.mag(), "{} != {}", result, z);
+        }
+    }
+
+    #[test]
+    fn far_circle_round_trips() {
+        for center in [Complex::new(1e4, 1e4), Complex::new(2e6, 0.0)] {
+            let result = to_generalized_circle(&circle(Circle::new(center, 1.0))).unwrap();
+
+            match result {
+                GeneralizedCircle::Circle(Circle {
+                    center: result_center,
+                    radius,
+                }) => {
+                    assert!((result_center - center).mag() < 1e-6);
+                    assert!((radius - 1.0).abs() < 1e-3, "radius {}", radius);
+                }
+                x => panic!("expected a circle, got {:?}", x),
+            }
+        }
+    }
+
+    #[test]
+    fn circle_contains_points_on_it() {
+        let c = circle(Circle::new(Complex::new(1.0, 2.0), 3.0));
+
+        let on = dot(&point(Complex::new(4.0, 2.0)), &c).unwrap();
+        let off = dot(&point(Complex::new(0.0, 0.0)), &c).unwrap();
+
+        assert_eq!(on, 0.0);
+        assert!(off != 0.0);
+    }
+
+    #[test]
+    fn circle_round_trips() {
+        let expected = Circle::new(Complex::new(1.0, 2.0), 3.0);
+
+        let result = to_generalized_circle(&circle(expected).scale(-2.0)).unwrap();
+
+        assert_eq!(result, GeneralizedCircle::Circle(expected));
+    }
+
+    #[test]
+    fn line_round_trips() {
+        let expected = Line::from_points(Complex::new(1.0, 0.0), Complex::new(0.0, 1.0)).unwrap();
+
+        let result = to_generalized_circle(&line(expected).scale(3.0)).unwrap();
+
+        assert_eq!(result, GeneralizedCircle::Line(expected));
+    }
+
+    #[test]
+    fn circle_through_contains_fourth_point() {
+        let c = circle_through(Complex::ONE, Complex::I, Complex::new(-1.0, 0.0));
+
+        let on = point(Complex::new(0.0, -1.0)).wedge(&c).unwrap();
+        let off = point(Complex::new(0.5, 0.0)).wedge(&c).unwrap();
+
+        assert!(on.norm_squared().unwrap().abs() < 1e-12);
+        assert!(off.norm_squared().unwrap().abs() > 1e-3);
+    }
+
+    #[test]
+    fn line_through_contains_collinear_point() {
+        let l = line_through(Complex::Zero, Complex::new(1.0, 1.0));
+
+        let result = point(Complex::new(3.0, 3.0)).wedge(&l).unwrap();
+
+        assert!(result.norm_squared().unwrap().abs() < 1e-12);
+    }
+
+    #[test]
+    fn point_pair_of_same_point_is_zero() {
+        let result = point_pair(Complex::I, Complex::I);
+
+        assert_eq!(result, Multivector::zero(signature()));
+    }
+
+    #[test]
+    fn mobius_versor_matches_matrix() {
+        let points = [
+            Complex::Zero,
+            Complex::new(0.3, -0.7),
+            Complex::new(-2.0, 1.0),
+            Complex::Infinity,
+        ];
+
+        for m in test_mobius() {
+            let versor = mobius_versor(m);
+            for z in points {
+                let result = to_point(&apply_versor(&versor, &point(z)).unwrap()).unwrap();
+                assert_close(result, m * z);
+            }
+        }
+    }
+
+    #[test]
+    fn mobius_versor_maps_circles_to_image_circles() {
+        let original = Circle::new(Complex::new(0.5, 0.5), 2.0);
+        let on_circle = [0.0, 1.0, 2.0].map(|t| original.get_point(t));
+
+        for m in test_mobius() {
+            let image = apply_versor(&mobius_versor(m), &circle(original)).unwrap();
+            for z in on_circle {
+                let result = dot(&point(m * z), &image).unwrap();
+                assert!(result.abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn mobius_versor_of_product_is_product_of_versors() {
+        let [a, b] = [test_mobius()[3], test_mobius()[4]];
+        let z = Complex::new(0.25, 0.5);
+
+        let versor = mobius_versor(a)
+            .geometric_product(&mobius_versor(b))
+            .unwrap();
+        let result = to_point(&apply_versor(&versor, &point(z)).unwrap()).unwrap();
+
+        assert_close(result, (a * b) * z);
+    }
+}
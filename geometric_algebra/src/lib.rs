@@ -1,14 +1,26 @@
+pub mod cga2d;
+mod multivector;
+mod product_table;
 mod signature;
 mod unit_blade;
+mod versor;
+pub mod viz;
 
+pub use multivector::Multivector;
+pub use product_table::ProductTable;
 pub use signature::Signature;
-use unit_blade::UnitBlade;
+pub use unit_blade::UnitBlade;
+pub use versor::{Cga2dVersor, Versor};
 
 fn choose_bits(n: usize, choices: &[u8]) -> Vec<u8> {
-    if n == 0 || choices.is_empty() {
+    if n == 0 {
         return vec![0];
     }
 
+    if choices.is_empty() {
+        return vec![];
+    }
+
     let mut result = Vec::new();
     for i in 0..choices.len() {
         let choice = choices[i];
@@ -67,7 +79,7 @@ mod test {
             0b0, // pseudoscalar
             0b1,
         ]
-        .map(|x| UnitBlade::new(x))
+        .map(UnitBlade::new)
         .to_vec();
 
         assert_eq!(result, expected_blades);
@@ -86,7 +98,7 @@ mod test {
             0b011, 0b101, 0b110, // pseudoscalar
             0b111,
         ]
-        .map(|x| UnitBlade::new(x))
+        .map(UnitBlade::new)
         .to_vec();
 
         assert_eq!(result, expected_blades);
@@ -106,7 +118,7 @@ mod test {
             0b0111, 0b1011, 0b1101, 0b1110, // pseudoscalr
             0b1111,
         ]
-        .map(|x| UnitBlade::new(x))
+        .map(UnitBlade::new)
         .to_vec();
 
         assert_eq!(result, expected_blades)
@@ -0,0 +1,659 @@
+use mobius::Tolerance;
+
+use crate::{ProductTable, Signature, UnitBlade};
+
+/// Relative size below which parts of a product are treated as rounding
+/// error in exp() and log()
+const TOLERANCE: f64 = 1e-12;
+
+/// TOLERANCE scaled to the current mobius::Tolerance
+fn tolerance() -> f64 {
+    Tolerance::current().scale_relative(TOLERANCE)
+}
+
+/// Multivector in any signature, with dimensions chosen at runtime. The
+/// components are indexed by the bitmask of each unit blade, so there
+/// are 2^n of them for n dimensions.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Multivector {
+    signature: Signature,
+    components: Vec<f64>,
+}
+
+impl Multivector {
+    pub fn zero(signature: Signature) -> Self {
+        Self {
+            signature,
+            components: vec![0.0; 1 << signature.get_dimensions()],
+        }
+    }
+
+    pub fn scalar(signature: Signature, value: f64) -> Self {
+        let mut result = Self::zero(signature);
+        result.components[0] = value;
+        result
+    }
+
+    /// A multiple of a single unit blade
+    pub fn from_blade(signature: Signature, blade: UnitBlade, value: f64) -> Result<Self, String> {
+        let mut result = Self::zero(signature);
+        let index = result.index(blade)?;
+        result.components[index] = value;
+        Ok(result)
+    }
+
+    /// The i-th basis vector, counting from 0. See Signature for the order
+    pub fn basis_vector(signature: Signature, i: usize) -> Result<Self, String> {
+        let n = signature.get_dimensions();
+        if i >= n {
+            return Err(format!("basis vector must be in [0, {}]", n - 1));
+        }
+
+        Self::from_blade(signature, UnitBlade::new(1 << i), 1.0)
+    }
+
+    /// Vector with the given components, one per dimension
+    pub fn vector(signature: Signature, components: &[f64]) -> Result<Self, String> {
+        let n = signature.get_dimensions();
+        if components.len() != n {
+            return Err(format!(
+                "expected {} components, got {}",
+                n,
+                components.len()
+            ));
+        }
+
+        let mut result = Self::zero(signature);
+        for (i, value) in components.iter().enumerate() {
+            result.components[1 << i] = *value;
+        }
+        Ok(result)
+    }
+
+    pub fn signature(&self) -> Signature {
+        self.signature
+    }
+
+    fn index(&self, blade: UnitBlade) -> Result<usize, String> {
+        let index = blade.bits() as usize;
+        if index >= self.components.len() {
+            return Err(format!(
+                "blade {:#b} is not in a {}-dimensional algebra",
+                index,
+                self.signature.get_dimensions()
+            ));
+        }
+        Ok(index)
+    }
+
+    /// Coefficient of a unit blade
+    pub fn get(&self, blade: UnitBlade) -> Result<f64, String> {
+        Ok(self.components[self.index(blade)?])
+    }
+
+    /// Coefficients of every unit blade, indexed by the blade's bitmask
+    pub fn components(&self) -> &[f64] {
+        &self.components
+    }
+
+    pub fn scalar_part(&self) -> f64 {
+        self.components[0]
+    }
+
+    fn require_same_signature(&self, other: &Self) -> Result<(), String> {
+        if self.signature != other.signature {
+            return Err(format!(
+                "signatures must match, got {:?} and {:?}",
+                self.signature, other.signature
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn add(&self, other: &Self) -> Result<Self, String> {
+        self.require_same_signature(other)?;
+        let components = self
+            .components
+            .iter()
+            .zip(other.components.iter())
+            .map(|(a, b)| a + b)
+            .collect();
+
+        Ok(Self {
+            signature: self.signature,
+            components,
+        })
+    }
+
+    pub fn sub(&self, other: &Self) -> Result<Self, String> {
+        self.add(&other.scale(-1.0))
+    }
+
+    pub fn scale(&self, k: f64) -> Self {
+        Self {
+            signature: self.signature,
+            components: self.components.iter().map(|x| k * x).collect(),
+        }
+    }
+
+    /// Sum of products of pairs of blades, keeping only the pairs where
+    /// keep(grade of self blade, grade of other blade, grade of product)
+    /// returns true
+    fn filtered_product(
+        &self,
+        other: &Self,
+        keep: impl Fn(usize, usize, usize) -> bool,
+    ) -> Result<Self, String> {
+        self.require_same_signature(other)?;
+
+        let table = ProductTable::cached(self.signature);
+        let mut result = Self::zero(self.signature);
+        for (i, a) in self.components.iter().enumerate() {
+            if *a == 0.0 {
+                continue;
+            }
+            let blade_a = UnitBlade::new(i as u8);
+            for (j, b) in other.components.iter().enumerate() {
+                if *b == 0.0 {
+                    continue;
+                }
+                let blade_b = UnitBlade::new(j as u8);
+                let (sign, blade) = table.get(blade_a, blade_b)?;
+                if sign != 0 && keep(blade_a.grade(), blade_b.grade(), blade.grade()) {
+                    result.components[blade.bits() as usize] += (sign as f64) * a * b;
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    pub fn geometric_product(&self, other: &Self) -> Result<Self, String> {
+        self.filtered_product(other, |_, _, _| true)
+    }
+
+    /// Outer product a ^ b: the part of the geometric product where the
+    /// grades add
+    pub fn wedge(&self, other: &Self) -> Result<Self, String> {
+        self.filtered_product(other, |r, s, t| t == r + s)
+    }
+
+    /// Inner product, using the left contraction convention: the part of
+    /// the geometric product with grade s - r for blades of grade r and s.
+    /// This is 0 when r > s. For two vectors it is the usual dot product
+    /// as a scalar
+    pub fn dot(&self, other: &Self) -> Result<Self, String> {
+        self.filtered_product(other, |r, s, t| s >= r && t == s - r)
+    }
+
+    /// Only the components of grade k
+    pub fn grade(&self, k: usize) -> Self {
+        let components = self
+            .components
+            .iter()
+            .enumerate()
+            .map(|(i, x)| {
+                if (i as u8).count_ones() as usize == k {
+                    *x
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        Self {
+            signature: self.signature,
+            components,
+        }
+    }
+
+    /// Negate the odd grades. This is the automorphism that sends each
+    /// vector v to -v
+    pub fn grade_involution(&self) -> Self {
+        let components = self
+            .components
+            .iter()
+            .enumerate()
+            .map(|(i, x)| {
+                if (i as u8).count_ones() % 2 == 1 {
+                    -x
+                } else {
+                    *x
+                }
+            })
+            .collect();
+
+        Self {
+            signature: self.signature,
+            components,
+        }
+    }
+
+    /// Reverse the order of the vectors in each blade. A blade of grade k
+    /// picks up a sign of (-1)^(k(k - 1)/2)
+    pub fn reverse(&self) -> Self {
+        let components = self
+            .components
+            .iter()
+            .enumerate()
+            .map(|(i, x)| {
+                let k = (i as u8).count_ones();
+                // k(k - 1)/2 is odd when k = 2 or 3 mod 4
+                if k % 4 >= 2 {
+                    -x
+                } else {
+                    *x
+                }
+            })
+            .collect();
+
+        Self {
+            signature: self.signature,
+            components,
+        }
+    }
+
+    /// sqrt(|self * reverse(self)|). For versors in mixed signatures the
+    /// scalar can be negative, hence the absolute value
+    pub fn norm(&self) -> Result<f64, String> {
+        Ok(self.norm_squared()?.abs().sqrt())
+    }
+
+    /// Inverse of a versor (a product of vectors) or a blade, computed as
+    /// reverse(self) / (self * reverse(self)). This does not work for
+    /// general multivectors, or for null versors that have no inverse
+    pub fn versor_inverse(&self) -> Result<Self, String> {
+        let norm_squared = self.norm_squared()?;
+        if norm_squared == 0.0 {
+            return Err(String::from("null versors have no inverse"));
+        }
+
+        Ok(self.reverse().scale(1.0 / norm_squared))
+    }
+
+    /// Largest absolute value of any component
+    fn max_abs(&self) -> f64 {
+        self.components.iter().fold(0.0, |max, x| max.max(x.abs()))
+    }
+
+    /// Square of a simple bivector, which is a scalar. Returns an error
+    /// for other multivectors
+    fn simple_bivector_square(&self) -> Result<f64, String> {
+        if self.grade(2) != *self {
+            return Err(String::from("expected a bivector"));
+        }
+
+        let square = self.geometric_product(self)?;
+        let scalar = square.scalar_part();
+        let rest = square.sub(&Self::scalar(self.signature, scalar))?;
+        if rest.max_abs() > tolerance() * square.max_abs().max(1.0) {
+            return Err(String::from(
+                "only simple bivectors (B^2 is a scalar) are supported",
+            ));
+        }
+
+        Ok(scalar)
+    }
+
+    /// Exponential of a simple bivector B, which is a rotor. Depending on
+    /// the sign of B^2 = -theta^2, 0 or lambda^2 this is
+    ///
+    /// - cos(theta) + B sin(theta)/theta (rotations)
+    /// - 1 + B (translations in CGA and PGA)
+    /// - cosh(lambda) + B sinh(lambda)/lambda (boosts and dilations)
+    ///
+    /// exp(-B theta/2) rotates by theta in the plane of the unit bivector B.
+    pub fn exp(&self) -> Result<Self, String> {
+        let square = self.simple_bivector_square()?;
+        let (scalar, factor) = if square < 0.0 {
+            let theta = (-square).sqrt();
+            (theta.cos(), theta.sin() / theta)
+        } else if square > 0.0 {
+            let lambda = square.sqrt();
+            (lambda.cosh(), lambda.sinh() / lambda)
+        } else {
+            (1.0, 1.0)
+        };
+
+        self.scale(factor)
+            .add(&Self::scalar(self.signature, scalar))
+    }
+
+    /// Logarithm of a rotor s + B made of a scalar and a simple bivector,
+    /// the inverse of exp(). For rotations this picks the angle in
+    /// (-pi, pi]. Returns an error if there is no such logarithm, e.g. for
+    /// a rotation by exactly pi (where the plane is ambiguous) or for
+    /// rotors with other grades.
+    ///
+    /// Rotors of non-simple bivectors are not decomposed, so this also
+    /// returns an error for them. In 4D and above these have a grade 4
+    /// part, e.g. loxodromic transformations in CGA that rotate and
+    /// dilate about the same point
+    pub fn log(&self) -> Result<Self, String> {
+        let s = self.scalar_part();
+        let bivector = self.grade(2);
+        let rest = self.sub(&bivector)?.sub(&Self::scalar(self.signature, s))?;
+        if rest.max_abs() > tolerance() * self.max_abs() {
+            return Err(String::from(
+                "rotors must only have scalar and bivector parts",
+            ));
+        }
+
+        let square = bivector.simple_bivector_square()?;
+        let size = square.abs().sqrt();
+        if size <= tolerance() * s.abs() {
+            // B^2 = 0 up to rounding, so R = s(1 + B/s)
+            if s <= 0.0 {
+                return Err(format!("rotor has no logarithm: scalar part {}", s));
+            }
+            return Ok(bivector.scale(1.0 / s));
+        }
+
+        let factor = if square < 0.0 {
+            size.atan2(s) / size
+        } else {
+            if s <= 0.0 {
+                return Err(format!("rotor has no logarithm: scalar part {}", s));
+            }
+            (size / s).atanh() / size
+        };
+
+        Ok(bivector.scale(factor))
+    }
+
+    /// Scalar part of self * reverse(self). This is the squared length
+    /// for vectors, which can be negative or zero in mixed signatures
+    pub fn norm_squared(&self) -> Result<f64, String> {
+        Ok(self.geometric_product(&self.reverse())?.scalar_part())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn basis(signature: Signature, i: usize) -> Multivector {
+        Multivector::basis_vector(signature, i).unwrap()
+    }
+
+    #[test]
+    fn basis_vector_out_of_range_returns_error() {
+        let result = Multivector::basis_vector(Signature::cga_2d(), 4);
+
+        assert!(result.is_err_and(|e| e.contains("basis vector must be in [0, 3]")));
+    }
+
+    #[test]
+    fn mismatched_signatures_return_error() {
+        let a = basis(Signature::cga_2d(), 0);
+        let b = basis(Signature::pga_3d(), 0);
+
+        let result = a.geometric_product(&b);
+
+        assert!(result.is_err_and(|e| e.contains("signatures must match")));
+    }
+
+    #[test]
+    fn euclidean_bivector_squares_to_negative_one() {
+        let signature = Signature::new(3, 0, 0).unwrap();
+        let xy = basis(signature, 0).wedge(&basis(signature, 1)).unwrap();
+
+        let result = xy.geometric_product(&xy).unwrap();
+
+        assert_eq!(result, Multivector::scalar(signature, -1.0));
+    }
+
+    #[test]
+    fn cga_null_vectors_square_to_zero() {
+        // origin = (e4 - e3) / 2 and infinity = e3 + e4
+        let signature = Signature::cga_2d();
+        let origin = basis(signature, 3)
+            .sub(&basis(signature, 2))
+            .unwrap()
+            .scale(0.5);
+        let infinity = basis(signature, 2).add(&basis(signature, 3)).unwrap();
+
+        assert_eq!(origin.norm_squared().unwrap(), 0.0);
+        assert_eq!(infinity.norm_squared().unwrap(), 0.0);
+        assert_eq!(origin.dot(&infinity).unwrap().scalar_part(), -1.0);
+    }
+
+    #[test]
+    fn pga_degenerate_vector_squares_to_zero() {
+        let signature = Signature::pga_3d();
+        let e0 = basis(signature, 3);
+
+        let result = e0.geometric_product(&e0).unwrap();
+
+        assert_eq!(result, Multivector::zero(signature));
+    }
+
+    #[test]
+    fn spacetime_interval_uses_minkowski_metric() {
+        let event = Multivector::vector(Signature::spacetime(), &[5.0, 3.0, 0.0, 0.0]).unwrap();
+
+        let result = event.norm_squared().unwrap();
+
+        assert_eq!(result, 16.0);
+    }
+
+    #[test]
+    fn wedge_of_parallel_vectors_is_zero() {
+        let signature = Signature::cga_3d();
+        let v = Multivector::vector(signature, &[1.0, 2.0, 3.0, 4.0, 5.0]).unwrap();
+
+        let result = v.wedge(&v.scale(2.0)).unwrap();
+
+        assert_eq!(result, Multivector::zero(signature));
+    }
+
+    #[test]
+    fn geometric_product_is_dot_plus_wedge_for_vectors() {
+        let signature = Signature::cga_2d();
+        let a = Multivector::vector(signature, &[1.0, 2.0, 0.0, 1.0]).unwrap();
+        let b = Multivector::vector(signature, &[0.0, 1.0, 3.0, 2.0]).unwrap();
+
+        let result = a.geometric_product(&b).unwrap();
+
+        let expected = a.dot(&b).unwrap().add(&a.wedge(&b).unwrap()).unwrap();
+        assert_eq!(result, expected);
+        assert_eq!(result.grade(0).scalar_part(), 2.0 - 2.0);
+    }
+
+    #[test]
+    fn versor_inverse_undoes_rotor() {
+        let signature = Signature::new(3, 0, 0).unwrap();
+        let a = Multivector::vector(signature, &[1.0, 2.0, 0.0]).unwrap();
+        let b = Multivector::vector(signature, &[0.0, 1.0, 1.0]).unwrap();
+        let rotor = a.geometric_product(&b).unwrap();
+
+        let result = rotor
+            .geometric_product(&rotor.versor_inverse().unwrap())
+            .unwrap();
+
+        assert_eq!(result, Multivector::scalar(signature, 1.0));
+    }
+
+    #[test]
+    fn versor_inverse_of_null_vector_returns_error() {
+        let signature = Signature::pga_3d();
+
+        let result = basis(signature, 3).versor_inverse();
+
+        assert!(result.is_err_and(|e| e.contains("null versors have no inverse")));
+    }
+
+    #[test]
+    fn grade_involution_negates_odd_grades() {
+        let signature = Signature::new(2, 0, 0).unwrap();
+        let x = Multivector::vector(signature, &[1.0, 2.0])
+            .unwrap()
+            .add(&Multivector::scalar(signature, 3.0))
+            .unwrap();
+
+        let result = x.grade_involution();
+
+        let expected = Multivector::vector(signature, &[-1.0, -2.0])
+            .unwrap()
+            .add(&Multivector::scalar(signature, 3.0))
+            .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn norm_of_spacelike_vector_is_positive() {
+        let v = Multivector::vector(Signature::spacetime(), &[0.0, 3.0, 4.0, 0.0]).unwrap();
+
+        let result = v.norm().unwrap();
+
+        assert_eq!(result, 5.0);
+    }
+
+    fn assert_close(a: &Multivector, b: &Multivector) {
+        let diff = a.sub(b).unwrap();
+        assert!(diff.max_abs() < 1e-12, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn exp_of_bivector_rotates_vectors() {
+        let signature = Signature::new(3, 0, 0).unwrap();
+        let xy = Multivector::from_blade(signature, UnitBlade::new(0b011), 1.0).unwrap();
+        let angle = std::f64::consts::FRAC_PI_2;
+
+        let rotor = xy.scale(-angle / 2.0).exp().unwrap();
+        let result = rotor
+            .geometric_product(&basis(signature, 0))
+            .unwrap()
+            .geometric_product(&rotor.reverse())
+            .unwrap();
+
+        assert_close(&result, &basis(signature, 1));
+    }
+
+    #[test]
+    fn exp_of_null_bivector_is_one_plus_bivector() {
+        // e1 ^ (e3 + e4) generates translations in CGA
+        let signature = Signature::cga_2d();
+        let infinity = basis(signature, 2).add(&basis(signature, 3)).unwrap();
+        let b = basis(signature, 0).wedge(&infinity).unwrap();
+
+        let result = b.exp().unwrap();
+
+        assert_close(
+            &result,
+            &b.add(&Multivector::scalar(signature, 1.0)).unwrap(),
+        );
+    }
+
+    #[test]
+    fn exp_of_non_bivector_returns_error() {
+        let result = basis(Signature::cga_2d(), 0).exp();
+
+        assert!(result.is_err_and(|e| e.contains("expected a bivector")));
+    }
+
+    #[test]
+    fn exp_of_non_simple_bivector_returns_error() {
+        // e12 + e34 squares to a scalar plus a 4-vector
+        let signature = Signature::new(4, 0, 0).unwrap();
+        let b = Multivector::from_blade(signature, UnitBlade::new(0b0011), 1.0)
+            .unwrap()
+            .add(&Multivector::from_blade(signature, UnitBlade::new(0b1100), 1.0).unwrap())
+            .unwrap();
+
+        let result = b.exp();
+
+        assert!(result.is_err_and(|e| e.contains("only simple bivectors")));
+    }
+
+    #[test]
+    fn log_undoes_exp() {
+        let signature = Signature::cga_2d();
+        let [e1, e2, e3, e4] = [0, 1, 2, 3].map(|i| basis(signature, i));
+        let bivectors = [
+            // rotation
+            e1.wedge(&e2).unwrap().scale(0.7),
+            // translation
+            e2.wedge(&e3.add(&e4).unwrap()).unwrap().scale(1.5),
+            // dilation
+            e3.wedge(&e4).unwrap().scale(-0.4),
+        ];
+
+        for b in bivectors {
+            let result = b.exp().unwrap().log().unwrap();
+
+            assert_close(&result, &b);
+        }
+    }
+
+    #[test]
+    fn log_of_non_simple_rotor_returns_error() {
+        let signature = Signature::cga_2d();
+        let [e1, e2, e3, e4] = [0, 1, 2, 3].map(|i| basis(signature, i));
+        let rotation = e1.wedge(&e2).unwrap().scale(0.7).exp().unwrap();
+        let dilation = e3.wedge(&e4).unwrap().scale(-0.4).exp().unwrap();
+        let loxodromic = rotation.geometric_product(&dilation).unwrap();
+
+        let result = loxodromic.log();
+
+        assert!(result.is_err_and(|e| e.contains("only have scalar and bivector parts")));
+    }
+
+    #[test]
+    fn log_of_negative_one_returns_error() {
+        let signature = Signature::new(3, 0, 0).unwrap();
+
+        let result = Multivector::scalar(signature, -1.0).log();
+
+        assert!(result.is_err_and(|e| e.contains("rotor has no logarithm")));
+    }
+
+    #[test]
+    fn dot_of_vector_into_bivector_is_vector_in_plane() {
+        let signature = Signature::new(3, 0, 0).unwrap();
+        let [x, y, z] = [0, 1, 2].map(|i| basis(signature, i));
+        let xy = x.wedge(&y).unwrap();
+
+        assert_eq!(x.dot(&xy).unwrap(), y);
+        assert_eq!(z.dot(&xy).unwrap(), Multivector::zero(signature));
+    }
+
+    #[test]
+    fn dot_of_higher_grade_into_lower_is_zero() {
+        let signature = Signature::new(3, 0, 0).unwrap();
+        let xy = basis(signature, 0).wedge(&basis(signature, 1)).unwrap();
+
+        let result = xy.dot(&basis(signature, 0)).unwrap();
+
+        assert_eq!(result, Multivector::zero(signature));
+    }
+
+    #[test]
+    fn grade_projections_sum_to_whole() {
+        let signature = Signature::new(3, 0, 0).unwrap();
+        let a = Multivector::vector(signature, &[1.0, 2.0, 3.0]).unwrap();
+        let b = Multivector::vector(signature, &[-1.0, 0.5, 2.0]).unwrap();
+        let product = a.geometric_product(&b).unwrap();
+
+        let result = (0..=3).fold(Multivector::zero(signature), |sum, k| {
+            sum.add(&product.grade(k)).unwrap()
+        });
+
+        assert_eq!(result, product);
+        assert_eq!(
+            product.grade(0).scalar_part(),
+            a.dot(&b).unwrap().scalar_part()
+        );
+        assert_eq!(product.grade(2), a.wedge(&b).unwrap());
+    }
+
+    #[test]
+    fn reverse_negates_bivectors() {
+        let signature = Signature::new(3, 0, 0).unwrap();
+        let xy = Multivector::from_blade(signature, UnitBlade::new(0b011), 2.0).unwrap();
+
+        let result = xy.reverse();
+
+        assert_eq!(result, xy.scale(-1.0));
+    }
+}
@@ -0,0 +1,208 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use crate::{Signature, UnitBlade};
+
+thread_local! {
+    static CACHED_TABLES: RefCell<HashMap<Signature, Rc<ProductTable>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Geometric product of every pair of unit blades in a signature,
+/// computed from the bit representation so no signs are written by hand.
+/// Besides lookups, this can generate the expanded product of two
+/// multivectors as Rust source, for writing concrete multivector structs
+/// for a new signature.
+#[derive(Clone, Debug)]
+pub struct ProductTable {
+    signature: Signature,
+    blade_count: usize,
+    entries: Vec<(i8, UnitBlade)>,
+}
+
+impl ProductTable {
+    pub fn new(signature: Signature) -> Self {
+        let blade_count = 1 << signature.get_dimensions();
+        let entries = (0..blade_count)
+            .flat_map(|a| (0..blade_count).map(move |b| (a, b)))
+            .map(|(a, b)| {
+                UnitBlade::new(a as u8)
+                    .product(&UnitBlade::new(b as u8), &signature)
+                    .expect("blades are within the signature")
+            })
+            .collect();
+
+        Self {
+            signature,
+            blade_count,
+            entries,
+        }
+    }
+
+    /// Shared table for a signature, built the first time it is needed
+    /// on each thread. Multivector products look up signs here rather
+    /// than recomputing them for every pair of blades.
+    pub fn cached(signature: Signature) -> Rc<Self> {
+        CACHED_TABLES.with(|tables| {
+            tables
+                .borrow_mut()
+                .entry(signature)
+                .or_insert_with(|| Rc::new(Self::new(signature)))
+                .clone()
+        })
+    }
+
+    pub fn signature(&self) -> Signature {
+        self.signature
+    }
+
+    /// The product a * b as (sign, blade)
+    pub fn get(&self, a: UnitBlade, b: UnitBlade) -> Result<(i8, UnitBlade), String> {
+        let (i, j) = (a.bits() as usize, b.bits() as usize);
+        if i >= self.blade_count || j >= self.blade_count {
+            return Err(format!(
+                "blades must be in a {}-dimensional algebra",
+                self.signature.get_dimensions()
+            ));
+        }
+
+        Ok(self.entries[i * self.blade_count + j])
+    }
+
+    /// Name of a blade for generated code: the labels of its vectors
+    /// concatenated, or "scalar" for the scalar blade
+    fn field_name(blade: UnitBlade, labels: &[&str]) -> String {
+        match blade.bits() {
+            0 => String::from("scalar"),
+            _ => blade.pretty(labels),
+        }
+    }
+
+    /// Rust source for the geometric product of two full multivectors
+    /// a and b whose fields are named after the blades, e.g.
+    /// `let xy = a.x * b.y - a.y * b.x + ...;`. One line per output
+    /// blade, in order of bitmask. labels names each basis vector.
+    pub fn generate_product_code(&self, labels: &[&str]) -> Result<String, String> {
+        let n = self.signature.get_dimensions();
+        if labels.len() != n {
+            return Err(format!("expected {} labels, got {}", n, labels.len()));
+        }
+
+        // terms[k] is the list of (sign, a field, b field) for blade k
+        let mut terms: Vec<Vec<(i8, String, String)>> = vec![Vec::new(); self.blade_count];
+        for i in 0..self.blade_count {
+            for j in 0..self.blade_count {
+                let (sign, blade) = self.entries[i * self.blade_count + j];
+                if sign != 0 {
+                    terms[blade.bits() as usize].push((
+                        sign,
+                        Self::field_name(UnitBlade::new(i as u8), labels),
+                        Self::field_name(UnitBlade::new(j as u8), labels),
+                    ));
+                }
+            }
+        }
+
+        let lines: Vec<String> = terms
+            .iter()
+            .enumerate()
+            .map(|(k, terms)| {
+                let mut expression = String::new();
+                for (index, (sign, a, b)) in terms.iter().enumerate() {
+                    let operator = match (index, sign) {
+                        (0, 1) => "",
+                        (0, _) => "-",
+                        (_, 1) => " + ",
+                        _ => " - ",
+                    };
+                    expression.push_str(&format!("{}a.{} * b.{}", operator, a, b));
+                }
+                if expression.is_empty() {
+                    expression.push_str("0.0");
+                }
+
+                let name = Self::field_name(UnitBlade::new(k as u8), labels);
+                format!("let {} = {};", name, expression)
+            })
+            .collect();
+
+        Ok(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn get_matches_unit_blade_product() {
+        let signature = Signature::cga_2d();
+        let table = ProductTable::new(signature);
+
+        for i in 0..16 {
+            for j in 0..16 {
+                let (a, b) = (UnitBlade::new(i), UnitBlade::new(j));
+                assert_eq!(table.get(a, b).unwrap(), a.product(&b, &signature).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn cached_shares_table_per_signature() {
+        let a = ProductTable::cached(Signature::cga_2d());
+        let b = ProductTable::cached(Signature::cga_2d());
+        let c = ProductTable::cached(Signature::pga_3d());
+
+        assert!(Rc::ptr_eq(&a, &b));
+        assert_eq!(c.signature(), Signature::pga_3d());
+    }
+
+    #[test]
+    fn get_out_of_range_returns_error() {
+        let table = ProductTable::new(Signature::new(2, 0, 0).unwrap());
+
+        let result = table.get(UnitBlade::new(0b100), UnitBlade::new(0));
+
+        assert!(result.is_err_and(|e| e.contains("2-dimensional")));
+    }
+
+    #[test]
+    fn generate_product_code_expands_complex_numbers() {
+        // The even subalgebra of R(2, 0) is the complex numbers, and the
+        // full product of R(2, 0) contains it
+        let table = ProductTable::new(Signature::new(2, 0, 0).unwrap());
+
+        let result = table.generate_product_code(&["x", "y"]).unwrap();
+
+        let expected = [
+            "let scalar = a.scalar * b.scalar + a.x * b.x + a.y * b.y - a.xy * b.xy;",
+            "let x = a.scalar * b.x + a.x * b.scalar - a.y * b.xy + a.xy * b.y;",
+            "let y = a.scalar * b.y + a.x * b.xy + a.y * b.scalar - a.xy * b.x;",
+            "let xy = a.scalar * b.xy + a.x * b.y - a.y * b.x + a.xy * b.scalar;",
+        ]
+        .join("\n");
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn generate_product_code_skips_degenerate_terms() {
+        let table = ProductTable::new(Signature::new(0, 0, 1).unwrap());
+
+        let result = table.generate_product_code(&["e0"]).unwrap();
+
+        let expected = [
+            "let scalar = a.scalar * b.scalar;",
+            "let e0 = a.scalar * b.e0 + a.e0 * b.scalar;",
+        ]
+        .join("\n");
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn generate_product_code_with_wrong_label_count_returns_error() {
+        let table = ProductTable::new(Signature::pga_3d());
+
+        let result = table.generate_product_code(&["x", "y"]);
+
+        assert!(result.is_err_and(|e| e.contains("expected 4 labels")));
+    }
+}
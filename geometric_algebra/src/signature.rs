@@ -1,3 +1,6 @@
+/// Number of basis vectors that square to +1, -1 and 0 respectively.
+/// Basis vectors are ordered positive, then negative, then zero.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub struct Signature {
     positive: usize,
     negative: usize,
@@ -17,6 +20,26 @@ impl Signature {
         })
     }
 
+    /// Conformal geometric algebra for the plane, R(3, 1)
+    pub fn cga_2d() -> Self {
+        Self::new(3, 1, 0).unwrap()
+    }
+
+    /// Conformal geometric algebra for 3D space, R(4, 1)
+    pub fn cga_3d() -> Self {
+        Self::new(4, 1, 0).unwrap()
+    }
+
+    /// Projective geometric algebra for 3D space, R(3, 0, 1)
+    pub fn pga_3d() -> Self {
+        Self::new(3, 0, 1).unwrap()
+    }
+
+    /// Spacetime algebra R(1, 3), with time as the first basis vector
+    pub fn spacetime() -> Self {
+        Self::new(1, 3, 0).unwrap()
+    }
+
     pub fn get_dimensions(&self) -> usize {
         self.positive + self.negative + self.zero
     }
@@ -1,13 +1,60 @@
+use crate::Signature;
+
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub struct UnitBlade {
     vectors: u8,
 }
 
 impl UnitBlade {
-    pub fn new(vectors: u8) -> Self {
+    pub const fn new(vectors: u8) -> Self {
         Self { vectors }
     }
 
+    /// Bitmask of the basis vectors in this blade
+    pub const fn bits(&self) -> u8 {
+        self.vectors
+    }
+
+    /// Number of basis vectors in this blade
+    pub fn grade(&self) -> usize {
+        self.vectors.count_ones() as usize
+    }
+
+    /// Sign from sorting the basis vectors of self * other into
+    /// increasing order, where each swap flips the sign. This does not
+    /// depend on the signature
+    pub const fn reorder_sign(&self, other: &Self) -> i8 {
+        let mut swaps = 0;
+        let mut a = self.vectors >> 1;
+        while a != 0 {
+            swaps += (a & other.vectors).count_ones();
+            a >>= 1;
+        }
+
+        if swaps % 2 == 0 {
+            1
+        } else {
+            -1
+        }
+    }
+
+    /// Geometric product of two unit blades, which is another unit blade
+    /// times a sign. The sign comes from reorder_sign(), then squaring
+    /// the shared vectors according to the signature, so it is 0 if a
+    /// degenerate vector is shared.
+    pub fn product(&self, other: &Self, signature: &Signature) -> Result<(i8, Self), String> {
+        let mut sign = self.reorder_sign(other);
+
+        let shared = self.vectors & other.vectors;
+        for i in 0..8 {
+            if shared >> i & 1 == 1 {
+                sign *= signature.get_sign(i)?;
+            }
+        }
+
+        Ok((sign, Self::new(self.vectors ^ other.vectors)))
+    }
+
     pub fn pretty(&self, labels: &[&str]) -> String {
         let components: Vec<&str> = (0..8)
             .map(|i| {
@@ -43,4 +90,37 @@ mod test {
 
         assert_eq!(result, "xz");
     }
+
+    #[test]
+    fn product_of_swapped_vectors_anticommutes() {
+        let signature = Signature::new(3, 0, 0).unwrap();
+        let x = UnitBlade::new(0b01);
+        let y = UnitBlade::new(0b10);
+
+        let xy = x.product(&y, &signature).unwrap();
+        let yx = y.product(&x, &signature).unwrap();
+
+        assert_eq!(xy, (1, UnitBlade::new(0b11)));
+        assert_eq!(yx, (-1, UnitBlade::new(0b11)));
+    }
+
+    #[test]
+    fn product_uses_signature_for_shared_vectors() {
+        let signature = Signature::new(1, 1, 1).unwrap();
+        let [x, y, z] = [0b001, 0b010, 0b100].map(UnitBlade::new);
+
+        assert_eq!(x.product(&x, &signature).unwrap().0, 1);
+        assert_eq!(y.product(&y, &signature).unwrap().0, -1);
+        assert_eq!(z.product(&z, &signature).unwrap().0, 0);
+    }
+
+    #[test]
+    fn product_of_bivector_with_itself_is_negative() {
+        let signature = Signature::new(3, 0, 0).unwrap();
+        let xy = UnitBlade::new(0b011);
+
+        let result = xy.product(&xy, &signature).unwrap();
+
+        assert_eq!(result, (-1, UnitBlade::new(0)));
+    }
 }
@@ -0,0 +1,385 @@
+use std::ops::Mul;
+
+use abstraction::{Group, Monoid};
+use mobius::Tolerance;
+
+use crate::{Multivector, Signature};
+
+/// How far components can differ for two versors to be considered equal
+const TOLERANCE: f64 = 1e-10;
+
+/// TOLERANCE scaled to the current mobius::Tolerance
+fn tolerance() -> f64 {
+    Tolerance::current().scale_relative(TOLERANCE)
+}
+
+/// A normalized versor, i.e. a product of invertible vectors scaled so
+/// |V reverse(V)| = 1. Versors act on multivectors by sandwiching, e.g.
+/// rotors in Euclidean space or Mobius transformations in CGA. The
+/// signature R(P, Q, R) is part of the type so the identity is known
+/// without any arguments.
+#[derive(Clone, Debug)]
+pub struct Versor<const P: usize, const Q: usize, const R: usize> {
+    multivector: Multivector,
+}
+
+/// Versors of 2D conformal geometric algebra, see cga2d
+pub type Cga2dVersor = Versor<3, 1, 0>;
+
+impl<const P: usize, const Q: usize, const R: usize> Versor<P, Q, R> {
+    pub fn signature() -> Signature {
+        Signature::new(P, Q, R).expect("signature must have at most 8 dimensions")
+    }
+
+    /// Normalize a versor. This checks that the multivector is invertible
+    /// and has only even or only odd grades, but not that it actually
+    /// factors into vectors
+    pub fn new(multivector: Multivector) -> Result<Self, String> {
+        if multivector.signature() != Self::signature() {
+            return Err(format!(
+                "expected signature {:?}, got {:?}",
+                Self::signature(),
+                multivector.signature()
+            ));
+        }
+
+        let has_grade = |odd: u32| {
+            multivector
+                .components()
+                .iter()
+                .enumerate()
+                .any(|(i, x)| *x != 0.0 && (i as u8).count_ones() % 2 == odd)
+        };
+        if has_grade(0) && has_grade(1) {
+            return Err(String::from("versors must be all even or all odd grades"));
+        }
+
+        let norm = multivector.norm()?;
+        if norm == 0.0 {
+            return Err(String::from("null versors have no inverse"));
+        }
+
+        Ok(Self {
+            multivector: multivector.scale(1.0 / norm),
+        })
+    }
+
+    pub fn get(&self) -> &Multivector {
+        &self.multivector
+    }
+
+    /// True for products of an odd number of vectors, which reverse
+    /// orientation
+    pub fn is_odd(&self) -> bool {
+        self.multivector
+            .components()
+            .iter()
+            .enumerate()
+            .any(|(i, x)| *x != 0.0 && (i as u8).count_ones() % 2 == 1)
+    }
+
+    /// sqrt(|V reverse(V)|). This is 1 up to rounding error, which
+    /// check_invariants() looks for
+    pub fn norm(&self) -> f64 {
+        self.multivector.norm().expect("signatures match")
+    }
+
+    /// Even versor exp(B) for a simple bivector B. See Multivector::exp()
+    pub fn exp(bivector: &Multivector) -> Result<Self, String> {
+        Self::new(bivector.exp()?)
+    }
+
+    /// Bivector B with exp(B) = +/- this versor. Since V and -V act the
+    /// same way, the sign is chosen to give the smaller rotation. Only
+    /// rotors of simple bivectors are supported, see Multivector::log()
+    pub fn log(&self) -> Result<Multivector, String> {
+        if self.multivector.scalar_part() < 0.0 {
+            self.multivector.scale(-1.0).log()
+        } else {
+            self.multivector.log()
+        }
+    }
+
+    /// Interpolate between two versors along the one-parameter subgroup
+    /// that connects them, self * exp(t log(self^-1 other)). t = 0 gives
+    /// self and t = 1 gives other. For rotors this is the GA version of
+    /// quaternion slerp. Returns an error when self^-1 other is not the
+    /// exponential of a simple bivector, e.g. loxodromic Mobius
+    /// transformations in CGA.
+    pub fn interpolate(&self, other: &Self, t: f64) -> Result<Self, String> {
+        let difference = self.inverse() * other.clone();
+        let step = Self::exp(&difference.log()?.scale(t))?;
+        Ok(self.clone() * step)
+    }
+
+    /// Apply the versor to a blade: V X V^-1 for even versors, and
+    /// V X' V^-1 for odd versors where X' is the grade involution of X.
+    /// With this sign, a vector v reflects in the hyperplane orthogonal
+    /// to v.
+    pub fn sandwich(&self, blade: &Multivector) -> Result<Multivector, String> {
+        let filling = if self.is_odd() {
+            blade.grade_involution()
+        } else {
+            blade.clone()
+        };
+
+        self.multivector
+            .geometric_product(&filling)?
+            .geometric_product(&self.multivector.versor_inverse()?)
+    }
+}
+
+impl<const P: usize, const Q: usize, const R: usize> Mul for Versor<P, Q, R> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let multivector = self
+            .multivector
+            .geometric_product(&rhs.multivector)
+            .expect("signatures match");
+        Self { multivector }
+    }
+}
+
+impl<const P: usize, const Q: usize, const R: usize> PartialEq for Versor<P, Q, R> {
+    /// Approximate comparison. Like Mobius, V and -V act the same way, so
+    /// they are considered equal
+    fn eq(&self, other: &Self) -> bool {
+        let a = self.multivector.components();
+        let b = other.multivector.components();
+        let same = a.iter().zip(b).all(|(x, y)| (x - y).abs() < tolerance());
+        let negated = a.iter().zip(b).all(|(x, y)| (x + y).abs() < tolerance());
+        same || negated
+    }
+}
+
+impl<const P: usize, const Q: usize, const R: usize> Monoid for Versor<P, Q, R> {
+    fn identity() -> Self {
+        Self {
+            multivector: Multivector::scalar(Self::signature(), 1.0),
+        }
+    }
+
+    fn check_invariants(&self) -> Result<(), String> {
+        let norm = self.norm();
+        if (norm - 1.0).abs() > tolerance() {
+            return Err(format!("versor is no longer normalized: |V| = {}", norm));
+        }
+        Ok(())
+    }
+}
+
+impl<const P: usize, const Q: usize, const R: usize> Group for Versor<P, Q, R> {
+    fn inverse(&self) -> Self {
+        let multivector = self
+            .multivector
+            .versor_inverse()
+            .expect("normalized versors are invertible");
+        Self { multivector }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use abstraction::{test_associativity, test_identity, test_inverse};
+    use mobius::{Complex, Mobius};
+
+    use crate::{cga2d, UnitBlade};
+
+    use super::*;
+
+    type Euclidean = Versor<3, 0, 0>;
+
+    fn vector(components: [f64; 3]) -> Multivector {
+        Multivector::vector(Euclidean::signature(), &components).unwrap()
+    }
+
+    fn reflection(components: [f64; 3]) -> Euclidean {
+        Euclidean::new(vector(components)).unwrap()
+    }
+
+    fn rotor() -> Euclidean {
+        reflection([0.0, 1.0, 1.0]) * reflection([1.0, 0.0, 0.0])
+    }
+
+    fn test_mobius() -> Mobius {
+        Mobius::from_unnormalized(
+            Complex::new(1.0, 2.0),
+            Complex::new(-1.0, 0.5),
+            Complex::new(0.5, -1.0),
+            Complex::new(2.0, 1.0),
+        )
+        .unwrap()
+    }
+
+    fn cga_versor() -> Cga2dVersor {
+        Cga2dVersor::new(cga2d::mobius_versor(test_mobius())).unwrap()
+    }
+
+    test_identity!(
+        Euclidean,
+        [
+            (identity_reflection, reflection([1.0, 2.0, 3.0])),
+            (identity_rotor, rotor())
+        ]
+    );
+
+    test_associativity!(
+        Euclidean,
+        [(
+            assoc_reflections,
+            reflection([1.0, 0.0, 0.0]),
+            reflection([1.0, 1.0, 0.0]),
+            reflection([0.0, 2.0, -1.0])
+        )]
+    );
+
+    test_inverse!(
+        Euclidean,
+        [
+            (inverse_reflection, reflection([1.0, 2.0, 3.0])),
+            (inverse_rotor, rotor())
+        ]
+    );
+
+    mod cga {
+        use super::*;
+
+        test_inverse!(Cga2dVersor, [(inverse_mobius, cga_versor())]);
+    }
+
+    #[test]
+    pub fn new_normalizes() {
+        let result = reflection([3.0, 4.0, 0.0]);
+
+        assert!((result.norm() - 1.0).abs() < 1e-12);
+        assert!(result.check_invariants().is_ok());
+    }
+
+    #[test]
+    pub fn new_with_mixed_parity_returns_error() {
+        let mixed = vector([1.0, 0.0, 0.0])
+            .add(&Multivector::scalar(Euclidean::signature(), 1.0))
+            .unwrap();
+
+        let result = Euclidean::new(mixed);
+
+        assert!(result.is_err_and(|e| e.contains("all even or all odd")));
+    }
+
+    #[test]
+    pub fn new_with_null_vector_returns_error() {
+        let result = Cga2dVersor::new(cga2d::infinity());
+
+        assert!(result.is_err_and(|e| e.contains("null versors have no inverse")));
+    }
+
+    #[test]
+    pub fn new_with_wrong_signature_returns_error() {
+        let result = Cga2dVersor::new(vector([1.0, 0.0, 0.0]));
+
+        assert!(result.is_err_and(|e| e.contains("expected signature")));
+    }
+
+    #[test]
+    pub fn sandwich_with_vector_reflects() {
+        let v = reflection([1.0, 0.0, 0.0]);
+
+        let result = v.sandwich(&vector([2.0, 3.0, 4.0])).unwrap();
+
+        assert_eq!(result, vector([-2.0, 3.0, 4.0]));
+    }
+
+    #[test]
+    pub fn sandwich_with_rotor_rotates_vector() {
+        // The normals are 45 degrees apart, so this is a quarter turn in
+        // the xy-plane
+        let r = reflection([1.0, 1.0, 0.0]) * reflection([1.0, 0.0, 0.0]);
+
+        let result = r.sandwich(&vector([1.0, 0.0, 0.0])).unwrap();
+
+        let expected = vector([0.0, 1.0, 0.0]);
+        let diff = result.sub(&expected).unwrap();
+        assert!(diff.components().iter().all(|x| x.abs() < 1e-12));
+    }
+
+    #[test]
+    pub fn sandwich_preserves_grade_of_bivector() {
+        let xy =
+            Multivector::from_blade(Euclidean::signature(), UnitBlade::new(0b011), 1.0).unwrap();
+
+        let result = rotor().sandwich(&xy).unwrap();
+
+        assert!(result.sub(&result.grade(2)).unwrap().norm().unwrap() < 1e-12);
+    }
+
+    #[test]
+    pub fn log_undoes_exp_for_rotor() {
+        let result = Euclidean::exp(&rotor().log().unwrap()).unwrap();
+
+        assert_eq!(result, rotor());
+    }
+
+    #[test]
+    pub fn interpolate_to_loxodromic_versor_returns_error() {
+        let signature = Cga2dVersor::signature();
+        let [e1, e2, e3, e4] =
+            [0, 1, 2, 3].map(|i| Multivector::basis_vector(signature, i).unwrap());
+        let rotation = Cga2dVersor::exp(&e1.wedge(&e2).unwrap().scale(0.7)).unwrap();
+        let dilation = Cga2dVersor::exp(&e3.wedge(&e4).unwrap().scale(-0.4)).unwrap();
+        let loxodromic = rotation * dilation;
+
+        let result = Cga2dVersor::identity().interpolate(&loxodromic, 0.5);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn interpolate_hits_endpoints() {
+        let a = rotor();
+        let b = reflection([1.0, 0.0, 0.0]) * reflection([0.0, 0.0, 1.0]);
+
+        assert_eq!(a.interpolate(&b, 0.0).unwrap(), a);
+        assert_eq!(a.interpolate(&b, 1.0).unwrap(), b);
+    }
+
+    #[test]
+    pub fn interpolate_halfway_halves_angle() {
+        // A quarter turn in the xy-plane; halfway is an eighth turn
+        let quarter = reflection([1.0, 1.0, 0.0]) * reflection([1.0, 0.0, 0.0]);
+
+        let half = Euclidean::identity().interpolate(&quarter, 0.5).unwrap();
+
+        assert_eq!(half.clone() * half, quarter);
+    }
+
+    #[test]
+    pub fn interpolate_takes_short_way_for_negated_rotor() {
+        let quarter = reflection([1.0, 1.0, 0.0]) * reflection([1.0, 0.0, 0.0]);
+        let negated = Euclidean::new(quarter.get().scale(-1.0)).unwrap();
+
+        let result = Euclidean::identity().interpolate(&negated, 0.5).unwrap();
+
+        assert_eq!(
+            result,
+            Euclidean::identity().interpolate(&quarter, 0.5).unwrap()
+        );
+    }
+
+    #[test]
+    pub fn is_odd_distinguishes_reflections_from_rotors() {
+        assert!(reflection([1.0, 0.0, 0.0]).is_odd());
+        assert!(!rotor().is_odd());
+        assert!(!Euclidean::identity().is_odd());
+    }
+
+    #[test]
+    pub fn cga_versor_sandwich_matches_mobius() {
+        let z = Complex::new(0.3, -0.7);
+        let versor = cga_versor();
+
+        let result = cga2d::to_point(&versor.sandwich(&cga2d::point(z)).unwrap()).unwrap();
+
+        assert!((result - test_mobius() * z).mag() < 1e-9);
+    }
+}
@@ -0,0 +1,185 @@
+//! Convert CGA 2D objects into render primitives, so GA experiments can
+//! be drawn with the same SVG pipeline as the Mobius code. Points are
+//! null vectors, circles and lines are either dual vectors (as from
+//! cga2d::circle()) or trivectors (as from cga2d::circle_through()).
+use mobius::{geometry::GeneralizedCircle, rendering::RenderPrimitive, Complex};
+
+use crate::{cga2d, Multivector};
+
+/// A vector is considered null, i.e. a point, if its squared norm is
+/// within rounding error of zero: this many ulps of its largest component
+/// squared. A circle's squared norm is r^2 times its origin component
+/// squared, so anything larger is a real circle, however small.
+const NULL_TOLERANCE: f64 = 256.0 * f64::EPSILON;
+
+fn max_abs(v: &Multivector) -> f64 {
+    v.components().iter().fold(0.0, |acc, x| acc.max(x.abs()))
+}
+
+/// Render a null vector as a point. The point at infinity can't be drawn
+pub fn point(v: &Multivector) -> Result<RenderPrimitive, String> {
+    match cga2d::to_point(v)? {
+        Complex::Infinity => Err(String::from("cannot render the point at infinity")),
+        z => Ok(RenderPrimitive::Point(z)),
+    }
+}
+
+/// Render a dual vector as a circle or line
+pub fn generalized_circle(v: &Multivector) -> Result<RenderPrimitive, String> {
+    match cga2d::to_generalized_circle(v)? {
+        GeneralizedCircle::Circle(circle) => Ok(RenderPrimitive::Circle(circle)),
+        GeneralizedCircle::Line(line) => Ok(RenderPrimitive::make_line(line)),
+    }
+}
+
+/// Render a point, circle or line, deciding which from the grade. Vectors
+/// are points if they are null, and circles/lines otherwise. Trivectors
+/// are circles/lines through points.
+pub fn to_primitive(v: &Multivector) -> Result<RenderPrimitive, String> {
+    let vector = v.grade(1);
+    let trivector = v.grade(3);
+    let (vector_size, trivector_size) = (max_abs(&vector), max_abs(&trivector));
+
+    if trivector_size > vector_size {
+        return generalized_circle(&cga2d::dual(&trivector)?);
+    }
+
+    if vector_size == 0.0 {
+        return Err(String::from(
+            "only points, circles and lines (grades 1 and 3) can be rendered",
+        ));
+    }
+
+    if vector.norm_squared()?.abs() <= NULL_TOLERANCE * vector_size * vector_size {
+        point(&vector)
+    } else {
+        generalized_circle(&vector)
+    }
+}
+
+/// Render several objects, e.g. the orbit of a blade under a list of
+/// versors
+pub fn to_primitives(objects: &[Multivector]) -> Result<Vec<RenderPrimitive>, String> {
+    objects.iter().map(to_primitive).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use mobius::geometry::{Circle, LineSegment};
+
+    use crate::Cga2dVersor;
+
+    use super::*;
+
+    fn assert_circle(primitive: RenderPrimitive, expected: Circle) {
+        let RenderPrimitive::Circle(Circle { center, radius }) = primitive else {
+            panic!("expected a circle");
+        };
+        assert!((center - expected.center).mag() < 1e-9);
+        assert!((radius - expected.radius).abs() < 1e-9);
+    }
+
+    #[test]
+    pub fn to_primitive_renders_point() {
+        let z = Complex::new(1.0, -2.0);
+
+        let result = to_primitive(&cga2d::point(z).scale(3.0)).unwrap();
+
+        let RenderPrimitive::Point(point) = result else {
+            panic!("expected a point");
+        };
+        assert!((point - z).mag() < 1e-12);
+    }
+
+    #[test]
+    pub fn to_primitive_with_infinity_returns_error() {
+        let result = to_primitive(&cga2d::infinity());
+
+        assert!(result.is_err_and(|e| e.contains("point at infinity")));
+    }
+
+    #[test]
+    pub fn to_primitive_renders_dual_circle() {
+        let circle = Circle::new(Complex::new(1.0, 1.0), 2.0);
+
+        let result = to_primitive(&cga2d::circle(circle)).unwrap();
+
+        assert_circle(result, circle);
+    }
+
+    #[test]
+    pub fn to_primitive_renders_small_circle_away_from_origin() {
+        let circles = [
+            Circle::new(Complex::new(100.0, 0.0), 0.1),
+            Circle::new(Complex::new(10.0, 0.0), 0.001),
+            Circle::new(Complex::ONE, 1e-5),
+        ];
+
+        for circle in circles {
+            let result = to_primitive(&cga2d::circle(circle)).unwrap();
+
+            assert_circle(result, circle);
+        }
+    }
+
+    #[test]
+    pub fn to_primitive_renders_transformed_point() {
+        let inversion = Cga2dVersor::new(cga2d::circle(Circle::unit_circle())).unwrap();
+        let z = Complex::new(0.3, 0.4);
+
+        let inverted = inversion.sandwich(&cga2d::point(z)).unwrap();
+        let result = to_primitive(&inverted).unwrap();
+
+        let RenderPrimitive::Point(point) = result else {
+            panic!("expected a point");
+        };
+        assert!((point - Complex::new(1.2, 1.6)).mag() < 1e-9);
+    }
+
+    #[test]
+    pub fn to_primitive_renders_circle_through_points() {
+        let trivector = cga2d::circle_through(Complex::ONE, Complex::I, -Complex::ONE);
+
+        let result = to_primitive(&trivector).unwrap();
+
+        assert_circle(result, Circle::unit_circle());
+    }
+
+    #[test]
+    pub fn to_primitive_renders_line_through_points() {
+        let trivector = cga2d::line_through(Complex::new(0.0, 1.0), Complex::new(1.0, 1.0));
+
+        let result = to_primitive(&trivector).unwrap();
+
+        let RenderPrimitive::LineSegment(LineSegment { start, end }) = result else {
+            panic!("expected a line segment");
+        };
+        assert!((start.imag() - 1.0).abs() < 1e-9);
+        assert!((end.imag() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    pub fn to_primitive_with_bivector_returns_error() {
+        let pair = cga2d::point_pair(Complex::Zero, Complex::ONE);
+
+        let result = to_primitive(&pair);
+
+        assert!(result.is_err_and(|e| e.contains("grades 1 and 3")));
+    }
+
+    #[test]
+    pub fn to_primitives_renders_inverted_circle() {
+        // Inverting the circle through 1, 2 and 1 + i in the unit circle
+        // gives the circle through 1, 1/2 and 1/(1 - i)
+        let inversion = Cga2dVersor::new(cga2d::circle(Circle::unit_circle())).unwrap();
+        let original =
+            cga2d::circle_through(Complex::ONE, Complex::new(2.0, 0.0), Complex::new(1.0, 1.0));
+
+        let inverted = inversion.sandwich(&original).unwrap();
+        let result = to_primitives(&[original, inverted]).unwrap();
+
+        let expected = Circle::new(Complex::new(0.75, 0.25), 0.125f64.sqrt());
+        assert_eq!(result.len(), 2);
+        assert_circle(result[1].clone(), expected);
+    }
+}
@@ -0,0 +1,210 @@
+use std::path::Path;
+
+use mobius::{
+    algorithms::{DensityGrid, GroupIFS, MonoidIFS},
+    element_table::{self, list_elements},
+    figures::{find_figure, registry},
+    group_spec::{read_group_specs, GroupSpec},
+    rendering::{write_scene_json, ColorRGB},
+    scale,
+    svg_plot::{render_density, render_views_with, OutputFormat},
+    svg_stream::{save_scene, scene_layers},
+    translation, Complex, FixedPoints, Mobius,
+};
+use permutations::cayley_table::{format_csv, format_markdown, make_table, GroupName};
+
+use crate::options::{SharedOptions, ViewArg};
+
+/// Colors of the emptiest and densest cells of density plots
+const LOW_COLOR: ColorRGB = ColorRGB(32, 0, 64);
+const HIGH_COLOR: ColorRGB = ColorRGB(255, 220, 0);
+
+/// Resolution of density plots in cells across
+const DENSITY_RESOLUTION: usize = 400;
+
+/// Number of chaos game points to skip before the orbit is on the attractor
+const BURN_IN: usize = 100;
+
+/// Seed for the chaos game when --seed is not given
+const DEFAULT_SEED: u64 = 2024;
+
+pub fn list_figures() {
+    for figure in registry() {
+        println!("{}", figure.name());
+        for spec in figure.params() {
+            println!(
+                "    {} (default {}): {}",
+                spec.name, spec.default, spec.description
+            );
+        }
+    }
+}
+
+/// Render one of the registered figures, see mobius::figures::registry()
+pub fn render_figure(
+    options: &SharedOptions,
+    name: &str,
+    overrides: &[(String, f64)],
+    json: bool,
+) -> Result<(), String> {
+    let figure = find_figure(name).ok_or(format!("unknown figure {}", name))?;
+    let mut params = figure.default_params();
+    if let Some(seed) = options.seed {
+        if figure.params().iter().any(|spec| spec.name == "seed") {
+            params.set("seed", seed as f64)?;
+        }
+    }
+    for (param, value) in overrides {
+        params.set(param, *value)?;
+    }
+
+    let scene = options.restyle(&figure.build(&params)?);
+    let (center, half_width) = figure.view();
+    let view = options.view_or(center, half_width);
+    let config = options.render_config()?;
+    let filename = config.output_path(figure.name(), "");
+    save_scene(
+        &config,
+        &filename,
+        scene_layers(&scene),
+        view.center,
+        view.half_width,
+        options.stream_above,
+    )
+    .map_err(|e| e.to_string())?;
+    println!("wrote {}", filename.display());
+
+    if json {
+        let mut metadata = vec![("name", String::from(figure.name()))];
+        metadata.extend(params.iter().map(|(k, v)| (k.as_str(), v.to_string())));
+
+        let filename = config
+            .with_format(OutputFormat::Json)
+            .output_path(figure.name(), "");
+        write_scene_json(&filename, &scene, &[view.view("")], &metadata)
+            .map_err(|e| e.to_string())?;
+        println!("wrote {}", filename.display());
+    }
+    Ok(())
+}
+
+/// Render the density of points in a grid as the SVG file prefix.svg
+fn write_density(
+    options: &SharedOptions,
+    prefix: &str,
+    view: ViewArg,
+    ifs: &MonoidIFS<Mobius>,
+    start: Complex,
+    points: usize,
+) -> Result<(), String> {
+    let mut grid = DensityGrid::new(view.center, view.half_width, DENSITY_RESOLUTION)?;
+    let seed = options.seed.unwrap_or(DEFAULT_SEED);
+    ifs.estimate_density(&mut grid, start, points, BURN_IN, seed)?;
+
+    let high = options.stroke.unwrap_or(HIGH_COLOR);
+    let density = render_density(&grid, LOW_COLOR, high);
+    let config = options.render_config()?;
+    render_views_with(&config, prefix, &[view.view("")], density).map_err(|e| e.to_string())?;
+    println!("wrote {}", prefix);
+    Ok(())
+}
+
+fn find_group_spec(spec_path: &Path, name: &str) -> Result<GroupSpec, String> {
+    let specs = read_group_specs(spec_path).map_err(|e| e.to_string())?;
+    specs
+        .into_iter()
+        .find(|spec| spec.name == name)
+        .ok_or(format!(
+            "no group named {} in {}",
+            name,
+            spec_path.display()
+        ))
+}
+
+/// Limit set of a two-generator group from a group spec file, drawn by
+/// playing the chaos game with the generators and their inverses
+pub fn render_limit_set(
+    options: &SharedOptions,
+    spec_path: &Path,
+    name: &str,
+    points: usize,
+) -> Result<(), String> {
+    let spec = find_group_spec(spec_path, name)?;
+    let (a, b) = (spec.a, spec.b);
+    let ifs = MonoidIFS::new(vec![a, b, a.inverse(), b.inverse()]);
+
+    // Fixed points of the generators are in the limit set, so the orbit
+    // starts on the attractor
+    let start = match a.fixed_points() {
+        FixedPoints::Single(z) => z,
+        FixedPoints::Pair(Complex::Infinity, z) => z,
+        FixedPoints::Pair(z, _) => z,
+    };
+    if start == Complex::Infinity {
+        return Err(format!("{}: generator a fixes infinity", name));
+    }
+
+    let view = options.view_or(Complex::Zero, 1.2);
+    write_density(
+        options,
+        &format!("limit_set_{}", name),
+        view,
+        &ifs,
+        start,
+        points,
+    )
+}
+
+/// Sierpinski triangle where each corner is chosen with the given weight
+pub fn render_chaos_game(
+    options: &SharedOptions,
+    weights: &[f64],
+    points: usize,
+) -> Result<(), String> {
+    let half = scale(0.5)?;
+    let xforms = [Complex::Zero, Complex::ONE, Complex::I]
+        .into_iter()
+        .map(|corner| Ok(translation(corner * 0.5.into())? * half))
+        .collect::<Result<Vec<Mobius>, String>>()?;
+    let ifs = MonoidIFS::new(xforms).with_weights(weights.to_vec())?;
+
+    let view = options.view_or(Complex::new(0.5, 0.5), 0.55);
+    write_density(options, "chaos_game", view, &ifs, Complex::Zero, points)
+}
+
+/// Print the elements of a group from a spec file up to a word length as
+/// CSV or JSON, see mobius::element_table
+pub fn print_elements(
+    spec_path: &Path,
+    name: &str,
+    max_length: usize,
+    unique: bool,
+    json: bool,
+) -> Result<(), String> {
+    let spec = find_group_spec(spec_path, name)?;
+    let ifs = GroupIFS::new(spec.generators());
+    let rows = list_elements(&ifs, max_length, unique);
+
+    if json {
+        print!("{}", element_table::format_json(&rows));
+    } else {
+        for line in element_table::format_csv(&rows) {
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
+
+/// Print the multiplication table of a permutation group
+pub fn print_cayley_table(group: GroupName, markdown: bool) {
+    let table = make_table(group);
+    let lines = if markdown {
+        format_markdown(&table)
+    } else {
+        format_csv(&table)
+    };
+
+    for line in lines {
+        println!("{}", line);
+    }
+}
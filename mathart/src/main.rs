@@ -0,0 +1,198 @@
+mod commands;
+mod options;
+
+use std::{io::Error, path::PathBuf};
+
+use clap::{Parser, Subcommand};
+use mobius::figure::parse_assignment;
+use permutations::cayley_table::GroupName;
+
+use options::SharedOptions;
+
+/// The group specs that ship with the mobius crate. The path is built
+/// from this package's directory so the default works from any working
+/// directory
+const DEFAULT_SPEC: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../mobius/data/groups.txt");
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the registered figures and their parameters
+    List,
+    /// Render a registered figure by name
+    Figure {
+        name: String,
+
+        /// Override a parameter in the form param=value. Can be repeated
+        #[arg(long = "set", value_parser = parse_assignment)]
+        overrides: Vec<(String, f64)>,
+
+        /// Also write the scene as JSON for use in external viewers
+        #[arg(long)]
+        json: bool,
+    },
+    /// {p, q} hyperbolic tiling of the Poincare disk
+    Tiling {
+        #[arg(long, default_value_t = 3)]
+        p: usize,
+
+        #[arg(long, default_value_t = 7)]
+        q: usize,
+
+        /// Number of reflections to apply
+        #[arg(long, default_value_t = 5)]
+        depth: usize,
+    },
+    /// Limit set of a two-generator group such as the Apollonian gasket
+    LimitSet {
+        /// Name of the group in the spec file
+        #[arg(default_value = "gasket")]
+        group: String,
+
+        /// Group spec file, see mobius::group_spec for the format
+        #[arg(long, default_value = DEFAULT_SPEC)]
+        spec: PathBuf,
+
+        /// Number of chaos game iterations
+        #[arg(long, default_value_t = 1_000_000)]
+        points: usize,
+    },
+    /// Density of a Sierpinski triangle with weighted corners
+    ChaosGame {
+        /// Relative weight of each corner, comma separated
+        #[arg(long, value_delimiter = ',', default_value = "0.6,0.25,0.15")]
+        weights: Vec<f64>,
+
+        /// Number of chaos game iterations
+        #[arg(long, default_value_t = 2_000_000)]
+        points: usize,
+    },
+    /// Print the elements of a two-generator group up to a word length,
+    /// with their matrices, traces, classifications and fixed points
+    Elements {
+        /// Name of the group in the spec file
+        #[arg(default_value = "gasket")]
+        group: String,
+
+        /// Group spec file, see mobius::group_spec for the format
+        #[arg(long, default_value = DEFAULT_SPEC)]
+        spec: PathBuf,
+
+        /// Maximum word length
+        #[arg(long, default_value_t = 3)]
+        length: usize,
+
+        /// Skip elements already reached by an earlier word
+        #[arg(long)]
+        unique: bool,
+
+        /// Print JSON instead of CSV
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the multiplication table of a permutation group
+    CayleyTable {
+        /// One of a4, s4, a5 or dN for the dihedral group of the n-gon
+        group: GroupName,
+
+        /// Print a Markdown table instead of CSV
+        #[arg(long)]
+        markdown: bool,
+    },
+}
+
+/// Render the artworks and examples from the command line
+#[derive(Parser)]
+#[command(name = "mathart")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    #[command(flatten)]
+    options: SharedOptions,
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    let options = &cli.options;
+    match cli.command {
+        Command::List => {
+            commands::list_figures();
+            Ok(())
+        }
+        Command::Figure {
+            name,
+            overrides,
+            json,
+        } => commands::render_figure(options, &name, &overrides, json),
+        Command::Tiling { p, q, depth } => {
+            let overrides = [
+                (String::from("p"), p as f64),
+                (String::from("q"), q as f64),
+                (String::from("depth"), depth as f64),
+            ];
+            commands::render_figure(options, "hyperbolic_tiling", &overrides, false)
+        }
+        Command::LimitSet {
+            group,
+            spec,
+            points,
+        } => commands::render_limit_set(options, &spec, &group, points),
+        Command::ChaosGame { weights, points } => {
+            commands::render_chaos_game(options, &weights, points)
+        }
+        Command::Elements {
+            group,
+            spec,
+            length,
+            unique,
+            json,
+        } => commands::print_elements(&spec, &group, length, unique, json),
+        Command::CayleyTable { group, markdown } => {
+            commands::print_cayley_table(group, markdown);
+            Ok(())
+        }
+    }
+}
+
+fn main() -> Result<(), Error> {
+    run(Cli::parse()).map_err(Error::other)
+}
+
+#[cfg(test)]
+mod test {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    pub fn cli_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    pub fn shared_options_work_after_subcommand() {
+        let cli = Cli::try_parse_from(["mathart", "tiling", "--p", "4", "--width", "0.5"]).unwrap();
+
+        assert!(matches!(cli.command, Command::Tiling { p: 4, .. }));
+        assert_eq!(cli.options.width, Some(0.5));
+    }
+
+    #[test]
+    pub fn default_spec_file_exists() {
+        let cli = Cli::try_parse_from(["mathart", "limit-set"]).unwrap();
+
+        let Command::LimitSet { spec, .. } = cli.command else {
+            panic!("expected limit-set");
+        };
+        assert!(spec.is_file());
+    }
+
+    #[test]
+    pub fn chaos_game_parses_weights() {
+        let cli = Cli::try_parse_from(["mathart", "chaos-game", "--weights", "1,2,3"]).unwrap();
+
+        let Command::ChaosGame { weights, .. } = cli.command else {
+            panic!("expected chaos-game");
+        };
+        assert_eq!(weights, vec![1.0, 2.0, 3.0]);
+    }
+}
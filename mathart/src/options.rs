@@ -0,0 +1,207 @@
+use std::{path::PathBuf, str::FromStr};
+
+use clap::Args;
+use mobius::{
+    rendering::{ColorRGB, Scene, Style},
+    svg_plot::{RenderConfig, View},
+    Complex,
+};
+
+/// The region to render, parsed from x,y,half_width
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewArg {
+    pub center: Complex,
+    pub half_width: f64,
+}
+
+impl ViewArg {
+    pub fn new(center: Complex, half_width: f64) -> Self {
+        Self { center, half_width }
+    }
+
+    pub fn view<'a>(&self, label: &'a str) -> View<'a> {
+        View(
+            label,
+            self.center.real(),
+            self.center.imag(),
+            self.half_width,
+        )
+    }
+}
+
+impl FromStr for ViewArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = s
+            .split(',')
+            .map(|x| x.trim().parse::<f64>())
+            .collect::<Result<Vec<f64>, _>>()
+            .map_err(|_| format!("expected x,y,half_width, got {}", s))?;
+        let [x, y, half_width] = values[..] else {
+            return Err(format!("expected x,y,half_width, got {}", s));
+        };
+        if !(half_width > 0.0 && half_width.is_finite()) {
+            return Err(String::from("half_width must be positive"));
+        }
+
+        Ok(Self::new(Complex::new(x, y), half_width))
+    }
+}
+
+/// Image size in pixels, parsed from WIDTHxHEIGHT
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SizeArg {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl FromStr for SizeArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expected = || format!("expected WIDTHxHEIGHT, got {}", s);
+        let (width, height) = s.split_once('x').ok_or_else(expected)?;
+        let width = width.trim().parse::<f64>().map_err(|_| expected())?;
+        let height = height.trim().parse::<f64>().map_err(|_| expected())?;
+
+        Ok(Self { width, height })
+    }
+}
+
+/// Flags shared by every subcommand
+#[derive(Args, Debug)]
+pub struct SharedOptions {
+    /// Directory to write the output files to
+    #[arg(long, global = true, default_value = "output")]
+    pub output_dir: PathBuf,
+
+    /// Override the region to render, in the form x,y,half_width
+    #[arg(long, global = true)]
+    pub view: Option<ViewArg>,
+
+    /// Override the stroke color of every layer, e.g. #ff7f00. For
+    /// density plots, this is the color of the densest cells
+    #[arg(long, global = true)]
+    pub stroke: Option<ColorRGB>,
+
+    /// Override the stroke width of every layer, in percent of the view
+    #[arg(long, global = true)]
+    pub width: Option<f64>,
+
+    /// Size of the image in pixels, e.g. 1000x1000
+    #[arg(long, global = true, default_value = "500x700")]
+    pub size: SizeArg,
+
+    /// Background color of the image, e.g. #ffffff
+    #[arg(long, global = true, default_value = "#000000")]
+    pub background: ColorRGB,
+
+    /// Leave the background transparent instead
+    #[arg(long, global = true)]
+    pub transparent: bool,
+
+    /// Empty margin around the view in pixels
+    #[arg(long, global = true, default_value_t = 0.0)]
+    pub padding: f64,
+
+    /// Clip the geometry to the view, so the file only contains what is
+    /// visible
+    #[arg(long, global = true)]
+    pub clip: bool,
+
+    /// Drop primitives that are nowhere near the view. This is cheaper
+    /// than --clip, but keeps anything that might be visible whole
+    #[arg(long, global = true)]
+    pub cull: bool,
+
+    /// Random seed for commands that use randomness
+    #[arg(long, global = true)]
+    pub seed: Option<u64>,
+
+    /// Scenes with more primitives than this are streamed to the SVG file
+    /// instead of being built in memory
+    #[arg(long, global = true, default_value_t = 200_000)]
+    pub stream_above: usize,
+}
+
+impl SharedOptions {
+    /// The view from the command line, or the command's default
+    pub fn view_or(&self, center: Complex, half_width: f64) -> ViewArg {
+        self.view.unwrap_or(ViewArg::new(center, half_width))
+    }
+
+    /// Image size, background and output directory for rendering
+    pub fn render_config(&self) -> Result<RenderConfig, String> {
+        let SizeArg { width, height } = self.size;
+        let background = if self.transparent {
+            None
+        } else {
+            Some(self.background)
+        };
+
+        Ok(RenderConfig::new(width, height)?
+            .with_background(background)
+            .with_padding(self.padding)?
+            .with_output_dir(&self.output_dir)
+            .with_clip(self.clip)
+            .with_cull(self.cull))
+    }
+
+    /// Apply the style overrides to every layer of a scene
+    pub fn restyle(&self, scene: &Scene) -> Scene {
+        scene.map_styles(|style| {
+            let style = Style {
+                stroke: self.stroke.or(style.stroke),
+                ..style
+            };
+            match self.width {
+                Some(width) => style.with_width(width),
+                None => style,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn view_arg_parses_triple() {
+        let result: ViewArg = "0.5, -1, 2".parse().unwrap();
+
+        assert_eq!(result, ViewArg::new(Complex::new(0.5, -1.0), 2.0));
+    }
+
+    #[test]
+    pub fn view_arg_with_invalid_text_returns_error() {
+        for text in ["1,2", "1,2,3,4", "a,b,c", "0,0,-1"] {
+            let result: Result<ViewArg, String> = text.parse();
+
+            assert!(result.is_err(), "{} should not parse", text);
+        }
+    }
+
+    #[test]
+    pub fn size_arg_parses_dimensions() {
+        let result: SizeArg = "1000x800".parse().unwrap();
+
+        assert_eq!(
+            result,
+            SizeArg {
+                width: 1000.0,
+                height: 800.0
+            }
+        );
+    }
+
+    #[test]
+    pub fn size_arg_with_invalid_text_returns_error() {
+        for text in ["1000", "1000x", "ax800", "1000,800"] {
+            let result: Result<SizeArg, String> = text.parse();
+
+            assert!(result.is_err(), "{} should not parse", text);
+        }
+    }
+}
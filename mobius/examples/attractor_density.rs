@@ -0,0 +1,33 @@
+use std::io::Error;
+
+use mobius::{
+    algorithms::{DensityGrid, MonoidIFS},
+    rendering::ColorRGB,
+    scale,
+    svg_plot::{flip_y, make_card, render_density},
+    translation, Complex,
+};
+
+/// Sierpinski triangle where the map towards one corner is chosen more
+/// often than the others. The outline is the same as the uniform version,
+/// but the invariant measure piles up near the favored corner.
+fn main() -> Result<(), Error> {
+    let half = scale(0.5).unwrap();
+    let xforms = [Complex::Zero, Complex::ONE, Complex::I]
+        .into_iter()
+        .map(|corner| translation(corner * 0.5.into()).unwrap() * half)
+        .collect();
+    let ifs = MonoidIFS::new(xforms)
+        .with_weights(vec![0.6, 0.25, 0.15])
+        .map_err(Error::other)?;
+
+    let center = Complex::new(0.5, 0.5);
+    let half_width = 0.55;
+    let mut grid = DensityGrid::new(center, half_width, 400).map_err(Error::other)?;
+    ifs.estimate_density(&mut grid, Complex::Zero, 2_000_000, 100, 2024)
+        .map_err(Error::other)?;
+
+    let density = render_density(&grid, ColorRGB(32, 0, 64), ColorRGB(255, 220, 0));
+    let doc = make_card(center, half_width).add(flip_y().add(density));
+    svg::save("output/attractor_density.svg", &doc)
+}
@@ -0,0 +1,73 @@
+use std::{f64::consts::PI, io::Error};
+
+use mobius::{
+    hyperbolic_tilings::{get_fundamental_region, reflection_group},
+    prelude::*,
+};
+
+/// Euclidean hexagonal wallpaper that fills the background
+fn make_wallpaper() -> Scene {
+    let sixth_roots = Complex::roots_of_unity(6);
+    let hexagon = ClineArcTile::new(
+        (0..6)
+            .map(|i| {
+                let side = LineSegment::new(sixth_roots[i], sixth_roots[(i + 1) % 6]);
+                ClineArc::from(side)
+            })
+            .collect(),
+    );
+    let up = translation(Complex::new(0.0, (3.0f64).sqrt())).unwrap();
+    let diagonal = translation(Complex::from_polar((3.0f64).sqrt(), PI / 6.0)).unwrap();
+    let grid = GridIFS::new(vec![(up, -6, 6), (diagonal, -6, 6)]);
+    let hex_tiles = grid.apply(&hexagon);
+
+    let mut scene = Scene::new();
+    scene.add_layer(
+        0,
+        Style::stroke(127, 127, 127).with_width(0.125),
+        &hex_tiles,
+    );
+    scene
+}
+
+/// {3, 7} hyperbolic tiling in the unit disk, drawn over an opaque disk
+/// so the wallpaper doesn't show through.
+fn make_tiling() -> Scene {
+    let (conj, r_conj, e2_conj) = reflection_group(3, 7).unwrap();
+    // The mirrors are involutions, so there's no need to store inverses
+    let ifs = MonoidIFS::new(vec![conj, r_conj, e2_conj]);
+    let (tile, _) = get_fundamental_region(3, 7).unwrap();
+    let tiles = ifs.apply(&tile, 0, 7);
+
+    let backdrop = Style {
+        stroke: Some(ColorRGB(255, 255, 255)),
+        fill: Some(ColorRGB(0, 0, 0)),
+        width: Some(StrokeWidth::Percent(0.25)),
+        ..Style::new()
+    };
+
+    let mut scene = Scene::new();
+    scene.add_layer(0, backdrop, &[Cline::unit_circle()]);
+    scene.add_layer(1, Style::stroke(255, 127, 0).with_width(0.125), &tiles);
+    scene
+}
+
+fn main() -> Result<(), Error> {
+    let wallpaper = make_wallpaper();
+    let tiling = make_tiling();
+
+    let shrink = scale(1.5).unwrap();
+    let shift = translation(Complex::new(0.5, 1.0)).unwrap();
+
+    let scene = Scene::compose(&[
+        (wallpaper, Isogonal::identity(), 0),
+        (tiling, (shift * shrink).into(), 10),
+    ]);
+
+    render_views(
+        "output",
+        "composed_scene",
+        &[View("", 0.0, 0.0, 3.0)],
+        render_scene(&scene),
+    )
+}
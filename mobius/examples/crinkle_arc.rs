@@ -41,8 +41,8 @@ impl ArcFractal {
         let arc_ab = CircularArc::new(circle, angles_ab);
         let arc_bc = CircularArc::new(circle, angles_bc);
 
-        let orthog_arc_cb = compute_orthogonal_arc(arc_bc);
-        let orthog_arc_ba = compute_orthogonal_arc(arc_ab);
+        let orthog_arc_cb = compute_orthogonal_arc(arc_bc).unwrap();
+        let orthog_arc_ba = compute_orthogonal_arc(arc_ab).unwrap();
 
         let d = orthog_arc_ba.interpolate(t);
         let e = orthog_arc_cb.interpolate(t);
@@ -56,7 +56,7 @@ impl ArcFractal {
         Self {
             xforms: (xform_ceb, xform_bda),
             arc,
-            orthog_arc: compute_orthogonal_arc(arc),
+            orthog_arc: compute_orthogonal_arc(arc).unwrap(),
             sub_arcs: (orthog_arc_cb, orthog_arc_ba),
         }
     }
@@ -0,0 +1,46 @@
+use std::io::Error;
+
+use mobius::{
+    algorithms::EscapeTime,
+    rendering::{ColorRGB, Palette},
+    Complex, Mobius,
+};
+
+/// Escape-time rasters of the quadratic family, plain and conjugated by
+/// z -> 1/z. The conjugated versions turn the filled Julia set inside
+/// out, so the escaping region is a bounded blob around the origin.
+fn main() -> Result<(), Error> {
+    let palette = Palette::new(vec![
+        ColorRGB(16, 0, 48),
+        ColorRGB(255, 127, 0),
+        ColorRGB(255, 255, 200),
+    ])
+    .map_err(Error::other)?;
+    let interior = ColorRGB(0, 0, 0);
+
+    let escape = EscapeTime::new(200, 4.0).map_err(Error::other)?;
+    let inversion =
+        Mobius::new(Complex::Zero, Complex::I, Complex::I, Complex::Zero).map_err(Error::other)?;
+    let inverted = escape.with_conjugator(inversion);
+
+    let douady_rabbit = Complex::new(-0.123, 0.745);
+    escape
+        .julia(douady_rabbit, Complex::Zero, 1.6, 600)
+        .map_err(Error::other)?
+        .write_ppm("output/julia_rabbit.ppm", &palette, interior)?;
+    inverted
+        .julia(douady_rabbit, Complex::Zero, 2.5, 600)
+        .map_err(Error::other)?
+        .write_ppm("output/julia_rabbit_inverted.ppm", &palette, interior)?;
+
+    escape
+        .mandelbrot(Complex::new(-0.6, 0.0), 1.5, 600)
+        .map_err(Error::other)?
+        .write_ppm("output/mandelbrot.ppm", &palette, interior)?;
+    inverted
+        .mandelbrot(Complex::new(1.4, 0.0), 2.5, 600)
+        .map_err(Error::other)?
+        .write_ppm("output/mandelbrot_inverted.ppm", &palette, interior)?;
+
+    Ok(())
+}
@@ -1,20 +1,10 @@
 use std::{f64::consts::PI, io::Error};
 
-use abstraction::Group;
 use mobius::{
-    algorithms::{GridIFS, GroupIFS, MonoidIFS},
-    cline_arc::ClineArc,
-    elliptic, gasket_group,
-    geometry::{Circle, LineSegment},
-    hyperbolic,
+    finite_subgroups::{octahedral, FiniteSubgroup},
     hyperbolic_tilings::{corner_rotation_group, get_fundamental_region, reflection_group},
-    loxodromic,
     motifs::{bone, candy_corn, ghost, skull, witch_hat},
-    rendering::Style,
-    rotation, scale,
-    svg_plot::{render_views, style_geometry, style_motifs, union, View},
-    transformable::{ClineArcTile, ClineTile, Motif, Transformable},
-    translation, Complex, Mobius,
+    prelude::*,
 };
 
 pub fn candy_corners() -> Result<(), Error> {
@@ -84,7 +74,7 @@ pub fn bone_tree() -> Result<(), Error> {
     let shift_trunk = translation(Complex::I).unwrap();
     let trunk = bone(20.0).transform(shrink_trunk * shift_trunk);
 
-    let rot2 = rotation(PI).unwrap();
+    let rot2 = Mobius::POINT_REFLECTION;
     let shift_head = translation(Complex::new(0.0, 2.1)).unwrap();
     let heads_will_roll = skull().transform(shift_head * rot2);
 
@@ -147,12 +137,10 @@ pub fn ghost_octahedral() -> Result<(), Error> {
 
     // Two 90 degree elliptic rotations 90 degrees apart. This is isomorphic
     // to the rotation symmetry group of the cube/octahedron
-    let swirl = elliptic(PI / 2.0).unwrap();
-    let rotate90 = rotation(PI / 2.0).unwrap();
-    let swirl2 = Mobius::sandwich(rotate90, swirl);
+    let FiniteSubgroup { generators, .. } = octahedral();
     let to_the_left = translation(Complex::new(-0.5, 0.0)).unwrap();
     let shifted_ghost = small_ghost.transform(to_the_left);
-    let ifs = MonoidIFS::new(vec![swirl, swirl2]);
+    let ifs = MonoidIFS::new(generators);
     let swirl_walk = ifs.apply(&shifted_ghost, 0, 8);
     render_views(
         "output",
@@ -254,7 +242,7 @@ pub fn ghost_gasket() -> Result<(), Error> {
 pub fn warpedpaper() -> Result<(), Error> {
     let (corn, styles) = candy_corn();
 
-    let rotate2 = rotation(PI).unwrap();
+    let rotate2 = Mobius::POINT_REFLECTION;
     let translate_edge = translation(Complex::from_polar(0.6, PI / 3.0)).unwrap();
     let rotate_edge = Mobius::sandwich(translate_edge, rotate2);
     let rotated_corn = corn.transform(rotate_edge);
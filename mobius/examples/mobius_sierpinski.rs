@@ -1,10 +1,11 @@
 use std::f64::consts::PI;
 
 use mobius::{
+    algorithms::chaos_game,
     geometry::{ArcAngles, Circle, CircularArc, LineSegment},
     scale,
     svg_plot::{add_geometry, flip_y, make_axes, make_card},
-    transformable::{Cline, ClineArcTile, ClineTile, Transformable},
+    transformable::{Cline, ClineArcTile, ClineTile, PointCloud, Transformable},
     Complex, Mobius,
 };
 use svg::{
@@ -177,4 +178,18 @@ fn main() {
 
     let doc = make_card(Complex::new(0.5, 0.5), 0.6).add(flipped2);
     svg::save("output/mobius_sierpinski.svg", &doc).unwrap();
+
+    // ----------------------
+
+    // The same IFS, plotted as a point cloud with the chaos game. The first
+    // few points are skipped since they haven't reached the attractor yet
+    const BURN_IN: usize = 20;
+    let orbit = chaos_game(&xforms, Complex::new(0.5, 0.5), 20000, None, 2024).unwrap();
+    let cloud = PointCloud::new(orbit[BURN_IN..].to_vec());
+
+    let mut geometry = Group::new().set("fill", "yellow");
+    geometry = add_geometry(geometry, &cloud);
+
+    let doc = make_card(Complex::new(0.5, 0.5), 0.6).add(flip_y().add(geometry));
+    svg::save("output/mobius_sierpinski_chaos.svg", &doc).unwrap();
 }
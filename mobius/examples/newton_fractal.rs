@@ -0,0 +1,41 @@
+use std::io::Error;
+
+use mobius::{algorithms::NewtonFractal, rendering::ColorRGB, Complex, ComplexPolynomial};
+
+/// Newton basins of z^3 - 1 and of a quintic with roots scattered around
+/// the plane. Pixels are colored by the root they converge to, fading to
+/// black the longer they take.
+fn main() -> Result<(), Error> {
+    let root_colors = [
+        ColorRGB(255, 127, 0),
+        ColorRGB(120, 0, 200),
+        ColorRGB(0, 180, 120),
+        ColorRGB(230, 230, 230),
+        ColorRGB(200, 30, 60),
+    ];
+    let black = ColorRGB(0, 0, 0);
+
+    let roots = [
+        Complex::roots_of_unity(3),
+        vec![
+            Complex::new(1.0, 0.5),
+            Complex::new(-1.0, 0.8),
+            Complex::new(-0.3, -1.2),
+            Complex::new(0.8, -0.7),
+            Complex::Zero,
+        ],
+    ];
+
+    for (i, roots) in roots.iter().enumerate() {
+        let polynomial = ComplexPolynomial::from_roots(roots).map_err(Error::other)?;
+        let fractal = NewtonFractal::new(polynomial, 40, 1e-6).map_err(Error::other)?;
+        let basins = fractal
+            .basins(Complex::Zero, 2.0, 600)
+            .map_err(Error::other)?;
+        basins.write_ppm(format!("output/newton_fractal{}.ppm", i), |basin| {
+            fractal.color(*basin, &root_colors, black)
+        })?;
+    }
+
+    Ok(())
+}
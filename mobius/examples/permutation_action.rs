@@ -0,0 +1,62 @@
+use std::io::Error;
+
+use abstraction::GroupAction;
+use mobius::{
+    geometry::Circle,
+    polygon::Polygon,
+    rendering::Style,
+    svg_plot::{render_views, style_geometry, union, View},
+    transformable::Cline,
+    Complex,
+};
+use permutations::Permutation;
+
+const COLUMNS: usize = 6;
+const SPACING: f64 = 3.0;
+
+/// The rotations of a cube permute its 4 body diagonals, which is why
+/// the octahedral group is isomorphic to S_4. Looking down at the cube,
+/// the diagonals end at the 4 corners of a square. Draw a quadrilateral
+/// through the corners in the order given by each permutation.
+fn main() -> Result<(), Error> {
+    let corners = [
+        Complex::new(1.0, 1.0),
+        Complex::new(-1.0, 1.0),
+        Complex::new(-1.0, -1.0),
+        Complex::new(1.0, -1.0),
+    ];
+
+    let mut outlines = Vec::new();
+    let mut markers = Vec::new();
+    for (i, permutation) in Permutation::<4>::symmetric_group().enumerate() {
+        let row = (i / COLUMNS) as f64;
+        let column = (i % COLUMNS) as f64;
+        let offset = Complex::new(column * SPACING, -row * SPACING);
+
+        let placed = corners.map(|z| z + offset);
+        let square = Polygon::new(placed.to_vec()).unwrap();
+        let permuted = permutation.act(&square);
+
+        // Mark the starting vertex so the direction is visible
+        markers.push(Cline::from(Circle::new(permuted.vertices()[0], 0.2)));
+        outlines.push(permuted);
+    }
+
+    let outline_style = Style::stroke(255, 127, 0).with_width(0.5);
+    let marker_style = Style::stroke(255, 255, 255).with_width(0.25);
+    let center = Complex::new(0.5 * SPACING * ((COLUMNS - 1) as f64), -0.5 * SPACING * 3.0);
+    render_views(
+        "output",
+        "permutation_action",
+        &[View(
+            "",
+            center.real(),
+            center.imag(),
+            0.5 * SPACING * (COLUMNS as f64),
+        )],
+        union(vec![
+            style_geometry(outline_style, &outlines[..]),
+            style_geometry(marker_style, &markers[..]),
+        ]),
+    )
+}
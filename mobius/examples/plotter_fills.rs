@@ -0,0 +1,58 @@
+use std::io::Error;
+
+use mobius::{
+    algorithms::{hatch_polygon, hatch_tile, stipple_tile, FillPattern},
+    motifs::ghost,
+    polygon::Polygon,
+    prelude::*,
+};
+
+/// Plotter-friendly versions of filled shapes: a cross-hatched ghost, a
+/// stippled ghost, and a hatched pentagram. Everything is drawn with
+/// strokes and dots only.
+fn main() -> Result<(), Error> {
+    let (ghost_tile, outline) = ghost();
+    let left = translation(Complex::new(-1.5, 0.0)).unwrap();
+    let right = translation(Complex::new(1.5, 0.0)).unwrap();
+    let hatched_ghost = ghost_tile.transform(left);
+    let stippled_ghost = ghost_tile.transform(right);
+
+    let hatching = hatch_tile(
+        &hatched_ghost,
+        FillPattern::CrossHatch {
+            angle: 0.4,
+            spacing: 0.08,
+        },
+    )
+    .map_err(Error::other)?;
+    let stipple = stipple_tile(&stippled_ghost, 0.06, 2024).map_err(Error::other)?;
+
+    let star_vertices = (0..5)
+        .map(|i| {
+            let angle = std::f64::consts::FRAC_PI_2 + 4.0 * std::f64::consts::PI * i as f64 / 5.0;
+            Complex::new(0.0, -3.5) + Complex::from_polar(1.2, angle)
+        })
+        .collect();
+    let star = Polygon::new(star_vertices).map_err(Error::other)?;
+    let star_hatching = hatch_polygon(
+        &star,
+        FillPattern::Hatch {
+            angle: -0.3,
+            spacing: 0.06,
+        },
+    )
+    .map_err(Error::other)?;
+
+    let ink = Style::stroke(255, 127, 0).with_width(0.125);
+    let mut scene = Scene::new();
+    scene.add_layer(0, ink, &[hatching, star_hatching]);
+    scene.add_layer(0, ink, &[stipple]);
+    scene.add_layer(1, outline, &[hatched_ghost, stippled_ghost]);
+
+    render_views(
+        "output",
+        "plotter_fills",
+        &[View("", 0.0, -1.5, 3.5)],
+        render_scene(&scene),
+    )
+}
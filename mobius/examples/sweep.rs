@@ -0,0 +1,134 @@
+use std::io::Error;
+
+use clap::Parser;
+use mobius::{
+    figure::{parse_assignment, Figure, Params, Sweep},
+    figures::{find_figure, registry},
+    rendering::{fingerprint, write_scene_json},
+    svg_plot::{render_contact_sheet, render_scene, render_views, View},
+};
+
+/// Render a registered figure, optionally sweeping one of its parameters
+/// to produce a contact sheet of variations.
+#[derive(Parser)]
+struct Cli {
+    /// Name of the figure to render. Omit to list the available figures
+    figure: Option<String>,
+
+    /// Sweep a parameter in the form param=start:end:steps
+    #[arg(long)]
+    sweep: Option<Sweep>,
+
+    /// Override a parameter in the form param=value. Can be repeated
+    #[arg(long = "set", value_parser = parse_assignment)]
+    overrides: Vec<(String, f64)>,
+
+    /// Number of thumbnails per row of the contact sheet
+    #[arg(long, default_value_t = 4)]
+    columns: usize,
+
+    /// Instead of rendering SVG, print a fingerprint of the quantized
+    /// geometry. If no figure is given, all figures are fingerprinted
+    #[arg(long)]
+    fingerprint: bool,
+
+    /// Also write the scene as JSON for use in external viewers
+    #[arg(long)]
+    json: bool,
+}
+
+fn list_figures() {
+    for figure in registry() {
+        println!("{}", figure.name());
+        for spec in figure.params() {
+            println!(
+                "    {} (default {}): {}",
+                spec.name, spec.default, spec.description
+            );
+        }
+    }
+}
+
+fn format_label(name: &str, value: f64) -> String {
+    format!("{}={:.3}", name, value)
+}
+
+fn render_sweep(
+    figure: &dyn Figure,
+    params: &Params,
+    sweep: &Sweep,
+    columns: usize,
+) -> Result<(), String> {
+    let mut cells = Vec::new();
+    for value in sweep.values() {
+        let mut variant = params.clone();
+        variant.set(&sweep.param, value)?;
+        let scene = figure.build(&variant)?;
+        cells.push((format_label(&sweep.param, value), render_scene(&scene)));
+    }
+
+    let (center, half_width) = figure.view();
+    let filename = format!("output/{}_sweep_{}.svg", figure.name(), sweep.param);
+    render_contact_sheet(&filename, &cells, columns, center, half_width)
+        .map_err(|e| e.to_string())?;
+    println!("wrote {}", filename);
+    Ok(())
+}
+
+fn render_single(figure: &dyn Figure, params: &Params, json: bool) -> Result<(), String> {
+    let scene = figure.build(params)?;
+    let (center, half_width) = figure.view();
+    let views = [View("", center.real(), center.imag(), half_width)];
+    render_views("output", figure.name(), &views, render_scene(&scene))
+        .map_err(|e| e.to_string())?;
+
+    if json {
+        let mut metadata = vec![("name", String::from(figure.name()))];
+        metadata.extend(params.iter().map(|(k, v)| (k.as_str(), v.to_string())));
+
+        let filename = format!("output/{}.json", figure.name());
+        write_scene_json(&filename, &scene, &views, &metadata).map_err(|e| e.to_string())?;
+        println!("wrote {}", filename);
+    }
+    Ok(())
+}
+
+fn print_fingerprint(figure: &dyn Figure, params: &Params) -> Result<(), String> {
+    let scene = figure.build(params)?;
+    let hash = fingerprint(&scene).map_err(|e| e.to_string())?;
+    println!("{} {:016x}", figure.name(), hash);
+    Ok(())
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    if cli.fingerprint && cli.figure.is_none() {
+        for figure in registry() {
+            print_fingerprint(figure.as_ref(), &figure.default_params())?;
+        }
+        return Ok(());
+    }
+
+    let Some(name) = cli.figure else {
+        list_figures();
+        return Ok(());
+    };
+
+    let figure = find_figure(&name).ok_or(format!("unknown figure {}", name))?;
+    let mut params = figure.default_params();
+    for (param, value) in cli.overrides {
+        params.set(&param, value)?;
+    }
+
+    if cli.fingerprint {
+        return print_fingerprint(figure.as_ref(), &params);
+    }
+
+    match cli.sweep {
+        Some(sweep) => render_sweep(figure.as_ref(), &params, &sweep, cli.columns),
+        None => render_single(figure.as_ref(), &params, cli.json),
+    }
+}
+
+fn main() -> Result<(), Error> {
+    run(Cli::parse()).map_err(Error::other)
+}
@@ -0,0 +1,65 @@
+use std::io::Error;
+
+use mobius::{
+    elliptic::{Lattice, WeierstrassP},
+    prelude::*,
+};
+
+/// Lines across the fundamental cell of a hexagonal lattice, on the left,
+/// and their images under the Weierstrass ℘ function, on the right. The
+/// cell's edges and its midlines map to curves through the half-period
+/// values of ℘.
+fn main() -> Result<(), Error> {
+    let omega1 = Complex::ONE;
+    let omega2 = Complex::from_polar(1.0, std::f64::consts::FRAC_PI_3);
+    let lattice = Lattice::new(omega1, omega2).map_err(Error::other)?;
+    let p = WeierstrassP::new(lattice);
+
+    // Lines parallel to each period, sampled finely since ℘ bends them
+    const LINES: usize = 8;
+    const SAMPLES: usize = 400;
+    let mut paths = Vec::new();
+    for i in 1..LINES {
+        let offset = i as f64 / LINES as f64;
+        let along = |start: Complex, direction: Complex| -> Vec<Complex> {
+            (0..=SAMPLES)
+                .map(|j| start + direction * (j as f64 / SAMPLES as f64).into())
+                .collect()
+        };
+        paths.push(along(omega2 * offset.into(), omega1));
+        paths.push(along(omega1 * offset.into(), omega2));
+    }
+
+    let shift = translation(Complex::new(-2.5, 0.0)).unwrap();
+    let cell_edges: Vec<ClineArc> = lattice
+        .fundamental_cell()
+        .edges()
+        .map(|edge| edge.into())
+        .collect();
+    let cell = ClineArcTile::new(cell_edges).transform(shift);
+    let grid = ClineArcTile::new(
+        paths
+            .iter()
+            .flat_map(|path| {
+                path.windows(2)
+                    .map(|pair| LineSegment::new(pair[0], pair[1]).into())
+            })
+            .collect(),
+    )
+    .transform(shift);
+    let warped: Vec<ClineArcTile> = paths.iter().map(|path| p.warp_path(path)).collect();
+
+    let ink = Style::stroke(255, 127, 0).with_width(0.25);
+    let outline = Style::stroke(255, 255, 255).with_width(0.5);
+    let mut scene = Scene::new();
+    scene.add_layer(0, ink, &[grid]);
+    scene.add_layer(1, outline, &[cell]);
+    scene.add_layer(0, ink, &warped);
+
+    render_views(
+        "output",
+        "weierstrass",
+        &[View("", 0.0, 0.0, 5.0)],
+        render_scene(&scene),
+    )
+}
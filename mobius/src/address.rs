@@ -88,6 +88,10 @@ impl FractalAddress {
     pub fn rightmost(&self) -> Symbol {
         self.symbols[self.symbols.len() - 1]
     }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
 }
 
 // Promote a symbol to an address of length 1
@@ -0,0 +1,470 @@
+//! Boolean operations on regions bounded by circular arcs and line
+//! segments.
+//!
+//! A tile is treated as the boundary of a region. A point is inside
+//! depending on the signed number of times a ray from it crosses the
+//! tile's arcs, see FillRule. The arcs do not need to be in order, but
+//! each loop must be closed.
+//!
+//! Boolean operations use FillRule::EvenOdd, like SVG's
+//! `fill-rule: evenodd`, so loops nested inside other loops (like the
+//! ghost's eyes) are holes, and loops don't need to be consistently
+//! oriented.
+//!
+//! Operations work on the boundary: arcs are split wherever they cross
+//! the other shape's arcs, and each piece is kept or discarded based on
+//! which side of the other shape its midpoint is on. Arcs that overlap
+//! exactly (e.g. two shapes sharing an edge) are not handled specially,
+//! so results along shared edges are unpredictable.
+use std::f64::consts::TAU;
+
+use crate::{
+    cline_arc::{ClineArc, ClineArcGeometry},
+    geometry::{
+        intersect_circle_line, intersect_circles, intersect_lines, ArcAngles, CircularArc,
+        Intersection, Line, LineSegment,
+    },
+    transformable::{ClineArcTile, Motif},
+    winding::FillRule,
+    Complex,
+};
+
+const EPSILON: f64 = 1e-9;
+
+/// Direction of the ray for even-odd tests. An arbitrary-looking angle
+/// makes it unlikely the ray passes exactly through a vertex or is
+/// tangent to an arc of a typical hand-made motif.
+const RAY_ANGLE: f64 = 0.7390851332;
+
+fn finite_geometry(arc: &ClineArc) -> Result<ClineArcGeometry, String> {
+    match arc.classify().map_err(|e| e.to_string())? {
+        geometry @ (ClineArcGeometry::CircularArc(_) | ClineArcGeometry::LineSegment(_)) => {
+            Ok(geometry)
+        }
+        geometry => Err(format!(
+            "regions must be bounded by finite arcs, got {}",
+            geometry
+        )),
+    }
+}
+
+pub(crate) fn tile_geometry(tile: &ClineArcTile) -> Result<Vec<ClineArcGeometry>, String> {
+    tile.get_primitives().iter().map(finite_geometry).collect()
+}
+
+pub(crate) fn point_at(geometry: &ClineArcGeometry, t: f64) -> Complex {
+    match geometry {
+        ClineArcGeometry::CircularArc(arc) => arc.interpolate(t),
+        ClineArcGeometry::LineSegment(LineSegment { start, end }) => {
+            *start + (*end - *start) * t.into()
+        }
+        _ => unreachable!("only finite geometry is used"),
+    }
+}
+
+/// Parameter t such that point_at(geometry, t) == z, assuming z is on the
+/// underlying circle or line. Points before the start of a circular arc
+/// wrap around to t > 1
+pub(crate) fn parameter_of(geometry: &ClineArcGeometry, z: Complex) -> f64 {
+    match geometry {
+        ClineArcGeometry::CircularArc(CircularArc { circle, angles }) => {
+            let ArcAngles(a, b) = *angles;
+            let delta = b - a;
+            let mut offset = ((z - circle.center).arg().unwrap_or(a) - a).rem_euclid(TAU);
+            if delta < 0.0 && offset > 0.0 {
+                offset -= TAU;
+            }
+            offset / delta
+        }
+        ClineArcGeometry::LineSegment(LineSegment { start, end }) => {
+            let direction = *end - *start;
+            Complex::dot(z - *start, direction) / direction.norm()
+        }
+        _ => unreachable!("only finite geometry is used"),
+    }
+}
+
+fn sub_geometry(geometry: &ClineArcGeometry, t0: f64, t1: f64) -> ClineArcGeometry {
+    match geometry {
+        ClineArcGeometry::CircularArc(CircularArc { circle, angles }) => {
+            let sub_angles = ArcAngles(angles.interpolate(t0), angles.interpolate(t1));
+            ClineArcGeometry::CircularArc(CircularArc::new(*circle, sub_angles))
+        }
+        _ => ClineArcGeometry::LineSegment(LineSegment::new(
+            point_at(geometry, t0),
+            point_at(geometry, t1),
+        )),
+    }
+}
+
+fn to_cline_arc(geometry: ClineArcGeometry) -> ClineArc {
+    match geometry {
+        ClineArcGeometry::CircularArc(arc) => arc.into(),
+        ClineArcGeometry::LineSegment(segment) => segment.into(),
+        _ => unreachable!("only finite geometry is used"),
+    }
+}
+
+/// Where the circles or lines under two pieces of geometry meet
+fn full_intersection(a: &ClineArcGeometry, b: &ClineArcGeometry) -> Intersection {
+    use ClineArcGeometry::{CircularArc as Arc, LineSegment as Segment};
+
+    match (a, b) {
+        (Arc(x), Arc(y)) => intersect_circles(x.circle, y.circle),
+        (Segment(segment), Arc(arc)) | (Arc(arc), Segment(segment)) => {
+            intersect_circle_line(arc.circle, Line::from(*segment))
+        }
+        (Segment(x), Segment(y)) => intersect_lines(Line::from(*x), Line::from(*y)),
+        _ => unreachable!("only finite geometry is used"),
+    }
+}
+
+/// Points where two pieces of geometry cross
+fn crossings(a: &ClineArcGeometry, b: &ClineArcGeometry) -> Vec<Complex> {
+    let on_piece = |geometry: &ClineArcGeometry, z: Complex| {
+        (-EPSILON..=1.0 + EPSILON).contains(&parameter_of(geometry, z))
+    };
+    full_intersection(a, b)
+        .points()
+        .into_iter()
+        .filter(|z| on_piece(a, *z) && on_piece(b, *z))
+        .collect()
+}
+
+/// Split a piece of geometry everywhere it crosses one of the cutters
+fn split(geometry: &ClineArcGeometry, cutters: &[ClineArcGeometry]) -> Vec<ClineArcGeometry> {
+    let mut cuts: Vec<f64> = cutters
+        .iter()
+        .flat_map(|cutter| crossings(geometry, cutter))
+        .map(|z| parameter_of(geometry, z))
+        .filter(|t| *t > EPSILON && *t < 1.0 - EPSILON)
+        .collect();
+    cuts.sort_by(f64::total_cmp);
+    cuts.dedup_by(|a, b| (*a - *b).abs() < EPSILON);
+
+    let mut bounds = vec![0.0];
+    bounds.extend(cuts);
+    bounds.push(1.0);
+    bounds
+        .windows(2)
+        .map(|pair| sub_geometry(geometry, pair[0], pair[1]))
+        .collect()
+}
+
+/// Direction a piece of geometry is heading at parameter t
+pub(crate) fn tangent_at(geometry: &ClineArcGeometry, t: f64) -> Complex {
+    match geometry {
+        ClineArcGeometry::CircularArc(arc) => {
+            let ArcAngles(a, b) = arc.angles;
+            let radial = arc.interpolate(t) - arc.circle.center;
+            Complex::I * radial * (b - a).signum().into()
+        }
+        ClineArcGeometry::LineSegment(LineSegment { start, end }) => *end - *start,
+        _ => unreachable!("only finite geometry is used"),
+    }
+}
+
+/// Values of s where the line start + s * direction crosses the boundary,
+/// in no particular order. Each is paired with +1 if the boundary crosses
+/// the line from right to left, and -1 if it crosses from left to right.
+///
+/// A crossing at an endpoint of a piece only counts if the piece leaves
+/// the line toward its left side, like the min <= y < max rule for
+/// polygon edges. This way a line through a vertex counts it once where
+/// the boundary passes through, and zero or two times where the boundary
+/// only touches the line and turns back, so crossings still pair up.
+fn signed_line_crossings(
+    boundary: &[ClineArcGeometry],
+    start: Complex,
+    direction: Complex,
+) -> Vec<(f64, f64)> {
+    let Ok(line) = Line::from_points(start, start + direction) else {
+        return vec![];
+    };
+
+    boundary
+        .iter()
+        .flat_map(|geometry| {
+            let intersection = match geometry {
+                ClineArcGeometry::CircularArc(CircularArc { circle, .. }) => {
+                    intersect_circle_line(*circle, line)
+                }
+                ClineArcGeometry::LineSegment(segment) => {
+                    intersect_lines(line, Line::from(*segment))
+                }
+                _ => unreachable!("only finite geometry is used"),
+            };
+
+            // A line that only touches a circle doesn't cross the boundary
+            let points = match intersection {
+                Intersection::Tangent(_) => vec![],
+                intersection => intersection.points(),
+            };
+
+            points.into_iter().filter_map(move |z| {
+                let t = parameter_of(geometry, z);
+                let s = Complex::dot(z - start, direction) / direction.norm();
+                let sign = Complex::wedge(direction, tangent_at(geometry, t)).signum();
+
+                // Direction the piece leaves the line in, if z is an endpoint
+                let leaving = if t.abs() < EPSILON {
+                    Some(tangent_at(geometry, 0.0))
+                } else if (t - 1.0).abs() < EPSILON {
+                    Some(-tangent_at(geometry, 1.0))
+                } else {
+                    None
+                };
+                let counted = match leaving {
+                    Some(leaving) => Complex::wedge(direction, leaving) > 0.0,
+                    None => (0.0..1.0).contains(&t),
+                };
+
+                counted.then_some((s, sign))
+            })
+        })
+        .collect()
+}
+
+/// Values of s where the line start + s * direction crosses the boundary,
+/// in no particular order. See signed_line_crossings()
+pub(crate) fn line_crossings(
+    boundary: &[ClineArcGeometry],
+    start: Complex,
+    direction: Complex,
+) -> Vec<f64> {
+    signed_line_crossings(boundary, start, direction)
+        .into_iter()
+        .map(|(s, _)| s)
+        .collect()
+}
+
+/// Check if a point is inside a list of boundary pieces. The winding
+/// number is the signed count of crossings along a ray from the point
+pub(crate) fn inside(boundary: &[ClineArcGeometry], z: Complex, rule: FillRule) -> bool {
+    let direction = Complex::from_polar(1.0, RAY_ANGLE);
+    let winding_number: f64 = signed_line_crossings(boundary, z, direction)
+        .into_iter()
+        .filter(|(s, _)| *s > 0.0)
+        .map(|(_, sign)| sign)
+        .sum();
+
+    rule.is_inside(winding_number)
+}
+
+fn midpoint(geometry: &ClineArcGeometry) -> Complex {
+    point_at(geometry, 0.5)
+}
+
+/// Check if a point is inside the region bounded by a tile. The arcs can
+/// be in any order, see the module documentation
+pub fn tile_contains(tile: &ClineArcTile, z: Complex, rule: FillRule) -> Result<bool, String> {
+    Ok(inside(&tile_geometry(tile)?, z, rule))
+}
+
+/// Shared implementation of Motif::difference() and Motif::intersect().
+/// The region of a motif is the union of the regions of its parts.
+///
+/// Each part of a keeps its style id. Its boundary is trimmed to the
+/// part outside (or inside) b, and the pieces of b's boundary that fall
+/// inside the part are added to close it up, with the part's style id.
+pub(crate) fn motif_boolean(a: &Motif, b: &Motif, keep_inside: bool) -> Result<Motif, String> {
+    let a_parts = a
+        .parts()
+        .into_iter()
+        .map(|(tile, id, z_order)| Ok((tile_geometry(tile)?, id, z_order)))
+        .collect::<Result<Vec<_>, String>>()?;
+    let b_parts = b
+        .iter()
+        .map(|(tile, _)| tile_geometry(tile))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let a_boundary: Vec<ClineArcGeometry> =
+        a_parts.iter().flat_map(|(x, _, _)| x.clone()).collect();
+    let b_boundary: Vec<ClineArcGeometry> = b_parts.iter().flatten().copied().collect();
+    let all_boundaries: Vec<ClineArcGeometry> =
+        a_boundary.iter().chain(&b_boundary).copied().collect();
+
+    // Point is in the region of b, optionally ignoring one of b's parts
+    let in_b = |z: Complex, skip: Option<usize>| {
+        b_parts
+            .iter()
+            .enumerate()
+            .any(|(j, part)| Some(j) != skip && inside(part, z, FillRule::EvenOdd))
+    };
+
+    // Each piece of b's boundary, along with the part it came from
+    let b_pieces: Vec<(ClineArcGeometry, usize)> = b_parts
+        .iter()
+        .enumerate()
+        .flat_map(|(j, part)| {
+            part.iter()
+                .flat_map(|geometry| split(geometry, &all_boundaries))
+                .map(move |piece| (piece, j))
+        })
+        .filter(|(piece, j)| !in_b(midpoint(piece), Some(*j)))
+        .collect();
+
+    let mut result = Motif::default();
+    for (geometry, id, z_order) in a_parts {
+        let mut pieces: Vec<ClineArc> = geometry
+            .iter()
+            .flat_map(|x| split(x, &b_boundary))
+            .filter(|piece| in_b(midpoint(piece), None) == keep_inside)
+            .map(to_cline_arc)
+            .collect();
+        pieces.extend(
+            b_pieces
+                .iter()
+                .filter(|(piece, _)| inside(&geometry, midpoint(piece), FillRule::EvenOdd))
+                .map(|(piece, _)| to_cline_arc(*piece)),
+        );
+
+        if !pieces.is_empty() {
+            result = result.with_part(ClineArcTile::new(pieces), id, z_order);
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        geometry::Circle,
+        motifs::ghost,
+        transformable::{circle_tile, square_tile},
+    };
+
+    use super::*;
+
+    fn part_sizes(motif: &Motif) -> Vec<(usize, usize)> {
+        motif
+            .iter()
+            .map(|(tile, id)| (tile.get_primitives().len(), id))
+            .collect()
+    }
+
+    #[test]
+    pub fn tile_contains_treats_eyes_as_holes() {
+        let (tile, _) = ghost();
+
+        assert!(tile_contains(&tile, Complex::new(0.0, 0.5), FillRule::EvenOdd).unwrap());
+        assert!(!tile_contains(&tile, Complex::new(-0.5, 0.0), FillRule::EvenOdd).unwrap());
+        assert!(!tile_contains(&tile, Complex::new(2.0, 0.0), FillRule::EvenOdd).unwrap());
+    }
+
+    #[test]
+    pub fn tile_contains_works_for_segments() {
+        let tile = square_tile(Complex::new(-1.0, -1.0), 2.0);
+
+        assert!(tile_contains(&tile, Complex::new(0.9, 0.2), FillRule::EvenOdd).unwrap());
+        assert!(!tile_contains(&tile, Complex::new(1.1, 0.2), FillRule::EvenOdd).unwrap());
+    }
+
+    #[test]
+    pub fn tile_contains_applies_fill_rule_to_nested_loops() {
+        // Two counterclockwise circles, with the arcs out of order
+        let outer = circle_tile(Circle::new(Complex::Zero, 2.0));
+        let inner = circle_tile(Circle::new(Complex::Zero, 1.0));
+        let mut arcs = inner.get_primitives().to_vec();
+        arcs.extend(outer.get_primitives().iter().rev());
+        let tile = ClineArcTile::new(arcs);
+        let (center, ring) = (Complex::new(0.1, 0.2), Complex::new(1.5, 0.0));
+
+        assert!(tile_contains(&tile, center, FillRule::NonZero).unwrap());
+        assert!(!tile_contains(&tile, center, FillRule::EvenOdd).unwrap());
+        assert!(tile_contains(&tile, ring, FillRule::NonZero).unwrap());
+        assert!(tile_contains(&tile, ring, FillRule::EvenOdd).unwrap());
+    }
+
+    #[test]
+    pub fn crossings_finds_circle_circle_points() {
+        let a = ClineArcGeometry::CircularArc(CircularArc::new(
+            Circle::unit_circle(),
+            ArcAngles::semicircles().0,
+        ));
+        let b = ClineArcGeometry::CircularArc(CircularArc::new(
+            Circle::new(Complex::ONE, 1.0),
+            ArcAngles::semicircles().0,
+        ));
+
+        let result = crossings(&a, &b);
+
+        // Only the upper intersection point is on both upper halves
+        assert_eq!(result.len(), 1);
+        assert!((result[0] - Complex::new(0.5, 0.75f64.sqrt())).mag() < 1e-12);
+    }
+
+    #[test]
+    pub fn difference_with_disjoint_motif_keeps_parts() {
+        let a = Motif::new(vec![(circle_tile(Circle::new(Complex::Zero, 1.0)), 3)]);
+        let b = Motif::new(vec![(
+            circle_tile(Circle::new(Complex::new(5.0, 0.0), 1.0)),
+            0,
+        )]);
+
+        let result = motif_boolean(&a, &b, false).unwrap();
+
+        assert_eq!(part_sizes(&result), vec![(2, 3)]);
+    }
+
+    #[test]
+    pub fn intersect_with_disjoint_motif_is_empty() {
+        let a = Motif::new(vec![(circle_tile(Circle::new(Complex::Zero, 1.0)), 3)]);
+        let b = Motif::new(vec![(
+            circle_tile(Circle::new(Complex::new(5.0, 0.0), 1.0)),
+            0,
+        )]);
+
+        let result = motif_boolean(&a, &b, true).unwrap();
+
+        assert_eq!(part_sizes(&result), vec![]);
+    }
+
+    #[test]
+    pub fn difference_with_nested_circle_adds_hole() {
+        let a = Motif::new(vec![(circle_tile(Circle::new(Complex::Zero, 1.0)), 1)]);
+        let b = Motif::new(vec![(circle_tile(Circle::new(Complex::Zero, 0.5)), 0)]);
+
+        let result = motif_boolean(&a, &b, false).unwrap();
+
+        assert_eq!(part_sizes(&result), vec![(4, 1)]);
+        let (tile, _) = result.iter().next().unwrap();
+        assert!(tile_contains(tile, Complex::new(0.75, 0.0), FillRule::EvenOdd).unwrap());
+        assert!(!tile_contains(tile, Complex::new(0.25, 0.0), FillRule::EvenOdd).unwrap());
+    }
+
+    #[test]
+    pub fn overlapping_circles_split_into_lune_pieces() {
+        let a = Motif::new(vec![(circle_tile(Circle::new(Complex::Zero, 1.0)), 1)]);
+        let b = Motif::new(vec![(circle_tile(Circle::new(Complex::ONE, 1.0)), 0)]);
+
+        let difference = motif_boolean(&a, &b, false).unwrap();
+        let intersection = motif_boolean(&a, &b, true).unwrap();
+
+        assert_eq!(part_sizes(&difference), vec![(4, 1)]);
+        assert_eq!(part_sizes(&intersection), vec![(4, 1)]);
+        let (tile, _) = difference.iter().next().unwrap();
+        assert!(tile_contains(tile, Complex::new(-0.5, 0.0), FillRule::EvenOdd).unwrap());
+        assert!(!tile_contains(tile, Complex::new(0.5, 0.0), FillRule::EvenOdd).unwrap());
+        let (tile, _) = intersection.iter().next().unwrap();
+        assert!(tile_contains(tile, Complex::new(0.5, 0.0), FillRule::EvenOdd).unwrap());
+        assert!(!tile_contains(tile, Complex::new(-0.5, 0.0), FillRule::EvenOdd).unwrap());
+    }
+
+    #[test]
+    pub fn difference_preserves_style_ids_of_each_part() {
+        let a = Motif::new(vec![
+            (circle_tile(Circle::new(Complex::Zero, 1.0)), 0),
+            (square_tile(Complex::new(-1.0, -1.0), 2.0), 1),
+        ]);
+        let b = Motif::new(vec![(
+            circle_tile(Circle::new(Complex::new(0.0, 0.5), 0.25)),
+            7,
+        )]);
+
+        let result = motif_boolean(&a, &b, false).unwrap();
+
+        assert_eq!(part_sizes(&result), vec![(4, 0), (6, 1)]);
+    }
+}
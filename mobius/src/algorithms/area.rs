@@ -0,0 +1,366 @@
+//! Areas of regions bounded by circular arcs and line segments.
+//!
+//! Like the boolean operations, a tile is the boundary of a region under
+//! the even-odd rule. The arcs are chained into closed loops, each loop's
+//! area is computed with Green's theorem, and loops nested inside an odd
+//! number of other loops are subtracted as holes.
+use std::f64::consts::TAU;
+
+use crate::{
+    cline_arc::ClineArcGeometry,
+    geometry::{ArcAngles, Circle, CircularArc, DirectedEdge, LineSegment},
+    transformable::ClineArcTile,
+    winding::FillRule,
+    Complex,
+};
+
+use super::{inside, tile_geometry};
+
+/// Distance within which two endpoints are considered the same point
+/// when chaining arcs into loops
+const EPSILON: f64 = 1e-9;
+
+/// Target accuracy for the numerical integration of hyperbolic area
+const INTEGRATION_TOLERANCE: f64 = 1e-10;
+
+/// Subdivision depth limit for adaptive integration
+const MAX_DEPTH: u32 = 40;
+
+fn reverse(geometry: &ClineArcGeometry) -> ClineArcGeometry {
+    match geometry {
+        ClineArcGeometry::CircularArc(arc) => ClineArcGeometry::CircularArc(arc.reverse()),
+        ClineArcGeometry::LineSegment(LineSegment { start, end }) => {
+            ClineArcGeometry::LineSegment(LineSegment::new(*end, *start))
+        }
+        _ => unreachable!("only finite geometry is used"),
+    }
+}
+
+fn endpoints(geometry: &ClineArcGeometry) -> (Complex, Complex) {
+    match geometry {
+        ClineArcGeometry::CircularArc(arc) => (arc.start(), arc.end()),
+        ClineArcGeometry::LineSegment(LineSegment { start, end }) => (*start, *end),
+        _ => unreachable!("only finite geometry is used"),
+    }
+}
+
+/// Point and velocity at parameter t in [0, 1]
+fn point_and_velocity(geometry: &ClineArcGeometry, t: f64) -> (Complex, Complex) {
+    match geometry {
+        ClineArcGeometry::CircularArc(CircularArc { circle, angles }) => {
+            let ArcAngles(a, b) = *angles;
+            let offset = Complex::from_polar(circle.radius, a + t * (b - a));
+            (circle.center + offset, Complex::I * offset * (b - a).into())
+        }
+        ClineArcGeometry::LineSegment(LineSegment { start, end }) => {
+            (*start + (*end - *start) * t.into(), *end - *start)
+        }
+        _ => unreachable!("only finite geometry is used"),
+    }
+}
+
+/// Split a tile's boundary into closed loops, with each piece oriented
+/// to continue from the end of the previous one. Pieces can be in any
+/// order or direction in the tile
+pub(crate) fn tile_loops(tile: &ClineArcTile) -> Result<Vec<Vec<ClineArcGeometry>>, String> {
+    let mut remaining = tile_geometry(tile)?;
+    let mut loops = Vec::new();
+    while let Some(first) = remaining.pop() {
+        let (loop_start, mut current) = endpoints(&first);
+        let mut pieces = vec![first];
+        while (current - loop_start).mag() > EPSILON {
+            let next = remaining.iter().enumerate().find_map(|(i, x)| {
+                let (start, end) = endpoints(x);
+                if (start - current).mag() <= EPSILON {
+                    Some((i, false))
+                } else if (end - current).mag() <= EPSILON {
+                    Some((i, true))
+                } else {
+                    None
+                }
+            });
+            let Some((index, reversed)) = next else {
+                return Err(format!("tile boundary is not closed at {}", current));
+            };
+
+            let piece = remaining.swap_remove(index);
+            let piece = if reversed { reverse(&piece) } else { piece };
+            current = endpoints(&piece).1;
+            pieces.push(piece);
+        }
+        loops.push(pieces);
+    }
+
+    Ok(loops)
+}
+
+/// Adaptive Simpson's rule for the integral of f over [a, b]
+fn integrate(f: &impl Fn(f64) -> f64, a: f64, b: f64) -> f64 {
+    fn simpson(fa: f64, fm: f64, fb: f64, a: f64, b: f64) -> f64 {
+        (b - a) / 6.0 * (fa + 4.0 * fm + fb)
+    }
+
+    fn refine(
+        f: &impl Fn(f64) -> f64,
+        (a, m, b): (f64, f64, f64),
+        (fa, fm, fb): (f64, f64, f64),
+        whole: f64,
+        tolerance: f64,
+        depth: u32,
+    ) -> f64 {
+        let (left_mid, right_mid) = (0.5 * (a + m), 0.5 * (m + b));
+        let (f_left, f_right) = (f(left_mid), f(right_mid));
+        let left = simpson(fa, f_left, fm, a, m);
+        let right = simpson(fm, f_right, fb, m, b);
+        let error = left + right - whole;
+        if depth >= MAX_DEPTH || error.abs() <= 15.0 * tolerance {
+            return left + right + error / 15.0;
+        }
+
+        refine(
+            f,
+            (a, left_mid, m),
+            (fa, f_left, fm),
+            left,
+            0.5 * tolerance,
+            depth + 1,
+        ) + refine(
+            f,
+            (m, right_mid, b),
+            (fm, f_right, fb),
+            right,
+            0.5 * tolerance,
+            depth + 1,
+        )
+    }
+
+    let m = 0.5 * (a + b);
+    let (fa, fm, fb) = (f(a), f(m), f(b));
+    let whole = simpson(fa, fm, fb, a, b);
+    refine(f, (a, m, b), (fa, fm, fb), whole, INTEGRATION_TOLERANCE, 0)
+}
+
+/// Area of a region given the signed area enclosed by each loop
+fn even_odd_area(
+    tile: &ClineArcTile,
+    loop_area: impl Fn(&[ClineArcGeometry]) -> f64,
+) -> Result<f64, String> {
+    let loops = tile_loops(tile)?;
+    let area = loops
+        .iter()
+        .enumerate()
+        .map(|(i, pieces)| {
+            // A loop inside an odd number of other loops is a hole
+            let (probe, _) = point_and_velocity(&pieces[0], 0.5);
+            let depth = loops
+                .iter()
+                .enumerate()
+                .filter(|(j, other)| *j != i && inside(other, probe, FillRule::EvenOdd))
+                .count();
+            let sign = if depth % 2 == 0 { 1.0 } else { -1.0 };
+            sign * loop_area(pieces).abs()
+        })
+        .sum::<f64>();
+
+    Ok(area.abs())
+}
+
+/// Euclidean area of the region bounded by a tile. The boundary must be
+/// made of finite arcs that form closed loops.
+pub fn tile_area(tile: &ClineArcTile) -> Result<f64, String> {
+    // By Green's theorem, area = 1/2 of the integral of x dy - y dx
+    // around the boundary. For a circular arc with center c and radius r
+    // that sweeps through an angle delta, this is
+    // (wedge(c, end - start) + r^2 delta) / 2
+    even_odd_area(tile, |pieces| {
+        pieces
+            .iter()
+            .map(|piece| match piece {
+                ClineArcGeometry::CircularArc(arc) => {
+                    let ArcAngles(a, b) = arc.angles;
+                    let Circle { center, radius } = arc.circle;
+                    0.5 * (Complex::wedge(center, arc.end() - arc.start())
+                        + radius * radius * (b - a))
+                }
+                ClineArcGeometry::LineSegment(LineSegment { start, end }) => {
+                    0.5 * Complex::wedge(*start, *end)
+                }
+                _ => unreachable!("only finite geometry is used"),
+            })
+            .sum()
+    })
+}
+
+/// Largest magnitude of any point of a piece of the boundary
+fn max_magnitude(piece: &ClineArcGeometry) -> f64 {
+    let (start, end) = endpoints(piece);
+    let endpoint_max = start.mag().max(end.mag());
+    match piece {
+        ClineArcGeometry::CircularArc(CircularArc { circle, angles }) => {
+            // The furthest point of the circle from the origin is in the
+            // direction of the center, it only counts if the arc reaches it
+            let ArcAngles(a, b) = *angles;
+            let furthest = circle.center.arg().unwrap_or(a);
+            let offset = if b > a {
+                (furthest - a).rem_euclid(TAU)
+            } else {
+                (a - furthest).rem_euclid(TAU)
+            };
+            if offset <= (b - a).abs() {
+                endpoint_max.max(circle.center.mag() + circle.radius)
+            } else {
+                endpoint_max
+            }
+        }
+        _ => endpoint_max,
+    }
+}
+
+/// Hyperbolic area of the region bounded by a tile in the Poincaré disk
+/// model, where the metric is 2|dz| / (1 - |z|^2). The tile must be
+/// strictly inside the unit disk, since tiles that reach the boundary
+/// have infinite area.
+///
+/// This is the natural measure for hyperbolic tilings: all the tiles of
+/// a regular tiling have the same hyperbolic area, even though their
+/// Euclidean areas shrink towards the boundary.
+pub fn hyperbolic_tile_area(tile: &ClineArcTile) -> Result<f64, String> {
+    for piece in tile_geometry(tile)? {
+        if max_magnitude(&piece) >= 1.0 {
+            return Err(format!(
+                "tile must be inside the unit disk, but {} reaches the boundary",
+                piece
+            ));
+        }
+    }
+
+    // The area form 4 dx dy / (1 - r^2)^2 is the exterior derivative of
+    // 2 (x dy - y dx) / (1 - r^2), so Green's theorem applies again.
+    even_odd_area(tile, |pieces| {
+        pieces
+            .iter()
+            .map(|piece| {
+                let integrand = |t| {
+                    let (z, velocity) = point_and_velocity(piece, t);
+                    2.0 * Complex::wedge(z, velocity) / (1.0 - z.norm())
+                };
+                integrate(&integrand, 0.0, 1.0)
+            })
+            .sum()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use crate::{
+        hyperbolic_tilings::get_fundamental_region,
+        motifs::ghost,
+        transformable::{circle_tile, square_tile, Transformable},
+        translation,
+    };
+
+    use super::*;
+
+    /// Unit square with its edges deliberately out of order and one edge
+    /// reversed
+    fn shuffled_square() -> ClineArcTile {
+        let edges = square_tile(Complex::Zero, 1.0);
+        let [bottom, right, top, left] = edges.get_primitives() else {
+            unreachable!("a square has four edges");
+        };
+        let reversed = LineSegment::new(bottom.end(), bottom.start());
+        ClineArcTile::new(vec![*top, reversed.into(), *left, *right])
+    }
+
+    #[test]
+    pub fn tile_area_of_square_with_shuffled_edges() {
+        let result = tile_area(&shuffled_square()).unwrap();
+
+        assert!((result - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    pub fn tile_area_of_circle_is_pi_r_squared() {
+        let tile = circle_tile(Circle::new(Complex::new(3.0, -1.0), 2.0));
+
+        let result = tile_area(&tile).unwrap();
+
+        assert!((result - 4.0 * PI).abs() < 1e-12);
+    }
+
+    #[test]
+    pub fn tile_area_subtracts_holes() {
+        let outer = circle_tile(Circle::new(Complex::Zero, 2.0));
+        let inner = circle_tile(Circle::new(Complex::Zero, 1.0));
+        let annulus = ClineArcTile::new([outer.get_primitives(), inner.get_primitives()].concat());
+
+        let result = tile_area(&annulus).unwrap();
+
+        assert!((result - 3.0 * PI).abs() < 1e-12);
+    }
+
+    #[test]
+    pub fn tile_area_of_ghost_is_positive() {
+        let (tile, _) = ghost();
+
+        let result = tile_area(&tile).unwrap();
+
+        assert!(result > 0.0);
+    }
+
+    #[test]
+    pub fn tile_area_of_open_boundary_returns_error() {
+        let tile = ClineArcTile::new(vec![
+            LineSegment::new(Complex::Zero, Complex::ONE).into(),
+            LineSegment::new(Complex::ONE, Complex::I).into(),
+        ]);
+
+        let result = tile_area(&tile);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn tile_area_is_translation_invariant() {
+        let tile = shuffled_square();
+        let xform = translation(Complex::new(-5.0, 2.0)).unwrap();
+
+        let result = tile_area(&tile.transform(xform)).unwrap();
+
+        assert!((result - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    pub fn hyperbolic_area_of_centered_disk() {
+        let r = 0.5;
+        let tile = circle_tile(Circle::new(Complex::Zero, r));
+
+        let result = hyperbolic_tile_area(&tile).unwrap();
+
+        let expected = 4.0 * PI * r * r / (1.0 - r * r);
+        assert!((result - expected).abs() < 1e-8);
+    }
+
+    #[test]
+    pub fn hyperbolic_area_of_fundamental_triangle_is_angle_defect() {
+        let (p, q) = (5, 4);
+        let (tile, _) = get_fundamental_region(p, q).unwrap();
+
+        let result = hyperbolic_tile_area(&tile).unwrap();
+
+        // Triangle with angles pi/p, pi/q, pi/2
+        let expected = PI / 2.0 - PI / p as f64 - PI / q as f64;
+        assert!((result - expected).abs() < 1e-8);
+    }
+
+    #[test]
+    pub fn hyperbolic_area_touching_boundary_returns_error() {
+        let tile = circle_tile(Circle::new(Complex::new(0.5, 0.0), 0.5));
+
+        let result = hyperbolic_tile_area(&tile);
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,186 @@
+use abstraction::GroupAction;
+
+use crate::{random::SplitMix64, Complex};
+
+use super::DensityGrid;
+
+/// Check that there is one weight per transformation, and that each weight
+/// is finite and non-negative. Weights are relative, so they don't need to
+/// sum to 1
+pub fn validate_weights(count: usize, weights: &[f64]) -> Result<(), String> {
+    if weights.len() != count {
+        return Err(format!("expected {} weights, got {}", count, weights.len()));
+    }
+
+    if weights.iter().any(|x| !x.is_finite() || *x < 0.0) {
+        return Err(String::from("weights must be finite and non-negative"));
+    }
+
+    Ok(())
+}
+
+/// Choose transformation indices at random, either uniformly or with
+/// the given weights
+struct WeightedChoice {
+    /// Running totals of the weights, normalized so the last is 1.0
+    cumulative: Vec<f64>,
+}
+
+impl WeightedChoice {
+    fn new(count: usize, weights: Option<&[f64]>) -> Result<Self, String> {
+        let weights = match weights {
+            None => vec![1.0; count],
+            Some(weights) => {
+                validate_weights(count, weights)?;
+                weights.to_vec()
+            }
+        };
+
+        let total: f64 = weights.iter().sum();
+        if total <= 0.0 {
+            return Err(String::from("at least one weight must be positive"));
+        }
+
+        let cumulative = weights
+            .iter()
+            .scan(0.0, |sum, x| {
+                *sum += x / total;
+                Some(*sum)
+            })
+            .collect();
+
+        Ok(Self { cumulative })
+    }
+
+    fn choose(&self, rng: &mut SplitMix64) -> usize {
+        let x = rng.next_f64();
+        self.cumulative
+            .iter()
+            .position(|threshold| x < *threshold)
+            // Rounding may leave the last total slightly under 1.0
+            .unwrap_or(self.cumulative.len() - 1)
+    }
+}
+
+/// Play the chaos game: starting from a point, repeatedly apply a randomly
+/// chosen transformation and record where it lands. For a contracting IFS
+/// the orbit quickly settles onto the attractor, so the result makes a
+/// point cloud picture of the fractal.
+///
+/// weights gives the relative probability of each transformation, or None
+/// to choose uniformly. The same rng_seed always gives the same orbit. The
+/// result has n points and does not include the start. The first few points
+/// may not be on the attractor yet, so callers may want to skip() them.
+pub fn chaos_game<G: GroupAction<X>, X: Clone>(
+    xforms: &[G],
+    start: X,
+    n: usize,
+    weights: Option<&[f64]>,
+    rng_seed: u64,
+) -> Result<Vec<X>, String> {
+    if xforms.is_empty() {
+        return Err(String::from("at least one transformation is required"));
+    }
+
+    let choice = WeightedChoice::new(xforms.len(), weights)?;
+    let mut rng = SplitMix64::new(rng_seed);
+
+    let mut current = start;
+    let mut result = Vec::with_capacity(n);
+    for _ in 0..n {
+        current = xforms[choice.choose(&mut rng)].act(&current);
+        result.push(current.clone());
+    }
+
+    Ok(result)
+}
+
+/// Estimate the invariant measure of an IFS by playing the chaos game and
+/// counting where the orbit lands in the grid. The first burn_in points
+/// are skipped since they may not be on the attractor yet. The same seed
+/// always gives the same counts.
+pub fn chaos_game_density<G: GroupAction<Complex>>(
+    xforms: &[G],
+    weights: Option<&[f64]>,
+    grid: &mut DensityGrid,
+    start: Complex,
+    iterations: usize,
+    burn_in: usize,
+    seed: u64,
+) -> Result<(), String> {
+    let orbit = chaos_game(xforms, start, burn_in + iterations, weights, seed)?;
+    grid.add_points(orbit.into_iter().skip(burn_in));
+    Ok(())
+}
+
+/// Sierpinski triangle maps: scale by 1/2 towards each corner of the
+/// triangle 0, 1, i. These are invertible, so they work for both kinds of
+/// IFS
+#[cfg(test)]
+pub fn sierpinski_maps() -> Vec<crate::Mobius> {
+    let half = crate::scale(0.5).unwrap();
+    [Complex::Zero, Complex::ONE, Complex::I]
+        .into_iter()
+        .map(|corner| crate::translation(corner * 0.5.into()).unwrap() * half)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Mobius;
+
+    use super::*;
+
+    #[test]
+    pub fn chaos_game_with_no_xforms_returns_error() {
+        let result = chaos_game::<Mobius, Complex>(&[], Complex::Zero, 10, None, 0);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn validate_weights_rejects_negative_weight() {
+        let result = validate_weights(2, &[1.0, -1.0]);
+
+        assert!(result.is_err_and(|e| e.contains("non-negative")));
+    }
+
+    #[test]
+    pub fn chaos_game_with_wrong_weight_count_returns_error() {
+        let result = chaos_game(&sierpinski_maps(), Complex::Zero, 10, Some(&[1.0, 2.0]), 0);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn chaos_game_is_deterministic() {
+        let xforms = sierpinski_maps();
+
+        let a = chaos_game(&xforms, Complex::Zero, 100, None, 42).unwrap();
+        let b = chaos_game(&xforms, Complex::Zero, 100, None, 42).unwrap();
+
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    pub fn chaos_game_stays_in_attractor() {
+        let result = chaos_game(&sierpinski_maps(), Complex::Zero, 1000, None, 1).unwrap();
+
+        // Starting from a corner, every point stays inside the triangle
+        // with corners 0, 1, i
+        assert!(result
+            .iter()
+            .all(|z| z.real() >= 0.0 && z.imag() >= 0.0 && z.real() + z.imag() <= 1.0));
+    }
+
+    #[test]
+    pub fn chaos_game_with_zero_weight_never_picks_that_xform() {
+        let xforms = sierpinski_maps();
+
+        let result = chaos_game(&xforms, Complex::ONE, 500, Some(&[1.0, 0.0, 1.0]), 3).unwrap();
+
+        // Starting from the corner at 1, the first step moves halfway to
+        // another corner, and nothing ever moves back towards 1
+        assert!(result.iter().all(|z| z.real() <= 0.5 + 1e-12));
+    }
+}
@@ -0,0 +1,282 @@
+//! Checkpoints for depth-first traversals that take hours, e.g. deep
+//! limit sets or dimension estimates. The traversal state is saved to a
+//! small text file every so often, so an interrupted run can pick up
+//! from the last save instead of starting over.
+//!
+//! The stack of a GroupIFS traversal only needs the addresses, since the
+//! group elements can be recomputed with GroupIFS::eval(). The file is a
+//! list of `key = value` lines, with one `stack` line per entry (bottom
+//! of the stack first, and an empty value for the identity):
+//!
+//! ```text
+//! # DFS checkpoint
+//! max_depth = 20
+//! visited = 1048576
+//! results = 0 12 4.5
+//! stack = abA
+//! stack = abB
+//! ```
+//!
+//! results holds whatever partial results the visitor accumulates, e.g.
+//! box counts for a dimension estimate.
+use std::{fs, path::Path};
+
+use abstraction::{Group, Monoid};
+use thiserror::Error;
+
+use crate::address::FractalAddress;
+
+use super::GroupIFS;
+
+#[derive(Debug, Error)]
+pub enum CheckpointError {
+    #[error("could not access checkpoint: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("line {0}: {1}")]
+    Syntax(usize, String),
+    #[error("checkpoint is missing {0}")]
+    Missing(&'static str),
+}
+
+/// Saved state of a depth-first traversal of a GroupIFS
+#[derive(Clone, Debug, PartialEq)]
+pub struct DFSCheckpoint {
+    pub max_depth: usize,
+    /// How many elements have been visited so far
+    pub visited: usize,
+    /// Partial results, interpreted by the visitor
+    pub results: Vec<f64>,
+    /// Addresses still to visit, bottom of the stack first
+    pub stack: Vec<FractalAddress>,
+}
+
+impl DFSCheckpoint {
+    /// State for a traversal that hasn't started yet
+    pub fn new(max_depth: usize, results: Vec<f64>) -> Self {
+        Self {
+            max_depth,
+            visited: 0,
+            results,
+            stack: vec![FractalAddress::identity()],
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Format the checkpoint as text, see the module documentation
+    pub fn to_text(&self) -> String {
+        let results: Vec<String> = self.results.iter().map(|x| x.to_string()).collect();
+        let mut lines = vec![
+            String::from("# DFS checkpoint"),
+            format!("max_depth = {}", self.max_depth),
+            format!("visited = {}", self.visited),
+            format!("results = {}", results.join(" ")),
+        ];
+        lines.extend(
+            self.stack
+                .iter()
+                .map(|address| format!("stack = {}", address).trim_end().to_string()),
+        );
+        lines.join("\n") + "\n"
+    }
+
+    /// Parse a checkpoint written by to_text()
+    pub fn from_text(text: &str) -> Result<Self, CheckpointError> {
+        let mut max_depth = None;
+        let mut visited = None;
+        let mut results = None;
+        let mut stack = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let syntax = |message: String| CheckpointError::Syntax(line_number, message);
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(syntax(format!("expected key = value, got {}", line)));
+            };
+            let value = value.trim();
+            let parse_count = || {
+                value
+                    .parse::<usize>()
+                    .map_err(|_| syntax(format!("invalid count {}", value)))
+            };
+            match key.trim() {
+                "max_depth" => max_depth = Some(parse_count()?),
+                "visited" => visited = Some(parse_count()?),
+                "results" => {
+                    results = Some(
+                        value
+                            .split_whitespace()
+                            .map(|x| x.parse::<f64>())
+                            .collect::<Result<Vec<f64>, _>>()
+                            .map_err(|_| syntax(format!("invalid results {}", value)))?,
+                    )
+                }
+                "stack" => stack.push(FractalAddress::try_from(value).map_err(syntax)?),
+                key => return Err(syntax(format!("unknown key {}", key))),
+            }
+        }
+
+        Ok(Self {
+            max_depth: max_depth.ok_or(CheckpointError::Missing("max_depth"))?,
+            visited: visited.ok_or(CheckpointError::Missing("visited"))?,
+            results: results.ok_or(CheckpointError::Missing("results"))?,
+            stack,
+        })
+    }
+
+    /// Write the checkpoint to a file. The text is written to a
+    /// temporary file first and then renamed, so an interruption
+    /// mid-write never leaves a truncated checkpoint behind
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), CheckpointError> {
+        let path = path.as_ref();
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, self.to_text())?;
+        fs::rename(temp_path, path)?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, CheckpointError> {
+        Self::from_text(&fs::read_to_string(path)?)
+    }
+
+    /// Load the checkpoint at path if there is one, otherwise start a new
+    /// traversal
+    pub fn load_or_new<P: AsRef<Path>>(
+        path: P,
+        max_depth: usize,
+        results: Vec<f64>,
+    ) -> Result<Self, CheckpointError> {
+        if path.as_ref().exists() {
+            Self::load(path)
+        } else {
+            Ok(Self::new(max_depth, results))
+        }
+    }
+}
+
+impl<G: Group> GroupIFS<G> {
+    /// Continue a depth-first traversal from a checkpoint, calling
+    /// visit(address, element, results) for each element. The checkpoint
+    /// is saved to path every save_interval visits and again at the end.
+    /// The visitor returns false to stop early, e.g. after a time limit.
+    /// Returns true if the traversal finished.
+    ///
+    /// The visitor should only change results based on the element it is
+    /// given, since elements visited after the last save are visited again
+    /// when resuming
+    pub fn dfs_checkpointed<P: AsRef<Path>>(
+        &self,
+        checkpoint: &mut DFSCheckpoint,
+        path: P,
+        save_interval: usize,
+        mut visit: impl FnMut(&FractalAddress, &G, &mut [f64]) -> bool,
+    ) -> Result<bool, CheckpointError> {
+        let save_interval = save_interval.max(1);
+        let mut iterator = self.dfs_resume(checkpoint.max_depth, &checkpoint.stack);
+        let mut keep_going = true;
+        while keep_going {
+            let Some((address, xform)) = iterator.next() else {
+                break;
+            };
+            keep_going = visit(&address, &xform, &mut checkpoint.results);
+            checkpoint.visited += 1;
+
+            if checkpoint.visited.is_multiple_of(save_interval) {
+                checkpoint.stack = iterator.stack_addresses();
+                checkpoint.save(&path)?;
+            }
+        }
+
+        checkpoint.stack = iterator.stack_addresses();
+        checkpoint.save(&path)?;
+        Ok(checkpoint.is_finished())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use crate::{translation, Complex, Mobius};
+
+    use super::*;
+
+    fn make_ifs() -> GroupIFS<Mobius> {
+        let a = translation(Complex::ONE).unwrap();
+        let b = translation(Complex::I).unwrap();
+        GroupIFS::new(vec![a, b])
+    }
+
+    /// Count the visited elements at each word length
+    fn count_lengths(address: &FractalAddress, _: &Mobius, results: &mut [f64]) -> bool {
+        results[address.len()] += 1.0;
+        true
+    }
+
+    #[test]
+    pub fn from_text_round_trips() {
+        let checkpoint = DFSCheckpoint {
+            max_depth: 4,
+            visited: 17,
+            results: vec![0.0, 1.5, -2e-9],
+            stack: vec![
+                FractalAddress::identity(),
+                FractalAddress::try_from("aB").unwrap(),
+            ],
+        };
+
+        let result = DFSCheckpoint::from_text(&checkpoint.to_text()).unwrap();
+
+        assert_eq!(result, checkpoint);
+    }
+
+    #[test]
+    pub fn from_text_reports_line_numbers() {
+        let text = "max_depth = 3\nvisited = 0\nresults =\nstack = a?";
+
+        let result = DFSCheckpoint::from_text(text);
+
+        assert!(matches!(result, Err(CheckpointError::Syntax(4, _))));
+    }
+
+    #[test]
+    pub fn from_text_with_missing_key_returns_error() {
+        let result = DFSCheckpoint::from_text("max_depth = 3\nresults = 0");
+
+        assert!(matches!(result, Err(CheckpointError::Missing("visited"))));
+    }
+
+    #[test]
+    pub fn dfs_checkpointed_resumes_after_interruption() -> Result<(), CheckpointError> {
+        let ifs = make_ifs();
+        let path = env::temp_dir().join(format!("dfs_checkpoint_{}.txt", std::process::id()));
+        let max_depth = 4;
+
+        let mut expected = vec![0.0; max_depth + 1];
+        for (address, xform) in ifs.dfs(max_depth) {
+            count_lengths(&address, &xform, &mut expected);
+        }
+
+        // Stop after 20 visits, then resume from the file
+        let mut checkpoint = DFSCheckpoint::new(max_depth, vec![0.0; max_depth + 1]);
+        let finished = ifs.dfs_checkpointed(&mut checkpoint, &path, 3, |a, x, r| {
+            count_lengths(a, x, r);
+            r.iter().sum::<f64>() < 20.0
+        })?;
+        let mut resumed = DFSCheckpoint::load_or_new(&path, max_depth, vec![])?;
+        let resumed_finished = ifs.dfs_checkpointed(&mut resumed, &path, 3, count_lengths)?;
+        fs::remove_file(&path)?;
+
+        assert!(!finished);
+        assert_eq!(resumed.visited, ifs.dfs(max_depth).count());
+        assert!(resumed_finished);
+        assert_eq!(resumed.results, expected);
+        Ok(())
+    }
+}
@@ -0,0 +1,368 @@
+use crate::{
+    geometry::Circle,
+    polygon::Polygon,
+    transformable::{Cline, ClineTile},
+    winding::FillRule,
+    Complex,
+};
+
+use super::TangencyGraph;
+
+/// Tangencies are detected within this fraction of the size of the region.
+/// Each circle is grown until it touches something, so the circle it
+/// touches is tangent up to rounding error.
+const TANGENCY_TOLERANCE: f64 = 1e-9;
+
+/// Region to fill with circles, e.g. the fundamental domain of a group
+#[derive(Clone, Debug)]
+pub enum PackingRegion {
+    Circle(Circle),
+    /// A polygon that doesn't intersect itself
+    Polygon(Polygon),
+}
+
+impl PackingRegion {
+    /// Corners (min, max) of the bounding box
+    fn bounds(&self) -> (Complex, Complex) {
+        match self {
+            Self::Circle(Circle { center, radius }) => {
+                let corner = Complex::new(*radius, *radius);
+                (*center - corner, *center + corner)
+            }
+            Self::Polygon(polygon) => {
+                let vertices = polygon.vertices();
+                let fold = |f: fn(f64, f64) -> f64, part: fn(&Complex) -> f64| {
+                    vertices.iter().map(part).reduce(f).unwrap()
+                };
+                (
+                    Complex::new(fold(f64::min, Complex::real), fold(f64::min, Complex::imag)),
+                    Complex::new(fold(f64::max, Complex::real), fold(f64::max, Complex::imag)),
+                )
+            }
+        }
+    }
+
+    /// Distance from z to the boundary, or None if z is outside
+    fn clearance(&self, z: Complex) -> Option<f64> {
+        match self {
+            Self::Circle(circle) => {
+                let clearance = circle.radius - (z - circle.center).mag();
+                (clearance > 0.0).then_some(clearance)
+            }
+            Self::Polygon(polygon) => polygon.contains(z, FillRule::NonZero).then(|| {
+                polygon
+                    .edges()
+                    .map(|edge| {
+                        let direction = edge.end - edge.start;
+                        let t = (Complex::dot(z - edge.start, direction) / direction.norm())
+                            .clamp(0.0, 1.0);
+                        (z - (edge.start + direction * t.into())).mag()
+                    })
+                    .fold(f64::INFINITY, f64::min)
+            }),
+        }
+    }
+}
+
+/// Settings for pack_circles()
+#[derive(Clone, Copy, Debug)]
+pub struct PackingOptions {
+    /// Stop once no circle at least this big fits
+    pub min_radius: f64,
+    /// Circles are never bigger than this, even if there is room. Without
+    /// this, packing a circle would just return the circle itself
+    pub max_radius: f64,
+    /// Stop after placing this many circles
+    pub max_circles: usize,
+    /// Size of the grid used to search for centers. The grid is as fine as
+    /// possible without going over this many points, so bigger values are
+    /// more accurate but slower
+    pub max_samples: usize,
+}
+
+/// Non-overlapping circles inside a region, along with which circles touch
+/// each other and which touch the boundary of the region
+pub struct CirclePacking {
+    graph: TangencyGraph,
+    boundary_contacts: Vec<usize>,
+}
+
+impl CirclePacking {
+    /// The circles in the order they were placed, which is from largest
+    /// to smallest
+    pub fn circles(&self) -> &[Circle] {
+        self.graph.circles()
+    }
+
+    /// Which circles touch each other
+    pub fn graph(&self) -> &TangencyGraph {
+        &self.graph
+    }
+
+    /// Indices of the circles that touch the boundary of the region
+    pub fn boundary_contacts(&self) -> &[usize] {
+        &self.boundary_contacts
+    }
+
+    pub fn to_tile(&self) -> ClineTile {
+        ClineTile::new(self.circles().iter().map(|x| Cline::from(*x)).collect())
+    }
+}
+
+/// Greedily pack circles into a region. Each step places the biggest
+/// circle that fits in the remaining space, centered at the point
+/// furthest from the boundary and the circles so far. Since each circle
+/// grows until it touches something, the gaps between circles fill up
+/// with smaller and smaller circles, much like an Apollonian gasket.
+///
+/// The centers are found by sampling the bounding box of the region on a
+/// grid of at most max_samples points, so each circle is within about one
+/// grid spacing of the biggest possible one.
+pub fn pack_circles(
+    region: &PackingRegion,
+    options: PackingOptions,
+) -> Result<CirclePacking, String> {
+    let PackingOptions {
+        min_radius,
+        max_radius,
+        max_circles,
+        max_samples,
+    } = options;
+    if !(min_radius > 0.0 && min_radius.is_finite()) {
+        return Err(format!("min radius must be positive, got {}", min_radius));
+    }
+    if max_radius < min_radius {
+        return Err(String::from("max radius must be at least the min radius"));
+    }
+
+    if max_samples == 0 {
+        return Err(String::from("max samples must be positive"));
+    }
+
+    let (min, max) = region.bounds();
+    let size = max - min;
+    let samples = sample_grid(min, max - min, max_samples)?;
+    // Distance from each sample to the nearest boundary or circle, or None
+    // for samples outside the region or inside a circle
+    let mut clearance: Vec<Option<f64>> = samples.iter().map(|z| region.clearance(*z)).collect();
+
+    let mut circles = Vec::new();
+    while circles.len() < max_circles {
+        let Some((index, radius)) = clearance
+            .iter()
+            .enumerate()
+            .filter_map(|(i, x)| x.map(|x| (i, x)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+        else {
+            break;
+        };
+        if radius < min_radius {
+            break;
+        }
+
+        let circle = Circle::new(samples[index], radius.min(max_radius));
+        for (z, x) in samples.iter().zip(clearance.iter_mut()) {
+            if let Some(distance) = x {
+                let gap = (*z - circle.center).mag() - circle.radius;
+                *x = (gap > 0.0).then_some(distance.min(gap));
+            }
+        }
+        circles.push(circle);
+    }
+
+    let tolerance = TANGENCY_TOLERANCE * size.mag();
+    let boundary_contacts = circles
+        .iter()
+        .enumerate()
+        .filter(|(_, circle)| {
+            region
+                .clearance(circle.center)
+                .is_some_and(|x| x - circle.radius < tolerance)
+        })
+        .map(|(i, _)| i)
+        .collect();
+    let graph = TangencyGraph::new(&circles, tolerance)?;
+
+    Ok(CirclePacking {
+        graph,
+        boundary_contacts,
+    })
+}
+
+/// The finest square grid over the box from min to min + size with at
+/// most max_samples points, centered in the box. With n = 1 / spacing, the
+/// grid has (width * n + 1) * (height * n + 1) points, so n is the positive
+/// root of that quadratic equal to max_samples
+fn sample_grid(min: Complex, size: Complex, max_samples: usize) -> Result<Vec<Complex>, String> {
+    let (width, height) = (size.real(), size.imag());
+    let area = width * height;
+    if !(area > 0.0 && area.is_finite()) {
+        return Err(format!("region must have positive area, got {}", area));
+    }
+
+    let perimeter = width + height;
+    let discriminant = perimeter * perimeter + 4.0 * area * (max_samples as f64 - 1.0);
+    let density = (discriminant.sqrt() - perimeter) / (2.0 * area);
+    let cols = (width * density).floor() as usize + 1;
+    let rows = (height * density).floor() as usize + 1;
+    // A single sample has no spacing, it just sits in the middle
+    let spacing = if density > 0.0 { 1.0 / density } else { 0.0 };
+
+    let corner = min
+        + Complex::new(
+            0.5 * (width - (cols - 1) as f64 * spacing),
+            0.5 * (height - (rows - 1) as f64 * spacing),
+        );
+    Ok((0..rows)
+        .flat_map(|row| {
+            (0..cols)
+                .map(move |col| corner + Complex::new(col as f64 * spacing, row as f64 * spacing))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn square() -> PackingRegion {
+        PackingRegion::Polygon(
+            Polygon::new(vec![
+                Complex::new(-1.0, -1.0),
+                Complex::new(1.0, -1.0),
+                Complex::new(1.0, 1.0),
+                Complex::new(-1.0, 1.0),
+            ])
+            .unwrap(),
+        )
+    }
+
+    fn options(max_radius: f64) -> PackingOptions {
+        PackingOptions {
+            min_radius: 0.05,
+            max_radius,
+            max_circles: 50,
+            max_samples: 10_000,
+        }
+    }
+
+    fn assert_valid_packing(region: &PackingRegion, packing: &CirclePacking) {
+        let circles = packing.circles();
+        for (i, a) in circles.iter().enumerate() {
+            let clearance = region.clearance(a.center).unwrap();
+            assert!(clearance >= a.radius - 1e-12, "circle {} leaves region", i);
+
+            for b in &circles[i + 1..] {
+                let distance = (a.center - b.center).mag();
+                assert!(distance >= a.radius + b.radius - 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    pub fn pack_circles_with_bad_radius_returns_error() {
+        let result = pack_circles(&square(), options(0.01));
+
+        assert!(result.is_err_and(|e| e.contains("max radius")));
+    }
+
+    #[test]
+    pub fn pack_circles_in_square_starts_with_incircle() {
+        let region = square();
+
+        let result = pack_circles(&region, options(10.0)).unwrap();
+
+        let first = result.circles()[0];
+        assert!((first.radius - 1.0).abs() < 0.05);
+        assert!(result.circles().len() > 4);
+        assert!(result.boundary_contacts().contains(&0));
+        assert_valid_packing(&region, &result);
+    }
+
+    #[test]
+    pub fn pack_circles_in_circle_records_tangencies() {
+        let region = PackingRegion::Circle(Circle::unit_circle());
+
+        let result = pack_circles(&region, options(0.5)).unwrap();
+
+        // The grid is centered on the region, so the first circle is
+        // within one grid spacing of the center
+        assert!(result.circles()[0].center.mag() < 0.02);
+        assert_eq!(result.circles()[0].radius, 0.5);
+        assert!(!result.graph().edges().is_empty());
+        // Every circle after the first touches the boundary or another
+        // circle, since it grows until it touches something
+        for i in 1..result.circles().len() {
+            assert!(
+                result.boundary_contacts().contains(&i) || !result.graph().neighbors(i).is_empty()
+            );
+        }
+        assert_valid_packing(&region, &result);
+    }
+
+    #[test]
+    pub fn pack_circles_stops_at_min_radius() {
+        let region = square();
+        let options = PackingOptions {
+            max_circles: 10_000,
+            ..options(10.0)
+        };
+
+        let result = pack_circles(&region, options).unwrap();
+
+        assert!(result.circles().len() < 10_000);
+        assert!(result.circles().iter().all(|x| x.radius >= 0.05));
+    }
+
+    #[test]
+    pub fn pack_circles_allows_min_radius_below_grid_spacing() {
+        let region = square();
+        let options = PackingOptions {
+            min_radius: 1e-6,
+            max_samples: 100,
+            ..options(10.0)
+        };
+
+        let result = pack_circles(&region, options).unwrap();
+
+        // Packing stops once every sample is covered rather than failing
+        // because the grid is coarser than min_radius
+        assert!(!result.circles().is_empty());
+        assert!(result.circles().len() < 50);
+        assert_valid_packing(&region, &result);
+    }
+
+    #[test]
+    pub fn pack_circles_with_no_samples_returns_error() {
+        let options = PackingOptions {
+            max_samples: 0,
+            ..options(10.0)
+        };
+
+        let result = pack_circles(&square(), options);
+
+        assert!(result.is_err_and(|e| e.contains("max samples")));
+    }
+
+    #[test]
+    pub fn sample_grid_stays_within_max_samples() {
+        let min = Complex::new(-1.0, 2.0);
+        for (size, max_samples) in [
+            (Complex::new(2.0, 2.0), 10_000),
+            (Complex::new(3.0, 0.1), 500),
+            (Complex::new(1.0, 1.0), 1),
+        ] {
+            let samples = sample_grid(min, size, max_samples).unwrap();
+
+            assert!(!samples.is_empty() && samples.len() <= max_samples);
+            let center = samples.iter().fold(Complex::Zero, |acc, z| acc + *z)
+                / (samples.len() as f64).into();
+            assert!((center - (min + size * 0.5.into())).mag() < 1e-12);
+            for z in samples {
+                let offset = z - min;
+                assert!((0.0..=size.real()).contains(&offset.real()));
+                assert!((0.0..=size.imag()).contains(&offset.imag()));
+            }
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use crate::Complex;
+
+/// Square grid of hit counts over a region of the plane. Plotting many
+/// points from the chaos game into the grid estimates the invariant
+/// measure of the IFS, i.e. how often the orbit visits each part of the
+/// attractor.
+///
+/// Rows go from the top of the region (largest imaginary part) to the
+/// bottom, so the counts can be written directly as an image.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DensityGrid {
+    center: Complex,
+    half_width: f64,
+    resolution: usize,
+    counts: Vec<u32>,
+    total: u64,
+}
+
+impl DensityGrid {
+    /// Create an empty grid covering the square of the given half width
+    /// around center, with resolution x resolution cells
+    pub fn new(center: Complex, half_width: f64, resolution: usize) -> Result<Self, String> {
+        if center == Complex::Infinity {
+            return Err(String::from("center must be finite"));
+        }
+
+        if !(half_width > 0.0 && half_width.is_finite()) {
+            return Err(format!("half width must be positive, got {}", half_width));
+        }
+
+        if resolution == 0 {
+            return Err(String::from("resolution must be at least 1"));
+        }
+
+        Ok(Self {
+            center,
+            half_width,
+            resolution,
+            counts: vec![0; resolution * resolution],
+            total: 0,
+        })
+    }
+
+    pub fn resolution(&self) -> usize {
+        self.resolution
+    }
+
+    /// Width of a single cell
+    pub fn cell_size(&self) -> f64 {
+        2.0 * self.half_width / self.resolution as f64
+    }
+
+    /// The (row, col) of the cell containing z, or None if z is outside
+    /// the grid
+    pub fn cell_of(&self, z: Complex) -> Option<(usize, usize)> {
+        if z == Complex::Infinity {
+            return None;
+        }
+
+        let offset = z - self.center;
+        let to_index = |x: f64| ((x + self.half_width) / self.cell_size()).floor();
+        let col = to_index(offset.real());
+        let row = to_index(-offset.imag());
+
+        let n = self.resolution as f64;
+        if (0.0..n).contains(&col) && (0.0..n).contains(&row) {
+            Some((row as usize, col as usize))
+        } else {
+            None
+        }
+    }
+
+    /// The center of the cell at (row, col)
+    pub fn cell_center(&self, row: usize, col: usize) -> Complex {
+        let size = self.cell_size();
+        let x = -self.half_width + (col as f64 + 0.5) * size;
+        let y = self.half_width - (row as f64 + 0.5) * size;
+        self.center + Complex::new(x, y)
+    }
+
+    /// Count a point. Returns false if the point was outside the grid,
+    /// in which case it is not counted in the total either
+    pub fn add_point(&mut self, z: Complex) -> bool {
+        match self.cell_of(z) {
+            Some((row, col)) => {
+                let count = &mut self.counts[row * self.resolution + col];
+                *count = count.saturating_add(1);
+                self.total += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn add_points(&mut self, points: impl IntoIterator<Item = Complex>) {
+        for z in points {
+            self.add_point(z);
+        }
+    }
+
+    pub fn count(&self, row: usize, col: usize) -> u32 {
+        self.counts[row * self.resolution + col]
+    }
+
+    pub fn max_count(&self) -> u32 {
+        self.counts.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Total number of points that landed in the grid
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// The fraction of points in each cell, as a matrix of rows. The
+    /// entries sum to 1 unless the grid is empty
+    pub fn to_matrix(&self) -> Vec<Vec<f64>> {
+        let total = self.total.max(1) as f64;
+        self.counts
+            .chunks(self.resolution)
+            .map(|row| row.iter().map(|x| *x as f64 / total).collect())
+            .collect()
+    }
+
+    /// Counts scaled to [0, 1] with log(1 + count) / log(1 + max). Hit
+    /// counts of attractors span many orders of magnitude, so a log scale
+    /// shows the fine structure better than the raw fractions
+    pub fn log_scaled(&self) -> Vec<Vec<f64>> {
+        let max = (self.max_count() as f64).ln_1p().max(f64::MIN_POSITIVE);
+        self.counts
+            .chunks(self.resolution)
+            .map(|row| row.iter().map(|x| (*x as f64).ln_1p() / max).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn new_with_zero_resolution_returns_error() {
+        let result = DensityGrid::new(Complex::Zero, 1.0, 0);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn cell_of_puts_top_left_in_first_row() {
+        let grid = DensityGrid::new(Complex::Zero, 1.0, 4).unwrap();
+
+        assert_eq!(grid.cell_of(Complex::new(-0.9, 0.9)), Some((0, 0)));
+        assert_eq!(grid.cell_of(Complex::new(0.9, -0.9)), Some((3, 3)));
+        assert_eq!(grid.cell_of(Complex::new(1.5, 0.0)), None);
+        assert_eq!(grid.cell_of(Complex::Infinity), None);
+    }
+
+    #[test]
+    pub fn cell_center_is_inside_its_cell() {
+        let grid = DensityGrid::new(Complex::new(2.0, 1.0), 0.5, 5).unwrap();
+
+        for row in 0..5 {
+            for col in 0..5 {
+                assert_eq!(grid.cell_of(grid.cell_center(row, col)), Some((row, col)));
+            }
+        }
+    }
+
+    #[test]
+    pub fn add_point_skips_points_outside() {
+        let mut grid = DensityGrid::new(Complex::Zero, 1.0, 2).unwrap();
+
+        grid.add_points([
+            Complex::new(0.5, 0.5),
+            Complex::new(0.5, 0.5),
+            Complex::new(-0.5, -0.5),
+            Complex::new(5.0, 0.0),
+        ]);
+
+        assert_eq!(grid.total(), 3);
+        assert_eq!(grid.count(0, 1), 2);
+        assert_eq!(grid.count(1, 0), 1);
+        assert_eq!(grid.max_count(), 2);
+    }
+
+    #[test]
+    pub fn to_matrix_sums_to_one() {
+        let mut grid = DensityGrid::new(Complex::Zero, 1.0, 3).unwrap();
+        grid.add_points([Complex::Zero, Complex::new(0.9, 0.9), Complex::Zero]);
+
+        let result = grid.to_matrix();
+
+        let sum: f64 = result.iter().flatten().sum();
+        assert!((sum - 1.0).abs() < 1e-12);
+        assert_eq!(result[1][1], 2.0 / 3.0);
+    }
+
+    #[test]
+    pub fn log_scaled_maps_max_to_one() {
+        let mut grid = DensityGrid::new(Complex::Zero, 1.0, 2).unwrap();
+        grid.add_points([Complex::new(0.5, 0.5), Complex::new(0.5, 0.5)]);
+
+        let result = grid.log_scaled();
+
+        assert_eq!(result[0][1], 1.0);
+        assert_eq!(result[1][0], 0.0);
+    }
+}
@@ -0,0 +1,281 @@
+use std::{io, path::Path};
+
+use abstraction::GroupAction;
+
+use crate::{
+    rendering::{ColorRGB, Palette},
+    Complex, Mobius,
+};
+
+use super::Raster;
+
+/// Escape-time iteration of the quadratic family q_c(z) = z^2 + c,
+/// optionally conjugated by a Möbius transformation M so the map becomes
+/// M q_c M^-1. Conjugating moves the escaping fixed point from infinity to
+/// M(infinity), so the familiar Julia sets come out inverted, bent around
+/// a circle, etc.
+///
+/// Iterating the conjugated map from z is the same as iterating q_c from
+/// M^-1(z) and mapping back, so counts are computed in the unconjugated
+/// coordinates where the bailout test is simple.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EscapeTime {
+    conjugator: Mobius,
+    conjugator_inverse: Mobius,
+    max_iterations: u32,
+    bailout: f64,
+}
+
+impl EscapeTime {
+    /// A point escapes once |z| > bailout. For the quadratic family any
+    /// bailout of at least 2 is safe
+    pub fn new(max_iterations: u32, bailout: f64) -> Result<Self, String> {
+        if max_iterations == 0 {
+            return Err(String::from("max_iterations must be at least 1"));
+        }
+
+        if !(bailout >= 2.0 && bailout.is_finite()) {
+            return Err(format!("bailout must be at least 2, got {}", bailout));
+        }
+
+        Ok(Self {
+            conjugator: Mobius::IDENTITY,
+            conjugator_inverse: Mobius::IDENTITY,
+            max_iterations,
+            bailout,
+        })
+    }
+
+    pub fn with_conjugator(self, conjugator: Mobius) -> Self {
+        Self {
+            conjugator,
+            conjugator_inverse: conjugator.inverse(),
+            ..self
+        }
+    }
+
+    pub fn conjugator(&self) -> Mobius {
+        self.conjugator
+    }
+
+    pub fn max_iterations(&self) -> u32 {
+        self.max_iterations
+    }
+
+    /// One step of the conjugated map M q_c M^-1
+    pub fn step(&self, c: Complex, z: Complex) -> Complex {
+        let w = self.conjugator_inverse.act(&z);
+        self.conjugator.act(&(w * w + c))
+    }
+
+    /// Number of steps of q_c before w escapes, or None if it is still
+    /// bounded after max_iterations steps
+    fn count(&self, c: Complex, mut w: Complex) -> Option<u32> {
+        let bailout_norm = self.bailout * self.bailout;
+        for i in 0..self.max_iterations {
+            if w == Complex::Infinity || w.norm() > bailout_norm {
+                return Some(i);
+            }
+            w = w * w + c;
+        }
+        None
+    }
+
+    /// Escape time of z under the conjugated map, the filled Julia set is
+    /// where this returns None
+    pub fn julia_count(&self, c: Complex, z: Complex) -> Option<u32> {
+        self.count(c, self.conjugator_inverse.act(&z))
+    }
+
+    /// Escape time of the critical point for the parameter at pixel. The
+    /// conjugator warps the parameter plane, e.g. z -> 1/z gives the
+    /// inverted Mandelbrot set
+    pub fn mandelbrot_count(&self, pixel: Complex) -> Option<u32> {
+        let c = self.conjugator_inverse.act(&pixel);
+        if c == Complex::Infinity {
+            return Some(0);
+        }
+        self.count(c, Complex::Zero)
+    }
+
+    /// Escape times of a Julia set over a square region of the plane
+    pub fn julia(
+        &self,
+        c: Complex,
+        center: Complex,
+        half_width: f64,
+        resolution: usize,
+    ) -> Result<EscapeRaster, String> {
+        EscapeRaster::compute(center, half_width, resolution, self.max_iterations, |z| {
+            self.julia_count(c, z)
+        })
+    }
+
+    /// Escape times of the Mandelbrot set over a square region of the
+    /// parameter plane
+    pub fn mandelbrot(
+        &self,
+        center: Complex,
+        half_width: f64,
+        resolution: usize,
+    ) -> Result<EscapeRaster, String> {
+        EscapeRaster::compute(center, half_width, resolution, self.max_iterations, |z| {
+            self.mandelbrot_count(z)
+        })
+    }
+}
+
+/// Escape times, one per pixel, None where the orbit stayed bounded
+#[derive(Clone, Debug, PartialEq)]
+pub struct EscapeRaster {
+    max_iterations: u32,
+    counts: Raster<Option<u32>>,
+}
+
+impl EscapeRaster {
+    fn compute(
+        center: Complex,
+        half_width: f64,
+        resolution: usize,
+        max_iterations: u32,
+        count: impl Fn(Complex) -> Option<u32>,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            max_iterations,
+            counts: Raster::compute(center, half_width, resolution, count)?,
+        })
+    }
+
+    /// The underlying grid of escape times
+    pub fn raster(&self) -> &Raster<Option<u32>> {
+        &self.counts
+    }
+
+    pub fn resolution(&self) -> usize {
+        self.counts.resolution()
+    }
+
+    pub fn pixel_size(&self) -> f64 {
+        self.counts.pixel_size()
+    }
+
+    /// The point sampled for the pixel at (row, col)
+    pub fn pixel_center(&self, row: usize, col: usize) -> Complex {
+        self.counts.pixel_center(row, col)
+    }
+
+    /// Escape time of the pixel at (row, col), None if it never escaped
+    pub fn count(&self, row: usize, col: usize) -> Option<u32> {
+        *self.counts.get(row, col)
+    }
+
+    fn color(&self, count: Option<u32>, palette: &Palette, interior: ColorRGB) -> ColorRGB {
+        match count {
+            Some(n) => palette.sample(n as f64 / self.max_iterations as f64),
+            None => interior,
+        }
+    }
+
+    /// Color every pixel, escaping pixels from the palette by count /
+    /// max_iterations and bounded pixels with the interior color. The
+    /// result is in row-major order.
+    pub fn to_colors(&self, palette: &Palette, interior: ColorRGB) -> Vec<ColorRGB> {
+        self.counts
+            .pixels()
+            .iter()
+            .map(|count| self.color(*count, palette, interior))
+            .collect()
+    }
+
+    /// Write the raster as a PPM image colored like to_colors()
+    pub fn write_ppm<P: AsRef<Path>>(
+        &self,
+        path: P,
+        palette: &Palette,
+        interior: ColorRGB,
+    ) -> io::Result<()> {
+        self.counts
+            .write_ppm(path, |count| self.color(*count, palette, interior))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn new_with_small_bailout_returns_error() {
+        let result = EscapeTime::new(100, 1.5);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn mandelbrot_count_bounded_at_origin() {
+        let escape = EscapeTime::new(100, 2.0).unwrap();
+
+        assert_eq!(escape.mandelbrot_count(Complex::Zero), None);
+        assert_eq!(escape.mandelbrot_count(Complex::new(-1.0, 0.0)), None);
+        assert_eq!(escape.mandelbrot_count(Complex::new(3.0, 0.0)), Some(1));
+    }
+
+    #[test]
+    pub fn julia_count_with_zero_c_escapes_outside_unit_circle() {
+        let escape = EscapeTime::new(50, 2.0).unwrap();
+
+        assert_eq!(
+            escape.julia_count(Complex::Zero, Complex::new(0.5, 0.5)),
+            None
+        );
+        assert!(escape
+            .julia_count(Complex::Zero, Complex::new(1.1, 0.0))
+            .is_some());
+    }
+
+    #[test]
+    pub fn conjugated_julia_count_matches_preimage() {
+        let conjugator = Mobius::new(Complex::Zero, Complex::I, Complex::I, Complex::Zero).unwrap();
+        let escape = EscapeTime::new(50, 2.0).unwrap();
+        let conjugated = escape.with_conjugator(conjugator);
+        let c = Complex::new(-0.5, 0.3);
+        let z = Complex::new(0.3, -0.8);
+
+        let result = conjugated.julia_count(c, conjugator.act(&z));
+
+        assert_eq!(result, escape.julia_count(c, z));
+    }
+
+    #[test]
+    pub fn step_with_identity_is_quadratic() {
+        let escape = EscapeTime::new(10, 2.0).unwrap();
+
+        let result = escape.step(Complex::ONE, Complex::I);
+
+        assert_eq!(result, Complex::Zero);
+    }
+
+    #[test]
+    pub fn mandelbrot_raster_has_interior_at_center() {
+        let escape = EscapeTime::new(50, 2.0).unwrap();
+
+        let raster = escape.mandelbrot(Complex::new(-0.5, 0.0), 1.5, 5).unwrap();
+
+        assert_eq!(raster.count(2, 2), None);
+        assert!(raster.count(0, 0).is_some());
+    }
+
+    #[test]
+    pub fn to_colors_uses_interior_for_bounded_pixels() {
+        let escape = EscapeTime::new(20, 2.0).unwrap();
+        let raster = escape.julia(Complex::Zero, Complex::Zero, 2.0, 4).unwrap();
+        let palette = Palette::new(vec![ColorRGB(255, 255, 255)]).unwrap();
+        let interior = ColorRGB(0, 0, 0);
+
+        let result = raster.to_colors(&palette, interior);
+
+        assert_eq!(result.len(), 16);
+        // The middle 4 pixels are inside the unit disk
+        assert_eq!(result[5], interior);
+        assert_eq!(result[0], ColorRGB(255, 255, 255));
+    }
+}
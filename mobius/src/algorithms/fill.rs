@@ -0,0 +1,440 @@
+//! Plotter-friendly fills. Pen plotters can only draw strokes, so filled
+//! regions are converted to hatch lines or stipple dots instead. Regions
+//! use the even-odd rule from the arc_boolean module, so holes (like the
+//! ghost's eyes) are left empty.
+use std::f64::consts::{FRAC_PI_2, SQRT_2, TAU};
+
+use crate::{
+    cline_arc::ClineArcGeometry,
+    geometry::LineSegment,
+    polygon::Polygon,
+    random::SplitMix64,
+    transformable::{ClineArcTile, PointCloud},
+    winding::FillRule,
+    Complex,
+};
+
+use super::arc_boolean::{inside, line_crossings, tile_geometry};
+
+/// Most grid cells stipple() will use, to catch spacings that are far too
+/// small for the region
+const MAX_CELLS: usize = 4_000_000;
+
+/// Hatch lines shorter than this are dropped
+const MIN_LENGTH: f64 = 1e-9;
+
+/// Number of candidates tried around each point before giving up on it,
+/// the usual choice for Bridson's algorithm
+const CANDIDATES: usize = 30;
+
+/// How to fill a region with strokes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FillPattern {
+    /// Parallel lines at the given angle (radians) and spacing
+    Hatch { angle: f64, spacing: f64 },
+    /// Two sets of hatch lines at right angles
+    CrossHatch { angle: f64, spacing: f64 },
+}
+
+/// Boundary of a region to fill, with its bounding box
+struct Region {
+    boundary: Vec<ClineArcGeometry>,
+    min: Complex,
+    max: Complex,
+}
+
+impl Region {
+    fn new(boundary: Vec<ClineArcGeometry>) -> Result<Self, String> {
+        let corners: Vec<Complex> = boundary
+            .iter()
+            .flat_map(|geometry| match geometry {
+                ClineArcGeometry::LineSegment(LineSegment { start, end }) => vec![*start, *end],
+                // The circle's bounding box is a little loose for arcs,
+                // but that only costs a few empty scan lines
+                ClineArcGeometry::CircularArc(arc) => {
+                    let offset = Complex::new(arc.circle.radius, arc.circle.radius);
+                    vec![arc.circle.center - offset, arc.circle.center + offset]
+                }
+                _ => unreachable!("only finite geometry is used"),
+            })
+            .collect();
+        if corners.is_empty() {
+            return Err(String::from("region has no boundary"));
+        }
+
+        let fold = |f: fn(f64, f64) -> f64| {
+            corners.iter().skip(1).fold(corners[0], |acc, z| {
+                Complex::new(f(acc.real(), z.real()), f(acc.imag(), z.imag()))
+            })
+        };
+        let min = fold(f64::min);
+        let max = fold(f64::max);
+
+        Ok(Self { boundary, min, max })
+    }
+
+    fn from_polygon(polygon: &Polygon) -> Result<Self, String> {
+        Self::new(polygon.edges().map(ClineArcGeometry::LineSegment).collect())
+    }
+
+    fn from_tile(tile: &ClineArcTile) -> Result<Self, String> {
+        Self::new(tile_geometry(tile)?)
+    }
+
+    fn hatch(&self, angle: f64, spacing: f64) -> Result<Vec<LineSegment>, String> {
+        if !(spacing > 0.0 && spacing.is_finite()) {
+            return Err(format!("spacing must be positive, got {}", spacing));
+        }
+
+        // Lines are z = offset * normal + s * direction. Find the range of
+        // offsets that reach the bounding box.
+        let direction = Complex::from_polar(1.0, angle);
+        let normal = Complex::I * direction;
+        let corner_offsets: Vec<f64> = [
+            self.min,
+            self.max,
+            Complex::new(self.min.real(), self.max.imag()),
+            Complex::new(self.max.real(), self.min.imag()),
+        ]
+        .iter()
+        .map(|z| Complex::dot(*z, normal))
+        .collect();
+        let low = corner_offsets.iter().copied().fold(f64::INFINITY, f64::min);
+        let high = corner_offsets
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        let first = (low / spacing).ceil() as i64;
+        let last = (high / spacing).floor() as i64;
+        if (last - first).max(0) as usize > MAX_CELLS {
+            return Err(format!("spacing {} is too small for the region", spacing));
+        }
+
+        let mut segments = Vec::new();
+        for k in first..=last {
+            let start = normal * (k as f64 * spacing).into();
+            let mut crossings = line_crossings(&self.boundary, start, direction);
+            crossings.sort_by(f64::total_cmp);
+
+            // Alternate between entering and leaving the region. A stray
+            // odd crossing (e.g. a tangent line) leaves the last one out,
+            // and lines that just graze a corner are skipped
+            for pair in crossings.chunks_exact(2) {
+                if pair[1] - pair[0] > MIN_LENGTH {
+                    segments.push(LineSegment::new(
+                        start + direction * pair[0].into(),
+                        start + direction * pair[1].into(),
+                    ));
+                }
+            }
+        }
+
+        Ok(segments)
+    }
+
+    fn fill(&self, pattern: FillPattern) -> Result<ClineArcTile, String> {
+        let segments = match pattern {
+            FillPattern::Hatch { angle, spacing } => self.hatch(angle, spacing)?,
+            FillPattern::CrossHatch { angle, spacing } => {
+                let mut segments = self.hatch(angle, spacing)?;
+                segments.extend(self.hatch(angle + FRAC_PI_2, spacing)?);
+                segments
+            }
+        };
+
+        Ok(ClineArcTile::new(
+            segments.into_iter().map(|x| x.into()).collect(),
+        ))
+    }
+
+    /// Blue noise points with Bridson's Poisson disk sampling: no two
+    /// points are closer than spacing, and the gaps are filled so no point
+    /// of the region is much further than 2 * spacing from a sample.
+    /// Bridson's method only grows outward from a seed point, so when it
+    /// runs out of room, the grid is scanned for a new seed. This way
+    /// every piece of a disconnected region gets points.
+    fn stipple(&self, spacing: f64, seed: u64) -> Result<PointCloud, String> {
+        if !(spacing > 0.0 && spacing.is_finite()) {
+            return Err(format!("spacing must be positive, got {}", spacing));
+        }
+
+        // Cells are small enough that each holds at most one point
+        let cell_size = spacing / SQRT_2;
+        let size = self.max - self.min;
+        let cols = (size.real() / cell_size).ceil() as usize + 1;
+        let rows = (size.imag() / cell_size).ceil() as usize + 1;
+        if rows.saturating_mul(cols) > MAX_CELLS {
+            return Err(format!("spacing {} is too small for the region", spacing));
+        }
+
+        let cell_of = |z: Complex| {
+            let offset = z - self.min;
+            let col = (offset.real() / cell_size).floor();
+            let row = (offset.imag() / cell_size).floor();
+            let in_grid = (0.0..cols as f64).contains(&col) && (0.0..rows as f64).contains(&row);
+            in_grid.then_some((row as usize, col as usize))
+        };
+
+        let mut rng = SplitMix64::new(seed);
+        let mut grid: Vec<Option<Complex>> = vec![None; rows * cols];
+        let mut points: Vec<Complex> = Vec::new();
+
+        let is_free = |grid: &[Option<Complex>], z: Complex| -> bool {
+            let Some((row, col)) = cell_of(z) else {
+                return false;
+            };
+            if !inside(&self.boundary, z, FillRule::EvenOdd) {
+                return false;
+            }
+
+            (row.saturating_sub(2)..(row + 3).min(rows)).all(|r| {
+                (col.saturating_sub(2)..(col + 3).min(cols)).all(|c| match grid[r * cols + c] {
+                    Some(p) => (p - z).mag() >= spacing,
+                    None => true,
+                })
+            })
+        };
+
+        let mut active: Vec<Complex> = Vec::new();
+        let mut scan_index = 0;
+        loop {
+            while !active.is_empty() {
+                let index = rng.next_index(active.len());
+                let center = active[index];
+
+                let candidate = (0..CANDIDATES)
+                    .map(|_| {
+                        let radius = spacing * (1.0 + rng.next_f64());
+                        let angle = TAU * rng.next_f64();
+                        center + Complex::from_polar(radius, angle)
+                    })
+                    .find(|z| is_free(&grid, *z));
+
+                match candidate {
+                    Some(z) => {
+                        let (row, col) = cell_of(z).unwrap();
+                        grid[row * cols + col] = Some(z);
+                        points.push(z);
+                        active.push(z);
+                    }
+                    None => {
+                        active.swap_remove(index);
+                    }
+                }
+            }
+
+            // Look for a new seed in the remaining empty cells
+            let next_seed = (scan_index..rows * cols).find_map(|i| {
+                if grid[i].is_some() {
+                    return None;
+                }
+
+                let corner = self.min
+                    + Complex::new((i % cols) as f64 * cell_size, (i / cols) as f64 * cell_size);
+                let jitter = Complex::new(rng.next_f64(), rng.next_f64()) * cell_size.into();
+                let z = corner + jitter;
+                is_free(&grid, z).then_some((i, z))
+            });
+
+            let Some((i, z)) = next_seed else {
+                break;
+            };
+            scan_index = i + 1;
+            let (row, col) = cell_of(z).unwrap();
+            grid[row * cols + col] = Some(z);
+            points.push(z);
+            active.push(z);
+        }
+
+        Ok(PointCloud::new(points))
+    }
+}
+
+/// Fill a polygon with hatch lines, clipped to the polygon
+pub fn hatch_polygon(polygon: &Polygon, pattern: FillPattern) -> Result<ClineArcTile, String> {
+    Region::from_polygon(polygon)?.fill(pattern)
+}
+
+/// Fill the region bounded by a tile with hatch lines, clipped to the
+/// region. The tile's arcs must form closed loops, and must be finite
+pub fn hatch_tile(tile: &ClineArcTile, pattern: FillPattern) -> Result<ClineArcTile, String> {
+    Region::from_tile(tile)?.fill(pattern)
+}
+
+/// Fill a polygon with blue noise stipple dots, at least spacing apart.
+/// The same seed always gives the same dots
+pub fn stipple_polygon(polygon: &Polygon, spacing: f64, seed: u64) -> Result<PointCloud, String> {
+    Region::from_polygon(polygon)?.stipple(spacing, seed)
+}
+
+/// Fill the region bounded by a tile with blue noise stipple dots, see
+/// stipple_polygon()
+pub fn stipple_tile(tile: &ClineArcTile, spacing: f64, seed: u64) -> Result<PointCloud, String> {
+    Region::from_tile(tile)?.stipple(spacing, seed)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{isogonal::Isogonal, motifs::ghost, transformable::Transformable, translation};
+
+    use super::*;
+
+    fn square() -> Polygon {
+        Polygon::new(vec![
+            Complex::Zero,
+            Complex::new(2.0, 0.0),
+            Complex::new(2.0, 2.0),
+            Complex::new(0.0, 2.0),
+        ])
+        .unwrap()
+    }
+
+    fn segments(tile: &ClineArcTile) -> Vec<LineSegment> {
+        tile.get_primitives()
+            .iter()
+            .map(|arc| match arc.classify().unwrap() {
+                ClineArcGeometry::LineSegment(segment) => segment,
+                x => panic!("expected a line segment, got {}", x),
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn hatch_with_zero_spacing_returns_error() {
+        let pattern = FillPattern::Hatch {
+            angle: 0.0,
+            spacing: 0.0,
+        };
+
+        let result = hatch_polygon(&square(), pattern);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn horizontal_hatch_spans_square() {
+        let pattern = FillPattern::Hatch {
+            angle: 0.0,
+            spacing: 0.5,
+        };
+
+        let result = segments(&hatch_polygon(&square(), pattern).unwrap());
+
+        // Lines at y = 0.5, 1.0, 1.5 (and possibly along the edges)
+        for y in [0.5, 1.0, 1.5] {
+            let line = result
+                .iter()
+                .find(|s| (s.start.imag() - y).abs() < 1e-9)
+                .unwrap();
+            assert!((line.start.real() - line.end.real()).abs() > 2.0 - 1e-9);
+        }
+    }
+
+    #[test]
+    pub fn horizontal_hatch_through_vertices_pairs_crossings() {
+        // An M shape, with vertices at every hatch line from y = 1 up
+        let polygon = Polygon::new(vec![
+            Complex::Zero,
+            Complex::new(4.0, 0.0),
+            Complex::new(4.0, 2.0),
+            Complex::new(3.0, 1.0),
+            Complex::new(2.0, 2.0),
+            Complex::new(1.0, 1.0),
+            Complex::new(0.0, 2.0),
+        ])
+        .unwrap();
+        let pattern = FillPattern::Hatch {
+            angle: 0.0,
+            spacing: 0.5,
+        };
+
+        let result = segments(&hatch_polygon(&polygon, pattern).unwrap());
+
+        // The notches touch y = 1 without leaving the polygon, and y = 2
+        // only touches the tips of the M
+        for (y, expected_length) in [(0.5, 4.0), (1.0, 4.0), (1.5, 2.0), (2.0, 0.0)] {
+            let length: f64 = result
+                .iter()
+                .filter(|s| (s.start.imag() - y).abs() < 1e-9)
+                .map(|s| (s.end - s.start).mag())
+                .sum();
+            assert!((length - expected_length).abs() < 1e-9, "y = {}", y);
+        }
+    }
+
+    #[test]
+    pub fn diagonal_hatch_stays_inside_square() {
+        let pattern = FillPattern::CrossHatch {
+            angle: 0.3,
+            spacing: 0.1,
+        };
+
+        let result = segments(&hatch_polygon(&square(), pattern).unwrap());
+
+        assert!(!result.is_empty());
+        let eps = 1e-9;
+        for LineSegment { start, end } in result {
+            for z in [start, end] {
+                assert!((-eps..=2.0 + eps).contains(&z.real()));
+                assert!((-eps..=2.0 + eps).contains(&z.imag()));
+            }
+        }
+    }
+
+    #[test]
+    pub fn hatch_tile_skips_holes() {
+        let (tile, _) = ghost();
+        let pattern = FillPattern::Hatch {
+            angle: 0.0,
+            spacing: 0.1,
+        };
+
+        let result = segments(&hatch_tile(&tile, pattern).unwrap());
+
+        // The line through the middle of the eyes is broken up by them
+        let eye_line: Vec<&LineSegment> = result
+            .iter()
+            .filter(|s| s.start.imag().abs() < 1e-9)
+            .collect();
+        assert_eq!(eye_line.len(), 3);
+    }
+
+    #[test]
+    pub fn stipple_points_are_spaced_and_inside() {
+        let spacing = 0.2;
+
+        let result = stipple_polygon(&square(), spacing, 7).unwrap();
+
+        let points = result.points();
+        assert!(points.len() > 40);
+        for (i, a) in points.iter().enumerate() {
+            assert!((0.0..=2.0).contains(&a.real()) && (0.0..=2.0).contains(&a.imag()));
+            for b in &points[i + 1..] {
+                assert!((*a - *b).mag() >= spacing);
+            }
+        }
+    }
+
+    #[test]
+    pub fn stipple_is_deterministic() {
+        let a = stipple_polygon(&square(), 0.3, 11).unwrap();
+        let b = stipple_polygon(&square(), 0.3, 11).unwrap();
+
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    pub fn stipple_reaches_every_component() {
+        let (tile, _) = ghost();
+        let shift = Isogonal::from(translation(Complex::new(5.0, 0.0)).unwrap());
+        let mut arcs = tile.get_primitives().to_vec();
+        arcs.extend_from_slice(tile.transform(shift).get_primitives());
+        let two_ghosts = ClineArcTile::new(arcs);
+
+        let result = stipple_tile(&two_ghosts, 0.3, 1).unwrap();
+
+        assert!(result.points().iter().any(|z| z.real() < 2.0));
+        assert!(result.points().iter().any(|z| z.real() > 3.0));
+    }
+}
@@ -46,7 +46,7 @@ impl<G: Group> GridIFS<G> {
         self.axes.len()
     }
 
-    pub fn iter(&self) -> GridIFSIterator<G> {
+    pub fn iter(&self) -> GridIFSIterator<'_, G> {
         GridIFSIterator::new(self)
     }
 
@@ -1,20 +1,30 @@
-use std::ops::Index;
+use std::{collections::HashSet, hash::Hash, ops::Index};
 
-use abstraction::{Group, Monoid};
+use abstraction::{Group, GroupAction, Monoid, Search, SearchOrder, SearchSpace};
 
 use crate::{
     address::{FractalAddress, Symbol},
+    invariants::debug_check,
     transformable::Transformable,
+    Complex,
 };
 
+use super::{chaos_game_density, validate_weights, DensityGrid};
+
 /// Iterated function system for a group. The depth-first-search iterator
-/// for this IFS avoids backtracking.
+/// for this IFS avoids backtracking, but it does not know about any other
+/// relations in the group, so the same element can appear many times.
+/// For groups with relations, use bfs_unique() instead.
 pub struct GroupIFS<G: Group> {
     /// List of transformations from the symmetry group.
     /// This is twice the length of the input to store the inverses
     /// explicitly. E.g. if the input was [a, b, c], the IFS will store
     /// [a, b, c, A, B, C]
     xforms: Vec<G>,
+    /// Relative probability of each transformation (including the
+    /// inverses) for the chaos game. None means uniform. These don't
+    /// affect the traversals
+    weights: Option<Vec<f64>>,
 }
 
 impl<G: Group> GroupIFS<G> {
@@ -22,7 +32,25 @@ impl<G: Group> GroupIFS<G> {
         let inverses: Vec<G> = xforms_no_inverses.iter().map(|x| x.inverse()).collect();
         let xforms: Vec<G> = xforms_no_inverses.into_iter().chain(inverses).collect();
 
-        Self { xforms }
+        Self {
+            xforms,
+            weights: None,
+        }
+    }
+
+    /// Attach a weight to each transformation, in the same order as
+    /// iter(), so the inverses are weighted separately. Weights must be
+    /// non-negative, and are normalized when used.
+    pub fn with_weights(self, weights: Vec<f64>) -> Result<Self, String> {
+        validate_weights(self.xforms.len(), &weights)?;
+        Ok(Self {
+            xforms: self.xforms,
+            weights: Some(weights),
+        })
+    }
+
+    pub fn weights(&self) -> Option<&[f64]> {
+        self.weights.as_deref()
     }
 
     pub fn get_index(&self, symbol: Symbol) -> usize {
@@ -45,10 +73,86 @@ impl<G: Group> GroupIFS<G> {
         self.xforms.iter()
     }
 
-    pub fn dfs(&self, max_depth: usize) -> GroupDFSIterator<G> {
+    pub fn dfs(&self, max_depth: usize) -> GroupDFSIterator<'_, G> {
         GroupDFSIterator::new(self, max_depth)
     }
 
+    /// Continue a depth-first traversal from the addresses that were
+    /// on its stack, bottom of the stack first. See
+    /// GroupDFSIterator::stack_addresses()
+    pub fn dfs_resume(
+        &self,
+        max_depth: usize,
+        stack: &[FractalAddress],
+    ) -> GroupDFSIterator<'_, G> {
+        let frontier = stack
+            .iter()
+            .map(|address| (address.len(), (address.clone(), self.eval(address))))
+            .collect();
+        GroupDFSIterator {
+            search: Search::from_frontier(self, SearchOrder::DepthFirst, frontier, max_depth),
+        }
+    }
+
+    /// The group element for an address, multiplying left to right
+    /// the same way the traversals do
+    pub fn eval(&self, address: &FractalAddress) -> G {
+        address.symbols().iter().fold(G::identity(), |val, symbol| {
+            val * self.xforms[self.get_index(*symbol)].clone()
+        })
+    }
+
+    /// Breadth-first traversal, so all words of length n come before
+    /// any words of length n + 1. This is useful with take() to stop
+    /// after a fixed number of words rather than a fixed depth.
+    /// Within each length, the order matches dfs(). Like dfs(), this
+    /// visits words, not group elements, so an element reached by more
+    /// than one word is visited more than once. Use bfs_unique() to
+    /// visit each element once
+    pub fn bfs(&self, max_depth: usize) -> GroupBFSIterator<'_, G> {
+        GroupBFSIterator::new(self, max_depth)
+    }
+
+    /// Count the distinct group elements at each word length, i.e. the
+    /// growth function of the group with respect to the generators.
+    /// result[n] is the number of elements whose shortest word has
+    /// length n. See bfs_unique() for the key function
+    pub fn word_length_counts<K: Hash + Eq>(
+        &self,
+        max_depth: usize,
+        key: impl Fn(&G) -> K,
+    ) -> Vec<usize> {
+        let mut counts = vec![0; max_depth + 1];
+        for (address, _) in self.bfs_unique(max_depth, key) {
+            counts[address.len()] += 1;
+        }
+        counts
+    }
+
+    /// Indices of the transformations that can follow the given
+    /// address without backtracking, in cyclic order starting just
+    /// after the inverse of the last symbol.
+    fn next_indices(&self, address: &FractalAddress) -> impl DoubleEndedIterator<Item = usize> {
+        let xform_count = self.xforms.len();
+        let (start, end) = if *address == FractalAddress::identity() {
+            // For the first step, we can choose any of the xforms
+            (0, xform_count)
+        } else {
+            // The transformations are stored abc...zABC...Z
+            // the last transformation is at the index matching
+            // the rightmost symbol of the address.
+            // its inverse is at that index + generator_count. Start
+            // one element past that and continue until we've seen
+            // all the transforms except the inverse
+            let generator_count = xform_count / 2;
+            let last_xform_index = self.get_index(address.rightmost());
+            let start = last_xform_index + (generator_count + 1);
+            (start, start + xform_count - 1)
+        };
+
+        (start..end).map(move |i| i % xform_count)
+    }
+
     pub fn apply<T: Transformable<G>>(
         &self,
         primitive: &T,
@@ -65,6 +169,118 @@ impl<G: Group> GroupIFS<G> {
             })
             .collect()
     }
+
+    /// Breadth-first search that visits each group element once, at the
+    /// shortest word that reaches it. Elements are compared with the
+    /// given key function, e.g. Mobius::quantized_key(), since float
+    /// matrices can't be hashed directly. Like dfs(), this never
+    /// applies the inverse of the last symbol.
+    pub fn bfs_unique<K: Hash + Eq>(
+        &self,
+        max_depth: usize,
+        key: impl Fn(&G) -> K,
+    ) -> Vec<(FractalAddress, G)> {
+        let identity = G::identity();
+        let mut seen: HashSet<K> = HashSet::from([key(&identity)]);
+
+        let root = (FractalAddress::identity(), identity);
+        Search::new(self, SearchOrder::BreadthFirst, root, max_depth)
+            .retain(move |_, (_, child)| seen.insert(key(child)))
+            .map(|(_, node)| node)
+            .collect()
+    }
+
+    /// Like apply(), but each group element is only applied once.
+    /// See bfs_unique()
+    pub fn apply_unique<T: Transformable<G>, K: Hash + Eq>(
+        &self,
+        primitive: &T,
+        min_depth: usize,
+        max_depth: usize,
+        key: impl Fn(&G) -> K,
+    ) -> Vec<T> {
+        self.bfs_unique(max_depth, key)
+            .into_iter()
+            .filter(|(address, _)| address.len() >= min_depth)
+            .map(|(_, xform)| primitive.transform(xform))
+            .collect()
+    }
+
+    /// Like bfs_unique(), but visits one element g from each left coset
+    /// gH of a subgroup H, at the shortest word that reaches the coset.
+    /// If H is the stabilizer of a tile, every element of gH maps the
+    /// tile to the same place, so this lists each copy of the tile once
+    /// instead of |H| times. stabilizer must list every element of H,
+    /// e.g. from bfs_unique() on its generators. Cosets are compared by
+    /// the smallest key(gh) over h in H.
+    ///
+    /// Unlike the other searches, words are extended on the left. Every
+    /// element gh of a coset leads to the same coset sgH, so the search
+    /// can skip cosets it has already seen without missing any.
+    pub fn bfs_cosets<K: Hash + Ord>(
+        &self,
+        max_depth: usize,
+        stabilizer: &[G],
+        key: impl Fn(&G) -> K,
+    ) -> Vec<(FractalAddress, G)> {
+        let coset_key = |g: &G| {
+            stabilizer
+                .iter()
+                .map(|h| key(&(g.clone() * h.clone())))
+                .chain([key(g)])
+                .min()
+        };
+
+        let identity = G::identity();
+        let mut seen = HashSet::from([coset_key(&identity)]);
+
+        let root = (FractalAddress::identity(), identity);
+        let space = PrependSymbols(self);
+        Search::new(&space, SearchOrder::BreadthFirst, root, max_depth)
+            .retain(|_, (_, child)| seen.insert(coset_key(child)))
+            .map(|(_, node)| node)
+            .collect()
+    }
+
+    /// Like apply_unique(), but each copy of the primitive is only
+    /// produced once. See bfs_cosets()
+    pub fn apply_cosets<T: Transformable<G>, K: Hash + Ord>(
+        &self,
+        primitive: &T,
+        max_depth: usize,
+        stabilizer: &[G],
+        key: impl Fn(&G) -> K,
+    ) -> Vec<T> {
+        self.bfs_cosets(max_depth, stabilizer, key)
+            .into_iter()
+            .map(|(_, xform)| primitive.transform(xform))
+            .collect()
+    }
+}
+
+impl<G: Group + GroupAction<Complex>> GroupIFS<G> {
+    /// Estimate the invariant measure of the IFS by playing the chaos game
+    /// with the IFS weights and counting where the orbit lands in the grid.
+    /// Unlike the traversals, the random walk may apply a transformation
+    /// right after its inverse. See MonoidIFS::estimate_density()
+    pub fn estimate_density(
+        &self,
+        grid: &mut DensityGrid,
+        start: Complex,
+        iterations: usize,
+        burn_in: usize,
+        seed: u64,
+    ) -> Result<(), String> {
+        chaos_game_density(
+            &self.xforms,
+            self.weights(),
+            grid,
+            start,
+            iterations,
+            burn_in,
+            seed,
+        )
+    }
 }
 
 impl<G: Group> Index<usize> for GroupIFS<G> {
@@ -75,73 +291,113 @@ impl<G: Group> Index<usize> for GroupIFS<G> {
     }
 }
 
+impl<G: Group> SearchSpace for GroupIFS<G> {
+    type State = (FractalAddress, G);
+
+    /// Extend the word by each transformation that doesn't backtrack,
+    /// see next_indices()
+    fn children(&self, _depth: usize, state: &Self::State) -> Vec<Self::State> {
+        let (address, val) = state;
+        self.next_indices(address)
+            .map(|index| {
+                let child_address = address.clone() * self.get_symbol(index).into();
+                let child_val = val.clone() * self.xforms[index].clone();
+                debug_check(&child_val, || child_address.to_string());
+                (child_address, child_val)
+            })
+            .collect()
+    }
+}
+
+/// Search space that extends words on the left instead, s * g. Like
+/// next_indices(), this never applies the inverse of the leftmost symbol
+struct PrependSymbols<'a, G: Group>(&'a GroupIFS<G>);
+
+impl<G: Group> SearchSpace for PrependSymbols<'_, G> {
+    type State = (FractalAddress, G);
+
+    fn children(&self, _depth: usize, state: &Self::State) -> Vec<Self::State> {
+        let Self(ifs) = self;
+        let (address, val) = state;
+        let backtrack = (!address.is_empty()).then(|| address.leftmost().inverse());
+        (0..ifs.xforms.len())
+            .filter(|&index| Some(ifs.get_symbol(index)) != backtrack)
+            .map(|index| {
+                let child_address = FractalAddress::from(ifs.get_symbol(index)) * address.clone();
+                let child_val = ifs.xforms[index].clone() * val.clone();
+                debug_check(&child_val, || child_address.to_string());
+                (child_address, child_val)
+            })
+            .collect()
+    }
+}
+
 pub struct GroupDFSIterator<'a, G: Group> {
-    ifs: &'a GroupIFS<G>,
-    max_depth: usize,
-    // pairs of (address, group)
-    stack: Vec<(FractalAddress, G)>,
+    search: Search<'a, GroupIFS<G>>,
 }
 
 impl<'a, G: Group> GroupDFSIterator<'a, G> {
     fn new(ifs: &'a GroupIFS<G>, max_depth: usize) -> Self {
+        let root = (FractalAddress::identity(), G::identity());
         Self {
-            ifs,
-            max_depth,
-            stack: vec![(FractalAddress::identity(), G::identity())],
+            search: Search::new(ifs, SearchOrder::DepthFirst, root, max_depth),
         }
     }
 }
 
+impl<G: Group> GroupDFSIterator<'_, G> {
+    /// Addresses of the elements still to visit, bottom of the stack
+    /// first. Together with the max depth, this is enough to resume the
+    /// traversal later with GroupIFS::dfs_resume()
+    pub fn stack_addresses(&self) -> Vec<FractalAddress> {
+        self.search
+            .frontier()
+            .map(|(_, (address, _))| address.clone())
+            .collect()
+    }
+}
+
 impl<'a, G: Group> Iterator for GroupDFSIterator<'a, G> {
     type Item = (FractalAddress, G);
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.stack.pop() {
-            None => None,
-            Some((address, val)) => {
-                let xform_count = self.ifs.xforms.len();
-                let generator_count = xform_count / 2;
-
-                if address.len() < self.max_depth && address == FractalAddress::identity() {
-                    // For the first step, we can choose any of the xforms
-                    // Push them to the stack in reverse order;
-                    for i in (0..xform_count).rev() {
-                        let child_address = FractalAddress::from(self.ifs.get_symbol(i));
-                        let child_val = self.ifs[i].clone();
-                        self.stack.push((child_address, child_val));
-                    }
-                } else if address.len() < self.max_depth {
-                    // Iterate over all of the transformations in the IFS
-                    // except for the inverse of the last one we just applied.
-
-                    // The transformations are stored abc...zABC...Z
-                    // the last transformation is at the index matching
-                    // the rightmost symbol of the address.
-                    // its inverse is at that index + generator_count. Start
-                    // one element past that and continue until we've seen
-                    // all the transforms except the inverse
-                    let last_xform_index = self.ifs.get_index(address.rightmost());
-                    let start = last_xform_index + (generator_count + 1);
-                    let end = start + xform_count - 1;
-
-                    // Again, push onto the stack in reverse order
-                    for i in (start..end).rev() {
-                        let index = i % xform_count;
-                        let next_val = self.ifs[index].clone();
-                        let child_address = address.clone() * self.ifs.get_symbol(index).into();
-                        let child_val = val.clone() * next_val;
-                        self.stack.push((child_address, child_val))
-                    }
-                }
-                Some((address, val))
-            }
+        self.search.next().map(|(_, node)| node)
+    }
+}
+
+pub struct GroupBFSIterator<'a, G: Group> {
+    search: Search<'a, GroupIFS<G>>,
+}
+
+impl<'a, G: Group> GroupBFSIterator<'a, G> {
+    fn new(ifs: &'a GroupIFS<G>, max_depth: usize) -> Self {
+        let root = (FractalAddress::identity(), G::identity());
+        Self {
+            search: Search::new(ifs, SearchOrder::BreadthFirst, root, max_depth),
         }
     }
 }
 
+impl<'a, G: Group> Iterator for GroupBFSIterator<'a, G> {
+    type Item = (FractalAddress, G);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.search.next().map(|(_, node)| node)
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::{scale, translation, Complex, Mobius};
+    use std::f64::consts::TAU;
+
+    use crate::{
+        algorithms::sierpinski_maps,
+        finite_subgroups::{self, FiniteSubgroup},
+        geometry::Circle,
+        rotation, scale,
+        transformable::Cline,
+        translation, Complex, Mobius,
+    };
 
     use pretty_assertions::assert_eq;
     use test_case::test_case;
@@ -297,4 +553,194 @@ mod test {
         ]);
         assert_eq!(&results, &expected)
     }
+
+    #[test]
+    pub fn dfs_resume_continues_where_it_left_off() {
+        let ifs = make_ifs(2);
+        let expected: Vec<FractalAddress> = ifs.dfs(3).map(|(address, _)| address).collect();
+
+        let mut iterator = ifs.dfs(3);
+        let mut result: Vec<FractalAddress> = iterator
+            .by_ref()
+            .take(7)
+            .map(|(address, _)| address)
+            .collect();
+        let stack = iterator.stack_addresses();
+        result.extend(ifs.dfs_resume(3, &stack).map(|(address, _)| address));
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn eval_matches_dfs() {
+        let ifs = make_ifs(3);
+
+        for (address, xform) in ifs.dfs(3) {
+            assert_eq!(ifs.eval(&address), xform);
+        }
+    }
+
+    #[test]
+    pub fn bfs_unique_visits_each_rotation_once() {
+        let ifs = GroupIFS::new(vec![rotation(TAU / 6.0).unwrap()]);
+
+        let results = ifs.bfs_unique(10, Mobius::quantized_key);
+
+        // rotations by 0, 1, 2, 3 steps forward and 1, 2 steps back
+        let lengths: Vec<usize> = results.iter().map(|(address, _)| address.len()).collect();
+        assert_eq!(lengths, vec![0, 1, 1, 2, 2, 3]);
+    }
+
+    #[test_case(finite_subgroups::tetrahedral(); "tetrahedral")]
+    #[test_case(finite_subgroups::octahedral(); "octahedral")]
+    #[test_case(finite_subgroups::icosahedral(); "icosahedral")]
+    pub fn bfs_unique_enumerates_finite_group(group: FiniteSubgroup) {
+        let ifs = GroupIFS::new(group.generators);
+
+        let results = ifs.bfs_unique(20, Mobius::quantized_key);
+
+        assert_eq!(results.len(), group.order);
+    }
+
+    #[test]
+    pub fn apply_unique_respects_min_depth() {
+        let ifs = GroupIFS::new(vec![translation(Complex::ONE).unwrap()]);
+        let circle = Cline::from(Circle::new(Complex::Zero, 0.5));
+
+        let results = ifs.apply_unique(&circle, 1, 2, Mobius::quantized_key);
+
+        let expected: Vec<Cline> = [1.0, -1.0, 2.0, -2.0]
+            .into_iter()
+            .map(|x| Cline::from(Circle::new(Complex::new(x, 0.0), 0.5)))
+            .collect();
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    pub fn bfs_visits_shorter_words_first() {
+        let ifs = make_ifs(2);
+
+        let results: Vec<FractalAddress> = ifs.bfs(2).map(|(address, _)| address).collect();
+
+        let lengths: Vec<usize> = results.iter().map(|x| x.len()).collect();
+        assert!(lengths.windows(2).all(|pair| pair[0] <= pair[1]));
+        // 1 + 4 + 4 * 3 words without backtracking
+        assert_eq!(results.len(), 17);
+    }
+
+    #[test]
+    pub fn with_weights_needs_weights_for_inverses() {
+        let result = GroupIFS::new(sierpinski_maps()).with_weights(vec![1.0, 1.0, 1.0]);
+
+        assert!(result.is_err_and(|e| e.contains("expected 6 weights")));
+    }
+
+    #[test]
+    pub fn estimate_density_follows_weights() {
+        // With the inverses weighted 0, this is the same chaos game as
+        // the Sierpinski triangle MonoidIFS
+        let ifs = GroupIFS::new(sierpinski_maps())
+            .with_weights(vec![8.0, 1.0, 1.0, 0.0, 0.0, 0.0])
+            .unwrap();
+        let mut grid = DensityGrid::new(Complex::new(0.5, 0.5), 0.5, 2).unwrap();
+
+        ifs.estimate_density(&mut grid, Complex::Zero, 10000, 10, 0)
+            .unwrap();
+
+        let bottom_left = grid.count(1, 0) as f64 / grid.total() as f64;
+        assert!((bottom_left - 0.8).abs() < 0.05);
+        assert_eq!(grid.count(0, 1), 0);
+    }
+
+    #[test]
+    pub fn bfs_repeats_elements_reached_by_different_words() {
+        // ab = ba in Z^2, so only 1 + 4 + 8 of the 17 words are distinct
+        let ifs = make_ifs(2);
+
+        let keys: HashSet<_> = ifs
+            .bfs(2)
+            .map(|(_, xform)| Mobius::quantized_key(&xform))
+            .collect();
+
+        assert_eq!(ifs.bfs(2).count(), 17);
+        assert_eq!(keys.len(), 13);
+    }
+
+    #[test]
+    pub fn bfs_matches_dfs_order_within_each_length() {
+        let ifs = make_ifs(3);
+
+        let bfs: Vec<(FractalAddress, Mobius)> = ifs.bfs(2).collect();
+        let mut dfs: Vec<(FractalAddress, Mobius)> = ifs.dfs(2).collect();
+        dfs.sort_by_key(|(address, _)| address.len());
+
+        assert_eq!(bfs, dfs);
+    }
+
+    #[test]
+    pub fn word_length_counts_of_free_abelian_group() {
+        // Z^2 has 4n elements at distance n from the identity
+        let ifs = make_ifs(2);
+
+        let result = ifs.word_length_counts(4, Mobius::quantized_key);
+
+        assert_eq!(result, vec![1, 4, 8, 12, 16]);
+    }
+
+    #[test_case(1, 6; "trivial stabilizer")]
+    #[test_case(2, 3; "half turn")]
+    #[test_case(3, 2; "third turn")]
+    #[test_case(6, 1; "whole group")]
+    pub fn bfs_cosets_counts_index_of_stabilizer(stabilizer_order: usize, expected: usize) {
+        let ifs = GroupIFS::new(vec![rotation(TAU / 6.0).unwrap()]);
+        let stabilizer: Vec<Mobius> =
+            GroupIFS::new(vec![rotation(TAU / stabilizer_order as f64).unwrap()])
+                .bfs_unique(6, Mobius::quantized_key)
+                .into_iter()
+                .map(|(_, x)| x)
+                .collect();
+
+        let results = ifs.bfs_cosets(10, &stabilizer, Mobius::quantized_key);
+
+        assert_eq!(results.len(), expected);
+    }
+
+    #[test_case(finite_subgroups::tetrahedral(); "tetrahedral")]
+    #[test_case(finite_subgroups::octahedral(); "octahedral")]
+    #[test_case(finite_subgroups::icosahedral(); "icosahedral")]
+    pub fn bfs_cosets_of_non_normal_subgroup_finds_every_coset(group: FiniteSubgroup) {
+        let stabilizer: Vec<Mobius> = GroupIFS::new(vec![group.generators[0]])
+            .bfs_unique(20, Mobius::quantized_key)
+            .into_iter()
+            .map(|(_, x)| x)
+            .collect();
+        let ifs = GroupIFS::new(group.generators);
+
+        let results = ifs.bfs_cosets(20, &stabilizer, Mobius::quantized_key);
+
+        assert_eq!(results.len(), group.order / stabilizer.len());
+        for (address, xform) in results {
+            assert_eq!(ifs.eval(&address), xform);
+        }
+    }
+
+    #[test]
+    pub fn apply_cosets_produces_each_tile_once() {
+        // A circle centered at -1 is fixed by the reflection z -> 1/z
+        // composed with z -> -z, i.e. z -> -1/z. Within the group
+        // generated by that and a half turn, there are two copies
+        let half_turn = rotation(TAU / 2.0).unwrap();
+        let flip = Mobius::INVERSION * half_turn;
+        let ifs = GroupIFS::new(vec![half_turn, flip]);
+        let circle = Cline::from(Circle::new(Complex::new(-1.0, 0.0), 2.0f64.sqrt()));
+
+        let results =
+            ifs.apply_cosets(&circle, 4, &[Mobius::IDENTITY, flip], Mobius::quantized_key);
+
+        let expected = vec![
+            circle,
+            Cline::from(Circle::new(Complex::new(1.0, 0.0), 2.0f64.sqrt())),
+        ];
+        assert_eq!(results, expected);
+    }
 }
@@ -0,0 +1,209 @@
+use std::collections::VecDeque;
+
+use crate::{
+    algorithms::QuantizedSet,
+    geometry::{Circle, GeneralizedCircle},
+    isogonal::Isogonal,
+    rendering::{Scene, Style},
+    transformable::{Cline, Transformable},
+};
+
+/// A circle produced by the inversion fractal, along with the number of
+/// inversions applied to the seed circle to produce it
+#[derive(Clone, Copy, Debug)]
+pub struct InversionCircle {
+    pub circle: Circle,
+    pub depth: usize,
+}
+
+/// Iterated function system where every generator is an inversion in a
+/// circle (a "mirror"). Since inversions are involutions, a word never
+/// needs to apply the same mirror twice in a row, so the search skips
+/// those branches.
+///
+/// Unlike GroupIFS, this works with the seed circles directly rather than
+/// the group elements. This way duplicate circles can be removed, and
+/// branches can stop once the circles get too small to see.
+pub struct InversionFractal {
+    inversions: Vec<Isogonal>,
+}
+
+impl InversionFractal {
+    pub fn new(mirrors: &[Circle]) -> Result<Self, String> {
+        if mirrors.is_empty() {
+            return Err(String::from("at least one mirror is required"));
+        }
+
+        let inversions = mirrors
+            .iter()
+            .map(|x| Isogonal::circle_inversion(*x))
+            .collect::<Result<Vec<Isogonal>, String>>()?;
+
+        Ok(Self { inversions })
+    }
+
+    /// Repeatedly invert the seed circles in the mirrors, breadth first.
+    /// A branch stops when it reaches max_depth inversions or when the
+    /// circle's radius drops below min_radius. Duplicate circles are
+    /// skipped, and so are images that are lines (when a circle passes
+    /// through the center of a mirror). The seeds are included at depth 0.
+    pub fn generate(
+        &self,
+        seeds: &[Circle],
+        max_depth: usize,
+        min_radius: f64,
+    ) -> Vec<InversionCircle> {
+        let mut seen: QuantizedSet<Circle> = QuantizedSet::new();
+        let mut result = Vec::new();
+        // entries are (circle, depth, index of the last mirror applied)
+        let mut queue: VecDeque<(Circle, usize, Option<usize>)> = VecDeque::new();
+
+        for seed in seeds {
+            if seen.insert(*seed) {
+                queue.push_back((*seed, 0, None));
+            }
+        }
+
+        while let Some((circle, depth, last_mirror)) = queue.pop_front() {
+            result.push(InversionCircle { circle, depth });
+            if depth >= max_depth {
+                continue;
+            }
+
+            let cline = Cline::from(circle);
+            for (i, inversion) in self.inversions.iter().enumerate() {
+                if last_mirror == Some(i) {
+                    continue;
+                }
+
+                let image = match cline.transform(*inversion).classify() {
+                    Ok(GeneralizedCircle::Circle(image)) => image,
+                    _ => continue,
+                };
+
+                if image.radius < min_radius || !seen.insert(image) {
+                    continue;
+                }
+
+                queue.push_back((image, depth + 1, Some(i)));
+            }
+        }
+
+        result
+    }
+}
+
+/// Build a scene with one layer per depth. Depth d is styled with
+/// styles[d], and any deeper circles reuse the last style.
+pub fn scene_by_depth(circles: &[InversionCircle], styles: &[Style]) -> Scene {
+    let mut scene = Scene::new();
+    let Some(max_depth) = circles.iter().map(|x| x.depth).max() else {
+        return scene;
+    };
+
+    for depth in 0..=max_depth {
+        let layer: Vec<Cline> = circles
+            .iter()
+            .filter(|x| x.depth == depth)
+            .map(|x| x.circle.into())
+            .collect();
+
+        if let (false, Some(style)) = (layer.is_empty(), styles.get(depth).or(styles.last())) {
+            scene.add_layer(depth as i32, *style, &layer);
+        }
+    }
+
+    scene
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use crate::Complex;
+
+    use super::*;
+
+    /// n circles centered on the roots of unity, just touching their
+    /// neighbors
+    fn ring_of_mirrors(n: usize) -> Vec<Circle> {
+        let radius = (PI / n as f64).sin();
+        Complex::roots_of_unity(n)
+            .into_iter()
+            .map(|center| Circle::new(center, radius))
+            .collect()
+    }
+
+    #[test]
+    pub fn new_with_no_mirrors_returns_error() {
+        let result = InversionFractal::new(&[]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn generate_with_depth_zero_returns_seeds() {
+        let mirrors = ring_of_mirrors(3);
+        let fractal = InversionFractal::new(&mirrors).unwrap();
+
+        let result = fractal.generate(&mirrors, 0, 0.0);
+
+        assert_eq!(result.len(), 3);
+        assert!(result.iter().all(|x| x.depth == 0));
+    }
+
+    #[test]
+    pub fn generate_skips_repeated_mirror() {
+        let mirrors = ring_of_mirrors(3);
+        let fractal = InversionFractal::new(&mirrors).unwrap();
+
+        let result = fractal.generate(&mirrors, 1, 0.0);
+
+        // Each seed is fixed by its own mirror, so it only has 2 new
+        // images, one in each of the other mirrors
+        let depth1 = result.iter().filter(|x| x.depth == 1).count();
+        assert_eq!(depth1, 6);
+    }
+
+    #[test]
+    pub fn generate_removes_duplicates() {
+        // The seed is the first mirror, so inverting it in that mirror
+        // gives back the seed, which is skipped as a duplicate
+        let mirrors = [
+            Circle::new(Complex::Zero, 2.0),
+            Circle::new(Complex::Zero, 3.0),
+        ];
+        let fractal = InversionFractal::new(&mirrors).unwrap();
+
+        let result = fractal.generate(&[Circle::new(Complex::Zero, 2.0)], 2, 0.0);
+
+        // radius 2 -> radius 4.5 (by the second mirror) -> radius 8/9
+        let radii: Vec<f64> = result.iter().map(|x| x.circle.radius).collect();
+        assert_eq!(radii.len(), 3);
+        assert!((radii[1] - 4.5).abs() < 1e-12);
+        assert!((radii[2] - 8.0 / 9.0).abs() < 1e-12);
+    }
+
+    #[test]
+    pub fn generate_stops_at_min_radius() {
+        let mirrors = ring_of_mirrors(4);
+        let fractal = InversionFractal::new(&mirrors).unwrap();
+        let min_radius = 0.05;
+
+        let result = fractal.generate(&mirrors, 20, min_radius);
+
+        assert!(result.iter().all(|x| x.circle.radius >= min_radius));
+    }
+
+    #[test]
+    pub fn scene_by_depth_makes_one_layer_per_depth() {
+        let mirrors = ring_of_mirrors(3);
+        let fractal = InversionFractal::new(&mirrors).unwrap();
+        let circles = fractal.generate(&mirrors, 3, 0.0);
+
+        let result = scene_by_depth(&circles, &[Style::stroke(255, 255, 255)]);
+
+        let z_indices: Vec<i32> = result.sorted_layers().iter().map(|x| x.z_index).collect();
+        assert_eq!(z_indices, vec![0, 1, 2, 3]);
+    }
+}
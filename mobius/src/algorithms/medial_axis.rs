@@ -0,0 +1,519 @@
+use std::f64::consts::FRAC_PI_2;
+
+use crate::{
+    cline_arc::ClineArcGeometry,
+    geometry::{Circle, CircularArc, LineSegment},
+    polygon::Polygon,
+    transformable::ClineArcTile,
+    winding::FillRule,
+    Complex,
+};
+
+use super::arc_boolean::{inside, parameter_of, point_at, tangent_at, tile_geometry};
+
+/// Largest grid the sampled computations will use, to catch tolerances
+/// that are far too small for the polygon
+const MAX_SAMPLES: usize = 4_000_000;
+
+/// How close two edge endpoints must be to count as the same vertex
+const VERTEX_TOLERANCE: f64 = 1e-9;
+
+/// Part of a boundary that can be the nearest to a point. Points whose
+/// nearest point on an edge is one of its endpoints are nearest to that
+/// vertex instead. At reflex vertices these points form a wedge rather
+/// than being split between the two edges.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Feature {
+    Edge(usize),
+    Vertex(usize),
+}
+
+/// Closest points of a boundary made of line segments and circular arcs,
+/// precomputed for sampling
+struct Boundary {
+    edges: Vec<ClineArcGeometry>,
+    vertices: Vec<Complex>,
+    /// Indices of the start and end vertex of each edge
+    ends: Vec<(usize, usize)>,
+    /// Whether the edges meeting at each vertex join without a corner,
+    /// like an arc continuing a tangent segment
+    smooth: Vec<bool>,
+}
+
+impl Boundary {
+    fn from_polygon(polygon: &Polygon) -> Self {
+        let vertices = polygon.vertices();
+        let n = vertices.len();
+        let edges = (0..n)
+            .map(|i| {
+                ClineArcGeometry::LineSegment(LineSegment::new(vertices[i], vertices[(i + 1) % n]))
+            })
+            .collect();
+
+        Self::new(edges)
+    }
+
+    /// Boundary of a tile. Returns an error if any of its arcs pass
+    /// through infinity
+    fn from_tile(tile: &ClineArcTile) -> Result<Self, String> {
+        Ok(Self::new(tile_geometry(tile)?))
+    }
+
+    /// edges must only contain line segments and circular arcs
+    fn new(edges: Vec<ClineArcGeometry>) -> Self {
+        let mut vertices: Vec<Complex> = Vec::new();
+        let mut vertex_index = |z: Complex| match vertices
+            .iter()
+            .position(|v| (*v - z).mag() <= VERTEX_TOLERANCE)
+        {
+            Some(index) => index,
+            None => {
+                vertices.push(z);
+                vertices.len() - 1
+            }
+        };
+        let ends: Vec<(usize, usize)> = edges
+            .iter()
+            .map(|edge| {
+                (
+                    vertex_index(point_at(edge, 0.0)),
+                    vertex_index(point_at(edge, 1.0)),
+                )
+            })
+            .collect();
+
+        // Directions leaving each vertex along the edges that touch it
+        let mut leaving: Vec<Vec<Complex>> = vec![Vec::new(); vertices.len()];
+        for (edge, &(start, end)) in edges.iter().zip(&ends) {
+            leaving[start].push(tangent_at(edge, 0.0));
+            leaving[end].push(-tangent_at(edge, 1.0));
+        }
+        let smooth = leaving
+            .iter()
+            .map(|directions| match directions[..] {
+                [u, v] => {
+                    let (u, v) = (u / u.mag().into(), v / v.mag().into());
+                    Complex::dot(u, v) < 0.0
+                        && Complex::wedge(u, v).abs() <= VERTEX_TOLERANCE.sqrt()
+                }
+                _ => false,
+            })
+            .collect();
+
+        Self {
+            edges,
+            vertices,
+            ends,
+            smooth,
+        }
+    }
+
+    /// Nearest point of an edge to z, and the vertex if that point is one
+    /// of the edge's endpoints
+    fn closest(&self, i: usize, z: Complex) -> (Complex, Option<usize>) {
+        let edge = &self.edges[i];
+        let t = parameter_of(edge, z);
+        if (0.0..=1.0).contains(&t) {
+            return (point_at(edge, t), None);
+        }
+
+        let (start, end) = self.ends[i];
+        let (p, q) = (self.vertices[start], self.vertices[end]);
+        if (z - p).mag() <= (z - q).mag() {
+            (p, Some(start))
+        } else {
+            (q, Some(end))
+        }
+    }
+
+    fn distance(&self, feature: Feature, z: Complex) -> f64 {
+        match feature {
+            Feature::Vertex(v) => (z - self.vertices[v]).mag(),
+            Feature::Edge(i) => (z - self.closest(i, z).0).mag(),
+        }
+    }
+
+    /// The nearest feature to z and the distance to it
+    fn nearest(&self, z: Complex) -> (Feature, f64) {
+        let (edge, (point, vertex)) = (0..self.edges.len())
+            .map(|i| (i, self.closest(i, z)))
+            .min_by(|a, b| (z - a.1 .0).mag().total_cmp(&(z - b.1 .0).mag()))
+            .unwrap();
+
+        let feature = match vertex {
+            Some(v) => Feature::Vertex(v),
+            None => Feature::Edge(edge),
+        };
+        (feature, (z - point).mag())
+    }
+
+    /// Points equidistant from two features are only on the medial axis
+    /// if the features are not an edge and one of its own endpoints,
+    /// since those share their closest point. Likewise for two edges that
+    /// join smoothly.
+    fn separates(&self, a: Feature, b: Feature) -> bool {
+        match (a, b) {
+            (Feature::Edge(e), Feature::Vertex(v)) | (Feature::Vertex(v), Feature::Edge(e)) => {
+                let (start, end) = self.ends[e];
+                v != start && v != end
+            }
+            (Feature::Edge(e), Feature::Edge(f)) if e != f => {
+                let (e_start, e_end) = self.ends[e];
+                let (f_start, f_end) = self.ends[f];
+                ![e_start, e_end]
+                    .into_iter()
+                    .any(|v| (v == f_start || v == f_end) && self.smooth[v])
+            }
+            _ => a != b,
+        }
+    }
+
+    fn contains(&self, z: Complex) -> bool {
+        inside(&self.edges, z, FillRule::EvenOdd)
+    }
+
+    /// Points that bound every edge: the vertices, plus the leftmost,
+    /// rightmost, top and bottom points of the arcs that reach them
+    fn extreme_points(&self) -> Vec<Complex> {
+        let mut points = self.vertices.clone();
+        for edge in &self.edges {
+            if let ClineArcGeometry::CircularArc(CircularArc { circle, .. }) = edge {
+                for k in 0..4 {
+                    let z =
+                        circle.center + Complex::from_polar(circle.radius, k as f64 * FRAC_PI_2);
+                    let t = parameter_of(edge, z);
+                    if (0.0..=1.0).contains(&t) {
+                        points.push(z);
+                    }
+                }
+            }
+        }
+        points
+    }
+}
+
+/// Sample grid over the bounding box of a boundary, storing the nearest
+/// feature of each sample inside it
+struct Samples {
+    origin: Complex,
+    spacing: f64,
+    rows: usize,
+    cols: usize,
+    nearest: Vec<Option<(Feature, f64)>>,
+}
+
+impl Samples {
+    fn new(boundary: &Boundary, spacing: f64) -> Result<Self, String> {
+        if !(spacing > 0.0 && spacing.is_finite()) {
+            return Err(format!("tolerance must be positive, got {}", spacing));
+        }
+
+        let (min_x, max_x, min_y, max_y) = boundary.extreme_points().iter().fold(
+            (
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+            ),
+            |(min_x, max_x, min_y, max_y), z| {
+                (
+                    min_x.min(z.real()),
+                    max_x.max(z.real()),
+                    min_y.min(z.imag()),
+                    max_y.max(z.imag()),
+                )
+            },
+        );
+        let cols = ((max_x - min_x) / spacing).ceil() as usize + 1;
+        let rows = ((max_y - min_y) / spacing).ceil() as usize + 1;
+        if rows.saturating_mul(cols) > MAX_SAMPLES {
+            return Err(format!(
+                "tolerance {} is too small, it needs a {} x {} grid",
+                spacing, cols, rows
+            ));
+        }
+
+        let origin = Complex::new(min_x, min_y);
+        let mut samples = Self {
+            origin,
+            spacing,
+            rows,
+            cols,
+            nearest: Vec::with_capacity(rows * cols),
+        };
+        for row in 0..rows {
+            for col in 0..cols {
+                let z = samples.point(row, col);
+                samples
+                    .nearest
+                    .push(boundary.contains(z).then(|| boundary.nearest(z)));
+            }
+        }
+
+        Ok(samples)
+    }
+
+    fn point(&self, row: usize, col: usize) -> Complex {
+        self.origin + Complex::new(col as f64 * self.spacing, row as f64 * self.spacing)
+    }
+
+    fn get(&self, row: usize, col: usize) -> Option<(Feature, f64)> {
+        self.nearest[row * self.cols + col]
+    }
+}
+
+/// Approximate the medial axis of a polygon: the points inside it that
+/// are equally close to two or more separate parts of the boundary. It
+/// runs down the middle of the polygon like a skeleton, so it's useful
+/// for centerline decorations.
+///
+/// The polygon is sampled on a grid with the given spacing, and the
+/// result is a set of short line segments within about tolerance of the
+/// true medial axis. The parts of the axis within tolerance of the
+/// boundary (near convex corners) may be missing. The polygon should
+/// not intersect itself.
+pub fn medial_axis(polygon: &Polygon, tolerance: f64) -> Result<Vec<LineSegment>, String> {
+    sampled_medial_axis(&Boundary::from_polygon(polygon), tolerance)
+}
+
+/// Like medial_axis(), but for the region bounded by a tile whose edges
+/// are line segments and circular arcs. The axis is still approximated
+/// by line segments. Returns an error if any of the tile's arcs pass
+/// through infinity, since then the region is unbounded.
+pub fn tile_medial_axis(tile: &ClineArcTile, tolerance: f64) -> Result<Vec<LineSegment>, String> {
+    sampled_medial_axis(&Boundary::from_tile(tile)?, tolerance)
+}
+
+fn sampled_medial_axis(boundary: &Boundary, tolerance: f64) -> Result<Vec<LineSegment>, String> {
+    let samples = Samples::new(boundary, tolerance)?;
+
+    let mut segments = Vec::new();
+    for row in 0..samples.rows.saturating_sub(1) {
+        for col in 0..samples.cols.saturating_sub(1) {
+            // Corners of the grid square in cyclic order
+            let corners = [
+                (row, col),
+                (row, col + 1),
+                (row + 1, col + 1),
+                (row + 1, col),
+            ];
+            let Some(nearest) = corners
+                .iter()
+                .map(|(r, c)| samples.get(*r, *c))
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+
+            // Where the axis crosses each side of the square, found by
+            // interpolating the difference in distance to the two features
+            let crossings: Vec<Complex> = (0..4)
+                .filter_map(|k| {
+                    let (a, _) = nearest[k];
+                    let (b, _) = nearest[(k + 1) % 4];
+                    if !boundary.separates(a, b) {
+                        return None;
+                    }
+
+                    let p = samples.point(corners[k].0, corners[k].1);
+                    let q = samples.point(corners[(k + 1) % 4].0, corners[(k + 1) % 4].1);
+                    let f = |z| boundary.distance(a, z) - boundary.distance(b, z);
+                    let (fp, fq) = (f(p), f(q));
+                    let t = if fp == fq { 0.5 } else { fp / (fp - fq) };
+                    Some(p + (q - p) * t.clamp(0.0, 1.0).into())
+                })
+                .collect();
+
+            match crossings[..] {
+                [] => {}
+                [a, b] => segments.push(LineSegment::new(a, b)),
+                _ => {
+                    // A junction of the axis (or a noisy square), connect
+                    // everything to the middle
+                    let center =
+                        samples.point(row, col) + Complex::new(0.5 * tolerance, 0.5 * tolerance);
+                    segments.extend(crossings.iter().map(|z| LineSegment::new(*z, center)));
+                }
+            }
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Approximate the largest circle that fits inside a polygon. Its center
+/// is the point furthest from the boundary, which is a good place for a
+/// label. The center is within about tolerance of the true one.
+pub fn largest_inscribed_circle(polygon: &Polygon, tolerance: f64) -> Result<Circle, String> {
+    sampled_inscribed_circle(&Boundary::from_polygon(polygon), tolerance)
+}
+
+/// Like largest_inscribed_circle(), but for the region bounded by a tile.
+/// Returns an error if any of the tile's arcs pass through infinity
+pub fn tile_largest_inscribed_circle(
+    tile: &ClineArcTile,
+    tolerance: f64,
+) -> Result<Circle, String> {
+    sampled_inscribed_circle(&Boundary::from_tile(tile)?, tolerance)
+}
+
+fn sampled_inscribed_circle(boundary: &Boundary, tolerance: f64) -> Result<Circle, String> {
+    let samples = Samples::new(boundary, tolerance)?;
+
+    let (index, radius) = samples
+        .nearest
+        .iter()
+        .enumerate()
+        .filter_map(|(i, x)| x.map(|(_, distance)| (i, distance)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .ok_or_else(|| format!("no samples inside the polygon at tolerance {}", tolerance))?;
+
+    let center = samples.point(index / samples.cols, index % samples.cols);
+    Ok(Circle::new(center, radius))
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::FRAC_PI_2;
+
+    use crate::{
+        cline_arc::ClineArc, geometry::ArcAngles, isogonal::Isogonal, transformable::Transformable,
+        Mobius,
+    };
+
+    use super::*;
+
+    fn rectangle() -> Polygon {
+        Polygon::new(vec![
+            Complex::new(-2.0, -1.0),
+            Complex::new(2.0, -1.0),
+            Complex::new(2.0, 1.0),
+            Complex::new(-2.0, 1.0),
+        ])
+        .unwrap()
+    }
+
+    fn l_shape() -> Polygon {
+        Polygon::new(vec![
+            Complex::Zero,
+            Complex::new(4.0, 0.0),
+            Complex::new(4.0, 1.0),
+            Complex::new(1.0, 1.0),
+            Complex::new(1.0, 4.0),
+            Complex::new(0.0, 4.0),
+        ])
+        .unwrap()
+    }
+
+    /// Rectangle [-2, 2] x [-1, 1] with semicircle caps of radius 1, so
+    /// the medial axis is the segment from -2 to 2
+    fn stadium() -> ClineArcTile {
+        let cap = |center: f64, start: f64| {
+            let angles = ArcAngles::new(start, start + 2.0 * FRAC_PI_2).unwrap();
+            CircularArc::new(Circle::new(Complex::new(center, 0.0), 1.0), angles)
+        };
+        ClineArcTile::new(vec![
+            LineSegment::new(Complex::new(-2.0, -1.0), Complex::new(2.0, -1.0)).into(),
+            cap(2.0, -FRAC_PI_2).into(),
+            LineSegment::new(Complex::new(2.0, 1.0), Complex::new(-2.0, 1.0)).into(),
+            cap(-2.0, FRAC_PI_2).into(),
+        ])
+    }
+
+    /// Distance from z to the true medial axis of rectangle()
+    fn distance_to_rectangle_axis(z: Complex) -> f64 {
+        let (x, y) = (z.real().abs(), z.imag().abs());
+        let spine = if x <= 1.0 { y } else { f64::INFINITY };
+        // Diagonals from (1, 0) to (2, 1) and the mirror images
+        let diagonal = ((x - 1.0) - y).abs() / 2f64.sqrt();
+        spine.min(diagonal)
+    }
+
+    #[test]
+    pub fn medial_axis_with_bad_tolerance_returns_error() {
+        assert!(medial_axis(&rectangle(), 0.0).is_err());
+        assert!(medial_axis(&rectangle(), 1e-6).is_err());
+    }
+
+    #[test]
+    pub fn medial_axis_of_rectangle_is_near_true_axis() {
+        let tolerance = 0.05;
+
+        let result = medial_axis(&rectangle(), tolerance).unwrap();
+
+        assert!(!result.is_empty());
+        for LineSegment { start, end } in result {
+            assert!(distance_to_rectangle_axis(start) < tolerance);
+            assert!(distance_to_rectangle_axis(end) < tolerance);
+        }
+    }
+
+    #[test]
+    pub fn medial_axis_of_rectangle_covers_spine() {
+        let result = medial_axis(&rectangle(), 0.05).unwrap();
+
+        for x in [-0.5, 0.0, 0.5] {
+            let z = Complex::new(x, 0.0);
+            assert!(result.iter().any(|s| (s.start - z).mag() < 0.1));
+        }
+    }
+
+    #[test]
+    pub fn medial_axis_skips_edges_meeting_at_reflex_vertex() {
+        let result = medial_axis(&l_shape(), 0.05).unwrap();
+
+        // Points near the reflex corner at (1, 1) are closest to that
+        // corner itself, there is no axis between the two edges meeting
+        // there
+        let corner = Complex::new(1.0, 1.0);
+        assert!(result
+            .iter()
+            .all(|s| (s.start - corner).mag() > 0.2 && (s.end - corner).mag() > 0.2));
+    }
+
+    #[test]
+    pub fn largest_inscribed_circle_of_rectangle_touches_long_sides() {
+        let result = largest_inscribed_circle(&rectangle(), 0.05).unwrap();
+
+        assert!((result.radius - 1.0).abs() < 0.05);
+        assert!(result.center.imag().abs() < 0.05);
+    }
+
+    #[test]
+    pub fn tile_medial_axis_of_stadium_is_near_spine() {
+        let tolerance = 0.05;
+
+        let result = tile_medial_axis(&stadium(), tolerance).unwrap();
+
+        assert!(!result.is_empty());
+        for LineSegment { start, end } in result.iter() {
+            for z in [start, end] {
+                assert!(z.imag().abs() < tolerance);
+                assert!(z.real().abs() < 2.0 + tolerance);
+            }
+        }
+        for x in [-1.5, 0.0, 1.5] {
+            let z = Complex::new(x, 0.0);
+            assert!(result.iter().any(|s| (s.start - z).mag() < 0.1));
+        }
+    }
+
+    #[test]
+    pub fn tile_largest_inscribed_circle_of_stadium_has_cap_radius() {
+        let result = tile_largest_inscribed_circle(&stadium(), 0.05).unwrap();
+
+        assert!((result.radius - 1.0).abs() < 0.05);
+        assert!(result.center.imag().abs() < 0.05);
+    }
+
+    #[test]
+    pub fn tile_medial_axis_with_edge_through_infinity_returns_error() {
+        let segment = ClineArc::from(LineSegment::new(-Complex::ONE, Complex::ONE));
+        let through_infinity = segment.transform(Isogonal::from(Mobius::INVERSION));
+        let tile = ClineArcTile::new(vec![through_infinity]);
+
+        let result = tile_medial_axis(&tile, 0.05);
+
+        assert!(result.is_err_and(|e| e.contains("finite arcs")));
+    }
+}
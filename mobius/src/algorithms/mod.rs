@@ -1,7 +1,45 @@
+pub mod arc_boolean;
+pub mod area;
+pub mod chaos_game;
+pub mod checkpoint;
+pub mod circle_packing;
+pub mod density;
+pub mod escape_time;
+pub mod fill;
 pub mod grid_ifs;
 pub mod group_ifs;
+pub mod inversion_fractal;
+pub mod medial_axis;
 pub mod monoid_ifs;
+pub mod newton_fractal;
+pub mod orbit_trap;
+pub mod point_set;
+pub mod raster;
+pub mod simplify;
+pub mod spatial_index;
+pub mod stroke;
+pub mod symmetry_detection;
+pub mod tangency_graph;
 
+pub use arc_boolean::*;
+pub use area::*;
+pub use chaos_game::*;
+pub use checkpoint::*;
+pub use circle_packing::*;
+pub use density::*;
+pub use escape_time::*;
+pub use fill::*;
 pub use grid_ifs::*;
 pub use group_ifs::*;
+pub use inversion_fractal::*;
+pub use medial_axis::*;
 pub use monoid_ifs::*;
+pub use newton_fractal::*;
+pub use orbit_trap::*;
+pub use point_set::*;
+pub use raster::*;
+pub use simplify::*;
+pub use spatial_index::*;
+pub use stroke::*;
+pub use symmetry_detection::*;
+pub use tangency_graph::*;
@@ -1,27 +1,79 @@
-use std::ops::Index;
+use std::{collections::HashSet, hash::Hash, ops::Index};
 
-use abstraction::Monoid;
+use abstraction::{GroupAction, Monoid, Search, SearchOrder, SearchSpace};
 
-use crate::transformable::Transformable;
+use crate::{invariants::debug_check, transformable::Transformable, Complex};
+
+use super::{chaos_game_density, validate_weights, DensityGrid};
 
 /// Iterated Function System. This is still in a prototype stage
 pub struct MonoidIFS<S: Monoid> {
     xforms: Vec<S>,
+    /// Relative probability of each transformation for the chaos game.
+    /// None means uniform. These don't affect dfs() or bfs()
+    weights: Option<Vec<f64>>,
 }
 
 impl<S: Monoid> MonoidIFS<S> {
     pub fn new(xforms: Vec<S>) -> Self {
-        Self { xforms }
+        Self {
+            xforms,
+            weights: None,
+        }
+    }
+
+    /// Attach a weight to each transformation. Weights must be
+    /// non-negative, and are normalized when used.
+    pub fn with_weights(self, weights: Vec<f64>) -> Result<Self, String> {
+        validate_weights(self.xforms.len(), &weights)?;
+        Ok(Self {
+            xforms: self.xforms,
+            weights: Some(weights),
+        })
+    }
+
+    pub fn weights(&self) -> Option<&[f64]> {
+        self.weights.as_deref()
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &S> {
         self.xforms.iter()
     }
 
-    pub fn dfs(&self, max_depth: usize) -> MonoidDFSIterator<S> {
+    pub fn dfs(&self, max_depth: usize) -> MonoidDFSIterator<'_, S> {
         MonoidDFSIterator::new(self, max_depth)
     }
 
+    /// Breadth-first traversal, so all words of length n come before
+    /// any words of length n + 1. Within each length, the order matches
+    /// dfs(). Every word is visited, so an element reached by more than
+    /// one word is visited more than once
+    pub fn bfs(&self, max_depth: usize) -> MonoidBFSIterator<'_, S> {
+        MonoidBFSIterator::new(self, max_depth)
+    }
+
+    /// Count the distinct elements at each word length. result[n] is the
+    /// number of elements whose shortest word has length n. Since floats
+    /// can't be hashed directly, elements are compared with the given key
+    /// function, e.g. Mobius::quantized_key()
+    pub fn word_length_counts<K: Hash + Eq>(
+        &self,
+        max_depth: usize,
+        key: impl Fn(&S) -> K,
+    ) -> Vec<usize> {
+        let identity = S::identity();
+        let mut seen: HashSet<K> = HashSet::from([key(&identity)]);
+
+        let mut counts = vec![0; max_depth + 1];
+        let search = Search::new(self, SearchOrder::BreadthFirst, identity, max_depth)
+            .retain(move |_, child| seen.insert(key(child)));
+        for (depth, _) in search {
+            counts[depth] += 1;
+        }
+
+        counts
+    }
+
     pub fn apply<T: Transformable<S>>(
         &self,
         primitive: &T,
@@ -40,6 +92,31 @@ impl<S: Monoid> MonoidIFS<S> {
     }
 }
 
+impl<S: Monoid + GroupAction<Complex>> MonoidIFS<S> {
+    /// Estimate the invariant measure of the IFS by playing the chaos game
+    /// with the IFS weights and counting where the orbit lands in the grid.
+    /// The first burn_in points are skipped since they may not be on the
+    /// attractor yet. The same seed always gives the same counts.
+    pub fn estimate_density(
+        &self,
+        grid: &mut DensityGrid,
+        start: Complex,
+        iterations: usize,
+        burn_in: usize,
+        seed: u64,
+    ) -> Result<(), String> {
+        chaos_game_density(
+            &self.xforms,
+            self.weights(),
+            grid,
+            start,
+            iterations,
+            burn_in,
+            seed,
+        )
+    }
+}
+
 impl<S: Monoid> Index<usize> for MonoidIFS<S> {
     type Output = S;
 
@@ -48,19 +125,40 @@ impl<S: Monoid> Index<usize> for MonoidIFS<S> {
     }
 }
 
+/// The traversals only track the depth, so invariant violations are
+/// reported by the length of the word and its last transformation
+fn describe_word(depth: usize, index: usize) -> String {
+    format!("of length {} ending in transformation {}", depth, index)
+}
+
+impl<S: Monoid> SearchSpace for MonoidIFS<S> {
+    type State = S;
+
+    /// Apply each transformation on the left. These are listed in
+    /// reverse, so dfs() tries the last transformation first
+    fn children(&self, depth: usize, xform: &S) -> Vec<S> {
+        self.xforms
+            .iter()
+            .cloned()
+            .enumerate()
+            .rev()
+            .map(|(index, next_xform)| {
+                let product = next_xform * xform.clone();
+                debug_check(&product, || describe_word(depth + 1, index));
+                product
+            })
+            .collect()
+    }
+}
+
 pub struct MonoidDFSIterator<'a, S: Monoid> {
-    ifs: &'a MonoidIFS<S>,
-    max_depth: usize,
-    // pairs of (depth, xform)
-    stack: Vec<(usize, S)>,
+    search: Search<'a, MonoidIFS<S>>,
 }
 
 impl<'a, S: Monoid> MonoidDFSIterator<'a, S> {
     fn new(ifs: &'a MonoidIFS<S>, max_depth: usize) -> Self {
         Self {
-            ifs,
-            max_depth,
-            stack: vec![(0, S::identity())],
+            search: Search::new(ifs, SearchOrder::DepthFirst, S::identity(), max_depth),
         }
     }
 }
@@ -69,16 +167,98 @@ impl<'a, S: Monoid> Iterator for MonoidDFSIterator<'a, S> {
     type Item = (usize, S);
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.stack.pop() {
-            None => None,
-            Some((depth, xform)) => {
-                if depth < self.max_depth {
-                    for next_xform in self.ifs.iter().cloned() {
-                        self.stack.push((depth + 1, next_xform * xform.clone()));
-                    }
-                }
-                Some((depth, xform))
-            }
+        self.search.next()
+    }
+}
+
+pub struct MonoidBFSIterator<'a, S: Monoid> {
+    search: Search<'a, MonoidIFS<S>>,
+}
+
+impl<'a, S: Monoid> MonoidBFSIterator<'a, S> {
+    fn new(ifs: &'a MonoidIFS<S>, max_depth: usize) -> Self {
+        Self {
+            search: Search::new(ifs, SearchOrder::BreadthFirst, S::identity(), max_depth),
         }
     }
 }
+
+impl<'a, S: Monoid> Iterator for MonoidBFSIterator<'a, S> {
+    type Item = (usize, S);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.search.next()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{algorithms::sierpinski_maps, scale, translation, Complex, Mobius};
+
+    use super::*;
+
+    fn make_ifs() -> MonoidIFS<Mobius> {
+        let a = translation(Complex::ONE).unwrap();
+        let b = scale(2.0).unwrap();
+        MonoidIFS::new(vec![a, b])
+    }
+
+    #[test]
+    pub fn bfs_matches_dfs_sorted_by_depth() {
+        let ifs = make_ifs();
+
+        let bfs: Vec<(usize, Mobius)> = ifs.bfs(3).collect();
+        let mut dfs: Vec<(usize, Mobius)> = ifs.dfs(3).collect();
+        dfs.sort_by_key(|(depth, _)| *depth);
+
+        assert_eq!(bfs, dfs);
+    }
+
+    #[test]
+    pub fn bfs_take_stops_after_n_elements() {
+        let ifs = make_ifs();
+
+        let result: Vec<usize> = ifs.bfs(10).take(4).map(|(depth, _)| depth).collect();
+
+        assert_eq!(result, vec![0, 1, 1, 2]);
+    }
+
+    #[test]
+    pub fn word_length_counts_removes_duplicates() {
+        // translation by 1 and 2 commute, so words of length n only
+        // give n + 1 distinct translations
+        let a = translation(Complex::ONE).unwrap();
+        let b = translation(Complex::new(2.0, 0.0)).unwrap();
+        let ifs = MonoidIFS::new(vec![a, b]);
+
+        let result = ifs.word_length_counts(3, Mobius::quantized_key);
+
+        // lengths 0..3 reach translations by 0, 1..2, 3..4, 5..6
+        assert_eq!(result, vec![1, 2, 2, 2]);
+    }
+
+    #[test]
+    pub fn with_weights_with_wrong_length_returns_error() {
+        let result = MonoidIFS::new(sierpinski_maps()).with_weights(vec![1.0]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn estimate_density_follows_weights() {
+        let ifs = MonoidIFS::new(sierpinski_maps())
+            .with_weights(vec![8.0, 1.0, 1.0])
+            .unwrap();
+        let mut grid = DensityGrid::new(Complex::new(0.5, 0.5), 0.5, 2).unwrap();
+
+        ifs.estimate_density(&mut grid, Complex::Zero, 10000, 10, 0)
+            .unwrap();
+
+        // The bottom left quadrant is the image of the heavily weighted
+        // map, so it gets about 80% of the points
+        let bottom_left = grid.count(1, 0) as f64 / grid.total() as f64;
+        assert!((bottom_left - 0.8).abs() < 0.05);
+        // The top right quadrant is not part of the attractor
+        assert_eq!(grid.count(0, 1), 0);
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{rendering::ColorRGB, Complex, ComplexPolynomial};
+
+use super::Raster;
+
+/// Which root a starting point converged to under Newton's method, and
+/// how many steps it took
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Basin {
+    pub root: usize,
+    pub iterations: u32,
+}
+
+/// Newton's method z -> z - p(z) / p'(z) for a polynomial p. Each root has
+/// a basin of starting points that converge to it, and the boundaries
+/// between basins are fractal.
+#[derive(Clone, Debug)]
+pub struct NewtonFractal {
+    polynomial: ComplexPolynomial,
+    derivative: ComplexPolynomial,
+    roots: Vec<Complex>,
+    max_iterations: u32,
+    tolerance: f64,
+}
+
+impl NewtonFractal {
+    /// A point has converged once it is within tolerance of a root. The
+    /// roots are found up front with ComplexPolynomial::roots()
+    pub fn new(
+        polynomial: ComplexPolynomial,
+        max_iterations: u32,
+        tolerance: f64,
+    ) -> Result<Self, String> {
+        if polynomial.degree().unwrap_or(0) < 2 {
+            return Err(String::from("polynomial must have degree at least 2"));
+        }
+
+        if !(tolerance > 0.0 && tolerance.is_finite()) {
+            return Err(format!("tolerance must be positive, got {}", tolerance));
+        }
+
+        let roots = polynomial.roots(1e-12, 1000)?;
+        Ok(Self {
+            derivative: polynomial.derivative(),
+            polynomial,
+            roots,
+            max_iterations,
+            tolerance,
+        })
+    }
+
+    pub fn polynomial(&self) -> &ComplexPolynomial {
+        &self.polynomial
+    }
+
+    pub fn roots(&self) -> &[Complex] {
+        &self.roots
+    }
+
+    /// One Newton step. Critical points of p are sent to infinity
+    pub fn step(&self, z: Complex) -> Complex {
+        let slope = self.derivative.eval(z);
+        if slope == Complex::Zero {
+            return Complex::Infinity;
+        }
+
+        z - self.polynomial.eval(z) / slope
+    }
+
+    /// The basin containing z, or None if it did not converge within
+    /// max_iterations steps
+    pub fn basin(&self, z: Complex) -> Option<Basin> {
+        let mut z = z;
+        for iterations in 0..=self.max_iterations {
+            if z == Complex::Infinity {
+                return None;
+            }
+
+            if let Some(root) = self
+                .roots
+                .iter()
+                .position(|root| (z - *root).mag() < self.tolerance)
+            {
+                return Some(Basin { root, iterations });
+            }
+
+            z = self.step(z);
+        }
+        None
+    }
+
+    /// Basins over a square region of the plane
+    pub fn basins(
+        &self,
+        center: Complex,
+        half_width: f64,
+        resolution: usize,
+    ) -> Result<Raster<Option<Basin>>, String> {
+        Raster::compute(center, half_width, resolution, |z| self.basin(z))
+    }
+
+    /// Color a basin by its root, cycling through root_colors if there are
+    /// more roots than colors. Points that take longer to converge fade
+    /// towards the interior color, which is also used for points that
+    /// don't converge at all.
+    pub fn color(
+        &self,
+        basin: Option<Basin>,
+        root_colors: &[ColorRGB],
+        interior: ColorRGB,
+    ) -> ColorRGB {
+        match basin {
+            Some(Basin { root, iterations }) if !root_colors.is_empty() => {
+                let fade = iterations as f64 / self.max_iterations.max(1) as f64;
+                ColorRGB::lerp(root_colors[root % root_colors.len()], interior, fade)
+            }
+            _ => interior,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn cubic() -> NewtonFractal {
+        let roots = Complex::roots_of_unity(3);
+        let polynomial = ComplexPolynomial::from_roots(&roots).unwrap();
+        NewtonFractal::new(polynomial, 50, 1e-6).unwrap()
+    }
+
+    #[test]
+    pub fn new_with_linear_polynomial_returns_error() {
+        let polynomial = ComplexPolynomial::from_roots(&[Complex::ONE]).unwrap();
+
+        let result = NewtonFractal::new(polynomial, 50, 1e-6);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn basin_near_root_converges_to_it() {
+        let fractal = cubic();
+        let target = fractal
+            .roots()
+            .iter()
+            .position(|x| *x == Complex::ONE)
+            .unwrap();
+
+        let result = fractal.basin(Complex::new(1.2, 0.1)).unwrap();
+
+        assert_eq!(result.root, target);
+        assert!(result.iterations > 0);
+    }
+
+    #[test]
+    pub fn basin_of_critical_point_is_none() {
+        let fractal = cubic();
+
+        let result = fractal.basin(Complex::Zero);
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    pub fn basins_finds_every_root() {
+        let fractal = cubic();
+
+        let raster = fractal.basins(Complex::Zero, 2.0, 16).unwrap();
+
+        for root in 0..3 {
+            assert!(raster
+                .pixels()
+                .iter()
+                .any(|x| matches!(x, Some(Basin { root: r, .. }) if *r == root)));
+        }
+    }
+
+    #[test]
+    pub fn color_fades_to_interior() {
+        let fractal = cubic();
+        let colors = [ColorRGB(255, 0, 0)];
+        let interior = ColorRGB(0, 0, 0);
+
+        let immediate = fractal.color(
+            Some(Basin {
+                root: 2,
+                iterations: 0,
+            }),
+            &colors,
+            interior,
+        );
+        let never = fractal.color(None, &colors, interior);
+
+        assert_eq!(immediate, ColorRGB(255, 0, 0));
+        assert_eq!(never, interior);
+    }
+}
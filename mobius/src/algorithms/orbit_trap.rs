@@ -0,0 +1,144 @@
+use crate::{
+    geometry::{Circle, Line},
+    rendering::{Scene, Style},
+    transformable::PointCloud,
+    Complex,
+};
+
+/// Geometry used as an orbit trap. Orbit points are colored by how
+/// close they come to the trap, which brings out structure in the orbit
+/// that a flat point cloud hides.
+#[derive(Clone, Debug)]
+pub enum OrbitTrap {
+    Point(Complex),
+    /// Distance to the nearest point in the set
+    Points(Vec<Complex>),
+    Circle(Circle),
+    Line(Line),
+}
+
+impl OrbitTrap {
+    /// Distance from z to the trap. The point at infinity is infinitely
+    /// far from every trap.
+    pub fn distance(&self, z: Complex) -> f64 {
+        if z == Complex::Infinity {
+            return f64::INFINITY;
+        }
+
+        match self {
+            Self::Point(p) => (z - *p).mag(),
+            Self::Points(points) => points
+                .iter()
+                .map(|p| (z - *p).mag())
+                .fold(f64::INFINITY, f64::min),
+            Self::Circle(circle) => ((z - circle.center).mag() - circle.radius).abs(),
+            Self::Line(line) => (Complex::dot(z, *line.unit_normal.get()) - line.distance).abs(),
+        }
+    }
+}
+
+/// For each point of an orbit (e.g. from chaos_game()), compute the
+/// minimum trap distance over that point and the history points before
+/// it. With history = 0 this is just the distance of each point. Larger
+/// values smear the trap along the orbit, like the classic escape-time
+/// orbit trap which takes the minimum over the whole orbit.
+pub fn trap_distances(orbit: &[Complex], trap: &OrbitTrap, history: usize) -> Vec<f64> {
+    let distances: Vec<f64> = orbit.iter().map(|z| trap.distance(*z)).collect();
+
+    (0..distances.len())
+        .map(|i| {
+            distances[i.saturating_sub(history)..=i]
+                .iter()
+                .copied()
+                .fold(f64::INFINITY, f64::min)
+        })
+        .collect()
+}
+
+/// Build a scene of point clouds colored by trap distance. The range
+/// [0, max_distance] is split evenly into one band per style, closest
+/// first. Points further than max_distance are skipped.
+pub fn scene_by_trap_distance(
+    orbit: &[Complex],
+    distances: &[f64],
+    max_distance: f64,
+    styles: &[Style],
+) -> Scene {
+    let mut bands: Vec<Vec<Complex>> = vec![Vec::new(); styles.len()];
+    for (z, distance) in orbit.iter().zip(distances) {
+        if *distance > max_distance || styles.is_empty() {
+            continue;
+        }
+
+        let band = (distance / max_distance * styles.len() as f64) as usize;
+        bands[band.min(styles.len() - 1)].push(*z);
+    }
+
+    let mut scene = Scene::new();
+    for (i, (band, style)) in bands.into_iter().zip(styles).enumerate() {
+        if !band.is_empty() {
+            scene.add_layer(i as i32, *style, &[PointCloud::new(band)]);
+        }
+    }
+    scene
+}
+
+#[cfg(test)]
+mod test {
+    use crate::unit_complex::UnitComplex;
+
+    use super::*;
+
+    #[test]
+    pub fn distance_to_circle_is_zero_on_circle() {
+        let trap = OrbitTrap::Circle(Circle::unit_circle());
+
+        assert_eq!(trap.distance(Complex::I), 0.0);
+        assert_eq!(trap.distance(Complex::Zero), 1.0);
+        assert_eq!(trap.distance(Complex::new(3.0, 0.0)), 2.0);
+    }
+
+    #[test]
+    pub fn distance_to_line_is_perpendicular_distance() {
+        // The line y = 1
+        let trap = OrbitTrap::Line(Line::new(UnitComplex::I, 1.0).unwrap());
+
+        assert_eq!(trap.distance(Complex::new(5.0, 3.0)), 2.0);
+        assert_eq!(trap.distance(Complex::new(-5.0, -1.0)), 2.0);
+    }
+
+    #[test]
+    pub fn distance_to_points_uses_nearest_point() {
+        let trap = OrbitTrap::Points(vec![Complex::Zero, Complex::new(10.0, 0.0)]);
+
+        assert_eq!(trap.distance(Complex::new(9.0, 0.0)), 1.0);
+        assert_eq!(trap.distance(Complex::Infinity), f64::INFINITY);
+    }
+
+    #[test]
+    pub fn trap_distances_takes_minimum_over_history() {
+        let orbit: Vec<Complex> = [3.0, 1.0, 4.0, 5.0, 2.0]
+            .into_iter()
+            .map(|x| Complex::new(x, 0.0))
+            .collect();
+        let trap = OrbitTrap::Point(Complex::Zero);
+
+        let pointwise = trap_distances(&orbit, &trap, 0);
+        let smeared = trap_distances(&orbit, &trap, 1);
+
+        assert_eq!(pointwise, vec![3.0, 1.0, 4.0, 5.0, 2.0]);
+        assert_eq!(smeared, vec![3.0, 1.0, 1.0, 4.0, 2.0]);
+    }
+
+    #[test]
+    pub fn scene_by_trap_distance_bands_points() {
+        let orbit = [Complex::Zero, Complex::ONE, Complex::new(2.0, 0.0)];
+        let distances = [0.1, 0.9, 5.0];
+        let styles = [Style::stroke(255, 0, 0), Style::stroke(0, 0, 255)];
+
+        let result = scene_by_trap_distance(&orbit, &distances, 1.0, &styles);
+
+        let z_indices: Vec<i32> = result.sorted_layers().iter().map(|x| x.z_index).collect();
+        assert_eq!(z_indices, vec![0, 1]);
+    }
+}
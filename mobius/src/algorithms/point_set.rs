@@ -0,0 +1,262 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+};
+
+use crate::{
+    geometry::{Circle, CircularArc, LineSegment},
+    isogonal::Isogonal,
+    Complex, Mobius,
+};
+
+/// Round a float to a multiple of step. The cast saturates, so infinities
+/// become i64::MIN/MAX
+pub fn quantize_to(x: f64, step: f64) -> i64 {
+    (x / step).round() as i64
+}
+
+/// Round a float to a multiple of Mobius::QUANTIZATION_STEP
+pub fn quantize(x: f64) -> i64 {
+    quantize_to(x, Mobius::QUANTIZATION_STEP)
+}
+
+/// Types that can be rounded to a hashable key, so values that are equal
+/// up to rounding error can be deduplicated. Like
+/// Mobius::quantized_key(), values that straddle a rounding boundary may
+/// get different keys.
+pub trait QuantizedHash {
+    type Key: Hash + Eq;
+
+    fn quantized_key(&self) -> Self::Key;
+}
+
+impl QuantizedHash for Complex {
+    type Key = (i64, i64);
+
+    fn quantized_key(&self) -> Self::Key {
+        (quantize(self.real()), quantize(self.imag()))
+    }
+}
+
+impl QuantizedHash for Circle {
+    type Key = (i64, i64, i64);
+
+    fn quantized_key(&self) -> Self::Key {
+        let (x, y) = self.center.quantized_key();
+        (x, y, quantize(self.radius))
+    }
+}
+
+impl QuantizedHash for LineSegment {
+    type Key = [(i64, i64); 2];
+
+    /// The key ignores direction, so a segment and its reverse coincide
+    fn quantized_key(&self) -> Self::Key {
+        let mut key = [self.start.quantized_key(), self.end.quantized_key()];
+        key.sort();
+        key
+    }
+}
+
+impl QuantizedHash for CircularArc {
+    type Key = ((i64, i64, i64), [(i64, i64); 3]);
+
+    /// The key ignores direction, so an arc and its reverse coincide. The
+    /// midpoint distinguishes an arc from its complement
+    fn quantized_key(&self) -> Self::Key {
+        let mut endpoints = [
+            self.interpolate(0.0).quantized_key(),
+            self.interpolate(1.0).quantized_key(),
+        ];
+        endpoints.sort();
+        let [start, end] = endpoints;
+        let mid = self.interpolate(0.5).quantized_key();
+
+        (self.circle.quantized_key(), [start, mid, end])
+    }
+}
+
+impl QuantizedHash for Mobius {
+    type Key = [i64; 8];
+
+    fn quantized_key(&self) -> Self::Key {
+        Mobius::quantized_key(self)
+    }
+}
+
+impl QuantizedHash for Isogonal {
+    type Key = (bool, [i64; 8]);
+
+    fn quantized_key(&self) -> Self::Key {
+        Isogonal::quantized_key(self)
+    }
+}
+
+/// Set of values deduplicated by QuantizedHash. The first value inserted
+/// for each key is kept, and iteration is in insertion order.
+#[derive(Clone, Debug)]
+pub struct QuantizedSet<T: QuantizedHash> {
+    indices: HashMap<T::Key, usize>,
+    values: Vec<T>,
+}
+
+/// Set of points, e.g. for deduplicating orbits
+pub type PointSet = QuantizedSet<Complex>;
+
+impl<T: QuantizedHash> QuantizedSet<T> {
+    pub fn new() -> Self {
+        Self {
+            indices: HashMap::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Insert a value. Returns false if an equivalent value was already
+    /// in the set, in which case the set is unchanged
+    pub fn insert(&mut self, value: T) -> bool {
+        match self.indices.entry(value.quantized_key()) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(self.values.len());
+                self.values.push(value);
+                true
+            }
+        }
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.indices.contains_key(&value.quantized_key())
+    }
+
+    /// The value in the set equivalent to the given one, if any
+    pub fn get(&self, value: &T) -> Option<&T> {
+        let &index = self.indices.get(&value.quantized_key())?;
+        Some(&self.values[index])
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.values.iter()
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.values
+    }
+}
+
+impl<T: QuantizedHash> Default for QuantizedSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: QuantizedHash> Extend<T> for QuantizedSet<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<T: QuantizedHash> FromIterator<T> for QuantizedSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut result = Self::new();
+        result.extend(iter);
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{geometry::ArcAngles, rotation};
+
+    use super::*;
+
+    #[test]
+    pub fn point_set_deduplicates_rounding_error() {
+        let z = Complex::new(0.1, 0.2);
+        let nearby = Complex::new(0.1 + 1e-13, 0.2 - 1e-13);
+
+        let set: PointSet = [z, nearby, Complex::new(0.5, 0.0)].into_iter().collect();
+
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&nearby));
+        assert_eq!(set.get(&nearby).unwrap().real(), 0.1);
+    }
+
+    #[test]
+    pub fn point_set_detects_orbit_closing() {
+        // Rotating by 1/5 turn returns to the start after 5 steps
+        let r = rotation(std::f64::consts::TAU / 5.0).unwrap();
+        let mut set = PointSet::new();
+        let mut z = Complex::ONE;
+
+        let mut steps = 0;
+        while set.insert(z) {
+            z = r * z;
+            steps += 1;
+        }
+
+        assert_eq!(steps, 5);
+    }
+
+    #[test]
+    pub fn circle_keys_distinguish_radius() {
+        let a = Circle::new(Complex::ONE, 1.0);
+        let b = Circle::new(Complex::ONE, 2.0);
+
+        assert_ne!(a.quantized_key(), b.quantized_key());
+    }
+
+    #[test]
+    pub fn line_segment_key_ignores_direction() {
+        let segment = LineSegment::new(Complex::Zero, Complex::new(1.0, 2.0));
+        let reversed = LineSegment::new(segment.end, segment.start);
+
+        assert_eq!(segment.quantized_key(), reversed.quantized_key());
+    }
+
+    #[test]
+    pub fn circular_arc_key_ignores_direction() {
+        let arc = CircularArc::new(Circle::unit_circle(), ArcAngles::new(0.0, 1.0).unwrap());
+
+        assert_eq!(arc.quantized_key(), arc.reverse().quantized_key());
+    }
+
+    #[test]
+    pub fn circular_arc_key_distinguishes_complement() {
+        let arc = CircularArc::new(Circle::unit_circle(), ArcAngles::new(0.0, 1.0).unwrap());
+
+        assert_ne!(arc.quantized_key(), arc.complement().quantized_key());
+    }
+
+    #[test]
+    pub fn mobius_key_matches_inherent_method() {
+        let m = rotation(1.0).unwrap();
+
+        assert_eq!(QuantizedHash::quantized_key(&m), m.quantized_key());
+    }
+
+    #[test]
+    pub fn isogonal_keys_distinguish_orientation() {
+        let m = rotation(1.0).unwrap();
+        let negated = Mobius::new(-m.a, -m.b, -m.c, -m.d).unwrap();
+
+        let set: QuantizedSet<Isogonal> = [
+            Isogonal::Conformal(m),
+            Isogonal::Conformal(negated),
+            Isogonal::AntiConformal(m),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(set.len(), 2);
+    }
+}
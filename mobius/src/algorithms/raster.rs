@@ -0,0 +1,113 @@
+use std::{fs, io, path::Path};
+
+use crate::{rendering::ColorRGB, Complex};
+
+/// Square grid of values sampled at pixel centers over a region of the
+/// plane. Rows go from the top of the region (largest imaginary part) to
+/// the bottom like DensityGrid, so the pixels can be written directly as
+/// an image.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Raster<T> {
+    center: Complex,
+    half_width: f64,
+    resolution: usize,
+    pixels: Vec<T>,
+}
+
+impl<T> Raster<T> {
+    /// Sample f at the center of each of the resolution x resolution
+    /// pixels covering the square of the given half width around center
+    pub fn compute(
+        center: Complex,
+        half_width: f64,
+        resolution: usize,
+        f: impl Fn(Complex) -> T,
+    ) -> Result<Self, String> {
+        if center == Complex::Infinity {
+            return Err(String::from("center must be finite"));
+        }
+
+        if !(half_width > 0.0 && half_width.is_finite()) {
+            return Err(format!("half width must be positive, got {}", half_width));
+        }
+
+        if resolution == 0 {
+            return Err(String::from("resolution must be at least 1"));
+        }
+
+        let mut raster = Self {
+            center,
+            half_width,
+            resolution,
+            pixels: Vec::with_capacity(resolution * resolution),
+        };
+        for row in 0..resolution {
+            for col in 0..resolution {
+                raster.pixels.push(f(raster.pixel_center(row, col)));
+            }
+        }
+
+        Ok(raster)
+    }
+
+    pub fn resolution(&self) -> usize {
+        self.resolution
+    }
+
+    pub fn pixel_size(&self) -> f64 {
+        2.0 * self.half_width / self.resolution as f64
+    }
+
+    /// The point sampled for the pixel at (row, col)
+    pub fn pixel_center(&self, row: usize, col: usize) -> Complex {
+        let size = self.pixel_size();
+        let x = -self.half_width + (col as f64 + 0.5) * size;
+        let y = self.half_width - (row as f64 + 0.5) * size;
+        self.center + Complex::new(x, y)
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> &T {
+        &self.pixels[row * self.resolution + col]
+    }
+
+    /// All the pixels in row-major order
+    pub fn pixels(&self) -> &[T] {
+        &self.pixels
+    }
+
+    /// Write the raster as a binary PPM image, which most image viewers
+    /// and converters can read
+    pub fn write_ppm<P: AsRef<Path>>(
+        &self,
+        path: P,
+        color: impl Fn(&T) -> ColorRGB,
+    ) -> io::Result<()> {
+        let mut bytes = format!("P6\n{0} {0}\n255\n", self.resolution).into_bytes();
+        for pixel in &self.pixels {
+            let ColorRGB(r, g, b) = color(pixel);
+            bytes.extend([r, g, b]);
+        }
+        fs::write(path, bytes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn compute_with_zero_resolution_returns_error() {
+        let result = Raster::compute(Complex::Zero, 1.0, 0, |z| z);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn compute_samples_pixel_centers_top_down() {
+        let raster = Raster::compute(Complex::new(1.0, 1.0), 1.0, 2, |z| z).unwrap();
+
+        assert_eq!(*raster.get(0, 0), Complex::new(0.5, 1.5));
+        assert_eq!(*raster.get(1, 1), Complex::new(1.5, 0.5));
+        assert_eq!(raster.pixels().len(), 4);
+    }
+}
@@ -0,0 +1,202 @@
+use std::fmt::Display;
+
+use crate::geometry::Circle;
+
+use super::CircleIndex;
+
+/// How much a simplification pass reduced a set of circles
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct SimplifyStats {
+    pub input_count: usize,
+    /// Circles removed because they matched an earlier circle
+    pub duplicates_removed: usize,
+    /// Circles removed because they were inside a larger circle
+    pub covered_removed: usize,
+}
+
+impl SimplifyStats {
+    pub fn output_count(&self) -> usize {
+        self.input_count - self.duplicates_removed - self.covered_removed
+    }
+
+    /// Fraction of the input that was removed, from 0 to 1
+    pub fn reduction(&self) -> f64 {
+        if self.input_count == 0 {
+            return 0.0;
+        }
+
+        1.0 - self.output_count() as f64 / self.input_count as f64
+    }
+}
+
+impl Display for SimplifyStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} -> {} circles ({} duplicates, {} covered, {:.1}% reduction)",
+            self.input_count,
+            self.output_count(),
+            self.duplicates_removed,
+            self.covered_removed,
+            100.0 * self.reduction()
+        )
+    }
+}
+
+fn nearly_same(a: &Circle, b: &Circle, tolerance: f64) -> bool {
+    (a.center - b.center).mag() < tolerance && (a.radius - b.radius).abs() < tolerance
+}
+
+/// Keep only the first of each group of circles whose centers and radii
+/// agree within tolerance. The order of the remaining circles is preserved.
+pub fn merge_duplicates(circles: &[Circle], tolerance: f64) -> Result<Vec<Circle>, String> {
+    let index = CircleIndex::with_median_size(circles)?;
+
+    let result = circles
+        .iter()
+        .enumerate()
+        .filter(|(i, circle)| {
+            !index
+                .query(circle, tolerance)
+                .into_iter()
+                .take_while(|j| j < i)
+                .any(|j| nearly_same(circle, &circles[j], tolerance))
+        })
+        .map(|(_, circle)| *circle)
+        .collect();
+
+    Ok(result)
+}
+
+/// Remove circles that lie entirely inside a strictly larger circle. This
+/// only makes sense for filled styles, where the smaller disk would be
+/// hidden anyway. Run merge_duplicates() first, otherwise two copies of
+/// the same circle are both kept.
+pub fn remove_covered(circles: &[Circle], tolerance: f64) -> Result<Vec<Circle>, String> {
+    let index = CircleIndex::with_median_size(circles)?;
+
+    let result = circles
+        .iter()
+        .filter(|circle| {
+            !index.query(circle, tolerance).into_iter().any(|j| {
+                let other = &circles[j];
+                let distance = (other.center - circle.center).mag();
+                other.radius > circle.radius + tolerance
+                    && distance + circle.radius <= other.radius + tolerance
+            })
+        })
+        .copied()
+        .collect();
+
+    Ok(result)
+}
+
+/// Simplification pass to run before rendering large sets of circles.
+/// Duplicates are always merged. Covered circles are only removed if
+/// remove_covered_circles is set, since for stroked styles the inner
+/// circles are still visible.
+pub fn simplify_circles(
+    circles: &[Circle],
+    tolerance: f64,
+    remove_covered_circles: bool,
+) -> Result<(Vec<Circle>, SimplifyStats), String> {
+    let merged = merge_duplicates(circles, tolerance)?;
+    let simplified = if remove_covered_circles {
+        remove_covered(&merged, tolerance)?
+    } else {
+        merged.clone()
+    };
+
+    let stats = SimplifyStats {
+        input_count: circles.len(),
+        duplicates_removed: circles.len() - merged.len(),
+        covered_removed: merged.len() - simplified.len(),
+    };
+
+    Ok((simplified, stats))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Complex;
+
+    use super::*;
+
+    fn circles() -> Vec<Circle> {
+        vec![
+            Circle::new(Complex::Zero, 2.0),
+            Circle::new(Complex::new(1e-12, 0.0), 2.0),
+            Circle::new(Complex::new(0.5, 0.0), 1.0),
+            Circle::new(Complex::new(5.0, 0.0), 1.0),
+            Circle::new(Complex::new(5.0, 0.0), 1.0 + 1e-12),
+        ]
+    }
+
+    #[test]
+    pub fn merge_duplicates_keeps_first_copy() {
+        let result = merge_duplicates(&circles(), 1e-9).unwrap();
+
+        let centers: Vec<f64> = result.iter().map(|x| x.center.real()).collect();
+        assert_eq!(centers, vec![0.0, 0.5, 5.0]);
+        assert_eq!(result[2].radius, 1.0);
+    }
+
+    #[test]
+    pub fn remove_covered_removes_inner_circles_only() {
+        let input = merge_duplicates(&circles(), 1e-9).unwrap();
+
+        let result = remove_covered(&input, 1e-9).unwrap();
+
+        let centers: Vec<f64> = result.iter().map(|x| x.center.real()).collect();
+        assert_eq!(centers, vec![0.0, 5.0]);
+    }
+
+    #[test]
+    pub fn remove_covered_keeps_overlapping_circles() {
+        let input = [
+            Circle::new(Complex::Zero, 1.0),
+            Circle::new(Complex::new(0.5, 0.0), 0.75),
+        ];
+
+        let result = remove_covered(&input, 1e-9).unwrap();
+
+        assert_eq!(result.len(), 2);
+    }
+
+    #[test]
+    pub fn remove_covered_removes_circles_inside_huge_circle() {
+        let mut input: Vec<Circle> = (0..10)
+            .map(|i| Circle::new(Complex::new(i as f64, 0.0), 0.25))
+            .collect();
+        input.push(Circle::new(Complex::Zero, 1e8));
+
+        let result = remove_covered(&input, 1e-9).unwrap();
+
+        assert_eq!(result, vec![Circle::new(Complex::Zero, 1e8)]);
+    }
+
+    #[test]
+    pub fn simplify_circles_reports_stats() {
+        let (result, stats) = simplify_circles(&circles(), 1e-9, true).unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            stats,
+            SimplifyStats {
+                input_count: 5,
+                duplicates_removed: 2,
+                covered_removed: 1,
+            }
+        );
+        assert_eq!(stats.output_count(), 2);
+        assert!((stats.reduction() - 0.6).abs() < 1e-12);
+    }
+
+    #[test]
+    pub fn simplify_circles_without_covered_removal_keeps_inner_circles() {
+        let (result, stats) = simplify_circles(&circles(), 1e-9, false).unwrap();
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(stats.covered_removed, 0);
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::geometry::Circle;
+
+/// Circles whose bounding box spans more than this many cells in either
+/// direction are kept in a separate list instead of the grid
+const MAX_CELL_SPAN: i64 = 8;
+
+/// Uniform grid over the plane for finding circles near a given region
+/// without comparing against every circle. Each circle is stored in every
+/// cell its bounding box overlaps, so a query only needs to look at the
+/// cells its own bounding box overlaps.
+///
+/// This works best when the cell size is close to the typical circle
+/// diameter. Circles much larger than a cell would fill a huge number of
+/// cells, so they are kept in a separate list that every query checks.
+/// Likewise, a query with a large circle returns every index.
+pub struct CircleIndex {
+    cell_size: f64,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+    /// Indices of circles too large for the grid, in increasing order
+    large: Vec<usize>,
+    count: usize,
+}
+
+impl CircleIndex {
+    /// Index a list of circles. Query results are indices into this list
+    pub fn new(circles: &[Circle], cell_size: f64) -> Result<Self, String> {
+        if !(cell_size > 0.0 && cell_size.is_finite()) {
+            return Err(format!("cell size must be positive, got {}", cell_size));
+        }
+
+        let mut index = Self {
+            cell_size,
+            cells: HashMap::new(),
+            large: Vec::new(),
+            count: circles.len(),
+        };
+        for (i, circle) in circles.iter().enumerate() {
+            if index.is_large(circle, 0.0) {
+                index.large.push(i);
+                continue;
+            }
+
+            for cell in index.cells_overlapping(circle, 0.0) {
+                index.cells.entry(cell).or_default().push(i);
+            }
+        }
+
+        Ok(index)
+    }
+
+    /// Pick a cell size from the median circle diameter, falling back to 1
+    /// if that is zero
+    pub fn with_median_size(circles: &[Circle]) -> Result<Self, String> {
+        let mut diameters: Vec<f64> = circles.iter().map(|x| 2.0 * x.radius).collect();
+        diameters.sort_by(f64::total_cmp);
+        let cell_size = diameters
+            .get(diameters.len() / 2)
+            .copied()
+            .filter(|x| *x > 0.0)
+            .unwrap_or(1.0);
+
+        Self::new(circles, cell_size)
+    }
+
+    /// Indices of circles whose bounding box comes within margin of the
+    /// given circle's bounding box, in increasing order. This may include
+    /// circles that are not actually close, so callers should still check.
+    pub fn query(&self, circle: &Circle, margin: f64) -> Vec<usize> {
+        if self.is_large(circle, margin) {
+            return (0..self.count).collect();
+        }
+
+        let mut found: HashSet<usize> = self.large.iter().copied().collect();
+        for cell in self.cells_overlapping(circle, margin) {
+            if let Some(indices) = self.cells.get(&cell) {
+                found.extend(indices);
+            }
+        }
+
+        let mut result: Vec<usize> = found.into_iter().collect();
+        result.sort_unstable();
+        result
+    }
+
+    /// Number of circles stored outside the grid
+    pub fn large_count(&self) -> usize {
+        self.large.len()
+    }
+
+    fn cell_range(&self, circle: &Circle, margin: f64) -> ((i64, i64), (i64, i64)) {
+        let extent = circle.radius + margin;
+        let to_cell = |x: f64| (x / self.cell_size).floor() as i64;
+        let (x, y) = (circle.center.real(), circle.center.imag());
+
+        (
+            (to_cell(x - extent), to_cell(x + extent)),
+            (to_cell(y - extent), to_cell(y + extent)),
+        )
+    }
+
+    fn is_large(&self, circle: &Circle, margin: f64) -> bool {
+        let ((x_min, x_max), (y_min, y_max)) = self.cell_range(circle, margin);
+        x_max.saturating_sub(x_min) >= MAX_CELL_SPAN || y_max.saturating_sub(y_min) >= MAX_CELL_SPAN
+    }
+
+    fn cells_overlapping(&self, circle: &Circle, margin: f64) -> impl Iterator<Item = (i64, i64)> {
+        let ((x_min, x_max), (y_min, y_max)) = self.cell_range(circle, margin);
+
+        (x_min..=x_max).flat_map(move |i| (y_min..=y_max).map(move |j| (i, j)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Complex;
+
+    use super::*;
+
+    #[test]
+    pub fn new_with_zero_cell_size_returns_error() {
+        let result = CircleIndex::new(&[Circle::unit_circle()], 0.0);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn query_finds_overlapping_circles_only() {
+        let circles = [
+            Circle::new(Complex::Zero, 1.0),
+            Circle::new(Complex::new(2.0, 0.0), 1.0),
+            Circle::new(Complex::new(10.0, 10.0), 1.0),
+        ];
+        let index = CircleIndex::new(&circles, 2.0).unwrap();
+
+        let result = index.query(&circles[0], 0.0);
+
+        assert_eq!(result, vec![0, 1]);
+    }
+
+    #[test]
+    pub fn query_finds_large_circle_containing_small_one() {
+        let circles = [
+            Circle::new(Complex::Zero, 100.0),
+            Circle::new(Complex::new(50.0, 50.0), 0.5),
+        ];
+        let index = CircleIndex::with_median_size(&circles).unwrap();
+
+        let result = index.query(&circles[1], 0.0);
+
+        assert_eq!(result, vec![0, 1]);
+    }
+
+    #[test]
+    pub fn huge_circle_is_stored_outside_grid() {
+        let circles = [
+            Circle::new(Complex::Zero, 1e9),
+            Circle::new(Complex::new(1.0, 0.0), 0.5),
+            Circle::new(Complex::new(3.0, 0.0), 0.5),
+            Circle::new(Complex::new(5.0, 0.0), 0.5),
+        ];
+        let index = CircleIndex::with_median_size(&circles).unwrap();
+
+        let large_count = index.large_count();
+        let result = index.query(&circles[3], 0.0);
+
+        assert_eq!(large_count, 1);
+        assert_eq!(result, vec![0, 3]);
+    }
+
+    #[test]
+    pub fn query_with_huge_circle_returns_every_index() {
+        let circles = [
+            Circle::new(Complex::new(1.0, 0.0), 0.5),
+            Circle::new(Complex::new(1e6, 0.0), 0.5),
+        ];
+        let index = CircleIndex::with_median_size(&circles).unwrap();
+
+        let result = index.query(&Circle::new(Complex::Zero, 1e12), 0.0);
+
+        assert_eq!(result, vec![0, 1]);
+    }
+}
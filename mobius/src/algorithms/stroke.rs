@@ -0,0 +1,191 @@
+use std::f64::consts::PI;
+
+use crate::{
+    cline_arc::{ClineArc, ClineArcGeometry},
+    polygon::Polygon,
+    Complex,
+};
+
+/// Shape of the ends of a stroke
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StrokeCap {
+    /// Cut the stroke off flat at the ends of the path
+    Butt,
+    /// Add a semicircle at each end of the path
+    Round,
+}
+
+/// Sample points along an arc of a path. Line segments only need their
+/// endpoints
+fn sample_arc(arc: &ClineArc, samples: usize, index: usize) -> Result<Vec<Complex>, String> {
+    match arc.classify().map_err(|e| e.to_string())? {
+        ClineArcGeometry::CircularArc(arc) => Ok(arc.sample(samples)),
+        ClineArcGeometry::LineSegment(segment) => Ok(segment.sample(2)),
+        geometry => Err(format!(
+            "edge {} passes through infinity, so it can't be stroked: {}",
+            index, geometry
+        )),
+    }
+}
+
+/// Append points, skipping the first one if it repeats the last point so
+/// far, e.g. where two arcs of the path meet
+fn extend_path(path: &mut Vec<Complex>, points: Vec<Complex>) {
+    let skip = usize::from(path.last().is_some_and(|last| Some(last) == points.first()));
+    path.extend(points.into_iter().skip(skip));
+}
+
+/// Semicircle of points from one side of the stroke to the other, not
+/// including the endpoints. Going counterclockwise from one side around
+/// the center always bulges away from the path, at either end.
+fn round_cap(from: Complex, to: Complex, samples: usize) -> Vec<Complex> {
+    let center = (from + to) * 0.5.into();
+    let Some(angle) = (from - center).arg() else {
+        return vec![];
+    };
+    let radius = (from - center).mag();
+
+    (1..samples)
+        .map(|i| center + Complex::from_polar(radius, angle + PI * i as f64 / samples as f64))
+        .collect()
+}
+
+/// Convert a stroked path into a filled polygon outlining the stroke, so
+/// it can be filled with gradients or patterns, or transformed with a
+/// Mobius map while keeping the width in proportion.
+///
+/// The path is a sequence of arcs, each starting where the previous one
+/// ended. The outline follows the right side of the path (see
+/// ClineArc::offset()), around the end cap, back along the left side and
+/// around the start cap. Each circular arc is sampled with the given
+/// number of points. Corners between arcs are beveled.
+///
+/// This returns an error if any arc passes through infinity, or if the
+/// stroke is wider than the diameter of one of the arcs.
+pub fn stroke_to_polygon(
+    path: &[ClineArc],
+    width: f64,
+    cap: StrokeCap,
+    samples: usize,
+) -> Result<Polygon, String> {
+    if !(width > 0.0 && width.is_finite()) {
+        return Err(format!("width must be positive, got {}", width));
+    }
+    if samples < 2 {
+        return Err(String::from("need at least 2 samples per arc"));
+    }
+
+    let half_width = 0.5 * width;
+    let mut right = Vec::new();
+    let mut left = Vec::new();
+    for (i, arc) in path.iter().enumerate() {
+        extend_path(
+            &mut right,
+            sample_arc(&arc.offset(half_width)?, samples, i)?,
+        );
+        extend_path(
+            &mut left,
+            sample_arc(&arc.offset(-half_width)?, samples, i)?,
+        );
+    }
+    left.reverse();
+
+    let (Some(&right_end), Some(&left_end)) = (right.last(), left.first()) else {
+        return Err(String::from("path must have at least one arc"));
+    };
+    let (right_start, left_start) = (right[0], left[left.len() - 1]);
+
+    let mut vertices = right;
+    if cap == StrokeCap::Round {
+        vertices.extend(round_cap(right_end, left_end, samples));
+    }
+    vertices.extend(left);
+    if cap == StrokeCap::Round {
+        vertices.extend(round_cap(left_start, right_start, samples));
+    }
+
+    Polygon::new(vertices)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        geometry::{ArcAngles, Circle, CircularArc, LineSegment},
+        isogonal::Isogonal,
+        transformable::Transformable,
+        winding::FillRule,
+        Mobius,
+    };
+
+    use super::*;
+
+    fn segment(a: Complex, b: Complex) -> ClineArc {
+        LineSegment::new(a, b).into()
+    }
+
+    #[test]
+    pub fn stroke_segment_with_butt_caps_is_rectangle() {
+        let path = [segment(Complex::Zero, Complex::new(2.0, 0.0))];
+
+        let result = stroke_to_polygon(&path, 1.0, StrokeCap::Butt, 8).unwrap();
+
+        let expected = vec![
+            Complex::new(0.0, -0.5),
+            Complex::new(2.0, -0.5),
+            Complex::new(2.0, 0.5),
+            Complex::new(0.0, 0.5),
+        ];
+        assert_eq!(result.vertices(), expected);
+    }
+
+    #[test]
+    pub fn stroke_with_round_caps_extends_past_ends() {
+        let path = [segment(Complex::Zero, Complex::new(2.0, 0.0))];
+
+        let butt = stroke_to_polygon(&path, 1.0, StrokeCap::Butt, 8).unwrap();
+        let round = stroke_to_polygon(&path, 1.0, StrokeCap::Round, 8).unwrap();
+
+        for x in [-0.4, 2.4] {
+            let point = Complex::new(x, 0.0);
+            assert!(!butt.contains(point, FillRule::NonZero));
+            assert!(round.contains(point, FillRule::NonZero));
+        }
+    }
+
+    #[test]
+    pub fn stroke_arc_path_follows_both_edges() {
+        let angles = ArcAngles::new(0.0, 0.5 * PI).unwrap();
+        let path = [
+            segment(Complex::new(1.0, -1.0), Complex::ONE),
+            CircularArc::new(Circle::unit_circle(), angles).into(),
+        ];
+
+        let result = stroke_to_polygon(&path, 0.2, StrokeCap::Butt, 16).unwrap();
+
+        let diagonal = Complex::from_polar(1.0, 0.25 * PI);
+        assert!(result.contains(diagonal, FillRule::NonZero));
+        assert!(result.contains(Complex::new(1.05, -0.5), FillRule::NonZero));
+        assert!(!result.contains(diagonal * 1.2.into(), FillRule::NonZero));
+        assert!(!result.contains(diagonal * 0.8.into(), FillRule::NonZero));
+    }
+
+    #[test]
+    pub fn stroke_through_infinity_returns_error() {
+        let path =
+            [segment(-Complex::ONE, Complex::ONE).transform(Isogonal::from(Mobius::INVERSION))];
+
+        let result = stroke_to_polygon(&path, 0.1, StrokeCap::Butt, 4);
+
+        assert!(result.is_err_and(|e| e.contains("infinity")));
+    }
+
+    #[test]
+    pub fn stroke_wider_than_arc_returns_error() {
+        let angles = ArcAngles::new(0.0, PI).unwrap();
+        let path = [CircularArc::new(Circle::unit_circle(), angles).into()];
+
+        let result = stroke_to_polygon(&path, 3.0, StrokeCap::Round, 4);
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,936 @@
+//! Detect the Euclidean symmetry group of a finished picture from its
+//! baked primitives. This is the inverse of building a picture from a
+//! symmetry group, and a handy check that the intended symmetry holds.
+//!
+//! Candidate symmetries are generated by mapping one reference primitive
+//! to each primitive of the same kind and size. A candidate is accepted
+//! if at least min_fraction of the primitives land on a primitive (within
+//! tolerance). Finite pictures never have exact translation symmetry, so
+//! for patches of frieze or wallpaper patterns, lower min_fraction to
+//! accept translations that only fail near the edges.
+//!
+//! With no translations, the group is a point group (cyclic or dihedral).
+//! With one or two independent translations, the rotations, mirrors and
+//! glide reflections pick out one of the 7 frieze groups or 17 wallpaper
+//! groups.
+use std::{
+    collections::HashMap,
+    error::Error,
+    f64::consts::{PI, TAU},
+    fmt::Display,
+};
+
+use crate::{
+    geometry::DirectedEdge,
+    rendering::{RenderPrimitive, Renderable},
+    Complex,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FriezeGroup {
+    P1,
+    P11G,
+    P1M1,
+    P2,
+    P2MG,
+    P11M,
+    P2MM,
+}
+
+impl Display for FriezeGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::P1 => "p1",
+            Self::P11G => "p11g",
+            Self::P1M1 => "p1m1",
+            Self::P2 => "p2",
+            Self::P2MG => "p2mg",
+            Self::P11M => "p11m",
+            Self::P2MM => "p2mm",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WallpaperGroup {
+    P1,
+    P2,
+    PM,
+    PG,
+    CM,
+    PMM,
+    PMG,
+    PGG,
+    CMM,
+    P4,
+    P4M,
+    P4G,
+    P3,
+    P3M1,
+    P31M,
+    P6,
+    P6M,
+}
+
+impl Display for WallpaperGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::P1 => "p1",
+            Self::P2 => "p2",
+            Self::PM => "pm",
+            Self::PG => "pg",
+            Self::CM => "cm",
+            Self::PMM => "pmm",
+            Self::PMG => "pmg",
+            Self::PGG => "pgg",
+            Self::CMM => "cmm",
+            Self::P4 => "p4",
+            Self::P4M => "p4m",
+            Self::P4G => "p4g",
+            Self::P3 => "p3",
+            Self::P3M1 => "p3m1",
+            Self::P31M => "p31m",
+            Self::P6 => "p6",
+            Self::P6M => "p6m",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SymmetryGroup {
+    /// Every rotation and reflection about the center, e.g. concentric
+    /// circles
+    Orthogonal,
+    /// n-fold rotations only. Cyclic(1) means no symmetry at all
+    Cyclic(usize),
+    /// n-fold rotations and n mirrors
+    Dihedral(usize),
+    Frieze(FriezeGroup),
+    Wallpaper(WallpaperGroup),
+}
+
+impl Display for SymmetryGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Orthogonal => write!(f, "O(2)"),
+            Self::Cyclic(n) => write!(f, "C{}", n),
+            Self::Dihedral(n) => write!(f, "D{}", n),
+            Self::Frieze(group) => write!(f, "frieze {}", group),
+            Self::Wallpaper(group) => write!(f, "wallpaper {}", group),
+        }
+    }
+}
+
+/// The detected group and the symmetries that were found
+#[derive(Clone, Debug)]
+pub struct SymmetryReport {
+    pub group: SymmetryGroup,
+    /// Basis of the translation lattice, with 0, 1 or 2 vectors
+    pub translations: Vec<Complex>,
+    /// Rotation centers and their order. Only the highest order found at
+    /// each center is listed
+    pub rotations: Vec<(Complex, usize)>,
+    /// Mirror lines as (point on the line, direction angle in [0, pi))
+    pub mirrors: Vec<(Complex, f64)>,
+    /// Whether there is a glide reflection whose axis is not a mirror
+    pub has_glides: bool,
+}
+
+/// A primitive reduced to what matters for matching: an anchor point,
+/// up to two end points (in either order) and a size
+#[derive(Clone, Copy)]
+struct Feature {
+    kind: u8,
+    anchor: Complex,
+    ends: Option<(Complex, Complex)>,
+    size: f64,
+}
+
+/// z -> rotation * z + translation, or with z conjugated first if flip
+#[derive(Clone, Copy)]
+struct Isometry {
+    rotation: Complex,
+    translation: Complex,
+    flip: bool,
+}
+
+impl Isometry {
+    fn apply(&self, z: Complex) -> Complex {
+        let z = if self.flip { z.conj() } else { z };
+        self.rotation * z + self.translation
+    }
+
+    fn apply_feature(&self, feature: &Feature) -> Feature {
+        Feature {
+            anchor: self.apply(feature.anchor),
+            ends: feature.ends.map(|(a, b)| (self.apply(a), self.apply(b))),
+            ..*feature
+        }
+    }
+
+    fn translation(offset: Complex) -> Self {
+        Self {
+            rotation: Complex::ONE,
+            translation: offset,
+            flip: false,
+        }
+    }
+
+    /// Rotation by angle about center
+    fn rotation(center: Complex, angle: f64) -> Self {
+        let rotation = Complex::from_polar(1.0, angle);
+        Self {
+            rotation,
+            translation: center - rotation * center,
+            flip: false,
+        }
+    }
+}
+
+fn features(primitives: &[RenderPrimitive], output: &mut Vec<Feature>) {
+    for primitive in primitives {
+        let feature = match primitive {
+            RenderPrimitive::Point(z) => Feature {
+                kind: 0,
+                anchor: *z,
+                ends: None,
+                size: 0.0,
+            },
+            RenderPrimitive::Circle(circle) => Feature {
+                kind: 1,
+                anchor: circle.center,
+                ends: None,
+                size: circle.radius,
+            },
+            RenderPrimitive::LineSegment(segment) => Feature {
+                kind: 2,
+                anchor: (segment.start + segment.end) * (0.5).into(),
+                ends: Some((segment.start, segment.end)),
+                size: (segment.end - segment.start).mag(),
+            },
+            RenderPrimitive::CircularArc(arc) => Feature {
+                kind: 3,
+                anchor: arc.interpolate(0.5),
+                ends: Some((arc.start(), arc.end())),
+                size: arc.circle.radius,
+            },
+            RenderPrimitive::QuadraticBezier(curve) => Feature {
+                kind: 4,
+                anchor: curve.interpolate(0.5),
+                ends: Some((curve.start, curve.end)),
+                size: (curve.end - curve.start).mag(),
+            },
+            RenderPrimitive::CubicBezier(curve) => Feature {
+                kind: 5,
+                anchor: curve.interpolate(0.5),
+                ends: Some((curve.start, curve.end)),
+                size: (curve.end - curve.start).mag(),
+            },
+            RenderPrimitive::Layer(layer) => {
+                features(&layer.children, output);
+                continue;
+            }
+            // Labels rarely follow the symmetry of the picture
+            RenderPrimitive::Text { .. } => continue,
+        };
+
+        if feature.anchor != Complex::Infinity {
+            output.push(feature);
+        }
+    }
+}
+
+/// Smallest difference between two angles modulo period
+fn angle_difference(a: f64, b: f64, period: f64) -> f64 {
+    let difference = (a - b).rem_euclid(period);
+    difference.min(period - difference)
+}
+
+/// Symmetry detection settings, see the module documentation
+#[derive(Clone, Copy, Debug)]
+pub struct SymmetryDetector {
+    /// How close two coordinates must be to match
+    pub tolerance: f64,
+    /// Fraction of primitives a symmetry must map onto other primitives
+    pub min_fraction: f64,
+}
+
+impl SymmetryDetector {
+    pub fn new(tolerance: f64, min_fraction: f64) -> Result<Self, String> {
+        if !(tolerance > 0.0 && tolerance.is_finite()) {
+            return Err(format!("tolerance must be positive, got {}", tolerance));
+        }
+        if !(min_fraction > 0.0 && min_fraction <= 1.0) {
+            return Err(format!(
+                "min_fraction must be in (0, 1], got {}",
+                min_fraction
+            ));
+        }
+
+        Ok(Self {
+            tolerance,
+            min_fraction,
+        })
+    }
+
+    /// Detect the symmetry group of the baked geometry, e.g. a Scene
+    pub fn detect(&self, renderable: &impl Renderable) -> Result<SymmetryReport, Box<dyn Error>> {
+        Ok(self.detect_primitives(&renderable.bake_geometry()?)?)
+    }
+
+    pub fn detect_primitives(
+        &self,
+        primitives: &[RenderPrimitive],
+    ) -> Result<SymmetryReport, String> {
+        let mut list = Vec::new();
+        features(primitives, &mut list);
+        if list.is_empty() {
+            return Err(String::from("there are no primitives to analyze"));
+        }
+
+        let matcher = Matcher::new(self, &list);
+        let centroid = list.iter().fold(Complex::Zero, |sum, x| sum + x.anchor)
+            * (1.0 / list.len() as f64).into();
+
+        let Some(reference) = self.reference(&list, centroid) else {
+            // Everything is centered on the centroid. Segments and arcs
+            // still have a direction, so look for the rotations and mirrors
+            // that map their end points onto each other
+            let ends: Vec<Feature> = list
+                .iter()
+                .flat_map(|x| {
+                    let (a, b) = x.ends?;
+                    Some([a, b].map(|end| Feature { anchor: end, ..*x }))
+                })
+                .flatten()
+                .collect();
+
+            let Some(reference) = self.reference(&ends, centroid) else {
+                // Only points and circles, all centered on the centroid
+                return Ok(SymmetryReport {
+                    group: SymmetryGroup::Orthogonal,
+                    translations: vec![],
+                    rotations: vec![],
+                    mirrors: vec![],
+                    has_glides: false,
+                });
+            };
+            let targets = self.similar(&ends, &reference);
+            return Ok(matcher.point_group(&reference, &targets, centroid));
+        };
+        let targets = self.similar(&list, &reference);
+
+        let translations = matcher.lattice(&reference, &targets);
+        if translations.is_empty() {
+            Ok(matcher.point_group(&reference, &targets, centroid))
+        } else {
+            Ok(matcher.lattice_group(&reference, &targets, translations))
+        }
+    }
+
+    /// The reference feature is the one closest to the centroid (but not
+    /// on it), so its images under symmetries are likely to be in the
+    /// picture too
+    fn reference(&self, features: &[Feature], centroid: Complex) -> Option<Feature> {
+        features
+            .iter()
+            .filter(|x| (x.anchor - centroid).mag() > self.tolerance)
+            .min_by(|a, b| {
+                let a = (a.anchor - centroid).mag();
+                let b = (b.anchor - centroid).mag();
+                a.total_cmp(&b)
+            })
+            .copied()
+    }
+
+    /// Features a symmetry could map the reference to
+    fn similar(&self, features: &[Feature], reference: &Feature) -> Vec<Feature> {
+        features
+            .iter()
+            .filter(|x| {
+                x.kind == reference.kind && (x.size - reference.size).abs() <= self.tolerance
+            })
+            .copied()
+            .collect()
+    }
+}
+
+struct Matcher<'a> {
+    tolerance: f64,
+    min_matches: usize,
+    features: &'a [Feature],
+    /// Grid of feature indices by anchor, with cells twice the tolerance
+    cells: HashMap<(i64, i64), Vec<usize>>,
+}
+
+impl<'a> Matcher<'a> {
+    fn new(detector: &SymmetryDetector, features: &'a [Feature]) -> Self {
+        let mut matcher = Self {
+            tolerance: detector.tolerance,
+            min_matches: (detector.min_fraction * features.len() as f64).ceil() as usize,
+            features,
+            cells: HashMap::new(),
+        };
+        for (i, feature) in features.iter().enumerate() {
+            let cell = matcher.cell(feature.anchor);
+            matcher.cells.entry(cell).or_default().push(i);
+        }
+        matcher
+    }
+
+    fn cell(&self, z: Complex) -> (i64, i64) {
+        let cell_size = 2.0 * self.tolerance;
+        (
+            (z.real() / cell_size).floor() as i64,
+            (z.imag() / cell_size).floor() as i64,
+        )
+    }
+
+    fn close(&self, a: Complex, b: Complex) -> bool {
+        (a - b).mag() <= self.tolerance
+    }
+
+    fn same(&self, a: &Feature, b: &Feature) -> bool {
+        if a.kind != b.kind || (a.size - b.size).abs() > self.tolerance {
+            return false;
+        }
+        if !self.close(a.anchor, b.anchor) {
+            return false;
+        }
+        match (a.ends, b.ends) {
+            (Some((a1, a2)), Some((b1, b2))) => {
+                (self.close(a1, b1) && self.close(a2, b2))
+                    || (self.close(a1, b2) && self.close(a2, b1))
+            }
+            _ => true,
+        }
+    }
+
+    fn contains(&self, feature: &Feature) -> bool {
+        if feature.anchor == Complex::Infinity {
+            return false;
+        }
+
+        let (x, y) = self.cell(feature.anchor);
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                if let Some(indices) = self.cells.get(&(x + dx, y + dy)) {
+                    if indices
+                        .iter()
+                        .any(|i| self.same(feature, &self.features[*i]))
+                    {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// Does the isometry map enough of the features onto features?
+    fn is_symmetry(&self, isometry: &Isometry) -> bool {
+        let allowed_misses = self.features.len() - self.min_matches.min(self.features.len());
+        let mut misses = 0;
+        for feature in self.features {
+            if !self.contains(&isometry.apply_feature(feature)) {
+                misses += 1;
+                if misses > allowed_misses {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Shortest translation, then the shortest one not parallel to it
+    fn lattice(&self, reference: &Feature, targets: &[Feature]) -> Vec<Complex> {
+        let mut offsets: Vec<Complex> = targets
+            .iter()
+            .map(|x| x.anchor - reference.anchor)
+            .filter(|offset| offset.mag() > self.tolerance)
+            .filter(|offset| self.is_symmetry(&Isometry::translation(*offset)))
+            .collect();
+        offsets.sort_by(|a, b| a.mag().total_cmp(&b.mag()));
+
+        let Some(&first) = offsets.first() else {
+            return vec![];
+        };
+        let second = offsets.iter().find(|offset| {
+            let sine = Complex::wedge(first, **offset) / (first.mag() * offset.mag());
+            sine.abs() > 1e-3
+        });
+        match second {
+            Some(&second) => vec![first, second],
+            None => vec![first],
+        }
+    }
+
+    fn point_group(
+        &self,
+        reference: &Feature,
+        targets: &[Feature],
+        center: Complex,
+    ) -> SymmetryReport {
+        let offset = reference.anchor - center;
+        let radius = offset.mag();
+        let reference_angle = offset.arg_or_zero();
+
+        let on_ring: Vec<f64> = targets
+            .iter()
+            .filter(|x| ((x.anchor - center).mag() - radius).abs() <= self.tolerance)
+            .filter_map(|x| (x.anchor - center).arg())
+            .collect();
+
+        // Several features can sit at the same angle, e.g. the segments
+        // of a cross, so only count each rotation once. The identity is
+        // always a symmetry
+        let mut rotation_angles: Vec<f64> = vec![0.0];
+        for angle in on_ring.iter() {
+            let rotation = (angle - reference_angle).rem_euclid(TAU);
+            let duplicate = rotation_angles
+                .iter()
+                .any(|x| angle_difference(*x, rotation, TAU) * radius <= self.tolerance);
+            if duplicate {
+                continue;
+            }
+            if self.is_symmetry(&Isometry::rotation(center, rotation)) {
+                rotation_angles.push(rotation);
+            }
+        }
+        let order = rotation_angles.len();
+
+        let mut mirror_angles: Vec<f64> = Vec::new();
+        for angle in on_ring.iter() {
+            let axis = (0.5 * (angle + reference_angle)).rem_euclid(PI);
+            let duplicate = mirror_angles
+                .iter()
+                .any(|x| angle_difference(*x, axis, PI) * radius <= self.tolerance);
+            if duplicate {
+                continue;
+            }
+
+            let rotation = Complex::from_polar(1.0, 2.0 * axis);
+            let mirror = Isometry {
+                rotation,
+                translation: center - rotation * center.conj(),
+                flip: true,
+            };
+            if self.is_symmetry(&mirror) {
+                mirror_angles.push(axis);
+            }
+        }
+
+        let group = if mirror_angles.is_empty() {
+            SymmetryGroup::Cyclic(order)
+        } else {
+            SymmetryGroup::Dihedral(order)
+        };
+        let rotations = if order > 1 {
+            vec![(center, order)]
+        } else {
+            vec![]
+        };
+        SymmetryReport {
+            group,
+            translations: vec![],
+            rotations,
+            mirrors: mirror_angles.into_iter().map(|x| (center, x)).collect(),
+            has_glides: false,
+        }
+    }
+
+    /// Is v = a * basis[0] + b * basis[1] for integers a, b?
+    fn in_lattice(&self, v: Complex, basis: &[Complex]) -> bool {
+        let coefficients = match basis {
+            [t] => {
+                if Complex::wedge(*t, v).abs() > self.tolerance * t.mag() {
+                    return false;
+                }
+                vec![Complex::dot(*t, v) / t.norm()]
+            }
+            [t1, t2] => {
+                let det = Complex::wedge(*t1, *t2);
+                vec![Complex::wedge(v, *t2) / det, Complex::wedge(*t1, v) / det]
+            }
+            _ => return v.mag() <= self.tolerance,
+        };
+        coefficients
+            .iter()
+            .zip(basis)
+            .all(|(x, t)| (x - x.round()).abs() * t.mag() <= self.tolerance)
+    }
+
+    fn lattice_group(
+        &self,
+        reference: &Feature,
+        targets: &[Feature],
+        translations: Vec<Complex>,
+    ) -> SymmetryReport {
+        let p = reference.anchor;
+
+        // Frieze groups only have half turns, and the crystallographic
+        // restriction limits wallpaper groups to these orders
+        let orders: &[usize] = if translations.len() == 1 {
+            &[2]
+        } else {
+            &[6, 4, 3, 2]
+        };
+        let mut rotations: Vec<(Complex, usize)> = Vec::new();
+        for &order in orders {
+            let omega = Complex::from_polar(1.0, TAU / order as f64);
+            for target in targets {
+                let center = (target.anchor - omega * p) / (Complex::ONE - omega);
+                let known = rotations.iter().any(|(c, _)| self.close(*c, center));
+                if !known && self.is_symmetry(&Isometry::rotation(center, TAU / order as f64)) {
+                    rotations.push((center, order));
+                }
+            }
+        }
+
+        // Mirror and glide axes run along lattice directions or
+        // perpendicular to them
+        let mut directions: Vec<f64> = Vec::new();
+        for a in -2..=2 {
+            for b in -2..=2 {
+                let vector = match translations[..] {
+                    [t1] if b == 0 => t1 * (a as f64).into(),
+                    [t1, t2] => t1 * (a as f64).into() + t2 * (b as f64).into(),
+                    _ => continue,
+                };
+                let Some(angle) = vector.arg() else {
+                    continue;
+                };
+                for direction in [angle, angle + 0.5 * PI] {
+                    let direction = direction.rem_euclid(PI);
+                    if !directions
+                        .iter()
+                        .any(|x| angle_difference(*x, direction, PI) < 1e-9)
+                    {
+                        directions.push(direction);
+                    }
+                }
+            }
+        }
+
+        let mut mirrors: Vec<(Complex, f64)> = Vec::new();
+        let mut has_glides = false;
+        for &direction in directions.iter() {
+            let rotation = Complex::from_polar(1.0, 2.0 * direction);
+            for target in targets {
+                let translation = target.anchor - rotation * p.conj();
+                let isometry = Isometry {
+                    rotation,
+                    translation,
+                    flip: true,
+                };
+                // Applying it twice translates along the axis by glide
+                let glide = rotation * translation.conj() + translation;
+                let half_glide = glide * (0.5).into();
+                let is_mirror = self.in_lattice(half_glide, &translations);
+                let axis_point = (translation - half_glide) * (0.5).into();
+                let known = is_mirror
+                    && mirrors.iter().any(|(point, angle)| {
+                        *angle == direction && on_line(axis_point, *point, *angle, self.tolerance)
+                    });
+                if known || (!is_mirror && has_glides) || !self.is_symmetry(&isometry) {
+                    continue;
+                }
+
+                if is_mirror {
+                    mirrors.push((axis_point, direction));
+                } else {
+                    has_glides = true;
+                }
+            }
+        }
+
+        let group = if translations.len() == 1 {
+            SymmetryGroup::Frieze(classify_frieze(
+                translations[0],
+                &rotations,
+                &mirrors,
+                has_glides,
+            ))
+        } else {
+            SymmetryGroup::Wallpaper(classify_wallpaper(
+                &rotations,
+                &mirrors,
+                has_glides,
+                self.tolerance,
+            ))
+        };
+
+        SymmetryReport {
+            group,
+            translations,
+            rotations,
+            mirrors,
+            has_glides,
+        }
+    }
+}
+
+fn on_line(z: Complex, point: Complex, angle: f64, tolerance: f64) -> bool {
+    Complex::wedge(Complex::from_polar(1.0, angle), z - point).abs() <= tolerance
+}
+
+fn classify_frieze(
+    translation: Complex,
+    rotations: &[(Complex, usize)],
+    mirrors: &[(Complex, f64)],
+    has_glides: bool,
+) -> FriezeGroup {
+    let axis = translation.arg_or_zero().rem_euclid(PI);
+    let along = |angle: f64| angle_difference(angle, axis, PI) < 1e-6;
+
+    let half_turn = !rotations.is_empty();
+    let horizontal = mirrors.iter().any(|(_, angle)| along(*angle));
+    let vertical = mirrors.iter().any(|(_, angle)| !along(*angle));
+
+    match (horizontal, vertical) {
+        (true, true) => FriezeGroup::P2MM,
+        (true, false) => FriezeGroup::P11M,
+        (false, true) if has_glides || half_turn => FriezeGroup::P2MG,
+        (false, true) => FriezeGroup::P1M1,
+        (false, false) if has_glides => FriezeGroup::P11G,
+        (false, false) if half_turn => FriezeGroup::P2,
+        (false, false) => FriezeGroup::P1,
+    }
+}
+
+fn classify_wallpaper(
+    rotations: &[(Complex, usize)],
+    mirrors: &[(Complex, f64)],
+    has_glides: bool,
+    tolerance: f64,
+) -> WallpaperGroup {
+    let max_order = rotations.iter().map(|(_, n)| *n).max().unwrap_or(1);
+    // Are all the centers of the highest order on a mirror?
+    let centers_on_mirrors = |order: usize| {
+        rotations
+            .iter()
+            .filter(|(_, n)| *n == order)
+            .all(|(center, _)| {
+                mirrors
+                    .iter()
+                    .any(|(point, angle)| on_line(*center, *point, *angle, tolerance))
+            })
+    };
+    let mut mirror_directions: Vec<f64> = mirrors.iter().map(|(_, angle)| *angle).collect();
+    mirror_directions.dedup_by(|a, b| angle_difference(*a, *b, PI) < 1e-6);
+    let has_mirrors = !mirrors.is_empty();
+
+    match (max_order, has_mirrors) {
+        (1, false) if has_glides => WallpaperGroup::PG,
+        (1, false) => WallpaperGroup::P1,
+        (1, true) if has_glides => WallpaperGroup::CM,
+        (1, true) => WallpaperGroup::PM,
+        (2, false) if has_glides => WallpaperGroup::PGG,
+        (2, false) => WallpaperGroup::P2,
+        (2, true) if mirror_directions.len() == 1 => WallpaperGroup::PMG,
+        (2, true) if centers_on_mirrors(2) => WallpaperGroup::PMM,
+        (2, true) => WallpaperGroup::CMM,
+        (3, false) => WallpaperGroup::P3,
+        (3, true) if centers_on_mirrors(3) => WallpaperGroup::P3M1,
+        (3, true) => WallpaperGroup::P31M,
+        (4, false) => WallpaperGroup::P4,
+        (4, true) if centers_on_mirrors(4) => WallpaperGroup::P4M,
+        (4, true) => WallpaperGroup::P4G,
+        (_, false) => WallpaperGroup::P6,
+        (_, true) => WallpaperGroup::P6M,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geometry::{Circle, LineSegment};
+
+    use super::*;
+
+    fn detector() -> SymmetryDetector {
+        SymmetryDetector::new(1e-6, 1.0).unwrap()
+    }
+
+    fn segment(a: Complex, b: Complex) -> RenderPrimitive {
+        RenderPrimitive::LineSegment(LineSegment::new(a, b))
+    }
+
+    /// Regular polygon with n sides, plus a pinwheel of short segments
+    /// that break the mirror symmetry if pinwheel is true
+    fn polygon(n: usize, pinwheel: bool) -> Vec<RenderPrimitive> {
+        let corners = Complex::roots_of_unity(n);
+        let mut primitives = Vec::new();
+        for i in 0..n {
+            let a = corners[i];
+            let b = corners[(i + 1) % n];
+            primitives.push(segment(a, b));
+            if pinwheel {
+                primitives.push(segment(a, a + b * (0.2).into()));
+            }
+        }
+        primitives
+    }
+
+    #[test]
+    pub fn new_with_invalid_settings_returns_error() {
+        assert!(SymmetryDetector::new(0.0, 1.0).is_err());
+        assert!(SymmetryDetector::new(1e-6, 0.0).is_err());
+        assert!(SymmetryDetector::new(1e-6, 1.5).is_err());
+    }
+
+    #[test]
+    pub fn detects_dihedral_group_of_polygon() {
+        let result = detector().detect_primitives(&polygon(5, false)).unwrap();
+
+        assert_eq!(result.group, SymmetryGroup::Dihedral(5));
+        assert_eq!(result.mirrors.len(), 5);
+    }
+
+    #[test]
+    pub fn detects_dihedral_group_of_crosses() {
+        // Each + has two segments with the same midpoint, so each
+        // rotation is found from several features
+        let primitives: Vec<RenderPrimitive> = Complex::roots_of_unity(4)
+            .into_iter()
+            .flat_map(|z| {
+                let (dx, dy) = (Complex::new(0.1, 0.0), Complex::new(0.0, 0.1));
+                [segment(z - dx, z + dx), segment(z - dy, z + dy)]
+            })
+            .collect();
+
+        let result = detector().detect_primitives(&primitives).unwrap();
+
+        assert_eq!(result.group, SymmetryGroup::Dihedral(4));
+        assert_eq!(result.mirrors.len(), 4);
+    }
+
+    #[test]
+    pub fn detects_cyclic_group_of_pinwheel() {
+        let result = detector().detect_primitives(&polygon(4, true)).unwrap();
+
+        assert_eq!(result.group, SymmetryGroup::Cyclic(4));
+        assert!(result.mirrors.is_empty());
+    }
+
+    #[test]
+    pub fn concentric_circles_have_orthogonal_symmetry() {
+        let primitives: Vec<RenderPrimitive> = [1.0, 2.0]
+            .into_iter()
+            .map(|r| RenderPrimitive::Circle(Circle::new(Complex::ONE, r)))
+            .collect();
+
+        let result = detector().detect_primitives(&primitives).unwrap();
+
+        assert_eq!(result.group, SymmetryGroup::Orthogonal);
+    }
+
+    #[test]
+    pub fn centered_cross_has_dihedral_symmetry() {
+        let primitives = vec![
+            segment(Complex::new(-1.0, 0.0), Complex::ONE),
+            segment(Complex::new(0.0, -1.0), Complex::I),
+        ];
+
+        let result = detector().detect_primitives(&primitives).unwrap();
+
+        assert_eq!(result.group, SymmetryGroup::Dihedral(4));
+        assert_eq!(result.mirrors.len(), 4);
+    }
+
+    #[test]
+    pub fn single_segment_has_dihedral_symmetry() {
+        let primitives = vec![segment(Complex::new(1.0, 2.0), Complex::new(3.0, 3.0))];
+
+        let result = detector().detect_primitives(&primitives).unwrap();
+
+        assert_eq!(result.group, SymmetryGroup::Dihedral(2));
+        assert_eq!(result.mirrors.len(), 2);
+    }
+
+    /// A row of copies of a motif, so the translation only fails at the
+    /// ends of the row
+    fn frieze(motif: &[(Complex, Complex)]) -> Vec<RenderPrimitive> {
+        (-5..=5)
+            .flat_map(|i| {
+                let offset = Complex::new(i as f64, 0.0);
+                motif
+                    .iter()
+                    .map(move |(a, b)| segment(*a + offset, *b + offset))
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn detects_frieze_groups() {
+        let detector = SymmetryDetector::new(1e-6, 0.8).unwrap();
+        let z = Complex::new;
+        // An L shape has no symmetry of its own
+        let hop = [(z(0.0, 0.0), z(0.0, 0.4)), (z(0.0, 0.0), z(0.2, 0.0))];
+        // A V shape is symmetric across the vertical line through its tip
+        let sidle = [(z(0.0, 0.0), z(-0.2, 0.4)), (z(0.0, 0.0), z(0.2, 0.4))];
+        // A > shape is symmetric across the axis of the frieze
+        let jump = [(z(0.0, 0.2), z(0.2, 0.0)), (z(0.0, -0.2), z(0.2, 0.0))];
+        // L-shaped footprints alternating above and below the axis
+        let step = [
+            (z(0.0, 0.1), z(0.3, 0.1)),
+            (z(0.0, 0.1), z(0.0, 0.2)),
+            (z(0.5, -0.1), z(0.8, -0.1)),
+            (z(0.5, -0.1), z(0.5, -0.2)),
+        ];
+
+        let cases = [
+            (&hop[..], FriezeGroup::P1),
+            (&sidle[..], FriezeGroup::P1M1),
+            (&jump[..], FriezeGroup::P11M),
+            (&step[..], FriezeGroup::P11G),
+        ];
+        for (motif, expected) in cases {
+            let result = detector.detect_primitives(&frieze(motif)).unwrap();
+
+            assert_eq!(result.group, SymmetryGroup::Frieze(expected));
+            assert_eq!(result.translations.len(), 1);
+        }
+    }
+
+    /// Grid of unit squares, which has the full symmetry of the square
+    /// lattice
+    #[test]
+    pub fn detects_square_grid_as_p4m() {
+        let detector = SymmetryDetector::new(1e-6, 0.7).unwrap();
+        let mut primitives = Vec::new();
+        for i in -4..=4 {
+            for j in -4..=4 {
+                let corner = Complex::new(i as f64, j as f64);
+                primitives.push(segment(corner, corner + Complex::ONE));
+                primitives.push(segment(corner, corner + Complex::I));
+            }
+        }
+
+        let result = detector.detect_primitives(&primitives).unwrap();
+
+        assert_eq!(result.group, SymmetryGroup::Wallpaper(WallpaperGroup::P4M));
+        assert_eq!(result.translations.len(), 2);
+    }
+
+    #[test]
+    pub fn detects_pinwheel_grid_as_p4() {
+        let detector = SymmetryDetector::new(1e-6, 0.7).unwrap();
+        let mut primitives = Vec::new();
+        for i in -4..=4 {
+            for j in -4..=4 {
+                let offset = Complex::new(i as f64, j as f64);
+                for corner in Complex::roots_of_unity(4) {
+                    let start = offset + corner * (0.25).into();
+                    let end = start + corner * Complex::I * (0.1).into();
+                    primitives.push(segment(start, end));
+                }
+            }
+        }
+
+        let result = detector.detect_primitives(&primitives).unwrap();
+
+        assert_eq!(result.group, SymmetryGroup::Wallpaper(WallpaperGroup::P4));
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::VecDeque;
+
+use crate::{
+    geometry::{Circle, LineSegment},
+    rendering::{Scene, Style},
+    transformable::{Cline, ClineArcTile},
+    Complex,
+};
+
+use super::CircleIndex;
+
+/// How two circles touch
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Tangency {
+    /// The circles touch from outside, like two coins on a table
+    External,
+    /// One circle touches the other from inside
+    Internal,
+}
+
+/// Check if two circles are tangent within tolerance. Concentric circles
+/// of the same size are not considered tangent.
+pub fn tangency(a: &Circle, b: &Circle, tolerance: f64) -> Option<Tangency> {
+    let distance = (b.center - a.center).mag();
+    if (distance - (a.radius + b.radius)).abs() < tolerance {
+        Some(Tangency::External)
+    } else if distance > tolerance && (distance - (a.radius - b.radius).abs()).abs() < tolerance {
+        Some(Tangency::Internal)
+    } else {
+        None
+    }
+}
+
+/// The "kissing graph" of a set of circles: one vertex per circle, and an
+/// edge between every pair of tangent circles. For circle packings like
+/// the Apollonian gasket, this graph is what determines the packing.
+pub struct TangencyGraph {
+    circles: Vec<Circle>,
+    /// (i, j, kind) with i < j
+    edges: Vec<(usize, usize, Tangency)>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl TangencyGraph {
+    /// Find all tangent pairs among the circles. A spatial index is used
+    /// so each circle is only compared with circles near it.
+    pub fn new(circles: &[Circle], tolerance: f64) -> Result<Self, String> {
+        let index = CircleIndex::with_median_size(circles)?;
+
+        let mut edges = Vec::new();
+        let mut adjacency = vec![Vec::new(); circles.len()];
+        for (i, circle) in circles.iter().enumerate() {
+            for j in index.query(circle, tolerance) {
+                if j <= i {
+                    continue;
+                }
+
+                if let Some(kind) = tangency(circle, &circles[j], tolerance) {
+                    edges.push((i, j, kind));
+                    adjacency[i].push(j);
+                    adjacency[j].push(i);
+                }
+            }
+        }
+
+        Ok(Self {
+            circles: circles.to_vec(),
+            edges,
+            adjacency,
+        })
+    }
+
+    pub fn circles(&self) -> &[Circle] {
+        &self.circles
+    }
+
+    pub fn edges(&self) -> &[(usize, usize, Tangency)] {
+        &self.edges
+    }
+
+    /// Indices of the circles tangent to circle i
+    pub fn neighbors(&self, i: usize) -> &[usize] {
+        &self.adjacency[i]
+    }
+
+    /// Number of tangency edges between each circle and the root, or None
+    /// for circles not connected to the root
+    pub fn distances_from(&self, root: usize) -> Vec<Option<usize>> {
+        let mut distances = vec![None; self.circles.len()];
+        if root >= self.circles.len() {
+            return distances;
+        }
+
+        distances[root] = Some(0);
+        let mut queue = VecDeque::from([root]);
+        while let Some(i) = queue.pop_front() {
+            let next_distance = distances[i].map(|x| x + 1);
+            for &j in &self.adjacency[i] {
+                if distances[j].is_none() {
+                    distances[j] = next_distance;
+                    queue.push_back(j);
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// The point where the two circles of an edge touch
+    pub fn tangent_point(&self, edge: &(usize, usize, Tangency)) -> Complex {
+        let (a, b) = (self.circles[edge.0], self.circles[edge.1]);
+        let offset = b.center - a.center;
+        let distance = offset.mag();
+        // For internal tangency with a inside b, the point is on the far
+        // side of a from b's center
+        let sign = match edge.2 {
+            Tangency::Internal if a.radius < b.radius => -1.0,
+            _ => 1.0,
+        };
+
+        a.center + offset * (sign * a.radius / distance).into()
+    }
+
+    /// Line segments between the centers of tangent circles, i.e. the
+    /// graph drawn with each circle's center as its vertex
+    pub fn edge_tile(&self) -> ClineArcTile {
+        ClineArcTile::new(
+            self.edges
+                .iter()
+                .map(|(i, j, _)| {
+                    LineSegment::new(self.circles[*i].center, self.circles[*j].center).into()
+                })
+                .collect(),
+        )
+    }
+
+    /// Build a scene with the circles colored by distance from the root.
+    /// Distance d is styled with styles[d], and further circles reuse the
+    /// last style. Circles not connected to the root are skipped.
+    pub fn scene_by_distance(&self, root: usize, styles: &[Style]) -> Scene {
+        let mut scene = Scene::new();
+        let distances = self.distances_from(root);
+        let Some(max_distance) = distances.iter().flatten().max().copied() else {
+            return scene;
+        };
+
+        for distance in 0..=max_distance {
+            let layer: Vec<Cline> = self
+                .circles
+                .iter()
+                .zip(distances.iter())
+                .filter(|(_, d)| **d == Some(distance))
+                .map(|(circle, _)| Cline::from(*circle))
+                .collect();
+
+            if let (false, Some(style)) = (layer.is_empty(), styles.get(distance).or(styles.last()))
+            {
+                scene.add_layer(distance as i32, *style, &layer);
+            }
+        }
+
+        scene
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Three unit circles in a row, each touching the next, inside a
+    /// circle of radius 3 that touches the two ends
+    fn row_of_circles() -> Vec<Circle> {
+        vec![
+            Circle::new(Complex::new(-2.0, 0.0), 1.0),
+            Circle::new(Complex::Zero, 1.0),
+            Circle::new(Complex::new(2.0, 0.0), 1.0),
+            Circle::new(Complex::Zero, 3.0),
+            Circle::new(Complex::new(10.0, 0.0), 1.0),
+        ]
+    }
+
+    #[test]
+    pub fn tangency_detects_external_and_internal() {
+        let a = Circle::new(Complex::Zero, 1.0);
+        let b = Circle::new(Complex::new(2.0, 0.0), 1.0);
+        let c = Circle::new(Complex::new(1.0, 0.0), 2.0);
+
+        assert_eq!(tangency(&a, &b, 1e-9), Some(Tangency::External));
+        assert_eq!(tangency(&a, &c, 1e-9), Some(Tangency::Internal));
+        assert_eq!(tangency(&a, &a, 1e-9), None);
+    }
+
+    #[test]
+    pub fn new_finds_all_tangent_pairs() {
+        let graph = TangencyGraph::new(&row_of_circles(), 1e-9).unwrap();
+
+        let pairs: Vec<(usize, usize)> = graph.edges().iter().map(|(i, j, _)| (*i, *j)).collect();
+        assert_eq!(pairs, vec![(0, 1), (0, 3), (1, 2), (2, 3)]);
+    }
+
+    #[test]
+    pub fn distances_from_counts_edges_and_skips_disconnected() {
+        let graph = TangencyGraph::new(&row_of_circles(), 1e-9).unwrap();
+
+        let result = graph.distances_from(1);
+
+        assert_eq!(result, vec![Some(1), Some(0), Some(1), Some(2), None]);
+    }
+
+    #[test]
+    pub fn tangent_point_lies_on_both_circles() {
+        let graph = TangencyGraph::new(&row_of_circles(), 1e-9).unwrap();
+
+        for edge in graph.edges() {
+            let point = graph.tangent_point(edge);
+
+            for i in [edge.0, edge.1] {
+                let circle = graph.circles()[i];
+                assert!(((point - circle.center).mag() - circle.radius).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    pub fn scene_by_distance_makes_one_layer_per_distance() {
+        let graph = TangencyGraph::new(&row_of_circles(), 1e-9).unwrap();
+
+        let result = graph.scene_by_distance(1, &[Style::stroke(255, 255, 255)]);
+
+        let z_indices: Vec<i32> = result.sorted_layers().iter().map(|x| x.z_index).collect();
+        assert_eq!(z_indices, vec![0, 1, 2]);
+    }
+}
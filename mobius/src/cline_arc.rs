@@ -1,18 +1,31 @@
 use std::fmt::Display;
 
+use abstraction::Group;
+use thiserror::Error;
+
 use crate::{
     complex_error::ComplexError,
     geometry::{
-        ArcAngles, ArcDirection, Circle, CircularArc, DoubleRay, GeneralizedCircle, Line,
-        LineSegment, Ray,
+        ArcAngles, ArcDirection, Circle, CircleError, CircularArc, DirectedEdge, DoubleRay,
+        GeneralizedCircle, Line, LineSegment, Ray,
     },
     isogonal::Isogonal,
     rendering::{RenderPrimitive, Renderable},
+    scale,
     transformable::{Cline, Transformable},
+    translation,
     unit_complex::UnitComplex,
-    Complex,
+    Complex, Mobius,
 };
 
+#[derive(Debug, Error)]
+pub enum ClineArcError {
+    #[error("{0}")]
+    InvalidComplexParam(#[from] ComplexError),
+    #[error("{0}")]
+    BadCircle(#[from] CircleError),
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum ClineArcGeometry {
     CircularArc(CircularArc),
@@ -26,6 +39,18 @@ pub enum ClineArcGeometry {
     ThruInfinity(DoubleRay),
 }
 
+impl ClineArcGeometry {
+    /// Length of the edge, see DirectedEdge::arc_length()
+    pub fn arc_length(&self) -> f64 {
+        match self {
+            Self::CircularArc(arc) => arc.arc_length(),
+            Self::LineSegment(segment) => segment.arc_length(),
+            Self::FromInfinity(ray) | Self::ToInfinity(ray) => ray.arc_length(),
+            Self::ThruInfinity(double_ray) => double_ray.arc_length(),
+        }
+    }
+}
+
 impl Display for ClineArcGeometry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -60,6 +85,12 @@ pub struct ClineArc {
 }
 
 impl ClineArc {
+    /// Create an arc that goes a -> b -> c along the cline. The points are
+    /// assumed to lie on the cline, this is not checked.
+    pub(crate) fn new(cline: Cline, a: Complex, b: Complex, c: Complex) -> Self {
+        Self { cline, a, b, c }
+    }
+
     fn compute_line_geometry(&self) -> Result<ClineArcGeometry, ComplexError> {
         if let Complex::Infinity = self.a {
             // ray goes inf -> b -> c
@@ -129,7 +160,7 @@ impl ClineArc {
     /// Implementation detail - to go from the ClineArc representation
     /// to a CircularArc, it requires computing the arc a -> b -> c. the middle
     /// point is necessary to disambiguate clockwise from counter-clockwise
-    fn compute_circle_geometry(&self, circle: Circle) -> ClineArcGeometry {
+    fn compute_circle_geometry(&self, circle: Circle) -> Result<ClineArcGeometry, CircleError> {
         let &Self { a, b, c, .. } = self;
 
         // Determine if the 3 points circulate counterclockwise or
@@ -139,9 +170,11 @@ impl ClineArc {
         let ac = c - a;
         let ccw = Complex::wedge(ab, ac) > 0.0;
 
-        // Get the raw angles
-        let theta_a = circle.get_angle(a).unwrap();
-        let theta_c = circle.get_angle(c).unwrap();
+        // Get the raw angles. The circle and the points were computed
+        // separately, so after many transformations the points may have
+        // drifted off the circle. Snap them back rather than failing
+        let theta_a = circle.closest_angle(a)?;
+        let theta_c = circle.closest_angle(c)?;
 
         let direction = if ccw {
             ArcDirection::Counterclockwise
@@ -152,15 +185,57 @@ impl ClineArc {
         let angles = ArcAngles::from_raw_angles(theta_a, theta_c, direction);
         let arc = CircularArc::new(circle, angles);
 
-        ClineArcGeometry::CircularArc(arc)
+        Ok(ClineArcGeometry::CircularArc(arc))
     }
 
-    pub fn classify(&self) -> Result<ClineArcGeometry, ComplexError> {
+    pub fn classify(&self) -> Result<ClineArcGeometry, ClineArcError> {
         match self.cline.classify()? {
-            GeneralizedCircle::Line(_) => self.compute_line_geometry(),
-            GeneralizedCircle::Circle(circle) => Ok(self.compute_circle_geometry(circle)),
+            GeneralizedCircle::Line(_) => Ok(self.compute_line_geometry()?),
+            GeneralizedCircle::Circle(circle) => Ok(self.compute_circle_geometry(circle)?),
         }
     }
+
+    pub fn start(&self) -> Complex {
+        self.a
+    }
+
+    pub fn end(&self) -> Complex {
+        self.c
+    }
+
+    /// Parallel arc at a signed distance to the right of the direction of
+    /// travel, see CircularArc::offset() and LineSegment::offset(). Arcs
+    /// of lines are translated, and arcs of circles are scaled about the
+    /// center, so points at infinity stay there.
+    pub fn offset(&self, distance: f64) -> Result<Self, String> {
+        let xform = match self.classify().map_err(|e| e.to_string())? {
+            ClineArcGeometry::CircularArc(arc) => {
+                let Circle { center, radius } = arc.circle;
+                let offset_radius = arc.offset(distance)?.circle.radius;
+                Mobius::sandwich(translation(center)?, scale(offset_radius / radius)?)
+            }
+            ClineArcGeometry::LineSegment(segment) => {
+                translation(segment.offset(distance).start - segment.start)?
+            }
+            ClineArcGeometry::ToInfinity(Ray { unit_dir, .. })
+            | ClineArcGeometry::ThruInfinity(DoubleRay(Ray { unit_dir, .. }, _)) => {
+                translation(-*unit_dir.rot90().get() * distance.into())?
+            }
+            // The ray points back towards infinity, the opposite of the
+            // direction of travel
+            ClineArcGeometry::FromInfinity(Ray { unit_dir, .. }) => {
+                translation(*unit_dir.rot90().get() * distance.into())?
+            }
+        };
+
+        Ok(self.transform(Isogonal::from(xform)))
+    }
+
+    /// Length of the arc. This is infinite if the arc passes through
+    /// infinity
+    pub fn arc_length(&self) -> Result<f64, ClineArcError> {
+        Ok(self.classify()?.arc_length())
+    }
 }
 
 impl From<CircularArc> for ClineArc {
@@ -254,3 +329,69 @@ impl Display for ClineArc {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use crate::nearly::assert_nearly;
+
+    use super::*;
+
+    #[test]
+    pub fn classify_snaps_points_that_drifted_off_the_circle() -> Result<(), ClineArcError> {
+        // The end points are 1e-4 off the unit circle, like points that
+        // went through many more transformations than the cline
+        let drift = 1.0 + 1e-4;
+        let arc = ClineArc::new(
+            Cline::unit_circle(),
+            Complex::new(drift, 0.0),
+            Complex::I,
+            Complex::new(-drift, 0.0),
+        );
+
+        let ClineArcGeometry::CircularArc(result) = arc.classify()? else {
+            panic!("expected a circular arc");
+        };
+
+        assert_eq!(result.start(), Complex::ONE);
+        assert_eq!(result.end(), -Complex::ONE);
+        assert_eq!(result.direction(), ArcDirection::Counterclockwise);
+        Ok(())
+    }
+
+    #[test]
+    pub fn arc_length_of_circular_arc() -> Result<(), ClineArcError> {
+        let angles = ArcAngles::new(0.0, PI).unwrap();
+        let arc = ClineArc::from(CircularArc::new(Circle::unit_circle(), angles));
+
+        assert_nearly(arc.arc_length()?, PI);
+        Ok(())
+    }
+
+    #[test]
+    pub fn arc_length_through_infinity_is_infinite() -> Result<(), ClineArcError> {
+        let segment = ClineArc::from(LineSegment::new(-Complex::ONE, Complex::ONE));
+
+        let result = segment.transform(Isogonal::from(Mobius::INVERSION));
+
+        assert_eq!(result.arc_length()?, f64::INFINITY);
+        Ok(())
+    }
+
+    #[test]
+    pub fn offset_ray_keeps_point_at_infinity() {
+        let segment = ClineArc::from(LineSegment::new(-Complex::ONE, Complex::ONE));
+        let ray = segment.transform(Isogonal::from(Mobius::INVERSION));
+
+        let result = ray.offset(2.0).unwrap();
+
+        let original = ray.classify().unwrap();
+        let ClineArcGeometry::ThruInfinity(DoubleRay(out_ray, _)) = original else {
+            panic!("expected a ray pair, got {}", original);
+        };
+        let shift = -*out_ray.unit_dir.rot90().get() * 2.0.into();
+        assert_eq!(result.start(), ray.start() + shift);
+        assert_eq!(result.end(), ray.end() + shift);
+    }
+}
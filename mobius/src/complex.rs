@@ -2,7 +2,7 @@ use core::f64;
 use std::fmt::{self, Display};
 use std::ops::{Add, Div, Mul, Neg, Sub};
 
-use crate::nearly::is_nearly;
+use crate::{nearly::is_nearly, unit_complex::UnitComplex};
 
 #[derive(Copy, Clone, Debug)]
 pub enum Complex {
@@ -94,6 +94,26 @@ impl Complex {
         }
     }
 
+    /// Like arg(), but returns 0 for zero and infinity by convention, for
+    /// callers that only need some reference angle
+    pub fn arg_or_zero(&self) -> f64 {
+        self.arg().unwrap_or(0.0)
+    }
+
+    /// Polar form (r, theta), the inverse of from_polar(). Zero has
+    /// angle 0 by convention. Returns None for infinity
+    pub fn to_polar(&self) -> Option<(f64, f64)> {
+        match self {
+            Complex::Infinity => None,
+            z => Some((z.mag(), z.arg_or_zero())),
+        }
+    }
+
+    /// Scale to magnitude 1. Returns None for zero and infinity
+    pub fn normalize(&self) -> Option<UnitComplex> {
+        UnitComplex::normalize(*self).ok()
+    }
+
     pub fn conj(&self) -> Self {
         match self {
             Complex::Zero => Complex::Zero,
@@ -129,6 +149,102 @@ impl Complex {
         }
     }
 
+    /// e^z. Returns None for z = infinity, which is an essential
+    /// singularity
+    pub fn exp(&self) -> Option<Self> {
+        match self {
+            Complex::Zero => Some(Complex::ONE),
+            Complex::Infinity => None,
+            Complex::Finite(a, b) => {
+                let r = a.exp();
+                if r.is_infinite() {
+                    return Some(Complex::Infinity);
+                }
+
+                let (s, c) = b.sin_cos();
+                Some(Complex::new(r * c, r * s))
+            }
+        }
+    }
+
+    /// Principal branch of the natural log, with imaginary part in
+    /// (-pi, pi]. Both 0 and infinity are sent to infinity
+    pub fn ln(&self) -> Self {
+        match self {
+            Complex::Zero => Complex::Infinity,
+            Complex::Infinity => Complex::Infinity,
+            Complex::Finite(a, b) => Complex::new(self.mag().ln(), b.atan2(*a)),
+        }
+    }
+
+    /// sin(z). Returns None for z = infinity
+    pub fn sin(&self) -> Option<Self> {
+        match self {
+            Complex::Zero => Some(Complex::Zero),
+            Complex::Infinity => None,
+            // sin(a + bi) = sin(a)cosh(b) + i cos(a)sinh(b)
+            Complex::Finite(a, b) => {
+                if b.cosh().is_infinite() {
+                    return Some(Complex::Infinity);
+                }
+
+                let (s, c) = a.sin_cos();
+                Some(Complex::new(s * b.cosh(), c * b.sinh()))
+            }
+        }
+    }
+
+    /// cos(z). Returns None for z = infinity
+    pub fn cos(&self) -> Option<Self> {
+        match self {
+            Complex::Zero => Some(Complex::ONE),
+            Complex::Infinity => None,
+            // cos(a + bi) = cos(a)cosh(b) - i sin(a)sinh(b)
+            Complex::Finite(a, b) => {
+                if b.cosh().is_infinite() {
+                    return Some(Complex::Infinity);
+                }
+
+                let (s, c) = a.sin_cos();
+                Some(Complex::new(c * b.cosh(), -s * b.sinh()))
+            }
+        }
+    }
+
+    /// tan(z). The poles at pi/2 + k pi are sent to infinity. Returns
+    /// None for z = infinity
+    pub fn tan(&self) -> Option<Self> {
+        match self {
+            // Far from the real axis, tan(z) approaches +/- i, but
+            // sin(z) and cos(z) overflow.
+            Complex::Finite(_, b) if b.abs() > 20.0 => Some(Complex::new(0.0, b.signum())),
+            _ => Some(self.sin()? / self.cos()?),
+        }
+    }
+
+    /// Principal value of z^w = e^(w ln z). For z = 0 or infinity, the
+    /// result depends on the sign of Re(w). Returns None for the
+    /// indeterminate cases, i.e. w = infinity, 0^w or infinity^w with
+    /// Re(w) = 0 (except 0^0 = 1) and infinity^0
+    pub fn powc(&self, w: Complex) -> Option<Self> {
+        match (self, w) {
+            (_, Complex::Infinity) => None,
+            (Complex::Infinity, Complex::Zero) => None,
+            (_, Complex::Zero) => Some(Complex::ONE),
+            (Complex::Zero, w) if w.real() > 0.0 => Some(Complex::Zero),
+            (Complex::Zero, w) if w.real() < 0.0 => Some(Complex::Infinity),
+            (Complex::Infinity, w) if w.real() > 0.0 => Some(Complex::Infinity),
+            (Complex::Infinity, w) if w.real() < 0.0 => Some(Complex::Zero),
+            (Complex::Finite(_, _), w) => (w * self.ln()).exp(),
+            _ => None,
+        }
+    }
+
+    /// Principal value of z^x for a real exponent. See powc()
+    pub fn powf(&self, x: f64) -> Option<Self> {
+        self.powc(x.into())
+    }
+
     pub fn dot(a: Complex, b: Complex) -> f64 {
         (a * b.conj()).real()
     }
@@ -350,4 +466,163 @@ mod test {
 
         assert_eq!(result, pole)
     }
+
+    #[test_case(Complex::Zero, Complex::ONE; "zero")]
+    #[test_case(Complex::new(0.0, f64::consts::PI), Complex::new(-1.0, 0.0); "euler identity")]
+    #[test_case(Complex::new(2.0f64.ln(), f64::consts::FRAC_PI_2), Complex::new(0.0, 2.0); "2i")]
+    #[test_case(Complex::new(1000.0, 0.0), Complex::Infinity; "overflow")]
+    #[test_case(Complex::new(-1000.0, 0.0), Complex::Zero; "underflow")]
+    pub fn exp_computes_correct_value(z: Complex, expected: Complex) {
+        let result = z.exp().unwrap();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn exp_of_infinity_is_none() {
+        let result = Complex::Infinity.exp();
+
+        assert!(result.is_none());
+    }
+
+    #[test_case(Complex::Zero, Complex::Infinity; "zero")]
+    #[test_case(Complex::Infinity, Complex::Infinity; "infinity")]
+    #[test_case(Complex::new(-1.0, 0.0), Complex::new(0.0, f64::consts::PI); "negative one")]
+    #[test_case(Complex::new(0.0, -3.0), Complex::new(3.0f64.ln(), -f64::consts::FRAC_PI_2); "negative imaginary")]
+    pub fn ln_computes_principal_branch(z: Complex, expected: Complex) {
+        let result = z.ln();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn exp_undoes_ln() {
+        let z = Complex::new(-2.0, 0.5);
+
+        let result = z.ln().exp().unwrap();
+
+        assert_eq!(result, z);
+    }
+
+    #[test]
+    pub fn sin_and_cos_satisfy_pythagorean_identity() {
+        let z = Complex::new(0.7, -1.3);
+        let s = z.sin().unwrap();
+        let c = z.cos().unwrap();
+
+        let result = s * s + c * c;
+
+        assert_eq!(result, Complex::ONE);
+    }
+
+    #[test]
+    pub fn sin_and_cos_of_imaginary_are_hyperbolic() {
+        let z = Complex::new(0.0, 2.0);
+
+        assert_eq!(z.sin().unwrap(), Complex::new(0.0, 2.0f64.sinh()));
+        assert_eq!(z.cos().unwrap(), Complex::new(2.0f64.cosh(), 0.0));
+    }
+
+    #[test_case(Complex::new(f64::consts::FRAC_PI_2, 0.0), Complex::Infinity; "pole")]
+    #[test_case(Complex::new(f64::consts::FRAC_PI_4, 0.0), Complex::ONE; "pi over 4")]
+    #[test_case(Complex::new(1.0, 100.0), Complex::I; "far above real axis")]
+    #[test_case(Complex::new(1.0, -100.0), -Complex::I; "far below real axis")]
+    pub fn tan_computes_correct_value(z: Complex, expected: Complex) {
+        let result = z.tan().unwrap();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test_case(Complex::Infinity; "infinity")]
+    pub fn trig_of_infinity_is_none(z: Complex) {
+        assert!(z.sin().is_none());
+        assert!(z.cos().is_none());
+        assert!(z.tan().is_none());
+    }
+
+    #[test]
+    pub fn powc_computes_i_to_the_i() {
+        let result = Complex::I.powc(Complex::I).unwrap();
+
+        // i^i = e^(i * i pi/2) = e^(-pi/2)
+        assert_eq!(result, Complex::new((-f64::consts::FRAC_PI_2).exp(), 0.0));
+    }
+
+    #[test_case(Complex::Zero, Complex::new(2.0, 1.0), Complex::Zero; "zero to positive power")]
+    #[test_case(Complex::Zero, Complex::new(-2.0, 1.0), Complex::Infinity; "zero to negative power")]
+    #[test_case(Complex::Zero, Complex::Zero, Complex::ONE; "zero to the zero")]
+    #[test_case(Complex::Infinity, Complex::new(0.5, 0.0), Complex::Infinity; "infinity to positive power")]
+    #[test_case(Complex::Infinity, Complex::new(-0.5, 0.0), Complex::Zero; "infinity to negative power")]
+    pub fn powc_handles_poles(z: Complex, w: Complex, expected: Complex) {
+        let result = z.powc(w).unwrap();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test_case(Complex::Zero, Complex::I; "zero to imaginary power")]
+    #[test_case(Complex::Infinity, Complex::Zero; "infinity to the zero")]
+    #[test_case(Complex::new(2.0, 0.0), Complex::Infinity; "infinite exponent")]
+    pub fn powc_indeterminate_forms_are_none(z: Complex, w: Complex) {
+        let result = z.powc(w);
+
+        assert!(result.is_none());
+    }
+
+    #[test]
+    pub fn powf_matches_sqrt() {
+        let z = Complex::new(-3.0, 4.0);
+
+        let result = z.powf(0.5).unwrap();
+
+        assert_eq!(result, z.sqrt());
+    }
+
+    #[test]
+    pub fn powf_computes_integer_power() {
+        let z = Complex::new(1.0, 1.0);
+
+        let result = z.powf(4.0).unwrap();
+
+        assert_eq!(result, Complex::new(-4.0, 0.0));
+    }
+
+    #[test_case(Complex::Zero, (0.0, 0.0); "zero")]
+    #[test_case(Complex::new(-2.0, 0.0), (2.0, f64::consts::PI); "negative real")]
+    #[test_case(Complex::new(0.0, -3.0), (3.0, -f64::consts::FRAC_PI_2); "negative imaginary")]
+    pub fn to_polar_computes_magnitude_and_angle(z: Complex, expected: (f64, f64)) {
+        let (r, theta) = z.to_polar().unwrap();
+
+        assert_eq!((r, theta), expected);
+        assert_eq!(Complex::from_polar(r, theta), z);
+    }
+
+    #[test]
+    pub fn to_polar_of_infinity_is_none() {
+        let result = Complex::Infinity.to_polar();
+
+        assert!(result.is_none());
+    }
+
+    #[test_case(Complex::Zero; "zero")]
+    #[test_case(Complex::Infinity; "infinity")]
+    pub fn arg_or_zero_of_poles_is_zero(pole: Complex) {
+        let result = pole.arg_or_zero();
+
+        assert_eq!(result, 0.0);
+    }
+
+    #[test]
+    pub fn normalize_scales_to_unit_length() {
+        let result = Complex::new(3.0, 4.0).normalize().unwrap();
+
+        assert_eq!(*result.get(), Complex::new(0.6, 0.8));
+    }
+
+    #[test_case(Complex::Zero; "zero")]
+    #[test_case(Complex::Infinity; "infinity")]
+    pub fn normalize_of_poles_is_none(pole: Complex) {
+        let result = pole.normalize();
+
+        assert!(result.is_none());
+    }
 }
@@ -0,0 +1,199 @@
+//! Tables of group elements for inspecting the algebra of a set of
+//! generators before deciding how to render it. Each row describes the
+//! element at one word: the matrix entries, trace, classification and
+//! fixed points. The table can be written as CSV (e.g. for a spreadsheet)
+//! or JSON.
+use std::fmt::Write;
+
+use crate::{
+    address::FractalAddress,
+    algorithms::GroupIFS,
+    rendering::json_export::{write_point, write_string},
+    Complex, FixedPoints, Mobius,
+};
+
+/// A group element and the word that produced it
+pub struct ElementRow {
+    pub address: FractalAddress,
+    pub xform: Mobius,
+}
+
+impl ElementRow {
+    /// Classification of the element, see Mobius::classify(). The identity
+    /// is listed separately since its trace makes it look parabolic
+    pub fn classification(&self) -> String {
+        if self.xform == Mobius::IDENTITY {
+            String::from("identity")
+        } else {
+            self.xform.classify().to_string()
+        }
+    }
+
+    /// Both fixed points. A single fixed point is repeated, and the
+    /// identity (which fixes everything) has no fixed points
+    pub fn fixed_points(&self) -> Option<(Complex, Complex)> {
+        if self.xform == Mobius::IDENTITY {
+            return None;
+        }
+
+        match self.xform.fixed_points() {
+            FixedPoints::Single(z) => Some((z, z)),
+            FixedPoints::Pair(p, q) => Some((p, q)),
+        }
+    }
+
+    fn entries(&self) -> [(&'static str, Complex); 5] {
+        let Mobius { a, b, c, d } = self.xform;
+        [
+            ("a", a),
+            ("b", b),
+            ("c", c),
+            ("d", d),
+            ("trace", self.xform.trace()),
+        ]
+    }
+}
+
+/// Every element of the group generated by the IFS up to the given word
+/// length, in breadth-first order. If unique is true, elements that were
+/// already reached by a shorter (or earlier) word are skipped, see
+/// GroupIFS::bfs_unique()
+pub fn list_elements(ifs: &GroupIFS<Mobius>, max_length: usize, unique: bool) -> Vec<ElementRow> {
+    let elements = if unique {
+        ifs.bfs_unique(max_length, Mobius::quantized_key)
+    } else {
+        ifs.bfs(max_length).collect()
+    };
+
+    elements
+        .into_iter()
+        .map(|(address, xform)| ElementRow { address, xform })
+        .collect()
+}
+
+/// Format the table as CSV lines, starting with the header. Complex
+/// numbers are split into _re and _im columns. The identity word is
+/// written as an empty string
+pub fn format_csv(rows: &[ElementRow]) -> Vec<String> {
+    let mut header = vec![String::from("word"), String::from("length")];
+    for name in ["a", "b", "c", "d", "trace", "fixed1", "fixed2"] {
+        header.push(format!("{}_re", name));
+        header.push(format!("{}_im", name));
+    }
+    header.push(String::from("classification"));
+
+    let mut lines = vec![header.join(",")];
+    for row in rows {
+        let mut values = vec![row.address.to_string(), row.address.len().to_string()];
+        let mut push_complex = |z: Option<Complex>| match z {
+            Some(z) => {
+                values.push(z.real().to_string());
+                values.push(z.imag().to_string());
+            }
+            None => values.extend([String::new(), String::new()]),
+        };
+        for (_, z) in row.entries() {
+            push_complex(Some(z));
+        }
+        let fixed_points = row.fixed_points();
+        push_complex(fixed_points.map(|(p, _)| p));
+        push_complex(fixed_points.map(|(_, q)| q));
+        values.push(row.classification());
+
+        lines.push(values.join(","));
+    }
+
+    lines
+}
+
+/// Format the table as a JSON array with one object per element.
+/// Complex numbers are [re, im] pairs like the scene JSON format, and
+/// infinite values are null
+pub fn format_json(rows: &[ElementRow]) -> String {
+    let mut output = String::from("[");
+    for (i, row) in rows.iter().enumerate() {
+        output.push_str(if i > 0 { ",\n  " } else { "\n  " });
+        output.push_str("{\"word\": ");
+        write_string(&mut output, &row.address.to_string());
+        let _ = write!(output, ", \"length\": {}", row.address.len());
+        for (name, z) in row.entries() {
+            let _ = write!(output, ", \"{}\": ", name);
+            write_point(&mut output, z);
+        }
+        output.push_str(", \"classification\": ");
+        write_string(&mut output, &row.classification());
+        output.push_str(", \"fixed_points\": [");
+        if let Some((p, q)) = row.fixed_points() {
+            write_point(&mut output, p);
+            output.push_str(", ");
+            write_point(&mut output, q);
+        }
+        output.push_str("]}");
+    }
+    output.push_str("\n]\n");
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rotation, translation};
+
+    use super::*;
+
+    fn make_ifs() -> GroupIFS<Mobius> {
+        let a = translation(Complex::ONE).unwrap();
+        let b = rotation(std::f64::consts::PI).unwrap();
+        GroupIFS::new(vec![a, b])
+    }
+
+    #[test]
+    pub fn list_elements_with_unique_skips_repeats() {
+        let ifs = make_ifs();
+
+        let all = list_elements(&ifs, 2, false);
+        let unique = list_elements(&ifs, 2, true);
+
+        // b is a half turn, so bb = BB = identity
+        assert_eq!(all.len(), 17);
+        assert!(unique.len() < all.len());
+        assert_eq!(unique[0].classification(), "identity");
+    }
+
+    #[test]
+    pub fn format_csv_has_one_line_per_element() {
+        let ifs = make_ifs();
+        let rows = list_elements(&ifs, 1, false);
+
+        let result = format_csv(&rows);
+
+        assert_eq!(result.len(), rows.len() + 1);
+        assert!(result[0].starts_with("word,length,a_re,a_im,"));
+        assert!(result[0].ends_with(",fixed2_im,classification"));
+        // translation by 1 fixes only infinity
+        assert_eq!(
+            result[2],
+            "a,1,1,0,1,0,0,0,1,0,2,0,inf,inf,inf,inf,parabolic"
+        );
+        // the identity has no fixed points
+        assert_eq!(result[1], ",0,1,0,0,0,0,0,1,0,2,0,,,,,identity");
+    }
+
+    #[test]
+    pub fn format_json_writes_infinity_as_null() {
+        let a = translation(Complex::ONE).unwrap();
+        let rows = vec![ElementRow {
+            address: FractalAddress::try_from("a").unwrap(),
+            xform: a,
+        }];
+
+        let result = format_json(&rows);
+
+        assert_eq!(
+            result,
+            "[\n  {\"word\": \"a\", \"length\": 1, \"a\": [1, 0], \"b\": [1, 0], \
+            \"c\": [0, 0], \"d\": [1, 0], \"trace\": [2, 0], \
+            \"classification\": \"parabolic\", \
+            \"fixed_points\": [[null, null], [null, null]]}\n]\n"
+        );
+    }
+}
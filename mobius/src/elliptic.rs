@@ -0,0 +1,384 @@
+use std::f64::consts::PI;
+
+use crate::{geometry::LineSegment, polygon::Polygon, transformable::ClineArcTile, Complex};
+
+/// Shorter warped segments are dropped, they can't be drawn
+const MIN_LENGTH: f64 = 1e-9;
+
+/// Terms of the q-series to sum. After lattice reduction |q| < 0.005, so
+/// this is far more than double precision needs
+const SERIES_TERMS: usize = 12;
+
+/// Lattice of periods m * omega1 + n * omega2 for integers m, n. Doubly
+/// periodic functions repeat over each translate of the fundamental cell,
+/// the parallelogram spanned by the two periods.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lattice {
+    omega1: Complex,
+    omega2: Complex,
+}
+
+impl Lattice {
+    pub fn new(omega1: Complex, omega2: Complex) -> Result<Self, String> {
+        if omega1 == Complex::Infinity || omega2 == Complex::Infinity {
+            return Err(String::from("periods must be finite"));
+        }
+
+        if Complex::wedge(omega1, omega2).abs() < 1e-12 * omega1.norm().max(omega2.norm()) {
+            return Err(format!(
+                "periods must be linearly independent, got {} and {}",
+                omega1, omega2
+            ));
+        }
+
+        Ok(Self { omega1, omega2 })
+    }
+
+    pub fn periods(&self) -> (Complex, Complex) {
+        (self.omega1, self.omega2)
+    }
+
+    /// The fundamental cell with corners 0, omega1, omega1 + omega2, omega2
+    pub fn fundamental_cell(&self) -> Polygon {
+        Polygon::new(vec![
+            Complex::Zero,
+            self.omega1,
+            self.omega1 + self.omega2,
+            self.omega2,
+        ])
+        .unwrap()
+    }
+
+    /// The lattice point m * omega1 + n * omega2
+    pub fn point(&self, m: i64, n: i64) -> Complex {
+        self.omega1 * (m as f64).into() + self.omega2 * (n as f64).into()
+    }
+
+    /// Translate z by a lattice vector into the parallelogram centered at
+    /// the origin. Doubly periodic functions are unchanged by this.
+    pub fn reduce(&self, z: Complex) -> Complex {
+        let (a, b) = self.coordinates(z);
+        z - self.point(a.round() as i64, b.round() as i64)
+    }
+
+    /// Real coordinates (a, b) with z = a * omega1 + b * omega2
+    fn coordinates(&self, z: Complex) -> (f64, f64) {
+        let det = Complex::wedge(self.omega1, self.omega2);
+        (
+            Complex::wedge(z, self.omega2) / det,
+            Complex::wedge(self.omega1, z) / det,
+        )
+    }
+
+    /// An equivalent basis (w1, tau) with the lattice equal to
+    /// w1 * (Z + tau Z), tau in the upper half plane with |tau| >= 1 and
+    /// |Re(tau)| <= 1/2. This is Lagrange-Gauss reduction, and it keeps
+    /// q = exp(2 pi i tau) small so the q-series converge quickly.
+    fn reduced_basis(&self) -> (Complex, Complex) {
+        let (mut u, mut v) = (self.omega1, self.omega2);
+        loop {
+            if v.norm() < u.norm() {
+                (u, v) = (v, u);
+            }
+            let k = (Complex::dot(v, u) / u.norm()).round();
+            if k == 0.0 {
+                break;
+            }
+            v = v - u * k.into();
+        }
+
+        let tau = v / u;
+        if tau.imag() < 0.0 {
+            (u, -tau)
+        } else {
+            (u, tau)
+        }
+    }
+}
+
+/// exp(2 pi i z)
+fn exp_2pi_i(z: Complex) -> Complex {
+    Complex::from_polar((-2.0 * PI * z.imag()).exp(), 2.0 * PI * z.real())
+}
+
+/// The Weierstrass elliptic function ℘ of a lattice. It is even, doubly
+/// periodic with the lattice periods, and has a double pole at each
+/// lattice point, so it maps each fundamental cell twice over the whole
+/// Riemann sphere. Together with its derivative it satisfies
+///
+/// ℘'(z)^2 = 4℘(z)^3 - g2 ℘(z) - g3
+///
+/// This is the basic building block for doubly periodic conformal
+/// artwork. Values are computed from q-series for the reduced lattice
+/// rather than the slowly converging lattice sum.
+#[derive(Clone, Copy, Debug)]
+pub struct WeierstrassP {
+    lattice: Lattice,
+    /// Reduced basis, see Lattice::reduced_basis()
+    scale: Complex,
+    tau: Complex,
+    g2: Complex,
+    g3: Complex,
+}
+
+impl WeierstrassP {
+    pub fn new(lattice: Lattice) -> Self {
+        let (scale, tau) = lattice.reduced_basis();
+        let q = exp_2pi_i(tau);
+
+        // Eisenstein series E4 = 1 + 240 sum(n^3 q^n / (1 - q^n)) and
+        // E6 = 1 - 504 sum(n^5 q^n / (1 - q^n))
+        let (mut e4, mut e6) = (Complex::ONE, Complex::ONE);
+        let mut q_n = Complex::ONE;
+        for n in 1..=SERIES_TERMS {
+            q_n = q_n * q;
+            let term = q_n / (Complex::ONE - q_n);
+            let n = n as f64;
+            e4 = e4 + term * (240.0 * n.powi(3)).into();
+            e6 = e6 - term * (504.0 * n.powi(5)).into();
+        }
+
+        // Invariants of Z + tau Z, scaled to the actual lattice
+        let scale2 = scale * scale;
+        let g2 = e4 * (4.0 * PI.powi(4) / 3.0).into() / (scale2 * scale2);
+        let g3 = e6 * (8.0 * PI.powi(6) / 27.0).into() / (scale2 * scale2 * scale2);
+
+        Self {
+            lattice,
+            scale,
+            tau,
+            g2,
+            g3,
+        }
+    }
+
+    pub fn lattice(&self) -> &Lattice {
+        &self.lattice
+    }
+
+    /// The invariants (g2, g3) from the differential equation
+    pub fn invariants(&self) -> (Complex, Complex) {
+        (self.g2, self.g3)
+    }
+
+    /// Reduce z to the cell around the origin in the normalized lattice
+    /// Z + tau Z, or None at a lattice point
+    fn normalize(&self, z: Complex) -> Option<Complex> {
+        if z == Complex::Infinity {
+            return None;
+        }
+
+        let u = self.lattice.reduce(z) / self.scale;
+        let b = (u.imag() / self.tau.imag()).round();
+        let u = u - self.tau * b.into();
+        let u = u - u.real().round().into();
+        if u.mag() < 1e-12 {
+            None
+        } else {
+            Some(u)
+        }
+    }
+
+    /// ℘(z). Lattice points (and infinity, where ℘ has an essential
+    /// singularity) map to infinity
+    pub fn eval(&self, z: Complex) -> Complex {
+        let Some(u) = self.normalize(z) else {
+            return Complex::Infinity;
+        };
+
+        // With q = exp(2 pi i tau) and w = exp(2 pi i u),
+        // ℘(u) = (2 pi i)^2 (1/12 + sum over n in Z of q^n w / (1 - q^n w)^2
+        //   - 2 sum over n >= 1 of q^n / (1 - q^n)^2)
+        let q = exp_2pi_i(self.tau);
+        let w = exp_2pi_i(u);
+        let f = |x: Complex| x / ((Complex::ONE - x) * (Complex::ONE - x));
+
+        let mut sum = f(w) + (1.0 / 12.0).into();
+        let mut q_n = Complex::ONE;
+        for _ in 0..SERIES_TERMS {
+            q_n = q_n * q;
+            // Terms for n < 0 are rewritten in terms of 1/w so they are
+            // also small
+            sum = sum + f(q_n * w) + f(q_n / w) - f(q_n) * 2.0.into();
+        }
+
+        let two_pi_i = Complex::new(0.0, 2.0 * PI);
+        two_pi_i * two_pi_i * sum / (self.scale * self.scale)
+    }
+
+    /// ℘'(z), which is odd and has triple poles at the lattice points
+    pub fn derivative(&self, z: Complex) -> Complex {
+        let Some(u) = self.normalize(z) else {
+            return Complex::Infinity;
+        };
+
+        // Differentiating the series for ℘ term by term,
+        // ℘'(u) = (2 pi i)^3 sum over n in Z of q^n w (1 + q^n w) / (1 - q^n w)^3
+        let q = exp_2pi_i(self.tau);
+        let w = exp_2pi_i(u);
+        let f = |x: Complex| {
+            let denominator = Complex::ONE - x;
+            x * (Complex::ONE + x) / (denominator * denominator * denominator)
+        };
+
+        let mut sum = f(w);
+        let mut q_n = Complex::ONE;
+        for _ in 0..SERIES_TERMS {
+            q_n = q_n * q;
+            sum = sum + f(q_n * w) - f(q_n / w);
+        }
+
+        let two_pi_i = Complex::new(0.0, 2.0 * PI);
+        two_pi_i * two_pi_i * two_pi_i * sum / (self.scale * self.scale * self.scale)
+    }
+
+    /// Apply ℘ to each point, e.g. to warp a point cloud
+    pub fn warp_points(&self, points: &[Complex]) -> Vec<Complex> {
+        points.iter().map(|z| self.eval(*z)).collect()
+    }
+
+    /// Warp a polyline through ℘, returning the segments between
+    /// consecutive images. ℘ is not a Möbius transformation, so lines and
+    /// circles bend into other curves, and the polyline should be sampled
+    /// finely enough for the images to look smooth. Segments that touch a
+    /// pole are skipped.
+    pub fn warp_path(&self, path: &[Complex]) -> ClineArcTile {
+        let images = self.warp_points(path);
+        ClineArcTile::new(
+            images
+                .windows(2)
+                .filter(|pair| !pair.contains(&Complex::Infinity))
+                .filter(|pair| (pair[1] - pair[0]).mag() > MIN_LENGTH)
+                .map(|pair| LineSegment::new(pair[0], pair[1]).into())
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn square_lattice() -> WeierstrassP {
+        WeierstrassP::new(Lattice::new(Complex::ONE, Complex::I).unwrap())
+    }
+
+    fn skewed_lattice() -> WeierstrassP {
+        let omega = Complex::from_polar(1.0, PI / 3.0);
+        WeierstrassP::new(Lattice::new(Complex::new(2.0, 0.5), omega).unwrap())
+    }
+
+    fn assert_close(a: Complex, b: Complex, tolerance: f64) {
+        let scale = a.mag().max(b.mag()).max(1.0);
+        assert!((a - b).mag() < tolerance * scale, "{} != {}", a, b);
+    }
+
+    #[test]
+    pub fn new_with_parallel_periods_returns_error() {
+        let result = Lattice::new(Complex::ONE, Complex::new(-2.0, 0.0));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn reduce_moves_point_near_origin() {
+        let lattice = Lattice::new(Complex::ONE, Complex::new(0.5, 1.0)).unwrap();
+        let z = Complex::new(0.1, 0.2);
+
+        let result = lattice.reduce(z + lattice.point(3, -2));
+
+        assert_close(result, z, 1e-12);
+    }
+
+    #[test]
+    pub fn eval_near_origin_is_close_to_double_pole() {
+        let p = skewed_lattice();
+        let z = Complex::new(1e-3, 2e-3);
+
+        let result = p.eval(z);
+
+        // ℘(z) = 1 / z^2 + O(z^2)
+        assert_close(result, (z * z).inverse(), 1e-5);
+    }
+
+    #[test]
+    pub fn eval_at_lattice_point_is_infinity() {
+        let p = skewed_lattice();
+
+        let result = p.eval(p.lattice().point(2, 1));
+
+        assert_eq!(result, Complex::Infinity);
+    }
+
+    #[test]
+    pub fn eval_is_doubly_periodic_and_even() {
+        let p = skewed_lattice();
+        let (omega1, omega2) = p.lattice().periods();
+        let z = Complex::new(0.3, -0.4);
+
+        let value = p.eval(z);
+
+        assert_close(p.eval(z + omega1), value, 1e-10);
+        assert_close(p.eval(z - omega2 * 3.0.into()), value, 1e-10);
+        assert_close(p.eval(-z), value, 1e-10);
+    }
+
+    #[test]
+    pub fn derivative_is_odd() {
+        let p = skewed_lattice();
+        let z = Complex::new(0.3, -0.4);
+
+        let result = p.derivative(-z);
+
+        assert_close(result, -p.derivative(z), 1e-10);
+    }
+
+    #[test]
+    pub fn derivative_matches_finite_difference() {
+        let p = skewed_lattice();
+        let z = Complex::new(0.7, 0.2);
+        let h = Complex::new(1e-5, 0.0);
+
+        let result = p.derivative(z);
+
+        let difference = (p.eval(z + h) - p.eval(z - h)) / (h * 2.0.into());
+        assert_close(result, difference, 1e-6);
+    }
+
+    #[test]
+    pub fn satisfies_differential_equation() {
+        for p in [square_lattice(), skewed_lattice()] {
+            let (g2, g3) = p.invariants();
+            for z in [Complex::new(0.3, 0.1), Complex::new(-0.2, 0.45)] {
+                let value = p.eval(z);
+                let slope = p.derivative(z);
+
+                let rhs = value * value * value * 4.0.into() - g2 * value - g3;
+                assert_close(slope * slope, rhs, 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    pub fn square_lattice_has_g3_zero() {
+        let (g2, g3) = square_lattice().invariants();
+
+        // g2 = Γ(1/4)^8 / (16 π^2) for the square lattice Z + iZ
+        assert_close(g2, Complex::new(189.07272012923, 0.0), 1e-10);
+        assert!(g3.mag() < 1e-9);
+    }
+
+    #[test]
+    pub fn warp_path_skips_poles() {
+        let p = square_lattice();
+        let path = [
+            Complex::new(-0.5, 0.0),
+            Complex::Zero,
+            Complex::new(0.5, 0.0),
+        ];
+
+        let result = p.warp_path(&path);
+
+        assert!(result.get_primitives().is_empty());
+    }
+}
@@ -0,0 +1,433 @@
+//! Mobius transformations with Gaussian integer entries, for groups like
+//! the modular group SL(2, Z) and the Apollonian gasket group where every
+//! element can be computed exactly. Equality and hashing are exact, so
+//! elements can be deduplicated in a HashSet without the false positives
+//! of Mobius::quantized_key().
+use std::{
+    fmt::Display,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+use abstraction::{Group, GroupAction, Monoid};
+
+use crate::{Complex, Mobius, Tolerance};
+
+/// How far a float can be from an integer to be rounded to it in
+/// ExactMobius::from_mobius(), at the default tolerance
+const ROUNDING_TOLERANCE: f64 = 1e-9;
+
+/// Complex number a + bi with integer parts. The arithmetic operators
+/// panic on overflow rather than silently wrapping, use the checked_*
+/// methods to get an error instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GaussianInteger {
+    pub re: i64,
+    pub im: i64,
+}
+
+impl GaussianInteger {
+    pub const ZERO: Self = Self::new(0, 0);
+    pub const ONE: Self = Self::new(1, 0);
+    pub const I: Self = Self::new(0, 1);
+
+    pub const fn new(re: i64, im: i64) -> Self {
+        Self { re, im }
+    }
+
+    /// Round a complex number to the nearest Gaussian integer if it is
+    /// within tolerance of it
+    pub fn from_complex(z: Complex, tolerance: f64) -> Result<Self, String> {
+        let (re, im) = (z.real().round(), z.imag().round());
+        if z == Complex::Infinity || (z - Complex::new(re, im)).mag() > tolerance {
+            return Err(format!("{} is not a Gaussian integer", z));
+        }
+
+        Ok(Self::new(re as i64, im as i64))
+    }
+
+    pub fn to_complex(&self) -> Complex {
+        Complex::new(self.re as f64, self.im as f64)
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Result<Self, String> {
+        match (self.re.checked_add(rhs.re), self.im.checked_add(rhs.im)) {
+            (Some(re), Some(im)) => Ok(Self::new(re, im)),
+            _ => Err(format!("overflow computing ({}) + ({})", self, rhs)),
+        }
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Result<Self, String> {
+        match (self.re.checked_sub(rhs.re), self.im.checked_sub(rhs.im)) {
+            (Some(re), Some(im)) => Ok(Self::new(re, im)),
+            _ => Err(format!("overflow computing ({}) - ({})", self, rhs)),
+        }
+    }
+
+    pub fn checked_neg(self) -> Result<Self, String> {
+        match (self.re.checked_neg(), self.im.checked_neg()) {
+            (Some(re), Some(im)) => Ok(Self::new(re, im)),
+            _ => Err(format!("overflow computing -({})", self)),
+        }
+    }
+
+    pub fn checked_mul(self, rhs: Self) -> Result<Self, String> {
+        let (a, b, c, d) = (self.re, self.im, rhs.re, rhs.im);
+        let re = a
+            .checked_mul(c)
+            .zip(b.checked_mul(d))
+            .and_then(|(ac, bd)| ac.checked_sub(bd));
+        let im = a
+            .checked_mul(d)
+            .zip(b.checked_mul(c))
+            .and_then(|(ad, bc)| ad.checked_add(bc));
+        match (re, im) {
+            (Some(re), Some(im)) => Ok(Self::new(re, im)),
+            _ => Err(format!("overflow computing ({}) * ({})", self, rhs)),
+        }
+    }
+
+    /// Sign convention used to pick between M and -M: positive if the
+    /// real part is positive, or it's zero and the imaginary part is
+    /// positive
+    fn is_positive(&self) -> bool {
+        self.re > 0 || (self.re == 0 && self.im > 0)
+    }
+}
+
+impl From<i64> for GaussianInteger {
+    fn from(value: i64) -> Self {
+        Self::new(value, 0)
+    }
+}
+
+impl Add for GaussianInteger {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl Sub for GaussianInteger {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl Neg for GaussianInteger {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self.checked_neg().unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl Mul for GaussianInteger {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(rhs).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl Display for GaussianInteger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} + {}i", self.re, self.im)
+    }
+}
+
+/// Mobius transformation with Gaussian integer entries and determinant 1.
+/// Since M and -M are the same transformation, the matrix is stored with
+/// its first nonzero entry positive (see GaussianInteger) so the derived
+/// equality and hashing are exact.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ExactMobius {
+    a: GaussianInteger,
+    b: GaussianInteger,
+    c: GaussianInteger,
+    d: GaussianInteger,
+}
+
+impl ExactMobius {
+    pub const IDENTITY: Self = Self {
+        a: GaussianInteger::ONE,
+        b: GaussianInteger::ZERO,
+        c: GaussianInteger::ZERO,
+        d: GaussianInteger::ONE,
+    };
+
+    pub fn new(
+        a: GaussianInteger,
+        b: GaussianInteger,
+        c: GaussianInteger,
+        d: GaussianInteger,
+    ) -> Result<Self, String> {
+        let det = a.checked_mul(d)?.checked_sub(b.checked_mul(c)?)?;
+        if det != GaussianInteger::ONE {
+            return Err(format!("ad - bc must equal 1, got {}", det));
+        }
+
+        Self::checked_canonical(a, b, c, d)
+    }
+
+    /// Convert a Mobius whose entries are (within rounding error)
+    /// Gaussian integers, like the ones from gasket_group()
+    pub fn from_mobius(m: Mobius) -> Result<Self, String> {
+        let rounding_tolerance = Tolerance::current().scale_relative(ROUNDING_TOLERANCE);
+        let entries =
+            [m.a, m.b, m.c, m.d].map(|z| GaussianInteger::from_complex(z, rounding_tolerance));
+        let [a, b, c, d] = entries;
+        Self::new(a?, b?, c?, d?)
+    }
+
+    fn checked_canonical(
+        a: GaussianInteger,
+        b: GaussianInteger,
+        c: GaussianInteger,
+        d: GaussianInteger,
+    ) -> Result<Self, String> {
+        let leading = [a, b, c, d]
+            .into_iter()
+            .find(|x| *x != GaussianInteger::ZERO)
+            .expect("determinant 1 matrices are nonzero");
+
+        if leading.is_positive() {
+            Ok(Self { a, b, c, d })
+        } else {
+            Ok(Self {
+                a: a.checked_neg()?,
+                b: b.checked_neg()?,
+                c: c.checked_neg()?,
+                d: d.checked_neg()?,
+            })
+        }
+    }
+
+    /// checked_canonical() for the operators, which panic on overflow
+    fn canonical(
+        a: GaussianInteger,
+        b: GaussianInteger,
+        c: GaussianInteger,
+        d: GaussianInteger,
+    ) -> Self {
+        Self::checked_canonical(a, b, c, d).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// The entries [a, b, c, d], with the sign chosen as described above
+    pub fn entries(&self) -> [GaussianInteger; 4] {
+        [self.a, self.b, self.c, self.d]
+    }
+
+    /// Trace a + d. Like the matrix, this is only defined up to sign
+    pub fn trace(&self) -> GaussianInteger {
+        self.a + self.d
+    }
+
+    pub fn to_mobius(&self) -> Mobius {
+        let [a, b, c, d] = self.entries().map(|x| x.to_complex());
+        Mobius::from_unnormalized(a, b, c, d).expect("entries are finite with determinant 1")
+    }
+}
+
+impl Mul for ExactMobius {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let Self { a, b, c, d } = self;
+        let Self {
+            a: e,
+            b: f,
+            c: g,
+            d: h,
+        } = rhs;
+
+        Self::canonical(a * e + b * g, a * f + b * h, c * e + d * g, c * f + d * h)
+    }
+}
+
+impl Monoid for ExactMobius {
+    fn identity() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Group for ExactMobius {
+    fn inverse(&self) -> Self {
+        let &Self { a, b, c, d } = self;
+        Self::canonical(d, -b, -c, a)
+    }
+}
+
+impl GroupAction<Complex> for ExactMobius {
+    fn act(&self, x: &Complex) -> Complex {
+        self.to_mobius() * *x
+    }
+}
+
+impl Display for ExactMobius {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {}; {}, {}]", self.a, self.b, self.c, self.d)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use abstraction::{test_associativity, test_group, test_identity};
+
+    use crate::{algorithms::GroupIFS, gasket_group, nearly::EPSILON};
+
+    use super::*;
+
+    fn int(x: i64) -> GaussianInteger {
+        x.into()
+    }
+
+    /// Generators S: z -> -1/z and T: z -> z + 1 of the modular group
+    fn modular_generators() -> (ExactMobius, ExactMobius) {
+        let s = ExactMobius::new(int(0), int(-1), int(1), int(0)).unwrap();
+        let t = ExactMobius::new(int(1), int(1), int(0), int(1)).unwrap();
+        (s, t)
+    }
+
+    test_identity!(
+        ExactMobius,
+        [
+            (identity_s, modular_generators().0),
+            (identity_t, modular_generators().1)
+        ]
+    );
+
+    test_associativity!(
+        ExactMobius,
+        [(
+            assoc_s_t_st,
+            modular_generators().0,
+            modular_generators().1,
+            modular_generators().0 * modular_generators().1
+        )]
+    );
+
+    test_group!(
+        ExactMobius,
+        [(group_s_t, modular_generators().0, modular_generators().1)]
+    );
+
+    #[test]
+    pub fn modular_group_relations_hold_exactly() {
+        let (s, t) = modular_generators();
+
+        assert_eq!(s * s, ExactMobius::IDENTITY);
+        assert_eq!(Group::pow(&(s * t), 3), ExactMobius::IDENTITY);
+    }
+
+    #[test]
+    pub fn negated_matrix_is_same_element() {
+        let m = ExactMobius::new(int(2), int(1), int(1), int(1)).unwrap();
+        let negated = ExactMobius::new(int(-2), int(-1), int(-1), int(-1)).unwrap();
+
+        assert_eq!(m, negated);
+        assert_eq!(HashSet::from([m, negated]).len(), 1);
+    }
+
+    #[test]
+    pub fn checked_neg_of_min_returns_error() {
+        let z = GaussianInteger::new(i64::MIN, 0);
+
+        let result = z.checked_neg();
+
+        assert!(result.is_err_and(|e| e.contains("overflow")));
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow")]
+    pub fn neg_of_min_panics() {
+        let z = GaussianInteger::new(0, i64::MIN);
+
+        let _ = -z;
+    }
+
+    #[test]
+    pub fn checked_mul_overflow_returns_error() {
+        let z = GaussianInteger::new(i64::MAX / 2, 3);
+
+        let result = z.checked_mul(z);
+
+        assert!(result.is_err_and(|e| e.contains("overflow")));
+    }
+
+    #[test]
+    pub fn new_with_overflowing_determinant_returns_error() {
+        let big = int(i64::MAX);
+
+        let result = ExactMobius::new(big, big, big, big);
+
+        assert!(result.is_err_and(|e| e.contains("overflow")));
+    }
+
+    #[test]
+    pub fn new_with_wrong_determinant_returns_error() {
+        let result = ExactMobius::new(int(2), int(0), int(0), int(1));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn from_mobius_converts_gasket_group() {
+        let (a, b) = gasket_group();
+
+        let exact_a = ExactMobius::from_mobius(a).unwrap();
+        let exact_b = ExactMobius::from_mobius(b).unwrap();
+
+        // The commutator is parabolic
+        let commutator = ExactMobius::commutator(exact_a, exact_b);
+        assert!([int(2), int(-2)].contains(&commutator.trace()));
+        assert_eq!(exact_b.to_mobius(), b);
+    }
+
+    #[test]
+    pub fn from_mobius_with_non_integer_entries_returns_error() {
+        let m = crate::scale(4.0).unwrap();
+
+        let result = ExactMobius::from_mobius(m);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn from_mobius_rounding_follows_current_tolerance() {
+        let m = crate::translation(Complex::new(2.0 + 1e-11, 0.0)).unwrap();
+        let tight = Tolerance::new(EPSILON, EPSILON * 1e-3).unwrap();
+
+        let default_result = ExactMobius::from_mobius(m);
+        let tight_result = tight.scope(|| ExactMobius::from_mobius(m));
+
+        assert!(default_result.is_ok());
+        assert!(tight_result.is_err());
+    }
+
+    #[test]
+    pub fn act_matches_mobius() {
+        let (s, t) = modular_generators();
+        let m = s * t * t;
+        let z = Complex::new(0.3, 0.7);
+
+        assert_eq!(m.act(&z), m.to_mobius() * z);
+    }
+
+    #[test]
+    pub fn bfs_unique_with_exact_keys_matches_quantized_keys() {
+        let (a, b) = gasket_group();
+        let exact_gens = [a, b].map(|x| ExactMobius::from_mobius(x).unwrap());
+        let ifs = GroupIFS::new(vec![a, b]);
+        let exact_ifs = GroupIFS::new(exact_gens.to_vec());
+
+        let quantized = ifs.word_length_counts(4, Mobius::quantized_key);
+        let exact = exact_ifs.word_length_counts(4, |x| *x);
+
+        assert_eq!(exact, quantized);
+    }
+}
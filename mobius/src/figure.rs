@@ -0,0 +1,260 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use crate::{rendering::Scene, Complex};
+
+/// Description of a single numeric parameter of a figure
+#[derive(Clone, Copy, Debug)]
+pub struct ParamSpec {
+    pub name: &'static str,
+    pub default: f64,
+    pub description: &'static str,
+}
+
+impl ParamSpec {
+    pub const fn new(name: &'static str, default: f64, description: &'static str) -> Self {
+        Self {
+            name,
+            default,
+            description,
+        }
+    }
+}
+
+/// Values for a figure's parameters, keyed by name
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Params(BTreeMap<String, f64>);
+
+impl Params {
+    /// Fill in every parameter with its default value
+    pub fn from_defaults(specs: &[ParamSpec]) -> Self {
+        Self(
+            specs
+                .iter()
+                .map(|spec| (String::from(spec.name), spec.default))
+                .collect(),
+        )
+    }
+
+    /// Override a parameter value. Returns an error if the parameter
+    /// was not declared by the figure.
+    pub fn set(&mut self, name: &str, value: f64) -> Result<(), String> {
+        match self.0.get_mut(name) {
+            Some(x) => {
+                *x = value;
+                Ok(())
+            }
+            None => Err(format!("unknown parameter {}", name)),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Result<f64, String> {
+        self.0
+            .get(name)
+            .copied()
+            .ok_or(format!("unknown parameter {}", name))
+    }
+
+    /// Get a parameter that is used as a count. The value is rounded to
+    /// the nearest integer, and must be at least min.
+    pub fn get_count(&self, name: &str, min: usize) -> Result<usize, String> {
+        let value = self.get(name)?.round();
+        if !value.is_finite() || value < min as f64 {
+            return Err(format!("{} must be an integer >= {}", name, min));
+        }
+        Ok(value as usize)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &f64)> {
+        self.0.iter()
+    }
+}
+
+/// A parametric artwork. Figures declare their parameters up front so
+/// tools like the sweep example can vary them without editing code.
+pub trait Figure {
+    /// Short identifier used on the command line
+    fn name(&self) -> &'static str;
+
+    /// The parameters this figure understands, with their defaults
+    fn params(&self) -> Vec<ParamSpec>;
+
+    /// The center and half-width of the region to render
+    fn view(&self) -> (Complex, f64);
+
+    /// Build the scene for the given parameter values
+    fn build(&self, params: &Params) -> Result<Scene, String>;
+
+    fn default_params(&self) -> Params {
+        Params::from_defaults(&self.params())
+    }
+}
+
+/// Parse a parameter assignment of the form name=value
+pub fn parse_assignment(text: &str) -> Result<(String, f64), String> {
+    let (name, value) = text
+        .split_once('=')
+        .ok_or(format!("expected name=value, got {}", text))?;
+    let value: f64 = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid value for {}: {}", name, value))?;
+    Ok((String::from(name.trim()), value))
+}
+
+/// A linear sweep of a single parameter, parsed from the format
+/// param=start:end:steps. Both endpoints are included.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sweep {
+    pub param: String,
+    pub start: f64,
+    pub end: f64,
+    pub steps: usize,
+}
+
+impl Sweep {
+    /// The parameter values in order from start to end
+    pub fn values(&self) -> Vec<f64> {
+        if self.steps == 1 {
+            return vec![self.start];
+        }
+
+        let step_size = (self.end - self.start) / ((self.steps - 1) as f64);
+        (0..self.steps)
+            .map(|i| self.start + (i as f64) * step_size)
+            .collect()
+    }
+}
+
+impl FromStr for Sweep {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (param, range) = s
+            .split_once('=')
+            .ok_or(format!("expected param=start:end:steps, got {}", s))?;
+
+        let parts: Vec<&str> = range.split(':').collect();
+        let [start, end, steps] = parts[..] else {
+            return Err(format!("expected start:end:steps, got {}", range));
+        };
+
+        let start: f64 = start
+            .parse()
+            .map_err(|_| format!("invalid start value: {}", start))?;
+        let end: f64 = end
+            .parse()
+            .map_err(|_| format!("invalid end value: {}", end))?;
+        let steps: usize = steps
+            .parse()
+            .map_err(|_| format!("invalid step count: {}", steps))?;
+        if steps == 0 {
+            return Err(String::from("steps must be at least 1"));
+        }
+
+        Ok(Self {
+            param: String::from(param.trim()),
+            start,
+            end,
+            steps,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SPECS: [ParamSpec; 2] = [
+        ParamSpec::new("k", 1.5, "scale factor"),
+        ParamSpec::new("depth", 4.0, "recursion depth"),
+    ];
+
+    #[test]
+    pub fn from_defaults_uses_default_values() {
+        let params = Params::from_defaults(&SPECS);
+
+        assert_eq!(params.get("k"), Ok(1.5));
+        assert_eq!(params.get("depth"), Ok(4.0));
+    }
+
+    #[test]
+    pub fn set_with_unknown_param_returns_error() {
+        let mut params = Params::from_defaults(&SPECS);
+
+        let result = params.set("bogus", 1.0);
+
+        assert!(result.is_err_and(|e| e.contains("unknown parameter bogus")));
+    }
+
+    #[test]
+    pub fn get_count_rounds_value() {
+        let mut params = Params::from_defaults(&SPECS);
+        params.set("depth", 2.9).unwrap();
+
+        let result = params.get_count("depth", 1);
+
+        assert_eq!(result, Ok(3));
+    }
+
+    #[test]
+    pub fn get_count_below_min_returns_error() {
+        let mut params = Params::from_defaults(&SPECS);
+        params.set("depth", 0.0).unwrap();
+
+        let result = params.get_count("depth", 1);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn parse_assignment_parses_name_and_value() {
+        let result = parse_assignment("k=2.5");
+
+        assert_eq!(result, Ok((String::from("k"), 2.5)));
+    }
+
+    #[test]
+    pub fn sweep_parses_range() {
+        let result: Result<Sweep, String> = "k=1:2:5".parse();
+
+        let expected = Sweep {
+            param: String::from("k"),
+            start: 1.0,
+            end: 2.0,
+            steps: 5,
+        };
+        assert_eq!(result, Ok(expected));
+    }
+
+    #[test]
+    pub fn sweep_with_missing_steps_returns_error() {
+        let result: Result<Sweep, String> = "k=1:2".parse();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn sweep_with_zero_steps_returns_error() {
+        let result: Result<Sweep, String> = "k=1:2:0".parse();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn values_includes_endpoints() {
+        let sweep: Sweep = "k=1:2:5".parse().unwrap();
+
+        let result = sweep.values();
+
+        assert_eq!(result, vec![1.0, 1.25, 1.5, 1.75, 2.0]);
+    }
+
+    #[test]
+    pub fn values_with_one_step_returns_start() {
+        let sweep: Sweep = "k=3:5:1".parse().unwrap();
+
+        let result = sweep.values();
+
+        assert_eq!(result, vec![3.0]);
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{
+    algorithms::{chaos_game, scene_by_trap_distance, trap_distances, OrbitTrap},
+    figure::{Figure, ParamSpec, Params},
+    geometry::Circle,
+    rendering::{ColorRGB, Scene, Style},
+    scale, translation, Complex,
+};
+
+/// Chaos game for the Sierpinski triangle, with points colored by their
+/// distance to a circle through the middle of the triangle. Points close
+/// to the trap are bright yellow, fading to purple further away.
+pub struct OrbitTrapSierpinski;
+
+impl Figure for OrbitTrapSierpinski {
+    fn name(&self) -> &'static str {
+        "orbit_trap"
+    }
+
+    fn params(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::new("points", 20000.0, "number of chaos game iterations"),
+            ParamSpec::new(
+                "history",
+                2.0,
+                "how many previous points the trap remembers",
+            ),
+            ParamSpec::new("trap_radius", 0.25, "radius of the circle trap"),
+            ParamSpec::new("seed", 1.0, "random seed"),
+        ]
+    }
+
+    fn view(&self) -> (Complex, f64) {
+        (Complex::new(0.5, 0.5), 0.6)
+    }
+
+    fn build(&self, params: &Params) -> Result<Scene, String> {
+        let points = params.get_count("points", 1)?;
+        let history = params.get_count("history", 0)?;
+        let trap_radius = params.get("trap_radius")?;
+        let seed = params.get_count("seed", 0)? as u64;
+
+        let half = scale(0.5)?;
+        let xforms = [Complex::Zero, Complex::ONE, Complex::I]
+            .into_iter()
+            .map(|corner| Ok(translation(corner * 0.5.into())? * half))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let orbit = chaos_game(&xforms, Complex::Zero, points, None, seed)?;
+        let trap = OrbitTrap::Circle(Circle::new(Complex::new(1.0 / 3.0, 1.0 / 3.0), trap_radius));
+        let distances = trap_distances(&orbit, &trap, history);
+
+        const BANDS: usize = 6;
+        let styles: Vec<Style> = (0..BANDS)
+            .map(|i| {
+                let t = i as f64 / (BANDS - 1) as f64;
+                let fade = |a: f64, b: f64| (a + t * (b - a)) as u8;
+                Style {
+                    fill: Some(ColorRGB(
+                        fade(255.0, 96.0),
+                        fade(220.0, 0.0),
+                        fade(0.0, 160.0),
+                    )),
+                    ..Style::new()
+                }
+            })
+            .collect();
+        Ok(scene_by_trap_distance(&orbit, &distances, 0.5, &styles))
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{
+    figure::{Figure, ParamSpec, Params},
+    motifs::{mystic_rose, times_table, ChordKind},
+    rendering::{Scene, Style},
+    transformable::{Cline, Motif},
+    Complex,
+};
+
+fn chord_kind(params: &Params) -> Result<ChordKind, String> {
+    if params.get("orthogonal")? != 0.0 {
+        Ok(ChordKind::Orthogonal)
+    } else {
+        Ok(ChordKind::Straight)
+    }
+}
+
+/// One style per chord class, fading from bright (short chords) to
+/// dark (diameters)
+fn class_styles(n: usize) -> Vec<Style> {
+    let classes = n.div_ceil(2).max(1);
+    (0..classes)
+        .map(|i| {
+            let t = i as f64 / classes as f64;
+            let brightness = (255.0 * (1.0 - 0.75 * t)) as u8;
+            Style::stroke(brightness, brightness / 2, 255 - brightness / 2).with_width(0.125)
+        })
+        .collect()
+}
+
+fn chord_scene(motif: &Motif, n: usize) -> Scene {
+    let mut scene = Scene::new();
+    scene.add_layer(
+        0,
+        Style::stroke(127, 127, 127).with_width(0.25),
+        &[Cline::unit_circle()],
+    );
+    scene.add_motifs(1, std::slice::from_ref(motif), &class_styles(n));
+    scene
+}
+
+/// Every chord between n points evenly spaced around a circle, colored
+/// by chord length
+pub struct MysticRose;
+
+impl Figure for MysticRose {
+    fn name(&self) -> &'static str {
+        "mystic_rose"
+    }
+
+    fn params(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::new("n", 18.0, "number of points around the circle"),
+            ParamSpec::new("orthogonal", 0.0, "1 for orthogonal arcs, 0 for chords"),
+        ]
+    }
+
+    fn view(&self) -> (Complex, f64) {
+        (Complex::Zero, 1.1)
+    }
+
+    fn build(&self, params: &Params) -> Result<Scene, String> {
+        let n = params.get_count("n", 2)?;
+        let motif = mystic_rose(n, chord_kind(params)?)?;
+        Ok(chord_scene(&motif, n))
+    }
+}
+
+/// Modular multiplication table: point k connects to point
+/// multiplier * k (mod n). The default multiplier draws a cardioid
+pub struct TimesTable;
+
+impl Figure for TimesTable {
+    fn name(&self) -> &'static str {
+        "times_table"
+    }
+
+    fn params(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::new("n", 200.0, "number of points around the circle"),
+            ParamSpec::new("multiplier", 2.0, "multiplier for the times table"),
+            ParamSpec::new("orthogonal", 0.0, "1 for orthogonal arcs, 0 for chords"),
+        ]
+    }
+
+    fn view(&self) -> (Complex, f64) {
+        (Complex::Zero, 1.1)
+    }
+
+    fn build(&self, params: &Params) -> Result<Scene, String> {
+        let n = params.get_count("n", 2)?;
+        let multiplier = params.get("multiplier")?;
+        let motif = times_table(n, multiplier, chord_kind(params)?)?;
+        Ok(chord_scene(&motif, n))
+    }
+}
@@ -0,0 +1,101 @@
+use std::f64::consts::{PI, TAU};
+
+use abstraction::Group;
+
+use crate::{
+    algorithms::GridIFS,
+    elliptic,
+    figure::{Figure, ParamSpec, Params},
+    hyperbolic, loxodromic,
+    motifs::{bone, ghost, skull},
+    rendering::{Scene, Style},
+    rotation, scale,
+    transformable::Transformable,
+    translation, Complex, Mobius,
+};
+
+/// Ghosts arranged along a loxodromic double spiral from -i to i.
+/// Parametric version of the ghost_double_spiral artwork
+pub struct GhostDoubleSpiral;
+
+impl Figure for GhostDoubleSpiral {
+    fn name(&self) -> &'static str {
+        "ghost_double_spiral"
+    }
+
+    fn params(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::new("k_real", 1.5, "magnitude part of the loxodromic multiplier"),
+            ParamSpec::new("k_imag", 1.1, "twist part of the loxodromic multiplier"),
+            ParamSpec::new("arms", 4.0, "number of spiral arms"),
+        ]
+    }
+
+    fn view(&self) -> (Complex, f64) {
+        (Complex::Zero, 1.0)
+    }
+
+    fn build(&self, params: &Params) -> Result<Scene, String> {
+        let k = Complex::new(params.get("k_real")?, params.get("k_imag")?);
+        let arms = params.get_count("arms", 1)?;
+
+        let (ghost, ghost_style) = ghost();
+        let small_ghost = ghost.transform(scale(0.125)?);
+
+        // Rotate so the spiral goes from -i to i instead of -1 to 1
+        let rotate90 = rotation(PI / 2.0)?;
+        let vertical_spiral = Mobius::sandwich(rotate90, loxodromic(k)?);
+        let arm_rotation = Mobius::sandwich(rotate90, elliptic(TAU / arms as f64)?);
+
+        let grid = GridIFS::new(vec![
+            (vertical_spiral, -8, 8),
+            (arm_rotation, 0, arms as isize),
+        ]);
+        let ghosts = grid.apply(&small_ghost);
+
+        let mut scene = Scene::new();
+        scene.add_layer(0, ghost_style, &ghosts);
+        Ok(scene)
+    }
+}
+
+/// Bones pulled toward a sink below a skull, like a rib cage.
+/// Parametric version of the rib_cage artwork
+pub struct RibCage;
+
+impl Figure for RibCage {
+    fn name(&self) -> &'static str {
+        "rib_cage"
+    }
+
+    fn params(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::new("k", 1.6, "hyperbolic scale factor between ribs"),
+            ParamSpec::new("ribs", 10.0, "number of ribs on each side of the center"),
+        ]
+    }
+
+    fn view(&self) -> (Complex, f64) {
+        (Complex::new(0.0, 1.0), 1.5)
+    }
+
+    fn build(&self, params: &Params) -> Result<Scene, String> {
+        let k = params.get("k")?;
+        let ribs = params.get_count("ribs", 0)? as isize;
+
+        let smaller = scale(0.9)?;
+        let shift_up = translation(Complex::new(0.0, 2.0))?;
+        let head = skull().transform(shift_up * smaller);
+        let rot4 = rotation(PI / 2.0)?;
+        let rib = bone(20.0).transform(rot4 * smaller);
+        let pull_down = Mobius::sandwich(rot4, hyperbolic(k)?);
+        let cage = GridIFS::new(vec![(pull_down, -ribs, ribs)]);
+        let rib_cage = cage.apply(&rib);
+
+        let white_lines = Style::stroke(255, 255, 255).with_width(0.25);
+        let mut scene = Scene::new();
+        scene.add_layer(0, white_lines, &rib_cage);
+        scene.add_layer(0, white_lines, &[head]);
+        Ok(scene)
+    }
+}
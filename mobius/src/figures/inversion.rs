@@ -0,0 +1,118 @@
+use std::f64::consts::PI;
+
+use crate::{
+    algorithms::{scene_by_depth, InversionFractal, TangencyGraph},
+    figure::{Figure, ParamSpec, Params},
+    geometry::Circle,
+    rendering::{Scene, Style},
+    Complex,
+};
+
+/// A ring of mirror circles centered on the roots of unity, each touching
+/// its neighbors. Inverting the mirrors in each other fills in the
+/// gaps with smaller and smaller circles. Each depth gets a darker color
+pub struct InversionRing;
+
+impl Figure for InversionRing {
+    fn name(&self) -> &'static str {
+        "inversion_ring"
+    }
+
+    fn params(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::new("n", 4.0, "number of mirror circles"),
+            ParamSpec::new("depth", 6.0, "maximum number of inversions"),
+            ParamSpec::new("min_radius", 0.005, "smallest circle to draw"),
+        ]
+    }
+
+    fn view(&self) -> (Complex, f64) {
+        (Complex::Zero, 2.0)
+    }
+
+    fn build(&self, params: &Params) -> Result<Scene, String> {
+        let n = params.get_count("n", 3)?;
+        let depth = params.get_count("depth", 0)?;
+        let min_radius = params.get("min_radius")?;
+
+        let radius = (PI / n as f64).sin();
+        let mirrors: Vec<Circle> = Complex::roots_of_unity(n)
+            .into_iter()
+            .map(|center| Circle::new(center, radius))
+            .collect();
+
+        let fractal = InversionFractal::new(&mirrors)?;
+        let circles = fractal.generate(&mirrors, depth, min_radius);
+
+        let styles: Vec<Style> = (0..=depth)
+            .map(|d| {
+                let fade = (255.0 * (1.0 - d as f64 / (depth + 1) as f64)) as u8;
+                Style::stroke(fade, 255 - fade / 2, 255).with_width(0.25)
+            })
+            .collect();
+        Ok(scene_by_depth(&circles, &styles))
+    }
+}
+
+/// The same circles as InversionRing, but colored by how many tangencies
+/// away they are from the first mirror. The tangency graph is drawn on
+/// top, with an edge between the centers of each pair of kissing circles.
+pub struct KissingGraph;
+
+impl Figure for KissingGraph {
+    fn name(&self) -> &'static str {
+        "kissing_graph"
+    }
+
+    fn params(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::new("n", 4.0, "number of mirror circles"),
+            ParamSpec::new("depth", 4.0, "maximum number of inversions"),
+            ParamSpec::new("min_radius", 0.01, "smallest circle to draw"),
+            ParamSpec::new(
+                "tolerance",
+                1e-9,
+                "how close circles must be to count as tangent",
+            ),
+        ]
+    }
+
+    fn view(&self) -> (Complex, f64) {
+        (Complex::Zero, 2.0)
+    }
+
+    fn build(&self, params: &Params) -> Result<Scene, String> {
+        let n = params.get_count("n", 3)?;
+        let depth = params.get_count("depth", 0)?;
+        let min_radius = params.get("min_radius")?;
+        let tolerance = params.get("tolerance")?;
+
+        let radius = (PI / n as f64).sin();
+        let mirrors: Vec<Circle> = Complex::roots_of_unity(n)
+            .into_iter()
+            .map(|center| Circle::new(center, radius))
+            .collect();
+
+        let fractal = InversionFractal::new(&mirrors)?;
+        let circles: Vec<Circle> = fractal
+            .generate(&mirrors, depth, min_radius)
+            .into_iter()
+            .map(|x| x.circle)
+            .collect();
+        let graph = TangencyGraph::new(&circles, tolerance)?;
+
+        let styles: Vec<Style> = (0..6)
+            .map(|d| {
+                let fade = 255 - 40 * d as u8;
+                Style::stroke(255, fade, 255 - fade).with_width(0.25)
+            })
+            .collect();
+        let mut scene = graph.scene_by_distance(0, &styles);
+        scene.add_layer(
+            100,
+            Style::stroke(127, 127, 127).with_width(0.125),
+            &[graph.edge_tile()],
+        );
+        Ok(scene)
+    }
+}
@@ -0,0 +1,102 @@
+pub mod chaos;
+pub mod chords;
+pub mod halloween;
+pub mod inversion;
+pub mod normal_forms;
+pub mod poncelet;
+pub mod tilings;
+
+pub use chaos::*;
+pub use chords::*;
+pub use halloween::*;
+pub use inversion::*;
+pub use normal_forms::*;
+pub use poncelet::*;
+pub use tilings::*;
+
+use crate::figure::Figure;
+
+/// All the built-in figures, in the order they should be listed
+pub fn registry() -> Vec<Box<dyn Figure>> {
+    vec![
+        Box::new(GhostDoubleSpiral),
+        Box::new(RibCage),
+        Box::new(HyperbolicTiling),
+        Box::new(MysticRose),
+        Box::new(TimesTable),
+        Box::new(Poncelet),
+        Box::new(InversionRing),
+        Box::new(KissingGraph),
+        Box::new(OrbitTrapSierpinski),
+        Box::new(NormalForms),
+    ]
+}
+
+/// Look up a built-in figure by name
+pub fn find_figure(name: &str) -> Option<Box<dyn Figure>> {
+    registry().into_iter().find(|figure| figure.name() == name)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rendering::fingerprint;
+
+    use super::*;
+
+    /// Fingerprints of each figure with default parameters. If a change
+    /// to a figure is intentional, update the value here. Print the
+    /// current values with:
+    ///
+    /// cargo run --example sweep -- --fingerprint
+    const EXPECTED_FINGERPRINTS: [(&str, u64); 10] = [
+        ("ghost_double_spiral", 0xd2f480d24c1f46b3),
+        ("rib_cage", 0x46d1064d6ae5648d),
+        ("hyperbolic_tiling", 0xffb5dbc35bccb159),
+        ("mystic_rose", 0x54ffdff980ff1c50),
+        ("times_table", 0x3409a1ebf16cddbf),
+        ("poncelet", 0x332182c99284b034),
+        ("inversion_ring", 0x670516e9729ea471),
+        ("kissing_graph", 0x1799174123832f75),
+        ("orbit_trap", 0x32bc17bf2a469d16),
+        ("normal_forms", 0x591b1b929d91aebe),
+    ];
+
+    #[test]
+    pub fn registry_names_are_unique() {
+        let figures = registry();
+
+        for (i, a) in figures.iter().enumerate() {
+            for b in figures[i + 1..].iter() {
+                assert_ne!(a.name(), b.name());
+            }
+        }
+    }
+
+    #[test]
+    pub fn registry_figures_build_with_defaults() {
+        for figure in registry() {
+            let result = figure.build(&figure.default_params());
+
+            assert!(result.is_ok(), "{} failed to build", figure.name());
+        }
+    }
+
+    #[test]
+    pub fn find_figure_with_unknown_name_returns_none() {
+        let result = find_figure("not_a_figure");
+
+        assert!(result.is_none());
+    }
+
+    #[test]
+    pub fn registry_figures_match_stored_fingerprints() {
+        for (name, expected) in EXPECTED_FINGERPRINTS {
+            let figure = find_figure(name).unwrap();
+            let scene = figure.build(&figure.default_params()).unwrap();
+
+            let result = fingerprint(&scene).unwrap();
+
+            assert_eq!(result, expected, "fingerprint mismatch for {}", name);
+        }
+    }
+}
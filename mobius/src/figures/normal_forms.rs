@@ -0,0 +1,425 @@
+//! Reference portraits of the normal forms of each MobiusType, see
+//! Mobius::classify(). Each portrait shows the flow lines of the
+//! transformation (the continuous motion it is one step of), the discrete
+//! orbits of a few sample points, and the labeled fixed points.
+use std::f64::consts::PI;
+
+use crate::{
+    cline_arc::ClineArc,
+    elliptic,
+    figure::{Figure, ParamSpec, Params},
+    geometry::LineSegment,
+    isogonal::Isogonal,
+    parabolic,
+    rendering::{Scene, Style, TextAnchor},
+    transformable::{Cline, Label, PointCloud},
+    translation, Complex, FixedPoints, Mobius, MobiusType,
+};
+
+/// Number of samples along each flow line
+const FLOW_STEPS: usize = 400;
+
+/// Hyperbolic and loxodromic flow lines end where the flow has stretched
+/// by this much, close enough to the fixed points to look finished
+const FLOW_RATIO: f64 = 200.0;
+
+/// Number of forward and backward steps of each sample orbit
+const ORBIT_STEPS: i32 = 8;
+
+/// Size of the labels in math units
+const LABEL_SIZE: f64 = 0.18;
+
+/// Distance from the center of each portrait to the next
+const PORTRAIT_SPACING: f64 = 2.3;
+
+/// One of the four normal forms from recipes. The elliptic, hyperbolic
+/// and loxodromic forms fix +1 and -1, the parabolic form fixes 0.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NormalForm {
+    /// See parabolic(), points flow along circles tangent to d at 0
+    Parabolic(Complex),
+    /// See elliptic(), rotation by theta around +1
+    Elliptic(f64),
+    /// See hyperbolic(), multiplier k at +1
+    Hyperbolic(f64),
+    /// See loxodromic(), complex multiplier k at +1
+    Loxodromic(Complex),
+}
+
+impl NormalForm {
+    pub fn mobius_type(&self) -> MobiusType {
+        match self {
+            Self::Parabolic(_) => MobiusType::Parabolic,
+            Self::Elliptic(_) => MobiusType::Elliptic,
+            Self::Hyperbolic(_) => MobiusType::Hyperbolic,
+            Self::Loxodromic(_) => MobiusType::Loxodromic,
+        }
+    }
+
+    /// The transformation itself, the same as flow(1.0)
+    pub fn xform(&self) -> Result<Mobius, String> {
+        self.flow(1.0)
+    }
+
+    /// The transformation raised to the real power t. As t goes from 0
+    /// to 1, points move continuously from z to xform() * z
+    pub fn flow(&self, t: f64) -> Result<Mobius, String> {
+        match *self {
+            // the parabolic form adds 1/d to 1/z
+            Self::Parabolic(_) if t == 0.0 => Ok(Mobius::IDENTITY),
+            Self::Parabolic(d) => parabolic(d / t.into()),
+            Self::Elliptic(theta) => elliptic(t * theta),
+            // hyperbolic() and loxodromic() require the determinant to
+            // be exactly 1, which fails to round off for large powers
+            Self::Hyperbolic(k) => fixing_plus_minus_one(k.powf(t).into()),
+            Self::Loxodromic(k) => {
+                let arg = k.arg().ok_or("k must be finite and nonzero")?;
+                fixing_plus_minus_one(Complex::from_polar(k.mag().powf(t), t * arg))
+            }
+        }
+    }
+
+    /// Flow lines run from -t_max to t_max. The elliptic flow lines are
+    /// closed, the others approach the fixed points at both ends
+    fn flow_range(&self) -> f64 {
+        match *self {
+            Self::Parabolic(d) => 50.0 * d.mag(),
+            Self::Elliptic(theta) => PI / theta.abs(),
+            Self::Hyperbolic(k) => FLOW_RATIO.ln() / k.ln().abs(),
+            Self::Loxodromic(k) => FLOW_RATIO.ln() / k.mag().ln().abs(),
+        }
+    }
+
+    /// Starting points of the flow lines, one per flow line
+    fn flow_starts(&self) -> Vec<Complex> {
+        match *self {
+            // 1/z moves along lines parallel to 1/d, so start on the
+            // perpendicular line through the origin in 1/z coordinates
+            Self::Parabolic(d) => {
+                let normal = Complex::I * (Complex::ONE / d) * d.mag().into();
+                [-2.5, -1.2, -0.7, -0.5, 0.5, 0.7, 1.2, 2.5]
+                    .into_iter()
+                    .map(|c| (normal * c.into()).inverse())
+                    .collect()
+            }
+            // circles around the fixed points, symmetric across the
+            // imaginary axis
+            Self::Elliptic(_) => [1.15, 1.4, 1.8, 2.2]
+                .into_iter()
+                .flat_map(|x| [Complex::new(x, 0.0), Complex::new(-x, 0.0)])
+                .collect(),
+            // flow lines from source to sink cross the imaginary axis.
+            // The loxodromic spirals swing wider, so they start closer in
+            Self::Hyperbolic(_) => [-2.0, -1.0, -0.4, 0.0, 0.4, 1.0, 2.0]
+                .into_iter()
+                .map(|y| Complex::new(0.0, y))
+                .collect(),
+            Self::Loxodromic(_) => [-1.2, -0.6, -0.25, 0.0, 0.25, 0.6, 1.2]
+                .into_iter()
+                .map(|y| Complex::new(0.0, y))
+                .collect(),
+        }
+    }
+
+    fn title(&self) -> String {
+        match *self {
+            Self::Parabolic(d) => format!("parabolic, d = {}", d),
+            Self::Elliptic(theta) => format!("elliptic, theta = {:.3}", theta),
+            Self::Hyperbolic(k) => format!("hyperbolic, k = {}", k),
+            Self::Loxodromic(k) => format!("loxodromic, k = {}", k),
+        }
+    }
+}
+
+/// Same matrix as loxodromic(k), normalized after the fact
+fn fixing_plus_minus_one(k: Complex) -> Result<Mobius, String> {
+    let plus = Complex::ONE + k;
+    let minus = Complex::ONE - k;
+    Mobius::from_unnormalized(plus, minus, minus, plus)
+}
+
+/// Trace the flow line through start as a polyline, with t from -t_max
+/// to t_max in the given number of steps. Segments that touch infinity
+/// are skipped
+pub fn trace_flow_line(
+    form: NormalForm,
+    start: Complex,
+    t_max: f64,
+    steps: usize,
+) -> Result<Vec<ClineArc>, String> {
+    if steps == 0 {
+        return Err(String::from("steps must be positive"));
+    }
+
+    let points = (0..=steps)
+        .map(|i| {
+            let t = -t_max + 2.0 * t_max * (i as f64) / (steps as f64);
+            Ok(form.flow(t)? * start)
+        })
+        .collect::<Result<Vec<Complex>, String>>()?;
+
+    Ok(points
+        .windows(2)
+        .filter(|pair| pair.iter().all(|z| *z != Complex::Infinity))
+        .map(|pair| {
+            ClineArc::from(LineSegment {
+                start: pair[0],
+                end: pair[1],
+            })
+        })
+        .collect())
+}
+
+/// Orbit of start under xform^n for n from -steps to steps
+fn sample_orbit(xform: Mobius, start: Complex, steps: i32) -> PointCloud {
+    let inverse = xform.inverse();
+    let mut points = vec![start];
+    let (mut forward, mut backward) = (start, start);
+    for _ in 0..steps {
+        forward = xform * forward;
+        backward = inverse * backward;
+        points.push(forward);
+        points.push(backward);
+    }
+    PointCloud::new(points)
+}
+
+/// Label each fixed point by how the transformation acts near it
+fn fixed_point_labels(form: NormalForm, xform: Mobius) -> Vec<(Complex, &'static str)> {
+    let name = |z: Complex| match form.mobius_type() {
+        MobiusType::Hyperbolic | MobiusType::Loxodromic if xform.scale_factor(z) < 1.0 => "sink",
+        MobiusType::Hyperbolic | MobiusType::Loxodromic => "source",
+        _ => "fixed",
+    };
+
+    match xform.fixed_points() {
+        FixedPoints::Single(z) => vec![(z, name(z))],
+        FixedPoints::Pair(p, q) => vec![(p, name(p)), (q, name(q))],
+    }
+}
+
+/// Flow portrait of a normal form: flow lines with arrows showing the
+/// direction of motion, the orbits of the starting points, and the
+/// fixed points, titled with the parameters
+pub fn flow_portrait(form: NormalForm) -> Result<Scene, String> {
+    let xform = form.xform()?;
+    if xform.classify() != form.mobius_type() {
+        return Err(format!(
+            "{:?} is {}, not {}",
+            form,
+            xform.classify(),
+            form.mobius_type()
+        ));
+    }
+
+    let t_max = form.flow_range();
+    let starts = form.flow_starts();
+
+    let mut flow_lines = Vec::new();
+    let mut arrows = Vec::new();
+    for start in starts.iter() {
+        let line = trace_flow_line(form, *start, t_max, FLOW_STEPS)?;
+        // one arrow at the start point where the flow is most spread out
+        if let Some(middle) = line.get(line.len() / 2) {
+            arrows.push(*middle);
+        }
+        flow_lines.extend(line);
+    }
+
+    let orbits: Vec<PointCloud> = starts
+        .iter()
+        .map(|start| sample_orbit(xform, *start, ORBIT_STEPS))
+        .collect();
+
+    let fixed_points = fixed_point_labels(form, xform);
+    let fixed_positions = PointCloud::new(fixed_points.iter().map(|(z, _)| *z).collect());
+    let offset = Complex::new(0.0, -1.5 * LABEL_SIZE);
+    let mut labels: Vec<Label> = fixed_points
+        .iter()
+        .map(|(z, name)| Label::new(*z + offset, name, LABEL_SIZE))
+        .collect();
+    labels.push(
+        Label::new(Complex::new(-2.0, 2.4), &form.title(), LABEL_SIZE)
+            .with_anchor(TextAnchor::Start),
+    );
+
+    let flow_style = Style::stroke(80, 140, 255).with_width(0.125);
+    let mut scene = Scene::new();
+    scene.add_layer(
+        0,
+        Style::stroke(128, 128, 128)
+            .with_width(0.125)
+            .with_dashes(0.5, 0.5)
+            .with_stroke_opacity(0.5),
+        &[Cline::real_axis()],
+    );
+    scene.add_layer(1, flow_style, &flow_lines);
+    scene.add_layer(2, flow_style.with_arrow(6.0), &arrows);
+    scene.add_layer(3, Style::stroke(255, 160, 0).with_width(0.5), &orbits);
+    scene.add_layer(
+        4,
+        Style::stroke(255, 255, 255).with_width(1.0),
+        &[fixed_positions],
+    );
+    scene.add_layer(5, Style::text(255, 255, 255), &labels);
+    Ok(scene)
+}
+
+/// Portraits of all four normal forms in a 2x2 grid
+pub fn normal_form_portraits(forms: [NormalForm; 4]) -> Result<Scene, String> {
+    let offsets = [(-1.0, 1.0), (1.0, 1.0), (-1.0, -1.0), (1.0, -1.0)];
+    let placements = forms
+        .into_iter()
+        .zip(offsets)
+        .map(|(form, (x, y))| {
+            let offset = Complex::new(x * PORTRAIT_SPACING, y * 1.2 * PORTRAIT_SPACING);
+            Ok((
+                flow_portrait(form)?,
+                Isogonal::from(translation(offset)?),
+                0,
+            ))
+        })
+        .collect::<Result<Vec<(Scene, Isogonal, i32)>, String>>()?;
+
+    Ok(Scene::compose(&placements))
+}
+
+/// Flow portraits of the parabolic, elliptic, hyperbolic and loxodromic
+/// normal forms side by side
+pub struct NormalForms;
+
+impl Figure for NormalForms {
+    fn name(&self) -> &'static str {
+        "normal_forms"
+    }
+
+    fn params(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::new("d", 1.0, "parabolic displacement of 1/z"),
+            ParamSpec::new("theta", PI / 3.0, "elliptic rotation angle"),
+            ParamSpec::new("k", 2.0, "hyperbolic multiplier"),
+            ParamSpec::new("lox_re", 1.5, "real part of the loxodromic multiplier"),
+            ParamSpec::new("lox_im", 1.0, "imaginary part of the loxodromic multiplier"),
+        ]
+    }
+
+    fn view(&self) -> (Complex, f64) {
+        (Complex::Zero, 4.7)
+    }
+
+    fn build(&self, params: &Params) -> Result<Scene, String> {
+        let d = params.get("d")?;
+        let theta = params.get("theta")?;
+        let k = params.get("k")?;
+        let lox = Complex::new(params.get("lox_re")?, params.get("lox_im")?);
+
+        if d == 0.0 {
+            return Err(String::from("d must be nonzero"));
+        }
+        if !(theta.abs() > 0.0 && theta.abs() < 2.0 * PI) {
+            return Err(String::from("theta must be in (0, 2pi)"));
+        }
+        if !(k > 0.0 && k != 1.0) {
+            return Err(String::from("k must be positive and not 1"));
+        }
+
+        normal_form_portraits([
+            NormalForm::Parabolic(Complex::new(d, 0.0)),
+            NormalForm::Elliptic(theta),
+            NormalForm::Hyperbolic(k),
+            NormalForm::Loxodromic(lox),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        nearly::is_nearly,
+        rendering::{RenderPrimitive, Renderable},
+    };
+
+    use super::*;
+
+    fn forms() -> [NormalForm; 4] {
+        [
+            NormalForm::Parabolic(Complex::new(1.0, 1.0)),
+            NormalForm::Elliptic(PI / 3.0),
+            NormalForm::Hyperbolic(2.0),
+            NormalForm::Loxodromic(Complex::new(1.5, 1.0)),
+        ]
+    }
+
+    #[test]
+    pub fn normal_forms_classify_as_their_type() {
+        for form in forms() {
+            let result = form.xform().unwrap().classify();
+
+            assert_eq!(result, form.mobius_type(), "{:?}", form);
+        }
+    }
+
+    #[test]
+    pub fn flow_steps_compose() {
+        let z = Complex::new(0.3, 0.7);
+        for form in forms() {
+            let half = form.flow(0.5).unwrap();
+
+            let result = half * (half * z);
+
+            let expected = form.xform().unwrap() * z;
+            assert!(
+                is_nearly((result - expected).mag(), 0.0),
+                "{:?}: {} != {}",
+                form,
+                result,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    pub fn trace_flow_line_is_continuous() {
+        let result = trace_flow_line(NormalForm::Hyperbolic(2.0), Complex::I, 5.0, 100).unwrap();
+
+        assert_eq!(result.len(), 100);
+    }
+
+    #[test]
+    pub fn fixed_point_labels_find_source_and_sink() {
+        let form = NormalForm::Hyperbolic(2.0);
+        let xform = form.xform().unwrap();
+
+        let mut result: Vec<&str> = fixed_point_labels(form, xform)
+            .into_iter()
+            .map(|(_, name)| name)
+            .collect();
+        result.sort();
+
+        assert_eq!(result, vec!["sink", "source"]);
+    }
+
+    #[test]
+    pub fn flow_portrait_has_title_and_fixed_points() {
+        let scene = flow_portrait(NormalForm::Elliptic(PI / 3.0)).unwrap();
+
+        let primitives = scene.bake_geometry().unwrap();
+        let texts = primitives
+            .iter()
+            .filter(|x| matches!(x, RenderPrimitive::Text { .. }))
+            .count();
+
+        // two fixed point labels + the title
+        assert_eq!(texts, 3);
+    }
+
+    #[test]
+    pub fn normal_forms_with_invalid_k_returns_error() {
+        let mut params = NormalForms.default_params();
+        params.set("k", 1.0).unwrap();
+
+        let result = NormalForms.build(&params);
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,65 @@
+use std::f64::consts::TAU;
+
+use crate::{
+    figure::{Figure, ParamSpec, Params},
+    geometry::Circle,
+    poncelet::PonceletPorism,
+    rendering::{Scene, Style},
+    transformable::Cline,
+    Complex,
+};
+
+/// Tangent chord walks between a unit circle and a smaller circle inside
+/// it, starting from several evenly spaced points. Walks that close are
+/// drawn in orange, the rest in blue. The defaults satisfy Euler's
+/// triangle formula so every walk closes into a triangle.
+pub struct Poncelet;
+
+impl Figure for Poncelet {
+    fn name(&self) -> &'static str {
+        "poncelet"
+    }
+
+    fn params(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::new("radius", 0.3, "radius of the inner circle"),
+            ParamSpec::new(
+                "offset",
+                0.632_455_532_033_675_9,
+                "distance from the origin to the inner circle's center",
+            ),
+            ParamSpec::new("starts", 5.0, "number of starting points"),
+            ParamSpec::new("max_steps", 60.0, "maximum steps of each walk"),
+        ]
+    }
+
+    fn view(&self) -> (Complex, f64) {
+        (Complex::Zero, 1.1)
+    }
+
+    fn build(&self, params: &Params) -> Result<Scene, String> {
+        let radius = params.get("radius")?;
+        let offset = params.get("offset")?;
+        let starts = params.get_count("starts", 1)?;
+        let max_steps = params.get_count("max_steps", 1)?;
+
+        let inner = Circle::new(Complex::new(offset, 0.0), radius);
+        let porism = PonceletPorism::new(Circle::unit_circle(), inner)?;
+
+        let (closed, open): (Vec<_>, Vec<_>) = (0..starts)
+            .map(|i| porism.trajectory(TAU * i as f64 / starts as f64, max_steps))
+            .partition(|x| x.is_closed());
+        let closed: Vec<_> = closed.iter().map(|x| x.to_tile()).collect();
+        let open: Vec<_> = open.iter().map(|x| x.to_tile()).collect();
+
+        let mut scene = Scene::new();
+        scene.add_layer(
+            0,
+            Style::stroke(127, 127, 127).with_width(0.25),
+            &[Cline::unit_circle(), Cline::from(inner)],
+        );
+        scene.add_layer(1, Style::stroke(0, 127, 255).with_width(0.125), &open);
+        scene.add_layer(2, Style::stroke(255, 127, 0).with_width(0.125), &closed);
+        Ok(scene)
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{
+    algorithms::MonoidIFS,
+    figure::{Figure, ParamSpec, Params},
+    hyperbolic_tilings::{get_fundamental_region, reflection_group},
+    rendering::{Scene, Style},
+    transformable::Cline,
+    Complex,
+};
+
+/// A {p, q} hyperbolic tiling of the Poincare disk, drawn by reflecting
+/// the fundamental triangle in its three mirrors.
+pub struct HyperbolicTiling;
+
+impl Figure for HyperbolicTiling {
+    fn name(&self) -> &'static str {
+        "hyperbolic_tiling"
+    }
+
+    fn params(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::new("p", 3.0, "number of sides of each polygon"),
+            ParamSpec::new("q", 7.0, "number of polygons around each vertex"),
+            ParamSpec::new("depth", 5.0, "number of reflections to apply"),
+        ]
+    }
+
+    fn view(&self) -> (Complex, f64) {
+        (Complex::Zero, 1.1)
+    }
+
+    fn build(&self, params: &Params) -> Result<Scene, String> {
+        let p = params.get_count("p", 3)?;
+        let q = params.get_count("q", 3)?;
+        let depth = params.get_count("depth", 0)?;
+
+        let (conj, r_conj, e2_conj) = reflection_group(p, q)?;
+        // The mirrors are involutions, so there's no need to store inverses
+        let ifs = MonoidIFS::new(vec![conj, r_conj, e2_conj]);
+        let (tile, _) = get_fundamental_region(p, q)?;
+        let tiles = ifs.apply(&tile, 0, depth);
+
+        let mut scene = Scene::new();
+        scene.add_layer(
+            0,
+            Style::stroke(127, 127, 127).with_width(0.25),
+            &[Cline::unit_circle()],
+        );
+        scene.add_layer(1, Style::stroke(255, 127, 0).with_width(0.125), &tiles);
+        Ok(scene)
+    }
+}
@@ -0,0 +1,211 @@
+//! The finite subgroups of the Mobius group. Up to conjugation, these
+//! are the rotation groups of the sphere: cyclic, dihedral, and the
+//! rotation groups of the Platonic solids. Each one is given as a list
+//! of generators along with the order of the group they generate.
+//!
+//! Unit quaternions (SU(2)) double cover the rotations of the sphere,
+//! and SU(2) matrices act on the Riemann sphere as Mobius transformations.
+//! Where possible, generators are built from quaternions with exact
+//! entries so products accumulate less rounding error.
+use std::f64::consts::{PI, TAU};
+
+use abstraction::Group;
+
+use crate::{elliptic, rotation, Complex, Mobius};
+
+/// A finite subgroup of the Mobius group, given by generators
+pub struct FiniteSubgroup {
+    pub generators: Vec<Mobius>,
+    /// The number of elements in the group generated by the generators
+    pub order: usize,
+}
+
+/// Convert a unit quaternion a + bi + cj + dk to the corresponding
+/// SU(2) matrix
+///
+/// [a + bi   c + di]
+/// [-c + di  a - bi]
+fn from_quaternion(a: f64, b: f64, c: f64, d: f64) -> Mobius {
+    Mobius::new(
+        Complex::new(a, b),
+        Complex::new(c, d),
+        Complex::new(-c, d),
+        Complex::new(a, -b),
+    )
+    .unwrap()
+}
+
+/// Rotations about 0 by multiples of 2pi/n
+pub fn cyclic(n: usize) -> Result<FiniteSubgroup, String> {
+    if n == 0 {
+        return Err(String::from("n must be at least 1"));
+    }
+
+    Ok(FiniteSubgroup {
+        generators: vec![rotation(TAU / (n as f64))?],
+        order: n,
+    })
+}
+
+/// Symmetry group of a regular n-gon on the equator of the Riemann
+/// sphere (the unit circle). This is the cyclic group plus a half turn
+/// about the real axis that swaps 0 and infinity.
+pub fn dihedral(n: usize) -> Result<FiniteSubgroup, String> {
+    let FiniteSubgroup { generators, order } = cyclic(n)?;
+    let mut generators = generators;
+    // 1/z is the quaternion k, a half turn about the axis through +/- 1
+    generators.push(Mobius::INVERSION);
+
+    Ok(FiniteSubgroup {
+        generators,
+        order: 2 * order,
+    })
+}
+
+/// Rotation group of the tetrahedron, isomorphic to A_4
+pub fn tetrahedral() -> FiniteSubgroup {
+    // Half turn about 0, the quaternion i
+    let half_turn = Mobius::POINT_REFLECTION;
+    // 1/2(1 + i + j + k) is a 1/3 turn about a diagonal of the cube.
+    // Conjugating by it cycles the axes i -> j -> k
+    let third_turn = from_quaternion(0.5, 0.5, 0.5, 0.5);
+
+    FiniteSubgroup {
+        generators: vec![half_turn, third_turn],
+        order: 12,
+    }
+}
+
+/// Rotation group of the cube/octahedron, isomorphic to S_4. These
+/// are two quarter turns about axes 90 degrees apart, the same as in
+/// the ghost_octahedral artwork.
+pub fn octahedral() -> FiniteSubgroup {
+    let swirl = elliptic(PI / 2.0).unwrap();
+    let rotate90 = rotation(PI / 2.0).unwrap();
+    let swirl2 = Mobius::sandwich(rotate90, swirl);
+
+    FiniteSubgroup {
+        generators: vec![swirl, swirl2],
+        order: 24,
+    }
+}
+
+/// Rotation group of the icosahedron/dodecahedron, isomorphic to A_5
+pub fn icosahedral() -> FiniteSubgroup {
+    let phi = 0.5 * (1.0 + 5.0f64.sqrt());
+
+    // These generate the binary icosahedral group in SU(2), see
+    // https://en.wikipedia.org/wiki/Binary_icosahedral_group
+    // 1/2(1 + i + j + k) is a 1/3 turn
+    let third_turn = from_quaternion(0.5, 0.5, 0.5, 0.5);
+    // 1/2(phi + phi^-1 i + j) is a 1/5 turn
+    let fifth_turn = from_quaternion(0.5 * phi, 0.5 / phi, 0.5, 0.0);
+
+    FiniteSubgroup {
+        generators: vec![third_turn, fifth_turn],
+        order: 60,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use abstraction::{FiniteGroup, Monoid};
+    use test_case::test_case;
+
+    use super::*;
+
+    fn element_orders(group: &FiniteSubgroup) -> Vec<usize> {
+        group
+            .generators
+            .iter()
+            .map(|x| x.power_iter().count())
+            .collect()
+    }
+
+    #[test]
+    pub fn cyclic_with_zero_returns_error() {
+        let result = cyclic(0);
+
+        assert!(result.is_err());
+    }
+
+    #[test_case(1)]
+    #[test_case(5)]
+    #[test_case(12)]
+    pub fn cyclic_generator_has_order_n(n: usize) {
+        let group = cyclic(n).unwrap();
+
+        assert_eq!(element_orders(&group), vec![n]);
+        assert_eq!(group.order, n);
+    }
+
+    #[test_case(2)]
+    #[test_case(3)]
+    #[test_case(7)]
+    pub fn dihedral_has_order_2n(n: usize) {
+        let group = dihedral(n).unwrap();
+
+        let result = FiniteGroup::from_generators(&group.generators);
+
+        assert_eq!(element_orders(&group), vec![n, 2]);
+        assert_eq!(result.order(), group.order);
+    }
+
+    #[test]
+    pub fn tetrahedral_has_order_12() {
+        let group = tetrahedral();
+
+        let result = FiniteGroup::from_generators(&group.generators);
+
+        assert_eq!(element_orders(&group), vec![2, 3]);
+        assert_eq!(result.order(), 12);
+        assert_eq!(group.order, 12);
+    }
+
+    #[test]
+    pub fn octahedral_has_order_24() {
+        let group = octahedral();
+
+        let result = FiniteGroup::from_generators(&group.generators);
+
+        assert_eq!(element_orders(&group), vec![4, 4]);
+        assert_eq!(result.order(), 24);
+        assert_eq!(group.order, 24);
+    }
+
+    #[test]
+    pub fn icosahedral_has_order_60() {
+        let group = icosahedral();
+
+        let result = FiniteGroup::from_generators(&group.generators);
+
+        assert_eq!(element_orders(&group), vec![3, 5]);
+        assert_eq!(result.order(), 60);
+        assert_eq!(group.order, 60);
+    }
+
+    #[test]
+    pub fn icosahedral_is_simple() {
+        let group = FiniteGroup::from_generators(&icosahedral().generators);
+
+        let result = group.normal_subgroups();
+
+        // Only the trivial group and the whole group
+        assert_eq!(result.len(), 2);
+    }
+
+    #[test]
+    pub fn generators_are_elliptic_or_identity() {
+        let groups = [tetrahedral(), octahedral(), icosahedral()];
+
+        for group in groups {
+            for generator in group.generators {
+                // elements of SU(2) have real trace in [-2, 2]
+                let trace = generator.trace();
+                assert!(trace.imag().abs() < 1e-12);
+                assert!(trace.real().abs() <= 2.0);
+                assert_ne!(generator, Mobius::identity());
+            }
+        }
+    }
+}
@@ -164,7 +164,10 @@ impl Display for ArcAngles {
 
 #[cfg(test)]
 mod test {
-    use std::f64::{consts::PI, INFINITY, NAN};
+    use std::f64::consts::PI;
+
+    const INFINITY: f64 = f64::INFINITY;
+    const NAN: f64 = f64::NAN;
 
     use crate::nearly::assert_nearly;
 
@@ -243,6 +246,7 @@ mod test {
 
     #[test_case(ArcAngles::new(0.0, PI / 2.0).unwrap(); "ccw arc")]
     #[test_case(ArcAngles::new(0.0, -PI / 2.0).unwrap(); "cw arc")]
+    #[allow(clippy::eq_op)]
     pub fn arc_equals_itself(a: ArcAngles) {
         assert_eq!(a, a);
     }
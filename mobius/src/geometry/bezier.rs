@@ -0,0 +1,361 @@
+use crate::Complex;
+
+use super::{sample_params, DirectedEdge, Geometry};
+
+/// Number of chords used to approximate the length of a curve
+const LENGTH_SAMPLES: usize = 64;
+
+fn lerp_point(a: Complex, b: Complex, t: f64) -> Complex {
+    a + (b - a) * t.into()
+}
+
+/// Approximate the length of a curve by the length of an inscribed polyline
+fn polyline_length(points: Vec<Complex>) -> f64 {
+    points
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).mag())
+        .sum()
+}
+
+/// Quadratic Bezier curve from start to end, pulled towards the control
+/// point
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct QuadraticBezier {
+    pub start: Complex,
+    pub control: Complex,
+    pub end: Complex,
+}
+
+impl QuadraticBezier {
+    pub fn new(start: Complex, control: Complex, end: Complex) -> Self {
+        Self {
+            start,
+            control,
+            end,
+        }
+    }
+
+    /// Point at parameter t in [0, 1] by de Casteljau's algorithm
+    pub fn interpolate(&self, t: f64) -> Complex {
+        let a = lerp_point(self.start, self.control, t);
+        let b = lerp_point(self.control, self.end, t);
+        lerp_point(a, b, t)
+    }
+
+    /// Velocity dB/dt at parameter t
+    pub fn derivative(&self, t: f64) -> Complex {
+        let a = self.control - self.start;
+        let b = self.end - self.control;
+        lerp_point(a, b, t) * 2.0.into()
+    }
+
+    /// Split the curve in two at parameter t
+    pub fn split(&self, t: f64) -> (Self, Self) {
+        let a = lerp_point(self.start, self.control, t);
+        let b = lerp_point(self.control, self.end, t);
+        let middle = lerp_point(a, b, t);
+
+        (
+            Self::new(self.start, a, middle),
+            Self::new(middle, b, self.end),
+        )
+    }
+
+    /// The part of the curve between parameters t0 and t1. This splits
+    /// off whichever end leaves the longer piece first, so rescaling the
+    /// parameter for the second split never divides by zero
+    pub fn sub_curve(&self, t0: f64, t1: f64) -> Self {
+        if 1.0 - t0 >= t1 {
+            let (_, tail) = self.split(t0);
+            let (head, _) = tail.split((t1 - t0) / (1.0 - t0));
+            head
+        } else {
+            let (head, _) = self.split(t1);
+            let (_, tail) = head.split(t0 / t1);
+            tail
+        }
+    }
+
+    pub fn reverse(&self) -> Self {
+        Self::new(self.end, self.control, self.start)
+    }
+
+    /// The same curve as a cubic Bezier (degree elevation)
+    pub fn to_cubic(&self) -> CubicBezier {
+        let third: Complex = (1.0 / 3.0).into();
+        let two_thirds: Complex = (2.0 / 3.0).into();
+        CubicBezier::new(
+            self.start,
+            self.start * third + self.control * two_thirds,
+            self.end * third + self.control * two_thirds,
+            self.end,
+        )
+    }
+
+    pub fn control_points(&self) -> [Complex; 3] {
+        [self.start, self.control, self.end]
+    }
+
+    /// n evenly spaced parameters from start to end, inclusive. The points
+    /// are not evenly spaced along the curve
+    pub fn sample(&self, n: usize) -> Vec<Complex> {
+        sample_params(n).map(|t| self.interpolate(t)).collect()
+    }
+}
+
+impl Geometry for QuadraticBezier {}
+impl DirectedEdge for QuadraticBezier {
+    fn start(&self) -> Complex {
+        self.start
+    }
+
+    fn end(&self) -> Complex {
+        self.end
+    }
+
+    /// Approximate, see LENGTH_SAMPLES
+    fn arc_length(&self) -> f64 {
+        polyline_length(self.sample(LENGTH_SAMPLES + 1))
+    }
+}
+
+/// Cubic Bezier curve from start to end. It leaves start heading towards
+/// control1 and arrives at end coming from control2
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct CubicBezier {
+    pub start: Complex,
+    pub control1: Complex,
+    pub control2: Complex,
+    pub end: Complex,
+}
+
+impl CubicBezier {
+    pub fn new(start: Complex, control1: Complex, control2: Complex, end: Complex) -> Self {
+        Self {
+            start,
+            control1,
+            control2,
+            end,
+        }
+    }
+
+    /// Cubic Hermite curve with the given endpoints and velocities at the
+    /// endpoints
+    pub fn from_hermite(
+        start: Complex,
+        start_velocity: Complex,
+        end: Complex,
+        end_velocity: Complex,
+    ) -> Self {
+        let third: Complex = (1.0 / 3.0).into();
+        Self::new(
+            start,
+            start + start_velocity * third,
+            end - end_velocity * third,
+            end,
+        )
+    }
+
+    /// Point at parameter t in [0, 1] by de Casteljau's algorithm
+    pub fn interpolate(&self, t: f64) -> Complex {
+        let (head, _) = self.split(t);
+        head.end
+    }
+
+    /// Velocity dB/dt at parameter t
+    pub fn derivative(&self, t: f64) -> Complex {
+        let a = self.control1 - self.start;
+        let b = self.control2 - self.control1;
+        let c = self.end - self.control2;
+        QuadraticBezier::new(a, b, c).interpolate(t) * 3.0.into()
+    }
+
+    /// Split the curve in two at parameter t
+    pub fn split(&self, t: f64) -> (Self, Self) {
+        let a = lerp_point(self.start, self.control1, t);
+        let b = lerp_point(self.control1, self.control2, t);
+        let c = lerp_point(self.control2, self.end, t);
+        let ab = lerp_point(a, b, t);
+        let bc = lerp_point(b, c, t);
+        let middle = lerp_point(ab, bc, t);
+
+        (
+            Self::new(self.start, a, ab, middle),
+            Self::new(middle, bc, c, self.end),
+        )
+    }
+
+    /// The part of the curve between parameters t0 and t1. This splits
+    /// off whichever end leaves the longer piece first, so rescaling the
+    /// parameter for the second split never divides by zero
+    pub fn sub_curve(&self, t0: f64, t1: f64) -> Self {
+        if 1.0 - t0 >= t1 {
+            let (_, tail) = self.split(t0);
+            let (head, _) = tail.split((t1 - t0) / (1.0 - t0));
+            head
+        } else {
+            let (head, _) = self.split(t1);
+            let (_, tail) = head.split(t0 / t1);
+            tail
+        }
+    }
+
+    pub fn reverse(&self) -> Self {
+        Self::new(self.end, self.control2, self.control1, self.start)
+    }
+
+    pub fn control_points(&self) -> [Complex; 4] {
+        [self.start, self.control1, self.control2, self.end]
+    }
+
+    /// n evenly spaced parameters from start to end, inclusive. The points
+    /// are not evenly spaced along the curve
+    pub fn sample(&self, n: usize) -> Vec<Complex> {
+        sample_params(n).map(|t| self.interpolate(t)).collect()
+    }
+}
+
+impl From<QuadraticBezier> for CubicBezier {
+    fn from(value: QuadraticBezier) -> Self {
+        value.to_cubic()
+    }
+}
+
+impl Geometry for CubicBezier {}
+impl DirectedEdge for CubicBezier {
+    fn start(&self) -> Complex {
+        self.start
+    }
+
+    fn end(&self) -> Complex {
+        self.end
+    }
+
+    /// Approximate, see LENGTH_SAMPLES
+    fn arc_length(&self) -> f64 {
+        polyline_length(self.sample(LENGTH_SAMPLES + 1))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::nearly::assert_nearly;
+
+    use super::*;
+
+    fn s_curve() -> CubicBezier {
+        CubicBezier::new(
+            Complex::Zero,
+            Complex::new(1.0, 2.0),
+            Complex::new(2.0, -2.0),
+            Complex::new(3.0, 0.0),
+        )
+    }
+
+    #[test]
+    pub fn quadratic_interpolate_hits_endpoints_and_middle() {
+        let curve = QuadraticBezier::new(
+            Complex::Zero,
+            Complex::new(1.0, 2.0),
+            Complex::new(2.0, 0.0),
+        );
+
+        assert_eq!(curve.interpolate(0.0), Complex::Zero);
+        assert_eq!(curve.interpolate(0.5), Complex::new(1.0, 1.0));
+        assert_eq!(curve.interpolate(1.0), Complex::new(2.0, 0.0));
+    }
+
+    #[test]
+    pub fn quadratic_to_cubic_is_same_curve() {
+        let curve = QuadraticBezier::new(
+            Complex::Zero,
+            Complex::new(1.0, 2.0),
+            Complex::new(2.0, 0.0),
+        );
+
+        let result = curve.to_cubic();
+
+        for t in [0.0, 0.25, 0.5, 0.9] {
+            assert_eq!(result.interpolate(t), curve.interpolate(t));
+        }
+    }
+
+    #[test]
+    pub fn cubic_split_matches_original() {
+        let curve = s_curve();
+
+        let (head, tail) = curve.split(0.25);
+
+        assert_eq!(head.interpolate(0.5), curve.interpolate(0.125));
+        assert_eq!(tail.interpolate(0.5), curve.interpolate(0.625));
+    }
+
+    #[test]
+    pub fn cubic_sub_curve_matches_original() {
+        let curve = s_curve();
+
+        let result = curve.sub_curve(0.2, 0.6);
+
+        assert_eq!(result.start, curve.interpolate(0.2));
+        assert_eq!(result.end, curve.interpolate(0.6));
+        assert_eq!(result.interpolate(0.5), curve.interpolate(0.4));
+    }
+
+    #[test]
+    pub fn sub_curve_at_end_of_curve_is_finite() {
+        let curve = s_curve();
+        let quadratic = QuadraticBezier::new(
+            Complex::Zero,
+            Complex::new(1.0, 2.0),
+            Complex::new(2.0, 0.0),
+        );
+
+        let cubic_result = curve.sub_curve(1.0, 1.0);
+        let quadratic_result = quadratic.sub_curve(0.8, 1.0);
+
+        assert_eq!(cubic_result.start, curve.end);
+        assert_eq!(cubic_result.control1, curve.end);
+        assert_eq!(cubic_result.end, curve.end);
+        assert_eq!(quadratic_result.start, quadratic.interpolate(0.8));
+        assert_eq!(
+            quadratic_result.interpolate(0.5),
+            quadratic.interpolate(0.9)
+        );
+        assert_eq!(quadratic_result.end, quadratic.end);
+    }
+
+    #[test]
+    pub fn cubic_derivative_matches_finite_difference() {
+        let curve = s_curve();
+        let h = 1e-6;
+
+        let result = curve.derivative(0.3);
+
+        let expected = (curve.interpolate(0.3 + h) - curve.interpolate(0.3 - h)) * (0.5 / h).into();
+        assert!((result - expected).mag() < 1e-6);
+    }
+
+    #[test]
+    pub fn from_hermite_has_given_velocities() {
+        let velocity_start = Complex::new(3.0, 3.0);
+        let velocity_end = Complex::new(0.0, -3.0);
+
+        let result =
+            CubicBezier::from_hermite(Complex::Zero, velocity_start, Complex::ONE, velocity_end);
+
+        assert_eq!(result.derivative(0.0), velocity_start);
+        assert_eq!(result.derivative(1.0), velocity_end);
+    }
+
+    #[test]
+    pub fn arc_length_of_straight_cubic_is_chord() {
+        let curve = CubicBezier::new(
+            Complex::Zero,
+            Complex::ONE,
+            Complex::new(2.0, 0.0),
+            Complex::new(3.0, 0.0),
+        );
+
+        assert_nearly(curve.arc_length(), 3.0);
+    }
+}
@@ -1,8 +1,36 @@
 use std::fmt::Display;
 
-use crate::{nearly::is_nearly, Complex};
+use thiserror::Error;
 
-use super::Geometry;
+use crate::{
+    complex_error::ComplexError,
+    nearly::{is_nearly, EPSILON},
+    Complex, Tolerance,
+};
+
+use super::{GeneralizedCircle, Geometry, Line};
+
+/// Points computed to be on a circle may drift off it by this much
+/// (relative to the radius) from rounding error after many transformations,
+/// at the default tolerance
+const ON_CIRCLE_TOLERANCE: f64 = 1e-6;
+
+/// ON_CIRCLE_TOLERANCE scaled to the current tolerance
+pub(crate) fn on_circle_tolerance() -> f64 {
+    Tolerance::current().scale_relative(ON_CIRCLE_TOLERANCE)
+}
+
+#[derive(Debug, Error)]
+pub enum CircleError {
+    #[error("{0}")]
+    InvalidComplexParam(#[from] ComplexError),
+    #[error("points must be distinct: {0}")]
+    DuplicatePoint(Complex),
+    #[error("point is at the center of the circle: {0}")]
+    AtCenter(Complex),
+    #[error("point {0} is {1} away from the circle")]
+    NotOnCircle(Complex, f64),
+}
 
 #[derive(Clone, Copy, Debug)]
 pub struct Circle {
@@ -22,17 +50,99 @@ impl Circle {
         Self { center, radius }
     }
 
+    /// The generalized circle through three distinct finite points. If
+    /// the points are collinear, this is the line through them.
+    pub fn from_three_points(
+        a: Complex,
+        b: Complex,
+        c: Complex,
+    ) -> Result<GeneralizedCircle, CircleError> {
+        ComplexError::require_finite("a", a)?;
+        ComplexError::require_finite("b", b)?;
+        ComplexError::require_finite("c", c)?;
+
+        if a == b || a == c {
+            return Err(CircleError::DuplicatePoint(a));
+        }
+
+        if b == c {
+            return Err(CircleError::DuplicatePoint(b));
+        }
+
+        let ab = b - a;
+        let ac = c - a;
+        let wedge = Complex::wedge(ab, ac);
+        if wedge.abs() <= EPSILON * ab.mag() * ac.mag() {
+            let line = Line::from_points(a, c).expect("points are finite and distinct");
+            return Ok(GeneralizedCircle::Line(line));
+        }
+
+        // The center is equidistant from all three points. Relative to a,
+        // it's (|ab|^2 ac - |ac|^2 ab) / (2i wedge(ab, ac))
+        let numerator = ac * ab.norm().into() - ab * ac.norm().into();
+        let offset = -Complex::I * numerator / (2.0 * wedge).into();
+
+        Ok(GeneralizedCircle::Circle(Self::new(
+            a + offset,
+            offset.mag(),
+        )))
+    }
+
     pub fn get_point(&self, theta: f64) -> Complex {
         self.center + Complex::from_polar(self.radius, theta)
     }
 
-    pub fn get_angle(&self, point: Complex) -> Option<f64> {
-        (point - self.center).arg()
+    /// Angle of a point on the circle. Points are allowed to drift off the
+    /// circle by tolerance (relative to the radius) to account for
+    /// rounding error.
+    pub fn get_angle(&self, point: Complex, tolerance: f64) -> Result<f64, CircleError> {
+        ComplexError::require_finite("point", point)?;
+
+        let distance = ((point - self.center).mag() - self.radius).abs();
+        if distance > tolerance * self.radius {
+            return Err(CircleError::NotOnCircle(point, distance));
+        }
+
+        (point - self.center)
+            .arg()
+            .ok_or(CircleError::AtCenter(point))
+    }
+
+    /// Angle of the closest point on the circle. Unlike get_angle(), this
+    /// accepts points any distance off the circle, e.g. points that should
+    /// be on it but drifted from rounding error
+    pub fn closest_angle(&self, point: Complex) -> Result<f64, CircleError> {
+        ComplexError::require_finite("point", point)?;
+
+        (point - self.center)
+            .arg()
+            .ok_or(CircleError::AtCenter(point))
+    }
+
+    /// Project a point radially onto the circle. The center is equally
+    /// close to every point, so it has no closest point
+    pub fn closest_point(&self, point: Complex) -> Result<Complex, CircleError> {
+        Ok(self.get_point(self.closest_angle(point)?))
     }
 
     pub fn point_inside(&self, point: Complex) -> bool {
         (point - self.center).norm() <= self.radius * self.radius
     }
+
+    /// Concentric circle at a signed distance, outwards if the distance
+    /// is positive. This returns an error if the offset swallows the
+    /// whole circle.
+    pub fn offset(&self, distance: f64) -> Result<Self, String> {
+        let radius = self.radius + distance;
+        if radius <= 0.0 || radius.is_nan() {
+            return Err(format!(
+                "offsetting radius {} by {} leaves no circle",
+                self.radius, distance
+            ));
+        }
+
+        Ok(Self::new(self.center, radius))
+    }
 }
 
 impl PartialEq for Circle {
@@ -48,3 +158,101 @@ impl Display for Circle {
         write!(f, "Circle({}, {:.3})", self.center, self.radius)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::FRAC_PI_2;
+
+    use test_case::test_case;
+
+    use crate::nearly::assert_nearly;
+
+    use super::*;
+
+    #[test]
+    pub fn from_three_points_finds_circle() {
+        let circle = Circle::new(Complex::new(1.0, -2.0), 3.0);
+        let [a, b, c] = [0.3, 2.0, 4.5].map(|theta| circle.get_point(theta));
+
+        let result = Circle::from_three_points(a, b, c).unwrap();
+
+        match result {
+            GeneralizedCircle::Circle(x) => {
+                assert_eq!(x.center, circle.center);
+                assert_nearly(x.radius, circle.radius);
+            }
+            GeneralizedCircle::Line(x) => panic!("not a circle! {}", x),
+        }
+    }
+
+    #[test]
+    pub fn from_three_points_with_collinear_points_returns_line() {
+        let a = Complex::new(1.0, 1.0);
+        let b = Complex::new(2.0, 3.0);
+        let c = Complex::new(4.0, 7.0);
+
+        let result = Circle::from_three_points(a, b, c).unwrap();
+
+        let expected = Line::from_points(a, c).unwrap();
+        assert_eq!(result, GeneralizedCircle::Line(expected));
+    }
+
+    #[test]
+    pub fn get_angle_allows_small_drift() {
+        let circle = Circle::new(Complex::new(1.0, 1.0), 2.0);
+        let point = Complex::new(1.0, 3.0 + 1e-12);
+
+        let result = circle.get_angle(point, 1e-9).unwrap();
+
+        assert_nearly(result, FRAC_PI_2);
+    }
+
+    #[test_case(Complex::new(1.0, 3.1); "off circle")]
+    #[test_case(Complex::new(1.0, 1.0); "center")]
+    #[test_case(Complex::Infinity; "infinity")]
+    pub fn get_angle_with_bad_point_returns_error(point: Complex) {
+        let circle = Circle::new(Complex::new(1.0, 1.0), 2.0);
+
+        let result = circle.get_angle(point, 1e-9);
+
+        assert!(result.is_err());
+    }
+
+    #[test_case(Complex::new(4.0, 1.0), Complex::new(3.0, 1.0); "outside")]
+    #[test_case(Complex::new(1.0, 0.5), Complex::new(1.0, -1.0); "inside")]
+    #[test_case(Complex::new(1.0, 3.0), Complex::new(1.0, 3.0); "on circle")]
+    pub fn closest_point_projects_onto_circle(point: Complex, expected: Complex) {
+        let circle = Circle::new(Complex::new(1.0, 1.0), 2.0);
+
+        let result = circle.closest_point(point).unwrap();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn closest_point_to_center_returns_error() {
+        let circle = Circle::new(Complex::new(1.0, 1.0), 2.0);
+
+        let result = circle.closest_point(circle.center);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn point_inside_includes_boundary() {
+        let circle = Circle::unit_circle();
+
+        assert!(circle.point_inside(Complex::new(0.5, 0.5)));
+        assert!(circle.point_inside(Complex::I));
+        assert!(!circle.point_inside(Complex::new(1.0, 1.0)));
+    }
+
+    #[test_case(Complex::ONE, Complex::ONE, Complex::I; "a equals b")]
+    #[test_case(Complex::ONE, Complex::I, Complex::I; "b equals c")]
+    #[test_case(Complex::ONE, Complex::I, Complex::Infinity; "infinite point")]
+    pub fn from_three_points_with_bad_points_returns_error(a: Complex, b: Complex, c: Complex) {
+        let result = Circle::from_three_points(a, b, c);
+
+        assert!(result.is_err());
+    }
+}
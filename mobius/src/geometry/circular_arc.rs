@@ -2,14 +2,25 @@ use std::fmt::Display;
 
 use thiserror::Error;
 
-use crate::Complex;
+use crate::{
+    cline_arc::{ClineArc, ClineArcError, ClineArcGeometry},
+    transformable::Cline,
+    Complex,
+};
 
-use super::{circle::Circle, ArcAngles, ArcAnglesParseError, ArcDirection, DirectedEdge, Geometry};
+use super::{
+    circle::Circle, sample_params, ArcAngles, ArcAnglesParseError, ArcDirection, CircleError,
+    DirectedEdge, GeneralizedCircle, Geometry,
+};
 
 #[derive(Debug, Error)]
 pub enum CircularArcError {
     #[error("{0}")]
     BadAngles(#[from] ArcAnglesParseError),
+    #[error("{0}")]
+    BadCircle(#[from] CircleError),
+    #[error("{0}")]
+    BadClineArc(#[from] ClineArcError),
     #[error("duplicate point: {0}")]
     DuplicatePoint(Complex),
 }
@@ -26,6 +37,22 @@ impl CircularArc {
         Self { circle, angles }
     }
 
+    /// The arc that goes a -> b -> c. If the points are collinear this is
+    /// either a line segment from a to c, or if b is not between them, the
+    /// pair of rays from a to c through infinity.
+    pub fn from_three_points(
+        a: Complex,
+        b: Complex,
+        c: Complex,
+    ) -> Result<ClineArcGeometry, CircularArcError> {
+        let cline: Cline = match Circle::from_three_points(a, b, c)? {
+            GeneralizedCircle::Circle(circle) => circle.into(),
+            GeneralizedCircle::Line(line) => line.into(),
+        };
+
+        Ok(ClineArc::new(cline, a, b, c).classify()?)
+    }
+
     pub fn direction(&self) -> ArcDirection {
         self.angles.direction()
     }
@@ -34,6 +61,11 @@ impl CircularArc {
         self.circle.get_point(self.angles.interpolate(t))
     }
 
+    /// n points evenly spaced by arc length from start to end, inclusive
+    pub fn sample(&self, n: usize) -> Vec<Complex> {
+        sample_params(n).map(|t| self.interpolate(t)).collect()
+    }
+
     pub fn reverse(&self) -> Self {
         Self {
             circle: self.circle,
@@ -47,6 +79,21 @@ impl CircularArc {
             angles: self.angles.complement(),
         }
     }
+
+    /// Parallel arc at a signed distance to the right of the direction of
+    /// travel. For a counterclockwise arc this is away from the center,
+    /// the same as Circle::offset(). The angles are unchanged
+    pub fn offset(&self, distance: f64) -> Result<Self, String> {
+        let signed_distance = match self.direction() {
+            ArcDirection::Counterclockwise => distance,
+            ArcDirection::Clockwise => -distance,
+        };
+
+        Ok(Self {
+            circle: self.circle.offset(signed_distance)?,
+            angles: self.angles,
+        })
+    }
 }
 
 impl Geometry for CircularArc {}
@@ -60,6 +107,10 @@ impl DirectedEdge for CircularArc {
         let ArcAngles(_, b) = self.angles;
         self.circle.get_point(b)
     }
+
+    fn arc_length(&self) -> f64 {
+        self.circle.radius * self.angles.central_angle()
+    }
 }
 
 impl Display for CircularArc {
@@ -71,3 +122,91 @@ impl Display for CircularArc {
         write!(f, "Arc(c={}, r={:.3}, {})", center, radius, angles)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use crate::nearly::assert_nearly;
+
+    use super::*;
+
+    fn quarter_circle() -> CircularArc {
+        let angles = ArcAngles::new(0.0, 0.5 * PI).unwrap();
+        CircularArc::new(Circle::new(Complex::ONE, 2.0), angles)
+    }
+
+    #[test]
+    pub fn arc_length_is_radius_times_angle() {
+        let arc = quarter_circle();
+
+        assert_nearly(arc.arc_length(), PI);
+        assert_nearly(arc.reverse().arc_length(), PI);
+    }
+
+    #[test]
+    pub fn sample_includes_both_endpoints() {
+        let arc = quarter_circle();
+
+        let result = arc.sample(3);
+
+        let expected = vec![
+            Complex::new(3.0, 0.0),
+            Complex::ONE + Complex::from_polar(2.0, 0.25 * PI),
+            Complex::new(1.0, 2.0),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn sample_with_few_points() {
+        let arc = quarter_circle();
+
+        assert_eq!(arc.sample(0), vec![]);
+        assert_eq!(arc.sample(1), vec![arc.start()]);
+    }
+
+    #[test]
+    pub fn from_three_points_follows_middle_point() {
+        let arc = quarter_circle();
+        let (a, b, c) = (arc.start(), arc.interpolate(0.5), arc.end());
+
+        let ccw = CircularArc::from_three_points(a, b, c).unwrap();
+        let cw = CircularArc::from_three_points(c, b, a).unwrap();
+
+        match (ccw, cw) {
+            (ClineArcGeometry::CircularArc(ccw), ClineArcGeometry::CircularArc(cw)) => {
+                assert_eq!(ccw.direction(), ArcDirection::Counterclockwise);
+                assert_eq!(cw.direction(), ArcDirection::Clockwise);
+                assert_eq!(ccw.circle, arc.circle);
+                assert_nearly(ccw.arc_length(), arc.arc_length());
+            }
+            _ => panic!("not circular arcs! {}, {}", ccw, cw),
+        }
+    }
+
+    #[test]
+    pub fn from_three_points_with_collinear_points() {
+        let in_order = CircularArc::from_three_points(-Complex::ONE, Complex::Zero, Complex::ONE);
+        let out_of_order =
+            CircularArc::from_three_points(Complex::Zero, -Complex::ONE, Complex::ONE);
+
+        assert!(matches!(in_order, Ok(ClineArcGeometry::LineSegment(_))));
+        assert!(matches!(
+            out_of_order,
+            Ok(ClineArcGeometry::ThruInfinity(_))
+        ));
+    }
+
+    #[test]
+    pub fn offset_moves_right_of_direction_of_travel() {
+        let arc = quarter_circle();
+
+        let outwards = arc.offset(0.5).unwrap();
+        let inwards = arc.reverse().offset(0.5).unwrap();
+
+        assert_eq!(outwards.circle, Circle::new(Complex::ONE, 2.5));
+        assert_eq!(inwards.circle, Circle::new(Complex::ONE, 1.5));
+        assert!(arc.offset(-3.0).is_err());
+    }
+}
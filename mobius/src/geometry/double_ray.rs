@@ -16,4 +16,8 @@ impl DirectedEdge for DoubleRay {
         let Self(_, b) = self;
         b.start
     }
+
+    fn arc_length(&self) -> f64 {
+        f64::INFINITY
+    }
 }
@@ -0,0 +1,346 @@
+use std::f64::consts::TAU;
+
+use crate::{nearly::is_nearly, Complex};
+
+use super::{on_circle_tolerance, ArcAngles, Circle, CircularArc, Line};
+
+/// Where two curves meet. Tangency is detected with is_nearly(), so
+/// curves that barely miss or barely cross due to round-off are still
+/// reported as tangent.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Intersection {
+    /// The curves don't meet
+    Disjoint,
+    /// The curves touch at one point without crossing
+    Tangent(Complex),
+    /// The curves cross at one point, e.g. two lines that aren't parallel
+    Single(Complex),
+    /// The curves cross at two points
+    Pair(Complex, Complex),
+    /// The curves are the same circle or line, so they meet everywhere
+    Coincident,
+}
+
+impl Intersection {
+    /// The intersection points, or an empty list if the curves are
+    /// disjoint or coincident
+    pub fn points(&self) -> Vec<Complex> {
+        match *self {
+            Self::Disjoint | Self::Coincident => vec![],
+            Self::Tangent(z) | Self::Single(z) => vec![z],
+            Self::Pair(a, b) => vec![a, b],
+        }
+    }
+}
+
+/// Intersect two circles
+pub fn intersect_circles(a: Circle, b: Circle) -> Intersection {
+    let ab = b.center - a.center;
+    let distance = ab.mag();
+    if a == b {
+        return Intersection::Coincident;
+    }
+
+    let outer = a.radius + b.radius;
+    let inner = (a.radius - b.radius).abs();
+    if is_nearly(distance, 0.0) {
+        // concentric circles with different radii
+        return Intersection::Disjoint;
+    }
+
+    let unit = ab * (1.0 / distance).into();
+    if is_nearly(distance, outer) {
+        return Intersection::Tangent(a.center + unit * a.radius.into());
+    }
+    if is_nearly(distance, inner) {
+        // internally tangent, the point is on the far side of the
+        // smaller circle from the larger circle's center
+        let direction = if a.radius > b.radius { unit } else { -unit };
+        return Intersection::Tangent(a.center + direction * a.radius.into());
+    }
+    if distance > outer || distance < inner {
+        return Intersection::Disjoint;
+    }
+
+    // Distance from a's center to the chord through both points, and
+    // half the length of that chord
+    let along =
+        (a.radius * a.radius - b.radius * b.radius + distance * distance) / (2.0 * distance);
+    let half_chord = (a.radius * a.radius - along * along).max(0.0).sqrt();
+    let base = a.center + unit * along.into();
+    let offset = Complex::I * unit * half_chord.into();
+    Intersection::Pair(base + offset, base - offset)
+}
+
+/// Intersect a circle with a line
+pub fn intersect_circle_line(circle: Circle, line: Line) -> Intersection {
+    let Line {
+        unit_normal,
+        distance,
+    } = line;
+    let normal = *unit_normal.get();
+    let tangent = *unit_normal.rot90().get();
+
+    // signed distance from the center to the line along the normal
+    let offset = Complex::dot(circle.center, normal) - distance;
+    let foot = circle.center - normal * offset.into();
+    if is_nearly(offset.abs(), circle.radius) {
+        return Intersection::Tangent(foot);
+    }
+    if offset.abs() > circle.radius {
+        return Intersection::Disjoint;
+    }
+
+    let half_chord = (circle.radius * circle.radius - offset * offset).sqrt();
+    let step = tangent * half_chord.into();
+    Intersection::Pair(foot + step, foot - step)
+}
+
+/// Intersect two lines. Lines always cross at a single point unless they
+/// are parallel
+pub fn intersect_lines(a: Line, b: Line) -> Intersection {
+    let n1 = *a.unit_normal.get();
+    let n2 = *b.unit_normal.get();
+    let det = Complex::wedge(n1, n2);
+    if is_nearly(det, 0.0) {
+        return if a == b {
+            Intersection::Coincident
+        } else {
+            Intersection::Disjoint
+        };
+    }
+
+    // Solve n1 . z = d1, n2 . z = d2 with Cramer's rule
+    let x = (a.distance * n2.imag() - b.distance * n1.imag()) / det;
+    let y = (n1.real() * b.distance - n2.real() * a.distance) / det;
+    Intersection::Single(Complex::new(x, y))
+}
+
+/// Check if a point on an arc's circle is between the endpoints of the arc
+fn arc_contains(arc: &CircularArc, z: Complex) -> bool {
+    let Ok(theta) = arc.circle.get_angle(z, on_circle_tolerance()) else {
+        return false;
+    };
+
+    let ArcAngles(start, end) = arc.angles;
+    let (low, high) = (start.min(end), start.max(end));
+    (-2..=2).map(|k| theta + k as f64 * TAU).any(|angle| {
+        (low..=high).contains(&angle) || is_nearly(angle, low) || is_nearly(angle, high)
+    })
+}
+
+/// Check if a point on an arc's circle is between the endpoints of the
+/// arc, and not one of the endpoints
+fn arc_interior_contains(arc: &CircularArc, z: Complex) -> bool {
+    arc_contains(arc, z) && z != arc.interpolate(0.0) && z != arc.interpolate(1.0)
+}
+
+/// Intersect two arcs of the same circle. They are coincident if they
+/// overlap along an arc, otherwise they can only share endpoints
+fn intersect_cocircular_arcs(a: CircularArc, b: CircularArc) -> Intersection {
+    let samples = |arc: &CircularArc| [0.0, 0.5, 1.0].map(|t| arc.interpolate(t));
+    let overlap = samples(&a).iter().any(|z| arc_interior_contains(&b, *z))
+        || samples(&b).iter().any(|z| arc_interior_contains(&a, *z));
+    if overlap {
+        return Intersection::Coincident;
+    }
+
+    let mut shared: Vec<Complex> = Vec::new();
+    for z in [a.interpolate(0.0), a.interpolate(1.0)] {
+        if arc_contains(&b, z) && !shared.contains(&z) {
+            shared.push(z);
+        }
+    }
+    match shared[..] {
+        [] => Intersection::Disjoint,
+        [z] => Intersection::Tangent(z),
+        [p, q] => Intersection::Pair(p, q),
+        _ => unreachable!("an arc has two endpoints"),
+    }
+}
+
+/// Intersect two circular arcs. Only points on both arcs are kept, so two
+/// arcs of crossing circles may meet at 0, 1 or 2 points. Arcs of the same
+/// circle are coincident if they overlap, and tangent if they only share
+/// an endpoint
+pub fn intersect_arcs(a: CircularArc, b: CircularArc) -> Intersection {
+    match intersect_circles(a.circle, b.circle) {
+        Intersection::Coincident => intersect_cocircular_arcs(a, b),
+        Intersection::Tangent(z) if arc_contains(&a, z) && arc_contains(&b, z) => {
+            Intersection::Tangent(z)
+        }
+        Intersection::Pair(p, q) => {
+            let on_both = |z: &Complex| arc_contains(&a, *z) && arc_contains(&b, *z);
+            match (on_both(&p), on_both(&q)) {
+                (true, true) => Intersection::Pair(p, q),
+                (true, false) => Intersection::Single(p),
+                (false, true) => Intersection::Single(q),
+                (false, false) => Intersection::Disjoint,
+            }
+        }
+        _ => Intersection::Disjoint,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use crate::unit_complex::UnitComplex;
+
+    use super::*;
+
+    #[test]
+    pub fn intersect_circles_finds_two_points() {
+        let a = Circle::unit_circle();
+        let b = Circle::new(Complex::ONE, 1.0);
+
+        let result = intersect_circles(a, b);
+
+        let half_sqrt3 = 0.75f64.sqrt();
+        assert_eq!(
+            result,
+            Intersection::Pair(
+                Complex::new(0.5, half_sqrt3),
+                Complex::new(0.5, -half_sqrt3)
+            )
+        );
+    }
+
+    #[test]
+    pub fn intersect_circles_detects_external_tangency() {
+        let a = Circle::unit_circle();
+        let b = Circle::new(Complex::new(3.0, 0.0), 2.0);
+
+        let result = intersect_circles(a, b);
+
+        assert_eq!(result, Intersection::Tangent(Complex::ONE));
+    }
+
+    #[test]
+    pub fn intersect_circles_detects_internal_tangency() {
+        let a = Circle::new(Complex::Zero, 2.0);
+        let b = Circle::new(Complex::ONE, 1.0);
+
+        let result = intersect_circles(a, b);
+
+        assert_eq!(result, Intersection::Tangent(Complex::new(2.0, 0.0)));
+        assert_eq!(intersect_circles(b, a), result);
+    }
+
+    #[test]
+    pub fn intersect_circles_with_nested_circles_is_disjoint() {
+        let a = Circle::new(Complex::Zero, 3.0);
+        let b = Circle::new(Complex::ONE, 1.0);
+
+        let result = intersect_circles(a, b);
+
+        assert_eq!(result, Intersection::Disjoint);
+    }
+
+    #[test]
+    pub fn intersect_circles_with_same_circle_is_coincident() {
+        let result = intersect_circles(Circle::unit_circle(), Circle::unit_circle());
+
+        assert_eq!(result, Intersection::Coincident);
+    }
+
+    #[test]
+    pub fn intersect_circle_line_finds_two_points() {
+        let result = intersect_circle_line(Circle::unit_circle(), Line::real_axis());
+
+        let mut points = result.points();
+        points.sort_by(|a, b| a.real().total_cmp(&b.real()));
+        assert_eq!(points, vec![Complex::new(-1.0, 0.0), Complex::ONE]);
+    }
+
+    #[test]
+    pub fn intersect_circle_line_detects_tangency() {
+        let line = Line::new(UnitComplex::I, 1.0).unwrap();
+
+        let result = intersect_circle_line(Circle::unit_circle(), line);
+
+        assert_eq!(result, Intersection::Tangent(Complex::I));
+    }
+
+    #[test]
+    pub fn intersect_circle_line_with_distant_line_is_disjoint() {
+        let line = Line::new(UnitComplex::ONE, 5.0).unwrap();
+
+        let result = intersect_circle_line(Circle::unit_circle(), line);
+
+        assert_eq!(result, Intersection::Disjoint);
+    }
+
+    #[test]
+    pub fn intersect_lines_finds_crossing() {
+        let a = Line::from_points(Complex::new(0.0, 1.0), Complex::new(2.0, 3.0)).unwrap();
+        let b = Line::from_points(Complex::new(0.0, 3.0), Complex::new(2.0, 1.0)).unwrap();
+
+        let result = intersect_lines(a, b);
+
+        assert_eq!(result, Intersection::Single(Complex::new(1.0, 2.0)));
+    }
+
+    #[test]
+    pub fn intersect_lines_with_parallel_lines() {
+        let a = Line::real_axis();
+        let b = Line::new(UnitComplex::I, 2.0).unwrap();
+        let flipped = Line::new(-UnitComplex::I, 0.0).unwrap();
+
+        assert_eq!(intersect_lines(a, b), Intersection::Disjoint);
+        assert_eq!(intersect_lines(a, flipped), Intersection::Coincident);
+    }
+
+    #[test]
+    pub fn intersect_arcs_keeps_points_on_both_arcs() {
+        let upper = ArcAngles::new(0.0, PI).unwrap();
+        let a = CircularArc::new(Circle::unit_circle(), upper);
+        let b = CircularArc::new(
+            Circle::new(Complex::ONE, 1.0),
+            ArcAngles::new(PI, 0.5 * PI).unwrap(),
+        );
+
+        let result = intersect_arcs(a, b);
+
+        assert_eq!(
+            result,
+            Intersection::Single(Complex::new(0.5, 0.75f64.sqrt()))
+        );
+    }
+
+    #[test]
+    pub fn intersect_arcs_of_same_circle_checks_overlap() {
+        let circle = Circle::unit_circle();
+        let a = CircularArc::new(circle, ArcAngles::new(0.0, 0.5 * PI).unwrap());
+        let b = CircularArc::new(circle, ArcAngles::new(0.25 * PI, PI).unwrap());
+        let c = CircularArc::new(circle, ArcAngles::new(PI, 1.5 * PI).unwrap());
+
+        assert_eq!(intersect_arcs(a, b), Intersection::Coincident);
+        assert_eq!(intersect_arcs(a, c), Intersection::Disjoint);
+    }
+
+    #[test]
+    pub fn intersect_arcs_of_same_circle_sharing_endpoint_are_tangent() {
+        let circle = Circle::unit_circle();
+        let a = CircularArc::new(circle, ArcAngles::new(0.0, 0.5 * PI).unwrap());
+        let b = CircularArc::new(circle, ArcAngles::new(0.5 * PI, PI).unwrap());
+        let inside = CircularArc::new(circle, ArcAngles::new(0.0, 0.25 * PI).unwrap());
+
+        assert_eq!(intersect_arcs(a, b), Intersection::Tangent(Complex::I));
+        assert_eq!(intersect_arcs(b, a), Intersection::Tangent(Complex::I));
+        assert_eq!(intersect_arcs(a, inside), Intersection::Coincident);
+        assert_eq!(intersect_arcs(a, a), Intersection::Coincident);
+    }
+
+    #[test]
+    pub fn intersect_arcs_of_same_circle_sharing_both_endpoints() {
+        let circle = Circle::unit_circle();
+        let upper = CircularArc::new(circle, ArcAngles::new(0.0, PI).unwrap());
+        let lower = CircularArc::new(circle, ArcAngles::new(PI, TAU).unwrap());
+
+        let result = intersect_arcs(upper, lower);
+
+        assert_eq!(result, Intersection::Pair(Complex::ONE, -Complex::ONE));
+    }
+}
@@ -71,6 +71,15 @@ impl Line {
             distance: 0.0,
         }
     }
+
+    /// Parallel line shifted a signed distance in the direction of the
+    /// unit normal
+    pub fn offset(&self, distance: f64) -> Self {
+        Self {
+            unit_normal: self.unit_normal,
+            distance: self.distance + distance,
+        }
+    }
 }
 
 impl From<LineSegment> for Line {
@@ -120,6 +129,15 @@ pub mod test {
         assert!(result.is_ok_and(|x| x == expected));
     }
 
+    #[test]
+    pub fn offset_moves_along_normal() {
+        let line = Line::new(UnitComplex::I, 1.0).unwrap();
+
+        let result = line.offset(-3.0);
+
+        assert_eq!(result, Line::new(-UnitComplex::I, 2.0).unwrap());
+    }
+
     #[test]
     pub fn missing_tests() {
         todo!("test new, more line cases, invalid lines, distance value");
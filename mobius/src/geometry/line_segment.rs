@@ -1,6 +1,6 @@
-use crate::Complex;
+use crate::{interpolation::lerp, unit_complex::UnitComplex, Complex};
 
-use super::{DirectedEdge, Geometry};
+use super::{sample_params, DirectedEdge, Geometry};
 
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub struct LineSegment {
@@ -12,6 +12,30 @@ impl LineSegment {
     pub fn new(start: Complex, end: Complex) -> Self {
         Self { start, end }
     }
+
+    pub fn interpolate(&self, t: f64) -> Complex {
+        Complex::new(
+            lerp(self.start.real(), self.end.real(), t),
+            lerp(self.start.imag(), self.end.imag(), t),
+        )
+    }
+
+    /// Parallel segment at a signed distance to the right of the direction
+    /// of travel, to match CircularArc::offset(). A segment of length 0
+    /// has no direction, so it is returned unchanged
+    pub fn offset(&self, distance: f64) -> Self {
+        let Ok(unit_tangent) = UnitComplex::normalize(self.end - self.start) else {
+            return *self;
+        };
+        let shift = -*unit_tangent.rot90().get() * distance.into();
+
+        Self::new(self.start + shift, self.end + shift)
+    }
+
+    /// n evenly spaced points from start to end, inclusive
+    pub fn sample(&self, n: usize) -> Vec<Complex> {
+        sample_params(n).map(|t| self.interpolate(t)).collect()
+    }
 }
 
 impl Geometry for LineSegment {}
@@ -23,4 +47,47 @@ impl DirectedEdge for LineSegment {
     fn end(&self) -> Complex {
         self.end
     }
+
+    fn arc_length(&self) -> f64 {
+        (self.end - self.start).mag()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn arc_length_is_distance_between_endpoints() {
+        let segment = LineSegment::new(Complex::ONE, Complex::new(4.0, 4.0));
+
+        assert_eq!(segment.arc_length(), 5.0);
+    }
+
+    #[test]
+    pub fn sample_spaces_points_evenly() {
+        let segment = LineSegment::new(Complex::Zero, Complex::new(3.0, 6.0));
+
+        let result = segment.sample(4);
+
+        let expected = vec![
+            Complex::Zero,
+            Complex::new(1.0, 2.0),
+            Complex::new(2.0, 4.0),
+            Complex::new(3.0, 6.0),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn offset_moves_right_of_direction_of_travel() {
+        let segment = LineSegment::new(Complex::Zero, Complex::new(2.0, 0.0));
+
+        let result = segment.offset(1.0);
+
+        assert_eq!(
+            result,
+            LineSegment::new(-Complex::I, Complex::new(2.0, -1.0))
+        );
+    }
 }
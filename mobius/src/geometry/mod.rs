@@ -1,8 +1,10 @@
 pub mod arc_angles;
+pub mod bezier;
 pub mod circle;
 pub mod circular_arc;
 pub mod double_ray;
 pub mod generalized_circle;
+pub mod intersection;
 pub mod line;
 pub mod line_segment;
 pub mod ray;
@@ -10,10 +12,12 @@ pub mod ray;
 use crate::Complex;
 
 pub use arc_angles::*;
+pub use bezier::*;
 pub use circle::*;
 pub use circular_arc::*;
 pub use double_ray::*;
 pub use generalized_circle::*;
+pub use intersection::*;
 pub use line::*;
 pub use line_segment::*;
 pub use ray::*;
@@ -26,4 +30,14 @@ pub trait Geometry {}
 pub trait DirectedEdge {
     fn start(&self) -> Complex;
     fn end(&self) -> Complex;
+    /// Length of the edge, which is infinite for edges that pass through
+    /// infinity
+    fn arc_length(&self) -> f64;
+}
+
+/// Parameters 0, 1/(n - 1), ..., 1 for sampling n evenly spaced points
+/// along an edge. A single sample is placed at the start
+fn sample_params(n: usize) -> impl Iterator<Item = f64> {
+    let denominator = n.saturating_sub(1).max(1) as f64;
+    (0..n).map(move |i| i as f64 / denominator)
 }
@@ -17,4 +17,8 @@ impl DirectedEdge for Ray {
     fn end(&self) -> Complex {
         Complex::Infinity
     }
+
+    fn arc_length(&self) -> f64 {
+        f64::INFINITY
+    }
 }
@@ -0,0 +1,352 @@
+//! Reader for a small text format describing two-generator groups, so a
+//! library of interesting groups can be kept as data rather than code.
+//!
+//! A file is a list of sections, one per group. Each section starts with
+//! the group's name in square brackets, followed by `key = value` lines.
+//! Blank lines and everything after a `#` are ignored. A group is given
+//! either by the matrix entries of its generators:
+//!
+//! ```text
+//! # Apollonian gasket, Indra's Pearls Chapter 7
+//! [gasket]
+//! a = 1 0 -2i 1
+//! b = 1-i 1 1 1+i
+//! ```
+//!
+//! or by traces, in which case the generators are built with
+//! Grandma's recipe (see grandma_recipe()). tab is optional:
+//!
+//! ```text
+//! [spiral]
+//! ta = 1.91+0.05i
+//! tb = 3
+//! ```
+//!
+//! Matrix entries must have determinant 1 unless the section also has
+//! `normalize = true`, in which case each matrix is scaled to fix it.
+use std::{fs, path::Path};
+
+use thiserror::Error;
+
+use crate::{grandma_recipe, Complex, Mobius};
+
+#[derive(Debug, Error)]
+pub enum GroupSpecError {
+    #[error("could not read group spec: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("line {0}: {1}")]
+    Syntax(usize, String),
+    #[error("group {0}: {1}")]
+    InvalidGroup(String, String),
+}
+
+/// A named pair of generators read from a group spec file
+#[derive(Clone, Debug, PartialEq)]
+pub struct GroupSpec {
+    pub name: String,
+    pub a: Mobius,
+    pub b: Mobius,
+}
+
+impl GroupSpec {
+    pub fn generators(&self) -> Vec<Mobius> {
+        vec![self.a, self.b]
+    }
+}
+
+/// Parse a complex number written like 3, -2.5, i, -2i, 1-i or 1.5e-3+2i
+pub fn parse_complex(text: &str) -> Result<Complex, String> {
+    let text = text.trim();
+    let error = || format!("invalid complex number {:?}", text);
+
+    let Some(without_i) = text.strip_suffix('i') else {
+        let real: f64 = text.parse().map_err(|_| error())?;
+        return Ok(Complex::new(real, 0.0));
+    };
+
+    // Split before the last sign that isn't at the start or part of an
+    // exponent like 1e-3
+    let bytes = without_i.as_bytes();
+    let split = (1..bytes.len())
+        .rev()
+        .find(|&i| matches!(bytes[i], b'+' | b'-') && !matches!(bytes[i - 1], b'e' | b'E'))
+        .unwrap_or(0);
+    let (real, imag) = without_i.split_at(split);
+
+    let real: f64 = if real.is_empty() {
+        0.0
+    } else {
+        real.parse().map_err(|_| error())?
+    };
+    let imag: f64 = match imag {
+        "" | "+" => 1.0,
+        "-" => -1.0,
+        x => x.parse().map_err(|_| error())?,
+    };
+
+    Ok(Complex::new(real, imag))
+}
+
+/// The key = value pairs of one section, with the line number of each
+struct Section {
+    name: String,
+    line: usize,
+    entries: Vec<(String, String, usize)>,
+}
+
+impl Section {
+    fn get(&self, key: &str) -> Option<(&str, usize)> {
+        self.entries
+            .iter()
+            .find(|(k, _, _)| k == key)
+            .map(|(_, value, line)| (value.as_str(), *line))
+    }
+
+    fn get_complex(&self, key: &str) -> Result<Option<Complex>, GroupSpecError> {
+        self.get(key)
+            .map(|(value, line)| parse_complex(value).map_err(|e| GroupSpecError::Syntax(line, e)))
+            .transpose()
+    }
+
+    fn get_matrix(&self, key: &str, normalize: bool) -> Result<Mobius, GroupSpecError> {
+        let (value, line) = self
+            .get(key)
+            .ok_or_else(|| self.invalid(format!("missing {}", key)))?;
+
+        let entries = value
+            .split_whitespace()
+            .map(parse_complex)
+            .collect::<Result<Vec<Complex>, String>>()
+            .map_err(|e| GroupSpecError::Syntax(line, e))?;
+        let [a, b, c, d] = entries[..] else {
+            return Err(GroupSpecError::Syntax(
+                line,
+                format!("expected 4 matrix entries, got {}", entries.len()),
+            ));
+        };
+
+        if normalize {
+            if a * d - b * c == Complex::Zero {
+                return Err(self.invalid(format!("{} has determinant 0", key)));
+            }
+            Mobius::from_unnormalized(a, b, c, d)
+        } else {
+            Mobius::new(a, b, c, d)
+        }
+        .map_err(|e| self.invalid(format!("{}: {}", key, e)))
+    }
+
+    fn invalid(&self, message: String) -> GroupSpecError {
+        GroupSpecError::InvalidGroup(self.name.clone(), message)
+    }
+
+    fn to_spec(&self) -> Result<GroupSpec, GroupSpecError> {
+        let normalize = match self.get("normalize") {
+            None | Some(("false", _)) => false,
+            Some(("true", _)) => true,
+            Some((value, line)) => {
+                return Err(GroupSpecError::Syntax(
+                    line,
+                    format!("normalize must be true or false, got {}", value),
+                ))
+            }
+        };
+
+        let (a, b) = match (self.get("a"), self.get("ta")) {
+            (Some(_), None) => (
+                self.get_matrix("a", normalize)?,
+                self.get_matrix("b", normalize)?,
+            ),
+            (None, Some(_)) => {
+                let missing = |key: &str| self.invalid(format!("missing {}", key));
+                let ta = self.get_complex("ta")?.ok_or_else(|| missing("ta"))?;
+                let tb = self.get_complex("tb")?.ok_or_else(|| missing("tb"))?;
+                let tab = self.get_complex("tab")?;
+                grandma_recipe(ta, tb, tab).map_err(|e| self.invalid(e))?
+            }
+            _ => {
+                return Err(self.invalid(String::from(
+                    "expected either matrices a, b or traces ta, tb",
+                )))
+            }
+        };
+
+        Ok(GroupSpec {
+            name: self.name.clone(),
+            a,
+            b,
+        })
+    }
+}
+
+fn parse_sections(text: &str) -> Result<Vec<Section>, GroupSpecError> {
+    let mut sections: Vec<Section> = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|x| x.strip_suffix(']')) {
+            sections.push(Section {
+                name: String::from(name.trim()),
+                line: line_number,
+                entries: Vec::new(),
+            });
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(GroupSpecError::Syntax(
+                line_number,
+                format!("expected key = value, got {}", line),
+            ));
+        };
+        let Some(section) = sections.last_mut() else {
+            return Err(GroupSpecError::Syntax(
+                line_number,
+                String::from("expected [name] before the first entry"),
+            ));
+        };
+
+        let key = key.trim();
+        if section.get(key).is_some() {
+            return Err(GroupSpecError::Syntax(
+                line_number,
+                format!("duplicate key {}", key),
+            ));
+        }
+        section
+            .entries
+            .push((String::from(key), String::from(value.trim()), line_number));
+    }
+
+    Ok(sections)
+}
+
+/// Parse every group in a group spec file, in order. See the module
+/// documentation for the format
+pub fn parse_group_specs(text: &str) -> Result<Vec<GroupSpec>, GroupSpecError> {
+    let sections = parse_sections(text)?;
+    for (i, section) in sections.iter().enumerate() {
+        if sections[..i].iter().any(|x| x.name == section.name) {
+            return Err(GroupSpecError::Syntax(
+                section.line,
+                format!("duplicate group name {}", section.name),
+            ));
+        }
+    }
+
+    sections.iter().map(Section::to_spec).collect()
+}
+
+/// Read and parse a group spec file
+pub fn read_group_specs<P: AsRef<Path>>(path: P) -> Result<Vec<GroupSpec>, GroupSpecError> {
+    parse_group_specs(&fs::read_to_string(path)?)
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::gasket_group;
+
+    use super::*;
+
+    #[test_case("3", Complex::new(3.0, 0.0))]
+    #[test_case("-2.5", Complex::new(-2.5, 0.0))]
+    #[test_case("i", Complex::I)]
+    #[test_case("-2i", Complex::new(0.0, -2.0))]
+    #[test_case("1-i", Complex::new(1.0, -1.0))]
+    #[test_case("1.5e-3+2i", Complex::new(1.5e-3, 2.0))]
+    #[test_case("-1e2-1e-2i", Complex::new(-100.0, -0.01))]
+    pub fn parse_complex_parses_valid_numbers(text: &str, expected: Complex) {
+        let result = parse_complex(text).unwrap();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test_case(""; "empty")]
+    #[test_case("1+"; "missing imaginary part")]
+    #[test_case("one"; "word")]
+    pub fn parse_complex_with_invalid_text_returns_error(text: &str) {
+        let result = parse_complex(text);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn parse_group_specs_reads_matrices() {
+        let text = "
+            # Apollonian gasket
+            [gasket]
+            a = 1 0 -2i 1
+            b = 1-i 1 1 1+i  # parabolic
+        ";
+
+        let result = parse_group_specs(text).unwrap();
+
+        let (a, b) = gasket_group();
+        assert_eq!(
+            result,
+            vec![GroupSpec {
+                name: String::from("gasket"),
+                a,
+                b
+            }]
+        );
+    }
+
+    #[test]
+    pub fn parse_group_specs_reads_traces() {
+        let text = "[spiral]\nta = 1.91+0.05i\ntb = 3\n";
+
+        let result = parse_group_specs(text).unwrap();
+
+        let (a, b) =
+            grandma_recipe(Complex::new(1.91, 0.05), Complex::new(3.0, 0.0), None).unwrap();
+        assert_eq!(result[0].generators(), vec![a, b]);
+    }
+
+    #[test]
+    pub fn parse_group_specs_with_bad_determinant_returns_error() {
+        let text = "[scaled]\na = 2 0 0 2\nb = 1 1 0 1\n";
+
+        let result = parse_group_specs(text);
+
+        assert!(matches!(result, Err(GroupSpecError::InvalidGroup(..))));
+    }
+
+    #[test]
+    pub fn parse_group_specs_normalizes_when_requested() {
+        let text = "[scaled]\nnormalize = true\na = 2 0 0 2\nb = 1 1 0 1\n";
+
+        let result = parse_group_specs(text).unwrap();
+
+        assert_eq!(result[0].a, Mobius::IDENTITY);
+    }
+
+    #[test_case("a = 1 0 0 1", 1; "entry before section")]
+    #[test_case("[g]\na 1 0 0 1", 2; "missing equals")]
+    #[test_case("[g]\na = 1 0 0\nb = 1 0 0 1", 2; "wrong entry count")]
+    #[test_case("[g]\nta = 2\nta = 3", 3; "duplicate key")]
+    #[test_case("[g]\nta = 2\ntb = 2\n[g]\nta = 2\ntb = 2", 4; "duplicate name")]
+    pub fn parse_group_specs_reports_line_numbers(text: &str, expected_line: usize) {
+        let result = parse_group_specs(text);
+
+        match result {
+            Err(GroupSpecError::Syntax(line, _)) => assert_eq!(line, expected_line),
+            _ => panic!("expected a syntax error"),
+        }
+    }
+
+    #[test]
+    pub fn example_group_library_parses() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/data/groups.txt");
+
+        let result = read_group_specs(path).unwrap();
+
+        assert!(!result.is_empty());
+    }
+}
@@ -10,7 +10,7 @@ use crate::{
     Complex, Mobius,
 };
 
-fn compute_edge_circle(p: usize, q: usize) -> Circle {
+pub(crate) fn compute_edge_circle(p: usize, q: usize) -> Circle {
     let angle_p = PI / (p as f64);
     let angle_q = PI / (q as f64);
 
@@ -98,7 +98,7 @@ pub fn corner_rotation_group(p: usize, q: usize) -> Result<(Isogonal, Isogonal,
 }
 
 pub fn center_edge_subgroup(p: usize, q: usize) -> Result<(Isogonal, Isogonal), String> {
-    if q % 2 != 0 {
+    if !q.is_multiple_of(2) {
         return Err(String::from("q must be even"));
     }
 
@@ -109,7 +109,7 @@ pub fn center_edge_subgroup(p: usize, q: usize) -> Result<(Isogonal, Isogonal),
 }
 
 pub fn bisector_vertex_subgroup(p: usize, q: usize) -> Result<(Isogonal, Isogonal), String> {
-    if p % 2 != 0 {
+    if !p.is_multiple_of(2) {
         return Err(String::from("p must be even"));
     }
 
@@ -0,0 +1,417 @@
+use std::f64::consts::TAU;
+
+use combinatorics::{ChordDiagram, MatchedBalancedBrackets, Meander};
+
+use crate::{
+    cline_arc::ClineArc,
+    geometry::{Circle, GeneralizedCircle, Line},
+    nearly::EPSILON,
+    transformable::{Cline, Side},
+    Complex,
+};
+
+/// Get a point along a base cline from a parameter t. For lines, t is the
+/// signed distance from the point on the line closest to the origin,
+/// measured in the direction of the unit normal rotated 90 degrees
+/// clockwise. For circles, t is the angle in radians.
+///
+/// For the real axis this means t is the point itself, and for the unit
+/// circle this is e^(it)
+pub fn point_on_cline(base: Cline, t: f64) -> Result<Complex, String> {
+    let point = match base.classify().map_err(|e| e.to_string())? {
+        GeneralizedCircle::Line(Line {
+            unit_normal,
+            distance,
+        }) => {
+            let &normal = unit_normal.get();
+            let tangent = -*unit_normal.rot90().get();
+            normal * distance.into() + tangent * t.into()
+        }
+        GeneralizedCircle::Circle(circle) => circle.get_point(t),
+    };
+
+    Ok(point)
+}
+
+/// Compute the arc from a to b that is orthogonal to the base cline,
+/// staying on the given side of it. Both points must be on the base cline.
+///
+/// For lines, this is a semicircle centered on the line. For circles, this
+/// is an arc of the circle through a and b that is orthogonal to the base
+/// circle. If a and b are diametrically opposite, the orthogonal cline is
+/// the line through them, and the outside arc goes through infinity.
+pub fn orthogonal_arc(base: Cline, a: Complex, b: Complex, side: Side) -> Result<ClineArc, String> {
+    if side == Side::On {
+        return Err(String::from("side must be Inside or Outside"));
+    }
+
+    if a == b {
+        return Err(String::from("endpoints must be distinct"));
+    }
+
+    for point in [a, b] {
+        if base.point_side(point) != Side::On {
+            return Err(format!("point {} is not on the base cline", point));
+        }
+    }
+
+    match base.classify().map_err(|e| e.to_string())? {
+        GeneralizedCircle::Line(line) => Ok(arc_over_line(line, a, b, side)),
+        GeneralizedCircle::Circle(circle) => arc_over_circle(circle, a, b, side),
+    }
+}
+
+fn arc_over_line(line: Line, a: Complex, b: Complex, side: Side) -> ClineArc {
+    let center = (a + b) * (0.5).into();
+    let radius = 0.5 * (b - a).mag();
+
+    // Inside a line is the half-plane opposite the normal
+    let &normal = line.unit_normal.get();
+    let direction = if side == Side::Inside {
+        -normal
+    } else {
+        normal
+    };
+    let apex = center + direction * radius.into();
+
+    ClineArc::new(Circle::new(center, radius).into(), a, apex, b)
+}
+
+fn arc_over_circle(circle: Circle, a: Complex, b: Complex, side: Side) -> Result<ClineArc, String> {
+    let Circle { center, radius } = circle;
+    let midpoint = (a + b) * (0.5).into();
+    let offset = midpoint - center;
+
+    // If the chord is a diameter, the orthogonal cline is the line through
+    // a and b. The inside arc passes through the center of the base circle,
+    // the outside arc passes through infinity.
+    if offset.mag() <= EPSILON * radius {
+        let line = Line::from_points(a, b).map_err(|e| e.to_string())?;
+        let apex = if side == Side::Inside {
+            center
+        } else {
+            Complex::Infinity
+        };
+        return Ok(ClineArc::new(line.into(), a, apex, b));
+    }
+
+    // The orthogonal circle is centered at the pole of the chord ab, which
+    // is along the perpendicular bisector at a distance r^2 / |offset|
+    // from the base circle's center.
+    let direction = offset * offset.mag().recip().into();
+    let pole = center + direction * (radius * radius / offset.mag()).into();
+    let orthog_radius = (a - pole).mag();
+
+    let apex = if side == Side::Inside {
+        pole - direction * orthog_radius.into()
+    } else {
+        pole + direction * orthog_radius.into()
+    };
+
+    Ok(ClineArc::new(
+        Circle::new(pole, orthog_radius).into(),
+        a,
+        apex,
+        b,
+    ))
+}
+
+/// Connect pairs of points along a base cline with orthogonal arcs.
+/// The points are given by parameters along the cline (see point_on_cline()),
+/// and each pair (i, j) connects parameters[i] to parameters[j] with an arc
+/// on the given side of the base cline.
+///
+/// On the real axis with integer parameters, this produces the classic
+/// diagrams of nested semicircles used to draw brackets and meanders.
+/// Since the result is a list of ClineArc, the diagram can then be
+/// transformed to follow any cline in a composed scene.
+pub fn integer_arcs(
+    base: Cline,
+    parameters: &[f64],
+    pairs: &[(usize, usize)],
+    side: Side,
+) -> Result<Vec<ClineArc>, String> {
+    let points = parameters
+        .iter()
+        .map(|t| point_on_cline(base, *t))
+        .collect::<Result<Vec<Complex>, String>>()?;
+
+    pairs
+        .iter()
+        .map(|&(i, j)| {
+            let a = *points.get(i).ok_or(format!("index {} out of range", i))?;
+            let b = *points.get(j).ok_or(format!("index {} out of range", j))?;
+            orthogonal_arc(base, a, b, side)
+        })
+        .collect()
+}
+
+/// Arc diagram of balanced brackets: bracket i is placed at parameter
+/// i along the base cline, and each matching pair is connected with an
+/// orthogonal arc. Over the real axis, nested brackets give nested
+/// semicircles.
+pub fn bracket_arcs(
+    base: Cline,
+    brackets: &MatchedBalancedBrackets,
+    side: Side,
+) -> Result<Vec<ClineArc>, String> {
+    let parameters: Vec<f64> = (0..brackets.brackets().get().len())
+        .map(|i| i as f64)
+        .collect();
+    integer_arcs(base, &parameters, &brackets.arcs(), side)
+}
+
+/// Draw a meander as a closed curve of arcs: the upper arches on the
+/// Outside of the base cline, and the lower arches on the Inside.
+/// Crossing i is at parameter i, like bracket_arcs()
+pub fn meander_arcs(base: Cline, meander: &Meander) -> Result<Vec<ClineArc>, String> {
+    let mut result = bracket_arcs(base, meander.upper(), Side::Outside)?;
+    result.extend(bracket_arcs(base, meander.lower(), Side::Inside)?);
+    Ok(result)
+}
+
+/// Which side of the base cline to draw the chords of a chord diagram
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChordRouting {
+    Inside,
+    Outside,
+    /// Split the chords between the two sides to reduce crossings, see
+    /// ChordDiagram::route_two_sides()
+    MinimizeCrossings,
+}
+
+/// Draw a chord diagram with orthogonal arcs. The 2n points are evenly
+/// spaced at parameters 2 pi k / 2n along the base cline, so for the
+/// unit circle they are the 2n-th roots of unity.
+pub fn chord_arcs(
+    base: Cline,
+    diagram: &ChordDiagram,
+    routing: ChordRouting,
+) -> Result<Vec<ClineArc>, String> {
+    let n = diagram.point_count();
+    let points = (0..n)
+        .map(|k| point_on_cline(base, TAU * k as f64 / n as f64))
+        .collect::<Result<Vec<Complex>, String>>()?;
+
+    let outside = match routing {
+        ChordRouting::Inside => vec![false; diagram.chords().len()],
+        ChordRouting::Outside => vec![true; diagram.chords().len()],
+        ChordRouting::MinimizeCrossings => diagram.route_two_sides(),
+    };
+
+    diagram
+        .chords()
+        .iter()
+        .zip(outside)
+        .map(|(&(i, j), outside)| {
+            let side = if outside { Side::Outside } else { Side::Inside };
+            orthogonal_arc(base, points[i], points[j], side)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use test_case::test_case;
+
+    use crate::{
+        cline_arc::ClineArcGeometry,
+        geometry::{CircularArc, LineSegment},
+    };
+
+    use super::*;
+
+    fn unwrap_arc(arc: &ClineArc) -> CircularArc {
+        match arc.classify().unwrap() {
+            ClineArcGeometry::CircularArc(arc) => arc,
+            x => panic!("not a circular arc: {}", x),
+        }
+    }
+
+    #[test]
+    pub fn point_on_cline_with_real_axis_returns_parameter() {
+        let result = point_on_cline(Cline::real_axis(), 3.0);
+
+        assert_eq!(result, Ok(Complex::new(3.0, 0.0)));
+    }
+
+    #[test]
+    pub fn point_on_cline_with_unit_circle_returns_root_of_unity() {
+        let result = point_on_cline(Cline::unit_circle(), PI / 2.0);
+
+        assert_eq!(result, Ok(Complex::I));
+    }
+
+    #[test]
+    pub fn orthogonal_arc_with_side_on_returns_error() {
+        let result = orthogonal_arc(Cline::real_axis(), Complex::Zero, Complex::ONE, Side::On);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn orthogonal_arc_with_point_off_cline_returns_error() {
+        let result = orthogonal_arc(Cline::real_axis(), Complex::Zero, Complex::I, Side::Inside);
+
+        assert!(result.is_err_and(|e| e.contains("not on the base cline")));
+    }
+
+    #[test]
+    pub fn integer_arcs_over_real_axis_computes_semicircles() {
+        let result = integer_arcs(
+            Cline::real_axis(),
+            &[0.0, 1.0, 2.0, 3.0],
+            &[(0, 3), (1, 2)],
+            Side::Outside,
+        )
+        .unwrap();
+
+        let outer = unwrap_arc(&result[0]);
+        assert_eq!(outer.circle, Circle::new(Complex::new(1.5, 0.0), 1.5));
+        // The real axis normal points up, so Outside is the upper half-plane
+        let midpoint = outer.circle.get_point(outer.angles.interpolate(0.5));
+        assert_eq!(midpoint, Complex::new(1.5, 1.5));
+
+        let inner = unwrap_arc(&result[1]);
+        assert_eq!(inner.circle, Circle::new(Complex::new(1.5, 0.0), 0.5));
+    }
+
+    #[test]
+    pub fn bracket_arcs_draws_pairs_as_semicircles() {
+        let brackets: combinatorics::BalancedBrackets = "(())()".parse().unwrap();
+
+        let result = bracket_arcs(Cline::real_axis(), &brackets.matched(), Side::Outside).unwrap();
+
+        let circles: Vec<Circle> = result.iter().map(|arc| unwrap_arc(arc).circle).collect();
+        assert_eq!(
+            circles,
+            vec![
+                Circle::new(Complex::new(1.5, 0.0), 1.5),
+                Circle::new(Complex::new(1.5, 0.0), 0.5),
+                Circle::new(Complex::new(4.5, 0.0), 0.5),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn meander_arcs_draws_lower_arches_on_inside() {
+        let upper: combinatorics::BalancedBrackets = "(())()".parse().unwrap();
+        let lower: combinatorics::BalancedBrackets = "()(())".parse().unwrap();
+        let meander = Meander::new(&upper, &lower).unwrap();
+
+        let result = meander_arcs(Cline::real_axis(), &meander).unwrap();
+
+        // Outside the real axis is the upper half-plane
+        let above: Vec<bool> = result
+            .iter()
+            .map(|arc| {
+                let arc = unwrap_arc(arc);
+                arc.circle.get_point(arc.angles.interpolate(0.5)).imag() > 0.0
+            })
+            .collect();
+        assert_eq!(above, vec![true, true, true, false, false, false]);
+    }
+
+    #[test_case(ChordRouting::Inside, [Side::Inside; 4]; "inside")]
+    #[test_case(ChordRouting::Outside, [Side::Outside; 4]; "outside")]
+    #[test_case(ChordRouting::MinimizeCrossings, [Side::Inside, Side::Outside, Side::Inside, Side::Outside]; "minimize crossings")]
+    pub fn chord_arcs_routes_chords(routing: ChordRouting, expected: [Side; 4]) {
+        // A chain of crossing chords, which can be drawn without
+        // crossings by alternating sides. None of them are diameters, so
+        // every arc is a circular arc
+        let diagram = ChordDiagram::new(&[(0, 2), (1, 4), (3, 6), (5, 7)]).unwrap();
+
+        let result = chord_arcs(Cline::unit_circle(), &diagram, routing).unwrap();
+
+        let sides: Vec<Side> = result
+            .iter()
+            .map(|arc| {
+                let arc = unwrap_arc(arc);
+                Cline::unit_circle().point_side(arc.circle.get_point(arc.angles.interpolate(0.5)))
+            })
+            .collect();
+        assert_eq!(sides, expected);
+    }
+
+    #[test]
+    pub fn integer_arcs_over_offset_circle_accepts_generated_points() {
+        let base = Cline::from(Circle::new(Complex::new(1.3, 2.7), 4.1));
+        let parameters: Vec<f64> = (0..20).map(|i| i as f64).collect();
+        let pairs: Vec<(usize, usize)> = (0..19).map(|i| (i, i + 1)).collect();
+
+        let result = integer_arcs(base, &parameters, &pairs, Side::Inside);
+
+        assert!(result.is_ok_and(|arcs| arcs.len() == 19));
+    }
+
+    #[test]
+    pub fn chord_arcs_over_offset_circle_accepts_generated_points() {
+        let base = Cline::from(Circle::new(Complex::new(1.3, 2.7), 4.1));
+        let diagram = ChordDiagram::new(&[(0, 2), (1, 4), (3, 6), (5, 7)]).unwrap();
+
+        let result = chord_arcs(base, &diagram, ChordRouting::MinimizeCrossings);
+
+        assert!(result.is_ok_and(|arcs| arcs.len() == 4));
+    }
+
+    #[test]
+    pub fn integer_arcs_with_bad_index_returns_error() {
+        let result = integer_arcs(Cline::real_axis(), &[0.0, 1.0], &[(0, 2)], Side::Inside);
+
+        assert!(result.is_err_and(|e| e.contains("out of range")));
+    }
+
+    #[test]
+    pub fn orthogonal_arc_over_circle_computes_orthogonal_circle() {
+        let base = Cline::from(Circle::new(Complex::new(1.0, 2.0), 4.0));
+        let a = Complex::new(5.0, 2.0);
+        let b = Complex::new(1.0, 6.0);
+
+        let inside = unwrap_arc(&orthogonal_arc(base, a, b, Side::Inside).unwrap());
+        let outside = unwrap_arc(&orthogonal_arc(base, a, b, Side::Outside).unwrap());
+
+        let expected = Circle::new(Complex::new(5.0, 6.0), 4.0);
+        assert_eq!(inside.circle, expected);
+        assert_eq!(outside.circle, expected);
+        let inside_mid = inside.circle.get_point(inside.angles.interpolate(0.5));
+        let outside_mid = outside.circle.get_point(outside.angles.interpolate(0.5));
+        assert_eq!(base.point_side(inside_mid), Side::Inside);
+        assert_eq!(base.point_side(outside_mid), Side::Outside);
+    }
+
+    #[test]
+    pub fn orthogonal_arc_over_diameter_inside_is_segment() {
+        let a = Complex::ONE;
+        let b = -Complex::ONE;
+
+        let result = orthogonal_arc(Cline::unit_circle(), a, b, Side::Inside)
+            .unwrap()
+            .classify()
+            .unwrap();
+
+        match result {
+            ClineArcGeometry::LineSegment(LineSegment { start, end }) => {
+                assert_eq!(start, a);
+                assert_eq!(end, b);
+            }
+            x => panic!("not a line segment: {}", x),
+        }
+    }
+
+    #[test]
+    pub fn orthogonal_arc_over_diameter_outside_goes_through_infinity() {
+        let result = orthogonal_arc(
+            Cline::unit_circle(),
+            Complex::ONE,
+            -Complex::ONE,
+            Side::Outside,
+        )
+        .unwrap()
+        .classify()
+        .unwrap();
+
+        assert!(matches!(result, ClineArcGeometry::ThruInfinity(_)));
+    }
+}
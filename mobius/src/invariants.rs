@@ -0,0 +1,388 @@
+//! Numerical invariants of Mobius and isogonal transformations. Long
+//! products (e.g. deep IFS traversals) accumulate rounding error, which
+//! otherwise only shows up as subtly wrong pictures. The checks are:
+//!
+//! - the determinant is still 1 (relative to the size of the entries)
+//! - a small circle still maps to a circle. The cross ratio of four
+//!   evenly spaced points on a circle is exactly 2, and Mobius
+//!   transformations preserve cross ratios. The tolerance grows when the
+//!   image circle is tiny compared to its distance from the origin, since
+//!   the image points can't be computed any more precisely than that
+//! - orientation is preserved by conformal maps and flipped by
+//!   anti-conformal maps
+//!
+//! The IFS traversals run these checks after every product when the
+//! debug_invariants feature is enabled, see Monoid::check_invariants()
+//!
+//! This module also provides cross_ratio(), the basic invariant of four
+//! points. See Cline::inversive_distance() for the invariant of two
+//! circles.
+use crate::{isogonal::Isogonal, Complex, Mobius};
+
+/// Largest allowed |det - 1|, relative to |ad| + |bc|
+pub const DET_TOLERANCE: f64 = 1e-6;
+
+/// Largest allowed error in the cross ratio of the image of a circle,
+/// on top of the rounding error from where the image is (see
+/// CIRCLE_ROUNDING)
+pub const CIRCLE_TOLERANCE: f64 = 1e-6;
+
+/// Rounding error in the cross ratio of the image of a circle, relative
+/// to the distance of the image from the origin over its radius. The
+/// differences of image points only keep the digits that vary around the
+/// circle, so a tiny circle far from the origin has a much less accurate
+/// cross ratio than the map itself.
+const CIRCLE_ROUNDING: f64 = 64.0 * f64::EPSILON;
+
+/// Compute the cross ratio (a, b; c, d) = (a - c)(b - d) / ((b - c)(a - d)).
+/// Mobius transformations preserve it, and it's real exactly when the
+/// four points lie on a common cline. With this convention,
+/// cross_ratio(z, 1, 0, inf) = z.
+///
+/// Any one of the points may be infinity. If three points are distinct the
+/// result is 0, 1 or infinity depending on which pair coincides. This
+/// returns an error if fewer than three points are distinct, since the
+/// ratio is 0/0.
+pub fn cross_ratio(a: Complex, b: Complex, c: Complex, d: Complex) -> Result<Complex, String> {
+    let points = [a, b, c, d];
+    let mut distinct: Vec<Complex> = Vec::new();
+    for z in points {
+        if !distinct.contains(&z) {
+            distinct.push(z);
+        }
+    }
+    if distinct.len() < 3 {
+        return Err(format!(
+            "cross ratio needs at least three distinct points, got ({}, {}; {}, {})",
+            a, b, c, d
+        ));
+    }
+
+    let [a, b, c, d] = if points.contains(&Complex::Infinity) {
+        // The cross ratio is invariant, so apply z -> 1/(z - w) to move
+        // infinity to 0, where w is any point not in the list.
+        let w = [
+            Complex::Zero,
+            Complex::ONE,
+            Complex::I,
+            -Complex::ONE,
+            -Complex::I,
+        ]
+        .into_iter()
+        .find(|w| !points.contains(w))
+        .expect("there are more candidates than points");
+        points.map(|z| (z - w).inverse())
+    } else {
+        points
+    };
+
+    Ok(((a - c) * (b - d)) / ((b - c) * (a - d)))
+}
+
+/// Pick a small circle (center, radius) well away from the pole of the
+/// transformation, so none of the test points map near infinity
+fn probe_circle(xform: &Mobius) -> (Complex, f64) {
+    if xform.c == Complex::Zero {
+        return (Complex::Zero, 1.0);
+    }
+
+    let pole = -xform.d / xform.c;
+    let candidates = [
+        Complex::Zero,
+        Complex::ONE,
+        Complex::I,
+        -Complex::ONE,
+        -Complex::I,
+    ];
+    let (center, distance) = candidates.into_iter().map(|z| (z, (z - pole).mag())).fold(
+        (Complex::Zero, 0.0),
+        |best, x| if x.1 > best.1 { x } else { best },
+    );
+
+    (center, 0.5 * distance)
+}
+
+fn check_det(xform: &Mobius) -> Result<(), String> {
+    let &Mobius { a, b, c, d } = xform;
+    let scale = ((a * d).mag() + (b * c).mag()).max(1.0);
+    let error = (xform.det() - Complex::ONE).mag();
+    if error > DET_TOLERANCE * scale {
+        return Err(format!("determinant {} drifted from 1", xform.det()));
+    }
+
+    Ok(())
+}
+
+/// Check that f maps the circle to a circle, and that it flips the
+/// orientation of the circle exactly when flips is true. The circle must
+/// not contain the pole of f
+fn check_circle_and_orientation(
+    f: impl Fn(Complex) -> Complex,
+    center: Complex,
+    radius: f64,
+    flips: bool,
+) -> Result<(), String> {
+    let [z1, z2, z3, z4] = [0.0, 1.0, 2.0, 3.0].map(|quarter: f64| {
+        f(center + Complex::from_polar(radius, quarter * std::f64::consts::FRAC_PI_2))
+    });
+    if [z1, z2, z3, z4].contains(&Complex::Infinity) {
+        return Err(String::from("test circle maps through infinity"));
+    }
+
+    let image = [z1, z2, z3, z4];
+    let image_size = image
+        .iter()
+        .flat_map(|a| image.iter().map(move |b| (*a - *b).mag()))
+        .fold(0.0, f64::max);
+    let image_position = image.iter().map(|z| z.mag()).fold(0.0, f64::max);
+    let tolerance = CIRCLE_TOLERANCE + CIRCLE_ROUNDING * image_position / image_size;
+
+    let ratio = cross_ratio(z1, z2, z3, z4)?;
+    if (ratio - Complex::new(2.0, 0.0)).mag() > tolerance {
+        return Err(format!(
+            "circle is not mapped to a circle, cross ratio {} should be 2",
+            ratio
+        ));
+    }
+
+    // Signed area of the image of three counterclockwise points on the
+    // circle. The pole is outside the circle, so a conformal map keeps
+    // them counterclockwise
+    let area = Complex::wedge(z2 - z1, z3 - z1);
+    if (area < 0.0) != flips {
+        let expected = if flips { "flipped" } else { "preserved" };
+        return Err(format!("orientation should be {}", expected));
+    }
+
+    Ok(())
+}
+
+pub fn check_mobius(xform: &Mobius) -> Result<(), String> {
+    check_det(xform)?;
+    let (center, radius) = probe_circle(xform);
+    check_circle_and_orientation(|z| *xform * z, center, radius, false)
+}
+
+pub fn check_isogonal(xform: &Isogonal) -> Result<(), String> {
+    match xform {
+        Isogonal::Conformal(m) => check_mobius(m),
+        Isogonal::AntiConformal(m) => {
+            check_det(m)?;
+            // The conjugate is applied first, so probe near the
+            // reflection of the circle that avoids the pole
+            let (center, radius) = probe_circle(m);
+            check_circle_and_orientation(|z| *xform * z, center.conj(), radius, true)
+        }
+    }
+}
+
+/// When the debug_invariants feature is enabled, panic if a product
+/// computed by a traversal has drifted. word describes how the product
+/// was built, so the offending word is in the message
+pub(crate) fn debug_check<S: abstraction::Monoid>(xform: &S, word: impl FnOnce() -> String) {
+    if cfg!(feature = "debug_invariants") {
+        if let Err(message) = xform.check_invariants() {
+            panic!("invariant violated for word {}: {}", word(), message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use abstraction::{Group, Monoid};
+    use test_case::test_case;
+
+    use crate::{geometry::Circle, rotation, scale, translation};
+
+    use super::*;
+
+    #[test]
+    pub fn check_mobius_accepts_normalized_products() {
+        let a = translation(Complex::new(0.5, 0.25)).unwrap();
+        let b = rotation(0.3).unwrap();
+        let inversion =
+            Mobius::new(Complex::Zero, -Complex::ONE, Complex::ONE, Complex::Zero).unwrap();
+
+        let result = Monoid::pow(&(a * b * inversion), 20);
+
+        assert!(check_mobius(&result).is_ok());
+    }
+
+    #[test]
+    pub fn check_mobius_accepts_strong_contraction() {
+        // Shrinks the probe circle to radius 1e-12 around 10/9, so the
+        // image points only differ in their last few digits
+        let step = translation(Complex::ONE).unwrap() * scale(0.1).unwrap();
+
+        let result = Monoid::pow(&step, 12);
+
+        assert!(check_mobius(&result).is_ok(), "{:?}", check_mobius(&result));
+    }
+
+    #[test]
+    pub fn check_mobius_rejects_drifted_determinant() {
+        let drifted = Mobius {
+            a: Complex::new(1.01, 0.0),
+            ..Mobius::IDENTITY
+        };
+
+        let result = check_mobius(&drifted);
+
+        assert!(result.unwrap_err().contains("determinant"));
+    }
+
+    #[test]
+    pub fn check_isogonal_accepts_anti_conformal_maps() {
+        let inversion = Isogonal::circle_inversion(Circle::new(Complex::ONE, 2.0)).unwrap();
+        let rotate = Isogonal::from(rotation(1.0).unwrap());
+
+        let result = inversion * rotate * inversion.inverse() * Isogonal::conj();
+
+        assert!(check_isogonal(&result).is_ok());
+        assert!(check_isogonal(&inversion).is_ok());
+    }
+
+    /// Translations on the real line, which are only valid up to |x| <= 2.
+    /// Mobius products renormalize themselves, so this stands in for
+    /// drift that builds up with the length of the word
+    #[derive(Clone, Debug, PartialEq)]
+    struct Bounded(f64);
+
+    impl std::ops::Mul for Bounded {
+        type Output = Self;
+
+        #[allow(clippy::suspicious_arithmetic_impl)]
+        fn mul(self, rhs: Self) -> Self {
+            Self(self.0 + rhs.0)
+        }
+    }
+
+    impl Monoid for Bounded {
+        fn identity() -> Self {
+            Self(0.0)
+        }
+
+        fn check_invariants(&self) -> Result<(), String> {
+            if self.0.abs() > 2.0 {
+                return Err(format!("{} is out of bounds", self.0));
+            }
+            Ok(())
+        }
+    }
+
+    impl Group for Bounded {
+        fn inverse(&self) -> Self {
+            Self(-self.0)
+        }
+    }
+
+    #[cfg(feature = "debug_invariants")]
+    #[test]
+    #[should_panic(expected = "invariant violated for word aaa: 3 is out of bounds")]
+    pub fn group_ifs_reports_offending_word() {
+        let ifs = crate::algorithms::GroupIFS::new(vec![Bounded(1.0)]);
+
+        for _ in ifs.dfs(5) {}
+    }
+
+    #[cfg(feature = "debug_invariants")]
+    #[test]
+    #[should_panic(expected = "word of length 3 ending in transformation 1")]
+    pub fn monoid_ifs_reports_offending_word() {
+        let ifs = crate::algorithms::MonoidIFS::new(vec![Bounded(0.0), Bounded(1.0)]);
+
+        for _ in ifs.dfs(5) {}
+    }
+
+    #[cfg(not(feature = "debug_invariants"))]
+    #[test]
+    pub fn traversals_skip_checks_without_feature() {
+        let ifs = crate::algorithms::GroupIFS::new(vec![Bounded(1.0)]);
+
+        let result = ifs.dfs(5).count();
+
+        assert_eq!(result, 11);
+    }
+
+    #[test]
+    pub fn check_circle_and_orientation_rejects_wrong_orientation() {
+        let result = check_circle_and_orientation(|z| z.conj(), Complex::Zero, 1.0, false);
+
+        assert!(result.unwrap_err().contains("orientation"));
+    }
+
+    #[test]
+    pub fn check_circle_and_orientation_rejects_non_circle() {
+        // Stretching x doesn't map circles to circles
+        let stretch = |z: Complex| Complex::new(2.0 * z.real(), z.imag());
+
+        let result = check_circle_and_orientation(stretch, Complex::Zero, 1.0, false);
+
+        assert!(result.unwrap_err().contains("circle"));
+    }
+
+    #[test]
+    pub fn cross_ratio_of_square_on_circle_is_two() {
+        let result = cross_ratio(Complex::ONE, Complex::I, -Complex::ONE, -Complex::I).unwrap();
+
+        assert_eq!(result, Complex::new(2.0, 0.0));
+    }
+
+    #[test]
+    pub fn cross_ratio_with_standard_points_is_identity() {
+        let z = Complex::new(0.3, -1.2);
+
+        let result = cross_ratio(z, Complex::ONE, Complex::Zero, Complex::Infinity).unwrap();
+
+        assert_eq!(result, z);
+    }
+
+    #[test]
+    pub fn cross_ratio_with_repeated_point_is_degenerate() {
+        let a = Complex::new(2.0, 1.0);
+        let b = Complex::new(-1.0, 3.0);
+        let c = Complex::I;
+
+        assert_eq!(cross_ratio(a, b, a, c), Ok(Complex::Zero));
+        assert_eq!(cross_ratio(a, a, b, c), Ok(Complex::ONE));
+        assert_eq!(cross_ratio(a, b, b, c), Ok(Complex::Infinity));
+    }
+
+    #[test_case(Complex::ONE, Complex::ONE, Complex::I, Complex::I; "two pairs")]
+    #[test_case(Complex::ONE, Complex::ONE, Complex::ONE, Complex::I; "three equal")]
+    #[test_case(Complex::Infinity, Complex::Infinity, Complex::Zero, Complex::Zero; "pairs with infinity")]
+    pub fn cross_ratio_with_fewer_than_three_distinct_points_returns_error(
+        a: Complex,
+        b: Complex,
+        c: Complex,
+        d: Complex,
+    ) {
+        let result = cross_ratio(a, b, c, d);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn cross_ratio_is_preserved_by_mobius_maps() {
+        let xform = Mobius::from_unnormalized(
+            Complex::new(1.0, 2.0),
+            Complex::new(-1.0, 0.5),
+            Complex::new(0.5, 1.0),
+            Complex::new(2.0, 0.0),
+        )
+        .unwrap();
+        let points = [
+            Complex::new(0.5, 0.5),
+            Complex::new(-2.0, 1.0),
+            Complex::new(1.0, -3.0),
+            Complex::Infinity,
+        ];
+        let [a, b, c, d] = points;
+        let [fa, fb, fc, fd] = points.map(|z| xform * z);
+
+        let before = cross_ratio(a, b, c, d).unwrap();
+        let after = cross_ratio(fa, fb, fc, fd).unwrap();
+
+        assert!((before - after).mag() < 1e-12);
+    }
+}
@@ -1,8 +1,8 @@
 use std::{fmt::Display, ops::Mul};
 
-use abstraction::{Group, Monoid};
+use abstraction::{Group, GroupAction, Monoid};
 
-use crate::{Complex, Mobius};
+use crate::{geometry::Circle, Complex, Mobius};
 
 /// An isogonal (angle-preserving but not necessarily orientation preserving)
 /// map realized as either a Mobius transformation M or a mirror (complex conjugation)
@@ -20,7 +20,45 @@ pub enum Isogonal {
 impl Isogonal {
     /// Get the transform I * conj(z) = z.conj()
     pub fn conj() -> Self {
-        Self::AntiConformal(Mobius::identity())
+        Self::AntiConformal(Mobius::IDENTITY)
+    }
+
+    /// Hashable key for deduplicating transformations, see
+    /// Mobius::quantized_key(). The flag is true for anti-conformal maps
+    pub fn quantized_key(&self) -> (bool, [i64; 8]) {
+        match self {
+            Self::Conformal(m) => (false, m.quantized_key()),
+            Self::AntiConformal(m) => (true, m.quantized_key()),
+        }
+    }
+
+    /// Inversion in a circle, z -> center + radius^2 / conj(z - center).
+    /// Written as a Mobius transformation applied after conj, this is
+    ///
+    /// [center (radius^2 - |center|^2)] * conj
+    /// [1      -center.conj()         ]
+    pub fn circle_inversion(circle: Circle) -> Result<Self, String> {
+        let Circle { center, radius } = circle;
+        if radius <= 0.0 || !radius.is_finite() {
+            return Err(String::from("radius must be positive and finite"));
+        }
+
+        let m = Mobius::from_unnormalized(
+            center,
+            (radius * radius - center.norm()).into(),
+            Complex::ONE,
+            -center.conj(),
+        )?;
+        Ok(Self::AntiConformal(m))
+    }
+
+    /// Local scale factor at z, see Mobius::scale_factor(). conj is an
+    /// isometry, so only the Mobius part contributes
+    pub fn scale_factor(&self, z: Complex) -> f64 {
+        match self {
+            Self::Conformal(m) => m.scale_factor(z),
+            Self::AntiConformal(m) => m.scale_factor(z.conj()),
+        }
     }
 }
 
@@ -63,9 +101,19 @@ impl Mul<Complex> for Isogonal {
     }
 }
 
+impl GroupAction<Complex> for Isogonal {
+    fn act(&self, x: &Complex) -> Complex {
+        *self * *x
+    }
+}
+
 impl Monoid for Isogonal {
     fn identity() -> Self {
-        Self::Conformal(Mobius::identity())
+        Self::Conformal(Mobius::IDENTITY)
+    }
+
+    fn check_invariants(&self) -> Result<(), String> {
+        crate::invariants::check_isogonal(self)
     }
 }
 
@@ -212,4 +260,47 @@ mod test {
             )
         ]
     );
+
+    #[test]
+    pub fn scale_factor_of_circle_inversion_matches_radius() {
+        let circle = Circle::new(Complex::new(1.0, 1.0), 2.0);
+        let inversion = Isogonal::circle_inversion(circle).unwrap();
+        // A point at distance 4 from the center is sent to distance 1,
+        // and nearby points are shrunk by (r / d)^2
+        let z = Complex::new(5.0, 1.0);
+
+        let result = inversion.scale_factor(z);
+
+        assert!((result - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    pub fn circle_inversion_fixes_points_on_circle() {
+        let circle = Circle::new(Complex::new(1.0, 2.0), 3.0);
+        let inversion = Isogonal::circle_inversion(circle).unwrap();
+        let point = circle.get_point(0.5);
+
+        let result = inversion * point;
+
+        assert_eq!(result, point);
+    }
+
+    #[test]
+    pub fn circle_inversion_swaps_center_and_infinity() {
+        let circle = Circle::new(Complex::new(1.0, 2.0), 3.0);
+        let inversion = Isogonal::circle_inversion(circle).unwrap();
+
+        assert_eq!(inversion * circle.center, Complex::Infinity);
+        assert_eq!(inversion * Complex::Infinity, circle.center);
+    }
+
+    #[test]
+    pub fn circle_inversion_scales_distance_from_center() {
+        let circle = Circle::new(Complex::new(1.0, 2.0), 3.0);
+        let inversion = Isogonal::circle_inversion(circle).unwrap();
+
+        let result = inversion * Complex::new(2.0, 2.0);
+
+        assert_eq!(result, Complex::new(10.0, 2.0));
+    }
 }
@@ -0,0 +1,203 @@
+//! Isogonal counterparts to the recipes module. These include the
+//! orientation-reversing maps (reflections, glide reflections, inversions)
+//! needed to build reflective symmetry groups, and return Isogonal so they
+//! compose directly with conformal maps via Isogonal::from(mobius).
+use std::f64::consts::TAU;
+
+use abstraction::Monoid;
+
+use crate::{
+    elliptic_about,
+    geometry::{Circle, Line},
+    isogonal::Isogonal,
+    rotation,
+    transformable::Cline,
+    translation, Complex,
+};
+
+/// Reflection across a line, see Cline::reflection()
+pub fn line_reflection(line: Line) -> Isogonal {
+    Cline::from(line)
+        .reflection()
+        .expect("lines are never degenerate clines")
+}
+
+/// Reflection across the line through two distinct points
+pub fn reflection_through_points(a: Complex, b: Complex) -> Result<Isogonal, String> {
+    let line = Line::from_points(a, b).map_err(|e| e.to_string())?;
+    Ok(line_reflection(line))
+}
+
+/// Reflect across a line then slide a signed distance along it, in the
+/// direction of the line's tangent (see Line::from_points()). The
+/// reflection and translation commute, and the square is a translation
+/// by twice the distance
+pub fn glide_reflection(line: Line, distance: f64) -> Result<Isogonal, String> {
+    if !distance.is_finite() {
+        return Err(format!("distance must be finite, got {}", distance));
+    }
+
+    let tangent = -Complex::I * *line.unit_normal.get();
+    let slide = translation(tangent * distance.into())?;
+    Ok(Isogonal::from(slide) * line_reflection(line))
+}
+
+/// Rotation by angle about an arbitrary center. This is the elliptic
+/// transformation with fixed points center and infinity, except that a
+/// multiple of a full turn gives the identity
+pub fn rotation_about(center: Complex, angle: f64) -> Result<Isogonal, String> {
+    if center == Complex::Infinity {
+        return Err(String::from("center must be finite"));
+    }
+
+    if angle.is_finite() && Complex::from_polar(1.0, angle) == Complex::ONE {
+        return Ok(Isogonal::identity());
+    }
+
+    Ok(Isogonal::from(elliptic_about(
+        center,
+        Complex::Infinity,
+        angle,
+    )?))
+}
+
+/// Inversion in a circle followed by a rotation about its center. The
+/// circle is mapped to itself, turned by angle. With angle = 0 this is
+/// Isogonal::circle_inversion()
+pub fn rotated_inversion(circle: Circle, angle: f64) -> Result<Isogonal, String> {
+    let inversion = Isogonal::circle_inversion(circle)?;
+    Ok(rotation_about(circle.center, angle)? * inversion)
+}
+
+/// All 2n elements of the dihedral group of the regular n-gon centered at
+/// the origin with a vertex on the positive real axis. The n rotations
+/// come first, starting with the identity, followed by the n reflections
+pub fn dihedral_group(n: usize) -> Result<Vec<Isogonal>, String> {
+    if n == 0 {
+        return Err(String::from("n must be at least 1"));
+    }
+
+    let rotations = (0..n)
+        .map(|k| rotation(TAU * (k as f64) / (n as f64)).map(Isogonal::from))
+        .collect::<Result<Vec<_>, String>>()?;
+    let reflections: Vec<Isogonal> = rotations.iter().map(|r| *r * Isogonal::conj()).collect();
+
+    Ok(rotations.into_iter().chain(reflections).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use test_case::test_case;
+
+    use crate::unit_complex::UnitComplex;
+
+    use super::*;
+
+    /// The line y = 1, with the normal pointing up
+    fn horizontal_line() -> Line {
+        Line::new(UnitComplex::I, 1.0).unwrap()
+    }
+
+    #[test_case(Complex::Zero, Complex::new(0.0, 2.0); "below")]
+    #[test_case(Complex::new(3.0, 1.0), Complex::new(3.0, 1.0); "on_line")]
+    #[test_case(Complex::new(-1.0, 4.0), Complex::new(-1.0, -2.0); "above")]
+    pub fn line_reflection_flips_across_line(z: Complex, expected: Complex) {
+        let reflection = line_reflection(horizontal_line());
+
+        let result = reflection * z;
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn line_reflection_is_an_involution() {
+        let line = Line::from_points(Complex::new(1.0, 2.0), Complex::new(-3.0, 0.5)).unwrap();
+        let reflection = line_reflection(line);
+        let z = Complex::new(0.3, -0.7);
+
+        let result = reflection * (reflection * z);
+
+        assert_eq!(result, z);
+    }
+
+    #[test]
+    pub fn reflection_through_points_fixes_the_points() {
+        let a = Complex::new(1.0, 2.0);
+        let b = Complex::new(-3.0, 0.5);
+
+        let reflection = reflection_through_points(a, b).unwrap();
+
+        assert_eq!(reflection * a, a);
+        assert_eq!(reflection * b, b);
+    }
+
+    #[test]
+    pub fn reflection_through_same_point_returns_error() {
+        let result = reflection_through_points(Complex::ONE, Complex::ONE);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn glide_reflection_squares_to_translation() {
+        let glide = glide_reflection(horizontal_line(), 2.0).unwrap();
+        let z = Complex::new(0.5, -0.25);
+
+        let result = glide * (glide * z);
+
+        // The tangent of the line points in the +x direction
+        assert_eq!(result, z + Complex::new(4.0, 0.0));
+    }
+
+    #[test]
+    pub fn rotation_about_fixes_center() {
+        let center = Complex::new(2.0, 1.0);
+        let rotation = rotation_about(center, FRAC_PI_2).unwrap();
+
+        assert_eq!(rotation * center, center);
+        assert_eq!(rotation * Complex::new(3.0, 1.0), Complex::new(2.0, 2.0));
+    }
+
+    #[test_case(0.0; "zero")]
+    #[test_case(TAU; "full turn")]
+    pub fn rotation_about_by_full_turns_is_identity(angle: f64) {
+        let result = rotation_about(Complex::new(2.0, 1.0), angle).unwrap();
+
+        assert_eq!(result, Isogonal::identity());
+    }
+
+    #[test]
+    pub fn rotated_inversion_maps_circle_to_itself() {
+        let circle = Circle::new(Complex::new(1.0, 1.0), 2.0);
+        let xform = rotated_inversion(circle, PI).unwrap();
+
+        let result = xform * circle.get_point(0.0);
+
+        assert_eq!(result, circle.get_point(PI));
+        assert_eq!(xform * Complex::new(2.0, 1.0), Complex::new(-3.0, 1.0));
+    }
+
+    #[test]
+    pub fn dihedral_group_has_2n_elements() {
+        let result = dihedral_group(4).unwrap();
+
+        assert_eq!(result.len(), 8);
+        assert_eq!(result[0], Isogonal::identity());
+        // Every element maps the square's vertex 1 to another vertex
+        for xform in result.iter() {
+            let image = *xform * Complex::ONE;
+            assert!([Complex::ONE, Complex::I, -Complex::ONE, -Complex::I].contains(&image));
+        }
+    }
+
+    #[test]
+    pub fn dihedral_group_reflections_are_involutions() {
+        let result = dihedral_group(3).unwrap();
+
+        for reflection in &result[3..] {
+            assert_eq!(*reflection * *reflection, Isogonal::identity());
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Kaleidoscopes: the group generated by reflections in a set of mirrors.
+//! Each mirror is a cline, so the same code handles spherical (finite)
+//! groups like the dihedral groups, Euclidean wallpaper groups made of
+//! lines, and hyperbolic groups like reflection_group() where some of the
+//! mirrors are circles.
+use crate::{
+    address::FractalAddress,
+    algorithms::GroupIFS,
+    isogonal::Isogonal,
+    transformable::{Cline, Transformable},
+};
+
+/// A set of mirrors and the group generated by reflecting in them
+pub struct Kaleidoscope {
+    mirrors: Vec<Cline>,
+    ifs: GroupIFS<Isogonal>,
+}
+
+impl Kaleidoscope {
+    pub fn new(mirrors: Vec<Cline>) -> Result<Self, String> {
+        if mirrors.is_empty() {
+            return Err(String::from("a kaleidoscope needs at least one mirror"));
+        }
+
+        let reflections = mirrors
+            .iter()
+            .map(|x| x.reflection())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            mirrors,
+            ifs: GroupIFS::new(reflections),
+        })
+    }
+
+    pub fn mirrors(&self) -> &[Cline] {
+        &self.mirrors
+    }
+
+    /// Every distinct group element reachable by reflecting in at most
+    /// max_length mirrors, in breadth-first order. Since reflections are
+    /// involutions, different words often give the same element (e.g.
+    /// (ab)^p = 1 when two mirrors meet at angle pi/p), so elements are
+    /// deduplicated with Isogonal::quantized_key()
+    pub fn elements(&self, max_length: usize) -> Vec<(FractalAddress, Isogonal)> {
+        self.ifs.bfs_unique(max_length, Isogonal::quantized_key)
+    }
+
+    /// Reflect a motif by every element up to the given word length,
+    /// including the identity, so the motif itself comes first
+    pub fn reflect<T: Transformable<Isogonal>>(&self, motif: &T, max_length: usize) -> Vec<T> {
+        self.ifs
+            .apply_unique(motif, 0, max_length, Isogonal::quantized_key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use test_case::test_case;
+
+    use crate::{
+        geometry::{Circle, Line},
+        hyperbolic_tilings::{compute_edge_circle, reflection_group},
+        unit_complex::UnitComplex,
+        Complex,
+    };
+
+    use super::*;
+
+    /// Line through the origin at the given angle from the real axis
+    fn line_at_angle(angle: f64) -> Cline {
+        Line::new(UnitComplex::from_angle(angle + 0.5 * PI), 0.0)
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    pub fn new_with_no_mirrors_returns_error() {
+        let result = Kaleidoscope::new(vec![]);
+
+        assert!(result.is_err_and(|e| e.contains("at least one mirror")));
+    }
+
+    #[test_case(3; "triangle")]
+    #[test_case(5; "pentagon")]
+    pub fn dihedral_kaleidoscope_is_finite(p: usize) {
+        let mirrors = vec![Cline::real_axis(), line_at_angle(PI / p as f64)];
+        let kaleidoscope = Kaleidoscope::new(mirrors).unwrap();
+
+        let elements = kaleidoscope.elements(3 * p);
+
+        assert_eq!(elements.len(), 2 * p);
+    }
+
+    #[test]
+    pub fn euclidean_kaleidoscope_counts_shortest_words() {
+        // Mirrors around the unit square generate D_inf x D_inf. Each
+        // factor has 2 elements of every nonzero word length, so the
+        // number of elements with length <= 4 is 1 + 4 + 8 + 12 + 16
+        let mirrors = vec![
+            Cline::real_axis(),
+            Cline::imag_axis(),
+            Line::new(UnitComplex::ONE, 1.0).unwrap().into(),
+            Line::new(UnitComplex::I, 1.0).unwrap().into(),
+        ];
+        let kaleidoscope = Kaleidoscope::new(mirrors).unwrap();
+        let motif = Cline::from(Circle::new(Complex::new(0.3, 0.4), 0.1));
+
+        let images = kaleidoscope.reflect(&motif, 4);
+
+        assert_eq!(images.len(), 41);
+        assert_eq!(images[0], motif);
+        for (i, a) in images.iter().enumerate() {
+            for b in &images[i + 1..] {
+                assert!(!a.is_same_cline(b, 1e-9));
+            }
+        }
+    }
+
+    #[test]
+    pub fn hyperbolic_kaleidoscope_matches_reflection_group() {
+        let mirrors = vec![
+            Cline::real_axis(),
+            line_at_angle(PI / 3.0),
+            compute_edge_circle(3, 7).into(),
+        ];
+        let kaleidoscope = Kaleidoscope::new(mirrors).unwrap();
+        let (conj, r_conj, e2_conj) = reflection_group(3, 7).unwrap();
+        let ifs = GroupIFS::new(vec![conj, r_conj, e2_conj]);
+
+        let result = kaleidoscope.elements(6);
+
+        let expected = ifs.bfs_unique(6, Isogonal::quantized_key);
+        let keys = |x: &[(FractalAddress, Isogonal)]| -> Vec<(bool, [i64; 8])> {
+            x.iter().map(|(_, xform)| xform.quantized_key()).collect()
+        };
+        assert_eq!(keys(&result), keys(&expected));
+    }
+}
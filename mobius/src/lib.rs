@@ -3,22 +3,52 @@ pub mod algorithms;
 pub mod cline_arc;
 mod complex;
 pub mod complex_error;
+pub mod element_table;
+pub mod elliptic;
+pub mod exact;
+pub mod figure;
+pub mod figures;
+pub mod finite_subgroups;
 pub mod float_error;
 pub mod geometry;
+pub mod group_spec;
 pub mod interpolation;
+pub mod invariants;
 pub mod isogonal;
+pub mod isogonal_recipes;
+pub mod kaleidoscope;
+pub mod lsystems;
 mod mobius;
 pub mod motifs;
 mod nearly;
 pub mod orthogonal_arcs;
+pub mod polygon;
+mod polynomial;
+pub mod poncelet;
+pub mod precision;
+pub mod prelude;
+pub mod random;
+mod rational_map;
 mod recipes;
 pub mod rendering;
+pub mod spherical_tilings;
+pub mod steiner;
 pub mod svg_plot;
+pub mod svg_stream;
+pub mod trace_field;
 pub mod transformable;
+pub mod turtle;
 pub mod unit_complex;
+pub mod winding;
 
 pub mod hyperbolic_tilings;
+pub mod integer_arcs;
 
 pub use complex::Complex;
-pub use mobius::Mobius;
+pub use mobius::{FixedPoints, Mobius, MobiusNormalForm, MobiusType};
+#[cfg(feature = "parallel")]
+pub use nearly::par_sconcat;
+pub use nearly::Tolerance;
+pub use polynomial::ComplexPolynomial;
+pub use rational_map::RationalMap;
 pub use recipes::*;
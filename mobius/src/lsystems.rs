@@ -0,0 +1,268 @@
+//! Lindenmayer systems: rewrite a string of symbols with production rules,
+//! then draw the result with a turtle. Each symbol maps to a turtle
+//! command like drawing a segment or arc, turning, or saving the turtle's
+//! state, which Turtle carries out with a turtle::Turtle.
+use std::collections::HashMap;
+
+use crate::{transformable::ClineArcTile, turtle};
+
+/// An axiom and a set of production rules. Symbols without a rule are
+/// copied unchanged on each iteration
+#[derive(Clone, Debug)]
+pub struct LSystem {
+    axiom: String,
+    rules: HashMap<char, String>,
+}
+
+impl LSystem {
+    pub fn new(axiom: &str, rules: &[(char, &str)]) -> Result<Self, String> {
+        let mut rule_map = HashMap::new();
+        for &(symbol, replacement) in rules {
+            if rule_map.insert(symbol, String::from(replacement)).is_some() {
+                return Err(format!("more than one rule for symbol {}", symbol));
+            }
+        }
+
+        Ok(Self {
+            axiom: String::from(axiom),
+            rules: rule_map,
+        })
+    }
+
+    /// Apply the production rules n times starting from the axiom
+    pub fn iterate(&self, n: usize) -> String {
+        let mut current = self.axiom.clone();
+        for _ in 0..n {
+            current = current
+                .chars()
+                .map(|symbol| match self.rules.get(&symbol) {
+                    Some(replacement) => replacement.clone(),
+                    None => symbol.to_string(),
+                })
+                .collect();
+        }
+        current
+    }
+}
+
+/// What the turtle does for a symbol
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TurtleCommand {
+    /// Move one step forward drawing a line segment
+    Forward,
+    /// Move one step forward drawing a circular arc that turns the heading
+    /// by the given angle (positive is counterclockwise)
+    Arc(f64),
+    /// Move one step forward without drawing
+    Move,
+    /// Turn counterclockwise by the turtle's angle
+    TurnLeft,
+    /// Turn clockwise by the turtle's angle
+    TurnRight,
+    /// Save the current position and heading
+    Push,
+    /// Return to the last saved position and heading
+    Pop,
+}
+
+/// Turtle graphics interpretation of an L-system string. The turtle starts
+/// at the origin facing the positive real axis.
+///
+/// By default, F and G draw forward, f moves without drawing, + and - turn
+/// left and right, and [ and ] push and pop the turtle state. Other
+/// symbols are ignored.
+#[derive(Clone, Debug)]
+pub struct Turtle {
+    step: f64,
+    angle: f64,
+    commands: HashMap<char, TurtleCommand>,
+}
+
+impl Turtle {
+    pub fn new(step: f64, angle: f64) -> Result<Self, String> {
+        if !(step > 0.0 && step.is_finite()) {
+            return Err(format!("step must be positive, got {}", step));
+        }
+
+        if !angle.is_finite() {
+            return Err(format!("angle must be finite, got {}", angle));
+        }
+
+        let commands = HashMap::from([
+            ('F', TurtleCommand::Forward),
+            ('G', TurtleCommand::Forward),
+            ('f', TurtleCommand::Move),
+            ('+', TurtleCommand::TurnLeft),
+            ('-', TurtleCommand::TurnRight),
+            ('[', TurtleCommand::Push),
+            (']', TurtleCommand::Pop),
+        ]);
+
+        Ok(Self {
+            step,
+            angle,
+            commands,
+        })
+    }
+
+    /// Set (or replace) the command for a symbol
+    pub fn with_command(mut self, symbol: char, command: TurtleCommand) -> Self {
+        self.commands.insert(symbol, command);
+        self
+    }
+
+    /// Run the turtle over a string of symbols, collecting the segments
+    /// and arcs it draws
+    pub fn interpret(&self, symbols: &str) -> Result<ClineArcTile, String> {
+        let mut turtle = turtle::Turtle::new();
+        for symbol in symbols.chars() {
+            let Some(command) = self.commands.get(&symbol) else {
+                continue;
+            };
+
+            match *command {
+                // The arc has length step, so radius * |angle| = step
+                TurtleCommand::Arc(angle) if angle > 0.0 => {
+                    turtle.arc_left(self.step / angle, angle)?;
+                }
+                TurtleCommand::Arc(angle) if angle < 0.0 => {
+                    turtle.arc_right(-self.step / angle, -angle)?;
+                }
+                // An arc that doesn't turn is a line segment
+                TurtleCommand::Forward | TurtleCommand::Arc(_) => {
+                    turtle.forward(self.step);
+                }
+                TurtleCommand::Move => {
+                    turtle.jump(self.step);
+                }
+                TurtleCommand::TurnLeft => {
+                    turtle.turn(self.angle);
+                }
+                TurtleCommand::TurnRight => {
+                    turtle.turn(-self.angle);
+                }
+                TurtleCommand::Push => {
+                    turtle.push();
+                }
+                TurtleCommand::Pop => {
+                    turtle
+                        .pop()
+                        .map_err(|_| String::from("unbalanced ] in turtle program"))?;
+                }
+            }
+        }
+
+        Ok(turtle.to_tile())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_3};
+
+    use crate::Complex;
+
+    use super::*;
+
+    fn endpoints(tile: &ClineArcTile) -> Vec<(Complex, Complex)> {
+        tile.get_primitives()
+            .iter()
+            .map(|x| (x.start(), x.end()))
+            .collect()
+    }
+
+    #[test]
+    pub fn new_with_duplicate_rules_returns_error() {
+        let result = LSystem::new("F", &[('F', "FF"), ('F', "F+F")]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn iterate_rewrites_algae() {
+        let lsystem = LSystem::new("A", &[('A', "AB"), ('B', "A")]).unwrap();
+
+        let result: Vec<String> = (0..5).map(|n| lsystem.iterate(n)).collect();
+
+        assert_eq!(result, vec!["A", "AB", "ABA", "ABAAB", "ABAABABA"]);
+    }
+
+    #[test]
+    pub fn koch_curve_has_four_to_the_n_segments() {
+        let lsystem = LSystem::new("F", &[('F', "F+F--F+F")]).unwrap();
+        let turtle = Turtle::new(1.0, FRAC_PI_3).unwrap();
+
+        let result = turtle.interpret(&lsystem.iterate(3)).unwrap();
+
+        assert_eq!(result.get_primitives().len(), 64);
+    }
+
+    #[test]
+    pub fn square_closes_up() {
+        let turtle = Turtle::new(1.0, FRAC_PI_2).unwrap();
+
+        let result = turtle.interpret("F+F+F+F+").unwrap();
+
+        let expected = vec![
+            (Complex::Zero, Complex::ONE),
+            (Complex::ONE, Complex::new(1.0, 1.0)),
+            (Complex::new(1.0, 1.0), Complex::I),
+            (Complex::I, Complex::Zero),
+        ];
+        assert_eq!(endpoints(&result), expected);
+    }
+
+    #[test]
+    pub fn push_and_pop_restore_turtle() {
+        let turtle = Turtle::new(1.0, FRAC_PI_2).unwrap();
+
+        let result = turtle.interpret("F[+F]F").unwrap();
+
+        let expected = vec![
+            (Complex::Zero, Complex::ONE),
+            (Complex::ONE, Complex::new(1.0, 1.0)),
+            (Complex::ONE, Complex::new(2.0, 0.0)),
+        ];
+        assert_eq!(endpoints(&result), expected);
+    }
+
+    #[test]
+    pub fn unbalanced_pop_returns_error() {
+        let turtle = Turtle::new(1.0, FRAC_PI_2).unwrap();
+
+        let result = turtle.interpret("F]");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn arcs_turn_the_turtle() {
+        // Four quarter turns to the left trace out a circle
+        let turtle = Turtle::new(FRAC_PI_2, 0.0)
+            .unwrap()
+            .with_command('A', TurtleCommand::Arc(FRAC_PI_2));
+
+        let result = turtle.interpret("AAAAF").unwrap();
+
+        let expected = vec![
+            (Complex::Zero, Complex::new(1.0, 1.0)),
+            (Complex::new(1.0, 1.0), Complex::new(0.0, 2.0)),
+            (Complex::new(0.0, 2.0), Complex::new(-1.0, 1.0)),
+            (Complex::new(-1.0, 1.0), Complex::Zero),
+            (Complex::Zero, Complex::new(FRAC_PI_2, 0.0)),
+        ];
+        assert_eq!(endpoints(&result), expected);
+    }
+
+    #[test]
+    pub fn move_does_not_draw() {
+        let turtle = Turtle::new(1.0, FRAC_PI_2).unwrap();
+
+        let result = turtle.interpret("fF").unwrap();
+
+        assert_eq!(
+            endpoints(&result),
+            vec![(Complex::ONE, Complex::new(2.0, 0.0))]
+        );
+    }
+}
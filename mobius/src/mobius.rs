@@ -1,8 +1,14 @@
-use std::{fmt::Display, ops::Mul};
+use std::{f64::consts::PI, fmt::Display, ops::Mul, str::FromStr};
 
-use abstraction::{Group, Monoid};
+use abstraction::{Group, GroupAction, Monoid};
 
-use crate::{complex::Complex, nearly::is_nearly};
+use crate::{
+    algorithms::quantize, complex::Complex, group_spec::parse_complex, nearly::is_nearly, Tolerance,
+};
+
+/// How close q * theta / 2pi must be to an integer (per unit of q) for
+/// Mobius::order() to accept q as the order
+const ORDER_TOLERANCE: f64 = 1e-9;
 
 #[derive(PartialEq, Debug)]
 pub enum MobiusType {
@@ -21,6 +27,18 @@ pub enum MobiusType {
     Loxodromic,
 }
 
+impl Display for MobiusType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Parabolic => "parabolic",
+            Self::Elliptic => "elliptic",
+            Self::Hyperbolic => "hyperbolic",
+            Self::Loxodromic => "loxodromic",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 #[derive(Debug)]
 pub enum FixedPoints {
     Single(Complex),
@@ -36,6 +54,22 @@ impl Display for FixedPoints {
     }
 }
 
+/// Fixed points and multiplier of a Mobius transformation T, which
+/// describe it up to the choice of coordinates.
+///
+/// - With a single fixed point p, T is parabolic. Conjugating by
+///   S(z) = 1/(z - p) (or S(z) = z if p is infinity) turns T into the
+///   translation z -> z + multiplier. The identity is the translation by 0
+///   with fixed point infinity.
+/// - With a pair of fixed points (p, q), conjugating by a map S that sends
+///   p to 0 and q to infinity turns T into z -> multiplier * z. The
+///   multiplier is the derivative T'(p), and |k| < 1 means p is the sink.
+#[derive(Debug)]
+pub struct MobiusNormalForm {
+    pub fixed_points: FixedPoints,
+    pub multiplier: Complex,
+}
+
 /// A Mobius transformation is a function
 ///
 /// M(z) = (az + b) / (cz + d)
@@ -57,6 +91,56 @@ pub struct Mobius {
 }
 
 impl Mobius {
+    /// The identity function I(z) = z, implemented
+    /// as (1z + 0) / (0z + 1)
+    pub const IDENTITY: Self =
+        Self::from_exact(Complex::ONE, Complex::Zero, Complex::Zero, Complex::ONE);
+
+    /// Complex inversion nu(z) = 1/z, implemented as
+    /// (0z + i) / (iz + 0) to have determinant 1
+    pub const INVERSION: Self =
+        Self::from_exact(Complex::Zero, Complex::I, Complex::I, Complex::Zero);
+
+    /// Point reflection through the origin P(z) = -z, implemented as
+    /// (iz + 0) / (0z - i) to have determinant 1. This is a rotation
+    /// by pi, but without the rounding error from computing sin/cos.
+    pub const POINT_REFLECTION: Self = Self::from_exact(
+        Complex::I,
+        Complex::Zero,
+        Complex::Zero,
+        Complex::Finite(0.0, -1.0),
+    );
+
+    /// The Cayley map that sends the upper half-plane to the unit disk.
+    /// See recipes::cayley_map() for the derivation. The matrix
+    ///
+    /// [1 -i]
+    /// [1  i]
+    ///
+    /// is divided by (1 + i) to get determinant 1, which has exact
+    /// entries of +/- 1/2 +/- i/2
+    pub const CAYLEY_MAP: Self = Self::from_exact(
+        Complex::Finite(0.5, -0.5),
+        Complex::Finite(-0.5, -0.5),
+        Complex::Finite(0.5, -0.5),
+        Complex::Finite(0.5, 0.5),
+    );
+
+    /// Step size for quantized_key()
+    pub const QUANTIZATION_STEP: f64 = 1e-9;
+
+    /// Largest element order that order() looks for. Elliptic elements
+    /// with a larger order are treated as irrational rotations
+    pub const MAX_ORDER: usize = 1000;
+
+    /// Const constructor for matrices whose entries are exactly
+    /// representable and known to have determinant 1. This skips the
+    /// checks in new(), so it is only used for the associated constants
+    /// (which are checked in the unit tests)
+    const fn from_exact(a: Complex, b: Complex, c: Complex, d: Complex) -> Self {
+        Self { a, b, c, d }
+    }
+
     /// Constructor
     ///
     /// This enforces that a, b, c, d are all Zero or Finite and
@@ -93,6 +177,10 @@ impl Mobius {
         }
 
         let det = a * d - b * c;
+        if det == Complex::Zero {
+            return Err(String::from("matrix must be invertible, ad - bc = 0"));
+        }
+
         let normalize = det.sqrt().inverse();
         if det == Complex::ONE {
             Ok(Self { a, b, c, d })
@@ -120,7 +208,8 @@ impl Mobius {
 
     /// Classify the Mobius transformation as
     /// parabolic, elliptic, hyperbolic, or loxodromic
-    /// depending on the trace
+    /// depending on the trace. See figures::normal_forms for
+    /// flow portraits of each type
     pub fn classify(&self) -> MobiusType {
         let tr = self.trace();
 
@@ -142,6 +231,39 @@ impl Mobius {
         }
     }
 
+    /// Smallest n > 0 with M^n = I, or None for elements of infinite order.
+    /// Only elliptic elements (and the identity) have finite order. An
+    /// elliptic element is conjugate to a rotation by angle theta with
+    /// trace +/- 2cos(theta/2), so the order is the denominator of
+    /// theta / 2pi = p/q. This is read off the trace rather than found by
+    /// iterating, so rounding error doesn't build up. Denominators above
+    /// MAX_ORDER are treated as infinite order.
+    pub fn order(&self) -> Option<usize> {
+        if *self == Self::IDENTITY {
+            return Some(1);
+        }
+
+        if self.classify() != MobiusType::Elliptic {
+            return None;
+        }
+
+        // The sign of the trace doesn't matter: it replaces theta with
+        // 2pi - theta, which has the same order
+        let half_angle = (self.trace().real() / 2.0).clamp(-1.0, 1.0).acos();
+        let turns = half_angle / PI;
+        (1..=Self::MAX_ORDER).find(|&q| {
+            let multiple = q as f64 * turns;
+            (multiple - multiple.round()).abs() < ORDER_TOLERANCE * q as f64
+        })
+    }
+
+    /// Check if other = S self S^-1 for some transformation S, i.e. they
+    /// do the same thing in different coordinates. See
+    /// trace_field::conjugating_map() to solve for S
+    pub fn is_conjugate_to(&self, other: &Self) -> bool {
+        crate::trace_field::conjugating_map(*self, *other).is_ok()
+    }
+
     /// Since we assume det 1, the inverse transformation
     /// is a simplified matrix inverse
     ///
@@ -158,6 +280,13 @@ impl Mobius {
         }
     }
 
+    /// Compose the transformation with itself. Negative exponents use the
+    /// inverse. This is Group::pow, but callable without importing the
+    /// trait
+    pub fn pow(&self, exponent: i32) -> Self {
+        Group::pow(self, exponent as isize)
+    }
+
     /// Compute the distance
     /// |M(z) - M(w)| = |z - w| / (|cz + d||cw + d|)
     pub fn distance(&self, z: Complex, w: Complex) -> f64 {
@@ -170,6 +299,19 @@ impl Mobius {
         numerator / denominator
     }
 
+    /// Local scale factor |M'(z)| = 1 / |cz + d|^2, i.e. how much
+    /// M stretches (> 1) or shrinks (< 1) a small neighborhood of z.
+    /// At infinity this is 0 unless M is affine (c = 0), in which case
+    /// it is the constant 1 / |d|^2
+    pub fn scale_factor(&self, z: Complex) -> f64 {
+        let &Self { c, d, .. } = self;
+        match z {
+            Complex::Infinity if c == Complex::Zero => 1.0 / d.norm(),
+            Complex::Infinity => 0.0,
+            _ => 1.0 / (c * z + d).norm(),
+        }
+    }
+
     pub fn fixed_points(&self) -> FixedPoints {
         let &Self { a, b, c, d } = self;
 
@@ -212,14 +354,79 @@ impl Mobius {
         }
     }
 
-    // TODO: solve for the parameter k (loxodromic, elliptic, hyperbolic)
-    // or the displacement d (parabolic)
-    // this involves:
-    // 1. Finding the fixed points
-    // 2. Compute a transform S such that S(P) = inf
-    // 3. If there was only 1 fixed point, S 🥪 T = translation, so just extract
-    //      the translation amount
-    // 4. Otherwise, recompute S so that S(Q) = 0
+    /// Solve for the fixed points and the multiplier. See MobiusNormalForm
+    /// for what the multiplier means
+    pub fn normal_form(&self) -> MobiusNormalForm {
+        let &Self { b, c, d, .. } = self;
+        let fixed_points = self.fixed_points();
+        let multiplier = match fixed_points {
+            // z -> z + b/d
+            FixedPoints::Single(Complex::Infinity) => b / d,
+            // Since T is parabolic, (cp + d) = (a - cp) = tr(T)/2 = +/- 1.
+            // Then 1/(T(z) - p) = 1/(z - p) + c/(cp + d)
+            FixedPoints::Single(p) => c / (c * p + d),
+            // T'(p) = 1/(cp + d)^2. The fixed point at infinity (if any)
+            // is always listed second
+            FixedPoints::Pair(p, _) => {
+                let denominator = c * p + d;
+                (denominator * denominator).inverse()
+            }
+        };
+
+        MobiusNormalForm {
+            fixed_points,
+            multiplier,
+        }
+    }
+
+    /// Inverse of normal_form(). See MobiusNormalForm for the meaning of the
+    /// multiplier.
+    pub fn from_fixed_points_and_multiplier(
+        fixed_points: FixedPoints,
+        multiplier: Complex,
+    ) -> Result<Self, String> {
+        if multiplier == Complex::Infinity {
+            return Err(String::from("multiplier must be finite"));
+        }
+
+        match fixed_points {
+            FixedPoints::Single(Complex::Infinity) => {
+                Self::new(Complex::ONE, multiplier, Complex::Zero, Complex::ONE)
+            }
+            FixedPoints::Single(p) => {
+                // S(z) = 1 / (z - p) sends p to infinity and conjugates
+                // T to a translation
+                let to_infinity =
+                    Self::from_unnormalized(Complex::Zero, Complex::ONE, Complex::ONE, -p)?;
+                let translation = Self::new(Complex::ONE, multiplier, Complex::Zero, Complex::ONE)?;
+                Ok(Self::sandwich(to_infinity.inverse(), translation))
+            }
+            FixedPoints::Pair(p, q) if p == q => {
+                Err(format!("fixed points must be distinct, got {} twice", p))
+            }
+            FixedPoints::Pair(p, q) => {
+                if multiplier == Complex::Zero {
+                    return Err(String::from("multiplier must be nonzero"));
+                }
+
+                // S sends p to 0 and q to infinity, and conjugates T to
+                // z -> kz
+                let (sa, sb, sc, sd) = match (p, q) {
+                    (Complex::Infinity, q) => (Complex::Zero, Complex::ONE, Complex::ONE, -q),
+                    (p, Complex::Infinity) => (Complex::ONE, -p, Complex::Zero, Complex::ONE),
+                    (p, q) => (Complex::ONE, -p, Complex::ONE, -q),
+                };
+                let to_standard = Self::from_unnormalized(sa, sb, sc, sd)?;
+                let scale = Self::from_unnormalized(
+                    multiplier,
+                    Complex::Zero,
+                    Complex::Zero,
+                    Complex::ONE,
+                )?;
+                Ok(Self::sandwich(to_standard.inverse(), scale))
+            }
+        }
+    }
 
     /// Take the complex conjugate of each entry. This is used for
     /// anticonformal mappings, see isogonal.rs
@@ -231,6 +438,28 @@ impl Mobius {
             d: self.d.conj(),
         }
     }
+
+    /// Round the coefficients to multiples of QUANTIZATION_STEP so
+    /// transformations can be hashed. Since M and -M are the same
+    /// transformation, this picks whichever of the two keys sorts first.
+    /// Transformations that straddle a rounding boundary may get different
+    /// keys even if they are equal, but unequal transformations never share
+    /// a key unless they are within the step size.
+    pub fn quantized_key(&self) -> [i64; 8] {
+        let key = [
+            quantize(self.a.real()),
+            quantize(self.a.imag()),
+            quantize(self.b.real()),
+            quantize(self.b.imag()),
+            quantize(self.c.real()),
+            quantize(self.c.imag()),
+            quantize(self.d.real()),
+            quantize(self.d.imag()),
+        ];
+        let negated = key.map(|x| -x);
+
+        key.min(negated)
+    }
 }
 
 impl Mul for Mobius {
@@ -295,12 +524,11 @@ impl Monoid for Mobius {
     // The identity function I(z) = z, implemented
     // as (1z + 0) / (0z + 1)
     fn identity() -> Self {
-        Self {
-            a: Complex::ONE,
-            b: Complex::Zero,
-            c: Complex::Zero,
-            d: Complex::ONE,
-        }
+        Self::IDENTITY
+    }
+
+    fn check_invariants(&self) -> Result<(), String> {
+        crate::invariants::check_mobius(self)
     }
 }
 
@@ -310,6 +538,12 @@ impl Group for Mobius {
     }
 }
 
+impl GroupAction<Complex> for Mobius {
+    fn act(&self, x: &Complex) -> Complex {
+        *self * *x
+    }
+}
+
 impl PartialEq for Mobius {
     fn eq(&self, other: &Self) -> bool {
         // Subtlety that Indra's Pearls doesn't explain!
@@ -339,12 +573,330 @@ impl Display for Mobius {
     }
 }
 
+/// Polynomial in z, as coefficients in increasing powers of z
+type Polynomial = Vec<Complex>;
+
+fn poly_add(p: &[Complex], q: &[Complex]) -> Polynomial {
+    (0..p.len().max(q.len()))
+        .map(|i| *p.get(i).unwrap_or(&Complex::Zero) + *q.get(i).unwrap_or(&Complex::Zero))
+        .collect()
+}
+
+fn poly_mul(p: &[Complex], q: &[Complex]) -> Polynomial {
+    let mut result = vec![Complex::Zero; p.len() + q.len() - 1];
+    for (i, x) in p.iter().enumerate() {
+        for (j, y) in q.iter().enumerate() {
+            result[i + j] = result[i + j] + *x * *y;
+        }
+    }
+    result
+}
+
+/// Coefficients of z^0 and z^1, if the polynomial is at most linear
+fn poly_linear(p: &[Complex]) -> Option<(Complex, Complex)> {
+    let degree = p.iter().rposition(|x| *x != Complex::Zero).unwrap_or(0);
+    (degree <= 1).then(|| (p[0], *p.get(1).unwrap_or(&Complex::Zero)))
+}
+
+/// Rational function of z built up while parsing an expression. Only
+/// the final result needs to be a Mobius transformation, so intermediate
+/// values like z/2 in z/2 + 1 are fine
+#[derive(Clone)]
+struct Rational {
+    numerator: Polynomial,
+    denominator: Polynomial,
+}
+
+impl Rational {
+    fn constant(value: Complex) -> Self {
+        Self {
+            numerator: vec![value],
+            denominator: vec![Complex::ONE],
+        }
+    }
+
+    fn z() -> Self {
+        Self {
+            numerator: vec![Complex::Zero, Complex::ONE],
+            denominator: vec![Complex::ONE],
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        // Keep a shared denominator as is so 1/z + 1/z stays degree 1
+        if self.denominator == other.denominator {
+            return Self {
+                numerator: poly_add(&self.numerator, &other.numerator),
+                denominator: self.denominator,
+            };
+        }
+
+        Self {
+            numerator: poly_add(
+                &poly_mul(&self.numerator, &other.denominator),
+                &poly_mul(&other.numerator, &self.denominator),
+            ),
+            denominator: poly_mul(&self.denominator, &other.denominator),
+        }
+    }
+
+    fn neg(self) -> Self {
+        Self {
+            numerator: self.numerator.into_iter().map(|x| -x).collect(),
+            denominator: self.denominator,
+        }
+    }
+
+    fn mul(self, other: Self) -> Self {
+        Self {
+            numerator: poly_mul(&self.numerator, &other.numerator),
+            denominator: poly_mul(&self.denominator, &other.denominator),
+        }
+    }
+
+    fn recip(self) -> Self {
+        Self {
+            numerator: self.denominator,
+            denominator: self.numerator,
+        }
+    }
+}
+
+/// Recursive descent parser for expressions in z with the usual
+/// precedence. Juxtaposition like 2z or 2(z + 1) is multiplication.
+///
+/// expression = term (('+' | '-') term)*
+/// term = unary (('*' | '/')? unary)*
+/// unary = ('+' | '-') unary | number | 'i' | 'z' | '(' expression ')'
+struct ExpressionParser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> ExpressionParser<'a> {
+    /// Parse text with whitespace already removed
+    fn parse(text: &'a str) -> Result<Rational, String> {
+        let mut parser = Self { text, position: 0 };
+        let result = parser.expression()?;
+        match parser.peek() {
+            None => Ok(result),
+            Some(c) => Err(format!("unexpected {:?} in {:?}", c, text)),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    fn advance(&mut self) {
+        self.position += self.peek().map_or(0, char::len_utf8);
+    }
+
+    fn expression(&mut self) -> Result<Rational, String> {
+        let mut result = self.term()?;
+        while let Some(c @ ('+' | '-')) = self.peek() {
+            self.advance();
+            let term = self.term()?;
+            result = result.add(if c == '-' { term.neg() } else { term });
+        }
+        Ok(result)
+    }
+
+    fn term(&mut self) -> Result<Rational, String> {
+        let mut result = self.unary()?;
+        loop {
+            match self.peek() {
+                Some('*') => {
+                    self.advance();
+                    result = result.mul(self.unary()?);
+                }
+                Some('/') => {
+                    self.advance();
+                    result = result.mul(self.unary()?.recip());
+                }
+                Some(c) if c.is_ascii_digit() || matches!(c, '.' | 'i' | 'z' | '(') => {
+                    result = result.mul(self.unary()?);
+                }
+                _ => return Ok(result),
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<Rational, String> {
+        match self.peek() {
+            Some('-') => {
+                self.advance();
+                Ok(self.unary()?.neg())
+            }
+            Some('+') => {
+                self.advance();
+                self.unary()
+            }
+            Some('i') => {
+                self.advance();
+                Ok(Rational::constant(Complex::I))
+            }
+            Some('z') => {
+                self.advance();
+                Ok(Rational::z())
+            }
+            Some('(') => {
+                self.advance();
+                let result = self.expression()?;
+                if self.peek() != Some(')') {
+                    return Err(format!("missing ')' in {:?}", self.text));
+                }
+                self.advance();
+                Ok(result)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => self.number(),
+            Some(c) => Err(format!("unexpected {:?} in {:?}", c, self.text)),
+            None => Err(format!("missing term in {:?}", self.text)),
+        }
+    }
+
+    /// A real number like 2, 0.5 or 1e-3
+    fn number(&mut self) -> Result<Rational, String> {
+        let start = self.position;
+        while let Some(c) = self.peek() {
+            let previous = self.text[start..self.position].chars().last();
+            let is_exponent_sign = matches!(c, '+' | '-') && matches!(previous, Some('e' | 'E'));
+            if !(c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E') || is_exponent_sign) {
+                break;
+            }
+            self.advance();
+        }
+
+        let digits = &self.text[start..self.position];
+        let value: f64 = digits
+            .parse()
+            .map_err(|_| format!("invalid number {:?}", digits))?;
+        Ok(Rational::constant(Complex::new(value, 0.0)))
+    }
+}
+
+/// Parse the matrix entries from [[a, b], [c, d]]. Whitespace must
+/// already be removed
+fn parse_matrix(text: &str) -> Result<[Complex; 4], String> {
+    let error = || format!("expected a matrix like [[a, b], [c, d]], got {:?}", text);
+    let rows = text
+        .strip_prefix("[[")
+        .and_then(|x| x.strip_suffix("]]"))
+        .ok_or_else(error)?;
+
+    let entries = rows
+        .split("],[")
+        .flat_map(|row| row.split(','))
+        .map(parse_complex)
+        .collect::<Result<Vec<Complex>, String>>()?;
+    let [a, b, c, d] = entries[..] else {
+        return Err(error());
+    };
+
+    Ok([a, b, c, d])
+}
+
+/// Parse a Mobius transformation either as an expression in z like
+/// (2z + i)/(z - 1), 1/z + 1 or 2(z + 3), or in matrix syntax like
+/// [[2, i], [1, -1]]. The matrix doesn't need determinant 1, it is
+/// normalized like from_unnormalized(), but it must be invertible.
+///
+/// Parsing doesn't use the absolute tolerance, so tiny entries like the
+/// 1e-20 in [[1e-20, 0], [0, 1e20]] are kept rather than rounded to zero.
+/// Invertibility is checked relative to the size of the entries.
+impl FromStr for Mobius {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        let relative = Tolerance::current().relative;
+
+        Tolerance::new(0.0, relative)?.scope(|| {
+            let [a, b, c, d] = if text.starts_with('[') {
+                parse_matrix(&text)?
+            } else {
+                let Rational {
+                    numerator,
+                    denominator,
+                } = ExpressionParser::parse(&text)?;
+                let error = || format!("{:?} is not of the form (az + b)/(cz + d)", s);
+                let (b, a) = poly_linear(&numerator).ok_or_else(error)?;
+                let (d, c) = poly_linear(&denominator).ok_or_else(error)?;
+                [a, b, c, d]
+            };
+
+            let size = (a * d).mag().max((b * c).mag());
+            if (a * d - b * c).mag() <= relative * size {
+                return Err(format!("{:?} is not invertible, ad - bc = 0", s));
+            }
+
+            Self::from_unnormalized(a, b, c, d)
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use abstraction::{test_associativity, test_group, test_identity};
+    use test_case::test_case;
 
     use super::*;
 
+    #[test_case("(2z + i)/(z - 1)", [2.0.into(), Complex::I, Complex::ONE, -Complex::ONE]; "fraction")]
+    #[test_case("[[2, i], [1, -1]]", [2.0.into(), Complex::I, Complex::ONE, -Complex::ONE]; "matrix")]
+    #[test_case("1/z", [Complex::Zero, Complex::ONE, Complex::ONE, Complex::Zero]; "inversion")]
+    #[test_case("z + 3", [Complex::ONE, 3.0.into(), Complex::Zero, Complex::ONE]; "translation")]
+    #[test_case("-z", [-Complex::ONE, Complex::Zero, Complex::Zero, Complex::ONE]; "negation")]
+    #[test_case("((1+i)z - 1e-3) / 2", [Complex::new(1.0, 1.0), (-1e-3).into(), Complex::Zero, 2.0.into()]; "complex coefficient")]
+    #[test_case("(3*z)/(-2iz + 1)", [3.0.into(), Complex::Zero, Complex::new(0.0, -2.0), Complex::ONE]; "explicit multiplication")]
+    #[test_case("z/2 + 1", [Complex::ONE, 2.0.into(), Complex::Zero, 2.0.into()]; "division binds tighter than addition")]
+    #[test_case("1/z + 1", [Complex::ONE, Complex::ONE, Complex::ONE, Complex::Zero]; "reciprocal plus constant")]
+    #[test_case("2(z+1)", [2.0.into(), 2.0.into(), Complex::Zero, Complex::ONE]; "implicit multiplication by parentheses")]
+    #[test_case("z*2", [2.0.into(), Complex::Zero, Complex::Zero, Complex::ONE]; "coefficient after z")]
+    #[test_case("-(z)", [-Complex::ONE, Complex::Zero, Complex::Zero, Complex::ONE]; "negated parentheses")]
+    #[test_case("1/z + 1/z", [Complex::Zero, 2.0.into(), Complex::ONE, Complex::Zero]; "shared denominator")]
+    #[test_case("1/(1/z)", [Complex::ONE, Complex::Zero, Complex::Zero, Complex::ONE]; "nested division")]
+    pub fn from_str_parses_transformation(text: &str, entries: [Complex; 4]) {
+        let [a, b, c, d] = entries;
+        let expected = Mobius::from_unnormalized(a, b, c, d).unwrap();
+
+        let result: Mobius = text.parse().unwrap();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test_case("(2z + 4)/(z + 2)"; "not invertible")]
+    #[test_case("[[1, 2], [2, 4]]"; "singular matrix")]
+    #[test_case("z^2"; "not linear")]
+    #[test_case("z/z/z"; "not a mobius transformation")]
+    #[test_case("z + 1/z"; "degree two")]
+    #[test_case("(z + 1"; "unclosed parenthesis")]
+    #[test_case("[[1, 2], [3]]"; "missing matrix entry")]
+    #[test_case("2z + "; "missing term")]
+    #[test_case(""; "empty")]
+    pub fn from_str_with_invalid_text_returns_error(text: &str) {
+        let result: Result<Mobius, String> = text.parse();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn from_str_keeps_tiny_matrix_entries() {
+        let result: Mobius = "[[1e-20, 0], [0, 1e20]]".parse().unwrap();
+
+        assert_eq!(result.a.real(), 1e-20);
+        assert_eq!(result.d.real(), 1e20);
+    }
+
+    #[test]
+    pub fn from_unnormalized_returns_error_for_singular_matrix() {
+        let two: Complex = 2.0.into();
+
+        let result = Mobius::from_unnormalized(Complex::ONE, two, two, 4.0.into());
+
+        assert!(result.is_err_and(|e| e.contains("invertible")));
+    }
+
     #[test]
     pub fn new_returns_error_for_infinite_entry() {
         let result = Mobius::new(
@@ -456,6 +1008,70 @@ mod test {
         ]
     );
 
+    #[test]
+    pub fn scale_factor_of_similarity_is_constant() {
+        // z -> 4z + 1, written with determinant 1
+        let m = Mobius::new(2.0.into(), 0.5.into(), Complex::Zero, 0.5.into()).unwrap();
+
+        assert_eq!(m.scale_factor(Complex::Zero), 4.0);
+        assert_eq!(m.scale_factor(Complex::new(3.0, -2.0)), 4.0);
+        assert_eq!(m.scale_factor(Complex::Infinity), 4.0);
+    }
+
+    #[test]
+    pub fn scale_factor_of_inversion_is_one_over_r_squared() {
+        let z = Complex::new(0.0, 2.0);
+
+        let result = Mobius::INVERSION.scale_factor(z);
+
+        assert_eq!(result, 0.25);
+        assert_eq!(Mobius::INVERSION.scale_factor(Complex::Infinity), 0.0);
+    }
+
+    #[test]
+    pub fn constants_have_determinant_one() {
+        let constants = [
+            Mobius::IDENTITY,
+            Mobius::INVERSION,
+            Mobius::POINT_REFLECTION,
+            Mobius::CAYLEY_MAP,
+        ];
+
+        for xform in constants {
+            assert_eq!(xform.det(), Complex::ONE);
+        }
+    }
+
+    #[test]
+    pub fn point_reflection_negates_point() {
+        let z = Complex::new(4.0, 3.0);
+
+        let result = Mobius::POINT_REFLECTION * z;
+
+        assert_eq!(result, -z);
+    }
+
+    #[test]
+    pub fn point_reflection_is_an_involution() {
+        let result = Mobius::POINT_REFLECTION * Mobius::POINT_REFLECTION;
+
+        assert_eq!(result, Mobius::IDENTITY);
+    }
+
+    #[test]
+    pub fn quantized_key_is_same_for_negated_matrix() {
+        let m = Mobius::from_unnormalized(
+            Complex::new(1.0, 2.0),
+            Complex::new(-3.0, 0.5),
+            Complex::new(0.0, 1.0),
+            Complex::new(4.0, -1.0),
+        )
+        .unwrap();
+        let negated = Mobius::new(-m.a, -m.b, -m.c, -m.d).unwrap();
+
+        assert_eq!(m.quantized_key(), negated.quantized_key());
+    }
+
     #[test]
     pub fn identity_maps_point_to_itself() {
         let z = Complex::new(4.0, 3.0);
@@ -464,4 +1080,176 @@ mod test {
 
         assert_eq!(result, z);
     }
+
+    fn assert_close(a: Mobius, b: Mobius) {
+        // Points away from the poles of the test transformations
+        for z in [
+            Complex::new(1.5, 2.0),
+            Complex::new(0.3, -1.2),
+            Complex::new(-2.0, 0.5),
+        ] {
+            assert!((a * z - b * z).mag() < 1e-10, "{} != {}", a, b);
+        }
+    }
+
+    #[test]
+    pub fn normal_form_of_scale_is_multiplier() {
+        let m = Mobius::from_unnormalized(
+            Complex::new(2.0, 0.0),
+            Complex::Zero,
+            Complex::Zero,
+            Complex::ONE,
+        )
+        .unwrap();
+
+        let result = m.normal_form();
+
+        assert!(matches!(
+            result.fixed_points,
+            FixedPoints::Pair(Complex::Zero, Complex::Infinity)
+        ));
+        assert_eq!(result.multiplier, Complex::new(2.0, 0.0));
+    }
+
+    #[test]
+    pub fn normal_form_of_identity_is_zero_translation() {
+        let result = Mobius::IDENTITY.normal_form();
+
+        assert!(matches!(
+            result.fixed_points,
+            FixedPoints::Single(Complex::Infinity)
+        ));
+        assert_eq!(result.multiplier, Complex::Zero);
+    }
+
+    #[test]
+    pub fn normal_form_multiplier_is_derivative_at_fixed_point() {
+        let m = Mobius::from_unnormalized(
+            Complex::new(2.0, 1.0),
+            Complex::new(1.0, -1.0),
+            Complex::new(0.5, 0.0),
+            Complex::new(1.0, 0.5),
+        )
+        .unwrap();
+
+        let result = m.normal_form();
+
+        let FixedPoints::Pair(p, _) = result.fixed_points else {
+            panic!("expected two fixed points");
+        };
+        let h = Complex::new(1e-6, 0.0);
+        let derivative = (m * (p + h) - m * (p - h)) / (h + h);
+        assert!((derivative - result.multiplier).mag() < 1e-6);
+    }
+
+    #[test_case(Mobius::from_unnormalized(Complex::new(2.0, 1.0), Complex::new(1.0, -1.0), Complex::new(0.5, 0.0), Complex::new(1.0, 0.5)).unwrap(); "loxodromic")]
+    #[test_case(Mobius::new(Complex::ONE, Complex::new(3.0, 1.0), Complex::Zero, Complex::ONE).unwrap(); "translation")]
+    #[test_case(Mobius::new(Complex::ONE, Complex::Zero, Complex::new(1.0, 2.0), Complex::ONE).unwrap(); "parabolic")]
+    #[test_case(Mobius::new(-Complex::ONE, Complex::Zero, Complex::new(1.0, 2.0), -Complex::ONE).unwrap(); "parabolic with negative trace")]
+    #[test_case(Mobius::INVERSION; "elliptic")]
+    #[test_case(Mobius::from_unnormalized(Complex::Zero, Complex::ONE, Complex::ONE, Complex::new(3.0, 0.0)).unwrap(); "hyperbolic")]
+    #[test_case(Mobius::IDENTITY; "identity")]
+    pub fn from_fixed_points_and_multiplier_inverts_normal_form(m: Mobius) {
+        let MobiusNormalForm {
+            fixed_points,
+            multiplier,
+        } = m.normal_form();
+
+        let result = Mobius::from_fixed_points_and_multiplier(fixed_points, multiplier).unwrap();
+
+        assert_close(result, m);
+    }
+
+    #[test]
+    pub fn from_fixed_points_and_multiplier_with_infinity_first() {
+        let result = Mobius::from_fixed_points_and_multiplier(
+            FixedPoints::Pair(Complex::Infinity, Complex::ONE),
+            Complex::new(0.0, 2.0),
+        )
+        .unwrap();
+
+        assert_eq!(result * Complex::Infinity, Complex::Infinity);
+        assert_eq!(result * Complex::ONE, Complex::ONE);
+        let result_form = result.normal_form();
+        // The pair is listed with infinity second, so the multiplier is
+        // the reciprocal
+        assert_eq!(result_form.multiplier, Complex::new(0.0, -0.5));
+    }
+
+    #[test]
+    pub fn from_fixed_points_and_multiplier_with_repeated_point_returns_error() {
+        let result = Mobius::from_fixed_points_and_multiplier(
+            FixedPoints::Pair(Complex::ONE, Complex::ONE),
+            Complex::new(2.0, 0.0),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn pow_with_negative_exponent_uses_inverse() {
+        let m = Mobius::new(
+            Complex::new(2.0, 0.0),
+            Complex::ONE,
+            Complex::ONE,
+            Complex::ONE,
+        )
+        .unwrap();
+
+        let result = m.pow(-3);
+
+        assert_eq!(result, m.inverse() * m.inverse() * m.inverse());
+        assert_eq!(result * m.pow(3), Mobius::IDENTITY);
+    }
+
+    #[test]
+    pub fn compose_multiplies_left_to_right() {
+        let a = Mobius::new(
+            Complex::new(2.0, 0.0),
+            Complex::ONE,
+            Complex::ONE,
+            Complex::ONE,
+        )
+        .unwrap();
+        let b = Mobius::INVERSION;
+
+        let result = abstraction::compose![a, b, a.inverse()];
+
+        assert_eq!(result, a * b * a.inverse());
+        assert_ne!(result, b);
+    }
+
+    #[test_case(crate::rotation(std::f64::consts::TAU / 5.0).unwrap(), Some(5); "elliptic of order 5")]
+    #[test_case(crate::translation(Complex::ONE).unwrap(), None; "parabolic")]
+    #[test_case(crate::scale(2.0).unwrap(), None; "hyperbolic")]
+    pub fn power_iter_order_with_cap_terminates(m: Mobius, expected: Option<usize>) {
+        let result = m.power_iter().take_or_cycle(100).order();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test_case(Mobius::IDENTITY, Some(1); "identity")]
+    #[test_case(Mobius::INVERSION, Some(2); "inversion")]
+    #[test_case(crate::rotation(std::f64::consts::TAU / 5.0).unwrap(), Some(5); "fifth turn")]
+    #[test_case(crate::rotation(2.0 * std::f64::consts::TAU / 5.0).unwrap(), Some(5); "two fifths turn")]
+    #[test_case(crate::rotation(std::f64::consts::TAU / 7.0).unwrap().inverse(), Some(7); "negative seventh turn")]
+    #[test_case(Mobius::CAYLEY_MAP * crate::rotation(PI / 3.0).unwrap() * Mobius::CAYLEY_MAP.inverse(), Some(6); "conjugated sixth turn")]
+    #[test_case(crate::rotation(1.0).unwrap(), None; "irrational rotation")]
+    #[test_case(crate::translation(Complex::ONE).unwrap(), None; "parabolic")]
+    #[test_case(crate::scale(2.0).unwrap(), None; "hyperbolic")]
+    pub fn order_uses_trace(m: Mobius, expected: Option<usize>) {
+        let result = m.order();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn order_matches_power_iter() {
+        let m = crate::rotation(3.0 * std::f64::consts::TAU / 8.0).unwrap();
+
+        let result = m.order();
+
+        assert_eq!(result, m.power_iter().take_or_cycle(100).order());
+        assert_eq!(result, Some(8));
+    }
 }
@@ -0,0 +1,203 @@
+use std::f64::consts::TAU;
+
+use crate::{
+    cline_arc::ClineArc,
+    geometry::LineSegment,
+    integer_arcs::orthogonal_arc,
+    transformable::{Cline, ClineArcTile, Motif, Side},
+    Complex,
+};
+
+/// How to draw the connection between two points on the unit circle
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChordKind {
+    /// Straight line segments
+    Straight,
+    /// Arcs orthogonal to the unit circle, i.e. hyperbolic geodesics in
+    /// the Poincaré disk
+    Orthogonal,
+}
+
+fn make_chord(a: Complex, b: Complex, kind: ChordKind) -> Result<ClineArc, String> {
+    match kind {
+        ChordKind::Straight => Ok(LineSegment::new(a, b).into()),
+        ChordKind::Orthogonal => orthogonal_arc(Cline::unit_circle(), a, b, Side::Inside),
+    }
+}
+
+/// The number of steps of TAU/n between two angles going the short way
+/// around the circle, rounded to the nearest integer. For points at the
+/// n-th roots of unity, this ranges from 1 (neighbors) to n/2 (diameters).
+/// Other angles can round up to (n + 1) / 2 when n is odd.
+pub fn chord_class(n: usize, theta_a: f64, theta_b: f64) -> usize {
+    let diff = (theta_b - theta_a).rem_euclid(TAU);
+    let short_way = diff.min(TAU - diff);
+    (short_way * n as f64 / TAU).round() as usize
+}
+
+/// Group chords into one tile per class. Class c gets style id c - 1, so
+/// pass n.div_ceil(2) styles to Scene::add_motifs() to style each chord
+/// length differently.
+fn batch_by_class(n: usize, chords: Vec<(usize, ClineArc)>) -> Motif {
+    let mut batches: Vec<Vec<ClineArc>> = vec![Vec::new(); n.div_ceil(2)];
+    for (class, chord) in chords {
+        batches[class - 1].push(chord);
+    }
+
+    let parts = batches
+        .into_iter()
+        .enumerate()
+        .filter(|(_, arcs)| !arcs.is_empty())
+        .map(|(id, arcs)| (ClineArcTile::new(arcs), id))
+        .collect();
+
+    Motif::new(parts)
+}
+
+/// The "mystic rose": the complete graph on the n-th roots of unity.
+/// Chords are grouped by their chord_class(), see batch_by_class() for
+/// how they map to style ids.
+pub fn mystic_rose(n: usize, kind: ChordKind) -> Result<Motif, String> {
+    if n < 2 {
+        return Err(String::from("n must be at least 2"));
+    }
+
+    let step = TAU / n as f64;
+    let points: Vec<Complex> = (0..n)
+        .map(|i| Complex::from_polar(1.0, i as f64 * step))
+        .collect();
+
+    let mut chords = Vec::with_capacity(n * (n - 1) / 2);
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let class = chord_class(n, i as f64 * step, j as f64 * step);
+            chords.push((class, make_chord(points[i], points[j], kind)?));
+        }
+    }
+
+    Ok(batch_by_class(n, chords))
+}
+
+/// Modular multiplication "times table": connect point k to point
+/// multiplier * k (mod n) for n points around the unit circle. A
+/// multiplier of 2 traces a cardioid, 3 a nephroid, and so on. The
+/// multiplier does not need to be an integer, which is useful for
+/// animating between the patterns. Chords shorter than half a step are
+/// skipped, this includes the ones that would connect a point to itself.
+pub fn times_table(n: usize, multiplier: f64, kind: ChordKind) -> Result<Motif, String> {
+    if n < 2 {
+        return Err(String::from("n must be at least 2"));
+    }
+
+    if !multiplier.is_finite() {
+        return Err(String::from("multiplier must be finite"));
+    }
+
+    let step = TAU / n as f64;
+    let mut chords = Vec::with_capacity(n);
+    for k in 0..n {
+        let theta_a = k as f64 * step;
+        let theta_b = (multiplier * k as f64 * step).rem_euclid(TAU);
+        let class = chord_class(n, theta_a, theta_b);
+        if class == 0 {
+            continue;
+        }
+
+        let a = Complex::from_polar(1.0, theta_a);
+        let b = Complex::from_polar(1.0, theta_b);
+        chords.push((class, make_chord(a, b, kind)?));
+    }
+
+    Ok(batch_by_class(n, chords))
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::*;
+
+    fn count_arcs(motif: &Motif) -> usize {
+        motif
+            .iter()
+            .map(|(tile, _)| tile.get_primitives().len())
+            .sum()
+    }
+
+    #[test]
+    pub fn chord_class_goes_short_way_around() {
+        let step = TAU / 6.0;
+
+        assert_eq!(chord_class(6, 0.0, step), 1);
+        assert_eq!(chord_class(6, 0.0, 5.0 * step), 1);
+        assert_eq!(chord_class(6, step, 4.0 * step), 3);
+    }
+
+    #[test]
+    pub fn mystic_rose_has_one_chord_per_pair() {
+        let result = mystic_rose(7, ChordKind::Straight).unwrap();
+
+        assert_eq!(count_arcs(&result), 21);
+    }
+
+    #[test]
+    pub fn mystic_rose_groups_chords_by_class() {
+        let result = mystic_rose(6, ChordKind::Orthogonal).unwrap();
+
+        let sizes: Vec<(usize, usize)> = result
+            .iter()
+            .map(|(tile, id)| (id, tile.get_primitives().len()))
+            .collect();
+        // 6 edges of the hexagon, 6 short diagonals, 3 diameters
+        assert_eq!(sizes, vec![(0, 6), (1, 6), (2, 3)]);
+    }
+
+    #[test]
+    pub fn mystic_rose_with_one_point_returns_error() {
+        let result = mystic_rose(1, ChordKind::Straight);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn times_table_skips_fixed_points() {
+        // k -> 2k (mod 10) fixes only 0
+        let result = times_table(10, 2.0, ChordKind::Straight).unwrap();
+
+        assert_eq!(count_arcs(&result), 9);
+    }
+
+    #[test]
+    pub fn times_table_with_multiplier_one_is_empty() {
+        let result = times_table(12, 1.0, ChordKind::Orthogonal).unwrap();
+
+        assert_eq!(count_arcs(&result), 0);
+    }
+
+    #[test]
+    pub fn times_table_groups_chords_by_class() {
+        let result = times_table(4, 2.0, ChordKind::Straight).unwrap();
+
+        let sizes: Vec<(usize, usize)> = result
+            .iter()
+            .map(|(tile, id)| (id, tile.get_primitives().len()))
+            .collect();
+        // 1 -> 2 and 3 -> 2 are sides of the square, 2 -> 0 is a diameter
+        assert_eq!(sizes, vec![(0, 2), (1, 1)]);
+    }
+
+    #[test_case(5, 3.5; "small")]
+    #[test_case(201, 101.5; "large")]
+    pub fn times_table_with_odd_n_and_half_integer_multiplier_uses_longest_class(
+        n: usize,
+        multiplier: f64,
+    ) {
+        // With n odd, k -> multiplier * k can land halfway between two
+        // points, just over n/2 steps away, which rounds up to class
+        // (n + 1) / 2
+        let result = times_table(n, multiplier, ChordKind::Straight).unwrap();
+
+        let max_id = result.iter().map(|(_, id)| id).max().unwrap();
+        assert_eq!(max_id, n.div_ceil(2) - 1);
+    }
+}
@@ -1,5 +1,7 @@
 use std::f64::consts::{FRAC_PI_2, FRAC_PI_3, FRAC_PI_4, FRAC_PI_6, PI, SQRT_2, TAU};
 
+use abstraction::Semigroup;
+
 use crate::{
     geometry::{ArcAngles, Circle, CircularArc, LineSegment},
     rendering::Style,
@@ -16,7 +18,8 @@ fn circle_to_arcs(circle: Circle) -> (CircularArc, CircularArc) {
     (top, bottom)
 }
 
-pub fn ghost() -> (ClineArcTile, Style) {
+/// Outline of the ghost's body without the face
+fn ghost_body() -> ClineArcTile {
     const SIDE_HEIGHT: f64 = 1.5;
     const CIRCLE_SPACING: f64 = 2.0 / 5.0;
     const BOTTOM_CIRCLE_RADIUS: f64 = 1.0 / 5.0;
@@ -30,17 +33,9 @@ pub fn ghost() -> (ClineArcTile, Style) {
         })
         .collect();
 
-    let left_eye = Circle::new(Complex::new(-0.5, 0.0), 0.25);
-    let right_eye = Circle::new(Complex::new(0.5, 0.0), 0.25);
-    let mouth = Circle::new(Complex::new(0.0, -0.5), 0.125);
-
-    let (left_eye_top, left_eye_bottom) = circle_to_arcs(left_eye);
-    let (right_eye_top, right_eye_bottom) = circle_to_arcs(right_eye);
-    let (mouth_top, mouth_bottom) = circle_to_arcs(mouth);
-
     let (upper, lower) = ArcAngles::semicircles();
 
-    let ghost = ClineArcTile::new(vec![
+    ClineArcTile::new(vec![
         // top of ghost head is a semi-circle
         CircularArc::new(head_circle, upper).into(),
         // Left side
@@ -53,20 +48,62 @@ pub fn ghost() -> (ClineArcTile, Style) {
         CircularArc::new(bottom_circles[4], lower).into(),
         // Right side
         LineSegment::new(Complex::new(1.0, -SIDE_HEIGHT), Complex::ONE).into(),
-        // Eyes and mouths are circles drawn as two semicircles
+    ])
+}
+
+/// The ghost's eyes and mouth
+fn ghost_face() -> (ClineArcTile, ClineArcTile) {
+    let left_eye = Circle::new(Complex::new(-0.5, 0.0), 0.25);
+    let right_eye = Circle::new(Complex::new(0.5, 0.0), 0.25);
+    let mouth = Circle::new(Complex::new(0.0, -0.5), 0.125);
+
+    let (left_eye_top, left_eye_bottom) = circle_to_arcs(left_eye);
+    let (right_eye_top, right_eye_bottom) = circle_to_arcs(right_eye);
+    let (mouth_top, mouth_bottom) = circle_to_arcs(mouth);
+
+    // Eyes and mouths are circles drawn as two semicircles
+    let eyes = ClineArcTile::new(vec![
         left_eye_top.into(),
         left_eye_bottom.into(),
         right_eye_top.into(),
         right_eye_bottom.into(),
-        mouth_top.into(),
-        mouth_bottom.into(),
     ]);
+    let mouth = ClineArcTile::new(vec![mouth_top.into(), mouth_bottom.into()]);
+
+    (eyes, mouth)
+}
+
+pub fn ghost() -> (ClineArcTile, Style) {
+    let (eyes, mouth) = ghost_face();
+    let ghost = [ghost_body(), eyes, mouth]
+        .into_iter()
+        .reduce(Semigroup::combine)
+        .unwrap();
 
     let style = Style::stroke(0xc5, 0xf2, 0xfa).with_width(0.25);
 
     (ghost, style)
 }
 
+/// The same ghost as ghost(), but as a motif so the face can be styled
+/// separately. The face is a nested motif drawn on top of the body.
+pub fn ghost_with_face() -> (Motif, Vec<Style>) {
+    let (eyes, mouth) = ghost_face();
+    let face = Motif::new(vec![(eyes, 1), (mouth, 1)]);
+    let ghost = Motif::default()
+        .with_part(ghost_body(), 0, 0)
+        .with_motif(face, 1);
+
+    let styles = vec![
+        // Pale blue body
+        Style::stroke(0xc5, 0xf2, 0xfa).with_width(0.25),
+        // Dark face
+        Style::stroke(0x20, 0x20, 0x40).with_width(0.125),
+    ];
+
+    (ghost, styles)
+}
+
 fn lerp(a: Complex, b: Complex, t: f64) -> Complex {
     a * (1.0 - t).into() + b * t.into()
 }
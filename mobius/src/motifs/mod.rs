@@ -1,3 +1,7 @@
+pub mod chords;
 pub mod halloween;
+pub mod parametric;
 
-pub use halloween::*;
\ No newline at end of file
+pub use chords::*;
+pub use halloween::*;
+pub use parametric::*;
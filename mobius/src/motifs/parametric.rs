@@ -0,0 +1,292 @@
+//! Reusable shapes with a few parameters. Everything is built from line
+//! segments and circular arcs so the results can be transformed by Mobius
+//! maps like any other tile. Shapes are normalized to roughly fit in the
+//! unit circle.
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_3, FRAC_PI_4, PI, SQRT_2, TAU};
+
+use crate::{
+    cline_arc::ClineArc,
+    geometry::{ArcAngles, Circle, CircularArc, LineSegment},
+    polygon::Polygon,
+    transformable::ClineArcTile,
+    Complex,
+};
+
+/// Vertices of a regular polygon inscribed in the unit circle, with the
+/// first vertex pointing straight up
+fn regular_vertices(n: usize) -> Vec<Complex> {
+    (0..n)
+        .map(|i| Complex::from_polar(1.0, FRAC_PI_2 + TAU * (i as f64) / (n as f64)))
+        .collect()
+}
+
+/// Regular n-gon inscribed in the unit circle, with a vertex at i
+pub fn regular_polygon(n: usize) -> Result<Polygon, String> {
+    if n < 3 {
+        return Err(String::from("a polygon needs at least 3 sides"));
+    }
+
+    Polygon::new(regular_vertices(n))
+}
+
+/// Star with n points on the unit circle, the first at i. The notches
+/// between the points are at radius inner_ratio
+pub fn star(n: usize, inner_ratio: f64) -> Result<Polygon, String> {
+    if n < 2 {
+        return Err(String::from("a star needs at least 2 points"));
+    }
+
+    if !(inner_ratio > 0.0 && inner_ratio < 1.0) {
+        return Err(format!(
+            "inner_ratio must be between 0 and 1, got {}",
+            inner_ratio
+        ));
+    }
+
+    // Alternate between points and notches
+    let vertices = regular_vertices(2 * n)
+        .into_iter()
+        .enumerate()
+        .map(|(i, z)| {
+            if i % 2 == 0 {
+                z
+            } else {
+                z * inner_ratio.into()
+            }
+        })
+        .collect();
+
+    Polygon::new(vertices)
+}
+
+/// Heart made from a square standing on its corner with a semicircle on
+/// each of the top two sides. The point is at -i and the dip between the
+/// lobes is at i
+pub fn heart() -> ClineArcTile {
+    let bottom = -Complex::I;
+    let right = Complex::ONE;
+    let left = -Complex::ONE;
+
+    let lobe_radius = 0.5 * SQRT_2;
+    let right_lobe = Circle::new(Complex::new(0.5, 0.5), lobe_radius);
+    let left_lobe = Circle::new(Complex::new(-0.5, 0.5), lobe_radius);
+    let right_angles = ArcAngles::new(-FRAC_PI_4, 3.0 * FRAC_PI_4).unwrap();
+    let left_angles = ArcAngles::new(FRAC_PI_4, 5.0 * FRAC_PI_4).unwrap();
+
+    ClineArcTile::new(vec![
+        LineSegment::new(bottom, right).into(),
+        CircularArc::new(right_lobe, right_angles).into(),
+        CircularArc::new(left_lobe, left_angles).into(),
+        LineSegment::new(left, bottom).into(),
+    ])
+}
+
+/// Crescent moon cut from the unit disk by a unit disk shifted right by
+/// offset. Small offsets give a thin crescent, offsets close to 2 give
+/// nearly a full moon. The crescent opens to the right
+pub fn crescent(offset: f64) -> Result<ClineArcTile, String> {
+    if !(offset > 0.0 && offset < 2.0) {
+        return Err(format!("offset must be between 0 and 2, got {}", offset));
+    }
+
+    // The circles meet at offset/2 +/- i * height. theta is the angle to
+    // the upper horn from the center of the moon, and pi - theta is the
+    // angle from the center of the shadow
+    let half_offset = 0.5 * offset;
+    let height = (1.0 - half_offset * half_offset).sqrt();
+    let theta = height.atan2(half_offset);
+
+    let moon = Circle::unit_circle();
+    let shadow = Circle::new(Complex::from(offset), 1.0);
+
+    // Counterclockwise around the outside from the top horn to the bottom
+    // horn, then clockwise back along the shadow
+    let outer = ArcAngles::new(theta, TAU - theta).unwrap();
+    let inner = ArcAngles::new(theta - PI, -PI - theta).unwrap();
+
+    Ok(ClineArcTile::new(vec![
+        CircularArc::new(moon, outer).into(),
+        CircularArc::new(shadow, inner).into(),
+    ]))
+}
+
+/// One branch of a snowflake: a stem from 0 to 1 with evenly spaced pairs
+/// of side branches at 60 degrees that get shorter towards the tip.
+/// Rotating this by multiples of 60 degrees makes a whole snowflake
+pub fn snowflake_branch(side_branches: usize) -> ClineArcTile {
+    let mut arcs: Vec<ClineArc> = vec![LineSegment::new(Complex::Zero, Complex::ONE).into()];
+    for i in 1..=side_branches {
+        let t = (i as f64) / ((side_branches + 1) as f64);
+        let start = Complex::from(t);
+        let length = 0.5 * (1.0 - t);
+        for angle in [FRAC_PI_3, -FRAC_PI_3] {
+            let end = start + Complex::from_polar(length, angle);
+            arcs.push(LineSegment::new(start, end).into());
+        }
+    }
+
+    ClineArcTile::new(arcs)
+}
+
+/// Gear with the given number of teeth. The tips of the teeth are arcs
+/// of the unit circle, and the gaps between them are arcs of a circle
+/// with radius 1 - tooth_depth. Teeth and gaps are the same width
+pub fn gear(teeth: usize, tooth_depth: f64) -> Result<ClineArcTile, String> {
+    if teeth < 3 {
+        return Err(String::from("a gear needs at least 3 teeth"));
+    }
+
+    if !(tooth_depth > 0.0 && tooth_depth < 1.0) {
+        return Err(format!(
+            "tooth_depth must be between 0 and 1, got {}",
+            tooth_depth
+        ));
+    }
+
+    let tip_circle = Circle::unit_circle();
+    let root_radius = 1.0 - tooth_depth;
+    let root_circle = Circle::new(Complex::Zero, root_radius);
+    let period = TAU / (teeth as f64);
+
+    let mut arcs: Vec<ClineArc> = Vec::new();
+    for i in 0..teeth {
+        let start = period * (i as f64);
+        let middle = start + 0.5 * period;
+        let end = start + period;
+
+        let gap = ArcAngles::new(start, middle).unwrap();
+        let tip = ArcAngles::new(middle, end).unwrap();
+
+        arcs.push(CircularArc::new(root_circle, gap).into());
+        arcs.push(
+            LineSegment::new(
+                Complex::from_polar(root_radius, middle),
+                Complex::from_polar(1.0, middle),
+            )
+            .into(),
+        );
+        arcs.push(CircularArc::new(tip_circle, tip).into());
+        arcs.push(
+            LineSegment::new(
+                Complex::from_polar(1.0, end),
+                Complex::from_polar(root_radius, end),
+            )
+            .into(),
+        );
+    }
+
+    Ok(ClineArcTile::new(arcs))
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::{algorithms::tile_contains, nearly::assert_nearly, winding::FillRule};
+
+    use super::*;
+
+    #[test]
+    pub fn regular_polygon_has_vertices_on_unit_circle() {
+        let result = regular_polygon(5).unwrap();
+
+        assert_eq!(result.vertices().len(), 5);
+        assert_eq!(result.vertices()[0], Complex::I);
+        for vertex in result.vertices() {
+            assert_nearly(vertex.mag(), 1.0);
+        }
+    }
+
+    #[test]
+    pub fn regular_polygon_with_too_few_sides_returns_error() {
+        let result = regular_polygon(2);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn star_alternates_points_and_notches() {
+        let result = star(5, 0.4).unwrap();
+
+        let radii: Vec<f64> = result.vertices().iter().map(|x| x.mag()).collect();
+        assert_eq!(radii.len(), 10);
+        for (i, radius) in radii.into_iter().enumerate() {
+            assert_nearly(radius, if i % 2 == 0 { 1.0 } else { 0.4 });
+        }
+        assert!(result.contains(Complex::Zero, FillRule::NonZero));
+        assert!(!result.contains(Complex::new(0.0, -0.5), FillRule::NonZero));
+    }
+
+    #[test_case(0.0; "zero")]
+    #[test_case(1.0; "one")]
+    #[test_case(f64::NAN; "nan")]
+    pub fn star_with_invalid_ratio_returns_error(inner_ratio: f64) {
+        let result = star(5, inner_ratio);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn polygon_converts_to_tile() {
+        let square = regular_polygon(4).unwrap();
+
+        let result = ClineArcTile::from(&square);
+
+        assert_eq!(result.get_primitives().len(), 4);
+        assert!(tile_contains(&result, Complex::new(0.1, 0.2), FillRule::EvenOdd).unwrap());
+    }
+
+    #[test]
+    pub fn heart_contains_lobes_but_not_dip() {
+        let result = heart();
+
+        assert!(tile_contains(&result, Complex::new(0.5, 1.0), FillRule::EvenOdd).unwrap());
+        assert!(tile_contains(&result, Complex::new(-0.5, 1.0), FillRule::EvenOdd).unwrap());
+        assert!(tile_contains(&result, Complex::new(0.0, -0.5), FillRule::EvenOdd).unwrap());
+        assert!(!tile_contains(&result, Complex::new(0.0, 1.1), FillRule::EvenOdd).unwrap());
+        assert!(!tile_contains(&result, Complex::new(0.8, -0.5), FillRule::EvenOdd).unwrap());
+    }
+
+    #[test]
+    pub fn crescent_excludes_shadow() {
+        let result = crescent(1.0).unwrap();
+
+        assert!(tile_contains(&result, Complex::new(-0.5, 0.0), FillRule::EvenOdd).unwrap());
+        assert!(!tile_contains(&result, Complex::new(0.5, 0.0), FillRule::EvenOdd).unwrap());
+        assert!(!tile_contains(&result, Complex::new(-1.5, 0.0), FillRule::EvenOdd).unwrap());
+    }
+
+    #[test_case(0.0; "zero")]
+    #[test_case(2.0; "two")]
+    pub fn crescent_with_invalid_offset_returns_error(offset: f64) {
+        let result = crescent(offset);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn snowflake_branch_has_stem_and_side_branches() {
+        let result = snowflake_branch(3);
+
+        assert_eq!(result.get_primitives().len(), 7);
+    }
+
+    #[test]
+    pub fn gear_has_teeth_and_gaps() {
+        let result = gear(8, 0.25).unwrap();
+
+        assert_eq!(result.get_primitives().len(), 32);
+        // Middle of a tooth vs. middle of a gap
+        let tooth = Complex::from_polar(0.9, 0.75 * TAU / 8.0);
+        let gap = Complex::from_polar(0.9, 0.25 * TAU / 8.0);
+        assert!(tile_contains(&result, tooth, FillRule::EvenOdd).unwrap());
+        assert!(!tile_contains(&result, gap, FillRule::EvenOdd).unwrap());
+    }
+
+    #[test]
+    pub fn gear_with_invalid_depth_returns_error() {
+        let result = gear(8, 1.5);
+
+        assert!(result.is_err());
+    }
+}
@@ -1,19 +1,212 @@
+use std::cell::Cell;
+
 pub const EPSILON: f64 = 1e-15;
 
-pub fn is_nearly(a: f64, b: f64) -> bool {
-    // based on https://stackoverflow.com/a/28751714
-    let diff = (a - b).abs();
+thread_local! {
+    static CURRENT_TOLERANCE: Cell<Tolerance> = const { Cell::new(Tolerance::DEFAULT) };
+}
+
+/// Tolerance for approximate float comparisons. Two values are nearly equal
+/// if they differ by at most absolute, or by at most relative times the
+/// larger magnitude. The absolute part only matters near zero, so it should
+/// shrink for deep zooms and grow for very large geometry.
+///
+/// is_nearly() uses the current tolerance for the thread, and so does
+/// everything built on top of it, like equality of Complex and ArcAngles,
+/// snapping tiny values to Complex::Zero, and classifying clines. Use
+/// scope() to change it for one computation. Since it is per thread,
+/// work spawned on other threads starts from the default; par_sconcat()
+/// carries the caller's tolerance over to its worker threads.
+///
+/// Checks that need more slack than a single comparison, like whether a
+/// point that went through many transformations is still on its circle,
+/// use scale_relative() so they tighten or loosen along with it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerance {
+    pub absolute: f64,
+    pub relative: f64,
+}
+
+/// Restores the previous tolerance when dropped, even if the scope panics
+struct ToleranceGuard(Tolerance);
 
-    if diff <= EPSILON {
-        true
-    } else {
-        diff <= EPSILON * a.abs().max(b.abs())
+impl Drop for ToleranceGuard {
+    fn drop(&mut self) {
+        CURRENT_TOLERANCE.with(|current| current.set(self.0));
     }
 }
 
+impl Tolerance {
+    pub const DEFAULT: Self = Self {
+        absolute: EPSILON,
+        relative: EPSILON,
+    };
+
+    pub fn new(absolute: f64, relative: f64) -> Result<Self, String> {
+        for (label, value) in [("absolute", absolute), ("relative", relative)] {
+            if !(value >= 0.0 && value.is_finite()) {
+                return Err(format!(
+                    "{} tolerance must be finite and non-negative, got {}",
+                    label, value
+                ));
+            }
+        }
+
+        Ok(Self { absolute, relative })
+    }
+
+    /// The default relative tolerance with the absolute tolerance scaled
+    /// to the size of the geometry, e.g. the width of the viewport
+    pub fn for_scale(scale: f64) -> Result<Self, String> {
+        Self::new(EPSILON * scale, EPSILON)
+    }
+
+    pub fn is_nearly(&self, a: f64, b: f64) -> bool {
+        // based on https://stackoverflow.com/a/28751714
+        let diff = (a - b).abs();
+
+        if diff <= self.absolute {
+            true
+        } else {
+            diff <= self.relative * a.abs().max(b.abs())
+        }
+    }
+
+    /// Scale a looser relative threshold, given as its value at the
+    /// default tolerance, in proportion to this relative tolerance
+    pub fn scale_relative(&self, default: f64) -> f64 {
+        default * self.relative / Self::DEFAULT.relative
+    }
+
+    /// The tolerance is_nearly() uses on this thread
+    pub fn current() -> Self {
+        CURRENT_TOLERANCE.with(|current| current.get())
+    }
+
+    /// Run a computation with this as the current tolerance, then restore
+    /// the previous one
+    pub fn scope<T>(self, f: impl FnOnce() -> T) -> T {
+        let _guard = ToleranceGuard(CURRENT_TOLERANCE.with(|current| current.replace(self)));
+        f()
+    }
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+pub fn is_nearly(a: f64, b: f64) -> bool {
+    Tolerance::current().is_nearly(a, b)
+}
+
+/// abstraction::par_sconcat(), but each worker thread uses the calling
+/// thread's current tolerance instead of the default
+#[cfg(feature = "parallel")]
+pub fn par_sconcat<S: abstraction::Semigroup + Send + Sync>(items: &[S]) -> Option<S> {
+    let tolerance = Tolerance::current();
+    abstraction::par_sconcat_with(items, &|job| tolerance.scope(job))
+}
+
 #[cfg(test)]
 pub fn assert_nearly(a: f64, b: f64) {
     if !is_nearly(a, b) {
         panic!("!is_nearly({}, {})", a, b);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{geometry::ArcAngles, Complex};
+
+    use super::*;
+
+    #[test]
+    pub fn default_tolerance_is_relative() {
+        assert!(is_nearly(1e20, 1e20 + 1e4));
+        assert!(!is_nearly(1.0, 1.0 + 1e-10));
+        assert!(is_nearly(0.0, 1e-16));
+    }
+
+    #[test]
+    pub fn scope_changes_comparisons() {
+        let loose = Tolerance::new(1e-6, 1e-6).unwrap();
+
+        let result = loose.scope(|| Complex::new(1.0, 0.0) == Complex::new(1.0 + 1e-9, 0.0));
+
+        assert!(result);
+        assert_ne!(Complex::new(1.0, 0.0), Complex::new(1.0 + 1e-9, 0.0));
+    }
+
+    #[test]
+    pub fn scope_applies_to_arc_angles() {
+        let loose = Tolerance::new(1e-6, 1e-6).unwrap();
+        let a = ArcAngles::new(0.5, 1.0).unwrap();
+        let b = ArcAngles::new(0.5 + 1e-9, 1.0).unwrap();
+
+        assert!(loose.scope(|| a == b));
+        assert!(a != b);
+    }
+
+    #[test]
+    pub fn small_absolute_tolerance_keeps_tiny_values() {
+        let deep_zoom = Tolerance::for_scale(1e-20).unwrap();
+
+        let result = deep_zoom.scope(|| Complex::new(1e-18, 0.0));
+
+        assert_ne!(result, Complex::Zero);
+        assert_eq!(Complex::new(1e-18, 0.0), Complex::Zero);
+    }
+
+    #[test]
+    pub fn scope_restores_previous_tolerance() {
+        let outer = Tolerance::new(1e-3, 1e-3).unwrap();
+        let inner = Tolerance::new(1e-6, 1e-6).unwrap();
+
+        let (during, nested) = outer.scope(|| {
+            let nested = inner.scope(Tolerance::current);
+            (Tolerance::current(), nested)
+        });
+
+        assert_eq!(during, outer);
+        assert_eq!(nested, inner);
+        assert_eq!(Tolerance::current(), Tolerance::DEFAULT);
+    }
+
+    #[test]
+    pub fn new_with_negative_tolerance_returns_error() {
+        assert!(Tolerance::new(-1.0, 1e-9).is_err());
+        assert!(Tolerance::new(1e-9, f64::NAN).is_err());
+    }
+
+    #[test]
+    pub fn scale_relative_follows_relative_tolerance() {
+        let tight = Tolerance::new(EPSILON, EPSILON * 1e-3).unwrap();
+
+        assert_eq!(Tolerance::DEFAULT.scale_relative(1e-6), 1e-6);
+        assert_nearly(tight.scale_relative(1e-6), 1e-9);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    pub fn par_sconcat_uses_current_tolerance_on_workers() {
+        /// Remembers whether every combine saw the loose tolerance
+        #[derive(Clone)]
+        struct SawTolerance(bool);
+
+        impl abstraction::Semigroup for SawTolerance {
+            fn combine(self, other: Self) -> Self {
+                let loose = Tolerance::current().relative == 1e-6;
+                Self(self.0 && other.0 && loose)
+            }
+        }
+
+        let loose = Tolerance::new(1e-6, 1e-6).unwrap();
+        let items = vec![SawTolerance(true); 10000];
+
+        let result = loose.scope(|| par_sconcat(&items)).unwrap();
+
+        assert!(result.0);
+    }
+}
@@ -2,7 +2,8 @@ use std::f64::consts::{PI, TAU};
 
 use crate::{
     geometry::{
-        ArcAngles, Circle, CircularArc, DirectedEdge, GeneralizedCircle, Line, LineSegment,
+        on_circle_tolerance, ArcAngles, Circle, CircleError, CircularArc, DirectedEdge,
+        GeneralizedCircle, Line, LineSegment,
     },
     nearly::is_nearly,
     Complex,
@@ -62,7 +63,10 @@ pub fn compute_orthogonal_circle(
     GeneralizedCircle::Circle(orthog_circle)
 }
 
-pub fn compute_orthogonal_arc(arc: CircularArc) -> CircularArc {
+/// Arc of the circle orthogonal to arc's circle at its end points, from
+/// the end back to the start. Returns an error if rounding error moves the
+/// orthogonal circle too far from the end points
+pub fn compute_orthogonal_arc(arc: CircularArc) -> Result<CircularArc, CircleError> {
     let circle = arc.circle;
     let orthog_circle = match compute_orthogonal_circle(circle, arc.angles) {
         GeneralizedCircle::Circle(sub_circle) => sub_circle,
@@ -72,14 +76,14 @@ pub fn compute_orthogonal_arc(arc: CircularArc) -> CircularArc {
     // Compute the arc from b -> a that's inside the original circle. This will
     // match the orientation of the original arc for small input arcs,
     // but will be the opposite orientation for large input arcs.
-    let angle_a_raw = orthog_circle.get_angle(arc.start()).unwrap();
-    let angle_b_raw = orthog_circle.get_angle(arc.end()).unwrap();
+    let angle_a_raw = orthog_circle.get_angle(arc.start(), on_circle_tolerance())?;
+    let angle_b_raw = orthog_circle.get_angle(arc.end(), on_circle_tolerance())?;
     let mut sub_angles = ArcAngles::from_raw_angles(angle_b_raw, angle_a_raw, arc.direction());
     if sub_angles.central_angle() > PI {
         sub_angles = sub_angles.complement();
     }
 
-    CircularArc::new(orthog_circle, sub_angles)
+    Ok(CircularArc::new(orthog_circle, sub_angles))
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -90,7 +94,7 @@ pub enum OrthogonalArc {
 
 #[cfg(test)]
 mod test {
-    use crate::{geometry::Line, unit_complex::UnitComplex};
+    use crate::{geometry::Line, transformable::Cline, unit_complex::UnitComplex};
 
     use super::*;
     use test_case::test_case;
@@ -136,4 +140,30 @@ mod test {
             GeneralizedCircle::Line(line) => assert_eq!(line, expected),
         }
     }
+
+    #[test_case(0.3, 2.0; "minor arc")]
+    #[test_case(-1.0, 3.5; "major arc")]
+    #[test_case(0.0, PI; "semicircle")]
+    pub fn compute_orthog_circle_is_orthogonal(a: f64, b: f64) {
+        let circle = make_circle();
+        let angles = ArcAngles::new(a, b).unwrap();
+
+        let result: Cline = match compute_orthogonal_circle(circle, angles) {
+            GeneralizedCircle::Circle(x) => x.into(),
+            GeneralizedCircle::Line(x) => x.into(),
+        };
+
+        assert!(Cline::from(circle).is_orthogonal_to(&result));
+    }
+
+    #[test_case(0.3, 2.0; "minor arc")]
+    #[test_case(2.0, 0.3; "minor arc cw")]
+    pub fn compute_orthogonal_arc_connects_end_to_start(a: f64, b: f64) {
+        let arc = CircularArc::new(make_circle(), ArcAngles::new(a, b).unwrap());
+
+        let result = compute_orthogonal_arc(arc).unwrap();
+
+        assert_eq!(result.start(), arc.end());
+        assert_eq!(result.end(), arc.start());
+    }
 }
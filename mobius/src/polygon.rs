@@ -1 +1,200 @@
+use std::error::Error;
 
+use abstraction::GroupAction;
+use permutations::Permutation;
+
+use crate::{
+    cline_arc::ClineArcGeometry,
+    geometry::LineSegment,
+    rendering::{RenderPrimitive, Renderable},
+    transformable::ClineArcTile,
+    winding::{loop_contains, FillRule},
+    Complex,
+};
+
+/// Closed polygon with straight edges between consecutive vertices
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polygon {
+    vertices: Vec<Complex>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Complex>) -> Result<Self, String> {
+        if vertices.len() < 3 {
+            return Err(String::from("polygon must have at least 3 vertices"));
+        }
+
+        if vertices.contains(&Complex::Infinity) {
+            return Err(String::from("vertices must be finite"));
+        }
+
+        Ok(Self { vertices })
+    }
+
+    pub fn vertices(&self) -> &[Complex] {
+        &self.vertices
+    }
+
+    /// Iterate over the edges, including the one that closes the loop
+    /// from the last vertex back to the first
+    pub fn edges(&self) -> impl Iterator<Item = LineSegment> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| LineSegment::new(self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+
+    /// Check if a point is inside the polygon. The rule only matters for
+    /// self-intersecting polygons: the middle of a star is inside with
+    /// FillRule::NonZero but not with FillRule::EvenOdd.
+    pub fn contains(&self, point: Complex, rule: FillRule) -> bool {
+        let edges: Vec<ClineArcGeometry> =
+            self.edges().map(ClineArcGeometry::LineSegment).collect();
+        loop_contains(&edges, point, rule)
+    }
+
+    /// Permute the first N vertices, leaving the rest in place. See the
+    /// GroupAction impl below. Returns an error if the polygon has fewer
+    /// than N vertices
+    pub fn permute_vertices<const N: usize>(
+        &self,
+        permutation: &Permutation<N>,
+    ) -> Result<Self, String> {
+        if self.vertices.len() < N {
+            return Err(format!(
+                "polygon has {} vertices, fewer than the {} the permutation needs",
+                self.vertices.len(),
+                N
+            ));
+        }
+
+        let head: [Complex; N] = std::array::from_fn(|i| self.vertices[i]);
+        let mut vertices = permutation.act(&head).to_vec();
+        vertices.extend_from_slice(&self.vertices[N..]);
+
+        Ok(Self { vertices })
+    }
+}
+
+impl Renderable for Polygon {
+    fn bake_geometry(&self) -> Result<Vec<RenderPrimitive>, Box<dyn Error>> {
+        Ok(self.edges().map(RenderPrimitive::LineSegment).collect())
+    }
+}
+
+/// Convert the edges to arcs so the polygon can be transformed by
+/// Mobius maps
+impl From<&Polygon> for ClineArcTile {
+    fn from(value: &Polygon) -> Self {
+        ClineArcTile::new(value.edges().map(|x| x.into()).collect())
+    }
+}
+
+/// Permute the first N vertices of a polygon, leaving the rest in place.
+/// This changes the order the vertices are connected, not the set of
+/// vertices, so it can turn a convex polygon into a star or bowtie.
+///
+/// This panics if the polygon has fewer than N vertices, use
+/// Polygon::permute_vertices() to get an error instead
+impl<const N: usize> GroupAction<Polygon> for Permutation<N> {
+    fn act(&self, x: &Polygon) -> Polygon {
+        x.permute_vertices(self).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn square() -> Polygon {
+        Polygon::new(vec![Complex::ONE, Complex::I, -Complex::ONE, -Complex::I]).unwrap()
+    }
+
+    #[test]
+    pub fn new_with_too_few_vertices_returns_error() {
+        let result = Polygon::new(vec![Complex::Zero, Complex::ONE]);
+
+        assert!(result.is_err_and(|e| e.contains("at least 3 vertices")));
+    }
+
+    #[test]
+    pub fn new_with_infinite_vertex_returns_error() {
+        let result = Polygon::new(vec![Complex::Zero, Complex::ONE, Complex::Infinity]);
+
+        assert!(result.is_err_and(|e| e.contains("finite")));
+    }
+
+    #[test]
+    pub fn edges_closes_loop() {
+        let result: Vec<LineSegment> = square().edges().collect();
+
+        assert_eq!(result.len(), 4);
+        assert_eq!(result[3], LineSegment::new(-Complex::I, Complex::ONE));
+    }
+
+    #[test]
+    pub fn contains_checks_inside_and_outside() {
+        let polygon = square();
+
+        assert!(polygon.contains(Complex::new(0.2, 0.3), FillRule::NonZero));
+        assert!(!polygon.contains(Complex::new(0.8, 0.8), FillRule::NonZero));
+    }
+
+    #[test]
+    pub fn contains_applies_fill_rule_to_stars() {
+        let pentagram = Polygon::new(
+            (0..5)
+                .map(|i| Complex::from_polar(1.0, 0.8 * std::f64::consts::PI * i as f64))
+                .collect(),
+        )
+        .unwrap();
+
+        assert!(pentagram.contains(Complex::Zero, FillRule::NonZero));
+        assert!(!pentagram.contains(Complex::Zero, FillRule::EvenOdd));
+        assert!(!pentagram.contains(Complex::new(2.0, 0.0), FillRule::NonZero));
+    }
+
+    #[test]
+    pub fn permutation_acts_on_vertices() {
+        let swap = Permutation::new([1, 0, 2, 3]).unwrap();
+
+        let result = swap.act(&square());
+
+        let expected = vec![Complex::I, Complex::ONE, -Complex::ONE, -Complex::I];
+        assert_eq!(result.vertices(), &expected);
+    }
+
+    #[test]
+    pub fn smaller_permutation_leaves_extra_vertices_in_place() {
+        let three_cycle = Permutation::new([1, 2, 0]).unwrap();
+
+        let result = three_cycle.act(&square());
+
+        let expected = vec![-Complex::ONE, Complex::ONE, Complex::I, -Complex::I];
+        assert_eq!(result.vertices(), &expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "polygon has 4 vertices, fewer than the 5")]
+    pub fn larger_permutation_panics() {
+        let five_cycle = Permutation::new([1, 2, 3, 4, 0]).unwrap();
+
+        five_cycle.act(&square());
+    }
+
+    #[test]
+    pub fn permute_vertices_with_larger_permutation_returns_error() {
+        let five_cycle = Permutation::new([1, 2, 3, 4, 0]).unwrap();
+
+        let result = square().permute_vertices(&five_cycle);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn permute_vertices_matches_act() {
+        let three_cycle = Permutation::new([1, 2, 0]).unwrap();
+
+        let result = square().permute_vertices(&three_cycle).unwrap();
+
+        assert_eq!(result, three_cycle.act(&square()));
+    }
+}
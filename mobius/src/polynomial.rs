@@ -0,0 +1,344 @@
+use std::{
+    fmt::Display,
+    ops::{Add, Mul},
+};
+
+use crate::Complex;
+
+/// Polynomial with complex coefficients, stored from the constant term up.
+/// Trailing zero coefficients are trimmed, so the last coefficient is the
+/// leading one (except for the zero polynomial, which has none).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComplexPolynomial {
+    coefficients: Vec<Complex>,
+}
+
+impl ComplexPolynomial {
+    /// Create a polynomial from coefficients [a0, a1, ...] meaning
+    /// a0 + a1 z + a2 z^2 + ...
+    pub fn new(coefficients: Vec<Complex>) -> Result<Self, String> {
+        if coefficients.contains(&Complex::Infinity) {
+            return Err(String::from("coefficients must be finite"));
+        }
+
+        let mut coefficients = coefficients;
+        while coefficients.last() == Some(&Complex::Zero) {
+            coefficients.pop();
+        }
+
+        Ok(Self { coefficients })
+    }
+
+    /// The monic polynomial (z - r1)(z - r2)... with the given roots
+    pub fn from_roots(roots: &[Complex]) -> Result<Self, String> {
+        let mut coefficients = vec![Complex::ONE];
+        for root in roots {
+            if *root == Complex::Infinity {
+                return Err(String::from("roots must be finite"));
+            }
+
+            // Multiply by (z - root)
+            let mut next = vec![Complex::Zero; coefficients.len() + 1];
+            for (i, c) in coefficients.iter().enumerate() {
+                next[i + 1] = next[i + 1] + *c;
+                next[i] = next[i] - *c * *root;
+            }
+            coefficients = next;
+        }
+
+        Self::new(coefficients)
+    }
+
+    /// The constant polynomial c
+    pub fn constant(c: Complex) -> Result<Self, String> {
+        Self::new(vec![c])
+    }
+
+    /// The polynomial z
+    pub fn identity() -> Self {
+        Self {
+            coefficients: vec![Complex::Zero, Complex::ONE],
+        }
+    }
+
+    pub fn coefficients(&self) -> &[Complex] {
+        &self.coefficients
+    }
+
+    /// The degree, or None for the zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    /// Evaluate with Horner's method. Non-constant polynomials send
+    /// infinity to infinity
+    pub fn eval(&self, z: Complex) -> Complex {
+        if z == Complex::Infinity {
+            return match self.degree() {
+                None => Complex::Zero,
+                Some(0) => self.coefficients[0],
+                Some(_) => Complex::Infinity,
+            };
+        }
+
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Complex::Zero, |acc, c| acc * z + *c)
+    }
+
+    /// The coefficient of z^i, which is zero past the degree
+    pub fn coefficient(&self, i: usize) -> Complex {
+        self.coefficients.get(i).copied().unwrap_or(Complex::Zero)
+    }
+
+    /// Multiply every coefficient by k
+    pub fn scale(&self, k: Complex) -> Self {
+        Self::new(self.coefficients.iter().map(|c| *c * k).collect())
+            .expect("scaling by a finite number keeps coefficients finite")
+    }
+
+    /// The polynomial z -> self(inner(z)), computed with Horner's method
+    pub fn compose(&self, inner: &Self) -> Self {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Self::new(vec![]).unwrap(), |acc, c| {
+                &(&acc * inner)
+                    + &Self {
+                        coefficients: vec![*c],
+                    }
+            })
+    }
+
+    pub fn derivative(&self) -> Self {
+        let coefficients = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, c)| *c * (i as f64).into())
+            .collect();
+
+        Self { coefficients }
+    }
+
+    /// Find all the roots at once with the Durand–Kerner method. Iteration
+    /// stops when no root estimate moves more than tolerance in a step.
+    /// Repeated roots converge slowly, so they may need more iterations.
+    pub fn roots(&self, tolerance: f64, max_iterations: usize) -> Result<Vec<Complex>, String> {
+        let Some(degree) = self.degree() else {
+            return Err(String::from("the zero polynomial has no isolated roots"));
+        };
+
+        // Work with the monic polynomial with the same roots
+        let leading = self.coefficients[degree];
+        let monic = Self {
+            coefficients: self.coefficients.iter().map(|c| *c / leading).collect(),
+        };
+
+        // The standard starting guesses are powers of a number that is
+        // neither real nor a root of unity
+        let seed = Complex::new(0.4, 0.9);
+        let mut roots: Vec<Complex> = (0..degree)
+            .scan(Complex::ONE, |power, _| {
+                let current = *power;
+                *power = *power * seed;
+                Some(current)
+            })
+            .collect();
+
+        for _ in 0..max_iterations {
+            let mut max_step: f64 = 0.0;
+            for i in 0..degree {
+                let denominator = (0..degree)
+                    .filter(|j| *j != i)
+                    .fold(Complex::ONE, |acc, j| acc * (roots[i] - roots[j]));
+                if denominator == Complex::Zero {
+                    return Err(String::from("root estimates collided"));
+                }
+
+                let step = monic.eval(roots[i]) / denominator;
+                roots[i] = roots[i] - step;
+                max_step = max_step.max(step.mag());
+            }
+
+            if max_step < tolerance {
+                return Ok(roots);
+            }
+        }
+
+        Err(format!(
+            "roots did not converge after {} iterations",
+            max_iterations
+        ))
+    }
+}
+
+impl Add for &ComplexPolynomial {
+    type Output = ComplexPolynomial;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let length = self.coefficients.len().max(rhs.coefficients.len());
+        let coefficients = (0..length)
+            .map(|i| self.coefficient(i) + rhs.coefficient(i))
+            .collect();
+
+        ComplexPolynomial::new(coefficients).unwrap()
+    }
+}
+
+impl Mul for &ComplexPolynomial {
+    type Output = ComplexPolynomial;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        if self.coefficients.is_empty() || rhs.coefficients.is_empty() {
+            return ComplexPolynomial::new(vec![]).unwrap();
+        }
+
+        let mut coefficients =
+            vec![Complex::Zero; self.coefficients.len() + rhs.coefficients.len() - 1];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in rhs.coefficients.iter().enumerate() {
+                coefficients[i + j] = coefficients[i + j] + *a * *b;
+            }
+        }
+
+        ComplexPolynomial::new(coefficients).unwrap()
+    }
+}
+
+impl Display for ComplexPolynomial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.coefficients.is_empty() {
+            return write!(f, "0");
+        }
+
+        let terms: Vec<String> = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c != Complex::Zero)
+            .map(|(i, c)| match i {
+                0 => format!("{}", c),
+                1 => format!("{}z", c),
+                _ => format!("{}z^{}", c, i),
+            })
+            .collect();
+        write!(f, "{}", terms.join(" + "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn cubic() -> ComplexPolynomial {
+        // z^3 - 1
+        ComplexPolynomial::new(vec![
+            -Complex::ONE,
+            Complex::Zero,
+            Complex::Zero,
+            Complex::ONE,
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    pub fn new_trims_trailing_zeros() {
+        let result = ComplexPolynomial::new(vec![Complex::ONE, Complex::Zero]).unwrap();
+
+        assert_eq!(result.degree(), Some(0));
+    }
+
+    #[test]
+    pub fn new_with_infinite_coefficient_returns_error() {
+        let result = ComplexPolynomial::new(vec![Complex::Infinity]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn eval_computes_value() {
+        let result = cubic().eval(Complex::new(2.0, 0.0));
+
+        assert_eq!(result, Complex::new(7.0, 0.0));
+        assert_eq!(cubic().eval(Complex::Infinity), Complex::Infinity);
+    }
+
+    #[test]
+    pub fn derivative_computes_power_rule() {
+        let result = cubic().derivative();
+
+        assert_eq!(
+            result.coefficients(),
+            &[Complex::Zero, Complex::Zero, Complex::new(3.0, 0.0)]
+        );
+    }
+
+    #[test]
+    pub fn from_roots_vanishes_at_roots() {
+        let roots = [Complex::I, Complex::new(2.0, -1.0), -Complex::ONE];
+
+        let result = ComplexPolynomial::from_roots(&roots).unwrap();
+
+        assert_eq!(result.degree(), Some(3));
+        for root in roots {
+            assert_eq!(result.eval(root), Complex::Zero);
+        }
+    }
+
+    #[test]
+    pub fn mul_multiplies_roots() {
+        let a = ComplexPolynomial::from_roots(&[Complex::I]).unwrap();
+        let b = ComplexPolynomial::from_roots(&[Complex::ONE, Complex::new(2.0, 0.0)]).unwrap();
+
+        let result = &a * &b;
+
+        let expected =
+            ComplexPolynomial::from_roots(&[Complex::I, Complex::ONE, Complex::new(2.0, 0.0)])
+                .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn add_cancels_leading_terms() {
+        let a = ComplexPolynomial::new(vec![Complex::ONE, Complex::ONE]).unwrap();
+        let b = ComplexPolynomial::new(vec![Complex::ONE, -Complex::ONE]).unwrap();
+
+        let result = &a + &b;
+
+        assert_eq!(result.coefficients(), &[Complex::new(2.0, 0.0)]);
+    }
+
+    #[test]
+    pub fn compose_evaluates_inner_first() {
+        let outer = cubic();
+        let inner = ComplexPolynomial::new(vec![Complex::ONE, Complex::I]).unwrap();
+        let z = Complex::new(0.3, -0.7);
+
+        let result = outer.compose(&inner);
+
+        assert_eq!(result.degree(), Some(3));
+        assert_eq!(result.eval(z), outer.eval(inner.eval(z)));
+    }
+
+    #[test]
+    pub fn roots_finds_roots_of_unity() {
+        let result = cubic().roots(1e-12, 100).unwrap();
+
+        assert_eq!(result.len(), 3);
+        for root in Complex::roots_of_unity(3) {
+            assert!(result.iter().any(|x| (*x - root).mag() < 1e-9));
+        }
+    }
+
+    #[test]
+    pub fn roots_of_zero_polynomial_returns_error() {
+        let zero = ComplexPolynomial::new(vec![]).unwrap();
+
+        let result = zero.roots(1e-12, 100);
+
+        assert!(result.is_err());
+    }
+}
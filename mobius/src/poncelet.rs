@@ -0,0 +1,256 @@
+use std::f64::consts::TAU;
+
+use crate::{
+    geometry::{Circle, LineSegment},
+    transformable::ClineArcTile,
+    Complex,
+};
+
+/// How close the walk must return to its starting point to count as
+/// closed. This is much looser than is_nearly() since rounding error
+/// accumulates with each step of the walk.
+pub const CLOSURE_TOLERANCE: f64 = 1e-9;
+
+/// Poncelet's porism for a pair of nested circles. Starting from a point
+/// on the outer circle, draw the tangent line to the inner circle, and
+/// follow it until it meets the outer circle again. Repeating this either
+/// closes up into a polygon after n steps, or never closes. Poncelet's
+/// porism says which case happens depends only on the circles, not on the
+/// starting point.
+///
+/// See https://en.wikipedia.org/wiki/Poncelet%27s_closure_theorem
+#[derive(Clone, Copy, Debug)]
+pub struct PonceletPorism {
+    outer: Circle,
+    inner: Circle,
+}
+
+/// The vertices visited by the tangent chord walk
+#[derive(Clone, Debug)]
+pub struct PonceletTrajectory {
+    /// The vertices on the outer circle, starting with the initial point.
+    /// If the walk closed, the starting point is not repeated at the end.
+    pub vertices: Vec<Complex>,
+    /// If the walk closed, the number of steps it took
+    pub period: Option<usize>,
+    /// How many times the walk wound around the inner circle. Only
+    /// an integer if the walk closed
+    pub winding: f64,
+}
+
+impl PonceletPorism {
+    /// Create the porism for an inner circle that must lie strictly
+    /// inside the outer circle
+    pub fn new(outer: Circle, inner: Circle) -> Result<Self, String> {
+        if outer.radius <= 0.0 || inner.radius <= 0.0 {
+            return Err(String::from("radii must be positive"));
+        }
+
+        let offset = (inner.center - outer.center).mag();
+        if offset + inner.radius >= outer.radius {
+            return Err(String::from(
+                "inner circle must be strictly inside the outer circle",
+            ));
+        }
+
+        Ok(Self { outer, inner })
+    }
+
+    /// Concentric circles where the walk closes after n steps, i.e. the
+    /// incircle of a regular n-gon inscribed in the unit circle
+    pub fn regular(n: usize) -> Result<Self, String> {
+        if n < 3 {
+            return Err(String::from("n must be at least 3"));
+        }
+
+        let inner_radius = (TAU / (2.0 * n as f64)).cos();
+        Self::new(
+            Circle::unit_circle(),
+            Circle::new(Complex::Zero, inner_radius),
+        )
+    }
+
+    /// Circles where every walk closes into a triangle. By Euler's theorem
+    /// in geometry, the distance between the circumcenter and incenter of a
+    /// triangle is d^2 = R(R - 2r). The outer circle is the unit circle,
+    /// and the inner circle is offset along the real axis.
+    pub fn triangle(inner_radius: f64) -> Result<Self, String> {
+        if inner_radius <= 0.0 || inner_radius >= 0.5 {
+            return Err(String::from("inner radius must be in (0, 0.5)"));
+        }
+
+        let offset = (1.0 - 2.0 * inner_radius).sqrt();
+        Self::new(
+            Circle::unit_circle(),
+            Circle::new(Complex::new(offset, 0.0), inner_radius),
+        )
+    }
+
+    pub fn outer(&self) -> Circle {
+        self.outer
+    }
+
+    pub fn inner(&self) -> Circle {
+        self.inner
+    }
+
+    /// Take one step of the walk from a point on the outer circle. This
+    /// always takes the tangent line that passes the inner circle on the
+    /// left, so the walk goes counterclockwise around the inner circle.
+    pub fn step(&self, point: Complex) -> Complex {
+        let to_center = self.inner.center - point;
+        let distance = to_center.mag();
+
+        // The tangent line makes an angle asin(r / distance) with the line
+        // to the center of the inner circle
+        let tangent_angle = (self.inner.radius / distance).asin();
+        let direction = to_center * Complex::from_polar(1.0 / distance, -tangent_angle);
+
+        // The point is on the outer circle, so |point + t * direction - c|^2 = R^2
+        // simplifies to t = -2 dot(point - c, direction) for the other root
+        let t = -2.0 * Complex::dot(point - self.outer.center, direction);
+        point + direction * t.into()
+    }
+
+    /// Walk from the point on the outer circle at the given angle, stopping
+    /// when the walk returns to the start or after max_steps steps.
+    pub fn trajectory(&self, start_angle: f64, max_steps: usize) -> PonceletTrajectory {
+        let start = self.outer.get_point(start_angle);
+        let mut vertices = vec![start];
+        let mut winding = 0.0;
+        let mut current = start;
+
+        for steps in 1..=max_steps {
+            let next = self.step(current);
+            winding += self.angle_about_inner(current, next);
+
+            if (next - start).mag() < CLOSURE_TOLERANCE * self.outer.radius {
+                return PonceletTrajectory {
+                    vertices,
+                    period: Some(steps),
+                    winding: (winding / TAU).round(),
+                };
+            }
+
+            vertices.push(next);
+            current = next;
+        }
+
+        PonceletTrajectory {
+            vertices,
+            period: None,
+            winding: winding / TAU,
+        }
+    }
+
+    /// The angle swept about the inner circle's center going from a to b
+    fn angle_about_inner(&self, a: Complex, b: Complex) -> f64 {
+        let from = a - self.inner.center;
+        let to = b - self.inner.center;
+        Complex::wedge(from, to).atan2(Complex::dot(from, to))
+    }
+}
+
+impl PonceletTrajectory {
+    pub fn is_closed(&self) -> bool {
+        self.period.is_some()
+    }
+
+    /// The chords of the walk. If the walk closed, this includes the chord
+    /// from the last vertex back to the start
+    pub fn edges(&self) -> Vec<LineSegment> {
+        let mut edges: Vec<LineSegment> = self
+            .vertices
+            .windows(2)
+            .map(|pair| LineSegment::new(pair[0], pair[1]))
+            .collect();
+
+        if let (true, [first, .., last]) = (self.is_closed(), &self.vertices[..]) {
+            edges.push(LineSegment::new(*last, *first));
+        }
+
+        edges
+    }
+
+    pub fn to_tile(&self) -> ClineArcTile {
+        ClineArcTile::new(self.edges().into_iter().map(|x| x.into()).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use test_case::test_case;
+
+    use crate::{nearly::assert_nearly, transformable::Cline, transformable::Side};
+
+    use super::*;
+
+    #[test]
+    pub fn new_with_overlapping_circles_returns_error() {
+        let result = PonceletPorism::new(
+            Circle::unit_circle(),
+            Circle::new(Complex::new(0.5, 0.0), 0.6),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn step_stays_on_outer_circle_and_is_tangent_to_inner() {
+        let porism = PonceletPorism::triangle(0.3).unwrap();
+        let start = Complex::from_polar(1.0, 0.7);
+
+        let result = porism.step(start);
+
+        let outer: Cline = porism.outer().into();
+        assert_eq!(outer.point_side(result), Side::On);
+        // The distance from the inner center to the chord equals the radius
+        let inner = porism.inner();
+        let chord = result - start;
+        let distance = Complex::wedge(chord, inner.center - start).abs() / chord.mag();
+        assert!((distance - inner.radius).abs() < 1e-12);
+    }
+
+    #[test_case(3; "triangle")]
+    #[test_case(5; "pentagon")]
+    #[test_case(12; "dodecagon")]
+    pub fn regular_closes_after_n_steps(n: usize) {
+        let porism = PonceletPorism::regular(n).unwrap();
+
+        let result = porism.trajectory(0.3, 100);
+
+        assert_eq!(result.period, Some(n));
+        assert_eq!(result.vertices.len(), n);
+        assert_nearly(result.winding, 1.0);
+    }
+
+    #[test_case(0.0; "start at 0")]
+    #[test_case(1.0; "start at 1")]
+    #[test_case(PI; "start at pi")]
+    pub fn triangle_closes_for_any_start(start_angle: f64) {
+        let porism = PonceletPorism::triangle(0.3).unwrap();
+
+        let result = porism.trajectory(start_angle, 100);
+
+        assert_eq!(result.period, Some(3));
+        assert_eq!(result.edges().len(), 3);
+    }
+
+    #[test]
+    pub fn trajectory_that_does_not_close_stops_at_max_steps() {
+        // Concentric circles close only when the inner radius is cos(pi p/q)
+        let porism = PonceletPorism::new(
+            Circle::unit_circle(),
+            Circle::new(Complex::Zero, 0.5_f64.sqrt().cos()),
+        )
+        .unwrap();
+
+        let result = porism.trajectory(0.0, 50);
+
+        assert_eq!(result.period, None);
+        assert_eq!(result.vertices.len(), 51);
+        assert_eq!(result.edges().len(), 50);
+    }
+}
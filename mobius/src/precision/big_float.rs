@@ -0,0 +1,78 @@
+use dashu_float::{ops::SquareRoot, FBig};
+
+use super::Scalar;
+
+/// Binary arbitrary precision float from dashu. Values made with
+/// Scalar::from_f64() carry BIG_FLOAT_BITS bits of precision, and
+/// arithmetic keeps the larger precision of its operands, so build values
+/// with FBig::with_precision() directly to go beyond that.
+pub type BigFloat = FBig;
+
+/// Significant bits of a BigFloat converted from an f64, about 77 decimal
+/// digits
+pub const BIG_FLOAT_BITS: usize = 256;
+
+impl Scalar for BigFloat {
+    fn from_f64(x: f64) -> Self {
+        // dashu has no NaN, and its operators panic on infinite values
+        // anyway
+        BigFloat::try_from(x)
+            .expect("BigFloat can't represent NaN")
+            .with_precision(BIG_FLOAT_BITS)
+            .value()
+    }
+
+    fn to_f64(&self) -> f64 {
+        FBig::to_f64(self).value()
+    }
+
+    fn sqrt(&self) -> Self {
+        SquareRoot::sqrt(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        precision::{GenericComplex, GenericMobius},
+        scale, translation, Complex,
+    };
+
+    use super::*;
+
+    type Precise = GenericMobius<BigFloat>;
+
+    #[test]
+    pub fn from_f64_uses_big_float_bits() {
+        let result = BigFloat::from_f64(0.1);
+
+        assert_eq!(result.precision(), BIG_FLOAT_BITS);
+        assert_eq!(Scalar::to_f64(&result), 0.1);
+    }
+
+    #[test]
+    pub fn sqrt_squares_back() {
+        let two = BigFloat::from_f64(2.0);
+
+        let root = Scalar::sqrt(&two);
+
+        let error = Scalar::to_f64(&(root.clone() * root - two)).abs();
+        assert!(error < 1e-70);
+    }
+
+    #[test]
+    pub fn deep_zoom_beyond_double_double() {
+        // 1e-40 is far below what DoubleDouble can resolve next to 0.25
+        let tiny = BigFloat::from_f64(1e-40);
+        let near = GenericComplex::new(BigFloat::from_f64(0.25) + tiny, BigFloat::zero());
+        let far = GenericComplex::from_complex(Complex::new(0.25, 0.0)).unwrap();
+
+        let recenter = Precise::from(translation(Complex::new(-0.25, 0.0)).unwrap());
+        let zoom = Precise::from(scale(1e20).unwrap());
+        let xform = zoom.clone() * zoom * recenter;
+
+        let result = xform.apply(&near).unwrap() - xform.apply(&far).unwrap();
+
+        assert!((Scalar::to_f64(&result.re) - 1.0).abs() < 1e-10);
+    }
+}
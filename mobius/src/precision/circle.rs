@@ -0,0 +1,128 @@
+use crate::geometry::Circle;
+
+use super::{GenericComplex, GenericMobius, Scalar};
+
+/// Circle backed by any Scalar, so circles deep in a limit set keep their
+/// precision through many transformations. See Circle for the f64 version
+#[derive(Clone, Debug, PartialEq)]
+pub struct GenericCircle<S: Scalar> {
+    pub center: GenericComplex<S>,
+    pub radius: S,
+}
+
+impl<S: Scalar> GenericCircle<S> {
+    pub fn new(center: GenericComplex<S>, radius: S) -> Self {
+        Self { center, radius }
+    }
+
+    /// Convert a Circle, which is always finite
+    pub fn from_circle(circle: Circle) -> Self {
+        let center = GenericComplex::from_complex(circle.center).expect("center must be finite");
+        Self::new(center, S::from_f64(circle.radius))
+    }
+
+    /// Round to a Circle, e.g. for rendering
+    pub fn to_circle(&self) -> Circle {
+        Circle::new(self.center.to_complex(), self.radius.to_f64())
+    }
+
+    /// Reflect a point in the circle, z -> center + r^2 / conj(z - center).
+    /// Returns None for the center, which is sent to infinity
+    pub fn reflect(&self, z: &GenericComplex<S>) -> Option<GenericComplex<S>> {
+        let offset = z.clone() - self.center.clone();
+        if offset.is_zero() {
+            return None;
+        }
+
+        let r_squared = self.radius.clone() * self.radius.clone();
+        Some(self.center.clone() + GenericComplex::from(r_squared) / offset.conj())
+    }
+
+    /// Image of the circle under a Mobius transformation. Mobius
+    /// transformations preserve reflections, so the image of the center
+    /// is the image of the reflection of the pole -d/c. Returns an error
+    /// if the pole is on the circle, since then the image is a line
+    pub fn transform(&self, xform: &GenericMobius<S>) -> Result<Self, String> {
+        let pole_reflection = if xform.c.is_zero() {
+            // The pole is infinity, and its reflection is the center
+            self.center.clone()
+        } else {
+            let pole = -xform.d.clone() / xform.c.clone();
+            self.reflect(&pole)
+                .ok_or_else(|| String::from("the pole is at the center of the circle"))?
+        };
+
+        let image_center = xform
+            .apply(&pole_reflection)
+            .ok_or_else(|| String::from("the pole is on the circle, so the image is a line"))?;
+        let on_circle = self.center.clone() + GenericComplex::from(self.radius.clone());
+        let image_point = xform
+            .apply(&on_circle)
+            .ok_or_else(|| String::from("the pole is on the circle, so the image is a line"))?;
+
+        let radius = (image_point - image_center.clone()).mag();
+        Ok(Self::new(image_center, radius))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use abstraction::Group;
+
+    use crate::{
+        isogonal::Isogonal,
+        precision::DoubleDouble,
+        rotation, scale,
+        transformable::{Cline, Transformable},
+        translation, Complex, Mobius,
+    };
+
+    use super::*;
+
+    type Precise = GenericCircle<DoubleDouble>;
+
+    #[test]
+    pub fn transform_matches_cline_transform() {
+        let circle = Circle::new(Complex::new(1.0, 2.0), 0.5);
+        let m = translation(Complex::new(0.5, -1.0)).unwrap()
+            * Mobius::INVERSION
+            * rotation(0.3).unwrap();
+
+        let result = Precise::from_circle(circle)
+            .transform(&GenericMobius::from(m))
+            .unwrap();
+
+        let expected = Cline::from(circle).transform(Isogonal::from(m));
+        assert_eq!(Cline::from(result.to_circle()), expected);
+    }
+
+    #[test]
+    pub fn transform_with_pole_on_circle_returns_error() {
+        let circle = Circle::new(Complex::new(1.0, 0.0), 1.0);
+
+        let result =
+            Precise::from_circle(circle).transform(&GenericMobius::from(Mobius::INVERSION));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn deep_zoom_keeps_tiny_circle() {
+        // A circle of radius 1e-20 is lost to rounding in f64 once it is
+        // offset from the origin, but survives a zoom in and back out
+        let tiny = GenericCircle::new(
+            GenericComplex::new(DoubleDouble::new(0.25, 1e-20), DoubleDouble::from(0.0)),
+            DoubleDouble::from(1e-20),
+        );
+        let zoom = GenericMobius::from(scale(1e10).unwrap());
+        let recenter = GenericMobius::from(translation(Complex::new(-0.25, 0.0)).unwrap());
+        let xform = zoom.clone() * zoom * recenter;
+
+        let zoomed = tiny.transform(&xform).unwrap();
+        let restored = zoomed.transform(&xform.inverse()).unwrap();
+
+        assert!((zoomed.radius.to_f64() - 1.0).abs() < 1e-10);
+        assert!((zoomed.center.re.to_f64() - 1.0).abs() < 1e-10);
+        assert!((restored.radius.to_f64() / 1e-20 - 1.0).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,169 @@
+use std::{
+    fmt::Display,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use crate::Complex;
+
+use super::Scalar;
+
+/// Finite complex number backed by any Scalar. Unlike Complex, this has
+/// no point at infinity and does no rounding to zero, since with more
+/// precision, tiny values are meaningful.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GenericComplex<S: Scalar> {
+    pub re: S,
+    pub im: S,
+}
+
+impl<S: Scalar> GenericComplex<S> {
+    pub fn new(re: S, im: S) -> Self {
+        Self { re, im }
+    }
+
+    pub fn zero() -> Self {
+        Self::new(S::zero(), S::zero())
+    }
+
+    pub fn one() -> Self {
+        Self::new(S::one(), S::zero())
+    }
+
+    /// Convert a (finite) Complex
+    pub fn from_complex(z: Complex) -> Result<Self, String> {
+        match z {
+            Complex::Infinity => Err(String::from("z must be finite")),
+            z => Ok(Self::new(S::from_f64(z.real()), S::from_f64(z.imag()))),
+        }
+    }
+
+    /// Round to a Complex, e.g. for rendering
+    pub fn to_complex(&self) -> Complex {
+        Complex::new(self.re.to_f64(), self.im.to_f64())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.re == S::zero() && self.im == S::zero()
+    }
+
+    pub fn norm(&self) -> S {
+        self.re.clone() * self.re.clone() + self.im.clone() * self.im.clone()
+    }
+
+    pub fn mag(&self) -> S {
+        self.norm().sqrt()
+    }
+
+    pub fn conj(&self) -> Self {
+        Self::new(self.re.clone(), -self.im.clone())
+    }
+
+    /// Principal square root, computed without trig functions
+    pub fn sqrt(&self) -> Self {
+        let two = S::from_f64(2.0);
+        let r = self.mag();
+        let re = ((r.clone() + self.re.clone()) / two.clone()).sqrt();
+        let im = ((r - self.re.clone()) / two).sqrt();
+
+        if self.im < S::zero() {
+            Self::new(re, -im)
+        } else {
+            Self::new(re, im)
+        }
+    }
+}
+
+impl<S: Scalar> From<S> for GenericComplex<S> {
+    fn from(value: S) -> Self {
+        Self::new(value, S::zero())
+    }
+}
+
+impl<S: Scalar> Add for GenericComplex<S> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl<S: Scalar> Sub for GenericComplex<S> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl<S: Scalar> Neg for GenericComplex<S> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.re, -self.im)
+    }
+}
+
+impl<S: Scalar> Mul for GenericComplex<S> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let Self { re: a, im: b } = self;
+        let Self { re: c, im: d } = rhs;
+        Self::new(a.clone() * c.clone() - b.clone() * d.clone(), a * d + b * c)
+    }
+}
+
+impl<S: Scalar> Div for GenericComplex<S> {
+    type Output = Self;
+
+    /// Division by zero is not checked, it produces whatever S does
+    fn div(self, rhs: Self) -> Self::Output {
+        // (a + bi) / (c + di) = (a + bi)(c - di) / (c^2 + d^2)
+        let denominator = rhs.norm();
+        let Self { re, im } = self * rhs.conj();
+        Self::new(re / denominator.clone(), im / denominator)
+    }
+}
+
+impl<S: Scalar> Display for GenericComplex<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}) + ({})i", self.re, self.im)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::precision::DoubleDouble;
+
+    use super::*;
+
+    #[test]
+    pub fn arithmetic_matches_complex() {
+        let a = Complex::new(1.0, 2.0);
+        let b = Complex::new(-3.0, 0.5);
+        let [ga, gb]: [GenericComplex<f64>; 2] =
+            [a, b].map(|z| GenericComplex::from_complex(z).unwrap());
+
+        assert_eq!((ga.clone() + gb.clone()).to_complex(), a + b);
+        assert_eq!((ga.clone() - gb.clone()).to_complex(), a - b);
+        assert_eq!((ga.clone() * gb.clone()).to_complex(), a * b);
+        assert_eq!((ga / gb).to_complex(), a / b);
+    }
+
+    #[test]
+    pub fn sqrt_squares_back() {
+        let z = GenericComplex::<DoubleDouble>::from_complex(Complex::new(-3.0, -4.0)).unwrap();
+
+        let root = z.sqrt();
+
+        assert_eq!(root.to_complex(), Complex::new(1.0, -2.0));
+        assert_eq!((root.clone() * root).to_complex(), z.to_complex());
+    }
+
+    #[test]
+    pub fn from_infinity_returns_error() {
+        let result = GenericComplex::<f32>::from_complex(Complex::Infinity);
+
+        assert!(result.is_err());
+    }
+}
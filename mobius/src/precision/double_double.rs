@@ -0,0 +1,205 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use super::Scalar;
+
+/// Error-free sum: a + b = s + e exactly
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let b_virtual = s - a;
+    let a_virtual = s - b_virtual;
+    let e = (a - a_virtual) + (b - b_virtual);
+    (s, e)
+}
+
+/// Error-free sum when |a| >= |b|
+fn quick_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let e = b - (s - a);
+    (s, e)
+}
+
+/// Error-free product: a * b = p + e exactly
+fn two_prod(a: f64, b: f64) -> (f64, f64) {
+    let p = a * b;
+    let e = a.mul_add(b, -p);
+    (p, e)
+}
+
+/// Unevaluated sum of two f64s hi + lo with |lo| <= ulp(hi) / 2, for about
+/// 32 significant digits without any dependencies. Enough for zooming
+/// about 10^15 times further than f64 allows. The algorithms follow
+/// Hida, Li and Bailey's QD library.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DoubleDouble {
+    hi: f64,
+    lo: f64,
+}
+
+impl DoubleDouble {
+    pub fn new(hi: f64, lo: f64) -> Self {
+        let (hi, lo) = two_sum(hi, lo);
+        Self { hi, lo }
+    }
+
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+}
+
+impl From<f64> for DoubleDouble {
+    fn from(value: f64) -> Self {
+        Self { hi: value, lo: 0.0 }
+    }
+}
+
+impl Add for DoubleDouble {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let (s, e) = two_sum(self.hi, rhs.hi);
+        let (t, f) = two_sum(self.lo, rhs.lo);
+        let (s, e) = quick_two_sum(s, e + t);
+        let (hi, lo) = quick_two_sum(s, e + f);
+        Self { hi, lo }
+    }
+}
+
+impl Neg for DoubleDouble {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            hi: -self.hi,
+            lo: -self.lo,
+        }
+    }
+}
+
+impl Sub for DoubleDouble {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + (-rhs)
+    }
+}
+
+impl Mul for DoubleDouble {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let (p, e) = two_prod(self.hi, rhs.hi);
+        let e = e + (self.hi * rhs.lo + self.lo * rhs.hi);
+        let (hi, lo) = quick_two_sum(p, e);
+        Self { hi, lo }
+    }
+}
+
+impl Div for DoubleDouble {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        // Long division: estimate the quotient with f64, then correct it
+        // with the remainder
+        let q1 = self.hi / rhs.hi;
+        let remainder = self - rhs * q1.into();
+        let q2 = remainder.hi / rhs.hi;
+        let remainder = remainder - rhs * q2.into();
+        let q3 = remainder.hi / rhs.hi;
+
+        let (hi, lo) = quick_two_sum(q1, q2);
+        Self { hi, lo } + q3.into()
+    }
+}
+
+impl PartialEq for DoubleDouble {
+    fn eq(&self, other: &Self) -> bool {
+        self.hi == other.hi && self.lo == other.lo
+    }
+}
+
+impl PartialOrd for DoubleDouble {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.hi.partial_cmp(&other.hi)? {
+            Ordering::Equal => self.lo.partial_cmp(&other.lo),
+            ordering => Some(ordering),
+        }
+    }
+}
+
+impl Display for DoubleDouble {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:e} + {:e}", self.hi, self.lo)
+    }
+}
+
+impl Scalar for DoubleDouble {
+    fn from_f64(x: f64) -> Self {
+        x.into()
+    }
+
+    fn to_f64(&self) -> f64 {
+        self.hi + self.lo
+    }
+
+    fn sqrt(&self) -> Self {
+        if self.hi <= 0.0 {
+            return Self::from(self.hi.sqrt());
+        }
+
+        // One Newton step from the f64 estimate doubles the precision
+        let x: Self = self.hi.sqrt().into();
+        let half: Self = 0.5.into();
+        half * (x + *self / x)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn add_keeps_tiny_terms() {
+        let one = DoubleDouble::from(1.0);
+        let tiny = DoubleDouble::from(1e-20);
+
+        let result = (one + tiny) - one;
+
+        assert_eq!(result.to_f64(), 1e-20);
+    }
+
+    #[test]
+    pub fn mul_is_exact_beyond_f64() {
+        let third = DoubleDouble::from(1.0) / DoubleDouble::from(3.0);
+
+        let result = third * DoubleDouble::from(3.0) - DoubleDouble::from(1.0);
+
+        assert!(result.to_f64().abs() < 1e-30);
+    }
+
+    #[test]
+    pub fn sqrt_squares_back() {
+        let two = DoubleDouble::from(2.0);
+
+        let root = two.sqrt();
+        let result = root * root - two;
+
+        assert!(result.to_f64().abs() < 1e-30);
+    }
+
+    #[test]
+    pub fn ordering_uses_low_part() {
+        let a = DoubleDouble::new(1.0, 1e-20);
+        let b = DoubleDouble::new(1.0, 2e-20);
+
+        assert!(a < b);
+        assert_ne!(a, b);
+    }
+}
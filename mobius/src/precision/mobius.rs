@@ -0,0 +1,213 @@
+use std::ops::Mul;
+
+use abstraction::{Group, Monoid};
+
+use crate::Mobius;
+
+use super::{GenericComplex, Scalar};
+
+/// Mobius transformation (az + b) / (cz + d) with ad - bc = 1, backed by
+/// any Scalar. See Mobius for the f64 version used everywhere else.
+#[derive(Clone, Debug)]
+pub struct GenericMobius<S: Scalar> {
+    pub a: GenericComplex<S>,
+    pub b: GenericComplex<S>,
+    pub c: GenericComplex<S>,
+    pub d: GenericComplex<S>,
+}
+
+impl<S: Scalar> GenericMobius<S> {
+    /// Create a transformation, dividing by sqrt(ad - bc) so the
+    /// determinant is 1
+    pub fn from_unnormalized(
+        a: GenericComplex<S>,
+        b: GenericComplex<S>,
+        c: GenericComplex<S>,
+        d: GenericComplex<S>,
+    ) -> Result<Self, String> {
+        let det = a.clone() * d.clone() - b.clone() * c.clone();
+        if det.is_zero() {
+            return Err(String::from("ad - bc must be nonzero"));
+        }
+
+        let sqrt_det = det.sqrt();
+        Ok(Self {
+            a: a / sqrt_det.clone(),
+            b: b / sqrt_det.clone(),
+            c: c / sqrt_det.clone(),
+            d: d / sqrt_det,
+        })
+    }
+
+    /// Round to a Mobius, e.g. for rendering
+    pub fn to_mobius(&self) -> Result<Mobius, String> {
+        Mobius::from_unnormalized(
+            self.a.to_complex(),
+            self.b.to_complex(),
+            self.c.to_complex(),
+            self.d.to_complex(),
+        )
+    }
+
+    pub fn det(&self) -> GenericComplex<S> {
+        self.a.clone() * self.d.clone() - self.b.clone() * self.c.clone()
+    }
+
+    pub fn trace(&self) -> GenericComplex<S> {
+        self.a.clone() + self.d.clone()
+    }
+
+    /// Apply the transformation to a point. Returns None if z is the pole
+    /// -d/c, which is sent to infinity
+    pub fn apply(&self, z: &GenericComplex<S>) -> Option<GenericComplex<S>> {
+        let denominator = self.c.clone() * z.clone() + self.d.clone();
+        if denominator.is_zero() {
+            return None;
+        }
+
+        Some((self.a.clone() * z.clone() + self.b.clone()) / denominator)
+    }
+}
+
+impl<S: Scalar> From<Mobius> for GenericMobius<S> {
+    fn from(value: Mobius) -> Self {
+        // The entries of a Mobius are always finite
+        let convert = |z| GenericComplex::from_complex(z).expect("entries must be finite");
+        Self {
+            a: convert(value.a),
+            b: convert(value.b),
+            c: convert(value.c),
+            d: convert(value.d),
+        }
+    }
+}
+
+impl<S: Scalar> Mul for GenericMobius<S> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let Self { a, b, c, d } = self;
+        let Self {
+            a: e,
+            b: f,
+            c: g,
+            d: h,
+        } = rhs;
+
+        // The product of determinant 1 matrices has determinant 1, so
+        // don't renormalize. Rounding error is what the extra precision
+        // is for.
+        Self {
+            a: a.clone() * e.clone() + b.clone() * g.clone(),
+            b: a * f.clone() + b * h.clone(),
+            c: c.clone() * e + d.clone() * g,
+            d: c * f + d * h,
+        }
+    }
+}
+
+impl<S: Scalar> PartialEq for GenericMobius<S> {
+    /// Exact comparison. Like Mobius, M and -M are the same transformation
+    fn eq(&self, other: &Self) -> bool {
+        let same = self.a == other.a && self.b == other.b && self.c == other.c && self.d == other.d;
+        let negated = self.a == -other.a.clone()
+            && self.b == -other.b.clone()
+            && self.c == -other.c.clone()
+            && self.d == -other.d.clone();
+        same || negated
+    }
+}
+
+impl<S: Scalar> Monoid for GenericMobius<S> {
+    fn identity() -> Self {
+        Self {
+            a: GenericComplex::one(),
+            b: GenericComplex::zero(),
+            c: GenericComplex::zero(),
+            d: GenericComplex::one(),
+        }
+    }
+}
+
+impl<S: Scalar> Group for GenericMobius<S> {
+    fn inverse(&self) -> Self {
+        Self {
+            a: self.d.clone(),
+            b: -self.b.clone(),
+            c: -self.c.clone(),
+            d: self.a.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{precision::DoubleDouble, scale, translation, Complex};
+
+    use super::*;
+
+    type Precise = GenericMobius<DoubleDouble>;
+
+    #[test]
+    pub fn apply_matches_mobius() {
+        let m = Mobius::from_unnormalized(
+            Complex::new(2.0, 1.0),
+            Complex::new(1.0, -1.0),
+            Complex::new(0.5, 0.0),
+            Complex::new(1.0, 0.5),
+        )
+        .unwrap();
+        let z = Complex::new(0.3, -0.7);
+
+        let result = GenericMobius::<f64>::from(m)
+            .apply(&GenericComplex::from_complex(z).unwrap())
+            .unwrap();
+
+        assert_eq!(result.to_complex(), m * z);
+    }
+
+    #[test]
+    pub fn apply_at_pole_returns_none() {
+        let m = Precise::from(Mobius::INVERSION);
+
+        let result = m.apply(&GenericComplex::zero());
+
+        assert!(result.is_none());
+    }
+
+    #[test]
+    pub fn inverse_undoes_transformation() {
+        let m = Precise::from(translation(Complex::new(1.0, 2.0)).unwrap() * Mobius::INVERSION);
+        let z = GenericComplex::from_complex(Complex::new(0.3, -0.7)).unwrap();
+
+        let result = m.inverse().apply(&m.apply(&z).unwrap()).unwrap();
+
+        assert_eq!(result.to_complex(), z.to_complex());
+    }
+
+    #[test]
+    pub fn deep_zoom_separates_nearby_points() {
+        // Points 1e-20 apart are the same f64, but double-double can tell
+        // them apart and blow the difference back up to a visible size
+        let offset = DoubleDouble::new(0.25, 1e-20);
+        let near = GenericComplex::new(offset, DoubleDouble::from(0.0));
+        let far = GenericComplex::from_complex(Complex::new(0.25, 0.0)).unwrap();
+
+        let recenter = Precise::from(translation(Complex::new(-0.25, 0.0)).unwrap());
+        let zoom = Precise::from(scale(1e10).unwrap());
+        let xform = zoom.clone() * zoom * recenter;
+
+        let result = xform.apply(&near).unwrap() - xform.apply(&far).unwrap();
+
+        assert!((result.re.to_f64() - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    pub fn to_mobius_round_trips() {
+        let m = translation(Complex::new(1.0, 2.0)).unwrap() * Mobius::INVERSION;
+
+        let result = Precise::from(m).to_mobius().unwrap();
+
+        assert_eq!(result, m);
+    }
+}
@@ -0,0 +1,33 @@
+//! Complex numbers, Mobius transformations and circles generic over the
+//! real number type, for computations that need more (or less) precision
+//! than f64, such as deep zooms into limit sets. Compute with these, then
+//! round to Complex, Mobius and Circle for the rest of the geometry and
+//! rendering code.
+//!
+//! DoubleDouble gives about twice the precision of f64 without any
+//! dependencies. The arbitrary_precision feature adds BigFloat, backed by
+//! dashu, and makes it the Real type. Otherwise Real is f64. Code that
+//! should follow the build's choice uses Real, code that needs a specific
+//! precision picks the Scalar type parameter explicitly.
+#[cfg(feature = "arbitrary_precision")]
+pub mod big_float;
+pub mod circle;
+pub mod complex;
+pub mod double_double;
+pub mod mobius;
+pub mod scalar;
+
+#[cfg(feature = "arbitrary_precision")]
+pub use big_float::*;
+pub use circle::*;
+pub use complex::*;
+pub use double_double::*;
+pub use mobius::*;
+pub use scalar::*;
+
+/// The Scalar this build uses for high precision work, see the module docs
+#[cfg(feature = "arbitrary_precision")]
+pub type Real = BigFloat;
+/// The Scalar this build uses for high precision work, see the module docs
+#[cfg(not(feature = "arbitrary_precision"))]
+pub type Real = f64;
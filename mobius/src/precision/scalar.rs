@@ -0,0 +1,71 @@
+use std::{
+    fmt::{Debug, Display},
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+/// Real number type backing GenericComplex and GenericMobius. Only the
+/// field operations and a square root are needed, so arbitrary precision
+/// floats can implement this as well as the primitive types.
+pub trait Scalar:
+    Clone
+    + PartialEq
+    + PartialOrd
+    + Debug
+    + Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    fn from_f64(x: f64) -> Self;
+
+    /// Round to the nearest f64, e.g. for rendering
+    fn to_f64(&self) -> f64;
+
+    fn sqrt(&self) -> Self;
+
+    fn zero() -> Self {
+        Self::from_f64(0.0)
+    }
+
+    fn one() -> Self {
+        Self::from_f64(1.0)
+    }
+
+    fn abs(&self) -> Self {
+        if *self < Self::zero() {
+            -self.clone()
+        } else {
+            self.clone()
+        }
+    }
+}
+
+impl Scalar for f64 {
+    fn from_f64(x: f64) -> Self {
+        x
+    }
+
+    fn to_f64(&self) -> f64 {
+        *self
+    }
+
+    fn sqrt(&self) -> Self {
+        f64::sqrt(*self)
+    }
+}
+
+impl Scalar for f32 {
+    fn from_f64(x: f64) -> Self {
+        x as f32
+    }
+
+    fn to_f64(&self) -> f64 {
+        *self as f64
+    }
+
+    fn sqrt(&self) -> Self {
+        f32::sqrt(*self)
+    }
+}
@@ -0,0 +1,22 @@
+//! The types artworks use most often, for glob importing:
+//!
+//! ```
+//! use mobius::prelude::*;
+//! ```
+//!
+//! This includes the rendering prelude and the abstraction prelude, so
+//! the group traits like Monoid and Group are in scope too.
+pub use abstraction::prelude::*;
+
+pub use crate::{
+    algorithms::{GridIFS, GroupIFS, MonoidIFS},
+    cline_arc::ClineArc,
+    geometry::{ArcAngles, Circle, CircularArc, Line, LineSegment},
+    isogonal::Isogonal,
+    recipes::*,
+    rendering::prelude::*,
+    transformable::{
+        Cline, ClineArcTile, ClineTile, Collection, Label, Motif, PointCloud, Transformable,
+    },
+    Complex, Mobius,
+};
@@ -0,0 +1,69 @@
+/// Small deterministic pseudo-random number generator (SplitMix64). This
+/// is not suitable for cryptography, but it is fast, has no dependencies,
+/// and the same seed always gives the same sequence on every platform, so
+/// randomized artwork is reproducible.
+///
+/// See https://prng.di.unimi.it/splitmix64.c
+#[derive(Clone, Debug)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform random number in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        // Use the top 53 bits, the precision of an f64 mantissa
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform random index in [0, n). n must be positive
+    pub fn next_index(&mut self, n: usize) -> usize {
+        ((self.next_f64() * n as f64) as usize).min(n - 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn same_seed_gives_same_sequence() {
+        let mut a = SplitMix64::new(42);
+        let mut b = SplitMix64::new(42);
+
+        let result_a: Vec<u64> = (0..5).map(|_| a.next_u64()).collect();
+        let result_b: Vec<u64> = (0..5).map(|_| b.next_u64()).collect();
+
+        assert_eq!(result_a, result_b);
+    }
+
+    #[test]
+    pub fn next_u64_matches_reference_implementation() {
+        let mut rng = SplitMix64::new(1234567);
+
+        let result = rng.next_u64();
+
+        assert_eq!(result, 6457827717110365317);
+    }
+
+    #[test]
+    pub fn next_f64_is_in_unit_interval() {
+        let mut rng = SplitMix64::new(7);
+
+        assert!((0..1000)
+            .map(|_| rng.next_f64())
+            .all(|x| (0.0..1.0).contains(&x)));
+    }
+}
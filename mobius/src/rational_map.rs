@@ -0,0 +1,270 @@
+use std::fmt::Display;
+
+use crate::{Complex, ComplexPolynomial, Mobius};
+
+/// Rational function R(z) = P(z) / Q(z) of the Riemann sphere to itself.
+/// Möbius transformations are the rational maps of degree 1, so this is
+/// the next step up for dynamics experiments.
+///
+/// P and Q are assumed to have no common roots, this is not checked.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RationalMap {
+    numerator: ComplexPolynomial,
+    denominator: ComplexPolynomial,
+}
+
+impl RationalMap {
+    pub fn new(
+        numerator: ComplexPolynomial,
+        denominator: ComplexPolynomial,
+    ) -> Result<Self, String> {
+        if denominator.degree().is_none() {
+            return Err(String::from("denominator must not be zero"));
+        }
+
+        Ok(Self {
+            numerator,
+            denominator,
+        })
+    }
+
+    /// The polynomial P as the rational map P / 1
+    pub fn from_polynomial(polynomial: ComplexPolynomial) -> Self {
+        Self {
+            numerator: polynomial,
+            denominator: ComplexPolynomial::constant(Complex::ONE).unwrap(),
+        }
+    }
+
+    pub fn numerator(&self) -> &ComplexPolynomial {
+        &self.numerator
+    }
+
+    pub fn denominator(&self) -> &ComplexPolynomial {
+        &self.denominator
+    }
+
+    /// The larger of the degrees of P and Q. A rational map of degree d
+    /// covers the sphere d times
+    pub fn degree(&self) -> usize {
+        self.numerator
+            .degree()
+            .unwrap_or(0)
+            .max(self.denominator.degree().unwrap_or(0))
+    }
+
+    pub fn eval(&self, z: Complex) -> Complex {
+        if z == Complex::Infinity {
+            // Compare the leading terms
+            let p = self.numerator.degree();
+            let q = self.denominator.degree().unwrap_or(0);
+            return match p {
+                None => Complex::Zero,
+                Some(p) if p > q => Complex::Infinity,
+                Some(p) if p < q => Complex::Zero,
+                Some(p) => self.numerator.coefficient(p) / self.denominator.coefficient(q),
+            };
+        }
+
+        let q = self.denominator.eval(z);
+        if q == Complex::Zero {
+            return Complex::Infinity;
+        }
+
+        self.numerator.eval(z) / q
+    }
+
+    /// Derivative by the quotient rule, (P'Q - PQ') / Q^2
+    pub fn derivative(&self) -> Self {
+        let (p, q) = (&self.numerator, &self.denominator);
+        let numerator = &(&p.derivative() * q) + &(p * &q.derivative()).scale(-Complex::ONE);
+
+        Self {
+            numerator,
+            denominator: q * q,
+        }
+    }
+
+    /// The map z -> self(inner(z)). If self = A / B has degree n and
+    /// inner = P / Q, both A(P/Q) and B(P/Q) have denominator Q^n, which
+    /// cancels, so the result is
+    ///
+    /// sum(a_k P^k Q^(n-k)) / sum(b_k P^k Q^(n-k))
+    pub fn compose(&self, inner: &Self) -> Self {
+        let n = self.degree();
+        let (p, q) = (&inner.numerator, &inner.denominator);
+
+        // p_powers[k] = P^k, q_powers[k] = Q^k
+        let powers = |x: &ComplexPolynomial| {
+            let mut result = vec![ComplexPolynomial::constant(Complex::ONE).unwrap()];
+            for k in 0..n {
+                result.push(&result[k] * x);
+            }
+            result
+        };
+        let p_powers = powers(p);
+        let q_powers = powers(q);
+
+        let homogenize = |x: &ComplexPolynomial| {
+            (0..=n).fold(ComplexPolynomial::new(vec![]).unwrap(), |acc, k| {
+                let term = (&p_powers[k] * &q_powers[n - k]).scale(x.coefficient(k));
+                &acc + &term
+            })
+        };
+
+        Self {
+            numerator: homogenize(&self.numerator),
+            denominator: homogenize(&self.denominator),
+        }
+    }
+
+    /// Conjugate by a Möbius transformation, M R M^-1. This is R in the
+    /// coordinates where M has been applied, so fixed points, critical
+    /// points and Julia sets all move by M.
+    pub fn conjugate(&self, xform: Mobius) -> Self {
+        Self::from(xform)
+            .compose(self)
+            .compose(&Self::from(xform.inverse()))
+    }
+
+    /// Convert a degree 1 map (az + b) / (cz + d) to a Möbius
+    /// transformation, scaling the coefficients to have determinant 1
+    pub fn to_mobius(&self) -> Result<Mobius, String> {
+        if self.degree() != 1 {
+            return Err(format!(
+                "only degree 1 maps are Möbius transformations, got degree {}",
+                self.degree()
+            ));
+        }
+
+        let a = self.numerator.coefficient(1);
+        let b = self.numerator.coefficient(0);
+        let c = self.denominator.coefficient(1);
+        let d = self.denominator.coefficient(0);
+        if a * d - b * c == Complex::Zero {
+            return Err(String::from("map is constant, ad - bc = 0"));
+        }
+
+        Mobius::from_unnormalized(a, b, c, d)
+    }
+}
+
+impl From<Mobius> for RationalMap {
+    fn from(value: Mobius) -> Self {
+        let Mobius { a, b, c, d } = value;
+        Self {
+            numerator: ComplexPolynomial::new(vec![b, a]).unwrap(),
+            denominator: ComplexPolynomial::new(vec![d, c]).unwrap(),
+        }
+    }
+}
+
+impl Display for RationalMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}) / ({})", self.numerator, self.denominator)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use abstraction::GroupAction;
+
+    use crate::{rotation, translation};
+
+    use super::*;
+
+    fn square_plus(c: Complex) -> RationalMap {
+        let polynomial = ComplexPolynomial::new(vec![c, Complex::Zero, Complex::ONE]).unwrap();
+        RationalMap::from_polynomial(polynomial)
+    }
+
+    #[test]
+    pub fn new_with_zero_denominator_returns_error() {
+        let zero = ComplexPolynomial::new(vec![]).unwrap();
+
+        let result = RationalMap::new(ComplexPolynomial::identity(), zero);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn eval_handles_poles_and_infinity() {
+        let inversion = RationalMap::from(Mobius::INVERSION);
+
+        assert_eq!(inversion.eval(Complex::Zero), Complex::Infinity);
+        assert_eq!(inversion.eval(Complex::Infinity), Complex::Zero);
+        assert_eq!(
+            inversion.eval(Complex::new(2.0, 0.0)),
+            Complex::new(0.5, 0.0)
+        );
+        assert_eq!(
+            square_plus(Complex::ONE).eval(Complex::Infinity),
+            Complex::Infinity
+        );
+    }
+
+    #[test]
+    pub fn derivative_of_inversion() {
+        let inversion = RationalMap::from(Mobius::INVERSION);
+        let z = Complex::new(0.5, 1.5);
+
+        let result = inversion.derivative().eval(z);
+
+        // d/dz 1/z = -1/z^2
+        assert_eq!(result, -(z * z).inverse());
+    }
+
+    #[test]
+    pub fn compose_matches_nested_eval() {
+        let outer = square_plus(Complex::I);
+        let inner = RationalMap::from(Mobius::CAYLEY_MAP);
+        let z = Complex::new(0.2, 0.7);
+
+        let result = outer.compose(&inner);
+
+        assert_eq!(result.degree(), 2);
+        assert_eq!(result.eval(z), outer.eval(inner.eval(z)));
+    }
+
+    #[test]
+    pub fn conjugate_moves_fixed_points() {
+        let map = square_plus(Complex::Zero);
+        let xform = translation(Complex::new(1.0, 2.0)).unwrap();
+
+        let result = map.conjugate(xform);
+
+        // 0 is a fixed point of z^2, so 1 + 2i is a fixed point of the
+        // conjugated map
+        let fixed = xform.act(&Complex::Zero);
+        assert_eq!(result.eval(fixed), fixed);
+    }
+
+    #[test]
+    pub fn to_mobius_round_trips() {
+        let xform = rotation(0.5).unwrap() * Mobius::CAYLEY_MAP;
+
+        let result = RationalMap::from(xform).to_mobius().unwrap();
+
+        assert_eq!(result, xform);
+    }
+
+    #[test]
+    pub fn composition_of_mobius_maps_is_mobius_product() {
+        let a = Mobius::CAYLEY_MAP;
+        let b = translation(Complex::I).unwrap();
+
+        let result = RationalMap::from(a)
+            .compose(&RationalMap::from(b))
+            .to_mobius()
+            .unwrap();
+
+        assert_eq!(result, a * b);
+    }
+
+    #[test]
+    pub fn to_mobius_with_higher_degree_returns_error() {
+        let result = square_plus(Complex::ONE).to_mobius();
+
+        assert!(result.is_err());
+    }
+}
@@ -1,9 +1,14 @@
-use crate::{nearly::is_nearly, Complex, Mobius};
+use crate::{
+    isogonal::Isogonal,
+    nearly::is_nearly,
+    transformable::{Cline, Transformable},
+    Complex, FixedPoints, Mobius,
+};
 
 // Complex inversion nu(z) = 1/z, implemented as
 // (0z + i) / (iz + 0) to have determinant 1
 pub fn inversion() -> Mobius {
-    Mobius::new(Complex::Zero, Complex::I, Complex::I, Complex::Zero).unwrap()
+    Mobius::INVERSION
 }
 
 pub fn translation(displacement: Complex) -> Result<Mobius, String> {
@@ -80,6 +85,50 @@ pub fn loxodromic(k: Complex) -> Result<Mobius, String> {
     Mobius::new(plus, minus, minus, plus)
 }
 
+/// Elliptic transformation with fixed points p and q that rotates
+/// counterclockwise around p by theta. A multiple of a full turn would be
+/// the identity, which fixes every point, so this returns an error
+pub fn elliptic_about(p: Complex, q: Complex, theta: f64) -> Result<Mobius, String> {
+    if !theta.is_finite() {
+        return Err(String::from("theta must be finite"));
+    }
+
+    let multiplier = Complex::from_polar(1.0, theta);
+    if multiplier == Complex::ONE {
+        return Err(format!(
+            "theta must not be a multiple of 2pi, got {}",
+            theta
+        ));
+    }
+
+    Mobius::from_fixed_points_and_multiplier(FixedPoints::Pair(p, q), multiplier)
+}
+
+/// Hyperbolic transformation with fixed points p and q and multiplier k at
+/// p. When k < 1, p is the sink and q is the source. k = 1 would be the
+/// identity, so this returns an error
+pub fn hyperbolic_about(p: Complex, q: Complex, k: f64) -> Result<Mobius, String> {
+    if !(k > 0.0 && k.is_finite()) {
+        return Err(format!("k must be positive and finite, got {}", k));
+    }
+
+    if is_nearly(k, 1.0) {
+        return Err(String::from("k must not be 1"));
+    }
+
+    Mobius::from_fixed_points_and_multiplier(FixedPoints::Pair(p, q), k.into())
+}
+
+/// Parabolic transformation with a single fixed point p. Sending p to
+/// infinity with z -> 1/(z - p) turns it into a translation by d
+pub fn parabolic_about(p: Complex, d: Complex) -> Result<Mobius, String> {
+    if d == Complex::Zero {
+        return Err(String::from("d must be nonzero"));
+    }
+
+    Mobius::from_fixed_points_and_multiplier(FixedPoints::Single(p), d)
+}
+
 /// Compute a Mobius transform that fixes the upper half plane.
 /// It also separately fixes the extended real line, as it does
 /// the lower half plane.
@@ -106,16 +155,9 @@ pub fn cayley_map() -> Mobius {
     // so we must divide by sqrt(2i).
     // This has angle pi/4 and radius sqrt(2)
     // which is sqrt(2) (sqrt(2) / 2 + i * sqrt(2) / 2)
-    // which is (1 + i);
-    let divisor = Complex::new(1.0, 1.0);
-
-    Mobius::new(
-        Complex::ONE / divisor,
-        -Complex::I / divisor,
-        Complex::ONE / divisor,
-        Complex::I / divisor,
-    )
-    .unwrap()
+    // which is (1 + i). The entries are exact, so this is stored as
+    // a constant.
+    Mobius::CAYLEY_MAP
 }
 
 /// Create a map that preserves the unit circle
@@ -150,26 +192,25 @@ pub fn map_to_zero_one_inf(triple: PointTriple) -> Result<Mobius, String> {
         return Err(String::from("points must be distinct"));
     }
 
-    if p == Complex::Infinity || q == Complex::Infinity || r == Complex::Infinity {
-        panic!("not yet implemented: handle infinity gracefully")
-    }
-
-    let rq = q - r;
-    let pq = q - p;
-
-    let a = rq;
-    let b = rq * -p;
-    let c = pq;
-    let d = pq * -r;
-
-    let inv_sqr_det = (a * d - b * c).inverse().sqrt();
-
-    Mobius::new(
-        a * inv_sqr_det,
-        b * inv_sqr_det,
-        c * inv_sqr_det,
-        d * inv_sqr_det,
-    )
+    // If one of the points is infinity, the factors that contain it cancel
+    let (a, b, c, d) = if p == Complex::Infinity {
+        // S(z) = (q - r) / (z - r)
+        (Complex::Zero, q - r, Complex::ONE, -r)
+    } else if q == Complex::Infinity {
+        // S(z) = (z - p) / (z - r)
+        (Complex::ONE, -p, Complex::ONE, -r)
+    } else if r == Complex::Infinity {
+        // S(z) = (z - p) / (q - p)
+        (Complex::ONE, -p, Complex::Zero, q - p)
+    } else {
+        let rq = q - r;
+        let pq = q - p;
+        (rq, rq * -p, pq, pq * -r)
+    };
+
+    // Normalizing here rather than with Mobius::new() avoids rejecting
+    // the map when rounding leaves the determinant slightly off 1
+    Mobius::from_unnormalized(a, b, c, d)
 }
 
 pub fn map_triple(input: PointTriple, output: PointTriple) -> Result<Mobius, String> {
@@ -180,6 +221,110 @@ pub fn map_triple(input: PointTriple, output: PointTriple) -> Result<Mobius, Str
     Ok(output_to_standard.inverse() * input_to_standard)
 }
 
+type ClineTriple = (Cline, Cline, Cline);
+
+/// How far apart the normalized coefficients of an image cline and the
+/// requested cline can be in map_cline_triple()
+pub const CLINE_TRIPLE_TOLERANCE: f64 = 1e-9;
+
+/// Intersection points of each consecutive pair of clines (0, 1), (1, 2)
+/// and (2, 0). Each cline passes through the points of both of its pairs
+fn pairwise_intersections(clines: ClineTriple) -> Result<[Vec<Complex>; 3], String> {
+    let (a, b, c) = clines;
+    let pairs = [
+        (a, b, "first and second"),
+        (b, c, "second and third"),
+        (c, a, "third and first"),
+    ];
+
+    let mut result: [Vec<Complex>; 3] = Default::default();
+    for (points, (x, y, label)) in result.iter_mut().zip(pairs) {
+        *points = x.intersection_points(&y).map_err(|e| e.to_string())?;
+        if points.is_empty() {
+            return Err(format!(
+                "the {} clines do not meet at an isolated point",
+                label
+            ));
+        }
+    }
+
+    Ok(result)
+}
+
+/// Every way to pick one intersection point per pair where the three
+/// points are distinct
+fn point_triples(intersections: &[Vec<Complex>; 3]) -> Vec<PointTriple> {
+    let [ab, bc, ca] = intersections;
+    let mut result = Vec::new();
+    for &p in ab {
+        for &q in bc {
+            for &r in ca {
+                if p != q && q != r && r != p {
+                    result.push((p, q, r));
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Find an isogonal map that takes three clines to three others, in order.
+///
+/// Each pair of input clines must meet. A map that works sends the
+/// intersection points of each input pair to intersection points of the
+/// matching output pair, so this tries every way of matching up one point
+/// per pair with map_triple(), both with and without a reflection, and
+/// returns the first map that takes each input cline to its output cline.
+///
+/// This returns an error if some pair of clines doesn't meet, if the input
+/// clines all pass through the same two points (so they don't determine a
+/// map), or if the configurations aren't equivalent, for example when the
+/// angles between the clines don't match.
+pub fn map_cline_triple(input: ClineTriple, output: ClineTriple) -> Result<Isogonal, String> {
+    let input_triples = point_triples(&pairwise_intersections(input)?);
+    let Some(&(p, q, r)) = input_triples.first() else {
+        return Err(String::from(
+            "input clines meet at too few points to determine a map",
+        ));
+    };
+    let output_triples = point_triples(&pairwise_intersections(output)?);
+
+    let (a, b, c) = output;
+    let maps_clines = |xform: Isogonal| {
+        let (x, y, z) = input;
+        [(x, a), (y, b), (z, c)].iter().all(|(from, to)| {
+            from.transform(xform)
+                .is_same_cline(to, CLINE_TRIPLE_TOLERANCE)
+        })
+    };
+
+    // A candidate can fail to build, e.g. when rounding error in the
+    // intersection points throws off the determinant. That only rules out
+    // the candidate, not the whole search
+    let mut last_error = None;
+    for &target in output_triples.iter() {
+        let reflected = (p.conj(), q.conj(), r.conj());
+        let candidates = [
+            map_triple((p, q, r), target).map(Isogonal::Conformal),
+            map_triple(reflected, target).map(Isogonal::AntiConformal),
+        ];
+
+        for candidate in candidates {
+            match candidate {
+                Ok(xform) if maps_clines(xform) => return Ok(xform),
+                Ok(_) => {}
+                Err(error) => last_error = Some(error),
+            }
+        }
+    }
+
+    let message = "no Mobius or anti-Mobius map takes the input clines to the output clines";
+    Err(match last_error {
+        Some(error) => format!("{} (a candidate map failed: {})", message, error),
+        None => String::from(message),
+    })
+}
+
 /// Based on Indra's Pearls Chapter 7
 pub fn gasket_group() -> (Mobius, Mobius) {
     // Parabolic transformation with
@@ -208,15 +353,79 @@ pub fn gasket_strip() -> (Mobius, Mobius) {
     (a, b)
 }
 
+/// Grandma's recipe from Indra's Pearls Chapter 6 (Box 20). Given the
+/// traces of the two generators (and optionally of their product ab),
+/// build generators a, b with those traces such that the commutator abAB
+/// is parabolic with trace -2. Many of the limit sets in the book come
+/// from picking the traces and running this recipe.
+///
+/// If tab is None, it is the root of
+///
+/// tab^2 - ta tb tab + ta^2 + tb^2 = 0
+///
+/// with the minus sign, which is the choice the book usually makes.
+pub fn grandma_recipe(
+    ta: Complex,
+    tb: Complex,
+    tab: Option<Complex>,
+) -> Result<(Mobius, Mobius), String> {
+    let two: Complex = 2.0.into();
+    let four: Complex = 4.0.into();
+    let tab = tab.unwrap_or_else(|| {
+        let discriminant = ta * ta * tb * tb - four * (ta * ta + tb * tb);
+        let (minus, plus) = (ta * tb - discriminant.sqrt(), ta * tb + discriminant.sqrt());
+        // For large traces the minus root cancels, but the roots multiply
+        // to ta^2 + tb^2 so it can be found from the other one
+        if minus.mag() < plus.mag() {
+            two * (ta * ta + tb * tb) / plus
+        } else {
+            minus / two
+        }
+    });
+
+    let z0_denominator = tb * tab - two * ta + two * Complex::I * tab;
+    let z0 = (tab - two) * tb / z0_denominator;
+    if z0_denominator == Complex::Zero || z0 == Complex::Zero || tab == two || tab == -two {
+        return Err(format!(
+            "traces ({}, {}, {}) do not give a valid group",
+            ta, tb, tab
+        ));
+    }
+
+    // The determinants are 1 in exact arithmetic, but rounding error grows
+    // with the size of the traces, so normalize instead of checking
+    let a = Mobius::from_unnormalized(
+        ta / two,
+        (ta * tab - two * tb + four * Complex::I) / ((two * tab + four) * z0),
+        (ta * tab - two * tb - four * Complex::I) * z0 / (two * tab - four),
+        ta / two,
+    )?;
+    let b = Mobius::from_unnormalized(
+        (tb - two * Complex::I) / two,
+        tb / two,
+        tb / two,
+        (tb + two * Complex::I) / two,
+    )?;
+
+    Ok((a, b))
+}
+
 #[cfg(test)]
 mod test {
 
     use core::f64;
 
-    use abstraction::Monoid;
+    use abstraction::{find_isomorphism, FiniteGroup, FreeProduct, Group, Integer, Monoid};
+    use permutations::Permutation;
     use test_case::test_case;
 
-    use crate::{mobius::MobiusType, nearly::is_nearly};
+    use crate::{
+        geometry::{Circle, Line},
+        mobius::MobiusType,
+        nearly::is_nearly,
+        random::SplitMix64,
+        unit_complex::UnitComplex,
+    };
 
     use super::*;
 
@@ -303,6 +512,73 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    pub fn elliptic_about_rotates_around_fixed_points() -> Result<(), String> {
+        let p = Complex::new(1.0, 1.0);
+        let q = Complex::new(-2.0, 0.5);
+        let xform = elliptic_about(p, q, f64::consts::FRAC_PI_2)?;
+
+        assert_eq!(xform.classify(), MobiusType::Elliptic);
+        assert_eq!(xform * p, p);
+        assert_eq!(xform * q, q);
+        // Four quarter turns bring points back
+        let z = Complex::new(0.5, -0.25);
+        let result = xform * (xform * (xform * (xform * z)));
+        assert!((result - z).mag() < 1e-10);
+        Ok(())
+    }
+
+    #[test_case(0.0; "zero")]
+    #[test_case(f64::consts::TAU; "full turn")]
+    #[test_case(-2.0 * f64::consts::TAU; "two turns clockwise")]
+    pub fn elliptic_about_with_full_turns_returns_error(theta: f64) {
+        let result = elliptic_about(Complex::Zero, Complex::ONE, theta);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn hyperbolic_about_with_unit_multiplier_returns_error() {
+        let result = hyperbolic_about(Complex::Zero, Complex::ONE, 1.0);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn hyperbolic_about_flows_towards_sink() -> Result<(), String> {
+        let p = Complex::new(1.0, 1.0);
+        let xform = hyperbolic_about(p, Complex::Infinity, 0.5)?;
+
+        assert_eq!(xform.classify(), MobiusType::Hyperbolic);
+        let z = Complex::new(3.0, -1.0);
+        let result = xform * z;
+        // The distance to the sink halves
+        assert!(((result - p).mag() - 0.5 * (z - p).mag()).abs() < 1e-10);
+        Ok(())
+    }
+
+    #[test]
+    pub fn parabolic_about_fixes_point() -> Result<(), String> {
+        let p = Complex::new(2.0, -1.0);
+        let xform = parabolic_about(p, Complex::I)?;
+
+        assert_eq!(xform.classify(), MobiusType::Parabolic);
+        assert_eq!(xform * p, p);
+        assert_ne!(xform, Mobius::IDENTITY);
+        Ok(())
+    }
+
+    #[test_case(elliptic_about(Complex::ONE, Complex::ONE, 1.0); "elliptic repeated point")]
+    #[test_case(elliptic_about(Complex::ONE, Complex::I, f64::NAN); "elliptic bad angle")]
+    #[test_case(hyperbolic_about(Complex::ONE, Complex::I, -2.0); "hyperbolic negative k")]
+    #[test_case(hyperbolic_about(Complex::ONE, Complex::I, 0.0); "hyperbolic zero k")]
+    #[test_case(hyperbolic_about(Complex::Infinity, Complex::Infinity, 2.0); "hyperbolic both infinite")]
+    #[test_case(parabolic_about(Complex::ONE, Complex::Zero); "parabolic zero displacement")]
+    #[test_case(parabolic_about(Complex::ONE, Complex::Infinity); "parabolic infinite displacement")]
+    pub fn about_recipes_with_degenerate_input_return_error(result: Result<Mobius, String>) {
+        assert!(result.is_err());
+    }
+
     #[test]
     pub fn upper_half_plane_fixes_upper_half_plane() -> Result<(), String> {
         let upper_point = Complex::new(-2.0, 3.0);
@@ -345,6 +621,29 @@ mod test {
         assert_eq!(result, Complex::ONE);
     }
 
+    #[test]
+    pub fn cayley_map_matches_normalized_matrix() {
+        let divisor = Complex::new(1.0, 1.0);
+        let expected = Mobius::new(
+            Complex::ONE / divisor,
+            -Complex::I / divisor,
+            Complex::ONE / divisor,
+            Complex::I / divisor,
+        )
+        .unwrap();
+
+        let result = cayley_map();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn point_reflection_matches_half_turn() {
+        let half_turn = rotation(f64::consts::PI).unwrap();
+
+        assert_eq!(Mobius::POINT_REFLECTION, half_turn);
+    }
+
     #[test]
     pub fn cayley_map_has_order_3() {
         let k = cayley_map();
@@ -407,6 +706,9 @@ mod test {
 
     #[test_case((Complex::Zero, Complex::ONE, (2.0).into()) ; "colinear points")]
     #[test_case((Complex::Zero, (2.0).into(), Complex::I) ; "arbitrary points")]
+    #[test_case((Complex::Infinity, (2.0).into(), Complex::I) ; "first point infinite")]
+    #[test_case((Complex::Zero, Complex::Infinity, Complex::I) ; "second point infinite")]
+    #[test_case((Complex::Zero, (2.0).into(), Complex::Infinity) ; "third point infinite")]
     pub fn map_to_zero_one_inf_maps_respective_points(
         triple: (Complex, Complex, Complex),
     ) -> Result<(), String> {
@@ -443,4 +745,202 @@ mod test {
         assert_eq!(r2, w);
         Ok(())
     }
+
+    #[test]
+    pub fn elliptic_quarter_turns_generate_octahedral_group() {
+        // Two quarter turns about axes 90 degrees apart generate the
+        // rotation group of the octahedron, which is isomorphic to S_4
+        let swirl = elliptic(f64::consts::PI / 2.0).unwrap();
+        let rotate90 = rotation(f64::consts::PI / 2.0).unwrap();
+        let swirl2 = Mobius::sandwich(rotate90, swirl);
+        let s4 = FiniteGroup::from_generators(&[
+            Permutation::new([1, 0, 2, 3]).unwrap(),
+            Permutation::new([1, 2, 3, 0]).unwrap(),
+        ]);
+
+        let result = find_isomorphism(&[swirl, swirl2], &s4);
+
+        assert!(result.is_some());
+    }
+
+    #[test]
+    pub fn gasket_group_realizes_free_product_words() {
+        // Words in Z * Z evaluate to Mobius transformations by sending
+        // the generators of each factor to a and b
+        let (a, b) = gasket_group();
+        let x = FreeProduct::<Integer, Integer>::left(Integer(1));
+        let y = FreeProduct::right(Integer(1));
+        let word = FreeProduct::commutator(x, y) * FreeProduct::left(Integer(2));
+
+        let result = word.evaluate(|n| Group::pow(&a, n.0), |n| Group::pow(&b, n.0));
+
+        let expected = Mobius::commutator(a, b) * a * a;
+        assert_eq!(result, expected);
+    }
+
+    #[test_case(Complex::new(2.0, 0.0), Complex::new(2.0, 0.0); "gasket")]
+    #[test_case(Complex::new(1.91, 0.05), Complex::new(3.0, 0.0); "spiral")]
+    #[test_case(Complex::new(1.87, 0.1), Complex::new(1.87, -0.1); "conjugate traces")]
+    pub fn grandma_recipe_has_requested_traces(ta: Complex, tb: Complex) {
+        let (a, b) = grandma_recipe(ta, tb, None).unwrap();
+
+        assert_eq!(a.trace(), ta);
+        assert_eq!(b.trace(), tb);
+        // The commutator is parabolic with trace -2
+        let commutator = Mobius::commutator(a, b);
+        assert!((commutator.trace() + Complex::new(2.0, 0.0)).mag() < 1e-10);
+    }
+
+    #[test]
+    pub fn grandma_recipe_accepts_random_traces() {
+        let mut rng = SplitMix64::new(2300);
+        for _ in 0..100 {
+            let mut trace =
+                || Complex::new(1.8 + 20.0 * rng.next_f64(), 10.0 * rng.next_f64() - 5.0);
+            let ta = trace();
+            let tb = trace();
+
+            let (a, b) = grandma_recipe(ta, tb, None).unwrap();
+
+            assert!((a.trace() - ta).mag() < 1e-10 * ta.mag());
+            assert!((b.trace() - tb).mag() < 1e-10 * tb.mag());
+        }
+    }
+
+    #[test]
+    pub fn grandma_recipe_uses_given_tab() {
+        let ta = Complex::new(2.0, 0.0);
+        let tb = Complex::new(2.0, 0.0);
+        let tab = Complex::new(2.0, 2.0);
+
+        let (a, b) = grandma_recipe(ta, tb, Some(tab)).unwrap();
+
+        assert_eq!((a * b).trace(), tab);
+    }
+
+    fn triangle_sides(a: Complex, b: Complex, c: Complex) -> ClineTriple {
+        let side = |p, q| Cline::from(Line::from_points(p, q).unwrap());
+        (side(a, b), side(b, c), side(c, a))
+    }
+
+    fn assert_maps_clines(xform: Isogonal, input: ClineTriple, output: ClineTriple) {
+        let (a, b, c) = input;
+        let (x, y, z) = output;
+        for (from, to) in [(a, x), (b, y), (c, z)] {
+            assert!(from
+                .transform(xform)
+                .is_same_cline(&to, CLINE_TRIPLE_TOLERANCE));
+        }
+    }
+
+    #[test]
+    pub fn map_cline_triple_maps_triangle_to_circle_triangle() -> Result<(), String> {
+        let input = triangle_sides(Complex::Zero, Complex::ONE, Complex::new(0.2, 0.9));
+        let xform = Mobius::from_unnormalized(
+            Complex::new(1.0, 0.5),
+            Complex::I,
+            Complex::new(0.5, -0.25),
+            Complex::new(2.0, 0.0),
+        )?;
+        let (a, b, c) = input;
+        let output = (a.transform(xform), b.transform(xform), c.transform(xform));
+
+        let result = map_cline_triple(input, output)?;
+
+        assert!(matches!(result, Isogonal::Conformal(_)));
+        assert_maps_clines(result, input, output);
+        Ok(())
+    }
+
+    #[test]
+    pub fn map_cline_triple_maps_tangent_circles() -> Result<(), String> {
+        let input: ClineTriple = (
+            Circle::new(Complex::new(-1.0, 0.0), 1.0).into(),
+            Circle::new(Complex::new(1.0, 0.0), 1.0).into(),
+            Circle::new(Complex::new(0.0, 3.0f64.sqrt()), 1.0).into(),
+        );
+        // Sending one tangency point to infinity turns two of the circles
+        // into parallel lines
+        let output: ClineTriple = (
+            Line::new(UnitComplex::I, 1.0).unwrap().into(),
+            Line::new(UnitComplex::I, -1.0).unwrap().into(),
+            Circle::new(Complex::Zero, 1.0).into(),
+        );
+
+        let result = map_cline_triple(input, output)?;
+
+        assert_maps_clines(result, input, output);
+        Ok(())
+    }
+
+    #[test]
+    pub fn map_cline_triple_maps_random_mobius_images() -> Result<(), String> {
+        let input = triangle_sides(Complex::Zero, Complex::ONE, Complex::new(0.2, 0.9));
+        let (a, b, c) = input;
+        let mut rng = SplitMix64::new(2322);
+        let mut random_complex =
+            || Complex::new(4.0 * rng.next_f64() - 2.0, 4.0 * rng.next_f64() - 2.0);
+
+        for _ in 0..100 {
+            let Ok(xform) = Mobius::from_unnormalized(
+                random_complex(),
+                random_complex(),
+                random_complex(),
+                random_complex(),
+            ) else {
+                continue;
+            };
+            let output = (a.transform(xform), b.transform(xform), c.transform(xform));
+
+            let result = map_cline_triple(input, output)?;
+
+            assert_maps_clines(result, input, output);
+        }
+        Ok(())
+    }
+
+    #[test]
+    pub fn map_cline_triple_uses_reflection_for_mirror_image() -> Result<(), String> {
+        let input = triangle_sides(Complex::Zero, Complex::ONE, Complex::new(0.2, 0.9));
+        let (a, b, c) = input;
+        let mirror = Isogonal::conj();
+        let output = (
+            a.transform(mirror),
+            b.transform(mirror),
+            c.transform(mirror),
+        );
+
+        let result = map_cline_triple(input, output)?;
+
+        assert!(matches!(result, Isogonal::AntiConformal(_)));
+        assert_maps_clines(result, input, output);
+        Ok(())
+    }
+
+    #[test]
+    pub fn map_cline_triple_with_different_angles_returns_error() {
+        let equilateral = triangle_sides(
+            Complex::Zero,
+            Complex::ONE,
+            Complex::new(0.5, 0.75f64.sqrt()),
+        );
+        let right = triangle_sides(Complex::Zero, Complex::ONE, Complex::I);
+
+        let result = map_cline_triple(equilateral, right);
+
+        assert!(result.is_err_and(|e| e.contains("no Mobius")));
+    }
+
+    #[test]
+    pub fn map_cline_triple_with_disjoint_clines_returns_error() {
+        let input: ClineTriple = (
+            Cline::unit_circle(),
+            Circle::new(Complex::new(5.0, 0.0), 1.0).into(),
+            Cline::real_axis(),
+        );
+
+        let result = map_cline_triple(input, input);
+
+        assert!(result.is_err_and(|e| e.contains("first and second")));
+    }
 }
@@ -0,0 +1,181 @@
+use crate::{
+    algorithms::{hyperbolic_tile_area, tile_area},
+    transformable::ClineArcTile,
+};
+
+use super::{ColorRGB, Palette, Style};
+
+/// How to measure the area of a tile
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AreaMeasure {
+    Euclidean,
+    /// Area in the Poincaré disk model, tiles must be inside the unit disk
+    Hyperbolic,
+}
+
+/// Fill color for each tile based on its area. Areas are normalized over
+/// the tiles being shaded, so the smallest tile gets the start of the
+/// palette and the largest gets the end.
+///
+/// Euclidean areas of a hyperbolic tiling shrink exponentially towards
+/// the boundary of the disk, so a log scale makes the fade even.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AreaShading {
+    pub measure: AreaMeasure,
+    pub palette: Palette,
+    pub log_scale: bool,
+}
+
+impl AreaShading {
+    pub fn new(measure: AreaMeasure, palette: Palette) -> Self {
+        Self {
+            measure,
+            palette,
+            log_scale: false,
+        }
+    }
+
+    pub fn with_log_scale(&self) -> Self {
+        Self {
+            log_scale: true,
+            ..self.clone()
+        }
+    }
+
+    pub fn area(&self, tile: &ClineArcTile) -> Result<f64, String> {
+        match self.measure {
+            AreaMeasure::Euclidean => tile_area(tile),
+            AreaMeasure::Hyperbolic => hyperbolic_tile_area(tile),
+        }
+    }
+
+    /// Compute one color per tile
+    pub fn colors(&self, tiles: &[ClineArcTile]) -> Result<Vec<ColorRGB>, String> {
+        let values = tiles
+            .iter()
+            .map(|tile| {
+                let area = self.area(tile)?;
+                if !self.log_scale {
+                    Ok(area)
+                } else if area > 0.0 {
+                    Ok(area.ln())
+                } else {
+                    Err(String::from("log scale needs tiles with positive area"))
+                }
+            })
+            .collect::<Result<Vec<f64>, String>>()?;
+
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let range = max - min;
+
+        Ok(values
+            .iter()
+            .map(|value| {
+                let t = if range > 0.0 {
+                    (value - min) / range
+                } else {
+                    0.0
+                };
+                self.palette.sample(t)
+            })
+            .collect())
+    }
+
+    /// Copy a base style once per tile, filling each with its area color
+    pub fn styles(&self, tiles: &[ClineArcTile], base: Style) -> Result<Vec<Style>, String> {
+        Ok(self
+            .colors(tiles)?
+            .into_iter()
+            .map(|color| Style {
+                fill: Some(color),
+                ..base
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        geometry::LineSegment, hyperbolic_tilings::get_fundamental_region, isogonal::Isogonal,
+        transformable::Transformable, Complex,
+    };
+
+    use super::*;
+
+    const BLACK: ColorRGB = ColorRGB(0, 0, 0);
+    const WHITE: ColorRGB = ColorRGB(255, 255, 255);
+
+    fn palette() -> Palette {
+        Palette::new(vec![BLACK, WHITE]).unwrap()
+    }
+
+    fn square(size: f64) -> ClineArcTile {
+        let corners = [
+            Complex::Zero,
+            Complex::new(size, 0.0),
+            Complex::new(size, size),
+            Complex::new(0.0, size),
+        ];
+        ClineArcTile::new(
+            (0..4)
+                .map(|i| LineSegment::new(corners[i], corners[(i + 1) % 4]).into())
+                .collect(),
+        )
+    }
+
+    #[test]
+    pub fn colors_spans_palette() {
+        let shading = AreaShading::new(AreaMeasure::Euclidean, palette());
+        let tiles = [square(1.0), square(2.0), square(3.0)];
+
+        let result = shading.colors(&tiles).unwrap();
+
+        assert_eq!(result[0], BLACK);
+        assert_eq!(result[2], WHITE);
+        // areas 1, 4, 9
+        assert_eq!(result[1], ColorRGB::lerp(BLACK, WHITE, 3.0 / 8.0));
+    }
+
+    #[test]
+    pub fn colors_with_log_scale_evens_out_geometric_areas() {
+        let shading = AreaShading::new(AreaMeasure::Euclidean, palette()).with_log_scale();
+        // areas 1, 4, 16
+        let tiles = [square(1.0), square(2.0), square(4.0)];
+
+        let result = shading.colors(&tiles).unwrap();
+
+        assert_eq!(result[1], ColorRGB::lerp(BLACK, WHITE, 0.5));
+    }
+
+    #[test]
+    pub fn styles_keeps_base_stroke() {
+        let shading = AreaShading::new(AreaMeasure::Euclidean, palette());
+        let tiles = [square(1.0), square(2.0)];
+        let base = Style::stroke(255, 0, 0).with_width(0.5);
+
+        let result = shading.styles(&tiles, base).unwrap();
+
+        assert_eq!(result[0].stroke, base.stroke);
+        assert_eq!(result[0].fill, Some(BLACK));
+        assert_eq!(result[1].fill, Some(WHITE));
+    }
+
+    #[test]
+    pub fn hyperbolic_shading_is_uniform_for_congruent_tiles() {
+        let shading = AreaShading::new(AreaMeasure::Hyperbolic, palette());
+        let (tile, _) = get_fundamental_region(5, 4).unwrap();
+        let (reflection, _, _) = crate::hyperbolic_tilings::reflection_group(5, 4).unwrap();
+        let xform: Isogonal = reflection;
+        let tiles = [tile.clone(), tile.transform(xform)];
+
+        let euclidean = AreaShading::new(AreaMeasure::Euclidean, palette())
+            .area(&tiles[1])
+            .unwrap();
+        let result = shading.colors(&tiles).unwrap();
+
+        assert!(euclidean > 0.0);
+        assert_eq!(result[0], result[1]);
+    }
+}
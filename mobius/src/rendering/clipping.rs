@@ -0,0 +1,500 @@
+//! Geometric clipping of baked primitives to a region, so deep IFS
+//! renders don't write geometry that is never visible. Curves are split
+//! where they cross the boundary of the region, and only the pieces
+//! inside are kept. This is only correct for strokes: a clipped circle
+//! becomes an open arc, so filled shapes should be culled instead, see
+//! svg_plot::render_scene_clipped().
+use std::f64::consts::TAU;
+
+use crate::{
+    geometry::{
+        intersect_circle_line, intersect_circles, intersect_lines, ArcAngles, Circle, CircularArc,
+        CubicBezier, Intersection, Line, LineSegment, QuadraticBezier,
+    },
+    interpolation::lerp,
+    unit_complex::UnitComplex,
+    Complex,
+};
+
+use super::{PrimitiveLayer, RenderPrimitive};
+
+/// Pieces shorter than this fraction of the curve are dropped
+const MIN_PIECE: f64 = 1e-9;
+
+/// Region of the plane to keep
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClipRegion {
+    /// Axis-aligned rectangle, e.g. the visible part of a view
+    Rectangle {
+        x_min: f64,
+        y_min: f64,
+        x_max: f64,
+        y_max: f64,
+    },
+    /// Closed disk
+    Circle(Circle),
+}
+
+impl ClipRegion {
+    pub fn rectangle(center: Complex, half_width: f64, half_height: f64) -> Result<Self, String> {
+        let valid = |x: f64| x > 0.0 && x.is_finite();
+        if center == Complex::Infinity || !valid(half_width) || !valid(half_height) {
+            return Err(format!(
+                "clip rectangle must be finite with positive size, got {} x {} at {}",
+                half_width, half_height, center
+            ));
+        }
+
+        Ok(Self::Rectangle {
+            x_min: center.real() - half_width,
+            y_min: center.imag() - half_height,
+            x_max: center.real() + half_width,
+            y_max: center.imag() + half_height,
+        })
+    }
+
+    pub fn circle(circle: Circle) -> Result<Self, String> {
+        let Circle { center, radius } = circle;
+        if center == Complex::Infinity || !(radius > 0.0 && radius.is_finite()) {
+            return Err(format!("clip circle must be finite, got {}", circle));
+        }
+
+        Ok(Self::Circle(circle))
+    }
+
+    /// Check if a point is inside the region or on its boundary
+    pub fn contains(&self, z: Complex) -> bool {
+        if z == Complex::Infinity {
+            return false;
+        }
+
+        match *self {
+            Self::Rectangle {
+                x_min,
+                y_min,
+                x_max,
+                y_max,
+            } => (x_min..=x_max).contains(&z.real()) && (y_min..=y_max).contains(&z.imag()),
+            Self::Circle(Circle { center, radius }) => (z - center).mag() <= radius,
+        }
+    }
+
+    /// Check if a disk overlaps the region, see rendering::culling
+    pub fn intersects_circle(&self, circle: Circle) -> bool {
+        let Circle { center, radius } = circle;
+        if center == Complex::Infinity {
+            return false;
+        }
+
+        match *self {
+            Self::Rectangle {
+                x_min,
+                y_min,
+                x_max,
+                y_max,
+            } => {
+                let nearest = Complex::new(
+                    center.real().clamp(x_min, x_max),
+                    center.imag().clamp(y_min, y_max),
+                );
+                (center - nearest).mag() <= radius
+            }
+            Self::Circle(boundary) => (center - boundary.center).mag() <= radius + boundary.radius,
+        }
+    }
+
+    /// Intersection of a circle or line with each edge of the boundary.
+    /// The edges of a rectangle are extended to full lines, so some of
+    /// the points may be outside the region
+    fn boundary_intersections(
+        &self,
+        intersect_circle: impl Fn(Circle) -> Intersection,
+        intersect_line: impl Fn(Line) -> Intersection,
+    ) -> Vec<Complex> {
+        match *self {
+            Self::Rectangle {
+                x_min,
+                y_min,
+                x_max,
+                y_max,
+            } => {
+                let vertical = [x_min, x_max].map(|x| (UnitComplex::ONE, x));
+                let horizontal = [y_min, y_max].map(|y| (UnitComplex::I, y));
+                vertical
+                    .into_iter()
+                    .chain(horizontal)
+                    .filter_map(|(normal, distance)| Line::new(normal, distance).ok())
+                    .flat_map(|edge| intersect_line(edge).points())
+                    .collect()
+            }
+            Self::Circle(boundary) => intersect_circle(boundary).points(),
+        }
+    }
+
+    /// Angles where a circle crosses the boundary of the region
+    fn circle_crossings(&self, circle: Circle) -> Vec<f64> {
+        self.boundary_intersections(
+            |boundary| intersect_circles(circle, boundary),
+            |edge| intersect_circle_line(circle, edge),
+        )
+        .into_iter()
+        .map(|z| (z - circle.center).arg_or_zero())
+        .collect()
+    }
+
+    /// Parameters t in start + t * (end - start) where a line crosses
+    /// the boundary of the region
+    fn segment_crossings(&self, segment: LineSegment) -> Vec<f64> {
+        let LineSegment { start, end } = segment;
+        let Ok(line) = Line::from_points(start, end) else {
+            return vec![];
+        };
+
+        let direction = end - start;
+        self.boundary_intersections(
+            |boundary| intersect_circle_line(boundary, line),
+            |edge| intersect_lines(line, edge),
+        )
+        .into_iter()
+        .map(|z| Complex::dot(z - start, direction) / direction.norm())
+        .collect()
+    }
+}
+
+/// Split [0, 1] at the crossings and return the pieces whose midpoint is
+/// inside. Neighboring pieces are merged, so a curve that only touches
+/// the boundary stays in one piece
+fn inside_pieces(crossings: Vec<f64>, is_inside: impl Fn(f64) -> bool) -> Vec<(f64, f64)> {
+    let mut cuts: Vec<f64> = crossings
+        .into_iter()
+        .filter(|t| *t > 0.0 && *t < 1.0)
+        .collect();
+    cuts.push(0.0);
+    cuts.push(1.0);
+    cuts.sort_by(f64::total_cmp);
+
+    let mut pieces: Vec<(f64, f64)> = Vec::new();
+    for pair in cuts.windows(2) {
+        let (t0, t1) = (pair[0], pair[1]);
+        if t1 - t0 <= MIN_PIECE || !is_inside(0.5 * (t0 + t1)) {
+            continue;
+        }
+
+        match pieces.last_mut() {
+            Some(last) if last.1 == t0 => last.1 = t1,
+            _ => pieces.push((t0, t1)),
+        }
+    }
+    pieces
+}
+
+fn clip_segment(segment: LineSegment, region: &ClipRegion, output: &mut Vec<RenderPrimitive>) {
+    let LineSegment { start, end } = segment;
+    if start == Complex::Infinity || end == Complex::Infinity {
+        return;
+    }
+
+    let point = |t: f64| start + (end - start) * t.into();
+    let pieces = inside_pieces(region.segment_crossings(segment), |t| {
+        region.contains(point(t))
+    });
+    output.extend(pieces.into_iter().map(|(t0, t1)| {
+        RenderPrimitive::LineSegment(LineSegment {
+            start: point(t0),
+            end: point(t1),
+        })
+    }));
+}
+
+/// Parameters along an arc from a to b where it passes the given angles
+fn arc_params(crossings: Vec<f64>, a: f64, b: f64) -> Vec<f64> {
+    crossings
+        .into_iter()
+        .flat_map(|theta| (-2..=2).map(move |k| (theta + k as f64 * TAU - a) / (b - a)))
+        .collect()
+}
+
+fn clip_arc(arc: CircularArc, region: &ClipRegion, output: &mut Vec<RenderPrimitive>) {
+    let CircularArc { circle, angles } = arc;
+    let ArcAngles(a, b) = angles;
+    let crossings = arc_params(region.circle_crossings(circle), a, b);
+    let pieces = inside_pieces(crossings, |t| region.contains(arc.interpolate(t)));
+
+    if pieces == [(0.0, 1.0)] {
+        output.push(RenderPrimitive::CircularArc(arc));
+        return;
+    }
+
+    for (t0, t1) in pieces {
+        if let Ok(angles) = ArcAngles::new(lerp(a, b, t0), lerp(a, b, t1)) {
+            output.push(RenderPrimitive::CircularArc(CircularArc::new(
+                circle, angles,
+            )));
+        }
+    }
+}
+
+fn clip_circle(circle: Circle, region: &ClipRegion, output: &mut Vec<RenderPrimitive>) {
+    let crossings = arc_params(region.circle_crossings(circle), 0.0, TAU);
+    let mut pieces = inside_pieces(crossings, |t| region.contains(circle.get_point(t * TAU)));
+
+    if pieces == [(0.0, 1.0)] {
+        output.push(RenderPrimitive::Circle(circle));
+        return;
+    }
+
+    // A piece that ends at 1.0 continues into the piece at 0.0
+    if pieces.len() > 1 && pieces[0].0 == 0.0 && pieces[pieces.len() - 1].1 == 1.0 {
+        let (_, first_end) = pieces.remove(0);
+        if let Some(last) = pieces.last_mut() {
+            last.1 = 1.0 + first_end;
+        }
+    }
+
+    for (t0, t1) in pieces {
+        if let Ok(angles) = ArcAngles::new(t0 * TAU, t1 * TAU) {
+            output.push(RenderPrimitive::CircularArc(CircularArc::new(
+                circle, angles,
+            )));
+        }
+    }
+}
+
+/// Parameters in [0, 1] where a curve crosses the boundary of the region.
+/// Bezier curves have no closed form for this, so the curve is sampled
+/// and each change from inside to outside is refined by bisection
+fn sampled_crossings(point: impl Fn(f64) -> Complex, region: &ClipRegion) -> Vec<f64> {
+    const SAMPLES: usize = 32;
+    const BISECTION_STEPS: usize = 40;
+
+    let inside = |t: f64| region.contains(point(t));
+    let mut crossings = Vec::new();
+    for i in 0..SAMPLES {
+        let (mut t0, mut t1) = (i as f64 / SAMPLES as f64, (i + 1) as f64 / SAMPLES as f64);
+        let start_inside = inside(t0);
+        if start_inside == inside(t1) {
+            continue;
+        }
+
+        for _ in 0..BISECTION_STEPS {
+            let middle = 0.5 * (t0 + t1);
+            if inside(middle) == start_inside {
+                t0 = middle;
+            } else {
+                t1 = middle;
+            }
+        }
+        crossings.push(0.5 * (t0 + t1));
+    }
+    crossings
+}
+
+fn clip_quadratic_bezier(
+    curve: QuadraticBezier,
+    region: &ClipRegion,
+    output: &mut Vec<RenderPrimitive>,
+) {
+    let crossings = sampled_crossings(|t| curve.interpolate(t), region);
+    let pieces = inside_pieces(crossings, |t| region.contains(curve.interpolate(t)));
+    output.extend(
+        pieces
+            .into_iter()
+            .map(|(t0, t1)| RenderPrimitive::QuadraticBezier(curve.sub_curve(t0, t1))),
+    );
+}
+
+fn clip_cubic_bezier(curve: CubicBezier, region: &ClipRegion, output: &mut Vec<RenderPrimitive>) {
+    let crossings = sampled_crossings(|t| curve.interpolate(t), region);
+    let pieces = inside_pieces(crossings, |t| region.contains(curve.interpolate(t)));
+    output.extend(
+        pieces
+            .into_iter()
+            .map(|(t0, t1)| RenderPrimitive::CubicBezier(curve.sub_curve(t0, t1))),
+    );
+}
+
+fn clip_primitive(
+    primitive: &RenderPrimitive,
+    region: &ClipRegion,
+    output: &mut Vec<RenderPrimitive>,
+) {
+    match primitive {
+        RenderPrimitive::Point(z) => {
+            if region.contains(*z) {
+                output.push(primitive.clone());
+            }
+        }
+        RenderPrimitive::Text { position, .. } => {
+            if region.contains(*position) {
+                output.push(primitive.clone());
+            }
+        }
+        RenderPrimitive::LineSegment(segment) => clip_segment(*segment, region, output),
+        RenderPrimitive::CircularArc(arc) => clip_arc(*arc, region, output),
+        RenderPrimitive::Circle(circle) => clip_circle(*circle, region, output),
+        RenderPrimitive::QuadraticBezier(curve) => clip_quadratic_bezier(*curve, region, output),
+        RenderPrimitive::CubicBezier(curve) => clip_cubic_bezier(*curve, region, output),
+        RenderPrimitive::Layer(PrimitiveLayer {
+            name,
+            z_index,
+            children,
+        }) => {
+            let children = clip_primitives(children, region);
+            if !children.is_empty() {
+                output.push(RenderPrimitive::layer(name, children, *z_index));
+            }
+        }
+    }
+}
+
+/// Clip primitives to a region. Points and text are kept if their position
+/// is inside, curves are cut into the pieces inside the region, and
+/// layers that end up empty are dropped
+pub fn clip_primitives(
+    primitives: &[RenderPrimitive],
+    region: &ClipRegion,
+) -> Vec<RenderPrimitive> {
+    let mut output = Vec::new();
+    for primitive in primitives {
+        clip_primitive(primitive, region, &mut output);
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    fn unit_square() -> ClipRegion {
+        ClipRegion::rectangle(Complex::Zero, 1.0, 1.0).unwrap()
+    }
+
+    #[test]
+    pub fn rectangle_with_invalid_size_returns_error() {
+        let result = ClipRegion::rectangle(Complex::Zero, 0.0, 1.0);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn clip_segment_keeps_inside_part() {
+        let segment = LineSegment {
+            start: Complex::new(-3.0, 0.5),
+            end: Complex::new(3.0, 0.5),
+        };
+
+        let result = clip_primitives(&[RenderPrimitive::LineSegment(segment)], &unit_square());
+
+        let [RenderPrimitive::LineSegment(LineSegment { start, end })] = result[..] else {
+            panic!("expected a single segment");
+        };
+        assert_eq!(start, Complex::new(-1.0, 0.5));
+        assert_eq!(end, Complex::new(1.0, 0.5));
+    }
+
+    #[test]
+    pub fn clip_cubic_bezier_keeps_inside_part() {
+        // Straight cubic with evenly spaced control points, so t is
+        // proportional to x
+        let curve = CubicBezier::new(
+            Complex::new(-3.0, 0.5),
+            Complex::new(-1.0, 0.5),
+            Complex::new(1.0, 0.5),
+            Complex::new(3.0, 0.5),
+        );
+
+        let result = clip_primitives(&[RenderPrimitive::CubicBezier(curve)], &unit_square());
+
+        let [RenderPrimitive::CubicBezier(clipped)] = result[..] else {
+            panic!("expected a single curve");
+        };
+        assert!((clipped.start - Complex::new(-1.0, 0.5)).mag() < 1e-9);
+        assert!((clipped.end - Complex::new(1.0, 0.5)).mag() < 1e-9);
+    }
+
+    #[test]
+    pub fn clip_segment_outside_is_dropped() {
+        let segment = LineSegment {
+            start: Complex::new(2.0, 2.0),
+            end: Complex::new(3.0, 5.0),
+        };
+
+        let result = clip_primitives(&[RenderPrimitive::LineSegment(segment)], &unit_square());
+
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    pub fn clip_circle_inside_is_unchanged() {
+        let circle = Circle::new(Complex::Zero, 0.5);
+
+        let result = clip_primitives(&[RenderPrimitive::Circle(circle)], &unit_square());
+
+        assert!(matches!(result[..], [RenderPrimitive::Circle(c)] if c == circle));
+    }
+
+    #[test]
+    pub fn clip_circle_around_square_is_dropped() {
+        let circle = Circle::new(Complex::Zero, 2.0);
+
+        let result = clip_primitives(&[RenderPrimitive::Circle(circle)], &unit_square());
+
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    pub fn clip_circle_crossing_edge_becomes_arc() {
+        // circle centered on the right edge, only the left half is inside
+        let circle = Circle::new(Complex::new(1.0, 0.0), 0.5);
+
+        let result = clip_primitives(&[RenderPrimitive::Circle(circle)], &unit_square());
+
+        let [RenderPrimitive::CircularArc(arc)] = result[..] else {
+            panic!("expected a single arc");
+        };
+        let ArcAngles(a, b) = arc.angles;
+        assert!((a - 0.5 * PI).abs() < 1e-12);
+        assert!((b - 1.5 * PI).abs() < 1e-12);
+    }
+
+    #[test]
+    pub fn clip_circle_wrapping_zero_is_one_arc() {
+        // circle centered on the left edge, the inside part crosses angle 0
+        let circle = Circle::new(Complex::new(-1.0, 0.0), 0.5);
+
+        let result = clip_primitives(&[RenderPrimitive::Circle(circle)], &unit_square());
+
+        let [RenderPrimitive::CircularArc(arc)] = result[..] else {
+            panic!("expected a single arc");
+        };
+        assert!((arc.angles.central_angle() - PI).abs() < 1e-12);
+        assert!(unit_square().contains(arc.interpolate(0.5)));
+    }
+
+    #[test]
+    pub fn clip_arc_to_circle_keeps_inside_part() {
+        let region = ClipRegion::circle(Circle::new(Complex::new(1.0, 0.0), 1.0)).unwrap();
+        let arc = CircularArc::new(Circle::unit_circle(), ArcAngles::new(0.0, PI).unwrap());
+
+        let result = clip_primitives(&[RenderPrimitive::CircularArc(arc)], &region);
+
+        let [RenderPrimitive::CircularArc(clipped)] = result[..] else {
+            panic!("expected a single arc");
+        };
+        let ArcAngles(a, b) = clipped.angles;
+        assert_eq!(a, 0.0);
+        assert!((b - PI / 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    pub fn clip_primitives_drops_empty_layers() {
+        let outside = RenderPrimitive::Point(Complex::new(5.0, 5.0));
+        let layer = RenderPrimitive::layer("far", vec![outside], 1);
+
+        let result = clip_primitives(&[layer], &unit_square());
+
+        assert!(result.is_empty());
+    }
+}
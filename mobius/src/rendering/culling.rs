@@ -0,0 +1,103 @@
+//! Culling drops whole primitives whose bounding circle (see
+//! RenderPrimitive::bounding_circle()) misses a region. Unlike clipping,
+//! nothing is cut, so it is cheap enough to run on huge scenes like
+//! wallpaper grids that extend far past the view. Culling is conservative:
+//! a primitive near the region might be kept even if none of it is
+//! visible, but nothing visible is dropped.
+use super::{ClipRegion, PrimitiveLayer, RenderPrimitive};
+
+/// Keep the primitives whose bounding circle overlaps the region. Layers
+/// are culled child by child, and layers that end up empty are dropped
+pub fn cull_primitives(
+    primitives: &[RenderPrimitive],
+    region: &ClipRegion,
+) -> Vec<RenderPrimitive> {
+    primitives
+        .iter()
+        .filter_map(|primitive| match primitive {
+            RenderPrimitive::Layer(PrimitiveLayer {
+                name,
+                z_index,
+                children,
+            }) => {
+                let children = cull_primitives(children, region);
+                (!children.is_empty()).then(|| RenderPrimitive::layer(name, children, *z_index))
+            }
+            _ => primitive
+                .bounding_circle()
+                .filter(|circle| region.intersects_circle(*circle))
+                .map(|_| primitive.clone()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        geometry::{Circle, LineSegment},
+        Complex,
+    };
+
+    use super::*;
+
+    fn unit_square() -> ClipRegion {
+        ClipRegion::rectangle(Complex::Zero, 1.0, 1.0).unwrap()
+    }
+
+    #[test]
+    pub fn cull_primitives_keeps_overlapping_primitives() {
+        let primitives = vec![
+            RenderPrimitive::Point(Complex::new(0.5, 0.5)),
+            RenderPrimitive::Circle(Circle::new(Complex::new(1.5, 0.0), 1.0)),
+            RenderPrimitive::LineSegment(LineSegment {
+                start: Complex::new(-5.0, 0.0),
+                end: Complex::new(5.0, 0.0),
+            }),
+        ];
+
+        let result = cull_primitives(&primitives, &unit_square());
+
+        assert_eq!(result.len(), 3);
+    }
+
+    #[test]
+    pub fn cull_primitives_drops_distant_primitives() {
+        let primitives = vec![
+            RenderPrimitive::Point(Complex::new(1.5, 0.0)),
+            // the bounding circle of the corner misses the square
+            RenderPrimitive::Circle(Circle::new(Complex::new(2.0, 2.0), 1.0)),
+            RenderPrimitive::layer(
+                "far",
+                vec![RenderPrimitive::Point(Complex::new(10.0, 10.0))],
+                0,
+            ),
+        ];
+
+        let result = cull_primitives(&primitives, &unit_square());
+
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    pub fn cull_primitives_keeps_partial_layers() {
+        let layer = RenderPrimitive::layer(
+            "mixed",
+            vec![
+                RenderPrimitive::Point(Complex::Zero),
+                RenderPrimitive::Point(Complex::new(10.0, 0.0)),
+            ],
+            3,
+        );
+
+        let result = cull_primitives(&[layer], &unit_square());
+
+        let [RenderPrimitive::Layer(PrimitiveLayer {
+            z_index, children, ..
+        })] = &result[..]
+        else {
+            panic!("expected a single layer");
+        };
+        assert_eq!(*z_index, 3);
+        assert_eq!(children.len(), 1);
+    }
+}
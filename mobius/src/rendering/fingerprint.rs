@@ -0,0 +1,197 @@
+use std::error::Error;
+
+use crate::{
+    algorithms::quantize_to,
+    geometry::{ArcDirection, DirectedEdge},
+    Complex,
+};
+
+use super::{RenderPrimitive, Renderable};
+
+/// Coordinates are rounded to this many units before hashing. This is
+/// coarse enough to absorb last-bit differences in libm implementations,
+/// but fine enough to catch real changes in the geometry.
+pub const FINGERPRINT_QUANTUM: f64 = 1e-6;
+
+fn quantize(x: f64) -> i64 {
+    quantize_to(x, FINGERPRINT_QUANTUM)
+}
+
+fn quantize_point(z: Complex, output: &mut Vec<i64>) {
+    output.push(quantize(z.real()));
+    output.push(quantize(z.imag()));
+}
+
+/// Quantize a primitive to a list of integers. The first entry is a tag
+/// for the type of primitive. Arcs are described by their endpoints rather
+/// than their angles so equivalent angle ranges (e.g. shifted by 2pi)
+/// quantize the same. Layers include their name, z_index and the sorted
+/// quantized children, so only the order between layers matters.
+pub fn quantize_primitive(primitive: &RenderPrimitive) -> Vec<i64> {
+    let mut result = Vec::new();
+    match primitive {
+        RenderPrimitive::Point(z) => {
+            result.push(0);
+            quantize_point(*z, &mut result);
+        }
+        RenderPrimitive::Circle(circle) => {
+            result.push(1);
+            quantize_point(circle.center, &mut result);
+            result.push(quantize(circle.radius));
+        }
+        RenderPrimitive::LineSegment(segment) => {
+            result.push(2);
+            quantize_point(segment.start, &mut result);
+            quantize_point(segment.end, &mut result);
+        }
+        RenderPrimitive::CircularArc(arc) => {
+            result.push(3);
+            quantize_point(arc.circle.center, &mut result);
+            result.push(quantize(arc.circle.radius));
+            quantize_point(arc.start(), &mut result);
+            quantize_point(arc.end(), &mut result);
+            result.push((arc.direction() == ArcDirection::Counterclockwise) as i64);
+        }
+        RenderPrimitive::QuadraticBezier(curve) => {
+            result.push(6);
+            for z in curve.control_points() {
+                quantize_point(z, &mut result);
+            }
+        }
+        RenderPrimitive::CubicBezier(curve) => {
+            result.push(7);
+            for z in curve.control_points() {
+                quantize_point(z, &mut result);
+            }
+        }
+        RenderPrimitive::Layer(layer) => {
+            result.push(4);
+            result.push(layer.name.len() as i64);
+            result.extend(layer.name.bytes().map(i64::from));
+            result.push(layer.z_index as i64);
+
+            let mut children: Vec<Vec<i64>> =
+                layer.children.iter().map(quantize_primitive).collect();
+            children.sort();
+            result.push(children.len() as i64);
+            for child in children {
+                result.push(child.len() as i64);
+                result.extend(child);
+            }
+        }
+        RenderPrimitive::Text {
+            position,
+            content,
+            size,
+            anchor,
+        } => {
+            result.push(5);
+            quantize_point(*position, &mut result);
+            result.push(quantize(*size));
+            result.push(*anchor as i64);
+            result.push(content.len() as i64);
+            result.extend(content.bytes().map(i64::from));
+        }
+    }
+    result
+}
+
+/// 64-bit FNV-1a. The standard library hashers are not guaranteed to be
+/// stable across Rust releases, so fingerprints use this instead.
+fn fnv1a(values: &[Vec<i64>]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let mut hash = OFFSET_BASIS;
+    for value in values.iter().flatten() {
+        for byte in value.to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    hash
+}
+
+/// Compute a geometric fingerprint of the baked geometry. The primitives
+/// are quantized and sorted before hashing, so the fingerprint only
+/// depends on what is drawn, not the order it was generated in or how it
+/// would be formatted as SVG.
+pub fn fingerprint(renderable: &impl Renderable) -> Result<u64, Box<dyn Error>> {
+    let mut quantized: Vec<Vec<i64>> = renderable
+        .bake_geometry()?
+        .iter()
+        .map(quantize_primitive)
+        .collect();
+    quantized.sort();
+
+    Ok(fnv1a(&quantized))
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use crate::{
+        geometry::{ArcAngles, Circle, CircularArc, LineSegment},
+        transformable::ClineArcTile,
+    };
+
+    use super::*;
+
+    #[test]
+    pub fn quantize_primitive_distinguishes_layer_names() {
+        let children = vec![RenderPrimitive::Point(Complex::ONE)];
+        let a = RenderPrimitive::layer("a", children.clone(), 0);
+        let b = RenderPrimitive::layer("b", children, 0);
+
+        assert_ne!(quantize_primitive(&a), quantize_primitive(&b));
+    }
+
+    #[test]
+    pub fn fingerprint_ignores_primitive_order() -> Result<(), Box<dyn Error>> {
+        let a = LineSegment::new(Complex::Zero, Complex::ONE);
+        let b = LineSegment::new(Complex::ONE, Complex::I);
+
+        let forward = fingerprint(&ClineArcTile::new(vec![a.into(), b.into()]))?;
+        let backward = fingerprint(&ClineArcTile::new(vec![b.into(), a.into()]))?;
+
+        assert_eq!(forward, backward);
+        Ok(())
+    }
+
+    #[test]
+    pub fn fingerprint_ignores_tiny_differences() -> Result<(), Box<dyn Error>> {
+        let a = LineSegment::new(Complex::Zero, Complex::ONE);
+        let b = LineSegment::new(Complex::Zero, Complex::new(1.0 + 1e-12, 0.0));
+
+        let result_a = fingerprint(&ClineArcTile::new(vec![a.into()]))?;
+        let result_b = fingerprint(&ClineArcTile::new(vec![b.into()]))?;
+
+        assert_eq!(result_a, result_b);
+        Ok(())
+    }
+
+    #[test]
+    pub fn fingerprint_detects_changes() -> Result<(), Box<dyn Error>> {
+        let a = LineSegment::new(Complex::Zero, Complex::ONE);
+        let b = LineSegment::new(Complex::Zero, Complex::new(1.001, 0.0));
+
+        let result_a = fingerprint(&ClineArcTile::new(vec![a.into()]))?;
+        let result_b = fingerprint(&ClineArcTile::new(vec![b.into()]))?;
+
+        assert_ne!(result_a, result_b);
+        Ok(())
+    }
+
+    #[test]
+    pub fn quantize_primitive_uses_arc_endpoints() {
+        let circle = Circle::unit_circle();
+        let arc = CircularArc::new(circle, ArcAngles::new(0.0, PI / 2.0).unwrap());
+        let shifted = CircularArc::new(circle, ArcAngles(2.0 * PI, 2.5 * PI));
+
+        let result = quantize_primitive(&RenderPrimitive::CircularArc(arc));
+        let result_shifted = quantize_primitive(&RenderPrimitive::CircularArc(shifted));
+
+        assert_eq!(result, result_shifted);
+    }
+}
@@ -0,0 +1,434 @@
+use std::{error::Error, fmt::Write, fs, path::Path};
+
+use crate::{
+    geometry::{ArcAngles, Circle, CircularArc, CubicBezier, LineSegment, QuadraticBezier},
+    svg_plot::{svg_dasharray, View},
+    Complex,
+};
+
+use super::{ColorRGB, RenderPrimitive, Renderable, Scene, StrokeWidth, Style};
+
+/// Version of the JSON scene format. Bump this whenever a field is
+/// renamed or removed so viewers can detect files they don't understand.
+///
+/// Version 1 looks like this:
+///
+/// ```text
+/// {
+///   "version": 1,
+///   "metadata": {"name": "poncelet", ...},
+///   "views": [{"label": "", "center": [0, 0], "half_width": 1.1}],
+///   "styles": [{"stroke": "#ff7f00", "fill": null, "width_percent": 0.125,
+///     "width_units": null, "width_mm": null, "arrow_size": null,
+///     "dasharray": null, "stroke_opacity": null, "fill_opacity": null,
+///     "line_cap": null, "line_join": null}],
+///   "layers": [{"z_index": 0, "style": 0, "primitives": [...]}]
+/// }
+/// ```
+///
+/// Layers are listed in draw order, and each layer refers to its style by
+/// index. At most one of the style widths is non-null, see StrokeWidth.
+/// arrow_size is null unless edges have arrowheads, see Arrow. dasharray is
+/// the SVG stroke-dasharray value, e.g. "1% 0.5%". Primitives are one of:
+///
+/// - `{"type": "point", "position": [x, y]}`
+/// - `{"type": "circle", "center": [x, y], "radius": r}`
+/// - `{"type": "line_segment", "start": [x, y], "end": [x, y]}`
+/// - `{"type": "circular_arc", "center": [x, y], "radius": r,
+///   "start_angle": a, "end_angle": b}`
+/// - `{"type": "quadratic_bezier", "start": [x, y], "control": [x, y],
+///   "end": [x, y]}`
+/// - `{"type": "cubic_bezier", "start": [x, y], "control1": [x, y],
+///   "control2": [x, y], "end": [x, y]}`
+/// - `{"type": "layer", "name": "...", "z_index": z, "children": [...]}`
+/// - `{"type": "text", "position": [x, y], "content": "...", "size": s,
+///   "anchor": "start" | "middle" | "end"}`
+///
+/// Primitives are listed in draw order, so layers (see
+/// RenderPrimitive::layer()) are sorted by z_index.
+///
+/// Arc angles are in radians, and the arc goes counterclockwise when
+/// end_angle > start_angle. Coordinates are in the math convention with
+/// y pointing up. Non-finite numbers (e.g. the point at infinity) are
+/// written as null since JSON has no infinity.
+pub const SCENE_SCHEMA_VERSION: u32 = 1;
+
+pub(crate) fn write_string(output: &mut String, value: &str) {
+    output.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(output, "\\u{:04x}", c as u32);
+            }
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+fn write_number(output: &mut String, value: f64) {
+    if value.is_finite() {
+        let _ = write!(output, "{}", value);
+    } else {
+        output.push_str("null");
+    }
+}
+
+pub(crate) fn write_point(output: &mut String, z: Complex) {
+    output.push('[');
+    write_number(output, z.real());
+    output.push_str(", ");
+    write_number(output, z.imag());
+    output.push(']');
+}
+
+fn write_color(output: &mut String, color: Option<ColorRGB>) {
+    match color {
+        Some(color) => write_string(output, &color.to_string()),
+        None => output.push_str("null"),
+    }
+}
+
+fn style_json(style: &Style) -> String {
+    let mut output = String::from("{\"stroke\": ");
+    write_color(&mut output, style.stroke);
+    output.push_str(", \"fill\": ");
+    write_color(&mut output, style.fill);
+    // At most one of the widths is set, depending on the units
+    let widths = [
+        (
+            "width_percent",
+            matches!(style.width, Some(StrokeWidth::Percent(_))),
+        ),
+        (
+            "width_units",
+            matches!(style.width, Some(StrokeWidth::Units(_))),
+        ),
+        (
+            "width_mm",
+            matches!(style.width, Some(StrokeWidth::Millimeters(_))),
+        ),
+    ];
+    for (key, is_set) in widths {
+        let _ = write!(output, ", \"{}\": ", key);
+        match style.width {
+            Some(width) if is_set => write_number(&mut output, width.value()),
+            _ => output.push_str("null"),
+        }
+    }
+    output.push_str(", \"arrow_size\": ");
+    match style.arrow {
+        Some(arrow) => write_number(&mut output, arrow.size),
+        None => output.push_str("null"),
+    }
+    output.push_str(", \"dasharray\": ");
+    match style.stroke_dasharray {
+        Some(dasharray) => write_string(&mut output, &svg_dasharray(dasharray)),
+        None => output.push_str("null"),
+    }
+    for (key, opacity) in [
+        ("stroke_opacity", style.stroke_opacity),
+        ("fill_opacity", style.fill_opacity),
+    ] {
+        let _ = write!(output, ", \"{}\": ", key);
+        match opacity {
+            Some(opacity) => write_number(&mut output, opacity),
+            None => output.push_str("null"),
+        }
+    }
+    for (key, name) in [
+        ("line_cap", style.line_cap.map(|x| x.name())),
+        ("line_join", style.line_join.map(|x| x.name())),
+    ] {
+        let _ = write!(output, ", \"{}\": ", key);
+        match name {
+            Some(name) => write_string(&mut output, name),
+            None => output.push_str("null"),
+        }
+    }
+    output.push('}');
+    output
+}
+
+fn write_primitive(output: &mut String, primitive: &RenderPrimitive) {
+    match primitive {
+        RenderPrimitive::Point(z) => {
+            output.push_str("{\"type\": \"point\", \"position\": ");
+            write_point(output, *z);
+        }
+        RenderPrimitive::Circle(Circle { center, radius }) => {
+            output.push_str("{\"type\": \"circle\", \"center\": ");
+            write_point(output, *center);
+            output.push_str(", \"radius\": ");
+            write_number(output, *radius);
+        }
+        RenderPrimitive::LineSegment(LineSegment { start, end }) => {
+            output.push_str("{\"type\": \"line_segment\", \"start\": ");
+            write_point(output, *start);
+            output.push_str(", \"end\": ");
+            write_point(output, *end);
+        }
+        RenderPrimitive::CircularArc(CircularArc { circle, angles }) => {
+            let ArcAngles(start_angle, end_angle) = angles;
+            output.push_str("{\"type\": \"circular_arc\", \"center\": ");
+            write_point(output, circle.center);
+            output.push_str(", \"radius\": ");
+            write_number(output, circle.radius);
+            output.push_str(", \"start_angle\": ");
+            write_number(output, *start_angle);
+            output.push_str(", \"end_angle\": ");
+            write_number(output, *end_angle);
+        }
+        RenderPrimitive::QuadraticBezier(QuadraticBezier {
+            start,
+            control,
+            end,
+        }) => {
+            output.push_str("{\"type\": \"quadratic_bezier\", \"start\": ");
+            write_point(output, *start);
+            output.push_str(", \"control\": ");
+            write_point(output, *control);
+            output.push_str(", \"end\": ");
+            write_point(output, *end);
+        }
+        RenderPrimitive::CubicBezier(CubicBezier {
+            start,
+            control1,
+            control2,
+            end,
+        }) => {
+            output.push_str("{\"type\": \"cubic_bezier\", \"start\": ");
+            write_point(output, *start);
+            output.push_str(", \"control1\": ");
+            write_point(output, *control1);
+            output.push_str(", \"control2\": ");
+            write_point(output, *control2);
+            output.push_str(", \"end\": ");
+            write_point(output, *end);
+        }
+        RenderPrimitive::Layer(layer) => {
+            output.push_str("{\"type\": \"layer\", \"name\": ");
+            write_string(output, &layer.name);
+            let _ = write!(output, ", \"z_index\": {}, \"children\": [", layer.z_index);
+            for (i, child) in layer.children.iter().enumerate() {
+                if i > 0 {
+                    output.push_str(", ");
+                }
+                write_primitive(output, child);
+            }
+            output.push(']');
+        }
+        RenderPrimitive::Text {
+            position,
+            content,
+            size,
+            anchor,
+        } => {
+            output.push_str("{\"type\": \"text\", \"position\": ");
+            write_point(output, *position);
+            output.push_str(", \"content\": ");
+            write_string(output, content);
+            output.push_str(", \"size\": ");
+            write_number(output, *size);
+            output.push_str(", \"anchor\": ");
+            write_string(output, anchor.name());
+        }
+    }
+    output.push('}');
+}
+
+/// Serialize a scene to the JSON scene format, see SCENE_SCHEMA_VERSION.
+/// Metadata is a list of (key, value) string pairs, e.g. the figure name
+/// and its parameters. Identical styles are only listed once.
+pub fn scene_to_json(
+    scene: &Scene,
+    views: &[View],
+    metadata: &[(&str, String)],
+) -> Result<String, Box<dyn Error>> {
+    let mut output = format!("{{\n  \"version\": {},\n", SCENE_SCHEMA_VERSION);
+
+    output.push_str("  \"metadata\": {");
+    for (i, (key, value)) in metadata.iter().enumerate() {
+        if i > 0 {
+            output.push_str(", ");
+        }
+        write_string(&mut output, key);
+        output.push_str(": ");
+        write_string(&mut output, value);
+    }
+    output.push_str("},\n");
+
+    output.push_str("  \"views\": [");
+    for (i, View(label, x, y, half_width)) in views.iter().enumerate() {
+        if i > 0 {
+            output.push_str(", ");
+        }
+        output.push_str("{\"label\": ");
+        write_string(&mut output, label);
+        output.push_str(", \"center\": ");
+        write_point(&mut output, Complex::new(*x, *y));
+        output.push_str(", \"half_width\": ");
+        write_number(&mut output, *half_width);
+        output.push('}');
+    }
+    output.push_str("],\n");
+
+    let mut styles: Vec<String> = Vec::new();
+    let mut layers: Vec<String> = Vec::new();
+    for layer in scene.sorted_layers() {
+        let style = style_json(&layer.style);
+        let style_index = match styles.iter().position(|x| *x == style) {
+            Some(index) => index,
+            None => {
+                styles.push(style);
+                styles.len() - 1
+            }
+        };
+
+        let mut layer_json = format!(
+            "{{\"z_index\": {}, \"style\": {}, \"primitives\": [",
+            layer.z_index, style_index
+        );
+        let mut primitives = layer.bake_geometry()?;
+        RenderPrimitive::sort_layers(&mut primitives);
+        for (i, primitive) in primitives.iter().enumerate() {
+            layer_json.push_str(if i > 0 { ",\n      " } else { "\n      " });
+            write_primitive(&mut layer_json, primitive);
+        }
+        layer_json.push_str("\n    ]}");
+        layers.push(layer_json);
+    }
+
+    let _ = write!(
+        output,
+        "  \"styles\": [\n    {}\n  ],\n  \"layers\": [\n    {}\n  ]\n}}\n",
+        styles.join(",\n    "),
+        layers.join(",\n    ")
+    );
+
+    Ok(output)
+}
+
+/// Write a scene to a JSON file, see scene_to_json()
+pub fn write_scene_json<P: AsRef<Path>>(
+    path: P,
+    scene: &Scene,
+    views: &[View],
+    metadata: &[(&str, String)],
+) -> Result<(), Box<dyn Error>> {
+    fs::write(path, scene_to_json(scene, views, metadata)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use crate::transformable::{Cline, ClineArcTile};
+
+    use super::*;
+
+    fn make_scene() -> Scene {
+        let mut scene = Scene::new();
+        let style = Style::stroke(255, 127, 0).with_width(0.5);
+        scene.add_layer(1, style, &[Cline::unit_circle()]);
+        scene.add_layer(
+            0,
+            style,
+            &[ClineArcTile::new(vec![LineSegment::new(
+                Complex::Zero,
+                Complex::ONE,
+            )
+            .into()])],
+        );
+        scene
+    }
+
+    #[test]
+    pub fn write_string_escapes_special_characters() {
+        let mut output = String::new();
+
+        write_string(&mut output, "a \"b\"\\\n");
+
+        assert_eq!(output, r#""a \"b\"\\\n""#);
+    }
+
+    #[test]
+    pub fn write_number_with_infinity_writes_null() {
+        let mut output = String::new();
+
+        write_point(&mut output, Complex::Infinity);
+
+        assert_eq!(output, "[null, null]");
+    }
+
+    #[test]
+    pub fn style_json_writes_width_in_its_units() {
+        let style = Style::stroke(0, 0, 0).with_stroke_width(StrokeWidth::Millimeters(0.3));
+
+        let result = style_json(&style);
+
+        assert_eq!(
+            result,
+            "{\"stroke\": \"#000000\", \"fill\": null, \"width_percent\": null, \
+            \"width_units\": null, \"width_mm\": 0.3, \"arrow_size\": null, \
+            \"dasharray\": null, \"stroke_opacity\": null, \"fill_opacity\": null, \
+            \"line_cap\": null, \"line_join\": null}"
+        );
+    }
+
+    #[test]
+    pub fn write_primitive_formats_arc() {
+        let arc = CircularArc::new(Circle::unit_circle(), ArcAngles::new(0.0, PI).unwrap());
+        let mut output = String::new();
+
+        write_primitive(&mut output, &RenderPrimitive::CircularArc(arc));
+
+        assert_eq!(
+            output,
+            format!(
+                "{{\"type\": \"circular_arc\", \"center\": [0, 0], \"radius\": 1, \
+                \"start_angle\": 0, \"end_angle\": {}}}",
+                PI
+            )
+        );
+    }
+
+    #[test]
+    pub fn write_primitive_formats_layer() {
+        let layer = RenderPrimitive::layer("dots", vec![RenderPrimitive::Point(Complex::ONE)], 3);
+        let mut output = String::new();
+
+        write_primitive(&mut output, &layer);
+
+        assert_eq!(
+            output,
+            "{\"type\": \"layer\", \"name\": \"dots\", \"z_index\": 3, \"children\": \
+            [{\"type\": \"point\", \"position\": [1, 0]}]}"
+        );
+    }
+
+    #[test]
+    pub fn scene_to_json_shares_styles_and_sorts_layers() -> Result<(), Box<dyn Error>> {
+        let scene = make_scene();
+
+        let result = scene_to_json(
+            &scene,
+            &[View("", 0.0, 0.0, 1.5)],
+            &[("name", String::from("test"))],
+        )?;
+
+        assert!(result.starts_with("{\n  \"version\": 1,\n"));
+        assert!(result.contains("\"metadata\": {\"name\": \"test\"}"));
+        assert!(result.contains("{\"label\": \"\", \"center\": [0, 0], \"half_width\": 1.5}"));
+        assert_eq!(result.matches("\"stroke\"").count(), 1);
+        let segment = result.find("line_segment").unwrap();
+        let circle = result.find("\"circle\"").unwrap();
+        assert!(segment < circle);
+        Ok(())
+    }
+}
@@ -1,11 +1,32 @@
+pub mod area_shading;
+pub mod clipping;
+pub mod culling;
+pub mod fingerprint;
+pub mod json_export;
+pub mod palette;
+pub mod prelude;
 pub mod render_primitive;
+pub mod scene;
 pub mod style;
 
 use std::error::Error;
 
+pub use area_shading::*;
+pub use clipping::*;
+pub use culling::*;
+pub use fingerprint::*;
+pub use json_export::*;
+pub use palette::*;
 pub use render_primitive::*;
+pub use scene::*;
 pub use style::*;
 
 pub trait Renderable {
     fn bake_geometry(&self) -> Result<Vec<RenderPrimitive>, Box<dyn Error>>;
 }
+
+impl<T: Renderable + ?Sized> Renderable for &T {
+    fn bake_geometry(&self) -> Result<Vec<RenderPrimitive>, Box<dyn Error>> {
+        (**self).bake_geometry()
+    }
+}
@@ -0,0 +1,335 @@
+use std::fmt::Display;
+
+use super::{ColorRGB, Style};
+
+/// A gradient through a list of evenly spaced colors, for mapping a
+/// number in [0, 1] to a color.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Palette {
+    colors: Vec<ColorRGB>,
+}
+
+impl Palette {
+    pub fn new(colors: Vec<ColorRGB>) -> Result<Self, String> {
+        if colors.is_empty() {
+            return Err(String::from("palette must have at least one color"));
+        }
+
+        Ok(Self { colors })
+    }
+
+    pub fn colors(&self) -> &[ColorRGB] {
+        &self.colors
+    }
+
+    /// The Okabe-Ito palette of colors that stay distinguishable with the
+    /// common kinds of color blindness. Black is left out since the cards
+    /// have a black background. Several pairs are told apart by hue but
+    /// have similar luminance, so ContrastCheck flags them
+    pub fn okabe_ito() -> Self {
+        Self::from_hex(&[
+            0xe69f00, // orange
+            0x56b4e9, // sky blue
+            0x009e73, // bluish green
+            0xf0e442, // yellow
+            0x0072b2, // blue
+            0xd55e00, // vermillion
+            0xcc79a7, // reddish purple
+        ])
+    }
+
+    /// Ramp from dark purple to yellow that increases steadily in
+    /// lightness, so it reads correctly in grayscale and for color blind
+    /// viewers. The stops are matplotlib's viridis at multiples of 1/9
+    pub fn viridis() -> Self {
+        Self::from_hex(&[
+            0x440154, 0x482878, 0x3e4989, 0x31688e, 0x26828e, 0x1f9e89, 0x35b779, 0x6ece58,
+            0xb5de2b, 0xfde725,
+        ])
+    }
+
+    /// Like viridis(), but from black through red to pale yellow
+    pub fn inferno() -> Self {
+        Self::from_hex(&[
+            0x000004, 0x1b0c41, 0x4a0c6b, 0x781c6d, 0xa52c60, 0xcf4446, 0xed6925, 0xfb9b06,
+            0xf7d13d, 0xfcffa4,
+        ])
+    }
+
+    fn from_hex(colors: &[u32]) -> Self {
+        let channel = |hex: u32, shift: u32| ((hex >> shift) & 0xff) as u8;
+        Self {
+            colors: colors
+                .iter()
+                .map(|&hex| ColorRGB(channel(hex, 16), channel(hex, 8), channel(hex, 0)))
+                .collect(),
+        }
+    }
+
+    /// The color at t, interpolating between neighboring colors. t is
+    /// clamped to [0, 1]
+    pub fn sample(&self, t: f64) -> ColorRGB {
+        let last = self.colors.len() - 1;
+        if last == 0 {
+            return self.colors[0];
+        }
+
+        let scaled = t.clamp(0.0, 1.0) * last as f64;
+        let index = (scaled.floor() as usize).min(last - 1);
+        ColorRGB::lerp(
+            self.colors[index],
+            self.colors[index + 1],
+            scaled - index as f64,
+        )
+    }
+
+    /// Like sample(), but t wraps around so the palette repeats every
+    /// period. The last color fades back into the first, which suits
+    /// iteration counts that have no natural maximum.
+    pub fn sample_cyclic(&self, t: f64, period: f64) -> ColorRGB {
+        let n = self.colors.len();
+        let scaled = (t / period).rem_euclid(1.0) * n as f64;
+        let index = (scaled.floor() as usize).min(n - 1);
+        ColorRGB::lerp(
+            self.colors[index],
+            self.colors[(index + 1) % n],
+            scaled - index as f64,
+        )
+    }
+}
+
+/// Relative luminance of a color as defined by WCAG, from 0 for black to
+/// 1 for white
+pub fn relative_luminance(color: ColorRGB) -> f64 {
+    let linear = |x: u8| {
+        let x = x as f64 / 255.0;
+        if x <= 0.04045 {
+            x / 12.92
+        } else {
+            ((x + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let ColorRGB(r, g, b) = color;
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+/// WCAG contrast ratio between two colors, from 1 (identical luminance)
+/// to 21 (black on white). The order of the colors doesn't matter
+pub fn contrast_ratio(a: ColorRGB, b: ColorRGB) -> f64 {
+    let (a, b) = (relative_luminance(a), relative_luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// A pair of colors that may be hard to tell apart, see ContrastCheck
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ContrastWarning {
+    /// colors[index] is too close to the background
+    Background { index: usize, ratio: f64 },
+    /// colors[a] and colors[b] are too close to each other
+    Between { a: usize, b: usize, ratio: f64 },
+}
+
+impl Display for ContrastWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Background { index, ratio } => write!(
+                f,
+                "color {} has contrast {:.2} with the background",
+                index, ratio
+            ),
+            Self::Between { a, b, ratio } => {
+                write!(f, "colors {} and {} have contrast {:.2}", a, b, ratio)
+            }
+        }
+    }
+}
+
+/// Check that a list of colors stands out from the background and from
+/// each other. Luminance contrast is what survives color blindness and
+/// grayscale printing, so two colors of different hue but equal
+/// luminance are flagged too.
+///
+/// The defaults follow WCAG's 3:1 minimum for graphics against the
+/// background. Colors only need to differ from each other by a smaller
+/// ratio, since hue still helps most viewers
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContrastCheck {
+    pub background: ColorRGB,
+    pub min_background_ratio: f64,
+    pub min_between_ratio: f64,
+}
+
+impl ContrastCheck {
+    pub fn new(background: ColorRGB) -> Self {
+        Self {
+            background,
+            min_background_ratio: 3.0,
+            min_between_ratio: 1.5,
+        }
+    }
+
+    pub fn check_colors(&self, colors: &[ColorRGB]) -> Vec<ContrastWarning> {
+        let mut warnings = Vec::new();
+        for (index, color) in colors.iter().enumerate() {
+            let ratio = contrast_ratio(*color, self.background);
+            if ratio < self.min_background_ratio {
+                warnings.push(ContrastWarning::Background { index, ratio });
+            }
+        }
+
+        for (a, x) in colors.iter().enumerate() {
+            for (b, y) in colors.iter().enumerate().skip(a + 1) {
+                let ratio = contrast_ratio(*x, *y);
+                if ratio < self.min_between_ratio {
+                    warnings.push(ContrastWarning::Between { a, b, ratio });
+                }
+            }
+        }
+        warnings
+    }
+
+    pub fn check_palette(&self, palette: &Palette) -> Vec<ContrastWarning> {
+        self.check_colors(palette.colors())
+    }
+
+    /// Check the stroke colors of a list of styles, e.g. the styles of a
+    /// motif. Indices in the warnings refer to the styles, and styles
+    /// without a stroke are skipped
+    pub fn check_styles(&self, styles: &[Style]) -> Vec<ContrastWarning> {
+        let (indices, colors): (Vec<usize>, Vec<ColorRGB>) = styles
+            .iter()
+            .enumerate()
+            .filter_map(|(i, style)| style.stroke.map(|color| (i, color)))
+            .unzip();
+
+        self.check_colors(&colors)
+            .into_iter()
+            .map(|warning| match warning {
+                ContrastWarning::Background { index, ratio } => ContrastWarning::Background {
+                    index: indices[index],
+                    ratio,
+                },
+                ContrastWarning::Between { a, b, ratio } => ContrastWarning::Between {
+                    a: indices[a],
+                    b: indices[b],
+                    ratio,
+                },
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const BLACK: ColorRGB = ColorRGB(0, 0, 0);
+    const WHITE: ColorRGB = ColorRGB(255, 255, 255);
+    const RED: ColorRGB = ColorRGB(255, 0, 0);
+
+    #[test]
+    pub fn new_with_no_colors_returns_error() {
+        let result = Palette::new(vec![]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn sample_hits_each_color() {
+        let palette = Palette::new(vec![BLACK, WHITE, RED]).unwrap();
+
+        assert_eq!(palette.sample(0.0), BLACK);
+        assert_eq!(palette.sample(0.5), WHITE);
+        assert_eq!(palette.sample(1.0), RED);
+        assert_eq!(palette.sample(2.0), RED);
+        assert_eq!(palette.sample(0.25), ColorRGB(128, 128, 128));
+    }
+
+    #[test]
+    pub fn sample_cyclic_wraps_around() {
+        let palette = Palette::new(vec![BLACK, WHITE]).unwrap();
+
+        assert_eq!(palette.sample_cyclic(0.0, 10.0), BLACK);
+        assert_eq!(palette.sample_cyclic(5.0, 10.0), WHITE);
+        assert_eq!(palette.sample_cyclic(25.0, 10.0), WHITE);
+        assert_eq!(palette.sample_cyclic(7.5, 10.0), ColorRGB(128, 128, 128));
+    }
+
+    #[test]
+    pub fn contrast_ratio_of_black_and_white_is_21() {
+        let result = contrast_ratio(WHITE, BLACK);
+
+        assert!((result - 21.0).abs() < 1e-9);
+        assert_eq!(contrast_ratio(RED, RED), 1.0);
+    }
+
+    #[test]
+    pub fn check_colors_flags_background_color() {
+        let result = ContrastCheck::new(BLACK).check_colors(&[BLACK]);
+
+        assert_eq!(
+            result,
+            vec![ContrastWarning::Background {
+                index: 0,
+                ratio: 1.0
+            }]
+        );
+    }
+
+    #[test]
+    pub fn okabe_ito_on_black_only_has_expected_warnings() {
+        let result = ContrastCheck::new(BLACK).check_palette(&Palette::okabe_ito());
+
+        // Every color stands out from black, but the palette separates
+        // colors by hue rather than luminance, so these pairs are close
+        // in grayscale
+        let pairs: Vec<(usize, usize)> = result
+            .iter()
+            .map(|x| match *x {
+                ContrastWarning::Between { a, b, .. } => (a, b),
+                ContrastWarning::Background { .. } => panic!("unexpected warning {}", x),
+            })
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                (0, 1),
+                (0, 6),
+                (1, 2),
+                (1, 6),
+                (2, 5),
+                (2, 6),
+                (4, 5),
+                (5, 6)
+            ]
+        );
+    }
+
+    #[test]
+    pub fn viridis_gets_lighter() {
+        let luminance: Vec<f64> = Palette::viridis()
+            .colors()
+            .iter()
+            .map(|x| relative_luminance(*x))
+            .collect();
+
+        assert!(luminance.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    pub fn check_styles_flags_equal_luminance_and_skips_unstroked() {
+        // Pure red and this green have nearly the same luminance
+        let styles = [
+            Style::stroke(255, 0, 0),
+            Style::new(),
+            Style::stroke(0, 154, 0),
+        ];
+
+        let result = ContrastCheck::new(BLACK).check_styles(&styles);
+
+        assert!(matches!(
+            result[..],
+            [ContrastWarning::Between { a: 0, b: 2, .. }]
+        ));
+    }
+}
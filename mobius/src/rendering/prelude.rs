@@ -0,0 +1,13 @@
+//! Everything needed to style a scene and write it to SVG, for glob
+//! importing:
+//!
+//! ```
+//! use mobius::rendering::prelude::*;
+//! ```
+pub use super::{
+    fingerprint, ColorRGB, Palette, RenderPrimitive, Renderable, Scene, StrokeCompensation,
+    StrokeWidth, Style, TextAnchor,
+};
+pub use crate::svg_plot::{
+    render_scene, render_views, style_geometry, style_motif, style_motifs, union, View,
+};
@@ -1,19 +1,133 @@
+use std::f64::consts::PI;
+
 use crate::{
-    geometry::{Circle, CircularArc, Line, LineSegment, Ray},
+    geometry::{
+        Circle, CircularArc, CubicBezier, DirectedEdge, Line, LineSegment, QuadraticBezier, Ray,
+    },
     Complex,
 };
 
-#[derive(Clone, Copy)]
+/// A named group of primitives, see RenderPrimitive::layer()
+#[derive(Clone)]
+pub struct PrimitiveLayer {
+    pub name: String,
+    pub z_index: i32,
+    pub children: Vec<RenderPrimitive>,
+}
+
+/// Which part of the text is placed at its position
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextAnchor {
+    Start,
+    Middle,
+    End,
+}
+
+impl TextAnchor {
+    /// Value of the SVG text-anchor attribute
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::Middle => "middle",
+            Self::End => "end",
+        }
+    }
+}
+
+#[derive(Clone)]
 pub enum RenderPrimitive {
     Point(Complex),
     Circle(Circle),
     LineSegment(LineSegment),
     CircularArc(CircularArc),
+    QuadraticBezier(QuadraticBezier),
+    CubicBezier(CubicBezier),
+    Layer(PrimitiveLayer),
+    /// A text label with its baseline at position. The size is the font
+    /// size in the same units as the geometry. Text is filled with the
+    /// fill color of its style (and outlined with the stroke, if any),
+    /// see Style::text()
+    Text {
+        position: Complex,
+        content: String,
+        size: f64,
+        anchor: TextAnchor,
+    },
 }
 
 const FAR_AWAY: f64 = 10000.0;
 
 impl RenderPrimitive {
+    /// Group primitives under a name. In SVG output this becomes
+    /// <g id="name">. Layers are drawn from lowest z_index to highest
+    /// regardless of the order they were created in, so functions that
+    /// build parts of a picture can be composed in any order
+    pub fn layer(name: &str, children: Vec<RenderPrimitive>, z_index: i32) -> Self {
+        Self::Layer(PrimitiveLayer {
+            name: String::from(name),
+            z_index,
+            children,
+        })
+    }
+
+    /// Drawing order of the primitive. Only layers have a z_index, other
+    /// primitives are drawn at 0
+    pub fn z_index(&self) -> i32 {
+        match self {
+            Self::Layer(layer) => layer.z_index,
+            _ => 0,
+        }
+    }
+
+    /// Sort primitives into drawing order, including the children of
+    /// layers. The sort is stable, so primitives with the same z_index
+    /// stay in the order they were baked
+    pub fn sort_layers(primitives: &mut [RenderPrimitive]) {
+        primitives.sort_by_key(Self::z_index);
+        for primitive in primitives.iter_mut() {
+            if let Self::Layer(layer) = primitive {
+                Self::sort_layers(&mut layer.children);
+            }
+        }
+    }
+
+    /// A circle that contains the primitive, for culling primitives that
+    /// are far from the view. The circle is not always the smallest one.
+    /// Layers have no bounding circle, their children are handled one at
+    /// a time, and neither do primitives at infinity
+    pub fn bounding_circle(&self) -> Option<Circle> {
+        let circle = match self {
+            Self::Point(z) => Circle::new(*z, 0.0),
+            Self::Circle(circle) => *circle,
+            Self::LineSegment(LineSegment { start, end }) => {
+                Circle::new(midpoint(*start, *end), 0.5 * (*end - *start).mag())
+            }
+            // A minor arc fits inside the circle with its chord as the
+            // diameter, a major arc only fits in its own circle
+            Self::CircularArc(arc) if arc.angles.central_angle() <= PI => {
+                let (start, end) = (arc.start(), arc.end());
+                Circle::new(midpoint(start, end), 0.5 * (end - start).mag())
+            }
+            Self::CircularArc(arc) => arc.circle,
+            // A Bezier curve stays inside the convex hull of its control
+            // points
+            Self::QuadraticBezier(curve) => control_point_circle(&curve.control_points()),
+            Self::CubicBezier(curve) => control_point_circle(&curve.control_points()),
+            // Text can extend about one font size per character from its
+            // position, depending on the anchor
+            Self::Text {
+                position,
+                content,
+                size,
+                ..
+            } => Circle::new(*position, size * content.chars().count().max(1) as f64),
+            Self::Layer(_) => return None,
+        };
+
+        let Circle { center, radius } = circle;
+        (center != Complex::Infinity && radius.is_finite()).then_some(circle)
+    }
+
     /// Render a ray as a line segment from the start point to far off the canvas.
     pub fn make_ray(ray: Ray) -> Self {
         let Ray { start, unit_dir } = ray;
@@ -37,3 +151,111 @@ impl RenderPrimitive {
         Self::LineSegment(LineSegment { start, end })
     }
 }
+
+fn midpoint(a: Complex, b: Complex) -> Complex {
+    (a + b) * 0.5.into()
+}
+
+/// Circle centered on the bounding box of the points that contains all of
+/// them
+fn control_point_circle(points: &[Complex]) -> Circle {
+    let xs = points.iter().map(|z| z.real());
+    let ys = points.iter().map(|z| z.imag());
+    let x_mid =
+        0.5 * (xs.clone().fold(f64::INFINITY, f64::min) + xs.fold(f64::NEG_INFINITY, f64::max));
+    let y_mid =
+        0.5 * (ys.clone().fold(f64::INFINITY, f64::min) + ys.fold(f64::NEG_INFINITY, f64::max));
+    let center = Complex::new(x_mid, y_mid);
+    let radius = points
+        .iter()
+        .map(|z| (*z - center).mag())
+        .fold(0.0, f64::max);
+
+    Circle::new(center, radius)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geometry::ArcAngles;
+
+    use super::*;
+
+    fn name_of(primitive: &RenderPrimitive) -> &str {
+        match primitive {
+            RenderPrimitive::Layer(layer) => &layer.name,
+            _ => "",
+        }
+    }
+
+    #[test]
+    pub fn bounding_circle_of_minor_arc_uses_chord() {
+        let angles = ArcAngles::new(0.0, 0.5 * PI).unwrap();
+        let arc = RenderPrimitive::CircularArc(CircularArc::new(Circle::unit_circle(), angles));
+
+        let result = arc.bounding_circle().unwrap();
+
+        assert_eq!(result.center, Complex::new(0.5, 0.5));
+        assert!((result.radius - 0.5f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    pub fn bounding_circle_of_major_arc_is_its_circle() {
+        let angles = ArcAngles::new(0.0, 1.5 * PI).unwrap();
+        let arc = RenderPrimitive::CircularArc(CircularArc::new(Circle::unit_circle(), angles));
+
+        let result = arc.bounding_circle().unwrap();
+
+        assert_eq!(result, Circle::unit_circle());
+    }
+
+    #[test]
+    pub fn bounding_circle_of_bezier_contains_curve() {
+        let curve = CubicBezier::new(
+            Complex::Zero,
+            Complex::new(1.0, 2.0),
+            Complex::new(2.0, -2.0),
+            Complex::new(3.0, 0.0),
+        );
+
+        let result = RenderPrimitive::CubicBezier(curve)
+            .bounding_circle()
+            .unwrap();
+
+        assert_eq!(result.center, Complex::new(1.5, 0.0));
+        for z in curve.sample(16) {
+            assert!((z - result.center).mag() <= result.radius);
+        }
+    }
+
+    #[test]
+    pub fn sort_layers_orders_by_z_index() {
+        let mut primitives = vec![
+            RenderPrimitive::layer("top", vec![], 2),
+            RenderPrimitive::Point(Complex::Zero),
+            RenderPrimitive::layer("bottom", vec![], -1),
+            RenderPrimitive::layer("middle", vec![], 0),
+        ];
+
+        RenderPrimitive::sort_layers(&mut primitives);
+
+        let result: Vec<&str> = primitives.iter().map(name_of).collect();
+        assert_eq!(result, vec!["bottom", "", "middle", "top"]);
+    }
+
+    #[test]
+    pub fn sort_layers_sorts_nested_layers() {
+        let inner = vec![
+            RenderPrimitive::layer("b", vec![], 1),
+            RenderPrimitive::layer("a", vec![], 0),
+        ];
+        let mut primitives = vec![RenderPrimitive::layer("outer", inner, 0)];
+
+        RenderPrimitive::sort_layers(&mut primitives);
+
+        let RenderPrimitive::Layer(outer) = &primitives[0] else {
+            panic!("expected a layer");
+        };
+        let result: Vec<&str> = outer.children.iter().map(name_of).collect();
+        assert_eq!(result, vec!["a", "b"]);
+    }
+}
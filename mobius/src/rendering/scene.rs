@@ -0,0 +1,341 @@
+use std::error::Error;
+
+use crate::{
+    isogonal::Isogonal,
+    transformable::{Motif, Transformable},
+    Complex,
+};
+
+use super::{RenderPrimitive, Renderable, StrokeCompensation, Style};
+
+/// Geometry that can be both transformed and rendered. This is
+/// type-erased so a single Scene can hold clines, arcs, tiles, etc.
+pub trait SceneGeometry: Renderable {
+    fn transform_boxed(&self, xform: Isogonal) -> Box<dyn SceneGeometry>;
+    fn clone_boxed(&self) -> Box<dyn SceneGeometry>;
+}
+
+impl<T: Renderable + Transformable<Isogonal> + Clone + 'static> SceneGeometry for T {
+    fn transform_boxed(&self, xform: Isogonal) -> Box<dyn SceneGeometry> {
+        Box::new(self.transform(xform))
+    }
+
+    fn clone_boxed(&self) -> Box<dyn SceneGeometry> {
+        Box::new(self.clone())
+    }
+}
+
+/// A single style applied to a list of geometry. Layers are drawn
+/// from lowest z_index to highest.
+pub struct Layer {
+    pub z_index: i32,
+    pub style: Style,
+    geometry: Vec<Box<dyn SceneGeometry>>,
+}
+
+impl Clone for Layer {
+    fn clone(&self) -> Self {
+        Self {
+            z_index: self.z_index,
+            style: self.style,
+            geometry: self.geometry.iter().map(|x| x.clone_boxed()).collect(),
+        }
+    }
+}
+
+impl Transformable<Isogonal> for Layer {
+    fn transform(&self, xform: Isogonal) -> Self {
+        Self {
+            z_index: self.z_index,
+            style: self.style,
+            geometry: self
+                .geometry
+                .iter()
+                .map(|x| x.transform_boxed(xform))
+                .collect(),
+        }
+    }
+}
+
+impl Renderable for Layer {
+    fn bake_geometry(&self) -> Result<Vec<RenderPrimitive>, Box<dyn Error>> {
+        let mut result = Vec::new();
+        for geometry in self.geometry.iter() {
+            result.extend(geometry.bake_geometry()?);
+        }
+        Ok(result)
+    }
+}
+
+/// A scene is a list of styled layers. Scenes can be built independently
+/// (e.g. each from its own IFS), and then composed into a larger scene,
+/// each with a placement transform and a z-offset.
+#[derive(Clone, Default)]
+pub struct Scene {
+    layers: Vec<Layer>,
+}
+
+impl Scene {
+    pub fn new() -> Self {
+        Self { layers: Vec::new() }
+    }
+
+    /// Add a layer of geometry that all share the same style
+    pub fn add_layer<T: SceneGeometry + Clone + 'static>(
+        &mut self,
+        z_index: i32,
+        style: Style,
+        geometry: &[T],
+    ) {
+        self.layers.push(Layer {
+            z_index,
+            style,
+            geometry: geometry.iter().map(|x| x.clone_boxed()).collect(),
+        });
+    }
+
+    /// Add one layer per transform, each holding the transformed geometry.
+    /// The stroke width of each layer is scaled by the transform's local
+    /// scale factor at center (typically the center of the geometry
+    /// before transforming), see StrokeCompensation
+    pub fn add_compensated_layers<T: SceneGeometry + Clone + 'static>(
+        &mut self,
+        z_index: i32,
+        style: Style,
+        geometry: &T,
+        center: Complex,
+        xforms: &[Isogonal],
+        compensation: StrokeCompensation,
+    ) {
+        for xform in xforms {
+            self.layers.push(Layer {
+                z_index,
+                style: compensation.apply(style, xform.scale_factor(center)),
+                geometry: vec![geometry.transform_boxed(*xform)],
+            });
+        }
+    }
+
+    /// Add a list of motifs. The parts of all the motifs are stably sorted
+    /// by z-order alone, so parts with the same z-order keep the order they
+    /// were added in, even across styles. Each run of consecutive parts
+    /// with the same style and z-order becomes one layer, styled by
+    /// styles[style_id] and placed at z_index plus that z-order.
+    pub fn add_motifs<T: Renderable + Transformable<Isogonal> + Clone + 'static>(
+        &mut self,
+        z_index: i32,
+        motifs: &[Motif<T>],
+        styles: &[Style],
+    ) {
+        let mut parts: Vec<_> = motifs.iter().flat_map(|motif| motif.parts()).collect();
+        parts.sort_by_key(|(_, _, z_order)| *z_order);
+
+        for run in parts.chunk_by(|(_, a_id, a_z), (_, b_id, b_z)| a_id == b_id && a_z == b_z) {
+            let (_, style_id, z_order) = run[0];
+            let geometry: Vec<T> = run
+                .iter()
+                .map(|(geometry, _, _)| (*geometry).clone())
+                .collect();
+            self.add_layer(z_index + z_order, styles[style_id], &geometry);
+        }
+    }
+
+    /// Overlay another scene on top of this one. The other scene is
+    /// transformed by xform, and its layers are shifted by z_offset
+    pub fn overlay(&mut self, other: &Scene, xform: impl Into<Isogonal>, z_offset: i32) {
+        let xform = xform.into();
+        for layer in other.layers.iter() {
+            let mut placed = layer.transform(xform);
+            placed.z_index += z_offset;
+            self.layers.push(placed);
+        }
+    }
+
+    /// Compose several scenes into one. Each entry is a triple of
+    /// (scene, placement transform, z_offset)
+    pub fn compose(placements: &[(Scene, Isogonal, i32)]) -> Self {
+        let mut result = Self::new();
+        for (scene, xform, z_offset) in placements {
+            result.overlay(scene, *xform, *z_offset);
+        }
+        result
+    }
+
+    /// Copy the scene, changing the style of every layer, e.g. to
+    /// override the stroke color from the command line
+    pub fn map_styles(&self, f: impl Fn(Style) -> Style) -> Self {
+        Self {
+            layers: self
+                .layers
+                .iter()
+                .map(|layer| Layer {
+                    style: f(layer.style),
+                    ..layer.clone()
+                })
+                .collect(),
+        }
+    }
+
+    /// Get the layers sorted by z_index. Layers with the same z_index
+    /// are drawn in the order they were added.
+    pub fn sorted_layers(&self) -> Vec<&Layer> {
+        let mut layers: Vec<&Layer> = self.layers.iter().collect();
+        layers.sort_by_key(|x| x.z_index);
+        layers
+    }
+}
+
+/// Baking a scene bakes each layer in z-order
+impl Renderable for Scene {
+    fn bake_geometry(&self) -> Result<Vec<RenderPrimitive>, Box<dyn Error>> {
+        let mut result = Vec::new();
+        for layer in self.sorted_layers() {
+            result.extend(layer.bake_geometry()?);
+        }
+        Ok(result)
+    }
+}
+
+impl Transformable<Isogonal> for Scene {
+    fn transform(&self, xform: Isogonal) -> Self {
+        Self {
+            layers: self.layers.iter().map(|x| x.transform(xform)).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        motifs::ghost_with_face,
+        rendering::{ColorRGB, StrokeWidth},
+        scale,
+        transformable::Cline,
+        translation,
+    };
+
+    use super::*;
+
+    fn make_scene(z_index: i32) -> Scene {
+        let mut scene = Scene::new();
+        scene.add_layer(z_index, Style::stroke(255, 0, 0), &[Cline::unit_circle()]);
+        scene
+    }
+
+    #[test]
+    pub fn sorted_layers_sorts_by_z_index() {
+        let mut scene = make_scene(5);
+        scene.add_layer(-1, Style::stroke(0, 255, 0), &[Cline::real_axis()]);
+        scene.add_layer(5, Style::stroke(0, 0, 255), &[Cline::imag_axis()]);
+
+        let result: Vec<i32> = scene.sorted_layers().iter().map(|x| x.z_index).collect();
+
+        assert_eq!(result, vec![-1, 5, 5]);
+    }
+
+    #[test]
+    pub fn overlay_offsets_z_index() {
+        let mut scene = make_scene(0);
+        let inset = make_scene(1);
+        let shift = translation(Complex::ONE).unwrap();
+
+        scene.overlay(&inset, shift, 10);
+
+        let result: Vec<i32> = scene.sorted_layers().iter().map(|x| x.z_index).collect();
+        assert_eq!(result, vec![0, 11]);
+    }
+
+    #[test]
+    pub fn add_compensated_layers_scales_width_per_transform() {
+        let mut scene = Scene::new();
+        let style = Style::stroke(255, 0, 0).with_width(1.0);
+        let compensation = StrokeCompensation::new(0.1, 2.0).unwrap();
+        let shift = translation(Complex::ONE).unwrap();
+        let shrink = scale(0.25).unwrap();
+
+        scene.add_compensated_layers(
+            0,
+            style,
+            &Cline::unit_circle(),
+            Complex::Zero,
+            &[shift.into(), shrink.into()],
+            compensation,
+        );
+
+        let result: Vec<Option<f64>> = scene
+            .sorted_layers()
+            .iter()
+            .map(|x| x.style.width.map(|width| width.value()))
+            .collect();
+        assert_eq!(result, vec![Some(1.0), Some(0.25)]);
+    }
+
+    #[test]
+    pub fn add_motifs_layers_nested_parts_by_z_order() {
+        let mut scene = Scene::new();
+        let (ghost, styles) = ghost_with_face();
+
+        scene.add_motifs(10, &[ghost.clone(), ghost], &styles);
+
+        let result: Vec<(i32, Option<ColorRGB>)> = scene
+            .sorted_layers()
+            .iter()
+            .map(|x| (x.z_index, x.style.stroke))
+            .collect();
+        assert_eq!(result, vec![(10, styles[0].stroke), (11, styles[1].stroke)]);
+    }
+
+    #[test]
+    pub fn add_motifs_keeps_insertion_order_within_z_order() {
+        let mut scene = Scene::new();
+        let styles = [Style::stroke(255, 0, 0), Style::stroke(0, 0, 255)];
+        let motif = Motif::new(vec![
+            (Cline::unit_circle(), 0),
+            (Cline::real_axis(), 1),
+            (Cline::imag_axis(), 0),
+        ]);
+
+        scene.add_motifs(0, &[motif], &styles);
+
+        let result: Vec<Option<ColorRGB>> = scene
+            .sorted_layers()
+            .iter()
+            .map(|x| x.style.stroke)
+            .collect();
+        assert_eq!(
+            result,
+            vec![styles[0].stroke, styles[1].stroke, styles[0].stroke]
+        );
+    }
+
+    #[test]
+    pub fn map_styles_changes_every_layer() {
+        let mut scene = make_scene(0);
+        scene.add_layer(1, Style::stroke(0, 255, 0), &[Cline::real_axis()]);
+
+        let result = scene.map_styles(|style| style.with_width(0.5));
+
+        assert!(result
+            .sorted_layers()
+            .iter()
+            .all(|x| x.style.width == Some(StrokeWidth::Percent(0.5))));
+        assert_eq!(
+            result.sorted_layers()[1].style.stroke,
+            Some(ColorRGB(0, 255, 0))
+        );
+    }
+
+    #[test]
+    pub fn compose_transforms_geometry() -> Result<(), Box<dyn Error>> {
+        let shift = translation(Complex::ONE).unwrap();
+
+        let scene = Scene::compose(&[(make_scene(0), shift.into(), 0)]);
+
+        let baked = scene.sorted_layers()[0].bake_geometry()?;
+        match baked[..] {
+            [RenderPrimitive::Circle(circle)] => assert_eq!(circle.center, Complex::ONE),
+            _ => panic!("expected a single circle"),
+        }
+        Ok(())
+    }
+}
@@ -1,8 +1,17 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ColorRGB(pub u8, pub u8, pub u8);
 
+impl ColorRGB {
+    /// Linearly interpolate between two colors. t is clamped to [0, 1]
+    pub fn lerp(a: Self, b: Self, t: f64) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |x: u8, y: u8| (x as f64 + t * (y as f64 - x as f64)).round() as u8;
+        Self(mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+    }
+}
+
 impl Display for ColorRGB {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let Self(r, g, b) = self;
@@ -10,11 +19,133 @@ impl Display for ColorRGB {
     }
 }
 
-#[derive(Clone, Copy)]
+/// Parse a hex color like #ff7f00. The # is optional
+impl FromStr for ColorRGB {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.trim().trim_start_matches('#');
+        let error = || format!("expected a color like #ff7f00, got {}", s);
+        if hex.len() != 6 || !hex.is_ascii() {
+            return Err(error());
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| error());
+        Ok(Self(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+/// Stroke width, either relative to the output image or absolute
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StrokeWidth {
+    /// Percent of the SVG viewport. This looks the same in every view,
+    /// since each view is scaled to fill the card
+    Percent(f64),
+    /// Absolute width in the same coordinates as the geometry (SVG user
+    /// units). Zooming in on a view makes the strokes thicker
+    Units(f64),
+    /// Physical width on the rendered card, e.g. for plotter pens
+    Millimeters(f64),
+}
+
+impl StrokeWidth {
+    pub fn value(&self) -> f64 {
+        match self {
+            Self::Percent(x) | Self::Units(x) | Self::Millimeters(x) => *x,
+        }
+    }
+
+    /// Same units, different value
+    pub fn with_value(&self, value: f64) -> Self {
+        match self {
+            Self::Percent(_) => Self::Percent(value),
+            Self::Units(_) => Self::Units(value),
+            Self::Millimeters(_) => Self::Millimeters(value),
+        }
+    }
+
+    /// Convert a physical width to a percent of an image of width x height
+    /// pixels. Other units don't depend on the image size so they are
+    /// unchanged
+    pub fn for_image_size(&self, width: f64, height: f64) -> Self {
+        const MM_PER_INCH: f64 = 25.4;
+        // CSS pixels are defined as 1/96 of an inch
+        const PX_PER_INCH: f64 = 96.0;
+
+        match self {
+            Self::Millimeters(mm) => {
+                // SVG percentages are relative to the normalized diagonal
+                // sqrt((w^2 + h^2) / 2) of the viewport
+                let diagonal = (0.5 * (width * width + height * height)).sqrt();
+                let px = mm / MM_PER_INCH * PX_PER_INCH;
+                Self::Percent(100.0 * px / diagonal)
+            }
+            _ => *self,
+        }
+    }
+}
+
+/// Arrowhead drawn at the end of each line segment and arc, pointing
+/// in the direction of the edge (see DirectedEdge). Circles and points
+/// have no end, so they never get arrows
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Arrow {
+    /// Length of the arrowhead in multiples of the stroke width
+    pub size: f64,
+}
+
+/// Shape of the ends of open strokes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineCap {
+    Butt,
+    Round,
+    Square,
+}
+
+impl LineCap {
+    /// Value of the SVG stroke-linecap attribute
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Butt => "butt",
+            Self::Round => "round",
+            Self::Square => "square",
+        }
+    }
+}
+
+/// Shape of the corners where path segments meet
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+impl LineJoin {
+    /// Value of the SVG stroke-linejoin attribute
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Miter => "miter",
+            Self::Round => "round",
+            Self::Bevel => "bevel",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Style {
     pub stroke: Option<ColorRGB>,
     pub fill: Option<ColorRGB>,
-    pub width_percent: Option<f64>,
+    pub width: Option<StrokeWidth>,
+    pub arrow: Option<Arrow>,
+    /// Lengths of the dashes and the gaps between them. None is a solid
+    /// stroke
+    pub stroke_dasharray: Option<[StrokeWidth; 2]>,
+    /// Opacities in [0, 1]. None is fully opaque
+    pub stroke_opacity: Option<f64>,
+    pub fill_opacity: Option<f64>,
+    pub line_cap: Option<LineCap>,
+    pub line_join: Option<LineJoin>,
 }
 
 impl Style {
@@ -22,25 +153,148 @@ impl Style {
         Self {
             stroke: None,
             fill: None,
-            width_percent: None,
+            width: None,
+            arrow: None,
+            stroke_dasharray: None,
+            stroke_opacity: None,
+            fill_opacity: None,
+            line_cap: None,
+            line_join: None,
         }
     }
 
     pub fn stroke(r: u8, g: u8, b: u8) -> Self {
         Self {
             stroke: Some(ColorRGB(r, g, b)),
-            fill: None,
-            width_percent: None,
+            ..Self::new()
+        }
+    }
+
+    /// Style for text, which is filled rather than stroked
+    pub fn text(r: u8, g: u8, b: u8) -> Self {
+        Self {
+            fill: Some(ColorRGB(r, g, b)),
+            ..Self::new()
         }
     }
 
+    /// Set the stroke width as a percent of the viewport
     pub fn with_width(&self, width: f64) -> Self {
+        self.with_stroke_width(StrokeWidth::Percent(width))
+    }
+
+    pub fn with_stroke_width(&self, width: StrokeWidth) -> Self {
+        Self {
+            width: Some(width),
+            ..*self
+        }
+    }
+
+    /// Add arrowheads to directed edges. size is the length of the
+    /// arrowhead in multiples of the stroke width
+    pub fn with_arrow(&self, size: f64) -> Self {
         Self {
-            stroke: self.stroke,
-            fill: self.fill,
-            width_percent: Some(width),
+            arrow: Some(Arrow { size }),
+            ..*self
         }
     }
+
+    /// Dashed stroke, with dash and gap lengths as a percent of the
+    /// viewport like with_width()
+    pub fn with_dashes(&self, dash: f64, gap: f64) -> Self {
+        self.with_dasharray(StrokeWidth::Percent(dash), StrokeWidth::Percent(gap))
+    }
+
+    pub fn with_dasharray(&self, dash: StrokeWidth, gap: StrokeWidth) -> Self {
+        Self {
+            stroke_dasharray: Some([dash, gap]),
+            ..*self
+        }
+    }
+
+    /// Dotted stroke: zero-length dashes with round caps, so each dot is
+    /// as wide as the stroke. gap is a percent of the viewport
+    pub fn with_dots(&self, gap: f64) -> Self {
+        self.with_dashes(0.0, gap).with_line_cap(LineCap::Round)
+    }
+
+    /// Set the stroke opacity, clamped to [0, 1]
+    pub fn with_stroke_opacity(&self, opacity: f64) -> Self {
+        Self {
+            stroke_opacity: Some(opacity.clamp(0.0, 1.0)),
+            ..*self
+        }
+    }
+
+    /// Convert physical stroke widths and dashes to percents of an image
+    /// of width x height pixels, see StrokeWidth::for_image_size()
+    pub fn for_image_size(&self, width: f64, height: f64) -> Self {
+        Self {
+            width: self.width.map(|x| x.for_image_size(width, height)),
+            stroke_dasharray: self
+                .stroke_dasharray
+                .map(|dashes| dashes.map(|x| x.for_image_size(width, height))),
+            ..*self
+        }
+    }
+
+    /// Set the fill opacity, clamped to [0, 1]
+    pub fn with_fill_opacity(&self, opacity: f64) -> Self {
+        Self {
+            fill_opacity: Some(opacity.clamp(0.0, 1.0)),
+            ..*self
+        }
+    }
+
+    pub fn with_line_cap(&self, line_cap: LineCap) -> Self {
+        Self {
+            line_cap: Some(line_cap),
+            ..*self
+        }
+    }
+
+    pub fn with_line_join(&self, line_join: LineJoin) -> Self {
+        Self {
+            line_join: Some(line_join),
+            ..*self
+        }
+    }
+}
+
+/// Scale stroke widths by how much a transform shrinks or grows the
+/// geometry, so tiny tiles deep in a tiling or IFS don't turn into blobs
+/// of stroke. The result is clamped to [min_width, max_width] (in the
+/// style's own units) so strokes neither vanish nor swamp the large tiles.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StrokeCompensation {
+    pub min_width: f64,
+    pub max_width: f64,
+}
+
+impl StrokeCompensation {
+    pub fn new(min_width: f64, max_width: f64) -> Result<Self, String> {
+        if !(min_width > 0.0 && min_width <= max_width) {
+            return Err(format!(
+                "need 0 < min_width <= max_width, got {} and {}",
+                min_width, max_width
+            ));
+        }
+
+        Ok(Self {
+            min_width,
+            max_width,
+        })
+    }
+
+    /// Scale the style's stroke width by a local scale factor, e.g. from
+    /// Isogonal::scale_factor(). Styles without a width are unchanged
+    pub fn apply(&self, style: Style, scale_factor: f64) -> Style {
+        let width = style.width.map(|width| {
+            width.with_value((width.value() * scale_factor).clamp(self.min_width, self.max_width))
+        });
+
+        Style { width, ..style }
+    }
 }
 
 impl Default for Style {
@@ -48,3 +302,112 @@ impl Default for Style {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn color_from_str_parses_hex() {
+        let with_hash: ColorRGB = "#ff7f00".parse().unwrap();
+        let without_hash: ColorRGB = "20a0FF".parse().unwrap();
+
+        assert_eq!(with_hash, ColorRGB(255, 127, 0));
+        assert_eq!(without_hash, ColorRGB(32, 160, 255));
+    }
+
+    #[test]
+    pub fn color_from_str_with_invalid_text_returns_error() {
+        for text in ["#fff", "#gg0000", "orange", "#ff7f00ff"] {
+            let result: Result<ColorRGB, String> = text.parse();
+
+            assert!(result.is_err(), "{} should not parse", text);
+        }
+    }
+
+    #[test]
+    pub fn with_dots_uses_round_caps() {
+        let result = Style::stroke(0, 0, 0).with_dots(0.5);
+
+        assert_eq!(
+            result.stroke_dasharray,
+            Some([StrokeWidth::Percent(0.0), StrokeWidth::Percent(0.5)])
+        );
+        assert_eq!(result.line_cap, Some(LineCap::Round));
+    }
+
+    #[test]
+    pub fn with_opacity_clamps_to_unit_interval() {
+        let result = Style::new()
+            .with_stroke_opacity(1.5)
+            .with_fill_opacity(-0.5);
+
+        assert_eq!(result.stroke_opacity, Some(1.0));
+        assert_eq!(result.fill_opacity, Some(0.0));
+    }
+
+    #[test]
+    pub fn for_image_size_converts_millimeters() {
+        // The normalized diagonal of a 960x960 image is 960px, or 10 inches
+        let style = Style::stroke(0, 0, 0)
+            .with_stroke_width(StrokeWidth::Millimeters(25.4))
+            .with_dasharray(StrokeWidth::Millimeters(25.4), StrokeWidth::Units(1.0));
+
+        let result = style.for_image_size(960.0, 960.0);
+
+        assert_eq!(result.width, Some(StrokeWidth::Percent(10.0)));
+        assert_eq!(
+            result.stroke_dasharray,
+            Some([StrokeWidth::Percent(10.0), StrokeWidth::Units(1.0)])
+        );
+    }
+
+    #[test]
+    pub fn stroke_compensation_new_with_invalid_range_returns_error() {
+        let result = StrokeCompensation::new(1.0, 0.5);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn stroke_compensation_scales_width() {
+        let compensation = StrokeCompensation::new(0.01, 1.0).unwrap();
+        let style = Style::stroke(255, 0, 0).with_width(0.5);
+
+        let result = compensation.apply(style, 0.5);
+
+        assert_eq!(result.width, Some(StrokeWidth::Percent(0.25)));
+        assert_eq!(result.stroke, style.stroke);
+    }
+
+    #[test]
+    pub fn stroke_compensation_clamps_width() {
+        let compensation = StrokeCompensation::new(0.1, 1.0).unwrap();
+        let style = Style::stroke(255, 0, 0).with_width(0.5);
+
+        let tiny = compensation.apply(style, 1e-6);
+        let huge = compensation.apply(style, 100.0);
+
+        assert_eq!(tiny.width, Some(StrokeWidth::Percent(0.1)));
+        assert_eq!(huge.width, Some(StrokeWidth::Percent(1.0)));
+    }
+
+    #[test]
+    pub fn stroke_compensation_keeps_units() {
+        let compensation = StrokeCompensation::new(0.1, 1.0).unwrap();
+        let style = Style::stroke(255, 0, 0).with_stroke_width(StrokeWidth::Millimeters(0.8));
+
+        let result = compensation.apply(style, 0.5);
+
+        assert_eq!(result.width, Some(StrokeWidth::Millimeters(0.4)));
+    }
+
+    #[test]
+    pub fn stroke_compensation_ignores_style_without_width() {
+        let compensation = StrokeCompensation::new(0.1, 1.0).unwrap();
+
+        let result = compensation.apply(Style::stroke(0, 0, 0), 0.5);
+
+        assert_eq!(result.width, None);
+    }
+}
@@ -0,0 +1,244 @@
+//! Spherical counterpart to hyperbolic_tilings. A {p, q} tiling of the
+//! sphere with (p - 2)(q - 2) < 4 is one of the Platonic solids blown up
+//! onto its circumscribed sphere, and stereographic projection turns it
+//! into a tiling of the plane by circular triangles. The face center is
+//! at 0 and the sphere's equator is the unit circle.
+use std::f64::consts::{PI, TAU};
+
+use crate::{
+    algorithms::GroupIFS,
+    finite_subgroups::FiniteSubgroup,
+    geometry::{ArcAngles, Circle, CircularArc, LineSegment},
+    isogonal::Isogonal,
+    rotation,
+    transformable::{ClineArcTile, Transformable},
+    Complex,
+};
+
+fn check_spherical(p: usize, q: usize) -> Result<(), String> {
+    if p < 3 {
+        return Err(String::from("p must be at least 3"));
+    }
+
+    if q < 3 {
+        return Err(String::from("q must be at least 3"));
+    }
+
+    if (p - 2) * (q - 2) >= 4 {
+        return Err(String::from(
+            "To make a spherical tiling, (p - 2)(q - 2) must be less than 4",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Number of rotations of the sphere that preserve the {p, q} tiling.
+/// This is twice the number of edges, 4pq / (4 - (p - 2)(q - 2))
+fn rotation_order(p: usize, q: usize) -> usize {
+    4 * p * q / (4 - (p - 2) * (q - 2))
+}
+
+fn compute_edge_circle(p: usize, q: usize) -> Circle {
+    let angle_p = PI / (p as f64);
+    let angle_q = PI / (q as f64);
+
+    // Third mirror: circle invert in a circle with the following properties:
+    // 1. The circle is centered on the negative real line
+    // 2. The circle is a great circle of the sphere, so it meets the unit
+    //    circle at antipodal points, i.e. radius^2 = center^2 + 1
+    // 3. The circle intersects the line for r_conj making angle pi/q
+    // This is the same algebra as the hyperbolic case except for the sign
+    // in condition 2, which gives
+    //
+    // center = -cos(pi/q) * K
+    // radius = sin(pi/p) * K
+    // where K = sqrt(1 / (sin^2(pi/p) - cos^2(pi/q)))
+    let cos_q = angle_q.cos();
+    let sin_p = angle_p.sin();
+    let k = (1.0 / (sin_p * sin_p - cos_q * cos_q)).sqrt();
+    let center = -cos_q * k;
+    let radius = sin_p * k;
+
+    Circle::new(Complex::from(center), radius)
+}
+
+/// The three mirrors of the fundamental triangle of a {p, q} spherical
+/// tiling, in the same order as hyperbolic_tilings::reflection_group():
+/// the edge bisector (the real line), the line through a vertex at angle
+/// pi/p, and the great circle through the edge.
+pub fn reflection_group(p: usize, q: usize) -> Result<(Isogonal, Isogonal, Isogonal), String> {
+    check_spherical(p, q)?;
+
+    let conj = Isogonal::conj();
+    let rot_p = rotation(TAU / (p as f64)).unwrap();
+    let r_conj = Isogonal::from(rot_p) * conj;
+    let e2_conj = Isogonal::circle_inversion(compute_edge_circle(p, q))?;
+
+    Ok((conj, r_conj, e2_conj))
+}
+
+/// Rotation group of the Platonic solid with Schlafli symbol {p, q},
+/// generated by a p-fold rotation about the face center and a half turn
+/// about the edge midpoint. This is conjugate to one of the groups in
+/// finite_subgroups, but lined up with get_fundamental_region()
+pub fn rotation_group(p: usize, q: usize) -> Result<FiniteSubgroup, String> {
+    let (conj, r_conj, e2_conj) = reflection_group(p, q)?;
+
+    let generators = [r_conj * conj, e2_conj * conj]
+        .into_iter()
+        .map(|x| match x {
+            Isogonal::Conformal(m) => m,
+            Isogonal::AntiConformal(_) => unreachable!("product of two reflections"),
+        })
+        .collect();
+
+    Ok(FiniteSubgroup {
+        generators,
+        order: rotation_order(p, q),
+    })
+}
+
+/// Fundamental triangle of the {p, q} tiling with its corners at the face
+/// center, the edge midpoint and a vertex, in that order. Reflecting it
+/// in the mirrors from reflection_group() covers the sphere with
+/// 2 * rotation_group(p, q).order triangles
+pub fn get_fundamental_region(
+    p: usize,
+    q: usize,
+) -> Result<(ClineArcTile, (Complex, Complex, Complex)), String> {
+    check_spherical(p, q)?;
+
+    let center = Complex::Zero;
+
+    // The edge circle is to the left of the origin but big enough to
+    // contain it, so it crosses the positive real axis at the edge midpoint
+    let edge_circle = compute_edge_circle(p, q);
+    let edge_midpoint = edge_circle.center + edge_circle.radius.into();
+
+    // Solve |t e^(i pi/p) - center|^2 = radius^2 for t > 0. Since
+    // radius^2 - center^2 = 1, this is t^2 + 2 t |center| cos(pi/p) - 1 = 0
+    let angle_p = PI / (p as f64);
+    let offset = edge_circle.center.real().abs() * angle_p.cos();
+    let t = -offset + (offset * offset + 1.0).sqrt();
+    let vertex = Complex::from_polar(t, angle_p);
+
+    let angle_to_vertex = (vertex - edge_circle.center)
+        .arg()
+        .ok_or_else(|| String::from("vertex is at the center of the edge circle"))?;
+
+    let edge_bisector = LineSegment::new(center, edge_midpoint);
+    let angles = ArcAngles::new(0.0, angle_to_vertex).unwrap();
+    let edge = CircularArc::new(edge_circle, angles);
+    let angle_bisector = LineSegment::new(vertex, center);
+
+    Ok((
+        ClineArcTile::new(vec![
+            edge_bisector.into(),
+            edge.into(),
+            angle_bisector.into(),
+        ]),
+        (center, edge_midpoint, vertex),
+    ))
+}
+
+/// Apply every symmetry of the {p, q} tiling to a motif, including
+/// reflections, so each element of the group is used exactly once. The
+/// motif itself comes first
+pub fn apply_symmetries<T: Transformable<Isogonal>>(
+    p: usize,
+    q: usize,
+    motif: &T,
+) -> Result<Vec<T>, String> {
+    let (conj, r_conj, e2_conj) = reflection_group(p, q)?;
+    let ifs = GroupIFS::new(vec![conj, r_conj, e2_conj]);
+
+    // The full group has 2 * rotation_order() elements, and none of them
+    // needs a longer word than that
+    let max_depth = 2 * rotation_order(p, q);
+    Ok(ifs.apply_unique(motif, 0, max_depth, Isogonal::quantized_key))
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::FRAC_PI_8;
+
+    use test_case::test_case;
+
+    use crate::{nearly::assert_nearly, transformable::Cline, Mobius};
+
+    use super::*;
+
+    #[test_case(3, 7; "hyperbolic")]
+    #[test_case(4, 4; "euclidean")]
+    #[test_case(2, 5; "degenerate")]
+    pub fn reflection_group_rejects_non_spherical_tilings(p: usize, q: usize) {
+        let result = reflection_group(p, q);
+
+        assert!(result.is_err());
+    }
+
+    #[test_case(3, 3, 12; "tetrahedron")]
+    #[test_case(4, 3, 24; "cube")]
+    #[test_case(3, 4, 24; "octahedron")]
+    #[test_case(5, 3, 60; "dodecahedron")]
+    #[test_case(3, 5, 60; "icosahedron")]
+    pub fn rotation_group_has_correct_order(p: usize, q: usize, expected: usize) {
+        let group = rotation_group(p, q).unwrap();
+
+        // FiniteGroup compares elements with is_nearly(), which is too
+        // strict once rounding error builds up in the products
+        let ifs = GroupIFS::new(group.generators);
+        let result = ifs.bfs_unique(expected, Mobius::quantized_key);
+
+        assert_eq!(group.order, expected);
+        assert_eq!(result.len(), expected);
+    }
+
+    #[test_case(3, 3)]
+    #[test_case(4, 3)]
+    #[test_case(3, 5)]
+    pub fn mirrors_fix_corners_of_fundamental_region(p: usize, q: usize) {
+        let (conj, r_conj, e2_conj) = reflection_group(p, q).unwrap();
+        let (_, (center, midpoint, vertex)) = get_fundamental_region(p, q).unwrap();
+
+        assert_eq!(conj * center, center);
+        assert_eq!(conj * midpoint, midpoint);
+        assert_eq!(r_conj * center, center);
+        assert_eq!(r_conj * vertex, vertex);
+        assert_eq!(e2_conj * midpoint, midpoint);
+        assert_eq!(e2_conj * vertex, vertex);
+    }
+
+    #[test]
+    pub fn cube_edge_midpoint_matches_stereographic_projection() {
+        let (_, (_, midpoint, _)) = get_fundamental_region(4, 3).unwrap();
+
+        // The edge midpoint is 45 degrees from the face center, and
+        // stereographic projection maps that to tan(45/2 degrees)
+        assert_nearly(midpoint.real(), FRAC_PI_8.tan());
+        assert_nearly(midpoint.imag(), 0.0);
+    }
+
+    #[test]
+    pub fn edge_circle_is_a_great_circle() {
+        let edge = Cline::from(compute_edge_circle(5, 3));
+        let unit_circle = Cline::unit_circle();
+
+        let points = edge.intersection_points(&unit_circle).unwrap();
+
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0], -points[1]);
+    }
+
+    #[test_case(3, 3)]
+    #[test_case(3, 4)]
+    #[test_case(5, 3)]
+    pub fn apply_symmetries_covers_sphere_once(p: usize, q: usize) {
+        let (tile, _) = get_fundamental_region(p, q).unwrap();
+
+        let result = apply_symmetries(p, q, &tile).unwrap();
+
+        assert_eq!(result.len(), 2 * rotation_order(p, q));
+    }
+}
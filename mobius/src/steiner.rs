@@ -0,0 +1,242 @@
+use std::{f64::consts::PI, ops::Range};
+
+use crate::{
+    geometry::{Circle, GeneralizedCircle},
+    transformable::{Cline, Transformable},
+    Complex, Mobius,
+};
+
+/// A closed Steiner chain: n circles, each tangent to an outer circle, an
+/// inner circle nested inside it, and its two neighbors in the chain.
+/// Steiner's porism says that if one chain closes up for a pair of
+/// circles, then a chain starting from any circle tangent to both closes
+/// up too, which is what the phase parameter animates.
+///
+/// See https://en.wikipedia.org/wiki/Steiner_chain
+#[derive(Clone, Debug)]
+pub struct SteinerChain {
+    pub outer: Circle,
+    pub inner: Circle,
+    pub circles: Vec<Circle>,
+}
+
+impl SteinerChain {
+    /// Concentric chain of n equal circles between the unit circle and an
+    /// inner circle centered at the origin. Adjacent chain circles touch
+    /// when sin(pi/n) = (1 - r) / (1 + r), which fixes the inner radius r.
+    /// The phase rotates the chain around the annulus.
+    pub fn concentric(n: usize, phase: f64) -> Result<Self, String> {
+        if n < 3 {
+            return Err(String::from("n must be at least 3"));
+        }
+
+        let s = (PI / n as f64).sin();
+        let inner_radius = (1.0 - s) / (1.0 + s);
+        let chain_radius = 0.5 * (1.0 - inner_radius);
+        let orbit_radius = 0.5 * (1.0 + inner_radius);
+
+        let circles = (0..n)
+            .map(|i| {
+                let angle = phase + 2.0 * PI * (i as f64) / (n as f64);
+                Circle::new(Complex::from_polar(orbit_radius, angle), chain_radius)
+            })
+            .collect();
+
+        Ok(Self {
+            outer: Circle::unit_circle(),
+            inner: Circle::new(Complex::Zero, inner_radius),
+            circles,
+        })
+    }
+
+    /// Steiner chain of n circles inside the unit circle, with the inner
+    /// circle pushed off center. This is the concentric chain moved by the
+    /// disk automorphism z -> (z + offset) / (1 + offset z), so offset
+    /// must be in (-1, 1). Larger offsets squeeze the chain circles on one
+    /// side and swell them on the other.
+    pub fn new(n: usize, phase: f64, offset: f64) -> Result<Self, String> {
+        if offset.abs() >= 1.0 {
+            return Err(String::from("offset must be in (-1, 1)"));
+        }
+
+        let xform =
+            Mobius::from_unnormalized(Complex::ONE, offset.into(), offset.into(), Complex::ONE)?;
+        Self::concentric(n, phase)?.transform(xform)
+    }
+
+    /// Apply a Mobius transformation to every circle in the chain. Tangency
+    /// is preserved, so the result is still a Steiner chain. This returns
+    /// an error if any of the circles would map to a line.
+    pub fn transform(&self, xform: Mobius) -> Result<Self, String> {
+        Ok(Self {
+            outer: transform_circle(self.outer, xform)?,
+            inner: transform_circle(self.inner, xform)?,
+            circles: self
+                .circles
+                .iter()
+                .map(|x| transform_circle(*x, xform))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// The outer circle, inner circle and chain circles, in that order,
+    /// for rendering them all in one layer
+    pub fn all_circles(&self) -> Vec<Circle> {
+        let mut result = vec![self.outer, self.inner];
+        result.extend_from_slice(&self.circles);
+        result
+    }
+}
+
+fn transform_circle(circle: Circle, xform: Mobius) -> Result<Circle, String> {
+    match Cline::from(circle).transform(xform).classify() {
+        Ok(GeneralizedCircle::Circle(x)) => Ok(x),
+        Ok(GeneralizedCircle::Line(_)) => Err(format!(
+            "{} maps through infinity, so the image is a line",
+            circle
+        )),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Chain of circles spiraling around the origin, where circle j is the
+/// image of circle 0 under z -> k^j z, for j in the range of powers. The
+/// radius is chosen so each circle touches the next one, so for complex k
+/// this traces a loxodromic spiral of kissing circles, and for |k| = 1 it
+/// is a necklace of equal circles.
+///
+/// Conjugate with a Mobius map to move the fixed points away from 0 and
+/// infinity.
+pub fn loxodromic_chain(
+    k: Complex,
+    start: Complex,
+    powers: Range<i32>,
+) -> Result<Vec<Circle>, String> {
+    if k == Complex::Zero || k == Complex::Infinity || k == Complex::ONE {
+        return Err(String::from("k must be finite, nonzero, and not 1"));
+    }
+
+    if start == Complex::Zero || start == Complex::Infinity {
+        return Err(String::from("start must be finite and nonzero"));
+    }
+
+    // The first circle has center c and radius r, the next has center kc
+    // and radius |k| r. Tangent circles have |kc - c| = r + |k| r
+    let scale = k.mag();
+    let radius = (k - Complex::ONE).mag() * start.mag() / (1.0 + scale);
+    // k was checked to be nonzero, so it has an argument
+    let angle = k.arg_or_zero();
+
+    Ok(powers
+        .map(|j| {
+            let power = Complex::from_polar(scale.powi(j), angle * j as f64);
+            Circle::new(power * start, scale.powi(j) * radius)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::nearly::is_nearly;
+
+    use super::*;
+
+    /// Relative tolerance for tangency checks, since the chain circles go
+    /// through a Mobius transformation
+    const TOLERANCE: f64 = 1e-9;
+
+    fn externally_tangent(a: Circle, b: Circle) -> bool {
+        let distance = (a.center - b.center).mag();
+        (distance - (a.radius + b.radius)).abs() < TOLERANCE * distance
+    }
+
+    fn internally_tangent(outer: Circle, inner: Circle) -> bool {
+        let distance = (outer.center - inner.center).mag();
+        (distance - (outer.radius - inner.radius)).abs() < TOLERANCE * outer.radius
+    }
+
+    fn assert_is_steiner_chain(chain: &SteinerChain) {
+        let n = chain.circles.len();
+        for (i, &circle) in chain.circles.iter().enumerate() {
+            let next = chain.circles[(i + 1) % n];
+            assert!(
+                externally_tangent(circle, next),
+                "circles {} and {}",
+                i,
+                i + 1
+            );
+            assert!(internally_tangent(chain.outer, circle), "outer and {}", i);
+            assert!(externally_tangent(chain.inner, circle), "inner and {}", i);
+        }
+    }
+
+    #[test]
+    pub fn concentric_with_too_few_circles_returns_error() {
+        let result = SteinerChain::concentric(2, 0.0);
+
+        assert!(result.is_err_and(|e| e.contains("at least 3")));
+    }
+
+    #[test_case(3; "three circles")]
+    #[test_case(6; "six circles")]
+    #[test_case(11; "eleven circles")]
+    pub fn concentric_chain_closes(n: usize) {
+        let chain = SteinerChain::concentric(n, 0.3).unwrap();
+
+        assert_eq!(chain.circles.len(), n);
+        assert_is_steiner_chain(&chain);
+    }
+
+    #[test_case(0.0, 0.5; "no phase")]
+    #[test_case(1.0, 0.5; "rotated")]
+    #[test_case(2.5, -0.8; "offset left")]
+    pub fn offset_chain_closes(phase: f64, offset: f64) {
+        let chain = SteinerChain::new(7, phase, offset).unwrap();
+
+        assert_eq!(chain.outer, Circle::unit_circle());
+        assert!(!is_nearly(chain.inner.center.mag(), 0.0));
+        assert_is_steiner_chain(&chain);
+    }
+
+    #[test]
+    pub fn new_with_bad_offset_returns_error() {
+        let result = SteinerChain::new(5, 0.0, 1.0);
+
+        assert!(result.is_err_and(|e| e.contains("offset")));
+    }
+
+    #[test]
+    pub fn transform_through_infinity_returns_error() {
+        let chain = SteinerChain::concentric(4, 0.0).unwrap();
+        // Sends the point 1 on the outer circle to infinity
+        let xform =
+            Mobius::from_unnormalized(Complex::ONE, Complex::Zero, -Complex::ONE, Complex::ONE)
+                .unwrap();
+
+        let result = chain.transform(xform);
+
+        assert!(result.is_err_and(|e| e.contains("line")));
+    }
+
+    #[test_case(Complex::new(1.2, 0.3); "loxodromic")]
+    #[test_case(Complex::new(0.8, 0.0); "hyperbolic")]
+    #[test_case(Complex::from_polar(1.0, 0.5); "elliptic")]
+    pub fn loxodromic_chain_circles_touch_neighbors(k: Complex) {
+        let circles = loxodromic_chain(k, Complex::ONE, -3..4).unwrap();
+
+        assert_eq!(circles.len(), 7);
+        assert_eq!(circles[3].center, Complex::ONE);
+        for pair in circles.windows(2) {
+            assert!(externally_tangent(pair[0], pair[1]));
+        }
+    }
+
+    #[test]
+    pub fn loxodromic_chain_with_identity_returns_error() {
+        let result = loxodromic_chain(Complex::ONE, Complex::ONE, 0..5);
+
+        assert!(result.is_err_and(|e| e.contains("not 1")));
+    }
+}
@@ -1,18 +1,30 @@
 use core::f64;
-use std::{f64::consts::PI, path};
+use std::{error::Error, f64::consts::PI, path, str::FromStr};
 
 use svg::{
-    node::element::{path::Data, Circle as SvgCircle, Group, Line as SvgLine, Path, Rectangle},
+    node::element::{
+        path::Data, Circle as SvgCircle, Definitions, Group, Line as SvgLine, Marker, Path,
+        Rectangle, Text, SVG,
+    },
+    node::Children,
     Document, Node,
 };
 
 use crate::{
-    geometry::{ArcAngles, ArcDirection, Circle, CircularArc, DirectedEdge, LineSegment},
-    rendering::{RenderPrimitive, Renderable, Style},
-    transformable::{Cline, ClineTile, Motif},
+    algorithms::DensityGrid,
+    geometry::{
+        ArcAngles, ArcDirection, Circle, CircularArc, CubicBezier, DirectedEdge, LineSegment,
+        QuadraticBezier,
+    },
+    isogonal::Isogonal,
+    rendering::{
+        clip_primitives, cull_primitives, write_scene_json, Arrow, ClipRegion, ColorRGB,
+        PrimitiveLayer, RenderPrimitive, Renderable, Scene, StrokeWidth, Style, TextAnchor,
+    },
+    transformable::{Cline, ClineTile, Motif, Transformable},
     Complex,
 };
-pub struct SvgNode(Box<dyn Node>);
+pub struct SvgNode(pub(crate) Box<dyn Node>);
 
 fn svg_circle(circle: Circle) -> Box<dyn Node> {
     let Circle { center, radius } = circle;
@@ -67,6 +79,53 @@ fn svg_line_segment(line: LineSegment) -> Box<dyn Node> {
     )
 }
 
+fn svg_quadratic_bezier(curve: QuadraticBezier) -> Box<dyn Node> {
+    let QuadraticBezier {
+        start,
+        control,
+        end,
+    } = curve;
+    let data = Data::new()
+        .move_to((start.real(), start.imag()))
+        .quadratic_curve_to((control.real(), control.imag(), end.real(), end.imag()));
+
+    Box::new(Path::new().set("d", data))
+}
+
+fn svg_cubic_bezier(curve: CubicBezier) -> Box<dyn Node> {
+    let CubicBezier {
+        start,
+        control1,
+        control2,
+        end,
+    } = curve;
+    let data = Data::new()
+        .move_to((start.real(), start.imag()))
+        .cubic_curve_to((
+            control1.real(),
+            control1.imag(),
+            control2.real(),
+            control2.imag(),
+            end.real(),
+            end.imag(),
+        ));
+
+    Box::new(Path::new().set("d", data))
+}
+
+/// Text is drawn inside flip_y() like the rest of the geometry, so it is
+/// flipped back to be upright, which also flips the y-coordinate
+fn svg_text(position: Complex, content: String, size: f64, anchor: TextAnchor) -> Box<dyn Node> {
+    Box::new(
+        Text::new(content)
+            .set("x", position.real())
+            .set("y", -position.imag())
+            .set("font-size", size)
+            .set("text-anchor", anchor.name())
+            .set("transform", "scale(1, -1)"),
+    )
+}
+
 fn svg_point(z: Complex) -> Box<dyn Node> {
     const POINT_RADIUS: &str = "0.25%";
     Box::new(
@@ -84,6 +143,21 @@ impl From<RenderPrimitive> for SvgNode {
             RenderPrimitive::Circle(circle) => SvgNode(svg_circle(circle)),
             RenderPrimitive::LineSegment(line_segment) => SvgNode(svg_line_segment(line_segment)),
             RenderPrimitive::CircularArc(circular_arc) => SvgNode(svg_circular_arc(circular_arc)),
+            RenderPrimitive::QuadraticBezier(curve) => SvgNode(svg_quadratic_bezier(curve)),
+            RenderPrimitive::CubicBezier(curve) => SvgNode(svg_cubic_bezier(curve)),
+            RenderPrimitive::Layer(PrimitiveLayer {
+                name, mut children, ..
+            }) => {
+                RenderPrimitive::sort_layers(&mut children);
+                let group = Group::new().set("id", name);
+                SvgNode(Box::new(add_geometry(group, children)))
+            }
+            RenderPrimitive::Text {
+                position,
+                content,
+                size,
+                anchor,
+            } => SvgNode(svg_text(position, content, size, anchor)),
         }
     }
 }
@@ -105,11 +179,18 @@ impl From<Vec<SvgNode>> for SvgNodes {
     }
 }
 
+/// Convert primitives in drawing order, see RenderPrimitive::sort_layers()
+impl From<Vec<RenderPrimitive>> for SvgNodes {
+    fn from(mut value: Vec<RenderPrimitive>) -> Self {
+        RenderPrimitive::sort_layers(&mut value);
+        let nodes: Vec<SvgNode> = value.into_iter().map(SvgNode::from).collect();
+        nodes.into()
+    }
+}
+
 impl<T: Renderable> From<&T> for SvgNodes {
     fn from(value: &T) -> Self {
-        let baked = value.bake_geometry().unwrap();
-        let nodes: Vec<SvgNode> = baked.iter().map(|x| SvgNode::from(*x)).collect();
-        nodes.into()
+        value.bake_geometry().unwrap().into()
     }
 }
 
@@ -132,13 +213,125 @@ pub fn add_geometry(group: Group, geometry: impl Into<SvgNodes>) -> Group {
     nodes.into_iter().fold(group, |group, x| group.add(x))
 }
 
+/// SVG stroke-width attribute for a stroke width. SVG percentages are
+/// relative to the normalized diagonal sqrt((w^2 + h^2) / 2) of the
+/// viewport. Physical widths should be converted with
+/// Style::for_image_size() first, otherwise they assume the default card
+/// size from RenderConfig
+pub(crate) fn svg_stroke_width(width: StrokeWidth) -> String {
+    match width.for_image_size(CARD_WIDTH, CARD_HEIGHT) {
+        StrokeWidth::Percent(percent) => format!("{}%", percent),
+        StrokeWidth::Units(units) => units.to_string(),
+        StrokeWidth::Millimeters(_) => unreachable!("converted to a percent"),
+    }
+}
+
+/// Dash and gap lengths in the same form as stroke-width
+pub(crate) fn svg_dasharray([dash, gap]: [StrokeWidth; 2]) -> String {
+    format!("{} {}", svg_stroke_width(dash), svg_stroke_width(gap))
+}
+
+/// Id of the arrowhead marker for a stroke color and arrow. Styles that
+/// draw the same arrowhead share an id, so duplicate definitions are
+/// identical
+pub(crate) fn arrow_marker_id(stroke: Option<ColorRGB>, arrow: Arrow) -> String {
+    let color = stroke.map_or(String::from("none"), |ColorRGB(r, g, b)| {
+        format!("{:02x}{:02x}{:02x}", r, g, b)
+    });
+    format!(
+        "arrow-{}-{}",
+        color,
+        arrow.size.to_string().replace('.', "_")
+    )
+}
+
+/// Triangular arrowhead filled with the stroke color. The marker is in
+/// stroke width units and orients itself along the end of the path
+pub(crate) fn svg_arrow_marker(stroke: Option<ColorRGB>, arrow: Arrow) -> Marker {
+    let fill = stroke.map_or(String::from("black"), |color| color.to_string());
+    let head = Path::new()
+        .set("d", "M 0 0 L 10 5 L 0 10 z")
+        .set("fill", fill)
+        .set("stroke", "none");
+
+    Marker::new()
+        .set("id", arrow_marker_id(stroke, arrow))
+        .set("viewBox", "0 0 10 10")
+        .set("refX", 10)
+        .set("refY", 5)
+        .set("markerUnits", "strokeWidth")
+        .set("markerWidth", arrow.size)
+        .set("markerHeight", arrow.size)
+        .set("orient", "auto")
+        .add(head)
+}
+
+/// Move marker definitions out of wherever they are nested (e.g. the
+/// arrowheads style_group() defines in each group) into a single <defs>
+/// at the start of the document, keeping one per id. Empty <defs> left
+/// behind are removed
+pub fn hoist_markers(mut doc: Document) -> Document {
+    let Some(children) = doc.get_children_mut() else {
+        return doc;
+    };
+
+    let mut markers: Children = Vec::new();
+    take_markers(children, &mut markers);
+    if !markers.is_empty() {
+        let mut defs = Definitions::new();
+        for marker in markers {
+            defs.append(marker);
+        }
+        children.insert(0, Box::new(defs));
+    }
+    doc
+}
+
+fn node_id(node: &dyn Node) -> Option<String> {
+    let id = node.get_attributes()?.get("id")?;
+    Some(id.to_string())
+}
+
+fn take_markers(children: &mut Children, markers: &mut Children) {
+    for child in children.iter_mut() {
+        let is_defs = child.get_name() == "defs";
+        let Some(grandchildren) = child.get_children_mut() else {
+            continue;
+        };
+
+        if !is_defs {
+            take_markers(grandchildren, markers);
+            continue;
+        }
+
+        let (found, rest): (Children, Children) = grandchildren
+            .drain(..)
+            .partition(|x| x.get_name() == "marker");
+        *grandchildren = rest;
+        for marker in found {
+            let id = node_id(marker.as_ref());
+            if !markers.iter().any(|x| node_id(x.as_ref()) == id) {
+                markers.push(marker);
+            }
+        }
+    }
+
+    children.retain(|x| x.get_name() != "defs" || x.get_children().is_some_and(|c| !c.is_empty()));
+}
+
 pub fn style_group(style: Style) -> Group {
     let mut group = Group::new();
 
     let Style {
         stroke,
         fill,
-        width_percent,
+        width,
+        arrow,
+        stroke_dasharray,
+        stroke_opacity,
+        fill_opacity,
+        line_cap,
+        line_join,
     } = style;
     if let Some(color) = stroke {
         group = group.set("stroke", color.to_string());
@@ -150,8 +343,39 @@ pub fn style_group(style: Style) -> Group {
         group = group.set("fill", "none");
     }
 
-    if let Some(percent) = width_percent {
-        group = group.set("stroke-width", format!("{}%", percent));
+    if let Some(width) = width {
+        group = group.set("stroke-width", svg_stroke_width(width));
+    }
+
+    if let Some(dasharray) = stroke_dasharray {
+        group = group.set("stroke-dasharray", svg_dasharray(dasharray));
+    }
+
+    if let Some(opacity) = stroke_opacity {
+        group = group.set("stroke-opacity", opacity);
+    }
+
+    if let Some(opacity) = fill_opacity {
+        group = group.set("fill-opacity", opacity);
+    }
+
+    if let Some(line_cap) = line_cap {
+        group = group.set("stroke-linecap", line_cap.name());
+    }
+
+    if let Some(line_join) = line_join {
+        group = group.set("stroke-linejoin", line_join.name());
+    }
+
+    // marker-end is inherited, but only lines and paths have markers, so
+    // circles and points in the group are unaffected
+    if let Some(arrow) = arrow {
+        group = group
+            .set(
+                "marker-end",
+                format!("url(#{})", arrow_marker_id(stroke, arrow)),
+            )
+            .add(Definitions::new().add(svg_arrow_marker(stroke, arrow)));
     }
 
     group
@@ -164,15 +388,21 @@ pub fn style_geometry(style: Style, geometry: impl Into<SvgNodes>) -> Group {
     svg
 }
 
-pub fn style_motif(motif: &Motif, styles: &[Style]) -> Group {
+pub fn style_motif<T: Renderable + Transformable<Isogonal>>(
+    motif: &Motif<T>,
+    styles: &[Style],
+) -> Group {
     let groups: Vec<Group> = motif
         .iter()
-        .map(|(tile, style_id)| style_geometry(styles[*style_id], tile))
+        .map(|(geometry, style_id)| style_geometry(styles[style_id], geometry))
         .collect();
     union(groups)
 }
 
-pub fn style_motifs(motifs: &[Motif], styles: &[Style]) -> Group {
+pub fn style_motifs<T: Renderable + Transformable<Isogonal>>(
+    motifs: &[Motif<T>],
+    styles: &[Style],
+) -> Group {
     let groups: Vec<Group> = motifs
         .iter()
         .map(|motif| style_motif(motif, styles))
@@ -180,6 +410,110 @@ pub fn style_motifs(motifs: &[Motif], styles: &[Style]) -> Group {
     union(groups)
 }
 
+/// Render a scene's layers in z-order, each as its own styled group.
+/// Physical stroke widths assume the default card size, see
+/// render_scene_with()
+pub fn render_scene(scene: &Scene) -> Group {
+    render_scene_with(&RenderConfig::default(), scene)
+}
+
+/// Like render_scene(), but physical stroke widths are sized for the
+/// image size from config
+pub fn render_scene_with(config: &RenderConfig, scene: &Scene) -> Group {
+    let groups: Vec<Group> = scene
+        .sorted_layers()
+        .into_iter()
+        .map(|layer| style_geometry(config.resolve_style(layer.style), layer))
+        .collect();
+    union(groups)
+}
+
+/// Render a scene, filtering each layer's baked primitives first. The
+/// filter is also given the layer's style. Layers with nothing left are
+/// skipped
+fn render_scene_trimmed(
+    config: &RenderConfig,
+    scene: &Scene,
+    trim: impl Fn(&[RenderPrimitive], Style) -> Vec<RenderPrimitive>,
+) -> Result<Group, Box<dyn Error>> {
+    let mut groups = Vec::new();
+    for layer in scene.sorted_layers() {
+        let primitives = trim(&layer.bake_geometry()?, layer.style);
+        if !primitives.is_empty() {
+            groups.push(style_geometry(
+                config.resolve_style(layer.style),
+                primitives,
+            ));
+        }
+    }
+    Ok(union(groups))
+}
+
+/// Like render_scene(), but each layer is clipped to region first, see
+/// rendering::clipping. Clipping cuts the outline of a shape, which
+/// would change the area a fill covers, so filled layers are only culled
+pub fn render_scene_clipped(scene: &Scene, region: &ClipRegion) -> Result<Group, Box<dyn Error>> {
+    render_scene_trimmed(&RenderConfig::default(), scene, |primitives, style| {
+        if style.fill.is_some() {
+            cull_primitives(primitives, region)
+        } else {
+            clip_primitives(primitives, region)
+        }
+    })
+}
+
+/// Like render_scene(), but primitives that miss the region are dropped
+/// first, see rendering::culling
+pub fn render_scene_culled(scene: &Scene, region: &ClipRegion) -> Result<Group, Box<dyn Error>> {
+    render_scene_trimmed(&RenderConfig::default(), scene, |primitives, _| {
+        cull_primitives(primitives, region)
+    })
+}
+
+/// Render a scene for one view, culled and clipped to the view if
+/// config asks for it
+pub fn render_scene_for_view(
+    config: &RenderConfig,
+    scene: &Scene,
+    center: Complex,
+    half_width: f64,
+) -> Result<Group, Box<dyn Error>> {
+    if !config.trims_geometry() {
+        return Ok(render_scene_with(config, scene));
+    }
+
+    let region = config.clip_region(center, half_width)?;
+    render_scene_trimmed(config, scene, |primitives, style| {
+        config.trim_primitives(primitives, &region, style)
+    })
+}
+
+/// Render a density grid as one square per non-empty cell, colored from
+/// low to high on a log scale (see DensityGrid::log_scaled()). Like the
+/// other geometry, this is in math coordinates, so add it inside flip_y()
+pub fn render_density(grid: &DensityGrid, low: ColorRGB, high: ColorRGB) -> Group {
+    let size = grid.cell_size();
+    let mut group = Group::new().set("stroke", "none");
+    for (row, values) in grid.log_scaled().iter().enumerate() {
+        for (col, value) in values.iter().enumerate() {
+            if grid.count(row, col) == 0 {
+                continue;
+            }
+
+            let center = grid.cell_center(row, col);
+            group = group.add(
+                Rectangle::new()
+                    .set("x", center.real() - 0.5 * size)
+                    .set("y", center.imag() - 0.5 * size)
+                    .set("width", size)
+                    .set("height", size)
+                    .set("fill", ColorRGB::lerp(low, high, *value).to_string()),
+            );
+        }
+    }
+    group
+}
+
 pub fn union(groups: Vec<Group>) -> Group {
     groups
         .into_iter()
@@ -203,38 +537,237 @@ pub fn flip_y() -> Group {
     Group::new().set("transform", "scale(1, -1)")
 }
 
-pub fn make_card(center: Complex, half_width: f64) -> Document {
-    // My usual art trading card format for my website is 500x700px
-    const WIDTH: f64 = 500.0;
-    const HEIGHT: f64 = 700.0;
-    const ASPECT_RATIO: f64 = WIDTH / HEIGHT;
+// My usual art trading card format for my website is 500x700px
+pub(crate) const CARD_WIDTH: f64 = 500.0;
+pub(crate) const CARD_HEIGHT: f64 = 700.0;
 
-    let half_height = half_width / ASPECT_RATIO;
-    let offset = Complex::new(half_width, half_height);
+/// File format for render_scene_views()
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// One SVG file per view
+    Svg,
+    /// A single JSON file with the scene and every view, see
+    /// rendering::json_export. Only scenes can be written this way
+    Json,
+}
 
-    let top_left = center.conj() - offset;
-    let dimensions = offset + offset;
+impl OutputFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Json => "json",
+        }
+    }
+}
 
-    let view_box = (
-        top_left.real(),
-        top_left.imag(),
-        dimensions.real(),
-        dimensions.imag(),
-    );
+impl FromStr for OutputFormat {
+    type Err = String;
 
-    let background = Rectangle::new()
-        .set("x", top_left.real())
-        .set("y", top_left.imag())
-        .set("width", "100%")
-        .set("height", "100%")
-        .set("fill", "black")
-        .set("stroke", "none");
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "svg" => Ok(Self::Svg),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("unknown output format {}, expected svg or json", s)),
+        }
+    }
+}
+
+/// Settings for rendering views to files. The default is my usual
+/// 500x700px card with a black background, written to output/ as SVG
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderConfig {
+    /// Size of the image in pixels
+    pub width: f64,
+    pub height: f64,
+    /// None leaves the background transparent
+    pub background: Option<ColorRGB>,
+    /// Empty margin in pixels on each side of the view
+    pub padding: f64,
+    pub output_dir: path::PathBuf,
+    pub format: OutputFormat,
+    /// Clip scenes to the visible part of each view before writing them,
+    /// see render_scene_clipped()
+    pub clip: bool,
+    /// Drop primitives that are nowhere near each view before writing
+    /// them, see render_scene_culled(). This is cheaper than clipping
+    pub cull: bool,
+}
 
-    Document::new()
-        .set("width", 500)
-        .set("height", 700)
-        .set("viewBox", view_box)
-        .add(background)
+impl Default for RenderConfig {
+    fn default() -> Self {
+        Self {
+            width: CARD_WIDTH,
+            height: CARD_HEIGHT,
+            background: Some(ColorRGB(0, 0, 0)),
+            padding: 0.0,
+            output_dir: path::PathBuf::from("output"),
+            format: OutputFormat::Svg,
+            clip: false,
+            cull: false,
+        }
+    }
+}
+
+impl RenderConfig {
+    /// Default settings with an image of width x height pixels
+    pub fn new(width: f64, height: f64) -> Result<Self, String> {
+        let valid = |x: f64| x > 0.0 && x.is_finite();
+        if !valid(width) || !valid(height) {
+            return Err(format!(
+                "image size must be positive, got {}x{}",
+                width, height
+            ));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            ..Self::default()
+        })
+    }
+
+    pub fn with_background(self, background: Option<ColorRGB>) -> Self {
+        Self { background, ..self }
+    }
+
+    pub fn with_padding(self, padding: f64) -> Result<Self, String> {
+        if !(padding >= 0.0 && 2.0 * padding < self.width.min(self.height)) {
+            return Err(format!(
+                "padding {} does not fit in a {}x{} image",
+                padding, self.width, self.height
+            ));
+        }
+
+        Ok(Self { padding, ..self })
+    }
+
+    pub fn with_output_dir<P: AsRef<path::Path>>(self, output_dir: P) -> Self {
+        Self {
+            output_dir: output_dir.as_ref().to_path_buf(),
+            ..self
+        }
+    }
+
+    pub fn with_format(self, format: OutputFormat) -> Self {
+        Self { format, ..self }
+    }
+
+    pub fn with_clip(self, clip: bool) -> Self {
+        Self { clip, ..self }
+    }
+
+    pub fn with_cull(self, cull: bool) -> Self {
+        Self { cull, ..self }
+    }
+
+    /// Convert physical stroke widths in a style to the image size, see
+    /// Style::for_image_size()
+    pub fn resolve_style(&self, style: Style) -> Style {
+        style.for_image_size(self.width, self.height)
+    }
+
+    /// Whether primitives are culled or clipped before they are written
+    pub fn trims_geometry(&self) -> bool {
+        self.clip || self.cull
+    }
+
+    /// Cull and clip primitives to the region as configured. Clipping
+    /// always culls first, since culling is much cheaper and never drops
+    /// anything clipping would keep. Primitives drawn with a fill are
+    /// only culled, see render_scene_clipped()
+    pub fn trim_primitives(
+        &self,
+        primitives: &[RenderPrimitive],
+        region: &ClipRegion,
+        style: Style,
+    ) -> Vec<RenderPrimitive> {
+        if !self.trims_geometry() {
+            return primitives.to_vec();
+        }
+
+        let culled = cull_primitives(primitives, region);
+        if self.clip && style.fill.is_none() {
+            clip_primitives(&culled, region)
+        } else {
+            culled
+        }
+    }
+
+    /// Path of the file for a view. An empty label gives prefix.ext
+    pub fn output_path(&self, prefix: &str, label: &str) -> path::PathBuf {
+        let separator = if label.is_empty() { "" } else { "_" };
+        let filename = format!(
+            "{}{}{}.{}",
+            prefix,
+            separator,
+            label,
+            self.format.extension()
+        );
+        self.output_dir.join(filename)
+    }
+
+    /// Compute the SVG viewBox (x, y, width, height) for an image
+    /// centered at center, where half_width is the distance from the
+    /// center to the inside of the padding. The y-coordinate is flipped
+    /// to match flip_y()
+    pub fn view_box(&self, center: Complex, half_width: f64) -> (f64, f64, f64, f64) {
+        let aspect_ratio = self.width / self.height;
+
+        let padded_width = half_width * self.width / (self.width - 2.0 * self.padding);
+        let half_height = padded_width / aspect_ratio;
+        let offset = Complex::new(padded_width, half_height);
+
+        let top_left = center.conj() - offset;
+        let dimensions = offset + offset;
+
+        (
+            top_left.real(),
+            top_left.imag(),
+            dimensions.real(),
+            dimensions.imag(),
+        )
+    }
+
+    /// The part of the plane visible in the image, including the padding
+    pub fn clip_region(&self, center: Complex, half_width: f64) -> Result<ClipRegion, String> {
+        let (_, _, width, height) = self.view_box(center, half_width);
+        ClipRegion::rectangle(center, 0.5 * width, 0.5 * height)
+    }
+
+    /// Empty document with the background, ready for geometry in math
+    /// coordinates wrapped in flip_y()
+    pub fn make_card(&self, center: Complex, half_width: f64) -> Document {
+        let view_box = self.view_box(center, half_width);
+        let (x, y, _, _) = view_box;
+
+        let mut doc = Document::new()
+            .set("width", self.width)
+            .set("height", self.height)
+            .set("viewBox", view_box);
+
+        if let Some(color) = self.background {
+            let background = Rectangle::new()
+                .set("x", x)
+                .set("y", y)
+                .set("width", "100%")
+                .set("height", "100%")
+                .set("fill", color.to_string())
+                .set("stroke", "none");
+            doc = doc.add(background);
+        }
+
+        doc
+    }
+}
+
+/// Compute the SVG viewBox (x, y, width, height) for a card centered
+/// at center. The y-coordinate is flipped to match flip_y()
+pub(crate) fn card_view_box(center: Complex, half_width: f64) -> (f64, f64, f64, f64) {
+    RenderConfig::default().view_box(center, half_width)
+}
+
+pub fn make_card(center: Complex, half_width: f64) -> Document {
+    RenderConfig::default().make_card(center, half_width)
 }
 
 pub struct View<'a>(pub &'a str, pub f64, pub f64, pub f64);
@@ -245,15 +778,375 @@ pub fn render_views<P: AsRef<path::Path>>(
     views: &[View],
     geometry: Group,
 ) -> Result<(), std::io::Error> {
+    let config = RenderConfig::default().with_output_dir(output_dir);
+    render_views_with(&config, prefix, views, geometry)
+}
+
+/// Like render_views(), but with the image size, background and output
+/// directory from config. SVG is the only format that works for a Group
+pub fn render_views_with(
+    config: &RenderConfig,
+    prefix: &str,
+    views: &[View],
+    geometry: Group,
+) -> Result<(), std::io::Error> {
+    if config.format != OutputFormat::Svg {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "only scenes can be rendered as JSON, see render_scene_views()",
+        ));
+    }
+
     for View(label, x, y, half_width) in views {
         let flipped = flip_y().add(geometry.clone());
-        let doc = make_card(Complex::new(*x, *y), *half_width).add(flipped);
+        let doc = config
+            .make_card(Complex::new(*x, *y), *half_width)
+            .add(flipped);
 
-        let separator = if label.is_empty() { "" } else { "_" };
-        let filename = format!("{}{}{}.svg", prefix, separator, label);
-        let path = output_dir.as_ref().join(path::Path::new(&filename));
-        svg::save(path, &doc)?
+        svg::save(config.output_path(prefix, label), &hoist_markers(doc))?
+    }
+
+    Ok(())
+}
+
+/// Render a scene in the format from config. SVG writes one file per view
+/// like render_views(), JSON writes a single prefix.json with every view.
+/// If config culls or clips, each SVG file only has the geometry near its
+/// view
+pub fn render_scene_views(
+    config: &RenderConfig,
+    prefix: &str,
+    views: &[View],
+    scene: &Scene,
+) -> Result<(), Box<dyn Error>> {
+    match config.format {
+        OutputFormat::Svg if config.trims_geometry() => {
+            for view in views {
+                let &View(_, x, y, half_width) = view;
+                let geometry =
+                    render_scene_for_view(config, scene, Complex::new(x, y), half_width)?;
+                render_views_with(config, prefix, std::slice::from_ref(view), geometry)?;
+            }
+        }
+        OutputFormat::Svg => {
+            render_views_with(config, prefix, views, render_scene_with(config, scene))?
+        }
+        OutputFormat::Json => write_scene_json(config.output_path(prefix, ""), scene, views, &[])?,
     }
 
     Ok(())
 }
+
+/// Render a grid of labeled thumbnails, all framed with the same view.
+/// This is helpful for comparing variations of a figure side by side.
+pub fn render_contact_sheet<P: AsRef<path::Path>>(
+    path: P,
+    cells: &[(String, Group)],
+    columns: usize,
+    center: Complex,
+    half_width: f64,
+) -> Result<(), std::io::Error> {
+    const CELL_WIDTH: f64 = CARD_WIDTH / 2.0;
+    const CELL_HEIGHT: f64 = CARD_HEIGHT / 2.0;
+    const LABEL_HEIGHT: f64 = 24.0;
+    const ROW_HEIGHT: f64 = CELL_HEIGHT + LABEL_HEIGHT;
+
+    let columns = columns.max(1);
+    let rows = cells.len().div_ceil(columns);
+    let width = CELL_WIDTH * (columns as f64);
+    let height = ROW_HEIGHT * (rows as f64);
+
+    let background = Rectangle::new()
+        .set("width", "100%")
+        .set("height", "100%")
+        .set("fill", "black")
+        .set("stroke", "none");
+    let mut doc = Document::new()
+        .set("width", width)
+        .set("height", height)
+        .set("viewBox", (0.0, 0.0, width, height))
+        .add(background);
+
+    let view_box = card_view_box(center, half_width);
+    for (i, (label, geometry)) in cells.iter().enumerate() {
+        let x = CELL_WIDTH * ((i % columns) as f64);
+        let y = ROW_HEIGHT * ((i / columns) as f64);
+
+        // A nested <svg> clips each thumbnail to its own cell
+        let thumbnail = SVG::new()
+            .set("x", x)
+            .set("y", y)
+            .set("width", CELL_WIDTH)
+            .set("height", CELL_HEIGHT)
+            .set("viewBox", view_box)
+            .add(flip_y().add(geometry.clone()));
+
+        let caption = Text::new(label.as_str())
+            .set("x", x + 0.5 * CELL_WIDTH)
+            .set("y", y + CELL_HEIGHT + 0.75 * LABEL_HEIGHT)
+            .set("text-anchor", "middle")
+            .set("font-family", "monospace")
+            .set("font-size", 14)
+            .set("fill", "white");
+
+        doc = doc.add(thumbnail).add(caption);
+    }
+
+    svg::save(path, &hoist_markers(doc))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rendering::LineJoin;
+
+    use super::*;
+
+    #[test]
+    pub fn style_group_sets_dashes_and_opacity() {
+        let style = Style::stroke(255, 255, 255)
+            .with_dashes(1.0, 0.5)
+            .with_stroke_opacity(0.5)
+            .with_line_join(LineJoin::Bevel);
+
+        let result = style_group(style).to_string();
+
+        assert!(result.contains("stroke-dasharray=\"1% 0.5%\""));
+        assert!(result.contains("stroke-opacity=\"0.5\""));
+        assert!(result.contains("stroke-linejoin=\"bevel\""));
+        assert!(!result.contains("fill-opacity"));
+        assert!(!result.contains("stroke-linecap"));
+    }
+
+    #[test]
+    pub fn style_group_with_arrow_sets_marker_end() {
+        let style = Style::stroke(0, 128, 255).with_arrow(3.5);
+
+        let result = style_group(style).to_string();
+
+        assert!(result.contains("marker-end=\"url(#arrow-0080ff-3_5)\""));
+        assert!(result.contains("<marker id=\"arrow-0080ff-3_5\""));
+        assert!(result.contains("fill=\"#0080ff\""));
+    }
+
+    #[test]
+    pub fn hoist_markers_defines_each_marker_once() {
+        let arrow = Style::stroke(0, 128, 255).with_arrow(3.5);
+        let other = Style::stroke(255, 0, 0).with_arrow(2.0);
+        let geometry = union(vec![
+            style_geometry(arrow, &Cline::real_axis()),
+            style_geometry(arrow, &Cline::imag_axis()),
+            style_geometry(other, &Cline::real_axis()),
+        ]);
+        let doc = make_card(Complex::Zero, 1.0).add(flip_y().add(geometry));
+
+        let result = hoist_markers(doc).to_string();
+
+        assert_eq!(result.matches("<marker id=\"arrow-0080ff-3_5\"").count(), 1);
+        assert_eq!(result.matches("<marker id=\"arrow-ff0000-2\"").count(), 1);
+        assert_eq!(result.matches("<defs>").count(), 1);
+        assert_eq!(result.matches("marker-end=").count(), 3);
+    }
+
+    #[test]
+    pub fn render_scene_with_sizes_physical_widths_for_image() {
+        let style = Style::stroke(0, 0, 0).with_stroke_width(StrokeWidth::Millimeters(25.4));
+        let mut scene = Scene::new();
+        scene.add_layer(0, style, &[Cline::unit_circle()]);
+        let small = RenderConfig::new(960.0, 960.0).unwrap();
+        let large = RenderConfig::new(1920.0, 1920.0).unwrap();
+
+        let small_result = render_scene_with(&small, &scene).to_string();
+        let large_result = render_scene_with(&large, &scene).to_string();
+
+        // The same physical width is a smaller fraction of a larger image
+        assert!(small_result.contains("stroke-width=\"10%\""));
+        assert!(large_result.contains("stroke-width=\"5%\""));
+    }
+
+    #[test]
+    pub fn view_box_matches_aspect_ratio() {
+        let config = RenderConfig::new(1000.0, 500.0).unwrap();
+
+        let result = config.view_box(Complex::new(1.0, 2.0), 2.0);
+
+        assert_eq!(result, (-1.0, -3.0, 4.0, 2.0));
+    }
+
+    #[test]
+    pub fn view_box_with_padding_leaves_margin() {
+        let config = RenderConfig::new(100.0, 100.0)
+            .unwrap()
+            .with_padding(25.0)
+            .unwrap();
+
+        let result = config.view_box(Complex::Zero, 1.0);
+
+        assert_eq!(result, (-2.0, -2.0, 4.0, 4.0));
+    }
+
+    #[test]
+    pub fn with_padding_too_large_returns_error() {
+        let config = RenderConfig::new(100.0, 50.0).unwrap();
+
+        let result = config.with_padding(25.0);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn new_with_invalid_size_returns_error() {
+        for (width, height) in [(0.0, 100.0), (100.0, -1.0), (f64::NAN, 100.0)] {
+            let result = RenderConfig::new(width, height);
+
+            assert!(result.is_err(), "{}x{} should be rejected", width, height);
+        }
+    }
+
+    #[test]
+    pub fn output_path_uses_format_extension() {
+        let config = RenderConfig::default()
+            .with_output_dir("renders")
+            .with_format(OutputFormat::Json);
+
+        let labeled = config.output_path("tiling", "zoomed");
+        let unlabeled = config.output_path("tiling", "");
+
+        assert_eq!(labeled, path::PathBuf::from("renders/tiling_zoomed.json"));
+        assert_eq!(unlabeled, path::PathBuf::from("renders/tiling.json"));
+    }
+
+    #[test]
+    pub fn make_card_without_background_has_no_rect() {
+        let config = RenderConfig::default().with_background(None);
+
+        let result = config.make_card(Complex::Zero, 1.0).to_string();
+
+        assert!(!result.contains("<rect"));
+    }
+
+    #[test]
+    pub fn layers_become_named_groups_in_z_order() {
+        let primitives = vec![
+            RenderPrimitive::layer("front", vec![RenderPrimitive::Point(Complex::ONE)], 1),
+            RenderPrimitive::layer("back", vec![RenderPrimitive::Point(Complex::I)], -1),
+        ];
+        let SvgNodes(nodes) = primitives.into();
+
+        let result: Vec<String> = nodes.iter().map(|node| node.to_string()).collect();
+
+        assert_eq!(result.len(), 2);
+        assert!(result[0].starts_with("<g id=\"back\">"));
+        assert!(result[1].starts_with("<g id=\"front\">"));
+    }
+
+    #[test]
+    pub fn text_is_flipped_upright() {
+        let text = RenderPrimitive::Text {
+            position: Complex::new(1.0, 2.0),
+            content: String::from("a < b"),
+            size: 0.5,
+            anchor: TextAnchor::End,
+        };
+        let SvgNode(node) = text.into();
+
+        let result = node.to_string();
+
+        assert!(result.contains("x=\"1\""));
+        assert!(result.contains("y=\"-2\""));
+        assert!(result.contains("font-size=\"0.5\""));
+        assert!(result.contains("text-anchor=\"end\""));
+        assert!(result.contains("transform=\"scale(1, -1)\""));
+        assert!(result.contains("a &lt; b"));
+    }
+
+    #[test]
+    pub fn clip_region_matches_view_box() {
+        let config = RenderConfig::new(100.0, 200.0).unwrap();
+
+        let result = config.clip_region(Complex::new(1.0, 1.0), 1.0).unwrap();
+
+        assert_eq!(
+            result,
+            ClipRegion::Rectangle {
+                x_min: 0.0,
+                y_min: -1.0,
+                x_max: 2.0,
+                y_max: 3.0
+            }
+        );
+    }
+
+    #[test]
+    pub fn render_scene_clipped_drops_layers_outside() {
+        let mut scene = Scene::new();
+        scene.add_layer(0, Style::stroke(255, 0, 0), &[Cline::unit_circle()]);
+        let far = Circle::new(Complex::new(10.0, 10.0), 1.0);
+        scene.add_layer(1, Style::stroke(0, 0, 255), &[Cline::from(far)]);
+        let region = ClipRegion::rectangle(Complex::Zero, 2.0, 2.0).unwrap();
+
+        let result = render_scene_clipped(&scene, &region).unwrap().to_string();
+
+        assert!(result.contains("#ff0000"));
+        assert!(!result.contains("#0000ff"));
+    }
+
+    #[test]
+    pub fn render_scene_clipped_keeps_fill_covering_region() {
+        let mut scene = Scene::new();
+        let disk = Circle::new(Complex::Zero, 10.0);
+        let style = Style {
+            fill: Some(ColorRGB(255, 0, 0)),
+            ..Style::new()
+        };
+        scene.add_layer(0, style, &[Cline::from(disk)]);
+        let region = ClipRegion::rectangle(Complex::Zero, 2.0, 2.0).unwrap();
+
+        let result = render_scene_clipped(&scene, &region).unwrap().to_string();
+
+        // the whole disk is kept, not dropped or cut into arcs
+        assert!(result.contains("<circle"));
+        assert!(result.contains("r=\"10\""));
+    }
+
+    #[test]
+    pub fn trim_primitives_clips_strokes_but_not_fills() {
+        let config = RenderConfig::default().with_clip(true);
+        let region = ClipRegion::rectangle(Complex::Zero, 1.0, 1.0).unwrap();
+        let primitives = vec![RenderPrimitive::Circle(Circle::new(
+            Complex::new(1.0, 0.0),
+            0.5,
+        ))];
+
+        let stroked = config.trim_primitives(&primitives, &region, Style::stroke(0, 0, 0));
+        let fill = Style {
+            fill: Some(ColorRGB(0, 0, 0)),
+            ..Style::new()
+        };
+        let filled = config.trim_primitives(&primitives, &region, fill);
+
+        assert!(matches!(stroked[..], [RenderPrimitive::CircularArc(_)]));
+        assert!(matches!(filled[..], [RenderPrimitive::Circle(_)]));
+    }
+
+    #[test]
+    pub fn trim_primitives_culls_without_clipping() {
+        let config = RenderConfig::default().with_cull(true);
+        let region = ClipRegion::rectangle(Complex::Zero, 1.0, 1.0).unwrap();
+        let primitives = vec![
+            RenderPrimitive::Circle(Circle::new(Complex::new(1.0, 0.0), 0.5)),
+            RenderPrimitive::Circle(Circle::new(Complex::new(5.0, 0.0), 0.5)),
+        ];
+
+        let result = config.trim_primitives(&primitives, &region, Style::stroke(0, 0, 0));
+
+        // the first circle sticks out of the region, but it isn't cut
+        assert!(matches!(result[..], [RenderPrimitive::Circle(_)]));
+    }
+
+    #[test]
+    pub fn output_format_parses_case_insensitive() {
+        assert_eq!("SVG".parse::<OutputFormat>(), Ok(OutputFormat::Svg));
+        assert_eq!("json".parse::<OutputFormat>(), Ok(OutputFormat::Json));
+        assert!("png".parse::<OutputFormat>().is_err());
+    }
+}
@@ -0,0 +1,537 @@
+//! SVG output for scenes too big to hold in memory as an svg::Document.
+//! Primitives are written to the file as soon as they are baked, so memory
+//! use stays bounded by the largest single piece of geometry rather than
+//! the whole scene.
+//!
+//! Instead of nesting each style in its own group with presentation
+//! attributes, each distinct style becomes a CSS class. Consecutive
+//! primitives with the same style share a `<g class="...">`, and the
+//! `<style>` element is written at the end once every style is known
+//! (CSS applies to the whole document no matter where it appears).
+use std::{
+    error::Error,
+    fmt::Write as _,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    rendering::{ClipRegion, Layer, RenderPrimitive, Renderable, Scene, Style},
+    svg_plot::{
+        arrow_marker_id, flip_y, hoist_markers, style_geometry, svg_arrow_marker, svg_dasharray,
+        svg_stroke_width, union, RenderConfig, SvgNode,
+    },
+    Complex,
+};
+
+/// Writes a single image (see RenderConfig::make_card()) one primitive at
+/// a time
+pub struct SvgStreamWriter<W: Write> {
+    writer: W,
+    /// Distinct styles seen so far. The class for styles[i] is s{i}
+    styles: Vec<Style>,
+    /// Class of the group that is currently open, if any
+    current: Option<usize>,
+    /// Settings and region for culling and clipping, if the config asks
+    /// for either. Geometry is trimmed before it is written
+    trim: Option<(RenderConfig, ClipRegion)>,
+    /// Image size in pixels, for physical stroke widths
+    image_size: (f64, f64),
+}
+
+impl SvgStreamWriter<BufWriter<File>> {
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        config: &RenderConfig,
+        center: Complex,
+        half_width: f64,
+    ) -> std::io::Result<Self> {
+        Self::with_config(
+            BufWriter::new(File::create(path)?),
+            config,
+            center,
+            half_width,
+        )
+    }
+}
+
+impl<W: Write> SvgStreamWriter<W> {
+    /// Start the document and write the background. The geometry is in
+    /// math coordinates like the rest of svg_plot
+    pub fn new(writer: W, center: Complex, half_width: f64) -> std::io::Result<Self> {
+        Self::with_config(writer, &RenderConfig::default(), center, half_width)
+    }
+
+    /// Like new(), but with the image size, background, culling and
+    /// clipping from config
+    pub fn with_config(
+        mut writer: W,
+        config: &RenderConfig,
+        center: Complex,
+        half_width: f64,
+    ) -> std::io::Result<Self> {
+        let (x, y, width, height) = config.view_box(center, half_width);
+        writeln!(
+            writer,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+            viewBox=\"{} {} {} {}\">",
+            config.width, config.height, x, y, width, height
+        )?;
+        if let Some(color) = config.background {
+            writeln!(
+                writer,
+                "<rect x=\"{}\" y=\"{}\" width=\"100%\" height=\"100%\" fill=\"{}\" \
+                stroke=\"none\"/>",
+                x, y, color
+            )?;
+        }
+        writeln!(writer, "<g transform=\"scale(1, -1)\">")?;
+
+        let trim = if config.trims_geometry() {
+            let region = config
+                .clip_region(center, half_width)
+                .map_err(std::io::Error::other)?;
+            Some((config.clone(), region))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            writer,
+            styles: Vec::new(),
+            current: None,
+            trim,
+            image_size: (config.width, config.height),
+        })
+    }
+
+    /// Number of distinct styles written so far
+    pub fn style_count(&self) -> usize {
+        self.styles.len()
+    }
+
+    fn class_of(&mut self, style: Style) -> usize {
+        match self.styles.iter().position(|x| *x == style) {
+            Some(index) => index,
+            None => {
+                self.styles.push(style);
+                self.styles.len() - 1
+            }
+        }
+    }
+
+    pub fn write_primitive(
+        &mut self,
+        style: Style,
+        primitive: RenderPrimitive,
+    ) -> std::io::Result<()> {
+        let (width, height) = self.image_size;
+        let class = self.class_of(style.for_image_size(width, height));
+        if self.current != Some(class) {
+            if self.current.is_some() {
+                writeln!(self.writer, "</g>")?;
+            }
+            writeln!(self.writer, "<g class=\"s{}\">", class)?;
+            self.current = Some(class);
+        }
+
+        let SvgNode(node) = primitive.into();
+        writeln!(self.writer, "{}", node)
+    }
+
+    pub fn write_geometry(
+        &mut self,
+        style: Style,
+        geometry: &impl Renderable,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut primitives = geometry.bake_geometry()?;
+        if let Some((config, region)) = &self.trim {
+            primitives = config.trim_primitives(&primitives, region, style);
+        }
+        self.write_layer(style, primitives)?;
+        Ok(())
+    }
+
+    /// Write primitives that were already baked and trimmed, in drawing
+    /// order
+    fn write_layer(
+        &mut self,
+        style: Style,
+        mut primitives: Vec<RenderPrimitive>,
+    ) -> std::io::Result<()> {
+        RenderPrimitive::sort_layers(&mut primitives);
+        for primitive in primitives {
+            self.write_primitive(style, primitive)?;
+        }
+        Ok(())
+    }
+
+    /// Write styled layers in the order the iterator yields them. Only one
+    /// layer is baked at a time
+    pub fn write_layers<I, R>(&mut self, layers: I) -> Result<(), Box<dyn Error>>
+    where
+        I: IntoIterator<Item = (Style, R)>,
+        R: Renderable,
+    {
+        for (style, geometry) in layers {
+            self.write_geometry(style, &geometry)?;
+        }
+        Ok(())
+    }
+
+    /// Write a scene's layers in z-order. Only one layer is baked at a time
+    pub fn write_scene(&mut self, scene: &Scene) -> Result<(), Box<dyn Error>> {
+        self.write_layers(scene_layers(scene))
+    }
+
+    /// Close the document and write the stylesheet. Returns the
+    /// underlying writer
+    pub fn finish(mut self) -> std::io::Result<W> {
+        if self.current.is_some() {
+            writeln!(self.writer, "</g>")?;
+        }
+        writeln!(self.writer, "</g>")?;
+        writeln!(self.writer, "<style>{}</style>", self.stylesheet())?;
+        let markers: Vec<_> = self
+            .styles
+            .iter()
+            .filter_map(|style| style.arrow.map(|arrow| (style.stroke, arrow)))
+            .collect();
+        if !markers.is_empty() {
+            writeln!(self.writer, "<defs>")?;
+            let mut ids = Vec::new();
+            for (stroke, arrow) in markers {
+                let id = arrow_marker_id(stroke, arrow);
+                if !ids.contains(&id) {
+                    writeln!(self.writer, "{}", svg_arrow_marker(stroke, arrow))?;
+                    ids.push(id);
+                }
+            }
+            writeln!(self.writer, "</defs>")?;
+        }
+        writeln!(self.writer, "</svg>")?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn stylesheet(&self) -> String {
+        let mut css = String::new();
+        for (i, style) in self.styles.iter().enumerate() {
+            let Style {
+                stroke,
+                fill,
+                width,
+                arrow,
+                stroke_dasharray,
+                stroke_opacity,
+                fill_opacity,
+                line_cap,
+                line_join,
+            } = style;
+            let _ = write!(css, "\n.s{} {{", i);
+            if let Some(color) = stroke {
+                let _ = write!(css, " stroke: {};", color);
+            }
+            match fill {
+                Some(color) => {
+                    let _ = write!(css, " fill: {};", color);
+                }
+                None => css.push_str(" fill: none;"),
+            }
+            if let Some(width) = width {
+                let _ = write!(css, " stroke-width: {};", svg_stroke_width(*width));
+            }
+            if let Some(dasharray) = stroke_dasharray {
+                let _ = write!(css, " stroke-dasharray: {};", svg_dasharray(*dasharray));
+            }
+            if let Some(opacity) = stroke_opacity {
+                let _ = write!(css, " stroke-opacity: {};", opacity);
+            }
+            if let Some(opacity) = fill_opacity {
+                let _ = write!(css, " fill-opacity: {};", opacity);
+            }
+            if let Some(line_cap) = line_cap {
+                let _ = write!(css, " stroke-linecap: {};", line_cap.name());
+            }
+            if let Some(line_join) = line_join {
+                let _ = write!(css, " stroke-linejoin: {};", line_join.name());
+            }
+            if let Some(arrow) = arrow {
+                let _ = write!(
+                    css,
+                    " marker-end: url(#{});",
+                    arrow_marker_id(*stroke, *arrow)
+                );
+            }
+            css.push_str(" }");
+        }
+        css.push('\n');
+        css
+    }
+}
+
+/// A scene's layers in z-order as (style, geometry) pairs, the form
+/// save_scene() and SvgStreamWriter::write_layers() take
+pub fn scene_layers(scene: &Scene) -> impl Iterator<Item = (Style, &Layer)> {
+    scene
+        .sorted_layers()
+        .into_iter()
+        .map(|layer| (layer.style, layer))
+}
+
+/// Save styled layers as an SVG image with the size and background from
+/// config. Layers are pulled from the iterator one at a time, so they can
+/// be generated lazily instead of collected into a Scene first (see
+/// scene_layers() for drawing an existing Scene). Once more than
+/// max_in_memory primitives have been baked, everything is streamed to the
+/// file with SvgStreamWriter, smaller images are built as an svg::Document
+/// like render_views() does
+pub fn save_scene<P, I, R>(
+    config: &RenderConfig,
+    path: P,
+    layers: I,
+    center: Complex,
+    half_width: f64,
+    max_in_memory: usize,
+) -> Result<(), Box<dyn Error>>
+where
+    P: AsRef<Path>,
+    I: IntoIterator<Item = (Style, R)>,
+    R: Renderable,
+{
+    let writer = BufWriter::new(File::create(path)?);
+    write_scene_to(config, writer, layers, center, half_width, max_in_memory)?;
+    Ok(())
+}
+
+/// Write layers like save_scene() does. Each layer is baked once: layers
+/// are kept in memory until they add up to more than max_in_memory
+/// primitives, then they and every later layer are streamed instead
+fn write_scene_to<W, I, R>(
+    config: &RenderConfig,
+    writer: W,
+    layers: I,
+    center: Complex,
+    half_width: f64,
+    max_in_memory: usize,
+) -> Result<W, Box<dyn Error>>
+where
+    W: Write,
+    I: IntoIterator<Item = (Style, R)>,
+    R: Renderable,
+{
+    let region = if config.trims_geometry() {
+        Some(config.clip_region(center, half_width)?)
+    } else {
+        None
+    };
+
+    let mut writer = Some(writer);
+    let mut stream: Option<SvgStreamWriter<W>> = None;
+    let mut in_memory: Vec<(Style, Vec<RenderPrimitive>)> = Vec::new();
+    let mut count = 0;
+    for (style, geometry) in layers {
+        let mut primitives = geometry.bake_geometry()?;
+        if let Some(region) = &region {
+            primitives = config.trim_primitives(&primitives, region, style);
+        }
+
+        if let Some(stream) = &mut stream {
+            stream.write_layer(style, primitives)?;
+            continue;
+        }
+
+        count += primitives.len();
+        in_memory.push((style, primitives));
+        if count > max_in_memory {
+            let writer = writer.take().expect("writer is only taken once");
+            let mut started = SvgStreamWriter::with_config(writer, config, center, half_width)?;
+            for (style, primitives) in in_memory.drain(..) {
+                started.write_layer(style, primitives)?;
+            }
+            stream = Some(started);
+        }
+    }
+
+    if let Some(stream) = stream {
+        return Ok(stream.finish()?);
+    }
+
+    let groups: Vec<_> = in_memory
+        .into_iter()
+        .filter(|(_, primitives)| !primitives.is_empty())
+        .map(|(style, primitives)| style_geometry(config.resolve_style(style), primitives))
+        .collect();
+    let doc = config
+        .make_card(center, half_width)
+        .add(flip_y().add(union(groups)));
+    let mut writer = writer.expect("writer is only taken when streaming");
+    write!(writer, "{}", hoist_markers(doc))?;
+    writer.flush()?;
+    Ok(writer)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{geometry::Circle, rendering::StrokeWidth, transformable::Cline};
+
+    use super::*;
+
+    fn write_to_string(scene: &Scene) -> String {
+        let mut writer = SvgStreamWriter::new(Vec::new(), Complex::Zero, 1.0).unwrap();
+        writer.write_scene(scene).unwrap();
+        String::from_utf8(writer.finish().unwrap()).unwrap()
+    }
+
+    #[test]
+    pub fn write_scene_shares_classes_between_equal_styles() {
+        let red = Style::stroke(255, 0, 0).with_width(0.5);
+        let mut scene = Scene::new();
+        scene.add_layer(0, red, &[Cline::unit_circle()]);
+        scene.add_layer(1, Style::stroke(0, 0, 255), &[Cline::real_axis()]);
+        scene.add_layer(2, red, &[Cline::imag_axis()]);
+
+        let result = write_to_string(&scene);
+
+        assert_eq!(result.matches("<g class=\"s0\">").count(), 2);
+        assert_eq!(result.matches("<g class=\"s1\">").count(), 1);
+        assert!(result.contains(".s0 { stroke: #ff0000; fill: none; stroke-width: 0.5%; }"));
+        assert!(result.contains(".s1 { stroke: #0000ff; fill: none; }"));
+    }
+
+    #[test]
+    pub fn write_scene_groups_consecutive_primitives() {
+        let mut scene = Scene::new();
+        let lines = [Cline::real_axis(), Cline::imag_axis(), Cline::unit_circle()];
+        scene.add_layer(0, Style::stroke(255, 0, 0), &lines);
+
+        let result = write_to_string(&scene);
+
+        assert_eq!(result.matches("<g class=").count(), 1);
+        assert_eq!(result.matches("<line").count(), 2);
+        assert_eq!(result.matches("<circle").count(), 1);
+        assert!(result.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    pub fn write_scene_defines_each_arrow_marker_once() {
+        let arrow = Style::stroke(255, 0, 0).with_arrow(4.0);
+        let mut scene = Scene::new();
+        scene.add_layer(0, arrow, &[Cline::real_axis()]);
+        scene.add_layer(1, Style::new(), &[Cline::unit_circle()]);
+        scene.add_layer(2, arrow, &[Cline::imag_axis()]);
+
+        let result = write_to_string(&scene);
+
+        assert_eq!(result.matches("<marker").count(), 1);
+        assert!(result.contains("id=\"arrow-ff0000-4\""));
+        assert!(result
+            .contains(".s0 { stroke: #ff0000; fill: none; marker-end: url(#arrow-ff0000-4); }"));
+    }
+
+    #[test]
+    pub fn stylesheet_includes_dashes_and_opacity() {
+        let mut scene = Scene::new();
+        let style = Style::stroke(0, 0, 0)
+            .with_dots(0.25)
+            .with_stroke_opacity(0.5);
+        scene.add_layer(0, style, &[Cline::real_axis()]);
+
+        let result = write_to_string(&scene);
+
+        assert!(result.contains(
+            ".s0 { stroke: #000000; fill: none; stroke-dasharray: 0% 0.25%; \
+            stroke-opacity: 0.5; stroke-linecap: round; }"
+        ));
+    }
+
+    #[test]
+    pub fn with_config_clips_geometry() {
+        let config = RenderConfig::default().with_clip(true);
+        let mut writer =
+            SvgStreamWriter::with_config(Vec::new(), &config, Complex::Zero, 0.8).unwrap();
+        let mut scene = Scene::new();
+        let far = Circle::new(Complex::new(10.0, 0.0), 1.0);
+        scene.add_layer(0, Style::new(), &[Cline::unit_circle(), Cline::from(far)]);
+        writer.write_scene(&scene).unwrap();
+
+        let result = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+        // the unit circle pokes out the sides of the card, so it is cut
+        // into two arcs
+        assert_eq!(result.matches("<path").count(), 2);
+        assert_eq!(result.matches("<circle").count(), 0);
+    }
+
+    #[test]
+    pub fn with_config_sizes_physical_widths_for_image() {
+        // The normalized diagonal of a 960x960 image is 10 inches
+        let config = RenderConfig::new(960.0, 960.0).unwrap();
+        let mut writer =
+            SvgStreamWriter::with_config(Vec::new(), &config, Complex::Zero, 1.0).unwrap();
+        let style = Style::stroke(0, 0, 0).with_stroke_width(StrokeWidth::Millimeters(25.4));
+        let mut scene = Scene::new();
+        scene.add_layer(0, style, &[Cline::unit_circle()]);
+        writer.write_scene(&scene).unwrap();
+
+        let result = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+        assert!(result.contains("stroke-width: 10%;"));
+    }
+
+    fn save_to_string(scene: &Scene, max_in_memory: usize) -> String {
+        let config = RenderConfig::default();
+        let writer = write_scene_to(
+            &config,
+            Vec::new(),
+            scene_layers(scene),
+            Complex::Zero,
+            1.0,
+            max_in_memory,
+        )
+        .unwrap();
+        String::from_utf8(writer).unwrap()
+    }
+
+    fn three_primitive_scene() -> Scene {
+        let mut scene = Scene::new();
+        scene.add_layer(0, Style::new(), &[Cline::real_axis(), Cline::imag_axis()]);
+        scene.add_layer(1, Style::new(), &[Cline::unit_circle()]);
+        scene
+    }
+
+    #[test]
+    pub fn save_scene_keeps_small_scenes_in_memory() {
+        let result = save_to_string(&three_primitive_scene(), 3);
+
+        assert!(!result.contains("<style>"));
+        assert_eq!(result.matches("<circle").count(), 1);
+        assert_eq!(result.matches("<line").count(), 2);
+    }
+
+    #[test]
+    pub fn save_scene_streams_every_layer_once_over_threshold() {
+        let result = save_to_string(&three_primitive_scene(), 2);
+
+        assert!(result.contains("<style>"));
+        assert_eq!(result.matches("<circle").count(), 1);
+        assert_eq!(result.matches("<line").count(), 2);
+    }
+
+    #[test]
+    pub fn save_scene_streams_layers_from_iterator() {
+        // No Scene is built, each layer is generated as it is written
+        let layers = (0..4).map(|i| {
+            let circle = Circle::new(Complex::new(i as f64, 0.0), 1.0);
+            (Style::new(), Cline::from(circle))
+        });
+
+        let config = RenderConfig::default();
+        let writer = write_scene_to(&config, Vec::new(), layers, Complex::Zero, 4.0, 2).unwrap();
+        let result = String::from_utf8(writer).unwrap();
+
+        assert!(result.contains("<style>"));
+        assert_eq!(result.matches("<circle").count(), 4);
+    }
+}
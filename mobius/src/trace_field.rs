@@ -0,0 +1,234 @@
+//! Traces and conjugacy for pairs of generators. Up to conjugation, a
+//! two-generator group is determined by the traces of a, b and ab
+//! (Indra's Pearls Chapter 6), so these are the coordinates most
+//! explorations of limit sets work in.
+use abstraction::Group;
+
+use crate::{Complex, Mobius, Tolerance};
+
+/// Relative tolerance for comparing traces and detecting scalar matrices,
+/// at the default tolerance. Traces of long products pick up more rounding
+/// error than the exact comparison Complex uses allows for
+const TOLERANCE: f64 = 1e-10;
+
+fn tolerance() -> f64 {
+    Tolerance::current().scale_relative(TOLERANCE)
+}
+
+fn is_close(a: Complex, b: Complex) -> bool {
+    (a - b).mag() <= tolerance() * a.mag().max(b.mag()).max(1.0)
+}
+
+/// Traces of a pair of generators a, b, their product ab and their
+/// commutator abAB
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeneratorTraces {
+    pub a: Complex,
+    pub b: Complex,
+    pub ab: Complex,
+    pub commutator: Complex,
+}
+
+impl GeneratorTraces {
+    pub fn new(a: Mobius, b: Mobius) -> Self {
+        Self {
+            a: a.trace(),
+            b: b.trace(),
+            ab: (a * b).trace(),
+            commutator: Mobius::commutator(a, b).trace(),
+        }
+    }
+}
+
+/// Fricke's identity for the trace of the commutator abAB in terms of the
+/// other three traces:
+///
+/// tr abAB = ta^2 + tb^2 + tab^2 - ta tb tab - 2
+pub fn fricke_commutator_trace(ta: Complex, tb: Complex, tab: Complex) -> Complex {
+    ta * ta + tb * tb + tab * tab - ta * tb * tab - 2.0.into()
+}
+
+/// Change of basis matrix [v | Mv] for a vector v that is not an
+/// eigenvector of M. Since M^2 = tr(M) M - I, in this basis M becomes
+///
+/// [0   -1   ]
+/// [1  tr(M) ]
+///
+/// which only depends on the trace. Returns None if M = +/- I, since then
+/// every vector is an eigenvector
+fn cyclic_basis(m: Mobius) -> Option<Mobius> {
+    let Mobius { a, b, c, d } = m;
+    let candidates = [
+        (Complex::ONE, Complex::Zero),
+        (Complex::Zero, Complex::ONE),
+        (Complex::ONE, Complex::ONE),
+    ];
+
+    // det [v | Mv] measures how far v is from an eigenvector
+    let (det, (x, y), (mx, my)) = candidates
+        .into_iter()
+        .map(|(x, y)| {
+            let (mx, my) = (a * x + b * y, c * x + d * y);
+            (x * my - y * mx, (x, y), (mx, my))
+        })
+        .max_by(|(p, ..), (q, ..)| p.mag().total_cmp(&q.mag()))?;
+
+    if det.mag() <= tolerance() {
+        return None;
+    }
+
+    Mobius::from_unnormalized(x, mx, y, my).ok()
+}
+
+/// Solve for S such that S a S^-1 = b. Such an S exists when a and b have
+/// the same trace up to sign, except that the identity is only conjugate
+/// to itself. S is not unique, it can be composed with anything that
+/// commutes with a.
+pub fn conjugating_map(a: Mobius, b: Mobius) -> Result<Mobius, String> {
+    let (ta, tb) = (a.trace(), b.trace());
+
+    // Matrices are only defined up to sign, so flip b to match the trace
+    // of a
+    let b = if is_close(ta, tb) {
+        b
+    } else if is_close(ta, -tb) {
+        Mobius {
+            a: -b.a,
+            b: -b.b,
+            c: -b.c,
+            d: -b.d,
+        }
+    } else {
+        return Err(format!(
+            "traces {} and {} differ, so the transformations are not conjugate",
+            ta, tb
+        ));
+    };
+
+    match (cyclic_basis(a), cyclic_basis(b)) {
+        // Both are similar to the same companion matrix
+        (Some(basis_a), Some(basis_b)) => Ok(basis_b * basis_a.inverse()),
+        (None, None) => Ok(Mobius::IDENTITY),
+        _ => Err(String::from("the identity is only conjugate to itself")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::{grandma_recipe, rotation, scale, translation};
+
+    use super::*;
+
+    fn loxodromic() -> Mobius {
+        Mobius::from_unnormalized(
+            Complex::new(2.0, 1.0),
+            Complex::new(1.0, -1.0),
+            Complex::new(0.5, 0.0),
+            Complex::new(1.0, 0.5),
+        )
+        .unwrap()
+    }
+
+    fn assert_conjugates(a: Mobius, b: Mobius) {
+        let s = conjugating_map(a, b).unwrap();
+        let result = s * a * s.inverse();
+        for z in [
+            Complex::Zero,
+            Complex::new(0.3, -1.2),
+            Complex::new(-2.0, 0.5),
+        ] {
+            let (actual, expected) = (result * z, b * z);
+            assert!(
+                (actual - expected).mag() < 1e-9,
+                "{} != {} at {}",
+                actual,
+                expected,
+                z
+            );
+        }
+    }
+
+    #[test]
+    pub fn generator_traces_of_grandma_recipe() {
+        let ta = Complex::new(1.87, 0.1);
+        let tb = Complex::new(1.87, -0.1);
+        let (a, b) = grandma_recipe(ta, tb, None).unwrap();
+
+        let result = GeneratorTraces::new(a, b);
+
+        assert_eq!(result.a, ta);
+        assert_eq!(result.b, tb);
+        assert!((result.commutator + Complex::new(2.0, 0.0)).mag() < 1e-10);
+    }
+
+    #[test]
+    pub fn fricke_identity_matches_commutator() {
+        let a = loxodromic();
+        let b = translation(Complex::new(1.0, 2.0)).unwrap() * rotation(0.7).unwrap();
+
+        let traces = GeneratorTraces::new(a, b);
+        let result = fricke_commutator_trace(traces.a, traces.b, traces.ab);
+
+        assert!((result - traces.commutator).mag() < 1e-10);
+    }
+
+    #[test]
+    pub fn conjugating_map_for_loxodromic() {
+        let a = loxodromic();
+        let s = translation(Complex::new(-1.0, 3.0)).unwrap() * Mobius::INVERSION;
+        let b = s * a * s.inverse();
+
+        assert_conjugates(a, b);
+    }
+
+    #[test]
+    pub fn conjugating_map_for_negated_trace() {
+        let a = scale(3.0).unwrap();
+        let b = Mobius::sandwich(Mobius::INVERSION, a);
+        // Same transformation, opposite sign matrix
+        let b = Mobius {
+            a: -b.a,
+            b: -b.b,
+            c: -b.c,
+            d: -b.d,
+        };
+
+        assert_conjugates(a, b);
+    }
+
+    #[test]
+    pub fn parabolics_are_conjugate() {
+        let a = translation(Complex::ONE).unwrap();
+        let b = Mobius::sandwich(Mobius::INVERSION, translation(Complex::I).unwrap());
+
+        assert_conjugates(a, b);
+    }
+
+    #[test]
+    pub fn identity_is_conjugate_to_itself() {
+        let result = conjugating_map(Mobius::IDENTITY, Mobius::IDENTITY).unwrap();
+
+        assert_eq!(result, Mobius::IDENTITY);
+    }
+
+    #[test_case(Mobius::IDENTITY, translation(Complex::ONE).unwrap(); "identity and parabolic")]
+    #[test_case(scale(2.0).unwrap(), scale(3.0).unwrap(); "different traces")]
+    #[test_case(rotation(1.0).unwrap(), scale(2.0).unwrap(); "elliptic and hyperbolic")]
+    pub fn conjugating_map_with_non_conjugates_returns_error(a: Mobius, b: Mobius) {
+        let result = conjugating_map(a, b);
+
+        assert!(result.is_err());
+        assert!(!a.is_conjugate_to(&b));
+    }
+
+    #[test]
+    pub fn rotations_by_opposite_angles_are_conjugate() {
+        let a = rotation(1.0).unwrap();
+        let b = rotation(-1.0).unwrap();
+
+        assert!(a.is_conjugate_to(&b));
+        assert_conjugates(a, b);
+    }
+}
@@ -0,0 +1,343 @@
+use std::error::Error;
+
+use abstraction::Group;
+
+use crate::{
+    geometry::{CubicBezier, QuadraticBezier},
+    isogonal::Isogonal,
+    polynomial::ComplexPolynomial,
+    rendering::{RenderPrimitive, Renderable},
+    Complex,
+};
+
+use super::Transformable;
+
+/// A refit piece can be this far from the true image, relative to the
+/// length of its chord
+const FIT_TOLERANCE: f64 = 1e-4;
+
+/// Maximum number of times a curve is halved when refitting it
+const MAX_SUBDIVISIONS: usize = 12;
+
+/// How close a root of B(t) - pole must be to the real axis, and how close
+/// B(t) must be to the pole relative to the size of the curve, for the
+/// curve to pass through the pole at t
+const POLE_TOLERANCE: f64 = 1e-9;
+
+/// Maximum Durand-Kerner iterations when looking for poles on a curve
+const MAX_POLE_ITERATIONS: usize = 100;
+
+/// Map a tangent vector v at z through the derivative of the transform.
+/// For z -> (az + b) / (cz + d) the derivative is det / (cz + d)^2. An
+/// anti-conformal map conjugates z and v first
+fn push_forward(xform: Isogonal, z: Complex, v: Complex) -> Complex {
+    let (m, z, v) = match xform {
+        Isogonal::Conformal(m) => (m, z, v),
+        Isogonal::AntiConformal(m) => (m, z.conj(), v.conj()),
+    };
+    let denominator = m.c * z + m.d;
+    m.det() * v / (denominator * denominator)
+}
+
+/// Parameters in (0, 1) where the curve passes through the point the
+/// transform sends to infinity, in increasing order. These are the real
+/// roots of the cubic B(t) - pole
+fn pole_parameters(curve: &CubicBezier, xform: Isogonal) -> Vec<f64> {
+    let pole = xform.inverse() * Complex::Infinity;
+    if pole == Complex::Infinity {
+        return vec![];
+    }
+
+    // Power basis coefficients of B(t) - pole
+    let [p0, p1, p2, p3] = curve.control_points();
+    let three: Complex = 3.0.into();
+    let Ok(polynomial) = ComplexPolynomial::new(vec![
+        p0 - pole,
+        three * (p1 - p0),
+        three * (p2 - p1 - p1 + p0),
+        p3 - three * p2 + three * p1 - p0,
+    ]) else {
+        return vec![];
+    };
+    let Ok(roots) = polynomial.roots(POLE_TOLERANCE, MAX_POLE_ITERATIONS) else {
+        return vec![];
+    };
+
+    let size = [p1, p2, p3]
+        .into_iter()
+        .map(|z| (z - p0).mag())
+        .fold(0.0, f64::max);
+    let mut parameters: Vec<f64> = roots
+        .into_iter()
+        .filter(|t| t.imag().abs() <= POLE_TOLERANCE)
+        .map(|t| t.real())
+        .filter(|t| *t > 0.0 && *t < 1.0)
+        .filter(|t| (curve.interpolate(*t) - pole).mag() <= POLE_TOLERANCE * size)
+        .collect();
+    parameters.sort_by(f64::total_cmp);
+    parameters.dedup_by(|a, b| (*a - *b).abs() <= POLE_TOLERANCE);
+    parameters
+}
+
+/// Fit a cubic to the image of curve restricted to [t0, t1], splitting in
+/// half until the fit is close enough. at_pole says which ends of the
+/// interval map to infinity. Those pieces are halved toward the pole
+/// until MAX_SUBDIVISIONS, and the last piece touching it is dropped, as
+/// are pieces whose image otherwise passes through infinity
+fn fit_image(
+    curve: &CubicBezier,
+    xform: Isogonal,
+    (t0, t1): (f64, f64),
+    at_pole: (bool, bool),
+    depth: usize,
+    output: &mut Vec<CubicBezier>,
+) {
+    if at_pole.0 || at_pole.1 {
+        if depth < MAX_SUBDIVISIONS {
+            let middle = 0.5 * (t0 + t1);
+            fit_image(
+                curve,
+                xform,
+                (t0, middle),
+                (at_pole.0, false),
+                depth + 1,
+                output,
+            );
+            fit_image(
+                curve,
+                xform,
+                (middle, t1),
+                (false, at_pole.1),
+                depth + 1,
+                output,
+            );
+        }
+        return;
+    }
+
+    let image = |t: f64| xform * curve.interpolate(t);
+    let (start, end) = (image(t0), image(t1));
+    if start == Complex::Infinity || end == Complex::Infinity {
+        return;
+    }
+
+    // Velocities of the piece reparametrized to [0, 1]
+    let scale: Complex = (t1 - t0).into();
+    let start_velocity = push_forward(xform, curve.interpolate(t0), curve.derivative(t0)) * scale;
+    let end_velocity = push_forward(xform, curve.interpolate(t1), curve.derivative(t1)) * scale;
+    let fit = CubicBezier::from_hermite(start, start_velocity, end, end_velocity);
+
+    let tolerance = FIT_TOLERANCE * (end - start).mag();
+    let is_close = [0.25, 0.5, 0.75].into_iter().all(|s| {
+        let expected = image(t0 + s * (t1 - t0));
+        expected != Complex::Infinity && (fit.interpolate(s) - expected).mag() <= tolerance
+    });
+
+    if is_close || depth == MAX_SUBDIVISIONS {
+        output.push(fit);
+        return;
+    }
+
+    let middle = 0.5 * (t0 + t1);
+    fit_image(
+        curve,
+        xform,
+        (t0, middle),
+        (false, false),
+        depth + 1,
+        output,
+    );
+    fit_image(
+        curve,
+        xform,
+        (middle, t1),
+        (false, false),
+        depth + 1,
+        output,
+    );
+}
+
+/// A sequence of cubic Bezier curves. Mobius transformations don't map
+/// Bezier curves to Bezier curves, so transforming a path subdivides each
+/// curve and fits a new cubic to each piece of the image, matching the
+/// endpoints and tangents exactly. Quadratic curves are converted to
+/// cubics. Curves through the point that is sent to infinity are split
+/// there, and the image stops short of infinity on either side.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct BezierPath {
+    curves: Vec<CubicBezier>,
+}
+
+impl BezierPath {
+    pub fn new(curves: Vec<CubicBezier>) -> Self {
+        Self { curves }
+    }
+
+    pub fn curves(&self) -> &[CubicBezier] {
+        &self.curves
+    }
+}
+
+impl From<CubicBezier> for BezierPath {
+    fn from(value: CubicBezier) -> Self {
+        Self::new(vec![value])
+    }
+}
+
+impl From<QuadraticBezier> for BezierPath {
+    fn from(value: QuadraticBezier) -> Self {
+        Self::new(vec![value.to_cubic()])
+    }
+}
+
+impl Transformable<Isogonal> for BezierPath {
+    fn transform(&self, xform: Isogonal) -> Self {
+        let mut curves = Vec::new();
+        for curve in self.curves.iter() {
+            let poles = pole_parameters(curve, xform);
+            let mut bounds = vec![0.0];
+            bounds.extend(poles.iter().copied());
+            bounds.push(1.0);
+
+            let pole_count = poles.len();
+            for (i, pair) in bounds.windows(2).enumerate() {
+                let at_pole = (i > 0, i < pole_count);
+                fit_image(curve, xform, (pair[0], pair[1]), at_pole, 0, &mut curves);
+            }
+        }
+
+        Self { curves }
+    }
+}
+
+impl Renderable for BezierPath {
+    fn bake_geometry(&self) -> Result<Vec<RenderPrimitive>, Box<dyn Error>> {
+        Ok(self
+            .curves
+            .iter()
+            .map(|x| RenderPrimitive::CubicBezier(*x))
+            .collect())
+    }
+}
+
+impl Renderable for QuadraticBezier {
+    fn bake_geometry(&self) -> Result<Vec<RenderPrimitive>, Box<dyn Error>> {
+        Ok(vec![RenderPrimitive::QuadraticBezier(*self)])
+    }
+}
+
+impl Renderable for CubicBezier {
+    fn bake_geometry(&self) -> Result<Vec<RenderPrimitive>, Box<dyn Error>> {
+        Ok(vec![RenderPrimitive::CubicBezier(*self)])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rotation, scale, translation, Mobius};
+
+    use super::*;
+
+    fn s_curve() -> CubicBezier {
+        CubicBezier::new(
+            Complex::Zero,
+            Complex::new(1.0, 2.0),
+            Complex::new(2.0, -2.0),
+            Complex::new(3.0, 0.0),
+        )
+    }
+
+    #[test]
+    pub fn similarity_maps_curve_exactly() {
+        let path = BezierPath::from(s_curve());
+        let xform = translation(Complex::I).unwrap() * rotation(1.0).unwrap() * scale(2.0).unwrap();
+
+        let result = path.transform(xform);
+
+        assert_eq!(result.curves().len(), 1);
+        let [a, b, c, d] = s_curve().control_points();
+        let expected = CubicBezier::new(xform * a, xform * b, xform * c, xform * d);
+        assert_eq!(result.curves()[0], expected);
+    }
+
+    #[test]
+    pub fn conj_maps_curve_exactly() {
+        let path = BezierPath::from(s_curve());
+
+        let result = path.transform(Isogonal::conj());
+
+        let [a, b, c, d] = s_curve().control_points();
+        let expected = CubicBezier::new(a.conj(), b.conj(), c.conj(), d.conj());
+        assert_eq!(result.curves(), &[expected]);
+    }
+
+    #[test]
+    pub fn inversion_refits_close_to_image() {
+        // Move the curve away from the pole at 0
+        let offset = Complex::new(2.0, 1.0);
+        let [a, b, c, d] = s_curve().control_points();
+        let curve = CubicBezier::new(a + offset, b + offset, c + offset, d + offset);
+        let path = BezierPath::from(curve);
+        let xform = Isogonal::from(Mobius::INVERSION);
+
+        let result = path.transform(xform);
+
+        assert!(result.curves().len() > 1);
+        assert_eq!(result.curves()[0].start, xform * curve.start);
+        assert_eq!(
+            result.curves()[result.curves().len() - 1].end,
+            xform * curve.end
+        );
+        // Check the image of some points on the curve are close to the
+        // fitted path
+        let fitted: Vec<Complex> = result.curves().iter().flat_map(|x| x.sample(64)).collect();
+        for t in [0.1, 0.3, 0.5, 0.7, 0.9] {
+            let expected = xform * curve.interpolate(t);
+            let distance = fitted
+                .iter()
+                .map(|z| (*z - expected).mag())
+                .fold(f64::INFINITY, f64::min);
+            assert!(distance < 1e-2, "t = {}: distance {}", t, distance);
+        }
+    }
+
+    #[test]
+    pub fn curve_through_pole_off_dyadic_parameter_splits_at_pole() {
+        // B(t) = -1 + 3t passes through the pole of 1/z at t = 1/3
+        let curve = QuadraticBezier::new(-Complex::ONE, Complex::new(0.5, 0.0), 2.0.into());
+        let path = BezierPath::from(curve);
+
+        let result = path.transform(Mobius::INVERSION);
+
+        // The image is the real axis minus infinity, so no piece may jump
+        // from the negative side to the positive side
+        assert!(!result.curves().is_empty());
+        for curve in result.curves() {
+            let signs: Vec<bool> = curve
+                .control_points()
+                .iter()
+                .map(|z| z.real() > 0.0)
+                .collect();
+            assert!(signs.iter().all(|x| *x == signs[0]), "{:?}", curve);
+        }
+        assert_eq!(result.curves()[0].start, -Complex::ONE);
+        assert_eq!(
+            result.curves()[result.curves().len() - 1].end,
+            Complex::new(0.5, 0.0)
+        );
+    }
+
+    #[test]
+    pub fn curve_through_pole_drops_pieces_at_infinity() {
+        let curve = QuadraticBezier::new(-Complex::ONE, Complex::Zero, Complex::ONE);
+        let path = BezierPath::from(curve);
+
+        let result = path.transform(Mobius::INVERSION);
+
+        for curve in result.curves() {
+            for z in curve.control_points() {
+                assert_ne!(z, Complex::Infinity);
+            }
+        }
+    }
+}
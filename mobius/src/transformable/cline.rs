@@ -2,15 +2,54 @@ use std::{error::Error, fmt::Display};
 
 use crate::{
     complex_error::ComplexError,
-    geometry::{Circle, GeneralizedCircle, Line},
+    geometry::{
+        intersect_circle_line, intersect_circles, intersect_lines, Circle, GeneralizedCircle,
+        Intersection, Line,
+    },
     isogonal::Isogonal,
+    nearly::is_nearly,
     rendering::{RenderPrimitive, Renderable},
     unit_complex::UnitComplex,
-    Complex, Mobius,
+    Complex, Mobius, Tolerance,
 };
 
 use super::Transformable;
 
+/// Rounding error allowed when checking whether a computed point is on a
+/// cline, relative to the size of the terms of the implicit equation, at
+/// the default tolerance
+const ON_CLINE_TOLERANCE: f64 = 1e-12;
+
+/// Error allowed when checking whether two computed clines are tangent or
+/// orthogonal, relative to the size of the terms of their inversive
+/// product, at the default tolerance. Clines built from intersections or
+/// transformations carry far more rounding error than a single
+/// comparison allows.
+const RELATION_TOLERANCE: f64 = 1e-9;
+
+/// Which side of a cline a point is on, determined by the sign of the
+/// implicit equation (see Cline).
+///
+/// For circles, Inside is the interior of the disk. For lines, Inside is
+/// the half-plane opposite the unit normal, i.e. dot(n, z) < d.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Side {
+    Inside,
+    On,
+    Outside,
+}
+
+impl Side {
+    /// Swap Inside and Outside, leaving On unchanged
+    pub fn flip(&self) -> Self {
+        match self {
+            Self::Inside => Self::Outside,
+            Self::On => Self::On,
+            Self::Outside => Self::Inside,
+        }
+    }
+}
+
 /// Generalized circle/line, sometimes called a "cline"
 /// See https://en.wikipedia.org/wiki/Generalised_circle
 ///
@@ -72,6 +111,48 @@ impl Cline {
         Ok(gen_circle)
     }
 
+    /// Determine which side of the cline a point is on. See Side for
+    /// how the sides are oriented.
+    ///
+    /// A point counts as On when the two sides of the implicit equation
+    /// agree to within ON_CLINE_TOLERANCE times the size of its terms, so
+    /// computed points like intersections and points sampled along an arc
+    /// still land on the cline even far from the origin.
+    pub fn point_side(&self, z: Complex) -> Side {
+        let &Cline { a, b, c: _, d } = self;
+
+        if z == Complex::Infinity {
+            // Infinity is on every line, and outside every circle
+            return if a == Complex::Zero {
+                Side::On
+            } else {
+                Side::Outside
+            };
+        }
+
+        // Since C = B.conj() and A, D are real, the implicit equation
+        // A z z.conj() + B z + C z.conj() + D = 0 can be rearranged to
+        // A|z|^2 + D = -2 Re(Bz). The terms can be much larger than their
+        // sum, so the band is relative to their sizes rather than to the
+        // two sides.
+        let quadratic = a.real() * z.norm() + d.real();
+        let linear = -2.0 * (b * z).real();
+
+        let size = a.real().abs() * z.norm() + d.real().abs() + 2.0 * b.mag() * z.mag();
+        let tolerance = Tolerance::current();
+        let band = tolerance
+            .absolute
+            .max(tolerance.scale_relative(ON_CLINE_TOLERANCE) * size);
+
+        if (quadratic - linear).abs() <= band {
+            Side::On
+        } else if quadratic < linear {
+            Side::Inside
+        } else {
+            Side::Outside
+        }
+    }
+
     pub fn complex_conjugate(&self) -> Self {
         // computing the complex conjugate of the matrix is just the transpose!
         //
@@ -87,6 +168,172 @@ impl Cline {
         }
     }
 
+    /// Determinant of the coefficient matrix, AD - BC. After normalization
+    /// this is -r^2 for circles and -1 for lines
+    fn determinant(&self) -> f64 {
+        let &Self { a, b, c, d } = self;
+        (a * d - b * c).real()
+    }
+
+    /// Symmetric bilinear form on the coefficient matrices, sometimes called
+    /// the inversive product. A cline's product with itself is its
+    /// determinant. Mobius transformations only rescale it, so ratios of
+    /// these products are conformal invariants.
+    fn inversive_product(&self, other: &Self) -> f64 {
+        let sum = self.a * other.d + other.a * self.d - self.b * other.c - other.b * self.c;
+        0.5 * sum.real()
+    }
+
+    /// Size of the terms of the inversive product, which bounds its
+    /// rounding error
+    fn inversive_product_size(&self, other: &Self) -> f64 {
+        let terms = [
+            self.a * other.d,
+            other.a * self.d,
+            self.b * other.c,
+            other.b * self.c,
+        ];
+        0.5 * terms.iter().map(|x| x.mag()).sum::<f64>()
+    }
+
+    /// How far the inversive product can be from a target value, like 0
+    /// for orthogonal clines, and still count as equal
+    fn relation_tolerance(&self, other: &Self) -> f64 {
+        Tolerance::current().scale_relative(RELATION_TOLERANCE) * self.inversive_product_size(other)
+    }
+
+    /// Check if the coefficients are multiples of each other, meaning
+    /// both describe the same set of points
+    fn is_coincident(&self, other: &Self) -> bool {
+        let &Self { a, b, d, .. } = self;
+        let &Self {
+            a: other_a,
+            b: other_b,
+            d: other_d,
+            ..
+        } = other;
+
+        // The scale factor is real, so B and other B must be parallel as
+        // vectors, and every 2x2 cross term must vanish
+        is_nearly(Complex::dot(b, other_b).abs(), b.mag() * other_b.mag())
+            && a * other_b == other_a * b
+            && a * other_d == other_a * d
+            && b * other_d == other_b * d
+    }
+
+    /// Find the points where two clines cross or touch on the Riemann
+    /// sphere. Lines always meet at infinity, so two crossing lines have
+    /// two intersection points and parallel lines have one. Coincident
+    /// clines meet everywhere, which is reported as no points.
+    pub fn intersection_points(&self, other: &Self) -> Result<Vec<Complex>, ComplexError> {
+        let intersection = match (self.classify()?, other.classify()?) {
+            (GeneralizedCircle::Circle(a), GeneralizedCircle::Circle(b)) => intersect_circles(a, b),
+            (GeneralizedCircle::Circle(circle), GeneralizedCircle::Line(line))
+            | (GeneralizedCircle::Line(line), GeneralizedCircle::Circle(circle)) => {
+                intersect_circle_line(circle, line)
+            }
+            (GeneralizedCircle::Line(a), GeneralizedCircle::Line(b)) => {
+                let intersection = intersect_lines(a, b);
+                if intersection == Intersection::Coincident {
+                    return Ok(vec![]);
+                }
+
+                let mut points = intersection.points();
+                points.push(Complex::Infinity);
+                return Ok(points);
+            }
+        };
+
+        Ok(intersection.points())
+    }
+
+    /// Check if two clines are the same set of points, allowing each
+    /// coefficient to differ by up to tolerance after scaling both clines
+    /// to unit size. This is more forgiving than ==, which needs the
+    /// coefficients to match to within rounding error, and unlike == it
+    /// ignores the orientation of lines.
+    pub fn is_same_cline(&self, other: &Self, tolerance: f64) -> bool {
+        let normalize = |x: &Self| {
+            let coefficients = [x.a, x.b, x.d];
+            let length = coefficients.iter().map(|z| z.norm()).sum::<f64>().sqrt();
+            coefficients.map(|z| z / length.into())
+        };
+
+        let [a, b, d] = normalize(self);
+        let [other_a, other_b, other_d] = normalize(other);
+        let close = |sign: f64| {
+            let sign: Complex = sign.into();
+            [(a, other_a), (b, other_b), (d, other_d)]
+                .iter()
+                .all(|&(x, y)| (x - sign * y).mag() <= tolerance)
+        };
+
+        close(1.0) || close(-1.0)
+    }
+
+    /// Compute the inversive distance between two clines. For circles this
+    /// is (d^2 - r1^2 - r2^2) / (2 r1 r2) where d is the distance between
+    /// the centers. It's 1 for externally tangent circles, 0 for
+    /// orthogonal ones and -1 for internally tangent ones. Values above 1
+    /// mean the circles are outside each other, below -1 means one is
+    /// nested in the other.
+    ///
+    /// The magnitude is a Mobius invariant. The sign can flip when a
+    /// transformation turns the inside of a circle into the outside.
+    pub fn inversive_distance(&self, other: &Self) -> f64 {
+        let scale = self.determinant() * other.determinant();
+        self.inversive_product(other) / scale.sqrt()
+    }
+
+    /// Compute the angle between two clines where they cross, measured
+    /// between their normals: the outward normal for circles and the unit
+    /// normal for lines. This is in [0, pi]. Externally tangent circles
+    /// meet at an angle of pi, internally tangent circles at 0.
+    ///
+    /// Returns None if the clines don't meet.
+    pub fn intersection_angle(&self, other: &Self) -> Option<f64> {
+        let product = self.inversive_product(other);
+        let scale = self.determinant() * other.determinant();
+
+        // Tangent clines still meet, with the same tolerance as
+        // is_tangent_to()
+        if product.abs() > scale.sqrt() + self.relation_tolerance(other) {
+            return None;
+        }
+
+        let cos_angle = -self.inversive_distance(other);
+        Some(cos_angle.clamp(-1.0, 1.0).acos())
+    }
+
+    /// Check if two clines cross at right angles. For circles this is
+    /// r1^2 + r2^2 = |c1 - c2|^2.
+    pub fn is_orthogonal_to(&self, other: &Self) -> bool {
+        self.inversive_product(other).abs() <= self.relation_tolerance(other)
+    }
+
+    /// Check if two clines touch at exactly one point. Parallel lines
+    /// count as tangent at infinity. A cline isn't tangent to itself.
+    pub fn is_tangent_to(&self, other: &Self) -> bool {
+        // The inversive distance is +/- 1
+        let product = self.inversive_product(other);
+        let scale = self.determinant() * other.determinant();
+        let is_tangent = (product.abs() - scale.sqrt()).abs() <= self.relation_tolerance(other);
+        is_tangent && !self.is_coincident(other)
+    }
+
+    /// Reflection in this cline: inversion for circles, mirror reflection
+    /// for lines. Solving the implicit equation for the image w of z gives
+    ///
+    /// w = (-C conj(z) - D) / (A conj(z) + B)
+    ///
+    /// which is an anti-conformal map. This returns an error for a
+    /// degenerate cline (e.g. a circle of radius 0)
+    pub fn reflection(&self) -> Result<Isogonal, String> {
+        let &Self { a, b, c, d } = self;
+        let m = Mobius::from_unnormalized(-c, -d, a, b)?;
+        Ok(Isogonal::AntiConformal(m))
+    }
+
     fn transform(xform: Mobius, cline: Cline) -> Self {
         // According to the Wikipedia article, the implicit equation
         // can be written 0 = z^T C conj(z)
@@ -234,6 +481,10 @@ impl Display for Cline {
 
 #[cfg(test)]
 mod test {
+    use std::f64::consts::{PI, TAU};
+
+    use crate::{nearly::assert_nearly, random::SplitMix64};
+
     use super::*;
 
     #[test]
@@ -286,4 +537,335 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    pub fn point_side_of_unit_circle() {
+        let circle = Cline::unit_circle();
+
+        assert_eq!(circle.point_side(Complex::new(0.5, 0.25)), Side::Inside);
+        assert_eq!(circle.point_side(Complex::I), Side::On);
+        assert_eq!(circle.point_side(Complex::new(2.0, -1.0)), Side::Outside);
+        assert_eq!(circle.point_side(Complex::Infinity), Side::Outside);
+    }
+
+    #[test]
+    pub fn point_side_of_offset_circle() {
+        let circle: Cline = Circle::new(Complex::new(3.0, 4.0), 2.0).into();
+
+        assert_eq!(circle.point_side(Complex::new(3.0, 5.0)), Side::Inside);
+        assert_eq!(circle.point_side(Complex::new(5.0, 4.0)), Side::On);
+        assert_eq!(circle.point_side(Complex::Zero), Side::Outside);
+    }
+
+    #[test]
+    pub fn point_side_of_computed_points_on_offset_circles_is_on() {
+        let circles = [
+            Circle::new(Complex::new(1.3, 2.7), 4.1),
+            Circle::new(Complex::new(-250.0, 1e3), 0.75),
+        ];
+
+        for circle in circles {
+            let cline = Cline::from(circle);
+            for i in 0..100 {
+                let point = circle.get_point(0.37 * i as f64);
+                assert_eq!(cline.point_side(point), Side::On, "{}", point);
+            }
+        }
+    }
+
+    #[test]
+    pub fn point_side_of_intersection_points_is_on() -> Result<(), ComplexError> {
+        let a = Cline::from(Circle::new(Complex::new(1.3, 2.7), 4.1));
+        let b = Cline::from(Circle::new(Complex::new(-2.2, 0.9), 3.3));
+
+        let points = a.intersection_points(&b)?;
+
+        assert_eq!(points.len(), 2);
+        for point in points {
+            assert_eq!(a.point_side(point), Side::On);
+            assert_eq!(b.point_side(point), Side::On);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn point_side_of_line_is_outside_in_normal_direction() {
+        // unit normal is i, so the upper half-plane is outside
+        let line = Cline::real_axis();
+
+        assert_eq!(line.point_side(Complex::new(1.0, 1.0)), Side::Outside);
+        assert_eq!(line.point_side(Complex::new(5.0, 0.0)), Side::On);
+        assert_eq!(line.point_side(Complex::new(1.0, -1.0)), Side::Inside);
+        assert_eq!(line.point_side(Complex::Infinity), Side::On);
+    }
+
+    #[test]
+    pub fn point_side_of_offset_line() {
+        let line: Cline = Line::new(UnitComplex::ONE, 2.0).unwrap().into();
+
+        assert_eq!(line.point_side(Complex::new(3.0, 1.0)), Side::Outside);
+        assert_eq!(line.point_side(Complex::new(2.0, -7.0)), Side::On);
+        assert_eq!(line.point_side(Complex::ONE), Side::Inside);
+    }
+
+    #[test]
+    pub fn reflection_matches_circle_inversion_and_conj() -> Result<(), String> {
+        let circle = Circle::new(Complex::new(1.0, 2.0), 3.0);
+
+        let inversion = Cline::from(circle).reflection()?;
+        let conj = Cline::real_axis().reflection()?;
+
+        assert_eq!(inversion, Isogonal::circle_inversion(circle)?);
+        assert_eq!(conj * Complex::new(2.0, 5.0), Complex::new(2.0, -5.0));
+        Ok(())
+    }
+
+    #[test]
+    pub fn reflection_fixes_the_mirror() -> Result<(), String> {
+        let line = Line::from_points(Complex::ONE, Complex::new(0.0, 2.0)).unwrap();
+        let mirror = Cline::from(line);
+
+        let reflection = mirror.reflection()?;
+
+        assert_eq!(reflection * Complex::ONE, Complex::ONE);
+        assert_eq!(reflection * Complex::Zero, Complex::new(1.6, 0.8));
+        assert!(mirror.transform(reflection).is_same_cline(&mirror, 1e-12));
+        Ok(())
+    }
+
+    #[test]
+    pub fn is_orthogonal_to_detects_orthogonal_clines() {
+        let unit_circle = Cline::unit_circle();
+        let orthogonal: Cline = Circle::new(Complex::new(2.0, 0.0), 3.0f64.sqrt()).into();
+        let crossing: Cline = Circle::new(Complex::ONE, 1.0).into();
+
+        assert!(unit_circle.is_orthogonal_to(&Cline::real_axis()));
+        assert!(unit_circle.is_orthogonal_to(&orthogonal));
+        assert!(Cline::real_axis().is_orthogonal_to(&Cline::imag_axis()));
+        assert!(!unit_circle.is_orthogonal_to(&crossing));
+    }
+
+    #[test]
+    pub fn is_tangent_to_detects_tangent_clines() {
+        let unit_circle = Cline::unit_circle();
+        let outside: Cline = Circle::new(Complex::new(3.0, 0.0), 2.0).into();
+        let inside: Cline = Circle::new(Complex::new(0.5, 0.0), 0.5).into();
+        let touching_line: Cline = Line::new(UnitComplex::ONE, 1.0).unwrap().into();
+
+        assert!(unit_circle.is_tangent_to(&outside));
+        assert!(unit_circle.is_tangent_to(&inside));
+        assert!(unit_circle.is_tangent_to(&touching_line));
+        assert!(!unit_circle.is_tangent_to(&Cline::real_axis()));
+        assert!(!unit_circle.is_tangent_to(&unit_circle));
+    }
+
+    /// Random circle with center in [-10, 10]^2 and radius in [0.1, 10]
+    fn random_circle(rng: &mut SplitMix64) -> Circle {
+        let mut coordinate = || 20.0 * rng.next_f64() - 10.0;
+        let center = Complex::new(coordinate(), coordinate());
+        Circle::new(center, 0.1 + 9.9 * rng.next_f64())
+    }
+
+    #[test]
+    pub fn is_tangent_to_accepts_random_tangent_circles() {
+        let mut rng = SplitMix64::new(2319);
+        for _ in 0..200 {
+            let circle = random_circle(&mut rng);
+            let direction = Complex::from_polar(1.0, TAU * rng.next_f64());
+            let radius = circle.radius * (0.05 + 0.9 * rng.next_f64());
+            let outside = Circle::new(
+                circle.center + direction * (circle.radius + radius).into(),
+                radius,
+            );
+            let inside = Circle::new(
+                circle.center + direction * (circle.radius - radius).into(),
+                radius,
+            );
+
+            for other in [outside, inside] {
+                assert!(
+                    Cline::from(circle).is_tangent_to(&Cline::from(other)),
+                    "{} and {}",
+                    circle,
+                    other
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn is_orthogonal_to_accepts_random_orthogonal_circles() {
+        let mut rng = SplitMix64::new(2319);
+        for _ in 0..200 {
+            let circle = random_circle(&mut rng);
+            let direction = Complex::from_polar(1.0, TAU * rng.next_f64());
+            let distance = circle.radius * (1.05 + 5.0 * rng.next_f64());
+            let other = Circle::new(
+                circle.center + direction * distance.into(),
+                (distance * distance - circle.radius * circle.radius).sqrt(),
+            );
+
+            assert!(
+                Cline::from(circle).is_orthogonal_to(&Cline::from(other)),
+                "{} and {}",
+                circle,
+                other
+            );
+        }
+    }
+
+    #[test]
+    pub fn random_crossing_circles_are_neither_tangent_nor_orthogonal() {
+        let mut rng = SplitMix64::new(2319);
+        for _ in 0..200 {
+            let circle = random_circle(&mut rng);
+            let direction = Complex::from_polar(1.0, TAU * rng.next_f64());
+            // Centers are closer than for tangent circles, and further
+            // than for orthogonal circles
+            let radius = circle.radius * (0.5 + rng.next_f64());
+            let orthogonal_distance = (circle.radius.powi(2) + radius.powi(2)).sqrt();
+            let tangent_distance = circle.radius + radius;
+            let distance = 0.5 * (orthogonal_distance + tangent_distance);
+            let other = Circle::new(circle.center + direction * distance.into(), radius);
+
+            let (a, b) = (Cline::from(circle), Cline::from(other));
+            assert!(!a.is_tangent_to(&b));
+            assert!(!a.is_orthogonal_to(&b));
+        }
+    }
+
+    #[test]
+    pub fn is_tangent_to_treats_parallel_lines_as_tangent_at_infinity() {
+        let shifted: Cline = Line::new(UnitComplex::I, 2.0).unwrap().into();
+        let flipped: Cline = Line::new(-UnitComplex::I, 0.0).unwrap().into();
+
+        assert!(Cline::real_axis().is_tangent_to(&shifted));
+        assert!(!Cline::real_axis().is_tangent_to(&flipped));
+    }
+
+    #[test]
+    pub fn intersection_angle_measures_angle_between_normals() {
+        let unit_circle = Cline::unit_circle();
+        let crossing: Cline = Circle::new(Complex::ONE, 1.0).into();
+        let outside: Cline = Circle::new(Complex::new(3.0, 0.0), 2.0).into();
+
+        assert_nearly(unit_circle.intersection_angle(&crossing).unwrap(), PI / 3.0);
+        assert_nearly(
+            Cline::real_axis()
+                .intersection_angle(&Cline::imag_axis())
+                .unwrap(),
+            PI / 2.0,
+        );
+        assert_nearly(unit_circle.intersection_angle(&outside).unwrap(), PI);
+    }
+
+    #[test]
+    pub fn intersection_angle_of_disjoint_clines_is_none() {
+        let far: Cline = Circle::new(Complex::new(5.0, 0.0), 1.0).into();
+        let nested: Cline = Circle::new(Complex::Zero, 0.5).into();
+
+        assert_eq!(Cline::unit_circle().intersection_angle(&far), None);
+        assert_eq!(Cline::unit_circle().intersection_angle(&nested), None);
+    }
+
+    #[test]
+    pub fn intersection_angle_is_preserved_by_mobius_maps() {
+        let unit_circle = Cline::unit_circle();
+        let crossing: Cline = Circle::new(Complex::ONE, 1.0).into();
+        let xform = Mobius::from_unnormalized(
+            Complex::new(1.0, 1.0),
+            Complex::new(0.0, 2.0),
+            Complex::ONE,
+            Complex::new(3.0, 1.0),
+        )
+        .unwrap();
+
+        let before = unit_circle.intersection_angle(&crossing).unwrap();
+        let after = unit_circle
+            .transform(xform)
+            .intersection_angle(&crossing.transform(xform))
+            .unwrap();
+
+        assert!((before - after).abs() < 1e-12);
+    }
+
+    #[test]
+    pub fn inversive_distance_of_circles() {
+        let unit_circle = Cline::unit_circle();
+        let far: Cline = Circle::new(Complex::new(4.0, 0.0), 2.0).into();
+        let orthogonal: Cline = Circle::new(Complex::new(2.0, 0.0), 3.0f64.sqrt()).into();
+        let nested: Cline = Circle::new(Complex::Zero, 2.0).into();
+
+        assert_nearly(unit_circle.inversive_distance(&far), 11.0 / 4.0);
+        assert_nearly(unit_circle.inversive_distance(&orthogonal), 0.0);
+        assert_nearly(unit_circle.inversive_distance(&nested), -5.0 / 4.0);
+    }
+
+    #[test]
+    pub fn inversive_distance_magnitude_is_preserved_by_mobius_maps() {
+        let a: Cline = Circle::new(Complex::new(-1.0, 0.5), 0.5).into();
+        let b: Cline = Circle::new(Complex::new(2.0, 1.0), 1.5).into();
+        let xform = Mobius::from_unnormalized(
+            Complex::ONE,
+            Complex::new(0.5, -0.5),
+            Complex::new(1.0, 1.0),
+            Complex::new(2.0, 0.0),
+        )
+        .unwrap();
+
+        let before = a.inversive_distance(&b).abs();
+        let after = a
+            .transform(xform)
+            .inversive_distance(&b.transform(xform))
+            .abs();
+
+        assert!((before - after).abs() < 1e-12);
+    }
+
+    #[test]
+    pub fn intersection_points_of_circle_and_line() {
+        let result = Cline::unit_circle()
+            .intersection_points(&Cline::imag_axis())
+            .unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert!(result.contains(&Complex::I));
+        assert!(result.contains(&-Complex::I));
+    }
+
+    #[test]
+    pub fn intersection_points_of_lines_include_infinity() {
+        let shifted: Cline = Line::new(UnitComplex::I, 2.0).unwrap().into();
+
+        let crossing = Cline::real_axis()
+            .intersection_points(&Cline::imag_axis())
+            .unwrap();
+        let parallel = Cline::real_axis().intersection_points(&shifted).unwrap();
+        let same = Cline::real_axis()
+            .intersection_points(&Cline::real_axis())
+            .unwrap();
+
+        assert_eq!(crossing, vec![Complex::Zero, Complex::Infinity]);
+        assert_eq!(parallel, vec![Complex::Infinity]);
+        assert_eq!(same, vec![]);
+    }
+
+    #[test]
+    pub fn is_same_cline_ignores_scale_and_orientation() {
+        let flipped: Cline = Line::new(-UnitComplex::I, 0.0).unwrap().into();
+        let nudged: Cline = Circle::new(Complex::new(1e-10, 0.0), 1.0).into();
+        let other: Cline = Circle::new(Complex::new(0.1, 0.0), 1.0).into();
+
+        assert!(Cline::real_axis().is_same_cline(&flipped, 1e-9));
+        assert!(Cline::unit_circle().is_same_cline(&nudged, 1e-9));
+        assert!(!Cline::unit_circle().is_same_cline(&other, 1e-9));
+    }
+
+    #[test]
+    pub fn flip_swaps_inside_and_outside() {
+        assert_eq!(Side::Inside.flip(), Side::Outside);
+        assert_eq!(Side::On.flip(), Side::On);
+        assert_eq!(Side::Outside.flip(), Side::Inside);
+    }
 }
@@ -1,9 +1,15 @@
 use std::{error::Error, fmt::Display};
 
+use abstraction::Semigroup;
+
 use crate::{
-    cline_arc::ClineArc,
+    cline_arc::{ClineArc, ClineArcError, ClineArcGeometry},
+    geometry::Circle,
     isogonal::Isogonal,
     rendering::{RenderPrimitive, Renderable},
+    unit_complex::UnitComplex,
+    winding::{loop_contains, winding_number, FillRule},
+    Complex,
 };
 
 use super::{Cline, Transformable};
@@ -32,6 +38,16 @@ impl<T: Transformable<Isogonal>> Transformable<Isogonal> for Collection<T> {
     }
 }
 
+/// Collections form a semigroup under concatenation. This makes it easy to
+/// flatten the output of an IFS into a single drawable collection with
+/// Semigroup::sconcat()
+impl<T: Transformable<Isogonal> + Clone> Semigroup for Collection<T> {
+    fn combine(mut self, other: Self) -> Self {
+        self.primitives.extend(other.primitives);
+        self
+    }
+}
+
 impl<T: Display + Transformable<Isogonal>> Display for Collection<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for primitive in self.primitives.iter() {
@@ -65,3 +81,335 @@ impl<T: Renderable + Transformable<Isogonal>> Renderable for Collection<T> {
 
 pub type ClineTile = Collection<Cline>;
 pub type ClineArcTile = Collection<ClineArc>;
+
+/// Distance between two arc endpoints. Infinity only meets itself
+fn endpoint_gap(a: Complex, b: Complex) -> f64 {
+    match (a, b) {
+        (Complex::Infinity, Complex::Infinity) => 0.0,
+        (Complex::Infinity, _) | (_, Complex::Infinity) => f64::INFINITY,
+        (a, b) => (b - a).mag(),
+    }
+}
+
+/// A place where an arc of a ClineArcTile doesn't end where the next arc
+/// starts
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChainGap {
+    /// Index of the arc before the gap
+    pub edge: usize,
+    /// Index of the arc after the gap
+    pub next: usize,
+    pub end: Complex,
+    pub start: Complex,
+}
+
+impl ChainGap {
+    pub fn distance(&self) -> f64 {
+        endpoint_gap(self.end, self.start)
+    }
+}
+
+impl Display for ChainGap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "edge {} ends at {} but edge {} starts at {} (gap {:.3e})",
+            self.edge,
+            self.end,
+            self.next,
+            self.start,
+            self.distance()
+        )
+    }
+}
+
+/// A tile whose arcs are in order around its boundary can be treated as a
+/// polygon with circular arc edges
+impl ClineArcTile {
+    fn edges(&self) -> Result<Vec<ClineArcGeometry>, ClineArcError> {
+        self.primitives.iter().map(|x| x.classify()).collect()
+    }
+
+    /// Find every pair of consecutive arcs (including the last and first)
+    /// whose endpoints are more than tolerance apart
+    pub fn gaps(&self, tolerance: f64) -> Vec<ChainGap> {
+        let n = self.primitives.len();
+        (0..n)
+            .map(|edge| {
+                let next = (edge + 1) % n;
+                ChainGap {
+                    edge,
+                    next,
+                    end: self.primitives[edge].end(),
+                    start: self.primitives[next].start(),
+                }
+            })
+            .filter(|gap| gap.distance() > tolerance)
+            .collect()
+    }
+
+    /// Check that the arcs form a closed loop in order, which is needed
+    /// for contains() and offset(). The error lists every gap
+    pub fn validate_chain(&self, tolerance: f64) -> Result<(), String> {
+        let gaps = self.gaps(tolerance);
+        if gaps.is_empty() {
+            return Ok(());
+        }
+
+        let lines: Vec<String> = gaps.iter().map(|x| x.to_string()).collect();
+        Err(format!(
+            "arcs do not form a closed loop:\n{}",
+            lines.join("\n")
+        ))
+    }
+
+    /// Reorder the arcs so each one starts where the previous one ended,
+    /// starting from the first arc. Arcs keep their direction. This
+    /// returns an error if some arc can't be continued or the arcs
+    /// don't close up into a single loop
+    pub fn chain(&self, tolerance: f64) -> Result<Self, String> {
+        let mut remaining: Vec<usize> = (1..self.primitives.len()).collect();
+        let mut order = Vec::with_capacity(self.primitives.len());
+        order.extend(self.primitives.first().map(|_| 0));
+
+        while let Some(&last) = order.last() {
+            if remaining.is_empty() {
+                break;
+            }
+
+            let end = self.primitives[last].end();
+            let Some(position) = remaining
+                .iter()
+                .position(|&i| endpoint_gap(end, self.primitives[i].start()) <= tolerance)
+            else {
+                return Err(format!(
+                    "no arc starts where edge {} ends ({}), remaining edges: {:?}",
+                    last, end, remaining
+                ));
+            };
+            order.push(remaining.remove(position));
+        }
+
+        let result = Self::new(order.iter().map(|&i| self.primitives[i]).collect());
+        result.validate_chain(tolerance)?;
+        Ok(result)
+    }
+
+    /// Compute the winding number of the boundary around a point. See
+    /// winding::winding_number()
+    pub fn winding_number(&self, point: Complex) -> Result<f64, ClineArcError> {
+        Ok(winding_number(&self.edges()?, point))
+    }
+
+    /// Check if a point is inside the tile using the given fill rule.
+    /// This needs the arcs in order around the boundary, and handles arcs
+    /// through infinity. See algorithms::tile_contains() for tiles made of
+    /// finite arcs in any order.
+    pub fn contains(&self, point: Complex, rule: FillRule) -> Result<bool, ClineArcError> {
+        Ok(loop_contains(&self.edges()?, point, rule))
+    }
+
+    /// Offset the boundary a signed distance to the right of each arc, so
+    /// a counterclockwise tile grows when the distance is positive. See
+    /// ClineArc::offset().
+    ///
+    /// Where the offset arcs no longer meet at a corner, they are joined
+    /// with an arc of radius |distance| around the corner, like a stroke
+    /// with round joins. At corners that turn the other way, the offset
+    /// arcs cross each other and the join makes a small loop.
+    pub fn offset(&self, distance: f64) -> Result<Self, String> {
+        let offsets = self
+            .primitives
+            .iter()
+            .map(|x| x.offset(distance))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let n = offsets.len();
+        let mut primitives = Vec::with_capacity(2 * n);
+        for (i, arc) in offsets.iter().enumerate() {
+            primitives.push(*arc);
+
+            let corner = self.primitives[i].end();
+            let next = &offsets[(i + 1) % n];
+            if let Some(join) = round_join(corner, arc.end(), next.start(), distance) {
+                primitives.push(join);
+            }
+        }
+
+        Ok(Self { primitives })
+    }
+}
+
+/// Arc around a corner from one offset arc to the next, or None if the
+/// offset arcs already meet or the corner is at infinity
+fn round_join(corner: Complex, from: Complex, to: Complex, distance: f64) -> Option<ClineArc> {
+    if corner == Complex::Infinity || from == to {
+        return None;
+    }
+
+    // The join bulges away from the corner, halfway between the two ends
+    let bisector = UnitComplex::normalize((from - corner) + (to - corner)).ok()?;
+    let middle = corner + *bisector.get() * distance.abs().into();
+    let circle = Circle::new(corner, distance.abs());
+
+    Some(ClineArc::new(Cline::from(circle), from, middle, to))
+}
+
+/// Axis-aligned square with the given bottom left corner, with its edges
+/// counterclockwise starting from that corner
+#[cfg(test)]
+pub fn square_tile(corner: Complex, side: f64) -> ClineArcTile {
+    let corners = [
+        corner,
+        corner + Complex::new(side, 0.0),
+        corner + Complex::new(side, side),
+        corner + Complex::new(0.0, side),
+    ];
+    ClineArcTile::new(
+        (0..4)
+            .map(|i| crate::geometry::LineSegment::new(corners[i], corners[(i + 1) % 4]).into())
+            .collect(),
+    )
+}
+
+/// A whole circle as two semicircular arcs, upper then lower
+#[cfg(test)]
+pub fn circle_tile(circle: Circle) -> ClineArcTile {
+    use crate::geometry::{ArcAngles, CircularArc};
+
+    let (upper, lower) = ArcAngles::semicircles();
+    ClineArcTile::new(vec![
+        CircularArc::new(circle, upper).into(),
+        CircularArc::new(circle, lower).into(),
+    ])
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        algorithms::GridIFS,
+        geometry::{ArcAngles, Circle, CircularArc, LineSegment},
+        translation,
+    };
+
+    use super::*;
+
+    #[test]
+    pub fn sconcat_flattens_ifs_output() {
+        let tile = ClineTile::new(vec![Cline::unit_circle(), Cline::real_axis()]);
+        let ifs = GridIFS::new(vec![(translation(Complex::ONE).unwrap(), 0, 3)]);
+        let applied = ifs.apply(&tile);
+
+        let result = ClineTile::sconcat(&applied).unwrap();
+
+        assert_eq!(result.get_primitives().len(), 6);
+        assert_eq!(result.get_primitives()[2], applied[1].get_primitives()[0]);
+    }
+
+    fn shuffle(tile: &ClineArcTile, order: &[usize]) -> ClineArcTile {
+        ClineArcTile::new(order.iter().map(|&i| tile.get_primitives()[i]).collect())
+    }
+
+    #[test]
+    pub fn validate_chain_accepts_closed_loop() {
+        let tile = square_tile(Complex::Zero, 1.0);
+
+        assert!(tile.gaps(1e-12).is_empty());
+        assert!(tile.validate_chain(1e-12).is_ok());
+    }
+
+    #[test]
+    pub fn validate_chain_reports_each_gap() {
+        let tile = shuffle(&square_tile(Complex::Zero, 1.0), &[0, 2, 1, 3]);
+
+        let gaps = tile.gaps(1e-12);
+        let result = tile.validate_chain(1e-12);
+
+        let pairs: Vec<(usize, usize)> = gaps.iter().map(|x| (x.edge, x.next)).collect();
+        assert_eq!(pairs, vec![(0, 1), (1, 2), (2, 3)]);
+        assert!(result.is_err_and(|e| e.contains("edge 0 ends at") && e.contains("edge 3")));
+    }
+
+    #[test]
+    pub fn chain_reorders_arcs() {
+        let square = square_tile(Complex::Zero, 1.0);
+        let tile = shuffle(&square, &[0, 3, 1, 2]);
+
+        let result = tile.chain(1e-12).unwrap();
+
+        assert_eq!(result.get_primitives().len(), 4);
+        for (a, b) in result.get_primitives().iter().zip(square.get_primitives()) {
+            assert_eq!(a.start(), b.start());
+            assert_eq!(a.end(), b.end());
+        }
+    }
+
+    #[test]
+    pub fn chain_with_missing_arc_returns_error() {
+        let tile = shuffle(&square_tile(Complex::Zero, 1.0), &[0, 2, 3]);
+
+        let result = tile.chain(1e-12);
+
+        assert!(result.is_err_and(|e| e.contains("edge 0")));
+    }
+
+    #[test]
+    pub fn offset_square_adds_round_corners() {
+        let tile = square_tile(Complex::Zero, 1.0);
+
+        let result = tile.offset(0.25).unwrap();
+
+        assert_eq!(result.get_primitives().len(), 8);
+        assert_eq!(result.get_primitives()[0].start(), Complex::new(0.0, -0.25));
+        let corner = result.get_primitives()[1].classify().unwrap();
+        let ClineArcGeometry::CircularArc(arc) = corner else {
+            panic!("expected a round join, got {}", corner);
+        };
+        assert_eq!(arc.circle, Circle::new(Complex::ONE, 0.25));
+        assert!(result
+            .contains(Complex::new(1.1, 1.1), FillRule::NonZero)
+            .unwrap());
+        assert!(!result
+            .contains(Complex::new(1.2, 1.2), FillRule::NonZero)
+            .unwrap());
+    }
+
+    #[test]
+    pub fn offset_disk_scales_arcs() {
+        let (upper, lower) = ArcAngles::semicircles();
+        let tile = ClineArcTile::new(vec![
+            CircularArc::new(Circle::unit_circle(), upper).into(),
+            CircularArc::new(Circle::unit_circle(), lower).into(),
+        ]);
+
+        let result = tile.offset(-0.5).unwrap();
+
+        // Smooth boundaries don't need joins
+        assert_eq!(result.get_primitives().len(), 2);
+        assert!(result
+            .contains(Complex::new(0.4, 0.0), FillRule::NonZero)
+            .unwrap());
+        assert!(!result
+            .contains(Complex::new(0.6, 0.0), FillRule::NonZero)
+            .unwrap());
+    }
+
+    #[test]
+    pub fn cline_arc_tile_contains_points_in_half_disk() {
+        let upper = ArcAngles::new(0.0, std::f64::consts::PI).unwrap();
+        let tile = ClineArcTile::new(vec![
+            CircularArc::new(Circle::unit_circle(), upper).into(),
+            LineSegment::new(-Complex::ONE, Complex::ONE).into(),
+        ]);
+
+        assert!(tile
+            .contains(Complex::new(0.0, 0.5), FillRule::NonZero)
+            .unwrap());
+        assert!(!tile
+            .contains(Complex::new(0.0, -0.5), FillRule::NonZero)
+            .unwrap());
+        assert!(!tile
+            .contains(Complex::new(0.0, 1.5), FillRule::NonZero)
+            .unwrap());
+    }
+}
@@ -0,0 +1,92 @@
+use std::error::Error;
+
+use crate::{
+    isogonal::Isogonal,
+    rendering::{RenderPrimitive, Renderable, TextAnchor},
+    Complex,
+};
+
+use super::Transformable;
+
+/// A text annotation, e.g. the name of a fixed point or a generator.
+/// Transforming a label moves its position and scales its size by the
+/// local scale factor there, but the text itself stays upright.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Label {
+    pub position: Complex,
+    pub content: String,
+    pub size: f64,
+    pub anchor: TextAnchor,
+}
+
+impl Label {
+    /// Label centered horizontally at position
+    pub fn new(position: Complex, content: &str, size: f64) -> Self {
+        Self {
+            position,
+            content: String::from(content),
+            size,
+            anchor: TextAnchor::Middle,
+        }
+    }
+
+    pub fn with_anchor(self, anchor: TextAnchor) -> Self {
+        Self { anchor, ..self }
+    }
+}
+
+impl Transformable<Isogonal> for Label {
+    fn transform(&self, xform: Isogonal) -> Self {
+        Self {
+            position: xform * self.position,
+            content: self.content.clone(),
+            size: self.size * xform.scale_factor(self.position),
+            anchor: self.anchor,
+        }
+    }
+}
+
+impl Renderable for Label {
+    /// Labels at infinity are skipped, like PointCloud
+    fn bake_geometry(&self) -> Result<Vec<RenderPrimitive>, Box<dyn Error>> {
+        if self.position == Complex::Infinity {
+            return Ok(vec![]);
+        }
+
+        Ok(vec![RenderPrimitive::Text {
+            position: self.position,
+            content: self.content.clone(),
+            size: self.size,
+            anchor: self.anchor,
+        }])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{scale, translation};
+
+    use super::*;
+
+    #[test]
+    pub fn transform_scales_size() {
+        let label = Label::new(Complex::ONE, "z", 0.1);
+        let xform = translation(Complex::I).unwrap() * scale(2.0).unwrap();
+
+        let result = label.transform(xform);
+
+        assert_eq!(result.position, Complex::new(2.0, 1.0));
+        assert!((result.size - 0.2).abs() < 1e-12);
+        assert_eq!(result.content, "z");
+    }
+
+    #[test]
+    pub fn bake_geometry_skips_infinity() -> Result<(), Box<dyn Error>> {
+        let label = Label::new(Complex::Infinity, "inf", 0.1);
+
+        let result = label.bake_geometry()?;
+
+        assert!(result.is_empty());
+        Ok(())
+    }
+}
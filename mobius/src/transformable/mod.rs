@@ -1,11 +1,17 @@
+pub mod bezier_path;
 pub mod cline;
 pub mod collection;
+pub mod label;
 pub mod motif;
+pub mod point_cloud;
 
 use crate::{isogonal::Isogonal, Mobius};
+pub use bezier_path::*;
 pub use cline::*;
 pub use collection::*;
+pub use label::*;
 pub use motif::*;
+pub use point_cloud::*;
 
 pub trait Transformable<T> {
     fn transform(&self, xform: T) -> Self;
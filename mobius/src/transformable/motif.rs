@@ -1,47 +1,228 @@
-use std::fmt::Display;
+use std::{error::Error, fmt::Display};
 
-use crate::isogonal::Isogonal;
+use abstraction::Semigroup;
+
+use crate::{
+    algorithms::arc_boolean::motif_boolean,
+    isogonal::Isogonal,
+    rendering::{RenderPrimitive, Renderable},
+};
 
 use super::{ClineArcTile, Transformable};
 
+/// One piece of a motif: either some geometry with a style id, or a whole
+/// motif nested inside this one
+#[derive(Clone)]
+enum Part<T: Transformable<Isogonal>> {
+    Geometry(T, usize),
+    Nested(Motif<T>),
+}
+
+/// A reusable picture made of several pieces of geometry, each with a
+/// style id (an index into a list of styles chosen at render time) and a
+/// z-order. Motifs can be nested, so a compound character can be built
+/// from smaller motifs and still be transformed as one value.
+///
+/// Pieces are drawn from lowest to highest z-order. A nested motif is
+/// shifted by its own z-order, and ties are drawn in the order they were
+/// added.
 #[derive(Clone)]
-pub struct Motif {
-    parts: Vec<(ClineArcTile, usize)>,
+pub struct Motif<T: Transformable<Isogonal> = ClineArcTile> {
+    parts: Vec<(Part<T>, i32)>,
 }
 
-impl Motif {
-    pub fn new(parts: Vec<(ClineArcTile, usize)>) -> Self {
+impl<T: Transformable<Isogonal>> Motif<T> {
+    /// Create a motif from (geometry, style_id) pairs, all at z-order 0
+    pub fn new(parts: Vec<(T, usize)>) -> Self {
+        let parts = parts
+            .into_iter()
+            .map(|(geometry, id)| (Part::Geometry(geometry, id), 0))
+            .collect();
+
         Self { parts }
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &(ClineArcTile, usize)> {
-        self.parts.iter()
+    /// Add a piece of geometry at the given z-order
+    pub fn with_part(mut self, geometry: T, style_id: usize, z_order: i32) -> Self {
+        self.parts
+            .push((Part::Geometry(geometry, style_id), z_order));
+        self
+    }
+
+    /// Nest another motif inside this one, shifting its z-orders by z_order
+    pub fn with_motif(mut self, motif: Self, z_order: i32) -> Self {
+        self.parts.push((Part::Nested(motif), z_order));
+        self
+    }
+
+    fn flatten<'a>(&'a self, z_offset: i32, output: &mut Vec<(&'a T, usize, i32)>) {
+        for (part, z_order) in self.parts.iter() {
+            let z_order = z_offset + z_order;
+            match part {
+                Part::Geometry(geometry, id) => output.push((geometry, *id, z_order)),
+                Part::Nested(motif) => motif.flatten(z_order, output),
+            }
+        }
+    }
+
+    /// Flatten the motif into (geometry, style_id, z_order) triples in
+    /// draw order
+    pub fn parts(&self) -> Vec<(&T, usize, i32)> {
+        let mut parts = Vec::new();
+        self.flatten(0, &mut parts);
+        parts.sort_by_key(|(_, _, z_order)| *z_order);
+        parts
+    }
+
+    /// Iterate over (geometry, style_id) pairs in draw order
+    pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.parts()
+            .into_iter()
+            .map(|(geometry, id, _)| (geometry, id))
     }
 
     pub fn union(a: Self, b: Self) -> Self {
-        let parts: Vec<(ClineArcTile, usize)> = a.parts.into_iter().chain(b.parts).collect();
+        let parts: Vec<(Part<T>, i32)> = a.parts.into_iter().chain(b.parts).collect();
 
         Self { parts }
     }
 }
 
-impl Transformable<Isogonal> for Motif {
+impl Motif<ClineArcTile> {
+    /// Cut b out of a. Each part of a keeps its style id and z-order,
+    /// including the new edges along b's boundary. Parts are treated as
+    /// closed regions, see the arc_boolean module for details. Nested
+    /// motifs are flattened in the result.
+    pub fn difference(a: Self, b: Self) -> Result<Self, String> {
+        motif_boolean(&a, &b, false)
+    }
+
+    /// The parts of a that overlap b, each keeping its style id and
+    /// z-order. See difference()
+    pub fn intersect(a: Self, b: Self) -> Result<Self, String> {
+        motif_boolean(&a, &b, true)
+    }
+}
+
+impl<T: Transformable<Isogonal>> Default for Motif<T> {
+    fn default() -> Self {
+        Self { parts: Vec::new() }
+    }
+}
+
+impl<T: Transformable<Isogonal> + Clone> Semigroup for Motif<T> {
+    fn combine(self, other: Self) -> Self {
+        Self::union(self, other)
+    }
+}
+
+impl<T: Transformable<Isogonal>> Transformable<Isogonal> for Part<T> {
+    fn transform(&self, xform: Isogonal) -> Self {
+        match self {
+            Self::Geometry(geometry, id) => Self::Geometry(geometry.transform(xform), *id),
+            Self::Nested(motif) => Self::Nested(motif.transform(xform)),
+        }
+    }
+}
+
+impl<T: Transformable<Isogonal>> Transformable<Isogonal> for Motif<T> {
     fn transform(&self, xform: Isogonal) -> Self {
         let parts = self
             .parts
             .iter()
-            .map(|(tile, id)| (tile.transform(xform), *id))
+            .map(|(part, z_order)| (part.transform(xform), *z_order))
             .collect();
 
         Self { parts }
     }
 }
 
-impl Display for Motif {
+/// Bake every piece in draw order. Style ids are ignored, use
+/// Scene::add_motifs() to style each piece.
+impl<T: Renderable + Transformable<Isogonal>> Renderable for Motif<T> {
+    fn bake_geometry(&self) -> Result<Vec<RenderPrimitive>, Box<dyn Error>> {
+        let mut baked = Vec::new();
+        for (geometry, _) in self.iter() {
+            baked.extend(geometry.bake_geometry()?);
+        }
+        Ok(baked)
+    }
+}
+
+impl<T: Display + Transformable<Isogonal>> Display for Motif<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for (tile, id) in self.iter() {
-            write!(f, "{}\n{}\n", tile, id)?;
+        for (geometry, id) in self.iter() {
+            write!(f, "{}\n{}\n", geometry, id)?;
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{geometry::Circle, transformable::Cline, translation, Complex};
+
+    use super::*;
+
+    fn circle(x: f64) -> Cline {
+        Cline::from(Circle::new(Complex::new(x, 0.0), 1.0))
+    }
+
+    fn style_ids(motif: &Motif<Cline>) -> Vec<usize> {
+        motif.iter().map(|(_, id)| id).collect()
+    }
+
+    #[test]
+    pub fn iter_draws_in_z_order() {
+        let motif = Motif::default()
+            .with_part(circle(0.0), 0, 2)
+            .with_part(circle(1.0), 1, -1)
+            .with_part(circle(2.0), 2, 2)
+            .with_part(circle(3.0), 3, 0);
+
+        let result = style_ids(&motif);
+
+        assert_eq!(result, vec![1, 3, 0, 2]);
+    }
+
+    #[test]
+    pub fn nested_motif_is_shifted_by_its_z_order() {
+        let face = Motif::default()
+            .with_part(circle(0.0), 1, 0)
+            .with_part(circle(1.0), 2, 1);
+        let motif = Motif::default()
+            .with_part(circle(2.0), 0, 1)
+            .with_motif(face, 1)
+            .with_part(circle(3.0), 3, 0);
+
+        let result: Vec<(usize, i32)> = motif.parts().iter().map(|(_, id, z)| (*id, *z)).collect();
+
+        assert_eq!(result, vec![(3, 0), (0, 1), (1, 1), (2, 2)]);
+    }
+
+    #[test]
+    pub fn transform_applies_to_nested_motifs() {
+        let inner = Motif::new(vec![(circle(0.0), 1)]);
+        let motif = Motif::new(vec![(circle(1.0), 0)]).with_motif(inner, 1);
+        let xform = Isogonal::from(translation(Complex::I).unwrap());
+
+        let result = motif.transform(xform);
+
+        let circles: Vec<Cline> = result.iter().map(|(x, _)| *x).collect();
+        let expected = vec![
+            Cline::from(Circle::new(Complex::new(1.0, 1.0), 1.0)),
+            Cline::from(Circle::new(Complex::I, 1.0)),
+        ];
+        assert_eq!(circles, expected);
+    }
+
+    #[test]
+    pub fn union_keeps_draw_order() {
+        let a = Motif::default().with_part(circle(0.0), 0, 1);
+        let b = Motif::default().with_part(circle(1.0), 1, 0);
+
+        let result = Motif::union(a, b);
+
+        assert_eq!(style_ids(&result), vec![1, 0]);
+    }
+}
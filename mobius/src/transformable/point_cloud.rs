@@ -0,0 +1,77 @@
+use std::error::Error;
+
+use crate::{
+    isogonal::Isogonal,
+    rendering::{RenderPrimitive, Renderable},
+    Complex,
+};
+
+use super::Transformable;
+
+/// A list of points drawn as small dots, e.g. the orbit from a chaos game.
+/// Points at infinity are kept so the cloud can be transformed back to
+/// finite points, but they are skipped when rendering.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct PointCloud(pub Vec<Complex>);
+
+impl PointCloud {
+    pub fn new(points: Vec<Complex>) -> Self {
+        Self(points)
+    }
+
+    pub fn points(&self) -> &[Complex] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Transformable<Isogonal> for PointCloud {
+    fn transform(&self, xform: Isogonal) -> Self {
+        Self(self.0.iter().map(|z| xform * *z).collect())
+    }
+}
+
+impl Renderable for PointCloud {
+    fn bake_geometry(&self) -> Result<Vec<RenderPrimitive>, Box<dyn Error>> {
+        Ok(self
+            .0
+            .iter()
+            .filter(|z| **z != Complex::Infinity)
+            .map(|z| RenderPrimitive::Point(*z))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::translation;
+
+    use super::*;
+
+    #[test]
+    pub fn bake_geometry_skips_infinity() -> Result<(), Box<dyn Error>> {
+        let cloud = PointCloud::new(vec![Complex::ONE, Complex::Infinity, Complex::I]);
+
+        let result = cloud.bake_geometry()?;
+
+        assert_eq!(result.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    pub fn transform_moves_every_point() {
+        let cloud = PointCloud::new(vec![Complex::Zero, Complex::I]);
+        let shift = translation(Complex::ONE).unwrap();
+
+        let result = cloud.transform(shift);
+
+        assert_eq!(result.points(), &[Complex::ONE, Complex::new(1.0, 1.0)]);
+    }
+}
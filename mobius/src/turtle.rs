@@ -0,0 +1,261 @@
+//! Turtle graphics for drawing paths of line segments and circular arcs.
+//! Instead of placing each circle by hand, describe a shape as a walk:
+//! go forward, turn, curve left or right along an arc.
+use std::f64::consts::FRAC_PI_2;
+
+use crate::{
+    cline_arc::ClineArc,
+    geometry::{ArcAngles, Circle, CircularArc, LineSegment},
+    transformable::ClineArcTile,
+    Complex,
+};
+
+/// A turtle with a position and heading that records the path it draws.
+/// Methods return &mut Self so moves can be chained.
+#[derive(Clone, Debug)]
+pub struct Turtle {
+    position: Complex,
+    /// Angle of the heading measured counterclockwise from the positive
+    /// real axis
+    heading: f64,
+    stack: Vec<(Complex, f64)>,
+    arcs: Vec<ClineArc>,
+}
+
+impl Turtle {
+    /// Turtle at the origin facing the positive real axis
+    pub fn new() -> Self {
+        Self::at(Complex::Zero, 0.0)
+    }
+
+    /// Turtle at the given position facing the given angle
+    pub fn at(position: Complex, heading: f64) -> Self {
+        Self {
+            position,
+            heading,
+            stack: Vec::new(),
+            arcs: Vec::new(),
+        }
+    }
+
+    pub fn position(&self) -> Complex {
+        self.position
+    }
+
+    pub fn heading(&self) -> f64 {
+        self.heading
+    }
+
+    fn direction(&self) -> Complex {
+        Complex::from_polar(1.0, self.heading)
+    }
+
+    /// Move forward drawing a line segment
+    pub fn forward(&mut self, distance: f64) -> &mut Self {
+        let start = self.position;
+        self.position = start + self.direction() * distance.into();
+        self.arcs
+            .push(LineSegment::new(start, self.position).into());
+        self
+    }
+
+    /// Move forward without drawing
+    pub fn jump(&mut self, distance: f64) -> &mut Self {
+        self.position = self.position + self.direction() * distance.into();
+        self
+    }
+
+    /// Turn counterclockwise by angle. Use a negative angle to turn
+    /// clockwise
+    pub fn turn(&mut self, angle: f64) -> &mut Self {
+        self.heading += angle;
+        self
+    }
+
+    /// Shared implementation of arc_left() and arc_right(). side is 1.0
+    /// to curve left (counterclockwise) and -1.0 to curve right
+    fn arc(&mut self, radius: f64, angle: f64, side: f64) -> Result<&mut Self, String> {
+        if !(radius > 0.0 && radius.is_finite()) {
+            return Err(format!("radius must be positive, got {}", radius));
+        }
+
+        // The center is a quarter turn from the heading, towards the side
+        // the turtle is curving
+        let to_center = Complex::from_polar(radius, self.heading + side * FRAC_PI_2);
+        let center = self.position + to_center;
+        let start_angle = self.heading - side * FRAC_PI_2;
+        let end_angle = start_angle + side * angle;
+        let angles = ArcAngles::new(start_angle, end_angle).map_err(|e| e.to_string())?;
+
+        self.arcs
+            .push(CircularArc::new(Circle::new(center, radius), angles).into());
+        self.position = center + Complex::from_polar(radius, end_angle);
+        self.heading += side * angle;
+        Ok(self)
+    }
+
+    /// Curve counterclockwise along a circle of the given radius, turning
+    /// the heading by angle
+    pub fn arc_left(&mut self, radius: f64, angle: f64) -> Result<&mut Self, String> {
+        self.arc(radius, angle, 1.0)
+    }
+
+    /// Curve clockwise along a circle of the given radius, turning the
+    /// heading by angle
+    pub fn arc_right(&mut self, radius: f64, angle: f64) -> Result<&mut Self, String> {
+        self.arc(radius, angle, -1.0)
+    }
+
+    /// Save the current position and heading
+    pub fn push(&mut self) -> &mut Self {
+        self.stack.push((self.position, self.heading));
+        self
+    }
+
+    /// Return to the last saved position and heading
+    pub fn pop(&mut self) -> Result<&mut Self, String> {
+        let (position, heading) = self
+            .stack
+            .pop()
+            .ok_or_else(|| String::from("pop() called without a matching push()"))?;
+        self.position = position;
+        self.heading = heading;
+        Ok(self)
+    }
+
+    /// The segments and arcs drawn so far, in order
+    pub fn arcs(&self) -> &[ClineArc] {
+        &self.arcs
+    }
+
+    pub fn to_tile(&self) -> ClineArcTile {
+        ClineArcTile::new(self.arcs.clone())
+    }
+}
+
+impl Default for Turtle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use crate::{algorithms::tile_contains, winding::FillRule};
+
+    use super::*;
+
+    fn endpoints(turtle: &Turtle) -> Vec<(Complex, Complex)> {
+        turtle.arcs().iter().map(|x| (x.start(), x.end())).collect()
+    }
+
+    #[test]
+    pub fn forward_and_turn_draw_square() {
+        let mut turtle = Turtle::new();
+
+        for _ in 0..4 {
+            turtle.forward(1.0).turn(FRAC_PI_2);
+        }
+
+        let expected = vec![
+            (Complex::Zero, Complex::ONE),
+            (Complex::ONE, Complex::new(1.0, 1.0)),
+            (Complex::new(1.0, 1.0), Complex::I),
+            (Complex::I, Complex::Zero),
+        ];
+        assert_eq!(endpoints(&turtle), expected);
+        assert_eq!(turtle.position(), Complex::Zero);
+    }
+
+    #[test]
+    pub fn jump_does_not_draw() {
+        let mut turtle = Turtle::new();
+
+        turtle.jump(2.0).forward(1.0);
+
+        assert_eq!(
+            endpoints(&turtle),
+            vec![(Complex::new(2.0, 0.0), Complex::new(3.0, 0.0))]
+        );
+    }
+
+    #[test]
+    pub fn arc_left_curves_counterclockwise() -> Result<(), String> {
+        let mut turtle = Turtle::new();
+
+        turtle.arc_left(1.0, FRAC_PI_2)?;
+
+        assert_eq!(turtle.position(), Complex::new(1.0, 1.0));
+        assert_eq!(turtle.heading(), FRAC_PI_2);
+        Ok(())
+    }
+
+    #[test]
+    pub fn arc_right_curves_clockwise() -> Result<(), String> {
+        let mut turtle = Turtle::at(Complex::Zero, FRAC_PI_2);
+
+        turtle.arc_right(2.0, PI)?;
+
+        assert_eq!(turtle.position(), Complex::new(4.0, 0.0));
+        assert_eq!(turtle.heading(), -FRAC_PI_2);
+        Ok(())
+    }
+
+    #[test]
+    pub fn arc_with_bad_radius_returns_error() {
+        let mut turtle = Turtle::new();
+
+        let result = turtle.arc_left(0.0, 1.0);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn pop_restores_position_and_heading() -> Result<(), String> {
+        let mut turtle = Turtle::new();
+
+        turtle
+            .forward(1.0)
+            .push()
+            .turn(FRAC_PI_2)
+            .forward(1.0)
+            .pop()?;
+
+        assert_eq!(turtle.position(), Complex::ONE);
+        assert_eq!(turtle.heading(), 0.0);
+        Ok(())
+    }
+
+    #[test]
+    pub fn pop_without_push_returns_error() {
+        let mut turtle = Turtle::new();
+
+        let result = turtle.pop();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn closed_path_makes_tile() -> Result<(), String> {
+        // Semicircle on top of a line segment
+        let mut turtle = Turtle::at(-Complex::ONE, 0.0);
+        turtle.forward(2.0).turn(FRAC_PI_2).arc_left(1.0, PI)?;
+
+        let tile = turtle.to_tile();
+
+        assert_eq!(turtle.position(), -Complex::ONE);
+        assert!(tile_contains(
+            &tile,
+            Complex::new(0.0, 0.5),
+            FillRule::EvenOdd
+        )?);
+        assert!(!tile_contains(
+            &tile,
+            Complex::new(0.0, -0.5),
+            FillRule::EvenOdd
+        )?);
+        Ok(())
+    }
+}
@@ -0,0 +1,330 @@
+use std::f64::consts::TAU;
+
+use crate::{
+    cline_arc::ClineArcGeometry,
+    geometry::{ArcDirection, CircularArc, DirectedEdge, DoubleRay, LineSegment, Ray},
+    Complex,
+};
+
+/// Which points a closed boundary encloses, like SVG's fill-rule. The
+/// two only differ where the boundary winds around a point more than
+/// once, e.g. the middle of a pentagram or a loop nested in another
+/// loop with the same orientation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FillRule {
+    /// Inside if the boundary winds around the point at all
+    NonZero,
+    /// Inside if the boundary winds around the point an odd number of
+    /// times, so nested loops alternate between filled and holes
+    EvenOdd,
+}
+
+impl FillRule {
+    /// Apply the rule to a winding number, rounding off any round-off
+    /// error first
+    pub fn is_inside(&self, winding_number: f64) -> bool {
+        let winding_number = winding_number.round() as i64;
+        match self {
+            Self::NonZero => winding_number != 0,
+            Self::EvenOdd => winding_number % 2 != 0,
+        }
+    }
+}
+
+/// Signed angle from direction a to direction b, in (-pi, pi]. If either
+/// direction is degenerate (e.g. the point sits on a vertex) this is 0
+fn turn(a: Complex, b: Complex) -> f64 {
+    (b * a.conj()).arg_or_zero()
+}
+
+/// Counterclockwise angle from direction a to direction b, in [0, 2pi)
+fn ccw_turn(a: Complex, b: Complex) -> f64 {
+    turn(a, b).rem_euclid(TAU)
+}
+
+/// One end of an edge as seen from the query point
+#[derive(Clone, Copy)]
+enum Endpoint {
+    /// Offset from the query point to a finite end point
+    Finite(Complex),
+    /// Direction the edge heads off to infinity
+    Infinite(Complex),
+}
+
+impl Endpoint {
+    fn direction(&self) -> Complex {
+        match *self {
+            Self::Finite(offset) => offset,
+            Self::Infinite(dir) => dir,
+        }
+    }
+}
+
+/// How an edge looks from the query point: where it starts, where it ends,
+/// and the signed angle swept out while traveling along it
+struct EdgeView {
+    start: Endpoint,
+    end: Endpoint,
+    sweep: f64,
+}
+
+fn view_segment(segment: &LineSegment, point: Complex) -> EdgeView {
+    let start = segment.start - point;
+    let end = segment.end - point;
+    EdgeView {
+        start: Endpoint::Finite(start),
+        end: Endpoint::Finite(end),
+        sweep: turn(start, end),
+    }
+}
+
+/// An arc sweeps out the same angle as its chord, unless the point is in
+/// the circular segment between the arc and its chord. Then the arc goes
+/// around the other side of the point, which adds a full turn in the
+/// direction of the arc.
+fn view_arc(arc: &CircularArc, point: Complex) -> EdgeView {
+    let a = arc.start();
+    let b = arc.end();
+    let start = a - point;
+    let end = b - point;
+
+    let chord = b - a;
+    let arc_side = Complex::wedge(chord, arc.interpolate(0.5) - a);
+    let point_side = Complex::wedge(chord, point - a);
+    let in_segment = arc.circle.point_inside(point) && arc_side * point_side > 0.0;
+
+    let full_turn = match (in_segment, arc.direction()) {
+        (false, _) => 0.0,
+        (true, ArcDirection::Counterclockwise) => TAU,
+        (true, ArcDirection::Clockwise) => -TAU,
+    };
+
+    EdgeView {
+        start: Endpoint::Finite(start),
+        end: Endpoint::Finite(end),
+        sweep: turn(start, end) + full_turn,
+    }
+}
+
+fn view_to_infinity(ray: &Ray, point: Complex) -> EdgeView {
+    let start = ray.start - point;
+    let dir = *ray.unit_dir.get();
+    EdgeView {
+        start: Endpoint::Finite(start),
+        end: Endpoint::Infinite(dir),
+        sweep: turn(start, dir),
+    }
+}
+
+fn view_from_infinity(ray: &Ray, point: Complex) -> EdgeView {
+    let dir = *ray.unit_dir.get();
+    let end = ray.start - point;
+    EdgeView {
+        start: Endpoint::Infinite(dir),
+        end: Endpoint::Finite(end),
+        sweep: turn(dir, end),
+    }
+}
+
+fn view_edge(edge: &ClineArcGeometry, point: Complex) -> EdgeView {
+    match edge {
+        ClineArcGeometry::CircularArc(arc) => view_arc(arc, point),
+        ClineArcGeometry::LineSegment(segment) => view_segment(segment, point),
+        ClineArcGeometry::ToInfinity(ray) => view_to_infinity(ray, point),
+        ClineArcGeometry::FromInfinity(ray) => view_from_infinity(ray, point),
+        ClineArcGeometry::ThruInfinity(DoubleRay(out_ray, in_ray)) => {
+            let outgoing = view_to_infinity(out_ray, point);
+            let incoming = view_from_infinity(in_ray, point);
+            let jump = ccw_turn(outgoing.end.direction(), incoming.start.direction());
+            EdgeView {
+                start: outgoing.start,
+                end: incoming.end,
+                sweep: outgoing.sweep + jump + incoming.sweep,
+            }
+        }
+    }
+}
+
+/// Compute the winding number of a closed loop of edges around a point.
+/// Each edge is expected to start where the previous one ended, with the
+/// last edge wrapping around to the first.
+///
+/// Edges may pass through infinity. Since a straight line through infinity
+/// could be the limit of a circle on either side, this picks the convention
+/// that the path turns counterclockwise at infinity, so the region to the
+/// left of a counterclockwise boundary has winding number 1 whether or not
+/// it's bounded.
+///
+/// The result is only meaningful for points not on the boundary.
+pub fn winding_number(edges: &[ClineArcGeometry], point: Complex) -> f64 {
+    let views: Vec<EdgeView> = edges.iter().map(|x| view_edge(x, point)).collect();
+    let n = views.len();
+
+    let mut total = 0.0;
+    for (i, view) in views.iter().enumerate() {
+        total += view.sweep;
+
+        // Account for the turn between this edge and the next. For finite
+        // junctions this is 0 up to round-off.
+        let next = &views[(i + 1) % n];
+        total += match (view.end, next.start) {
+            (Endpoint::Infinite(a), Endpoint::Infinite(b)) => ccw_turn(a, b),
+            (a, b) => turn(a.direction(), b.direction()),
+        };
+    }
+
+    total / TAU
+}
+
+/// Check if a point is inside a closed loop of edges using the given
+/// fill rule. Points on the boundary may go either way.
+pub fn loop_contains(edges: &[ClineArcGeometry], point: Complex, rule: FillRule) -> bool {
+    rule.is_inside(winding_number(edges, point))
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use test_case::test_case;
+
+    use crate::{
+        geometry::{ArcAngles, Circle},
+        nearly::assert_nearly,
+        transformable::square_tile,
+        unit_complex::UnitComplex,
+    };
+
+    use super::*;
+
+    fn ray(start: Complex, unit_dir: UnitComplex) -> Ray {
+        Ray { start, unit_dir }
+    }
+
+    fn segment(a: Complex, b: Complex) -> ClineArcGeometry {
+        ClineArcGeometry::LineSegment(LineSegment::new(a, b))
+    }
+
+    fn unit_square() -> Vec<ClineArcGeometry> {
+        square_tile(Complex::new(-1.0, -1.0), 2.0)
+            .get_primitives()
+            .iter()
+            .map(|edge| edge.classify().unwrap())
+            .collect()
+    }
+
+    /// Square whose right side is replaced with a semicircle that bulges
+    /// outwards (or inwards if the arc is reversed)
+    fn square_with_bump(outwards: bool) -> Vec<ClineArcGeometry> {
+        let mut edges = unit_square();
+        let angles = ArcAngles::new(-0.5 * PI, 0.5 * PI).unwrap();
+        let bump = CircularArc::new(Circle::new(Complex::ONE, 1.0), angles);
+        let bump = if outwards {
+            bump
+        } else {
+            bump.complement().reverse()
+        };
+        edges[1] = ClineArcGeometry::CircularArc(bump);
+        edges
+    }
+
+    #[test_case(Complex::Zero, 1.0; "center")]
+    #[test_case(Complex::new(0.9, 0.9), 1.0; "near corner")]
+    #[test_case(Complex::new(3.0, 0.0), 0.0; "outside")]
+    pub fn winding_number_of_square(point: Complex, expected: f64) {
+        let result = winding_number(&unit_square(), point);
+
+        assert_nearly(result, expected);
+    }
+
+    #[test]
+    pub fn winding_number_of_clockwise_square_is_negative() {
+        let reversed: Vec<ClineArcGeometry> = unit_square()
+            .iter()
+            .rev()
+            .map(|x| match x {
+                ClineArcGeometry::LineSegment(s) => segment(s.end, s.start),
+                _ => unreachable!(),
+            })
+            .collect();
+
+        let result = winding_number(&reversed, Complex::Zero);
+
+        assert_nearly(result, -1.0);
+    }
+
+    #[test_case(2.0, FillRule::NonZero, true; "nonzero double loop")]
+    #[test_case(2.0, FillRule::EvenOdd, false; "even-odd double loop")]
+    #[test_case(-1.0, FillRule::EvenOdd, true; "even-odd clockwise loop")]
+    #[test_case(0.0, FillRule::NonZero, false; "outside")]
+    pub fn fill_rule_is_inside(winding_number: f64, rule: FillRule, expected: bool) {
+        // allow for round-off in the winding number
+        let result = rule.is_inside(winding_number + 1e-9);
+
+        assert_eq!(result, expected);
+    }
+
+    #[test_case(Complex::new(1.5, 0.0), true; "inside bump")]
+    #[test_case(Complex::new(1.5, 0.9), false; "beside bump")]
+    #[test_case(Complex::new(0.5, 0.0), true; "inside square")]
+    pub fn loop_contains_with_outward_arc(point: Complex, expected: bool) {
+        let result = loop_contains(&square_with_bump(true), point, FillRule::NonZero);
+
+        assert_eq!(result, expected);
+    }
+
+    #[test_case(Complex::new(0.5, 0.0), false; "inside dent")]
+    #[test_case(Complex::new(-0.5, 0.0), true; "beside dent")]
+    #[test_case(Complex::new(1.5, 0.0), false; "outside")]
+    pub fn loop_contains_with_inward_arc(point: Complex, expected: bool) {
+        let edges = square_with_bump(false);
+
+        let result = loop_contains(&edges, point, FillRule::NonZero);
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn winding_number_with_major_arc() {
+        // A circle with a notch cut out of it by a chord
+        let angles = ArcAngles::new(0.25 * PI, 1.75 * PI).unwrap();
+        let arc = CircularArc::new(Circle::unit_circle(), angles);
+        let edges = vec![
+            ClineArcGeometry::CircularArc(arc),
+            segment(arc.end(), arc.start()),
+        ];
+
+        assert_nearly(winding_number(&edges, Complex::Zero), 1.0);
+        assert_nearly(winding_number(&edges, Complex::new(0.9, 0.0)), 0.0);
+        assert_nearly(winding_number(&edges, Complex::new(-0.9, 0.0)), 1.0);
+    }
+
+    #[test]
+    pub fn winding_number_of_half_plane_through_infinity() {
+        // The real axis traversed left to right, closed up at infinity
+        let edges = vec![
+            ClineArcGeometry::ToInfinity(ray(Complex::Zero, UnitComplex::ONE)),
+            ClineArcGeometry::FromInfinity(ray(Complex::Zero, -UnitComplex::ONE)),
+        ];
+
+        assert_nearly(winding_number(&edges, Complex::I), 1.0);
+        assert_nearly(winding_number(&edges, -Complex::I), 0.0);
+    }
+
+    #[test]
+    pub fn winding_number_with_double_ray() {
+        // Upper half plane minus the triangle 0, i, 1
+        let edges = vec![
+            segment(Complex::Zero, Complex::I),
+            segment(Complex::I, Complex::ONE),
+            ClineArcGeometry::ThruInfinity(DoubleRay(
+                ray(Complex::ONE, UnitComplex::ONE),
+                ray(Complex::Zero, -UnitComplex::ONE),
+            )),
+        ];
+
+        assert_nearly(winding_number(&edges, Complex::new(0.2, 0.2)), 0.0);
+        assert_nearly(winding_number(&edges, Complex::new(2.0, 1.0)), 1.0);
+        assert_nearly(winding_number(&edges, Complex::new(2.0, -1.0)), 0.0);
+    }
+}
@@ -0,0 +1,33 @@
+use clap::{Parser, ValueEnum};
+use permutations::cayley_table::{format_csv, format_markdown, make_table, GroupName};
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Format {
+    Csv,
+    Markdown,
+}
+
+/// Print the labeled multiplication table of a finite permutation group.
+/// Elements are labeled in cycle notation, and the entry in row a,
+/// column b is the product ab (apply b first, then a)
+#[derive(Parser)]
+struct Cli {
+    /// One of a4, s4, a5 or dN for the dihedral group of the n-gon
+    group: GroupName,
+
+    #[arg(long, value_enum, default_value_t = Format::Csv)]
+    format: Format,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let table = make_table(cli.group);
+    let lines = match cli.format {
+        Format::Csv => format_csv(&table),
+        Format::Markdown => format_markdown(&table),
+    };
+
+    for line in lines {
+        println!("{}", line);
+    }
+}
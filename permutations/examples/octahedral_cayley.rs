@@ -70,10 +70,10 @@ fn make_cayley_table() -> Vec<Vec<String>> {
         table.push(row);
     }
 
-    return table;
+    table
 }
 
-fn format_csv(table: &Vec<Vec<String>>) -> Vec<String> {
+fn format_csv(table: &[Vec<String>]) -> Vec<String> {
     table.iter().map(|row| row.join(",")).collect()
 }
 
@@ -90,6 +90,8 @@ fn main() {
 mod test {
     use std::collections::HashSet;
 
+    use abstraction::CayleyGraph;
+
     use super::*;
 
     fn all_values_unique(values: &[String]) -> bool {
@@ -118,4 +120,17 @@ mod test {
 
         assert!(is_latin_square(&result));
     }
+
+    #[test]
+    fn x_and_y_generate_whole_table() {
+        let forward = make_forward_map();
+        let x = forward["x"];
+        let y = forward["y"];
+
+        let graph = CayleyGraph::generate(&[x, y]);
+
+        assert_eq!(graph.order(), ORDER);
+        let backward = make_reverse_map();
+        assert!(graph.elements().iter().all(|g| backward.contains_key(g)));
+    }
 }
@@ -0,0 +1,166 @@
+//! Labeled multiplication tables of small permutation groups, with the
+//! elements written in cycle notation.
+use std::{fmt::Display, str::FromStr};
+
+use abstraction::{FiniteGroup, Group};
+
+use crate::{DynPermutation, Permutation};
+
+/// A group to tabulate, parsed from names like a4, s4, a5 or d6
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GroupName {
+    /// Alternating group on 4 elements, the chiral tetrahedral group
+    A4,
+    /// Symmetric group on 4 elements, the chiral octahedral group
+    S4,
+    /// Alternating group on 5 elements, the chiral icosahedral group
+    A5,
+    /// Dihedral group of order 2n, the symmetries of a regular n-gon
+    Dihedral(usize),
+}
+
+impl FromStr for GroupName {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "a4" => Ok(Self::A4),
+            "s4" => Ok(Self::S4),
+            "a5" => Ok(Self::A5),
+            name => {
+                let n: usize = name
+                    .strip_prefix('d')
+                    .and_then(|x| x.parse().ok())
+                    .ok_or(format!("unknown group {}, expected a4, s4, a5 or dN", s))?;
+                if n < 3 {
+                    return Err(String::from("dihedral groups need n >= 3"));
+                }
+                Ok(Self::Dihedral(n))
+            }
+        }
+    }
+}
+
+fn parse_generators<const N: usize>(cycles: &[&str]) -> Vec<Permutation<N>> {
+    cycles.iter().map(|x| x.parse().unwrap()).collect()
+}
+
+/// Generators for the dihedral group acting on the vertices of an n-gon:
+/// a rotation by one step and a reflection i -> -i (mod n)
+fn dihedral_generators(n: usize) -> Vec<DynPermutation> {
+    let rotation = DynPermutation::new((0..n).map(|i| (i + 1) % n).collect()).unwrap();
+    let reflection = DynPermutation::new((0..n).map(|i| (n - i) % n).collect()).unwrap();
+    vec![rotation, reflection]
+}
+
+/// Enumerate the group and label the products. The entry in row a,
+/// column b is the product ab (apply b first, then a). The first row and
+/// column hold the element labels
+pub fn labeled_table<G: Group + Display>(generators: &[G]) -> Vec<Vec<String>> {
+    let group = FiniteGroup::from_generators(generators);
+    let labels: Vec<String> = group.elements().iter().map(|x| x.to_string()).collect();
+
+    let mut header = vec![String::from("*")];
+    header.extend(labels.iter().cloned());
+
+    let mut table = vec![header];
+    for (a, row) in group.cayley_table().iter().enumerate() {
+        let mut labeled_row = vec![labels[a].clone()];
+        labeled_row.extend(row.iter().map(|product| labels[*product].clone()));
+        table.push(labeled_row);
+    }
+
+    table
+}
+
+pub fn make_table(group: GroupName) -> Vec<Vec<String>> {
+    match group {
+        GroupName::A4 => labeled_table(&parse_generators::<4>(&["(0 1 2)", "(0 1)(2 3)"])),
+        GroupName::S4 => labeled_table(&parse_generators::<4>(&["(0 1 2 3)", "(0 1)"])),
+        GroupName::A5 => labeled_table(&parse_generators::<5>(&["(0 1 2 3 4)", "(0 1 2)"])),
+        GroupName::Dihedral(n) => labeled_table(&dihedral_generators(n)),
+    }
+}
+
+pub fn format_csv(table: &[Vec<String>]) -> Vec<String> {
+    table.iter().map(|row| row.join(",")).collect()
+}
+
+pub fn format_markdown(table: &[Vec<String>]) -> Vec<String> {
+    let format_row = |row: &[String]| format!("| {} |", row.join(" | "));
+
+    let mut lines = vec![format_row(&table[0])];
+    lines.push(format!("|{}", "---|".repeat(table[0].len())));
+    lines.extend(table[1..].iter().map(|row| format_row(row)));
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn is_latin_square(table: &[Vec<String>]) -> bool {
+        let body: Vec<&[String]> = table[1..].iter().map(|row| &row[1..]).collect();
+        let rows_unique = body
+            .iter()
+            .all(|row| row.iter().collect::<HashSet<_>>().len() == row.len());
+        let columns_unique = (0..body.len())
+            .all(|j| body.iter().map(|row| &row[j]).collect::<HashSet<_>>().len() == body.len());
+
+        rows_unique && columns_unique
+    }
+
+    #[test]
+    pub fn group_name_parses_dihedral() {
+        let result: Result<GroupName, String> = "d6".parse();
+
+        assert_eq!(result, Ok(GroupName::Dihedral(6)));
+    }
+
+    #[test]
+    pub fn group_name_rejects_unknown_group() {
+        let result: Result<GroupName, String> = "q8".parse();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn make_table_computes_expected_orders() {
+        let groups = [
+            (GroupName::A4, 12),
+            (GroupName::S4, 24),
+            (GroupName::A5, 60),
+            (GroupName::Dihedral(5), 10),
+        ];
+
+        for (group, order) in groups {
+            let table = make_table(group);
+
+            // one extra row and column for the labels
+            assert_eq!(table.len(), order + 1);
+            assert_eq!(table[0].len(), order + 1);
+        }
+    }
+
+    #[test]
+    pub fn make_table_returns_latin_square() {
+        for group in [GroupName::A4, GroupName::S4, GroupName::Dihedral(4)] {
+            let result = make_table(group);
+
+            assert!(is_latin_square(&result));
+        }
+    }
+
+    #[test]
+    pub fn format_markdown_adds_separator_row() {
+        let table = make_table(GroupName::Dihedral(3));
+
+        let result = format_markdown(&table);
+
+        assert_eq!(result.len(), table.len() + 1);
+        assert_eq!(result[1], "|---|---|---|---|---|---|---|");
+        assert!(result[0].starts_with("| * | () |"));
+    }
+}
@@ -0,0 +1,173 @@
+//! Cycle structure helpers shared by the fixed-size and dynamic-size
+//! permutation types. Permutations are passed around as slices where
+//! values[i] is the image of i.
+
+/// Whether a permutation is a product of an even or odd number of swaps
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Parity {
+    Even,
+    Odd,
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    a / gcd(a, b) * b
+}
+
+/// Decompose a permutation into disjoint cycles, including fixed
+/// points as 1-cycles. Each cycle starts with its smallest element,
+/// and the cycles are sorted by their first element.
+pub(crate) fn cycle_decomposition(values: &[usize]) -> Vec<Vec<usize>> {
+    let mut visited = vec![false; values.len()];
+    let mut cycles = Vec::new();
+    for start in 0..values.len() {
+        if visited[start] {
+            continue;
+        }
+
+        let mut cycle = Vec::new();
+        let mut current = start;
+        while !visited[current] {
+            visited[current] = true;
+            cycle.push(current);
+            current = values[current];
+        }
+        cycles.push(cycle);
+    }
+
+    cycles
+}
+
+/// The order of a permutation is the lcm of the cycle lengths
+pub(crate) fn order_of(values: &[usize]) -> usize {
+    cycle_decomposition(values)
+        .iter()
+        .map(|x| x.len())
+        .fold(1, lcm)
+}
+
+/// A k-cycle is a product of k - 1 swaps, so the parity is determined
+/// by the cycle structure
+pub(crate) fn parity_of(values: &[usize]) -> Parity {
+    let swaps: usize = cycle_decomposition(values)
+        .iter()
+        .map(|x| x.len() - 1)
+        .sum();
+    if swaps.is_multiple_of(2) {
+        Parity::Even
+    } else {
+        Parity::Odd
+    }
+}
+
+/// Check that values is a permutation of 0..values.len()
+pub(crate) fn validate(values: &[usize]) -> Result<(), String> {
+    let mut seen = vec![false; values.len()];
+    for value in values {
+        if *value >= values.len() {
+            return Err(String::from("values must be less than length of slice"));
+        }
+        if seen[*value] {
+            return Err(String::from("values must not have repeat elements"));
+        }
+        seen[*value] = true;
+    }
+
+    Ok(())
+}
+
+/// Parse cycle notation like "(0 1 2)(3 4)" into a list of cycles.
+/// Elements may be separated by spaces or commas. "()" or an empty
+/// string is the identity.
+pub(crate) fn parse_cycles(text: &str) -> Result<Vec<Vec<usize>>, String> {
+    let mut cycles = Vec::new();
+    let mut rest = text.trim();
+    while !rest.is_empty() {
+        let inner = rest
+            .strip_prefix('(')
+            .ok_or(format!("expected '(' at {}", rest))?;
+        let (contents, after) = inner
+            .split_once(')')
+            .ok_or(String::from("missing ')' in cycle notation"))?;
+
+        let cycle = contents
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|x| !x.is_empty())
+            .map(|x| {
+                x.parse::<usize>()
+                    .map_err(|_| format!("invalid cycle element: {}", x))
+            })
+            .collect::<Result<Vec<usize>, String>>()?;
+        cycles.push(cycle);
+
+        rest = after.trim_start();
+    }
+
+    Ok(cycles)
+}
+
+/// Format the nontrivial cycles in cycle notation, e.g. "(0 1 2)(3 4)".
+/// The identity is formatted as "()"
+pub(crate) fn format_cycles(values: &[usize]) -> String {
+    let nontrivial: Vec<String> = cycle_decomposition(values)
+        .iter()
+        .filter(|x| x.len() > 1)
+        .map(|cycle| {
+            let elements: Vec<String> = cycle.iter().map(|x| x.to_string()).collect();
+            format!("({})", elements.join(" "))
+        })
+        .collect();
+
+    if nontrivial.is_empty() {
+        String::from("()")
+    } else {
+        nontrivial.concat()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn parse_cycles_parses_multiple_cycles() {
+        let result = parse_cycles("(0 1 2)(3, 4)");
+
+        assert_eq!(result, Ok(vec![vec![0, 1, 2], vec![3, 4]]));
+    }
+
+    #[test]
+    pub fn parse_cycles_parses_identity() {
+        let result = parse_cycles("()");
+
+        assert_eq!(result, Ok(vec![vec![]]));
+    }
+
+    #[test]
+    pub fn parse_cycles_with_missing_paren_returns_error() {
+        let result = parse_cycles("(0 1");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn parse_cycles_with_bad_element_returns_error() {
+        let result = parse_cycles("(0 x)");
+
+        assert!(result.is_err_and(|e| e.contains("invalid cycle element: x")));
+    }
+
+    #[test]
+    pub fn format_cycles_skips_fixed_points() {
+        let result = format_cycles(&[1, 0, 2, 4, 3]);
+
+        assert_eq!(result, "(0 1)(3 4)");
+    }
+}
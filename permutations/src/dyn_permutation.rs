@@ -0,0 +1,286 @@
+use std::{fmt::Display, ops::Mul, str::FromStr};
+
+use abstraction::{Group, Monoid};
+
+use crate::{
+    cycles::{format_cycles, order_of, parity_of, parse_cycles, validate, Parity},
+    Permutation,
+};
+
+/// Permutation whose size is only known at runtime, e.g. when parsed
+/// from user input.
+///
+/// This is a permutation of the natural numbers that moves only finitely
+/// many elements, so every point past the end of the stored values is a
+/// fixed point. This way permutations of different sizes can be
+/// multiplied, and the identity does not need a size. Trailing fixed
+/// points are trimmed so equal permutations have equal representations.
+#[derive(Hash, PartialEq, Eq, Clone, Debug, Default)]
+pub struct DynPermutation {
+    values: Vec<usize>,
+}
+
+impl DynPermutation {
+    pub fn new(values: Vec<usize>) -> Result<Self, String> {
+        validate(&values)?;
+
+        Ok(Self::trimmed(values))
+    }
+
+    /// Build a permutation from a list of cycles, e.g.
+    /// [[0, 1, 2], [3, 4]] for (0 1 2)(3 4).
+    pub fn from_cycles(cycles: &[Vec<usize>]) -> Result<Self, String> {
+        let size = cycles.iter().flatten().max().map_or(0, |x| x + 1);
+        let mut values: Vec<usize> = (0..size).collect();
+        let mut seen = vec![false; size];
+        for cycle in cycles {
+            for (i, x) in cycle.iter().enumerate() {
+                if seen[*x] {
+                    return Err(format!("element {} appears in multiple cycles", x));
+                }
+                seen[*x] = true;
+                values[*x] = cycle[(i + 1) % cycle.len()];
+            }
+        }
+
+        Ok(Self::trimmed(values))
+    }
+
+    fn trimmed(mut values: Vec<usize>) -> Self {
+        while let Some(&last) = values.last() {
+            if last != values.len() - 1 {
+                break;
+            }
+            values.pop();
+        }
+
+        Self { values }
+    }
+
+    /// The number of stored values, i.e. one more than the largest
+    /// element that is not a fixed point
+    pub fn size(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Apply the permutation to a single element
+    pub fn apply(&self, x: usize) -> usize {
+        self.values.get(x).copied().unwrap_or(x)
+    }
+
+    /// See Permutation::cycles()
+    pub fn cycles(&self) -> Vec<Vec<usize>> {
+        crate::cycles::cycle_decomposition(&self.values)
+    }
+
+    pub fn order(&self) -> usize {
+        order_of(&self.values)
+    }
+
+    pub fn parity(&self) -> Parity {
+        parity_of(&self.values)
+    }
+
+    /// +1 for even permutations, -1 for odd permutations
+    pub fn sign(&self) -> i32 {
+        match self.parity() {
+            Parity::Even => 1,
+            Parity::Odd => -1,
+        }
+    }
+
+    pub fn is_even(&self) -> bool {
+        self.parity() == Parity::Even
+    }
+}
+
+impl Mul for DynPermutation {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let size = self.size().max(rhs.size());
+        let product = (0..size).map(|i| self.apply(rhs.apply(i))).collect();
+
+        Self::trimmed(product)
+    }
+}
+
+impl Monoid for DynPermutation {
+    fn identity() -> Self {
+        Self { values: Vec::new() }
+    }
+}
+
+impl Group for DynPermutation {
+    fn inverse(&self) -> Self {
+        let mut result = vec![0usize; self.size()];
+
+        for (i, x) in self.values.iter().enumerate() {
+            result[*x] = i;
+        }
+
+        Self { values: result }
+    }
+}
+
+/// Parse cycle notation, e.g. "(0 1 2)(3 4)"
+impl FromStr for DynPermutation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_cycles(&parse_cycles(s)?)
+    }
+}
+
+/// Format in cycle notation, e.g. "(0 1 2)(3 4)"
+impl Display for DynPermutation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format_cycles(&self.values))
+    }
+}
+
+impl<const N: usize> From<Permutation<N>> for DynPermutation {
+    fn from(value: Permutation<N>) -> Self {
+        Self::trimmed(value.values().to_vec())
+    }
+}
+
+impl<const N: usize> TryFrom<DynPermutation> for Permutation<N> {
+    type Error = String;
+
+    fn try_from(value: DynPermutation) -> Result<Self, Self::Error> {
+        if value.size() > N {
+            return Err(format!(
+                "permutation moves element {}, which does not fit in {} elements",
+                value.size() - 1,
+                N
+            ));
+        }
+
+        let values: [usize; N] = std::array::from_fn(|i| value.apply(i));
+        Permutation::new(values)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use abstraction::{test_associativity, test_identity, test_inverse};
+
+    use super::*;
+
+    #[test]
+    pub fn new_returns_error_for_out_of_range_element() {
+        let result = DynPermutation::new(vec![0, 1, 2, 4]);
+
+        assert!(result.is_err_and(|e| e.contains("values must be less than length of slice")));
+    }
+
+    #[test]
+    pub fn new_returns_error_for_duplicate_element() {
+        let result = DynPermutation::new(vec![0, 2, 2, 1]);
+
+        assert!(result.is_err_and(|e| e.contains("values must not have repeat elements")));
+    }
+
+    #[test]
+    pub fn new_trims_trailing_fixed_points() {
+        let a = DynPermutation::new(vec![1, 0, 2, 3]).unwrap();
+        let b = DynPermutation::new(vec![1, 0]).unwrap();
+
+        assert_eq!(a, b);
+        assert_eq!(a.size(), 2);
+    }
+
+    #[test]
+    pub fn from_str_parses_cycle_notation() {
+        let result: DynPermutation = "(0 1 2)(3 4)".parse().unwrap();
+
+        let expected = DynPermutation::new(vec![1, 2, 0, 4, 3]).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn from_str_with_repeated_element_returns_error() {
+        let result: Result<DynPermutation, String> = "(0 1)(1 2)".parse();
+
+        assert!(result.is_err_and(|e| e.contains("multiple cycles")));
+    }
+
+    #[test]
+    pub fn to_string_formats_cycle_notation() {
+        let permutation = DynPermutation::new(vec![1, 2, 0, 4, 3]).unwrap();
+
+        let result = permutation.to_string();
+
+        assert_eq!(result, "(0 1 2)(3 4)");
+    }
+
+    #[test]
+    pub fn to_string_formats_identity() {
+        let result = DynPermutation::identity().to_string();
+
+        assert_eq!(result, "()");
+    }
+
+    #[test]
+    pub fn multiplication_of_different_sizes_applies_a_after_b() {
+        let a: DynPermutation = "(1 2 3)".parse().unwrap();
+        let b: DynPermutation = "(2 3)".parse().unwrap();
+
+        let result = a * b;
+
+        // Same as the fixed-size version of this test
+        let expected: DynPermutation = "(1 2)".parse().unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn order_and_sign_match_cycle_structure() {
+        let permutation: DynPermutation = "(0 1)(2 3 4)".parse().unwrap();
+
+        assert_eq!(permutation.order(), 6);
+        assert_eq!(permutation.sign(), -1);
+    }
+
+    #[test]
+    pub fn from_permutation_round_trips() {
+        let permutation = Permutation::new([2, 0, 1, 3]).unwrap();
+
+        let dynamic = DynPermutation::from(permutation);
+        let result = Permutation::<4>::try_from(dynamic);
+
+        assert_eq!(result, Ok(permutation));
+    }
+
+    #[test]
+    pub fn try_from_with_too_large_permutation_returns_error() {
+        let permutation: DynPermutation = "(0 5)".parse().unwrap();
+
+        let result = Permutation::<4>::try_from(permutation);
+
+        assert!(result.is_err_and(|e| e.contains("does not fit in 4 elements")));
+    }
+
+    test_identity!(
+        DynPermutation,
+        [
+            (swap, DynPermutation::new(vec![1, 0]).unwrap()),
+            (three_cycle, DynPermutation::new(vec![1, 2, 0]).unwrap())
+        ]
+    );
+
+    test_associativity!(
+        DynPermutation,
+        [(
+            mixed_sizes,
+            DynPermutation::new(vec![1, 0]).unwrap(),
+            DynPermutation::new(vec![0, 2, 3, 1]).unwrap(),
+            DynPermutation::new(vec![0, 1, 2, 4, 3]).unwrap()
+        )]
+    );
+
+    test_inverse!(
+        DynPermutation,
+        [(three_cycle, DynPermutation::new(vec![1, 2, 0]).unwrap())]
+    );
+}
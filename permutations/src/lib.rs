@@ -1,5 +1,10 @@
+pub mod cayley_table;
+mod cycles;
+mod dyn_permutation;
 mod permutation;
 
+pub use cycles::Parity;
+pub use dyn_permutation::DynPermutation;
 pub use permutation::Permutation;
 
 pub fn add(left: u64, right: u64) -> u64 {
@@ -1,6 +1,11 @@
-use std::{collections::HashSet, ops::Mul};
+use std::{collections::HashSet, fmt::Display, ops::Mul, str::FromStr};
 
-use abstraction::{Group, Monoid};
+use abstraction::{Group, GroupAction, Monoid};
+
+use crate::{
+    cycles::{cycle_decomposition, format_cycles, order_of, parity_of, Parity},
+    DynPermutation,
+};
 
 /// Mathematical permutation of N elements. An element of the symmetric group S_N
 #[derive(Hash, PartialEq, Eq, Clone, Copy, Debug)]
@@ -23,6 +28,88 @@ impl<const N: usize> Permutation<N> {
 
         Ok(Self { values })
     }
+
+    /// values[i] is the image of i
+    pub fn values(&self) -> &[usize; N] {
+        &self.values
+    }
+
+    /// Decompose the permutation into disjoint cycles, including fixed
+    /// points as 1-cycles. Each cycle starts with its smallest element,
+    /// and the cycles are sorted by their first element.
+    pub fn cycles(&self) -> Vec<Vec<usize>> {
+        cycle_decomposition(&self.values)
+    }
+
+    /// The smallest n > 0 such that p^n = I. This is the lcm of the
+    /// cycle lengths.
+    pub fn order(&self) -> usize {
+        order_of(&self.values)
+    }
+
+    pub fn parity(&self) -> Parity {
+        parity_of(&self.values)
+    }
+
+    /// +1 for even permutations, -1 for odd permutations
+    pub fn sign(&self) -> i32 {
+        match self.parity() {
+            Parity::Even => 1,
+            Parity::Odd => -1,
+        }
+    }
+
+    pub fn is_even(&self) -> bool {
+        self.parity() == Parity::Even
+    }
+
+    /// Get the next permutation in lexicographic order, or None if this
+    /// is the last one
+    fn next_lexicographic(&self) -> Option<Self> {
+        let mut values = self.values;
+
+        // Find the rightmost ascent values[i] < values[i + 1]
+        let i = (0..N.saturating_sub(1))
+            .rev()
+            .find(|&i| values[i] < values[i + 1])?;
+
+        // Swap values[i] with the rightmost element larger than it,
+        // then reverse the tail to get the smallest arrangement
+        let j = (i + 1..N).rev().find(|&j| values[j] > values[i]).unwrap();
+        values.swap(i, j);
+        values[i + 1..].reverse();
+
+        Some(Self { values })
+    }
+
+    /// Iterate over all N! elements of the symmetric group S_N in
+    /// lexicographic order, starting from the identity
+    pub fn symmetric_group() -> impl Iterator<Item = Self> {
+        std::iter::successors(Some(Self::identity()), |x| x.next_lexicographic())
+    }
+
+    /// Iterate over the N!/2 even permutations that make up the
+    /// alternating group A_N, in lexicographic order
+    pub fn alternating_group() -> impl Iterator<Item = Self> {
+        Self::symmetric_group().filter(|x| x.is_even())
+    }
+}
+
+/// Parse cycle notation, e.g. "(0 1 2)(3)". Elements not mentioned
+/// are fixed points
+impl<const N: usize> FromStr for Permutation<N> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DynPermutation::from_str(s)?.try_into()
+    }
+}
+
+/// Format in cycle notation, e.g. "(0 1 2)"
+impl<const N: usize> Display for Permutation<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format_cycles(&self.values))
+    }
 }
 
 impl<const N: usize> Mul for Permutation<N> {
@@ -62,9 +149,21 @@ impl<const N: usize> Group for Permutation<N> {
     }
 }
 
+/// Permutations act on arrays by moving the entry at index i to
+/// index p(i). This matches the multiplication order, so
+/// a.act(b.act(x)) = (a * b).act(x)
+impl<T: Clone, const N: usize> GroupAction<[T; N]> for Permutation<N> {
+    fn act(&self, x: &[T; N]) -> [T; N] {
+        let inverse = self.inverse();
+        std::array::from_fn(|i| x[inverse.values[i]].clone())
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use abstraction::{test_associativity, test_group, test_identity, test_inverse};
+    use abstraction::{
+        orbit, test_associativity, test_group, test_identity, test_inverse, FiniteGroup,
+    };
 
     use super::*;
 
@@ -169,6 +268,152 @@ mod test {
         [(three_cycle, Permutation { values: [1, 2, 0] })]
     );
 
+    #[test]
+    pub fn symmetric_group_s4_has_expected_structure() {
+        let swap = Permutation::new([1, 0, 2, 3]).unwrap();
+        let four_cycle = Permutation::new([1, 2, 3, 0]).unwrap();
+
+        let s4 = FiniteGroup::from_generators(&[swap, four_cycle]);
+
+        assert_eq!(s4.order(), 24);
+        // Conjugacy classes are determined by cycle type:
+        // identity, swaps, double swaps, 3-cycles, 4-cycles
+        let mut class_sizes: Vec<usize> = s4.conjugacy_classes().iter().map(|x| x.len()).collect();
+        class_sizes.sort();
+        assert_eq!(class_sizes, vec![1, 3, 6, 6, 8]);
+        assert_eq!(s4.center(), vec![0]);
+        // 1, V4, A4, S4
+        let normal_orders: Vec<usize> = s4.normal_subgroups().iter().map(|x| x.len()).collect();
+        assert_eq!(normal_orders, vec![1, 4, 12, 24]);
+    }
+
+    #[test]
+    pub fn from_str_parses_cycle_notation() {
+        let result: Result<Permutation<4>, String> = "(0 1 2)".parse();
+
+        assert_eq!(result, Permutation::new([1, 2, 0, 3]));
+    }
+
+    #[test]
+    pub fn to_string_formats_cycle_notation() {
+        let permutation = Permutation::new([1, 0, 3, 2]).unwrap();
+
+        let result = permutation.to_string();
+
+        assert_eq!(result, "(0 1)(2 3)");
+    }
+
+    #[test]
+    pub fn cycles_decomposes_permutation() {
+        let permutation = Permutation::new([1, 0, 3, 4, 2, 5]).unwrap();
+
+        let result = permutation.cycles();
+
+        assert_eq!(result, vec![vec![0, 1], vec![2, 3, 4], vec![5]]);
+    }
+
+    #[test]
+    pub fn order_is_lcm_of_cycle_lengths() {
+        let permutation = Permutation::new([1, 0, 3, 4, 2, 5]).unwrap();
+
+        let result = permutation.order();
+
+        assert_eq!(result, 6);
+    }
+
+    #[test]
+    pub fn order_of_identity_is_one() {
+        let result = Permutation::<5>::identity().order();
+
+        assert_eq!(result, 1);
+    }
+
+    #[test]
+    pub fn sign_of_swap_is_negative() {
+        let swap = Permutation::new([1, 0, 2, 3]).unwrap();
+
+        assert_eq!(swap.sign(), -1);
+        assert_eq!(swap.parity(), Parity::Odd);
+        assert!(!swap.is_even());
+    }
+
+    #[test]
+    pub fn three_cycle_is_even() {
+        let three_cycle = Permutation::new([1, 2, 0, 3]).unwrap();
+
+        assert_eq!(three_cycle.sign(), 1);
+        assert!(three_cycle.is_even());
+    }
+
+    #[test]
+    pub fn sign_is_multiplicative() {
+        let a = Permutation::new([1, 2, 3, 0]).unwrap();
+        let b = Permutation::new([0, 2, 1, 3]).unwrap();
+
+        let result = (a * b).sign();
+
+        assert_eq!(result, a.sign() * b.sign());
+    }
+
+    #[test]
+    pub fn symmetric_group_lists_all_permutations() {
+        let elements: Vec<Permutation<4>> = Permutation::symmetric_group().collect();
+
+        assert_eq!(elements.len(), 24);
+        assert_eq!(elements[0], Permutation::identity());
+        let unique: HashSet<Permutation<4>> = elements.iter().cloned().collect();
+        assert_eq!(unique.len(), 24);
+    }
+
+    #[test]
+    pub fn symmetric_group_of_zero_elements_is_trivial() {
+        let result = Permutation::<0>::symmetric_group().count();
+
+        assert_eq!(result, 1);
+    }
+
+    #[test]
+    pub fn alternating_group_a5_is_closed_and_has_order_60() {
+        let elements: Vec<Permutation<5>> = Permutation::alternating_group().collect();
+
+        assert_eq!(elements.len(), 60);
+        let group = FiniteGroup::from_generators(&elements);
+        assert_eq!(group.order(), 60);
+        // A_5 is simple
+        assert_eq!(group.normal_subgroups().len(), 2);
+    }
+
+    #[test]
+    pub fn act_moves_entries_to_permuted_index() {
+        let three_cycle = Permutation::new([1, 2, 0]).unwrap();
+
+        let result = three_cycle.act(&['a', 'b', 'c']);
+
+        // a moves to index 1, b to index 2, c to index 0
+        assert_eq!(result, ['c', 'a', 'b']);
+    }
+
+    #[test]
+    pub fn act_is_compatible_with_multiplication() {
+        let a = Permutation::new([0, 2, 3, 1]).unwrap();
+        let b = Permutation::new([0, 1, 3, 2]).unwrap();
+        let x = ['w', 'x', 'y', 'z'];
+
+        let result = a.act(&b.act(&x));
+
+        assert_eq!(result, (a * b).act(&x));
+    }
+
+    #[test]
+    pub fn orbit_of_distinct_entries_has_n_factorial_points() {
+        let swap = Permutation::new([1, 0, 2, 3]).unwrap();
+        let four_cycle = Permutation::new([1, 2, 3, 0]).unwrap();
+
+        let result = orbit(&[swap, four_cycle], &[1, 2, 3, 4]);
+
+        assert_eq!(result.len(), 24);
+    }
+
     test_group!(
         Permutation<4>,
         [(